@@ -0,0 +1,191 @@
+//! An Apache Arrow Flight input source for the RTLola interpreter.
+//!
+//! The client requests the record batches of a ticket, casts the columns mapped to input streams to their declared
+//! types, and feeds the rows of each batch as events into a monitor.
+
+use arrow::array::{Array, ArrayRef, AsArray};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type, Int64Type, TimeUnit, UInt64Type};
+use arrow::record_batch::RecordBatch;
+use arrow_flight::{FlightClient, Ticket};
+use clap::{App, Arg};
+use futures::StreamExt;
+use ordered_float::NotNan;
+use rtlola_frontend::ir::Type;
+use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_interpreter::{Config, EvalConfig, Monitor, TimeRepresentation, Value};
+use std::error::Error;
+use std::time::Duration;
+use tonic::transport::Channel;
+
+const CONFIG: FrontendConfig =
+    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+
+/// Feeds the rows of the record batches into the monitor.
+struct Replay {
+    monitor: Monitor,
+    /// The input streams with the names of their columns.
+    inputs: Vec<(String, Type)>,
+    time_column: String,
+    /// The first timestamp in nanoseconds, times are relative to it.
+    start: Option<i64>,
+    last: Duration,
+    batches: usize,
+    events: usize,
+    triggers: usize,
+}
+
+impl Replay {
+    fn batch(&mut self, batch: &RecordBatch) -> Result<(), Box<dyn Error>> {
+        let column =
+            |name: &str| batch.column_by_name(name).ok_or_else(|| format!("the record batch has no column `{}`", name));
+        let times = timestamps(column(&self.time_column)?)?;
+        let columns = self
+            .inputs
+            .iter()
+            .map(|(name, ty)| column(name).map_err(Box::<dyn Error>::from).and_then(|c| cast_column(c, ty)))
+            .collect::<Result<Vec<ArrayRef>, _>>()?;
+
+        for (row, nanos) in times.into_iter().enumerate() {
+            let start = *self.start.get_or_insert(nanos);
+            if nanos < start {
+                return Err(format!("timestamp in row {} of batch {} precedes the first row", row, self.batches).into());
+            }
+            let ts = Duration::from_nanos((nanos - start) as u64);
+            if ts < self.last {
+                return Err(format!("the time column is not sorted in row {} of batch {}", row, self.batches).into());
+            }
+            self.last = ts;
+            let event = columns.iter().zip(&self.inputs).map(|(c, (_, ty))| value(c, row, ty)).collect();
+            let update = self.monitor.accept_event(event, ts);
+            for (ts, slice) in update.timed.iter().chain(std::iter::once(&(ts, update.event.clone()))) {
+                for (out, v) in slice {
+                    if let (Some(msg), Value::Bool(true)) = (self.monitor.trigger_message(*out), v) {
+                        println!("[{:.3}s] Trigger: {}", ts.as_secs_f64(), msg);
+                        self.triggers += 1;
+                    }
+                }
+            }
+        }
+        self.events += batch.num_rows();
+        self.batches += 1;
+        Ok(())
+    }
+}
+
+/// Casts a column to the array type representing the values of the input type.
+fn cast_column(column: &ArrayRef, ty: &Type) -> Result<ArrayRef, Box<dyn Error>> {
+    let target = match ty {
+        Type::Bool => DataType::Boolean,
+        Type::Int(_) => DataType::Int64,
+        Type::UInt(_) => DataType::UInt64,
+        Type::Float(_) => DataType::Float64,
+        Type::String => DataType::Utf8,
+        Type::Bytes => DataType::Binary,
+        _ => return Err(format!("inputs of type {} are not supported", ty).into()),
+    };
+    Ok(cast(column, &target)?)
+}
+
+/// Reads a value of a column cast by `cast_column`.
+fn value(column: &ArrayRef, row: usize, ty: &Type) -> Value {
+    if column.is_null(row) {
+        return Value::None;
+    }
+    match ty {
+        Type::Bool => Value::Bool(column.as_boolean().value(row)),
+        Type::Int(_) => Value::Signed(column.as_primitive::<Int64Type>().value(row)),
+        Type::UInt(_) => Value::Unsigned(column.as_primitive::<UInt64Type>().value(row)),
+        Type::Float(_) => {
+            NotNan::new(column.as_primitive::<Float64Type>().value(row)).map_or(Value::None, Value::Float)
+        }
+        Type::String => Value::Str(column.as_string::<i32>().value(row).into()),
+        Type::Bytes => Value::Bytes(column.as_binary::<i32>().value(row).into()),
+        _ => unreachable!("rejected by `cast_column`"),
+    }
+}
+
+/// Converts the time column into nanoseconds, numeric columns are interpreted as seconds.
+fn timestamps(column: &ArrayRef) -> Result<Vec<i64>, Box<dyn Error>> {
+    if column.null_count() > 0 {
+        return Err("the time column contains null values".into());
+    }
+    Ok(match column.data_type() {
+        DataType::Timestamp(unit, _) => {
+            let factor = match unit {
+                TimeUnit::Second => 1_000_000_000,
+                TimeUnit::Millisecond => 1_000_000,
+                TimeUnit::Microsecond => 1_000,
+                TimeUnit::Nanosecond => 1,
+            };
+            let column = cast(column, &DataType::Int64)?;
+            column.as_primitive::<Int64Type>().values().iter().map(|t| t * factor).collect()
+        }
+        _ => {
+            let column = cast(column, &DataType::Float64)?;
+            column.as_primitive::<Float64Type>().values().iter().map(|t| (t * 1e9) as i64).collect()
+        }
+    })
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    let matches = App::new("RTLola Arrow Flight Client")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Replays Apache Arrow Flight streams through the RTLola interpreter.")
+        .arg(Arg::with_name("SPEC").help("Sets the specification file to use").required(true))
+        .arg(Arg::with_name("ENDPOINT").help("The endpoint of the server, e.g., http://localhost:50052").required(true))
+        .arg(
+            Arg::with_name("TICKET")
+                .help("The ticket identifying the stream to replay")
+                .long("ticket")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("TIME")
+                .help("The column containing the timestamps")
+                .long("time-column")
+                .takes_value(true)
+                .default_value("time"),
+        )
+        .arg(
+            Arg::with_name("COLUMN")
+                .help("Reads an input stream from a column with a different name, e.g., --column velo=velocity")
+                .long("column")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .get_matches();
+
+    let path = matches.value_of("SPEC").unwrap();
+    let ir = rtlola_frontend::parse(path, &std::fs::read_to_string(path)?, CONFIG)?;
+    let mut inputs: Vec<(String, Type)> = ir.inputs.iter().map(|i| (i.name.clone(), i.ty.clone())).collect();
+    for mapping in matches.values_of("COLUMN").into_iter().flatten() {
+        let eq = mapping.find('=').ok_or_else(|| format!("malformed column mapping `{}`", mapping))?;
+        let (name, column) = (&mapping[..eq], &mapping[eq + 1..]);
+        let input = ir.inputs.iter().position(|i| i.name == name).ok_or_else(|| format!("unknown input `{}`", name))?;
+        inputs[input].0 = column.to_string();
+    }
+
+    let channel = Channel::from_shared(matches.value_of("ENDPOINT").unwrap().to_string())?.connect().await?;
+    let mut client = FlightClient::new(channel);
+    let mut batches = client.do_get(Ticket::new(matches.value_of("TICKET").unwrap().to_string())).await?;
+
+    let monitor = Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir).into_monitor()?;
+    let time_column = matches.value_of("TIME").unwrap().to_string();
+    let last = Duration::default();
+    let mut replay = Replay { monitor, inputs, time_column, start: None, last, batches: 0, events: 0, triggers: 0 };
+    while let Some(batch) = batches.next().await {
+        replay.batch(&batch?)?;
+    }
+    eprintln!(
+        "Replayed {} events in {} batches covering {:.3}s, {} triggers fired.",
+        replay.events,
+        replay.batches,
+        replay.last.as_secs_f64(),
+        replay.triggers
+    );
+    Ok(())
+}
@@ -0,0 +1,35 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use rtlola_frontend::FrontendConfig;
+
+/// Generates a specification with `lines` lines, where the outputs of each block of five declarations depend on the previous block.
+fn generate_spec(lines: usize) -> String {
+    let mut spec = String::new();
+    for block in 0..lines / 5 {
+        let previous = if block == 0 { String::from("0") } else { format!("c{}.hold().defaults(to: 0)", block - 1) };
+        spec += &format!("input a{}: Int64\n", block);
+        spec += &format!("input b{}: Float64\n", block);
+        spec += &format!("output c{} := a{} * 2 + (a{} - 1) % 7 + {}\n", block, block, block, previous);
+        spec += &format!(
+            "output d{} @ 1Hz := b{}.aggregate(over: 1s, using: avg).defaults(to: 0.0) + d{}.offset(by: -1).defaults(to: 0.0)\n",
+            block, block, block
+        );
+        spec += &format!("trigger c{} > 100 && !(a{} < 0) \"c{} exceeds the limit\"\n", block, block, block);
+    }
+    spec
+}
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse");
+    group.sample_size(10);
+    for lines in &[1_000, 10_000] {
+        let spec = generate_spec(*lines);
+        group.throughput(Throughput::Bytes(spec.len() as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(lines), &spec, |b, spec| {
+            b.iter(|| rtlola_frontend::parse("bench.lola", spec, FrontendConfig::default()).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, parse);
+criterion_main!(benches);
@@ -4,19 +4,26 @@
 //! * `naming` provides boundedness analysis for identifiers used in the Lola Specification
 //! * `id_assignment` assigns unique ids to all nodes of the AST
 //! * `type_checker` checks whether components of the AST have a valid type
+//! * `constant_evaluation` folds the expressions defining constants into values
 
+pub(crate) mod constant_evaluation;
 pub(crate) mod graph_based_analysis;
 // pub(crate) mod id_assignment;
 pub(crate) mod naming;
 
+use self::constant_evaluation::ConstantEvaluation;
 use self::naming::NamingAnalysis;
 use crate::ast;
 use crate::ast::RTLolaAst;
+use crate::parse::NodeId;
 use crate::reporting::Handler;
+use crate::stdlib::FunctionRegistry;
 use crate::ty::check::TypeAnalysis;
 use crate::FrontendConfig;
+use std::collections::HashSet;
 
 // Export output types.
+pub(crate) use self::constant_evaluation::ConstantTable;
 pub(crate) use self::graph_based_analysis::GraphAnalysisResult;
 pub(crate) use self::naming::DeclarationTable;
 pub(crate) use crate::ty::check::TypeTable;
@@ -24,6 +31,7 @@ pub(crate) use crate::ty::check::TypeTable;
 pub(crate) struct Report {
     pub(crate) declaration_table: DeclarationTable,
     pub(crate) type_table: TypeTable,
+    pub(crate) constants: ConstantTable,
     pub(crate) graph_analysis_result: GraphAnalysisResult,
 }
 
@@ -31,47 +39,77 @@ impl Report {
     fn new(
         declaration_table: DeclarationTable,
         type_table: TypeTable,
+        constants: ConstantTable,
         graph_analysis_result: GraphAnalysisResult,
     ) -> Report {
-        Report { declaration_table, type_table, graph_analysis_result }
+        Report { declaration_table, type_table, constants, graph_analysis_result }
     }
 }
 
-pub(crate) fn analyze(spec: &RTLolaAst, handler: &Handler, config: FrontendConfig) -> Result<Report, ()> {
+pub(crate) fn analyze(
+    spec: &RTLolaAst,
+    handler: &Handler,
+    config: FrontendConfig,
+    functions: &FunctionRegistry,
+) -> Result<Report, ()> {
     ast::verify::Verifier::new(spec, handler).check();
 
     if handler.contains_error() {
-        handler.error("aborting due to previous error");
+        handler.abort();
         return Err(());
     }
 
-    let mut naming_analyzer = NamingAnalysis::new(&handler, config);
+    let mut naming_analyzer = NamingAnalysis::new(&handler, config, functions);
     let mut decl_table = naming_analyzer.check(spec);
 
     if handler.contains_error() {
-        handler.error("aborting due to previous error");
+        // type errors of declarations unaffected by the naming errors are reported as well
+        let unaffected = without(spec, &naming_analyzer.poisoned());
+        let _ = TypeAnalysis::new(&handler, &mut decl_table, functions).check(&unaffected);
+        handler.abort();
         return Err(());
     }
 
-    let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+    let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, functions);
     let type_table = type_analysis.check(&spec);
     assert_eq!(type_table.is_none(), handler.contains_error());
 
     if handler.contains_error() {
-        handler.error("aborting due to previous error");
+        handler.abort();
         return Err(());
     }
 
     let tt = type_table.unwrap();
 
+    let constants = ConstantEvaluation::new(&handler, &decl_table, &tt).evaluate(spec);
+
+    if handler.contains_error() {
+        handler.abort();
+        return Err(());
+    }
+
     let graph_result = graph_based_analysis::analyze(spec, &decl_table, &tt, &handler);
 
     if handler.contains_error() || graph_result.is_err() {
-        handler.error("aborting due to previous error");
+        handler.abort();
         return Err(());
     }
 
     let graph_res = graph_result.unwrap();
 
-    Ok(Report::new(decl_table, tt, graph_res))
+    Ok(Report::new(decl_table, tt, constants, graph_res))
+}
+
+/// Returns the specification without the given constants, streams, and triggers.
+fn without(spec: &RTLolaAst, excluded: &HashSet<NodeId>) -> RTLolaAst {
+    RTLolaAst {
+        imports: spec.imports.clone(),
+        constants: spec.constants.iter().filter(|c| !excluded.contains(&c.id)).cloned().collect(),
+        inputs: spec.inputs.iter().filter(|i| !excluded.contains(&i.id)).cloned().collect(),
+        outputs: spec.outputs.iter().filter(|o| !excluded.contains(&o.id)).cloned().collect(),
+        trigger: spec.trigger.iter().filter(|t| !excluded.contains(&t.id)).cloned().collect(),
+        type_declarations: spec.type_declarations.clone(),
+        type_aliases: spec.type_aliases.clone(),
+        enum_declarations: spec.enum_declarations.clone(),
+    }
 }
@@ -0,0 +1,260 @@
+//! This module evaluates the expressions defining constants, e.g., `constant limit: Int64 := 3 * base + 1`.
+//!
+//! Constant expressions consist of literals, other non-configurable constants, let-expressions, unary and binary operators, and conditionals.
+//! The values are folded after the type analysis, so the lowering inlines them like literals.
+//! Integers are evaluated with 128 bits and checked against the bounds of their type after every operation.
+
+use crate::analysis::naming::{Declaration, DeclarationTable};
+use crate::analysis::TypeTable;
+use crate::ast::{BinOp, Constant, Expression, ExpressionKind, LitKind, Literal, RTLolaAst, UnOp};
+use crate::ir;
+use crate::reporting::{Code, Handler, LabeledSpan};
+use crate::ty::{Number, ValueTy};
+use num::ToPrimitive;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use uom::si::time::second;
+
+/// The values of the constants, excluding those whose evaluation failed.
+pub(crate) type ConstantTable = HashMap<crate::parse::NodeId, ir::Constant>;
+
+/// The value of a constant expression while it is evaluated.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Bool(bool),
+    Str(String),
+    Number(Number),
+}
+
+pub(crate) struct ConstantEvaluation<'a> {
+    handler: &'a Handler,
+    declarations: &'a DeclarationTable,
+    types: &'a TypeTable,
+    /// The evaluated constants, `None` if the evaluation failed, so it is reported only once.
+    values: HashMap<crate::parse::NodeId, Option<Value>>,
+    /// The constants being evaluated, innermost last, to detect constants defined in terms of themselves.
+    evaluating: Vec<crate::parse::NodeId>,
+}
+
+impl<'a> ConstantEvaluation<'a> {
+    pub(crate) fn new(handler: &'a Handler, declarations: &'a DeclarationTable, types: &'a TypeTable) -> Self {
+        ConstantEvaluation { handler, declarations, types, values: HashMap::new(), evaluating: Vec::new() }
+    }
+
+    /// Evaluates all constants of the specification, reporting those that are not defined by constant expressions.
+    pub(crate) fn evaluate(mut self, spec: &RTLolaAst) -> ConstantTable {
+        for constant in &spec.constants {
+            self.constant(constant);
+        }
+        spec.constants
+            .iter()
+            .filter_map(|constant| {
+                let value = self.values[&constant.id].clone()?;
+                Some((constant.id, self.to_constant(value, self.types.get_value_type(constant.id))))
+            })
+            .collect()
+    }
+
+    fn constant(&mut self, constant: &Constant) -> Option<Value> {
+        if let Some(value) = self.values.get(&constant.id) {
+            return value.clone();
+        }
+        if self.evaluating.contains(&constant.id) {
+            self.handler.error_with_span(
+                Code::NonConstantExpression,
+                &format!("the constant `{}` is defined in terms of itself", constant.name),
+                LabeledSpan::new(constant.name.span, "cyclic definition", true),
+            );
+            self.values.insert(constant.id, None);
+            return None;
+        }
+        self.evaluating.push(constant.id);
+        let value = self.expression(&constant.value);
+        self.evaluating.pop();
+        // a cyclic definition is already reported at the constant closing the cycle
+        self.values.entry(constant.id).or_insert(value).clone()
+    }
+
+    fn expression(&mut self, expr: &Expression) -> Option<Value> {
+        use ExpressionKind::*;
+        let value = match &expr.kind {
+            Lit(literal) => self.literal(literal, self.types.get_value_type(expr.id)),
+            Ident(name) => match self.declarations.get(&expr.id) {
+                // configurable constants are only known when the monitor starts
+                Some(Declaration::Const(constant)) if !constant.configurable => self.constant(constant)?,
+                Some(Declaration::Let(binding)) => self.expression(&binding.expr)?,
+                _ => {
+                    self.handler.error_with_span(
+                        Code::NonConstantExpression,
+                        &format!("cannot refer to `{}` in a constant", name),
+                        LabeledSpan::new(expr.span, "not a constant", true),
+                    );
+                    return None;
+                }
+            },
+            ParenthesizedExpression(_, inner, _) => self.expression(inner)?,
+            Let(_, body) => self.expression(body)?,
+            Ite(condition, consequence, alternative) => match self.expression(condition)? {
+                Value::Bool(true) => self.expression(consequence)?,
+                Value::Bool(false) => self.expression(alternative)?,
+                value => unreachable!("the type checker ensures a boolean condition, found {:?}", value),
+            },
+            Unary(op, operand) => {
+                let operand = self.expression(operand)?;
+                self.unary(*op, operand, self.types.get_value_type(expr.id))
+            }
+            Binary(op, left, right) => {
+                let (left, right) = (self.expression(left)?, self.expression(right)?);
+                match self.binary(*op, left, right) {
+                    Some(value) => value,
+                    None => {
+                        self.handler.error_with_span(
+                            Code::ConstantEvaluationFailed,
+                            &format!("cannot evaluate `{}`", expr),
+                            LabeledSpan::new(
+                                expr.span,
+                                "division by zero, negative exponent, or too large shift",
+                                true,
+                            ),
+                        );
+                        return None;
+                    }
+                }
+            }
+            _ => {
+                self.handler.error_with_span(
+                    Code::NonConstantExpression,
+                    "constants are defined by constant expressions",
+                    LabeledSpan::new(expr.span, "not allowed in a constant", true),
+                );
+                return None;
+            }
+        };
+        self.check_bounds(value, expr)
+    }
+
+    /// Returns the value if its type can represent it and reports an overflow otherwise.
+    fn check_bounds(&self, value: Value, expr: &Expression) -> Option<Value> {
+        let ty = self.types.get_value_type(expr.id);
+        let bounds = match ty {
+            ValueTy::Int(ty) => ty.bounds(),
+            ValueTy::UInt(ty) => ty.bounds(),
+            _ => return Some(value),
+        };
+        match value {
+            Value::Number(number @ Number::Integer(_)) if number.to_integer(bounds).is_none() => {
+                self.handler.error_with_span(
+                    Code::ConstantEvaluationFailed,
+                    &format!("evaluating `{}` overflows `{}`", expr, ty),
+                    LabeledSpan::new(expr.span, "overflow", true),
+                );
+                None
+            }
+            value => Some(value),
+        }
+    }
+
+    fn literal(&self, literal: &Literal, ty: &ValueTy) -> Value {
+        match &literal.kind {
+            LitKind::Str(s) | LitKind::RawStr(s) => Value::Str(s.clone()),
+            LitKind::Bool(b) => Value::Bool(*b),
+            LitKind::Variant(_, variant) => match ty {
+                // variants are represented by their index
+                ValueTy::Enum(_, variants) => Value::Number(Number::Integer(
+                    variants.iter().position(|v| v == variant).expect("checked by naming analysis") as i128,
+                )),
+                _ => unreachable!("checked by type checker {}", ty),
+            },
+            LitKind::Numeric(_, Some(_)) => {
+                // durations are represented by their length in seconds
+                let seconds = literal.parse_duration().expect("checked by type checker").get::<second>();
+                Value::Number(Number::Float(*seconds.numer() as f64 / *seconds.denom() as f64))
+            }
+            LitKind::Numeric(_, None) => match ty {
+                ValueTy::Float(_) => {
+                    Value::Number(Number::Float(literal.parse_numeric().expect("checked by type checker")))
+                }
+                _ => Value::Number(Number::Integer(literal.parse_numeric().expect("checked by type checker"))),
+            },
+        }
+    }
+
+    fn unary(&self, op: UnOp, operand: Value, ty: &ValueTy) -> Value {
+        match (op, operand) {
+            (UnOp::Not, Value::Bool(b)) => Value::Bool(!b),
+            (UnOp::Neg, Value::Number(Number::Integer(i))) => Value::Number(Number::Integer(-i)),
+            (UnOp::Neg, Value::Number(Number::Float(f))) => Value::Number(Number::Float(-f)),
+            (UnOp::BitNot, Value::Number(Number::Integer(i))) => match ty {
+                // the complement of an unsigned integer is taken within its bit width
+                ValueTy::UInt(ty) => Value::Number(Number::Integer(ty.bounds().1 - i)),
+                _ => Value::Number(Number::Integer(!i)),
+            },
+            (op, operand) => unreachable!("the type checker rejects {:?} {:?}", op, operand),
+        }
+    }
+
+    /// Applies the binary operator, returns `None` for a division by zero, a negative exponent, or a too large shift of an integer.
+    fn binary(&self, op: BinOp, left: Value, right: Value) -> Option<Value> {
+        use BinOp::*;
+        use Number::{Float, Integer};
+        let ordering = match (&left, &right) {
+            (Value::Number(Integer(l)), Value::Number(Integer(r))) => l.partial_cmp(r),
+            (Value::Number(Float(l)), Value::Number(Float(r))) => l.partial_cmp(r),
+            // booleans and strings are only compared for equality
+            (l, r) if l == r => Some(Ordering::Equal),
+            _ => None,
+        };
+        let compared = |accept: &[Ordering]| Some(Value::Bool(ordering.map_or(false, |o| accept.contains(&o))));
+        let number = |n| Some(Value::Number(n));
+        match (op, left, right) {
+            (Eq, ..) => compared(&[Ordering::Equal]),
+            (Ne, ..) => Some(Value::Bool(ordering != Some(Ordering::Equal))),
+            (Lt, ..) => compared(&[Ordering::Less]),
+            (Le, ..) => compared(&[Ordering::Less, Ordering::Equal]),
+            (Gt, ..) => compared(&[Ordering::Greater]),
+            (Ge, ..) => compared(&[Ordering::Greater, Ordering::Equal]),
+            (And, Value::Bool(l), Value::Bool(r)) => Some(Value::Bool(l && r)),
+            (Or, Value::Bool(l), Value::Bool(r)) => Some(Value::Bool(l || r)),
+            (op, Value::Number(Integer(l)), Value::Number(Integer(r))) => match op {
+                Add => l.checked_add(r).map(Integer).and_then(number),
+                Sub => l.checked_sub(r).map(Integer).and_then(number),
+                Mul => l.checked_mul(r).map(Integer).and_then(number),
+                Div => l.checked_div(r).map(Integer).and_then(number),
+                Rem => l.checked_rem(r).map(Integer).and_then(number),
+                Pow => r.to_u32().and_then(|r| l.checked_pow(r)).map(Integer).and_then(number),
+                Shl => r.to_u32().and_then(|r| l.checked_shl(r)).map(Integer).and_then(number),
+                Shr => r.to_u32().and_then(|r| l.checked_shr(r)).map(Integer).and_then(number),
+                BitAnd => number(Integer(l & r)),
+                BitOr => number(Integer(l | r)),
+                BitXor => number(Integer(l ^ r)),
+                _ => unreachable!("the type checker rejects {:?} on integers", op),
+            },
+            (op, Value::Number(Float(l)), Value::Number(Float(r))) => match op {
+                Add => number(Float(l + r)),
+                Sub => number(Float(l - r)),
+                Mul => number(Float(l * r)),
+                Div => number(Float(l / r)),
+                Rem => number(Float(l % r)),
+                Pow => number(Float(l.powf(r))),
+                _ => unreachable!("the type checker rejects {:?} on floats", op),
+            },
+            (op, left, right) => unreachable!("the type checker rejects {:?} {:?} {:?}", left, op, right),
+        }
+    }
+
+    /// Converts the value into a constant of the IR with the given type.
+    fn to_constant(&self, value: Value, ty: &ValueTy) -> ir::Constant {
+        match (value, ty) {
+            (Value::Bool(b), _) => ir::Constant::Bool(b),
+            (Value::Str(s), _) => ir::Constant::Str(s),
+            (Value::Number(Number::Integer(i)), ValueTy::UInt(_))
+            | (Value::Number(Number::Integer(i)), ValueTy::Enum(..)) => {
+                ir::Constant::UInt(i.to_u64().expect("checked against the bounds"))
+            }
+            (Value::Number(Number::Integer(i)), _) => {
+                ir::Constant::Int(i.to_i64().expect("checked against the bounds"))
+            }
+            (Value::Number(Number::Float(f)), _) => ir::Constant::Float(f),
+        }
+    }
+}
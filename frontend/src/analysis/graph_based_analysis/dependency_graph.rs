@@ -3,7 +3,7 @@ use crate::analysis::naming::{Declaration, DeclarationTable};
 use crate::ast;
 use crate::ast::{ExpressionKind, Output, RTLolaAst, TemplateSpec};
 use crate::parse::{NodeId, Span};
-use crate::reporting::{DiagnosticBuilder, Handler, LabeledSpan, Level};
+use crate::reporting::{Code, DiagnosticBuilder, Handler, LabeledSpan, Level};
 use crate::ty::check::TypeTable;
 use num::traits::sign::Signed;
 use petgraph::algo::tarjan_scc;
@@ -221,12 +221,22 @@ impl<'a> DependencyAnalyser<'a> {
     fn add_edges_for_expression(
         &mut self,
         current_node: NodeIndex<u32>,
-        expr: &crate::ast::Expression,
+        expr: &ast::Expression,
         location: Location,
         mapping: &mut StreamMapping,
     ) {
         match &expr.kind {
-            ExpressionKind::Tuple(elements) | ExpressionKind::Function(_, _, elements) => {
+            ExpressionKind::Tuple(elements)
+            | ExpressionKind::Array(elements)
+            | ExpressionKind::Function(_, _, elements) => {
+                // an instance of a parameterized output is accessed like a function, e.g., `count(id)`
+                if let Some(Declaration::ParamOut(output)) = self.naming_table.get(&expr.id) {
+                    self.dependency_graph.add_edge(
+                        current_node,
+                        mapping[&output.id].index,
+                        StreamDependency::Access(location, Offset::Discrete(0), expr.span),
+                    );
+                }
                 elements
                     .iter()
                     .for_each(|element| self.add_edges_for_expression(current_node, element, location, mapping));
@@ -238,6 +248,7 @@ impl<'a> DependencyAnalyser<'a> {
             }
             ExpressionKind::ParenthesizedExpression(_, expr, _)
             | ExpressionKind::Unary(_, expr)
+            | ExpressionKind::Cast(expr, _)
             | ExpressionKind::StreamAccess(expr, _) => {
                 self.add_edges_for_expression(current_node, expr, location, mapping);
             }
@@ -250,8 +261,17 @@ impl<'a> DependencyAnalyser<'a> {
                 self.add_edges_for_expression(current_node, left, location, mapping);
                 self.add_edges_for_expression(current_node, right, location, mapping);
             }
+            ExpressionKind::Let(binding, body) => {
+                self.add_edges_for_expression(current_node, &binding.expr, location, mapping);
+                self.add_edges_for_expression(current_node, body, location, mapping);
+            }
             ExpressionKind::Ident(_) => match &self.naming_table[&expr.id] {
-                Declaration::Type(_) | Declaration::Func(_) | Declaration::Param(_) | Declaration::Const(_) => {}
+                // the accesses of a binding are added with its expression
+                Declaration::Type(_)
+                | Declaration::Func(_)
+                | Declaration::Param(_)
+                | Declaration::Let(_)
+                | Declaration::Const(_) => {}
                 Declaration::In(input) => {
                     let target_stream_id = input.id;
                     let target_stream_entry = mapping[&target_stream_id];
@@ -340,7 +360,11 @@ impl<'a> DependencyAnalyser<'a> {
             {
                 StreamDependency::Access(_, offset, span) => match offset {
                     Offset::Time(_) | Offset::SlidingWindow => {
-                        self.handler.error_with_span("cycle with periodic stream", LabeledSpan::new(*span, "", true));
+                        self.handler.error_with_span(
+                            Code::CycleWithPeriodicStream,
+                            "cycle with periodic stream",
+                            LabeledSpan::new(*span, "", true),
+                        );
                         true
                     }
                     Offset::Discrete(_) => false,
@@ -399,6 +423,9 @@ impl<'a> DependencyAnalyser<'a> {
                 self.handle_extend(&mut mapping, current_node, template_spec);
                 self.handle_terminate(&mut mapping, current_node, template_spec);
             }
+            if let Some(ref termination) = output.termination {
+                self.add_edges_for_expression(current_node, termination, Location::Terminate, &mut mapping);
+            }
         }
     }
 
@@ -617,6 +644,7 @@ impl<'a> DependencyAnalyser<'a> {
                 builder.add_span_with_label(span, label.as_str(), false);
             } else {
                 let mut diagnostic_builder = self.handler.build_error_with_span(
+                    Code::ZeroWeightCycle,
                     &"There is a 0 weight cycle.".to_string(),
                     LabeledSpan::new(span, label.as_str(), false),
                 );
@@ -686,6 +714,7 @@ impl<'a> DependencyAnalyser<'a> {
                 builder.add_span_with_label(span, label.as_str(), false);
             } else {
                 let mut diagnostic_builder = self.handler.build_diagnostic(
+                    Code::PositiveWeightCycle,
                     "There is a positive weight cycle. This is a problem for monitoring.",
                     Level::Warning,
                 );
@@ -757,6 +786,7 @@ mod tests {
     use crate::parse::parse;
     use crate::parse::SourceMapper;
     use crate::reporting::Handler;
+    use crate::stdlib::STANDARD_LIBRARY;
     use crate::ty::check::TypeAnalysis;
     use crate::FrontendConfig;
     use std::path::PathBuf;
@@ -765,9 +795,9 @@ mod tests {
     fn check_graph(content: &str, num_errors: usize, num_warnings: usize) {
         let handler = Handler::new(SourceMapper::new(PathBuf::new(), content));
         let ast = parse(content, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
-        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = naming_analyzer.check(&ast);
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         let type_table = type_analysis.check(&ast);
         let type_table = type_table.as_ref().expect("We expect in these tests that the type analysis checks out.");
         let _dependency_analysis = analyse_dependencies(&ast, &decl_table, &handler, &type_table);
@@ -144,7 +144,16 @@ fn build_compute_graph(mut dependency_graph: DependencyGraph) -> ComputationGrap
                                     ),
                                 };
                             }
-                            Ordering::Greater => {} // no need to add dependency for positive edges
+                            Ordering::Greater => {
+                                // the deferred evaluation needs the latest value
+                                if *location == Location::Expression {
+                                    computation_graph.add_edge(
+                                        mapping[&source_id].evaluate,
+                                        mapping[&target_id].evaluate,
+                                        (),
+                                    );
+                                }
+                            }
                         }
                     }
                     Offset::Time(offset) => {
@@ -207,6 +216,7 @@ mod tests {
     use crate::parse::NodeId;
     use crate::parse::SourceMapper;
     use crate::reporting::Handler;
+    use crate::stdlib::STANDARD_LIBRARY;
     use crate::ty::check::TypeAnalysis;
     use crate::FrontendConfig;
     use std::path::PathBuf;
@@ -226,9 +236,9 @@ mod tests {
     ) {
         let handler = Handler::new(SourceMapper::new(PathBuf::new(), content));
         let ast = parse(content, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
-        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = naming_analyzer.check(&ast);
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         let type_table = type_analysis.check(&ast);
         let type_table = type_table.as_ref().expect("We expect in these tests that the type analysis checks out.");
         let dependency_analysis = analyse_dependencies(&ast, &decl_table, &handler, &type_table);
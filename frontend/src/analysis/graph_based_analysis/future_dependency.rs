@@ -1,15 +1,23 @@
 use crate::analysis::graph_based_analysis::get_ast_id;
 use crate::analysis::graph_based_analysis::DependencyGraph;
+use crate::analysis::graph_based_analysis::Location;
 use crate::analysis::graph_based_analysis::NIx;
 use crate::analysis::graph_based_analysis::Offset;
 use crate::analysis::graph_based_analysis::StreamDependency::Access;
 use crate::analysis::graph_based_analysis::TimeOffset;
 use crate::parse::NodeId;
+use crate::reporting::{Code, Handler, LabeledSpan};
+use crate::ty::check::TypeTable;
+use crate::ty::StreamTy;
+use petgraph::visit::EdgeRef;
 use petgraph::Direction;
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub(crate) type FutureDependentStreams = HashSet<NodeId>;
 
+/// The look-ahead of every stream and trigger, i.e., the number of its own activations by which its evaluation is deferred until all accessed future values arrived.
+pub(crate) type LookAhead = HashMap<NodeId, u32>;
+
 /// Computes the set of streams and triggers (represented by their `NodeId`)
 /// that (transitively) depend on future values.
 pub(crate) fn future_dependent_stream(dependency_graph: &DependencyGraph) -> FutureDependentStreams {
@@ -64,6 +72,97 @@ fn propagate_future_dependence(
     }
 }
 
+/// Computes the maximal look-ahead of every stream and trigger:
+/// a stream accessing `s[k]` has to wait for `k` more values of `s` and for the look-ahead of `s` itself,
+/// and for one more value if it accesses a past value of `s` that `s` is yet to resolve.
+/// Reports streams whose look-ahead has no bound, i.e., that are on a cycle of positive weight,
+/// and future dependencies outside of the fragment the evaluator resolves:
+/// event-driven streams accessing streams with the same activation by discrete offsets.
+pub(crate) fn determine_look_ahead(
+    dependency_graph: &DependencyGraph,
+    type_table: &TypeTable,
+    handler: &Handler,
+) -> LookAhead {
+    let mut look_ahead: HashMap<NIx, i64> = dependency_graph.node_indices().map(|node| (node, 0)).collect();
+    // Without a cycle of positive weight, the longest paths are found after as many rounds as there are nodes.
+    let mut changed = Vec::new();
+    for _ in 0..=dependency_graph.node_count() {
+        changed.clear();
+        for node in dependency_graph.node_indices() {
+            let required = dependency_graph
+                .edges_directed(node, Direction::Outgoing)
+                .filter_map(|edge| match edge.weight() {
+                    Access(_, Offset::Discrete(offset), _) => {
+                        let required = look_ahead[&edge.target()] + i64::from(*offset);
+                        // a past value is only read once the accessed stream moved on, so it exists in its buffer
+                        Some(if *offset < 0 && required >= 0 { required + 1 } else { required })
+                    }
+                    _ => None,
+                })
+                .max()
+                .unwrap_or(0);
+            if required > look_ahead[&node] {
+                look_ahead.insert(node, required);
+                changed.push(node);
+            }
+        }
+        if changed.is_empty() {
+            break;
+        }
+    }
+    if let Some(&node) = changed.first() {
+        let span = dependency_graph
+            .edges_directed(node, Direction::Outgoing)
+            .filter_map(|edge| match edge.weight() {
+                Access(_, Offset::Discrete(offset), span) => {
+                    Some((look_ahead[&edge.target()] + i64::from(*offset), *span))
+                }
+                _ => None,
+            })
+            .max_by_key(|(required, _)| *required)
+            .map(|(_, span)| span)
+            .expect("the look-ahead only grows through discrete accesses");
+        handler.error_with_span(
+            Code::UnboundedLookAhead,
+            "the stream depends on its own future values, so its evaluation is deferred forever",
+            LabeledSpan::new(span, "on a cycle of future offsets", true),
+        );
+        return LookAhead::new();
+    }
+
+    let id = |node: NIx| get_ast_id(dependency_graph.node_weight(node).expect("We iterate over the node indices"));
+    for edge in dependency_graph.edge_references() {
+        let (source, target) = (edge.source(), edge.target());
+        if look_ahead[&source] == 0 && look_ahead[&target] == 0 {
+            continue;
+        }
+        let (location, offset, span) = match edge.weight() {
+            Access(location, offset, span) => (location, offset, span),
+            _ => continue,
+        };
+        let source_ty = type_table.get_stream_type(id(source));
+        let reason = if let StreamTy::RealTime(_) = source_ty {
+            "real-time streams cannot wait for future values"
+        } else if *location != Location::Expression {
+            "future values are only resolved in the expression of a stream"
+        } else if let Offset::Discrete(_) = offset {
+            if source_ty == type_table.get_stream_type(id(target)) {
+                continue;
+            }
+            "the accessed stream has a different activation"
+        } else {
+            "sliding windows and real-time offsets cannot wait for future values"
+        };
+        handler.error_with_span(
+            Code::UnsupportedLookAhead,
+            "the future values of this access cannot be resolved",
+            LabeledSpan::new(*span, reason, true),
+        );
+    }
+
+    look_ahead.into_iter().map(|(node, look_ahead)| (id(node), look_ahead as u32)).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::analysis::graph_based_analysis::dependency_graph::analyse_dependencies;
@@ -73,6 +172,7 @@ mod tests {
     use crate::parse::parse;
     use crate::parse::SourceMapper;
     use crate::reporting::Handler;
+    use crate::stdlib::STANDARD_LIBRARY;
     use crate::ty::check::TypeAnalysis;
     use crate::FrontendConfig;
     use std::path::PathBuf;
@@ -87,9 +187,9 @@ mod tests {
     ) {
         let handler = Handler::new(SourceMapper::new(PathBuf::new(), content));
         let spec = parse(content, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
-        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = naming_analyzer.check(&spec);
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         let type_table = type_analysis.check(&spec);
         let type_table = type_table.as_ref().expect("We expect in these tests that the type analysis checks out.");
 
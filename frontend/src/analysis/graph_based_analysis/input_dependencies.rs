@@ -2,47 +2,48 @@ use crate::analysis::graph_based_analysis::{get_ast_id, DependencyGraph, NIx, St
 use crate::parse::NodeId;
 use petgraph::visit::IntoNodeIdentifiers;
 use petgraph::Direction;
+use rayon::prelude::*;
 use std::collections::{HashMap, HashSet};
 
 pub(crate) type RequiredInputs = HashMap<NodeId, Vec<NodeId>>;
 
 pub(crate) fn determine_required_inputs(dependency_graph: &DependencyGraph) -> RequiredInputs {
     let mut input_dependencies: RequiredInputs = HashMap::new();
+    let mut inputs = Vec::new();
     for node in dependency_graph.node_identifiers() {
         let node_info = dependency_graph.node_weight(node).expect("we iterate over the NIx");
         input_dependencies.insert(get_ast_id(node_info), Vec::new());
+        if let StreamNode::ClassicInput(node_id) = node_info {
+            inputs.push((node, *node_id));
+        }
     }
 
-    for node in dependency_graph.node_identifiers() {
-        let node_info = dependency_graph.node_weight(node).expect("we iterate over the NIx");
-        let mut visited: HashSet<NIx> = HashSet::new();
-        let mut stack: Vec<NIx> = Vec::new();
-        if let StreamNode::ClassicInput(node_id) = node_info {
-            visited.clear();
-            stack.push(node);
-            visited.insert(node);
-            input_dependencies.get_mut(node_id).unwrap().push(*node_id);
-            bfs_color_reachable(&mut visited, &mut stack, *node_id, &mut input_dependencies, dependency_graph);
+    // the searches from different inputs are independent, they are merged in the order of the inputs to stay deterministic
+    let reachable: Vec<(NodeId, Vec<NodeId>)> =
+        inputs.par_iter().map(|&(node, node_id)| (node_id, reachable_streams(node, dependency_graph))).collect();
+    for (node_id, streams) in reachable {
+        input_dependencies.get_mut(&node_id).unwrap().push(node_id);
+        for id in streams {
+            input_dependencies.get_mut(&id).unwrap().push(node_id);
         }
     }
     input_dependencies
 }
 
-fn bfs_color_reachable(
-    visited: &mut HashSet<NIx>,
-    stack: &mut Vec<NIx>,
-    node_id: NodeId,
-    input_dependencies: &mut RequiredInputs,
-    dependency_graph: &DependencyGraph,
-) {
-    while !stack.is_empty() {
-        let top = stack.pop().expect("We checked for empty stack");
+/// Returns the streams depending on the input, directly or transitively.
+fn reachable_streams(input: NIx, dependency_graph: &DependencyGraph) -> Vec<NodeId> {
+    let mut visited: HashSet<NIx> = HashSet::new();
+    let mut stack: Vec<NIx> = vec![input];
+    let mut reachable = Vec::new();
+    visited.insert(input);
+    while let Some(top) = stack.pop() {
         for dependent_stream in dependency_graph.neighbors_directed(top, Direction::Incoming) {
             if visited.insert(dependent_stream) {
-                let id = get_ast_id(dependency_graph.node_weight(dependent_stream).expect("We just got this NIx"));
-                input_dependencies.get_mut(&id).unwrap().push(node_id);
+                reachable
+                    .push(get_ast_id(dependency_graph.node_weight(dependent_stream).expect("We just got this NIx")));
                 stack.push(dependent_stream)
             }
         }
     }
+    reachable
 }
@@ -13,7 +13,7 @@ use std::cmp::min;
 use uom::si::frequency::hertz;
 use uom::si::time::second;
 
-fn is_efficient_operator(op: WindowOperation) -> bool {
+fn is_efficient_operator(op: &WindowOperation) -> bool {
     match op {
         WindowOperation::Count
         | WindowOperation::Min
@@ -23,18 +23,26 @@ fn is_efficient_operator(op: WindowOperation) -> bool {
         | WindowOperation::Average
         | WindowOperation::Disjunction
         | WindowOperation::Conjunction
-        | WindowOperation::Integral => true,
+        | WindowOperation::Integral
+        | WindowOperation::First
+        | WindowOperation::Last
+        | WindowOperation::Custom(_) => true,
+        WindowOperation::Median | WindowOperation::Percentile(_) => false,
     }
 }
 
-fn determine_needed_window_memory(type_size: u128, number_of_element: u128, op: WindowOperation) -> u128 {
+fn determine_needed_window_memory(type_size: u128, number_of_element: u128, op: &WindowOperation) -> u128 {
     match op {
         WindowOperation::Count => number_of_element * 8,
         WindowOperation::Min | WindowOperation::Max => number_of_element * type_size,
+        WindowOperation::First | WindowOperation::Last => number_of_element * type_size,
+        // the summary of a bucket of a registered aggregation is assumed to be as large as a value
+        WindowOperation::Custom(_) => number_of_element * type_size,
         WindowOperation::Sum | WindowOperation::Product => number_of_element * type_size,
         WindowOperation::Average => number_of_element * (8 + type_size),
         WindowOperation::Conjunction | WindowOperation::Disjunction => number_of_element * type_size,
         WindowOperation::Integral => number_of_element * (4 * 8 + 1 + 8),
+        WindowOperation::Median | WindowOperation::Percentile(_) => number_of_element * type_size,
     }
 }
 
@@ -62,7 +70,11 @@ fn add_sliding_windows(
             };
         }
         MissingExpression => return MemoryBound::Unknown,
-        Unary(_, inner) | ParenthesizedExpression(_, inner, _) | StreamAccess(inner, _) | Offset(inner, _) => {
+        Unary(_, inner)
+        | ParenthesizedExpression(_, inner, _)
+        | StreamAccess(inner, _)
+        | Offset(inner, _)
+        | Cast(inner, _) => {
             match add_sliding_windows(inner, type_table, declaration_table) {
                 MemoryBound::Bounded(u) => required_memory += u,
                 MemoryBound::Unbounded => return MemoryBound::Unbounded,
@@ -86,7 +98,7 @@ fn add_sliding_windows(
                 MemoryBound::Unknown => unknown_size = true,
             };
         }
-        Function(_, _, elements) | Tuple(elements) => {
+        Function(_, _, elements) | Tuple(elements) | Array(elements) => {
             for expr in elements {
                 match add_sliding_windows(expr, type_table, declaration_table) {
                     MemoryBound::Bounded(u) => required_memory += u,
@@ -95,6 +107,15 @@ fn add_sliding_windows(
                 };
             }
         }
+        Let(binding, body) => {
+            for expr in &[&binding.expr, body.as_ref()] {
+                match add_sliding_windows(expr, type_table, declaration_table) {
+                    MemoryBound::Bounded(u) => required_memory += u,
+                    MemoryBound::Unbounded => return MemoryBound::Unbounded,
+                    MemoryBound::Unknown => unknown_size = true,
+                };
+            }
+        }
         Method(inner, _, _, params) => {
             match add_sliding_windows(inner, type_table, declaration_table) {
                 MemoryBound::Bounded(u) => required_memory += u,
@@ -150,7 +171,7 @@ fn add_sliding_windows(
                     0
                 }
             };
-            let efficient_operator: bool = is_efficient_operator(*aggregation);
+            let efficient_operator: bool = is_efficient_operator(aggregation);
             match (stream_ty, efficient_operator) {
                 (StreamTy::Event(_), false) => {
                     return MemoryBound::Unbounded;
@@ -167,7 +188,7 @@ fn add_sliding_windows(
                 }
                 (StreamTy::Event(_), true) => {
                     let number_of_panes = 64;
-                    required_memory += determine_needed_window_memory(value_type_size, number_of_panes, *aggregation);
+                    required_memory += determine_needed_window_memory(value_type_size, number_of_panes, aggregation);
                 }
                 (StreamTy::RealTime(freq), true) => {
                     let number_of_panes = 64;
@@ -181,8 +202,7 @@ fn add_sliding_windows(
                             .expect("Number of complete periods does not fit in u128"),
                         number_of_panes,
                     );
-                    required_memory +=
-                        determine_needed_window_memory(value_type_size, number_of_elements, *aggregation);
+                    required_memory += determine_needed_window_memory(value_type_size, number_of_elements, aggregation);
                 }
                 _ => unreachable!("checked in type checking"),
             }
@@ -300,8 +320,8 @@ pub(crate) fn determine_worst_case_memory_consumption(
                 required_memory += 500;
             }
         } else {
-            // TODO also add the space for the parametrization of the streams
-            unimplemented!()
+            // the number of instances is only known at runtime
+            return MemoryBound::Unbounded;
         }
     }
     for trigger in &spec.trigger {
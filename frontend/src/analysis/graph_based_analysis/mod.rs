@@ -8,14 +8,14 @@ pub mod space_requirements;
 use super::DeclarationTable;
 use crate::ast::RTLolaAst;
 use crate::parse::{NodeId, Span};
-use crate::reporting::Handler;
+use crate::reporting::{Code, Handler, LabeledSpan};
 use crate::ty::check::TypeTable;
 use petgraph::Directed;
 use petgraph::Graph;
 use uom::si::rational64::Time as UOM_Time;
 
 pub(crate) use self::evaluation_order::EvaluationOrderResult;
-pub(crate) use self::future_dependency::FutureDependentStreams;
+pub(crate) use self::future_dependency::{FutureDependentStreams, LookAhead};
 pub(crate) use self::input_dependencies::RequiredInputs;
 pub(crate) use self::space_requirements::SpaceRequirements;
 use self::space_requirements::TrackingRequirements;
@@ -32,6 +32,7 @@ pub(crate) struct GraphAnalysisResult {
     pub(crate) evaluation_order: EvaluationOrderResult,
     #[allow(dead_code)]
     pub(crate) future_dependent_streams: FutureDependentStreams,
+    pub(crate) look_ahead: LookAhead,
     pub(crate) space_requirements: SpaceRequirements,
     pub(crate) tracking_requirements: TrackingRequirements,
     pub(crate) memory_requirements: MemoryBound,
@@ -47,17 +48,36 @@ pub(crate) fn analyze(
     let dependency_analysis = dependency_graph::analyse_dependencies(spec, declaration_table, &handler, type_table);
 
     if handler.contains_error() {
-        handler.error("aborting due to previous error");
+        handler.abort();
         return Err("Error during dependency analysis.".to_string());
     }
 
+    let look_ahead =
+        future_dependency::determine_look_ahead(&dependency_analysis.dependency_graph, type_table, handler);
+
+    if handler.contains_error() {
+        handler.abort();
+        return Err("Error during look-ahead analysis.".to_string());
+    }
+
     let (evaluation_order_result, pruned_graph) =
         evaluation_order::determine_evaluation_order(dependency_analysis.dependency_graph);
 
-    let future_dependent_streams = future_dependency::future_dependent_stream(&pruned_graph);
+    // both passes only read the pruned graph
+    let (future_dependent_streams, input_dependencies) = rayon::join(
+        || future_dependency::future_dependent_stream(&pruned_graph),
+        || input_dependencies::determine_required_inputs(&pruned_graph),
+    );
 
     let space_requirements = space_requirements::determine_buffer_size(&pruned_graph, &future_dependent_streams);
 
+    check_bounded_memory(spec, &space_requirements, type_table, handler);
+
+    if handler.contains_error() {
+        handler.abort();
+        return Err("Error during memory analysis.".to_string());
+    }
+
     let tracking_requirements =
         space_requirements::determine_tracking_size(&pruned_graph, type_table, &future_dependent_streams)?;
 
@@ -69,11 +89,10 @@ pub(crate) fn analyze(
         declaration_table,
     );
 
-    let input_dependencies = input_dependencies::determine_required_inputs(&pruned_graph);
-
     Ok(GraphAnalysisResult {
         evaluation_order: evaluation_order_result,
         future_dependent_streams,
+        look_ahead,
         space_requirements,
         tracking_requirements,
         memory_requirements,
@@ -81,6 +100,37 @@ pub(crate) fn analyze(
     })
 }
 
+/// Reports the streams annotated with `#[memory(bounded)]` that store all their values or whose values have no bound on their size.
+fn check_bounded_memory(
+    spec: &RTLolaAst,
+    space_requirements: &SpaceRequirements,
+    type_table: &TypeTable,
+    handler: &Handler,
+) {
+    let inputs = spec.inputs.iter().map(|i| (i.id, &i.name, &i.annotations));
+    let outputs = spec.outputs.iter().map(|o| (o.id, &o.name, &o.annotations));
+    for (id, name, annotations) in inputs.chain(outputs) {
+        let annotation = match annotations.iter().find(|a| a.name.name == "memory") {
+            Some(annotation) => annotation,
+            None => continue,
+        };
+        let reason = if let Some(StorageRequirement::Unbounded) = space_requirements.get(&id) {
+            "all values are stored"
+        } else if let MemoryBound::Unbounded = get_byte_size(type_table.get_value_type(id)) {
+            "the values have no bound on their size"
+        } else {
+            continue;
+        };
+        let mut builder = handler.build_error_with_span(
+            Code::UnboundedMemory,
+            &format!("the stream `{}` needs an unbounded amount of memory", name.name),
+            LabeledSpan::new(name.span, reason, true),
+        );
+        builder.add_span_with_label(annotation.span, "required to be bounded here", false);
+        builder.emit();
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) enum StreamNode {
     RTOutput(NodeId, StreamTy),
@@ -173,6 +223,7 @@ fn get_byte_size(value_ty: &ValueTy) -> MemoryBound {
             UIntTy::U32 => 4,
             UIntTy::U64 => 8,
         }),
+        ValueTy::Duration => MemoryBound::Bounded(8),
         ValueTy::Float(float_ty) => MemoryBound::Bounded(match float_ty {
             FloatTy::F16 => 2,
             FloatTy::F32 => 4,
@@ -180,6 +231,8 @@ fn get_byte_size(value_ty: &ValueTy) -> MemoryBound {
         }),
         // an abstract data type, e.g., structs, enums, etc.
         //ValueTy::Adt(AdtDef),
+        // variants are stored as their index
+        ValueTy::Enum(_, _) => MemoryBound::Bounded(4),
         ValueTy::String | ValueTy::Bytes => MemoryBound::Unbounded,
         ValueTy::Tuple(elements) => {
             let mut accu = 0_u128;
@@ -192,6 +245,10 @@ fn get_byte_size(value_ty: &ValueTy) -> MemoryBound {
             }
             MemoryBound::Bounded(accu)
         }
+        ValueTy::Array(element, size) => match get_byte_size(element) {
+            MemoryBound::Bounded(i) => MemoryBound::Bounded(i * *size as u128),
+            bound => bound,
+        },
         // an optional value type, e.g., resulting from accessing a stream with offset -1
         ValueTy::Option(inner) => get_byte_size(inner),
         // Used during type inference
@@ -165,6 +165,7 @@ mod tests {
     use crate::parse::parse;
     use crate::parse::SourceMapper;
     use crate::reporting::Handler;
+    use crate::stdlib::STANDARD_LIBRARY;
     use crate::ty::check::TypeAnalysis;
     use crate::FrontendConfig;
     use std::path::PathBuf;
@@ -184,9 +185,9 @@ mod tests {
     ) {
         let handler = Handler::new(SourceMapper::new(PathBuf::new(), content));
         let spec = parse(content, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
-        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = naming_analyzer.check(&spec);
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         let type_table = type_analysis.check(&spec);
         let type_table = type_table.as_ref().expect("We expect that the version analysis found no error");
 
@@ -225,14 +226,14 @@ mod tests {
     ) {
         let handler = Handler::new(SourceMapper::new(PathBuf::new(), content));
         let spec = parse(content, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
-        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = naming_analyzer.check(&spec);
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         let type_table = type_analysis.check(&spec);
         let type_table = type_table.as_ref().expect("We expect in these tests that the type analysis checks out.");
 
         let dependency_analysis = analyse_dependencies(&spec, &decl_table, &handler, &type_table);
-        let _ = TypeAnalysis::new(&handler, &mut decl_table);
+        let _ = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         let (_, pruned_graph) = determine_evaluation_order(dependency_analysis.dependency_graph);
 
         let future_dependent_stream = future_dependent_stream(&pruned_graph);
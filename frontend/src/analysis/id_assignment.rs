@@ -1,5 +1,6 @@
 use crate::ast::*;
 use crate::parse::NodeId;
+use std::rc::Rc;
 
 pub(crate) fn assign_ids(spec: &mut LolaAst) {
     let mut free_id = 0;
@@ -23,7 +24,7 @@ pub(crate) fn assign_ids(spec: &mut LolaAst) {
         if let Some(ref mut t) = c.ty {
             assign_ids_type(t, &mut next_id);
         }
-        assign_ids_literal(&mut c.literal, &mut next_id);
+        assign_ids_expr(&mut c.value, &mut next_id);
     }
     for i in &mut spec.inputs {
         assert_eq!(i.id, NodeId::DUMMY, "Ids already assigned.");
@@ -118,10 +119,14 @@ where
     E: FnMut() -> NodeId,
 {
     ty.id = next_id();
-    if let TypeKind::Tuple(ref mut elements) = ty.kind {
-        for element in elements.iter_mut() {
-            assign_ids_type(element, next_id);
+    match ty.kind {
+        TypeKind::Tuple(ref mut elements) => {
+            for element in elements.iter_mut() {
+                assign_ids_type(element, next_id);
+            }
         }
+        TypeKind::Array(ref mut element, _) | TypeKind::Optional(ref mut element) => assign_ids_type(element, next_id),
+        _ => {}
     }
 }
 
@@ -174,7 +179,9 @@ where
                 paren.id = next_id();
             }
         }
-        ExpressionKind::Tuple(exprs) => exprs.iter_mut().for_each(|e| assign_ids_expr(e, next_id)),
+        ExpressionKind::Tuple(exprs) | ExpressionKind::Array(exprs) => {
+            exprs.iter_mut().for_each(|e| assign_ids_expr(e, next_id))
+        }
         ExpressionKind::Function(_, types, args) => {
             types.iter_mut().for_each(|ty| assign_ids_type(ty, next_id));
             args.iter_mut().for_each(|e| assign_ids_expr(e, next_id));
@@ -187,6 +194,16 @@ where
             types.iter_mut().for_each(|ty| assign_ids_type(ty, next_id));
             args.iter_mut().for_each(|e| assign_ids_expr(e, next_id));
         }
+        ExpressionKind::Cast(expr, ty) => {
+            assign_ids_expr(expr, next_id);
+            assign_ids_type(ty, next_id);
+        }
+        ExpressionKind::Let(binding, body) => {
+            let binding = Rc::make_mut(binding);
+            binding.id = next_id();
+            assign_ids_expr(&mut binding.expr, next_id);
+            assign_ids_expr(body, next_id);
+        }
     }
 }
 
@@ -230,6 +247,7 @@ mod tests {
             name: Ident::new(String::from("Something"), span()),
             params: Vec::new(),
             ty: Type::new_simple(String::from("something"), span()),
+            annotations: Vec::new(),
             span: span(),
         }
     }
@@ -238,7 +256,8 @@ mod tests {
             id: NodeId::DUMMY,
             name: ident(),
             ty: Some(ty()),
-            literal: Literal::new_bool(false, span()),
+            value: Expression::new(ExpressionKind::Lit(Literal::new_bool(false, span())), span()),
+            configurable: false,
             span: span(),
         }
     }
@@ -252,6 +271,8 @@ mod tests {
             template_spec: None,
             termination: None,
             expression: expr,
+            derived_input: false,
+            annotations: Vec::new(),
             span: span(),
         }
     }
@@ -54,7 +54,7 @@ fn analyse_expression(version_tracker: &mut VersionTracker, expr: &Expression, t
             analyse_expression(version_tracker, nested, false);
         }
         ExpressionKind::MissingExpression => {}
-        ExpressionKind::Tuple(nested_exprs) => {
+        ExpressionKind::Tuple(nested_exprs) | ExpressionKind::Array(nested_exprs) => {
             nested_exprs.iter().for_each(|nested| {
                 analyse_expression(version_tracker, nested, false);
             });
@@ -64,7 +64,13 @@ fn analyse_expression(version_tracker: &mut VersionTracker, expr: &Expression, t
                 analyse_expression(version_tracker, arg, false);
             });
         }
-        ExpressionKind::Field(expr, _) => analyse_expression(version_tracker, expr, false),
+        ExpressionKind::Let(binding, body) => {
+            analyse_expression(version_tracker, &binding.expr, false);
+            analyse_expression(version_tracker, body, false);
+        }
+        ExpressionKind::Field(expr, _) | ExpressionKind::Cast(expr, _) => {
+            analyse_expression(version_tracker, expr, false)
+        }
         ExpressionKind::Method(expr, _, _, args) => {
             analyse_expression(version_tracker, expr, false);
             args.iter().for_each(|arg| {
@@ -273,6 +279,7 @@ mod tests {
     use crate::analysis::naming::NamingAnalysis;
     use crate::parse::parse;
     use crate::parse::SourceMapper;
+    use crate::stdlib::STANDARD_LIBRARY;
     use crate::ty::check::TypeAnalysis;
     use crate::FrontendConfig;
     use std::path::PathBuf;
@@ -293,9 +300,9 @@ mod tests {
     ) {
         let handler = Handler::new(SourceMapper::new(PathBuf::new(), content));
         let ast = parse(content, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
-        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = naming_analyzer.check(&ast);
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         let type_table = type_analysis.check(&ast).expect("We expect the spec to be well-typed");
         let mut version_analyzer = LolaVersionAnalysis::new(&handler, &type_table);
         let version = version_analyzer.analyse(&ast);
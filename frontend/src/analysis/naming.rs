@@ -2,21 +2,22 @@
 
 use crate::ast::*;
 use crate::parse::{Ident, NodeId, Span};
-use crate::reporting::{Handler, LabeledSpan};
-use crate::stdlib;
-use crate::stdlib::FuncDecl;
+use crate::reporting::{Code, Handler, LabeledSpan};
+use crate::stdlib::{FuncDecl, FunctionRegistry};
 use crate::ty::ValueTy;
 use crate::FrontendConfig;
-use std::{collections::HashMap, rc::Rc};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
 
 // These MUST all be lowercase
 // TODO add an static assertion for this
-pub(crate) const KEYWORDS: [&str; 26] = [
+pub(crate) const KEYWORDS: [&str; 29] = [
     "input",
     "output",
     "trigger",
     "import",
     "type",
+    "enum",
     "self",
     "include",
     "invoke",
@@ -32,6 +33,8 @@ pub(crate) const KEYWORDS: [&str; 26] = [
     "and",
     "or",
     "not",
+    "as",
+    "let",
     "forall",
     "exists",
     "any",
@@ -48,10 +51,17 @@ pub(crate) struct NamingAnalysis<'b> {
     fun_declarations: ScopedDecl,
     result: DeclarationTable,
     handler: &'b Handler,
+    functions: &'b FunctionRegistry,
+    /// The declaration that is currently checked, i.e., a constant, stream, or trigger.
+    current: Option<NodeId>,
+    /// The declarations containing names that could not be resolved.
+    unresolved: HashSet<NodeId>,
+    /// Maps declarations to the constants and streams they access.
+    accesses: HashMap<NodeId, HashSet<NodeId>>,
 }
 
 impl<'b> NamingAnalysis<'b> {
-    pub(crate) fn new(handler: &'b Handler, config: FrontendConfig) -> Self {
+    pub(crate) fn new(handler: &'b Handler, config: FrontendConfig, functions: &'b FunctionRegistry) -> Self {
         let mut scoped_decls = ScopedDecl::new();
 
         for (name, ty) in ValueTy::primitive_types(config.ty) {
@@ -67,6 +77,46 @@ impl<'b> NamingAnalysis<'b> {
             fun_declarations: ScopedDecl::new(),
             result: HashMap::new(),
             handler,
+            functions,
+            current: None,
+            unresolved: HashSet::new(),
+            accesses: HashMap::new(),
+        }
+    }
+
+    /// Returns the declarations containing names that could not be resolved and the declarations accessing them.
+    /// Type errors of these declarations would only follow from the naming errors.
+    pub(crate) fn poisoned(&self) -> HashSet<NodeId> {
+        let mut poisoned = self.unresolved.clone();
+        loop {
+            let count = poisoned.len();
+            for (declaration, accesses) in &self.accesses {
+                if accesses.iter().any(|access| poisoned.contains(access)) {
+                    poisoned.insert(*declaration);
+                }
+            }
+            if poisoned.len() == count {
+                return poisoned;
+            }
+        }
+    }
+
+    fn mark_unresolved(&mut self) {
+        if let Some(current) = self.current {
+            self.unresolved.insert(current);
+        }
+    }
+
+    fn add_access(&mut self, decl: &Declaration) {
+        let accessed = match decl {
+            Declaration::Const(constant) => constant.id,
+            Declaration::In(input) => input.id,
+            Declaration::Out(output) | Declaration::ParamOut(output) => output.id,
+            // the accesses of a binding are those of its expression
+            Declaration::Type(_) | Declaration::Param(_) | Declaration::Let(_) | Declaration::Func(_) => return,
+        };
+        if let Some(current) = self.current {
+            self.accesses.entry(current).or_default().insert(accessed);
         }
     }
 
@@ -85,6 +135,7 @@ impl<'b> NamingAnalysis<'b> {
         let lower = name.to_lowercase();
         if KEYWORDS.contains(&lower.as_str()) {
             self.handler.error_with_span(
+                Code::ReservedKeyword,
                 &format!("`{}` is a reserved keyword", name),
                 LabeledSpan::new(span, "use a different name here", true),
             )
@@ -92,6 +143,7 @@ impl<'b> NamingAnalysis<'b> {
 
         if let Some(decl) = self.declarations.get_decl_in_current_scope_for(name) {
             let mut builder = self.handler.build_error_with_span(
+                Code::DuplicateDefinition,
                 &format!("the name `{}` is defined multiple times", name),
                 LabeledSpan::new(span, &format!("`{}` redefined here", name), true),
             );
@@ -104,6 +156,118 @@ impl<'b> NamingAnalysis<'b> {
         }
     }
 
+    /// Declares the type aliases as the types they stand for.
+    /// Aliases may refer to aliases declared later; those referring to unknown types or to themselves are reported.
+    fn add_type_aliases(&mut self, aliases: &[TypeAlias]) {
+        let mut pending: Vec<&TypeAlias> = aliases.iter().collect();
+        loop {
+            let count = pending.len();
+            pending.retain(|alias| match self.resolve_type(&alias.ty) {
+                Some(ty) => {
+                    self.add_type_alias(alias, ty);
+                    false
+                }
+                None => true,
+            });
+            if pending.len() == count {
+                break;
+            }
+        }
+        for alias in pending {
+            self.handler.error_with_span(
+                Code::UnknownType,
+                &format!("cannot resolve the type alias `{}`", alias.name),
+                LabeledSpan::new(alias.ty.span, "refers to an unknown type or to the alias itself", true),
+            );
+        }
+    }
+
+    fn add_type_alias(&mut self, alias: &TypeAlias, ty: ValueTy) {
+        self.add_type(&alias.name, ty)
+    }
+
+    /// Declares the enumerations as types, reporting variants declared multiple times.
+    fn add_enums(&mut self, enums: &[EnumDeclaration]) {
+        for declaration in enums {
+            let mut variants: Vec<String> = Vec::with_capacity(declaration.variants.len());
+            for variant in &declaration.variants {
+                if variants.contains(&variant.name) {
+                    self.handler.error_with_span(
+                        Code::DuplicateDefinition,
+                        &format!("the variant `{}` is declared multiple times", variant.name),
+                        LabeledSpan::new(variant.span, &format!("`{}` redeclared here", variant.name), true),
+                    );
+                } else {
+                    variants.push(variant.name.clone());
+                }
+            }
+            self.add_type(&declaration.name, ValueTy::Enum(declaration.name.name.clone(), variants));
+        }
+    }
+
+    fn add_type(&mut self, name: &Ident, ty: ValueTy) {
+        if self.type_declarations.get_decl_for(&name.name).is_some() {
+            self.handler.error_with_span(
+                Code::DuplicateDefinition,
+                &format!("the type `{}` is defined multiple times", name),
+                LabeledSpan::new(name.span, &format!("`{}` redefined here", name), true),
+            );
+        } else {
+            self.type_declarations.add_decl_for(&name.name, Declaration::Type(Rc::new(ty)));
+        }
+    }
+
+    /// Checks that the literal `Mode::Armed` names a variant of a declared enumeration.
+    fn check_variant(&mut self, literal: &Literal, enumeration: &str, variant: &str) {
+        match self.type_declarations.get_decl_for(enumeration) {
+            Some(Declaration::Type(ty)) => match ty.as_ref() {
+                ValueTy::Enum(_, variants) if variants.iter().any(|v| v == variant) => {
+                    self.result.insert(literal.id, Declaration::Type(ty.clone()));
+                }
+                ValueTy::Enum(..) => {
+                    self.mark_unresolved();
+                    self.handler.error_with_span(
+                        Code::UnknownName,
+                        &format!("no variant `{}` in the enumeration `{}`", variant, enumeration),
+                        LabeledSpan::new(literal.span, "variant not found", true),
+                    );
+                }
+                _ => {
+                    self.mark_unresolved();
+                    self.handler.error_with_span(
+                        Code::UnknownType,
+                        &format!("the type `{}` is not an enumeration", enumeration),
+                        LabeledSpan::new(literal.span, "not an enumeration", true),
+                    );
+                }
+            },
+            _ => {
+                self.mark_unresolved();
+                self.handler.error_with_span(
+                    Code::UnknownType,
+                    &format!("cannot find enumeration `{}` in this scope", enumeration),
+                    LabeledSpan::new(literal.span, "not found in this scope", true),
+                );
+            }
+        }
+    }
+
+    /// Returns the type a type annotation stands for, `None` if it refers to an unknown type.
+    fn resolve_type(&self, ty: &Type) -> Option<ValueTy> {
+        match &ty.kind {
+            TypeKind::Simple(name) => match self.type_declarations.get_decl_for(name)? {
+                Declaration::Type(ty) => Some((*ty).clone()),
+                _ => unreachable!("only types are declared in the type scope"),
+            },
+            TypeKind::Tuple(elements) => {
+                elements.iter().map(|ty| self.resolve_type(ty)).collect::<Option<_>>().map(ValueTy::Tuple)
+            }
+            TypeKind::Array(ty, size) => self.resolve_type(ty).map(|ty| ValueTy::Array(ty.into(), *size)),
+            TypeKind::Optional(ty) => self.resolve_type(ty).map(|ty| ValueTy::Option(ty.into())),
+            TypeKind::Inferred => None,
+        }
+    }
+
     /// Checks if given type is bound
     fn check_type(&mut self, ty: &Type) {
         match &ty.kind {
@@ -113,7 +277,9 @@ impl<'b> NamingAnalysis<'b> {
                     self.result.insert(ty.id, decl);
                 } else {
                     // it does not exist
+                    self.mark_unresolved();
                     self.handler.error_with_span(
+                        Code::UnknownType,
                         &format!("cannot find type `{}` in this scope", name),
                         LabeledSpan::new(ty.span, "not found in this scope", true),
                     );
@@ -122,7 +288,7 @@ impl<'b> NamingAnalysis<'b> {
             TypeKind::Tuple(elements) => elements.iter().for_each(|ty| {
                 self.check_type(ty);
             }),
-            TypeKind::Optional(ty) => self.check_type(ty),
+            TypeKind::Array(ty, _) | TypeKind::Optional(ty) => self.check_type(ty),
             TypeKind::Inferred => {}
         }
     }
@@ -136,6 +302,7 @@ impl<'b> NamingAnalysis<'b> {
             // check if there is a parameter with the same name
             if let Some(decl) = self.declarations.get_decl_in_current_scope_for(&param.name.name) {
                 let mut builder = self.handler.build_error_with_span(
+                    Code::DuplicateParameter,
                     &format!("identifier `{}` is use more than once in this paramater list", param.name.name),
                     LabeledSpan::new(
                         param.name.span,
@@ -161,20 +328,24 @@ impl<'b> NamingAnalysis<'b> {
 
     /// Entry method, checks that every identifier in the given spec is bound.
     pub(crate) fn check(&mut self, spec: &RTLolaAst) -> DeclarationTable {
-        stdlib::import_implicit_module(&mut self.fun_declarations);
+        self.functions.import_functions(None, &mut self.fun_declarations);
         for import in &spec.imports {
-            match import.name.name.as_str() {
-                "math" => stdlib::import_math_module(&mut self.fun_declarations),
-                "regex" => stdlib::import_regex_module(&mut self.fun_declarations),
-                n => self.handler.error_with_span(
+            let n = import.name.name.as_str();
+            if !self.functions.import_functions(Some(n), &mut self.fun_declarations) {
+                self.handler.error_with_span(
+                    Code::UnresolvedImport,
                     &format!("unresolved import `{}`", n),
                     LabeledSpan::new(import.name.span, &format!("no `{}` in the root", n), true),
-                ),
+                )
             }
         }
 
+        self.add_enums(&spec.enum_declarations);
+        self.add_type_aliases(&spec.type_aliases);
+
         // Store global declarations, i.e., constants, inputs, and outputs of the given specification
         for constant in &spec.constants {
+            self.current = Some(constant.id);
             self.add_decl_for(Declaration::Const(constant.clone()));
             if let Some(ty) = constant.ty.as_ref() {
                 self.check_type(ty)
@@ -182,6 +353,7 @@ impl<'b> NamingAnalysis<'b> {
         }
 
         for input in &spec.inputs {
+            self.current = Some(input.id);
             self.add_decl_for(Declaration::In(input.clone()));
             self.check_type(&input.ty);
 
@@ -192,6 +364,7 @@ impl<'b> NamingAnalysis<'b> {
         }
 
         for output in &spec.outputs {
+            self.current = Some(output.id);
             if output.params.is_empty() {
                 self.add_decl_for(Declaration::Out(output.clone()));
             } else {
@@ -200,6 +373,12 @@ impl<'b> NamingAnalysis<'b> {
             self.check_type(&output.ty);
         }
 
+        // constants may refer to all global declarations, non-constant ones are rejected by the constant evaluation
+        for constant in &spec.constants {
+            self.current = Some(constant.id);
+            self.check_expression(&constant.value);
+        }
+
         self.check_outputs(&spec);
         self.check_triggers(&spec);
 
@@ -210,9 +389,11 @@ impl<'b> NamingAnalysis<'b> {
     fn check_triggers(&mut self, spec: &RTLolaAst) {
         let mut trigger_names: Vec<(&String, &Trigger)> = Vec::new();
         for trigger in &spec.trigger {
+            self.current = Some(trigger.id);
             if let Some(ident) = &trigger.name {
                 if let Some(decl) = self.declarations.get_decl_in_current_scope_for(&ident.name) {
                     let mut builder = self.handler.build_error_with_span(
+                        Code::DuplicateDefinition,
                         &format!("the name `{}` is defined multiple times", ident.name),
                         LabeledSpan::new(ident.span, &format!("`{}` redefined here", ident.name), true),
                     );
@@ -231,6 +412,7 @@ impl<'b> NamingAnalysis<'b> {
                     if ident.name == **name {
                         found = true;
                         let mut builder = self.handler.build_error_with_span(
+                            Code::DuplicateDefinition,
                             &format!("the trigger `{}` is defined multiple times", ident.name),
                             LabeledSpan::new(ident.span, &format!("`{}` redefined here", ident.name), true),
                         );
@@ -251,21 +433,37 @@ impl<'b> NamingAnalysis<'b> {
             self.declarations.push();
             self.check_expression(&trigger.expression);
             self.declarations.pop();
+            for name in trigger.placeholders() {
+                match self.declarations.get_decl_for(name) {
+                    Some(Declaration::In(_)) | Some(Declaration::Out(_)) => {}
+                    _ => {
+                        let mut builder = self.handler.build_error_with_span(
+                            Code::UnknownPlaceholder,
+                            &format!("the message refers to `{}`, which is no stream", name),
+                            LabeledSpan::new(trigger.span, &format!("`{{{}}}` cannot be replaced", name), true),
+                        );
+                        builder.add_help(&format!("write `{{{{{}}}}}` to keep the braces in the message", name));
+                        builder.emit();
+                    }
+                }
+            }
         }
     }
 
     fn check_outputs(&mut self, spec: &RTLolaAst) {
         // recurse into expressions and check them
         for output in &spec.outputs {
+            self.current = Some(output.id);
             self.declarations.push();
+            // the invoke clause computes the parameters, so they are not in its scope
+            if let Some(invoke) = output.template_spec.as_ref().and_then(|t| t.inv.as_ref()) {
+                self.check_expression(&invoke.target);
+                if let Some(ref cond) = invoke.condition {
+                    self.check_expression(&cond);
+                }
+            }
             output.params.iter().for_each(|param| self.check_param(&param));
             if let Some(ref template_spec) = output.template_spec {
-                if let Some(ref invoke) = template_spec.inv {
-                    self.check_expression(&invoke.target);
-                    if let Some(ref cond) = invoke.condition {
-                        self.check_expression(&cond);
-                    }
-                }
                 if let Some(ref extend) = template_spec.ext {
                     self.check_expression(&extend.target);
                 }
@@ -289,12 +487,55 @@ impl<'b> NamingAnalysis<'b> {
         if let Some(decl) = self.declarations.get_decl_for(&ident.name) {
             assert!(!decl.is_type());
 
+            self.check_deprecated(ident, &decl);
+            self.add_access(&decl);
             self.result.insert(expression.id, decl);
         } else {
-            self.handler.error_with_span(
+            self.mark_unresolved();
+            let mut builder = self.handler.build_error_with_span(
+                Code::UnknownName,
                 &format!("name `{}` does not exist in current scope", &ident.name),
                 LabeledSpan::new(ident.span, "does not exist", true),
             );
+            if let Some(similar) = self.declarations.similar_name(&ident.name) {
+                builder.add_suggestion("a similar name exists", ident.span, &similar);
+            }
+            builder.emit();
+        }
+    }
+
+    /// Warns about accesses to streams annotated with `#[deprecated]` from other declarations.
+    fn check_deprecated(&self, ident: &Ident, decl: &Declaration) {
+        let (id, annotations) = match decl {
+            Declaration::In(input) => (input.id, &input.annotations),
+            Declaration::Out(output) | Declaration::ParamOut(output) => (output.id, &output.annotations),
+            _ => return,
+        };
+        let annotation = match annotations.iter().find(|a| a.name.name == "deprecated") {
+            Some(annotation) if self.current != Some(id) => annotation,
+            _ => return,
+        };
+        let mut builder = self.handler.build_warning_with_span(
+            Code::DeprecatedStream,
+            &format!("use of deprecated stream `{}`", ident.name),
+            LabeledSpan::new(ident.span, "deprecated", true),
+        );
+        builder.add_span_with_label(annotation.span, "marked as deprecated here", false);
+        builder.emit();
+    }
+
+    /// Checks the operand of a stream operation, e.g., an offset, which cannot be a name bound by a let-expression.
+    fn check_stream_operand(&mut self, operand: &Expression) {
+        self.check_expression(operand);
+        if let Some(Declaration::Let(binding)) = self.result.get(&operand.id).cloned() {
+            self.mark_unresolved();
+            let mut builder = self.handler.build_error_with_span(
+                Code::ExpectedStreamVariable,
+                "operation can be only applied to streams directly",
+                LabeledSpan::new(operand.span, &format!("`{}` is no stream", binding.name), true),
+            );
+            builder.add_span_with_label(binding.name.span, "bound by this let-expression", false);
+            builder.emit();
         }
     }
 
@@ -306,12 +547,23 @@ impl<'b> NamingAnalysis<'b> {
             self.result.insert(expression.id, decl);
         } else if let Some(Declaration::ParamOut(out)) = self.declarations.get_decl_for(&name.name.name) {
             // parametric outputs are represented as functions
+            self.check_deprecated(&name.name, &Declaration::ParamOut(out.clone()));
+            self.add_access(&Declaration::ParamOut(out.clone()));
             self.result.insert(expression.id, Declaration::ParamOut(out));
         } else {
-            self.handler.error_with_span(
+            self.mark_unresolved();
+            let mut builder = self.handler.build_error_with_span(
+                Code::UnknownFunction,
                 &format!("function name `{}` does not exist in current scope", str_repr),
                 LabeledSpan::new(name.name.span, "does not exist", true),
             );
+            for module in self.functions.modules_declaring(&name.name.name) {
+                builder.add_help(&format!(
+                    "the module `{}` declares `{}`, consider `import {}`",
+                    module, name.name, module
+                ));
+            }
+            builder.emit();
         }
     }
 
@@ -322,19 +574,24 @@ impl<'b> NamingAnalysis<'b> {
             Ident(ident) => {
                 self.check_ident(expression, ident);
             }
-            StreamAccess(expr, _) => self.check_expression(expr),
+            StreamAccess(expr, _) => self.check_stream_operand(expr),
             Offset(expr, _) => {
-                self.check_expression(expr);
+                self.check_stream_operand(expr);
             }
             SlidingWindowAggregation { expr, duration, .. } => {
-                self.check_expression(expr);
+                self.check_stream_operand(expr);
                 self.check_expression(duration);
             }
             Binary(_, left, right) => {
                 self.check_expression(left);
                 self.check_expression(right);
             }
-            Lit(_) | MissingExpression => {}
+            Lit(literal) => {
+                if let LitKind::Variant(enumeration, variant) = &literal.kind {
+                    self.check_variant(literal, enumeration, variant);
+                }
+            }
+            MissingExpression => {}
             Ite(condition, if_case, else_case) => {
                 self.check_expression(condition);
                 self.check_expression(if_case);
@@ -343,7 +600,7 @@ impl<'b> NamingAnalysis<'b> {
             ParenthesizedExpression(_, expr, _) | Unary(_, expr) | Field(expr, _) => {
                 self.check_expression(expr);
             }
-            Tuple(exprs) => {
+            Tuple(exprs) | Array(exprs) => {
                 exprs.iter().for_each(|expr| self.check_expression(expr));
             }
             Function(name, types, exprs) => {
@@ -360,6 +617,18 @@ impl<'b> NamingAnalysis<'b> {
                 types.iter().for_each(|ty| self.check_type(ty));
                 args.iter().for_each(|expr| self.check_expression(expr));
             }
+            Cast(expr, ty) => {
+                self.check_expression(expr);
+                self.check_type(ty);
+            }
+            Let(binding, body) => {
+                // the name is only bound in the body
+                self.check_expression(&binding.expr);
+                self.declarations.push();
+                self.add_decl_for(Declaration::Let(binding.clone()));
+                self.check_expression(body);
+                self.declarations.pop();
+            }
         }
     }
 }
@@ -392,6 +661,26 @@ impl ScopedDecl {
         None
     }
 
+    /// Returns the declared stream, constant, or parameter whose name is closest to `name`, if it is close enough to be a typo.
+    fn similar_name(&self, name: &str) -> Option<String> {
+        let max_distance = name.chars().count() / 3;
+        self.scopes
+            .iter()
+            .flat_map(|scope| scope.iter())
+            .filter(|(_, decl)| match decl {
+                Declaration::Const(_)
+                | Declaration::In(_)
+                | Declaration::Out(_)
+                | Declaration::Param(_)
+                | Declaration::Let(_) => true,
+                Declaration::ParamOut(_) | Declaration::Type(_) | Declaration::Func(_) => false,
+            })
+            .map(|(candidate, _)| (edit_distance(name, candidate), candidate))
+            .filter(|(distance, _)| *distance <= max_distance)
+            .min()
+            .map(|(_, candidate)| candidate.clone())
+    }
+
     fn get_decl_in_current_scope_for(&self, name: &str) -> Option<Declaration> {
         match self.scopes.last().expect("It appears that we popped the global context.").get(name) {
             Some(decl) => Some(decl.clone()),
@@ -414,6 +703,22 @@ impl ScopedDecl {
     }
 }
 
+/// The Levenshtein distance of two strings, i.e., the number of inserted, deleted, or replaced characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let replaced = diagonal + if ca == *cb { 0 } else { 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = replaced.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
 #[derive(Debug, Clone)]
 pub(crate) enum Declaration {
     Const(Rc<Constant>),
@@ -424,6 +729,8 @@ pub(crate) enum Declaration {
     ParamOut(Rc<Output>),
     Type(Rc<ValueTy>),
     Param(Rc<Parameter>),
+    /// A name bound by a let-expression
+    Let(Rc<Binding>),
     Func(Rc<FuncDecl>),
 }
 
@@ -435,6 +742,7 @@ impl Declaration {
             Declaration::Out(output) => Some(output.name.span),
             Declaration::ParamOut(output) => Some(output.name.span),
             Declaration::Param(p) => Some(p.name.span),
+            Declaration::Let(binding) => Some(binding.name.span),
             Declaration::Type(_) | Declaration::Func(_) => None,
         }
     }
@@ -446,6 +754,7 @@ impl Declaration {
             Declaration::Out(output) => Some(&output.name.name),
             Declaration::ParamOut(output) => Some(&output.name.name),
             Declaration::Param(p) => Some(&p.name.name),
+            Declaration::Let(binding) => Some(&binding.name.name),
             Declaration::Type(_) | Declaration::Func(_) => None,
         }
     }
@@ -458,6 +767,7 @@ impl Declaration {
             | Declaration::Out(_)
             | Declaration::ParamOut(_)
             | Declaration::Param(_)
+            | Declaration::Let(_)
             | Declaration::Func(_) => false,
         }
     }
@@ -475,13 +785,14 @@ mod tests {
 
     use super::*;
     use crate::parse::{parse, SourceMapper};
+    use crate::stdlib::STANDARD_LIBRARY;
     use std::path::PathBuf;
 
     /// Parses the content, runs naming analysis, and returns number of errors
     fn number_of_naming_errors(content: &str) -> usize {
         let handler = Handler::new(SourceMapper::new(PathBuf::new(), content));
         let ast = parse(content, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
-        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut naming_analyzer = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         naming_analyzer.check(&ast);
         handler.emitted_errors()
     }
@@ -496,6 +807,35 @@ mod tests {
         assert_eq!(1, number_of_naming_errors("output test: Int8 := A"))
     }
 
+    #[test]
+    fn unknown_placeholders_are_reported() {
+        let content = "constant c: Int8 := 1\ninput a: Int8\ntrigger a > 1 \"{a} {c} {b} {count} { a } {} {{b}}\"";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), content));
+        let ast = parse(content, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY).check(&ast);
+        assert_eq!((handler.emitted_errors(), handler.emitted_warnings()), (3, 0));
+    }
+
+    #[test]
+    fn similar_names() {
+        assert_eq!(edit_distance("altitude", "altitdue"), 2);
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("speed", "speed"), 0);
+        let mut declarations = ScopedDecl::new();
+        let ast = parse(
+            "input altitude: Float64\ninput a: Int8",
+            &Handler::new(SourceMapper::new(PathBuf::new(), "")),
+            FrontendConfig::default(),
+        )
+        .unwrap();
+        for input in &ast.inputs {
+            declarations.add_decl_for(&input.name.name, Declaration::In(input.clone()));
+        }
+        assert_eq!(declarations.similar_name("altitdue"), Some(String::from("altitude")));
+        assert_eq!(declarations.similar_name("b"), None);
+        assert_eq!(declarations.similar_name("speed"), None);
+    }
+
     #[test]
     fn primitive_types_are_a_known() {
         for ty in &["Int8", "Int16", "Int32", "Int64", "Float32", "Float64", "Bool", "String"] {
@@ -525,7 +865,12 @@ mod tests {
 
     #[test]
     fn template_spec_is_also_tested() {
-        assert_eq!(1, number_of_naming_errors("output a {invoke b} := 3"))
+        assert_eq!(1, number_of_naming_errors("output a(p: Int8) {invoke b} := 3"))
+    }
+
+    #[test]
+    fn parameters_are_not_in_scope_of_invoke() {
+        assert_eq!(1, number_of_naming_errors("input i: Int8\noutput a(p: Int8) {invoke p extend p > 0} := p"))
     }
 
     #[test]
@@ -582,4 +927,23 @@ mod tests {
         let spec = "input in(a: Int8, b: Int8): Int8\noutput x := in(1)";
         assert_eq!(1, number_of_naming_errors(spec));
     }
+
+    #[test]
+    fn enum_variants() {
+        let spec = "enum Mode { Init, Armed, Flying }\ninput m: Mode\noutput armed := m == Mode::Armed";
+        assert_eq!(0, number_of_naming_errors(spec));
+    }
+
+    #[test]
+    fn unknown_enum_variant() {
+        let spec = "enum Mode { Init, Armed }\ninput m: Mode\noutput flying := m == Mode::Flying";
+        assert_eq!(1, number_of_naming_errors(spec));
+        assert_eq!(1, number_of_naming_errors("input m: Int8\noutput init := Phase::Init"));
+    }
+
+    #[test]
+    fn duplicate_enum_variant() {
+        assert_eq!(1, number_of_naming_errors("enum Mode { Init, Armed, Init }"));
+        assert_eq!(1, number_of_naming_errors("enum Mode { Init }\ntype Mode := Bool"));
+    }
 }
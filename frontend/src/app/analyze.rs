@@ -12,7 +12,8 @@ use simplelog::*;
 use crate::analysis;
 use crate::ir::lowering::Lowering;
 use crate::parse::{LolaParser, Rule, SourceMapper};
-use crate::reporting::Handler;
+use crate::reporting::{ColorMode, Handler, JsonDiagnostics, SarifLog, WarningConfig};
+use crate::stdlib::STANDARD_LIBRARY;
 use crate::ty::TypeConfig;
 use crate::FrontendConfig;
 
@@ -30,7 +31,7 @@ pub fn runAnalysisCLI(args: &[String]) -> Result<(), Box<dyn Error>> {
 }
 
 const CONFIG: FrontendConfig =
-    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: true }, allow_parameters: true };
+    FrontendConfig { ty: TypeConfig { use_64bit_only: false, type_aliases: true }, allow_parameters: true };
 
 enum Analysis {
     Parse,
@@ -38,11 +39,26 @@ enum Analysis {
     Prettyprint,
     Analyze,
     IR,
+    Rename { name: String, new_name: String, in_place: bool },
+}
+
+/// The format diagnostics are printed in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MessageFormat {
+    /// Human readable messages with source code snippets on stderr
+    Human,
+    /// A SARIF log of the analysis on stdout
+    Sarif,
+    /// One JSON object per diagnostic and line on stdout
+    Json,
 }
 
 pub(crate) struct Config {
     which: Analysis,
     filename: String,
+    format: MessageFormat,
+    color: ColorMode,
+    warnings: WarningConfig,
 }
 
 impl Config {
@@ -53,6 +69,48 @@ impl Config {
             .about("rtlola-analyze is a tool to analyze Lola specifications")
             .arg(Arg::with_name("v").short("v").multiple(true).required(false).help("Sets the level of verbosity"))
             .arg(Arg::with_name("INPUT").help("Sets the input file to use").required(true).index(1))
+            .arg(
+                Arg::with_name("MESSAGE_FORMAT")
+                    .long("message-format")
+                    .help(
+                        "Sets the format of diagnostics, `sarif` prints a SARIF log of the analysis to stdout, \
+                         `json` prints one JSON object per diagnostic and line to stdout",
+                    )
+                    .possible_values(&["human", "sarif", "json"])
+                    .default_value("human"),
+            )
+            .arg(
+                Arg::with_name("COLOR")
+                    .long("color")
+                    .help(
+                        "Sets when human readable diagnostics are colored, `auto` colors them if stderr is a terminal",
+                    )
+                    .possible_values(&["auto", "always", "never"])
+                    .default_value("auto"),
+            )
+            .arg(
+                Arg::with_name("ALLOW")
+                    .long("allow")
+                    .short("A")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .help("Suppresses the warnings with the given code, e.g., `--allow W0201`"),
+            )
+            .arg(
+                Arg::with_name("DENY")
+                    .long("deny")
+                    .short("D")
+                    .takes_value(true)
+                    .multiple(true)
+                    .number_of_values(1)
+                    .help("Promotes the warnings with the given code to errors, e.g., `--deny W0201`"),
+            )
+            .arg(
+                Arg::with_name("DENY_WARNINGS")
+                    .long("deny-warnings")
+                    .help("Promotes all warnings to errors, except for allowed ones"),
+            )
             .subcommand(SubCommand::with_name("parse").about("Parses the input file and outputs parse tree"))
             .subcommand(
                 SubCommand::with_name("ast")
@@ -66,6 +124,18 @@ impl Config {
             .subcommand(
                 SubCommand::with_name("ir").about("Parses the input file and returns the intermediate representation"),
             )
+            .subcommand(
+                SubCommand::with_name("rename")
+                    .about("Renames a stream, constant, or parameter and prints the renamed specification")
+                    .arg(Arg::with_name("NAME").help("Sets the name to replace").required(true).index(1))
+                    .arg(Arg::with_name("NEW_NAME").help("Sets the new name").required(true).index(2))
+                    .arg(
+                        Arg::with_name("IN_PLACE")
+                            .long("in-place")
+                            .short("i")
+                            .help("Writes the renamed specification to the input file instead of stdout"),
+                    ),
+            )
             .get_matches_from(args);
 
         let verbosity = match matches.occurrences_of("v") {
@@ -75,12 +145,12 @@ impl Config {
             _ => LevelFilter::Trace,
         };
 
-        let filename = matches.value_of("INPUT").map(std::string::ToString::to_string).unwrap();
+        let filename = matches.value_of("INPUT").map(ToString::to_string).unwrap();
         eprintln!("Input file `{}`", filename);
 
         let mut logger: Vec<Box<dyn SharedLogger>> = Vec::new();
         if let Some(term_logger) =
-            TermLogger::new(verbosity, simplelog::Config::default(), simplelog::TerminalMode::default())
+            TermLogger::new(verbosity, simplelog::Config::default(), TerminalMode::default())
         {
             logger.push(term_logger);
         } else {
@@ -89,18 +159,41 @@ impl Config {
 
         CombinedLogger::init(logger).expect("failed to initialize logging framework");
 
-        match matches.subcommand() {
-            ("parse", Some(_)) => Config { which: Analysis::Parse, filename },
-            ("ast", Some(_)) => Config { which: Analysis::AST, filename },
-            ("pretty-print", Some(_)) => Config { which: Analysis::Prettyprint, filename },
-            ("analyze", Some(_)) => Config { which: Analysis::Analyze, filename },
-            ("ir", Some(_)) | ("intermediate-representation", Some(_)) => Config { which: Analysis::IR, filename },
+        let format = match matches.value_of("MESSAGE_FORMAT") {
+            Some("sarif") => MessageFormat::Sarif,
+            Some("json") => MessageFormat::Json,
+            _ => MessageFormat::Human,
+        };
+        let color = match matches.value_of("COLOR") {
+            Some("always") => ColorMode::Always,
+            Some("never") => ColorMode::Never,
+            _ => ColorMode::Auto,
+        };
+        let which = match matches.subcommand() {
+            ("parse", Some(_)) => Analysis::Parse,
+            ("ast", Some(_)) => Analysis::AST,
+            ("pretty-print", Some(_)) => Analysis::Prettyprint,
+            ("analyze", Some(_)) => Analysis::Analyze,
+            ("ir", Some(_)) | ("intermediate-representation", Some(_)) => Analysis::IR,
+            ("rename", Some(rename)) => Analysis::Rename {
+                name: rename.value_of("NAME").unwrap().to_string(),
+                new_name: rename.value_of("NEW_NAME").unwrap().to_string(),
+                in_place: rename.is_present("IN_PLACE"),
+            },
             ("", None) => {
                 // default to `analyze`
-                Config { which: Analysis::Analyze, filename }
+                Analysis::Analyze
             }
             _ => unreachable!(),
+        };
+        if format != MessageFormat::Human && !matches!(which, Analysis::Analyze) {
+            eprintln!("SARIF and JSON output are only available for the `analyze` subcommand");
+            std::process::exit(1)
         }
+        let codes = |name| matches.values_of(name).map_or(vec![], |codes| codes.map(str::to_string).collect());
+        let warnings =
+            WarningConfig { deny_all: matches.is_present("DENY_WARNINGS"), deny: codes("DENY"), allow: codes("ALLOW") };
+        Config { which, filename, format, color, warnings }
     }
 
     pub(crate) fn run(&self) -> Result<(), Box<dyn Error>> {
@@ -108,43 +201,40 @@ impl Config {
         let mut contents = String::new();
         file.read_to_string(&mut contents)?;
         let mapper = SourceMapper::new(PathBuf::from(&self.filename), &contents);
-        let handler = Handler::new(mapper);
+        match self.format {
+            MessageFormat::Human => {}
+            MessageFormat::Sarif => return self.run_sarif(mapper, &contents),
+            MessageFormat::Json => return self.run_json(mapper, &contents),
+        }
+        let mut handler = Handler::with_color(mapper, self.color);
+        self.configure_warnings(&mut handler);
+        // syntax errors are displayed like the errors of the analysis
+        let syntax_error = |e: pest::error::Error<Rule>| -> ! {
+            handler.syntax_error(&e);
+            std::process::exit(1)
+        };
         match &self.which {
             Analysis::Parse => {
-                let result = LolaParser::parse(Rule::Spec, &contents).unwrap_or_else(|e| {
-                    eprintln!("parse error:\n{}", e);
-                    std::process::exit(1)
-                });
+                let result = LolaParser::parse(Rule::Spec, &contents).unwrap_or_else(|e| syntax_error(e));
                 println!("{:#?}", result);
                 Ok(())
             }
             Analysis::AST => {
-                let spec = crate::parse::parse(&contents, &handler, CONFIG).unwrap_or_else(|e| {
-                    eprintln!("parse error:\n{}", e);
-                    std::process::exit(1)
-                });
+                let spec = crate::parse::parse(&contents, &handler, CONFIG).unwrap_or_else(|e| syntax_error(e));
                 println!("{:#?}", spec);
                 Ok(())
             }
             Analysis::Prettyprint => {
-                let spec = crate::parse::parse(&contents, &handler, CONFIG).unwrap_or_else(|e| {
-                    eprintln!("parse error:\n{}", e);
-                    std::process::exit(1)
-                });
+                let spec = crate::parse::parse(&contents, &handler, CONFIG).unwrap_or_else(|e| syntax_error(e));
                 println!("{}", spec);
                 Ok(())
             }
             Analysis::Analyze => {
-                let report = match crate::parse::parse(&contents, &handler, CONFIG)
-                    .map_err(|_| "Parse Error.")
-                    .and_then(|spec| analysis::analyze(&spec, &handler, CONFIG).map_err(|_| "Analysis Error"))
-                {
-                    Err(s) => {
-                        eprintln!("parse error:\n{}", s);
-                        std::process::exit(1)
-                    }
-                    Ok(report) => report,
-                };
+                let spec = crate::parse::parse_with_features(&contents, &handler, CONFIG, &[])
+                    .unwrap_or_else(|e| syntax_error(e));
+                // the handler has displayed the errors already
+                let report = analysis::analyze(&spec, &handler, CONFIG, &STANDARD_LIBRARY)
+                    .unwrap_or_else(|_| std::process::exit(1));
 
                 use crate::analysis::graph_based_analysis::MemoryBound;
                 match report.graph_analysis_result.memory_requirements {
@@ -157,12 +247,10 @@ impl Config {
                 Ok(())
             }
             Analysis::IR => {
-                let spec = crate::parse::parse(&contents, &handler, CONFIG).unwrap_or_else(|e| {
-                    eprintln!("parse error:\n{}", e);
-                    std::process::exit(1)
-                });
+                let spec = crate::parse::parse_with_features(&contents, &handler, CONFIG, &[])
+                    .unwrap_or_else(|e| syntax_error(e));
 
-                if let Ok(report) = crate::analysis::analyze(&spec, &handler, CONFIG) {
+                if let Ok(report) = analysis::analyze(&spec, &handler, CONFIG, &STANDARD_LIBRARY) {
                     let ir = Lowering::new(&spec, &report).lower();
                     println!("{:#?}", ir);
                     Ok(())
@@ -171,6 +259,75 @@ impl Config {
                     Ok(()) // TODO throw a good `Error`
                 }
             }
+            Analysis::Rename { name, new_name, in_place } => {
+                let fail = |message: String| -> ! {
+                    eprintln!("cannot rename `{}`: {}", name, message);
+                    std::process::exit(1)
+                };
+                let declaration = match crate::navigation::declarations_named(&contents, name, CONFIG).as_slice() {
+                    [] => fail(String::from("there is no declaration of this name")),
+                    [declaration] => *declaration,
+                    declarations => fail(format!("the name is declared {} times", declarations.len())),
+                };
+                let edits = crate::navigation::rename(&contents, declaration.start, new_name, CONFIG)
+                    .unwrap_or_else(|e| fail(e));
+                let renamed = crate::navigation::apply(&contents, &edits);
+                if *in_place {
+                    std::fs::write(&self.filename, renamed)?;
+                    eprintln!("Replaced {} occurrences of `{}`", edits.len(), name);
+                } else {
+                    print!("{}", renamed);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn configure_warnings(&self, handler: &mut Handler) {
+        handler.configure_warnings(&self.warnings).unwrap_or_else(|e| {
+            eprintln!("{}", e);
+            std::process::exit(1)
+        })
+    }
+
+    /// Analyzes the specification and prints the diagnostics as SARIF log, exits with an error if there are errors.
+    fn run_sarif(&self, mapper: SourceMapper, contents: &str) -> Result<(), Box<dyn Error>> {
+        let log = SarifLog::new();
+        let path = PathBuf::from(&self.filename);
+        let mut handler = Handler::with_emitter(mapper, Box::new(log.clone()));
+        self.configure_warnings(&mut handler);
+        let valid = match crate::parse::parse_with_features(contents, &handler, CONFIG, &[]) {
+            Ok(spec) => analysis::analyze(&spec, &handler, CONFIG, &STANDARD_LIBRARY).is_ok(),
+            Err(e) => {
+                log.add_parse_error(&SourceMapper::new(path, contents), &e);
+                false
+            }
+        };
+        println!("{}", log.render());
+        if !valid {
+            std::process::exit(1)
+        }
+        Ok(())
+    }
+    /// Analyzes the specification and prints each diagnostic as JSON object on its own line, exits with an error if there are errors.
+    fn run_json(&self, mapper: SourceMapper, contents: &str) -> Result<(), Box<dyn Error>> {
+        let diagnostics = JsonDiagnostics::new();
+        let path = PathBuf::from(&self.filename);
+        let mut handler = Handler::with_emitter(mapper, Box::new(diagnostics.clone()));
+        self.configure_warnings(&mut handler);
+        let valid = match crate::parse::parse_with_features(contents, &handler, CONFIG, &[]) {
+            Ok(spec) => analysis::analyze(&spec, &handler, CONFIG, &STANDARD_LIBRARY).is_ok(),
+            Err(e) => {
+                diagnostics.add_parse_error(&SourceMapper::new(path, contents), &e);
+                false
+            }
+        };
+        for diagnostic in diagnostics.diagnostics() {
+            println!("{}", diagnostic);
+        }
+        if !valid {
+            std::process::exit(1)
         }
+        Ok(())
     }
 }
@@ -0,0 +1,89 @@
+//! This module contains the logic for the `rtlola-translate` binary.
+
+use std::error::Error;
+
+use clap::{App, AppSettings, Arg, SubCommand};
+
+use crate::translation;
+use crate::FrontendConfig;
+
+#[rustfmt::skip]
+/**
+Run the `rtlola-translate` program.
+
+**Warning: This will in general exit the process on error.**  
+The generated specification is printed to stdout.  
+Only problems while reading a specification file result in returning an `Err<Box<dyn Error>>`.
+*/
+#[allow(non_snake_case)]
+pub fn runTranslationCLI(args: &[String]) -> Result<(), Box<dyn Error>> {
+    let matches = App::new("rtlola-translate")
+        .version(env!("CARGO_PKG_VERSION"))
+        .author(env!("CARGO_PKG_AUTHORS"))
+        .about("rtlola-translate compiles formulas of other specification languages into Lola specifications")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(
+            SubCommand::with_name("stl")
+                .about("Translates a Signal Temporal Logic formula, e.g., `G[0,5](speed < 30)`")
+                .arg(Arg::with_name("FORMULA").help("The STL formula to translate").required(true).index(1))
+                .arg(
+                    Arg::with_name("FREQUENCY")
+                        .long("frequency")
+                        .takes_value(true)
+                        .default_value("10")
+                        .help("The frequency in Hz the signals are sampled with"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("ltl")
+                .about("Translates a past-time LTL formula, e.g., `grant -> Y (!grant S request)`")
+                .arg(Arg::with_name("FORMULA").help("The past-time LTL formula to translate").required(true).index(1))
+                .arg(
+                    Arg::with_name("SPEC")
+                        .long("spec")
+                        .takes_value(true)
+                        .help("Appends the definitions to this specification declaring the streams of the formula"),
+                )
+                .arg(
+                    Arg::with_name("NAME")
+                        .long("name")
+                        .takes_value(true)
+                        .default_value("ltl")
+                        .help("The name of the output stream computing the formula"),
+                ),
+        )
+        .get_matches_from(args);
+
+    let result = match matches.subcommand() {
+        ("stl", Some(m)) => {
+            let frequency = m.value_of("FREQUENCY").unwrap().parse::<f64>().unwrap_or_else(|e| {
+                eprintln!("invalid frequency: {}", e);
+                std::process::exit(1)
+            });
+            translation::stl::translate(m.value_of("FORMULA").unwrap(), frequency)
+        }
+        ("ltl", Some(m)) => {
+            let definitions =
+                translation::past_ltl::translate(m.value_of("FORMULA").unwrap(), m.value_of("NAME").unwrap());
+            match (definitions, m.value_of("SPEC")) {
+                (Ok(definitions), Some(path)) => {
+                    let spec = format!("{}\n\n{}", std::fs::read_to_string(path)?.trim_end(), definitions);
+                    // Checks that the streams of the formula exist and have suitable types.
+                    crate::parse(path, &spec, FrontendConfig::default()).map(|_| spec)
+                }
+                (result, _) => result,
+            }
+        }
+        _ => unreachable!(),
+    };
+    match result {
+        Ok(spec) => {
+            print!("{}", spec);
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("error: {}", e);
+            std::process::exit(1)
+        }
+    }
+}
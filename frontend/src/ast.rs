@@ -29,6 +29,10 @@ pub struct RTLolaAst {
     pub trigger: Vec<Rc<Trigger>>,
     /// The user-defined type declarations
     pub type_declarations: Vec<TypeDeclaration>,
+    /// The type alias declarations
+    pub type_aliases: Vec<TypeAlias>,
+    /// The enumeration declarations
+    pub enum_declarations: Vec<EnumDeclaration>,
 }
 
 impl RTLolaAst {
@@ -40,6 +44,8 @@ impl RTLolaAst {
             outputs: Vec::new(),
             trigger: Vec::new(),
             type_declarations: Vec::new(),
+            type_aliases: Vec::new(),
+            enum_declarations: Vec::new(),
         }
     }
 }
@@ -67,8 +73,10 @@ pub struct Constant {
     pub name: Ident,
     /// The value type of the constant stream
     pub ty: Option<Type>,
-    /// The literal defining the constant
-    pub literal: Literal,
+    /// The constant expression defining the constant
+    pub value: Expression,
+    /// Whether the value of the constant can be overridden when the monitor starts
+    pub configurable: bool,
     /// The ID of the node in the AST
     pub id: NodeId,
     /// The span in the specification declaring the constant stream
@@ -86,6 +94,8 @@ pub struct Input {
     pub ty: Type,
     /// The parameters of a parameterized input stream; The vector is empty in non-parametrized streams.
     pub params: Vec<Rc<Parameter>>,
+    /// The annotations of the input stream, e.g., `#[deprecated]`
+    pub annotations: Vec<Annotation>,
     /// The ID of the node in the AST
     pub id: NodeId,
     /// The span in the specification declaring the input stream
@@ -111,6 +121,10 @@ pub struct Output {
     pub termination: Option<Expression>,
     /// The stream expression of a output stream, e.g., a + b.offset(by: -1).defaults(to: 0)
     pub expression: Expression,
+    /// Whether the stream is an input derived from other inputs, declared as `input NAME: TYPE := EXPRESSION`
+    pub derived_input: bool,
+    /// The annotations of the output stream, e.g., `#[memory(bounded)]`
+    pub annotations: Vec<Annotation>,
     /// The ID of the node in the AST
     pub id: NodeId,
     /// The span in the specification declaring the output stream
@@ -132,6 +146,21 @@ pub struct Parameter {
     pub span: Span,
 }
 
+/**
+An AST node representing a name bound to an expression by a let-expression, e.g., `v := a + b` in `let v := a + b in v * v`.
+*/
+#[derive(Debug, Clone)]
+pub struct Binding {
+    /// The name of the binding
+    pub name: Ident,
+    /// The bound expression
+    pub expr: Expression,
+    /// The ID of the node in the AST
+    pub id: NodeId,
+    /// The span in the specification declaring the binding
+    pub span: Span,
+}
+
 /**
 An AST node representing the declaration of a activation condition of a stream.
 */
@@ -216,12 +245,98 @@ pub struct Trigger {
     pub expression: Expression,
     /// The optional trigger message, which is printed if the monitor raises the trigger
     pub message: Option<String>,
+    /// The optional escalation of the trigger
+    pub escalation: Option<Escalation>,
+    /// Whether the trigger is an assumption about the environment, which is violated if its expression is false
+    pub assumption: bool,
+    /// The annotations of the trigger, e.g., `#[severity(error)]`
+    pub annotations: Vec<Annotation>,
     /// The ID of the node in the AST
     pub id: NodeId,
     /// The span in the specification declaring the extend declaration
     pub span: Span,
 }
 
+impl Trigger {
+    /// Returns the names in the placeholders of the message, e.g., `altitude` in `"too low: {altitude}"`.
+    /// `{count}` of escalating triggers is no placeholder.
+    pub(crate) fn placeholders(&self) -> Vec<&str> {
+        let message = match &self.message {
+            Some(message) => message.as_str(),
+            None => return Vec::new(),
+        };
+        let mut names = Vec::new();
+        interpolate(message, |name| {
+            if !(name == "count" && self.escalation.is_some()) && !names.contains(&name) {
+                names.push(name);
+            }
+            None
+        });
+        names
+    }
+}
+
+/// Returns the message of a trigger with each placeholder `{NAME}` replaced by `value(NAME)`, unless it is `None`,
+/// and the escaped braces `{{` and `}}` replaced by single braces.
+/// Braces that enclose no identifier are kept as they are.
+pub(crate) fn interpolate<'m>(message: &'m str, mut value: impl FnMut(&'m str) -> Option<String>) -> String {
+    let mut res = String::with_capacity(message.len());
+    let mut rest = message;
+    while let Some(pos) = rest.find(|c| c == '{' || c == '}') {
+        let brace = &rest[pos..=pos];
+        res.push_str(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        if rest.starts_with(brace) {
+            res.push_str(brace);
+            rest = &rest[1..];
+            continue;
+        }
+        let name = match (brace, rest.find('}')) {
+            ("{", Some(close)) => &rest[..close],
+            _ => "",
+        };
+        let identifier = name.chars().next().map_or(false, |c| c.is_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if !identifier {
+            res.push_str(brace);
+            continue;
+        }
+        match value(name) {
+            Some(value) => res.push_str(&value),
+            None => res.push_str(&format!("{{{}}}", name)),
+        }
+        rest = &rest[name.len() + 1..];
+    }
+    res.push_str(rest);
+    res
+}
+
+/**
+An AST node representing the escalation of a trigger, which only notifies after a number of violations within a duration, e.g., `escalate after 3 within 10s`
+*/
+#[derive(Debug, Clone)]
+pub struct Escalation {
+    /// The number of violations
+    pub after: Literal,
+    /// The duration in which the violations have to occur
+    pub within: Literal,
+    /// The span in the specification declaring the escalation
+    pub span: Span,
+}
+
+/**
+An AST node representing an annotation of a stream or trigger, e.g., `#[severity(error)]`
+*/
+#[derive(Debug, Clone)]
+pub struct Annotation {
+    /// The name of the annotation
+    pub name: Ident,
+    /// The arguments of the annotation, empty if it has none
+    pub args: Vec<Ident>,
+    /// The span in the specification declaring the annotation
+    pub span: Span,
+}
+
 /**
 An AST node representing the declaration of a user-defined type.
 */
@@ -238,6 +353,36 @@ pub struct TypeDeclaration {
     pub span: Span,
 }
 
+/**
+An AST node representing the declaration of a type alias, e.g., `type Temperature := Float64`.
+*/
+#[derive(Debug, Clone)]
+pub struct TypeAlias {
+    /// The name of the alias
+    pub name: Ident,
+    /// The type the alias stands for
+    pub ty: Type,
+    /// The ID of the node in the AST
+    pub id: NodeId,
+    /// The span in the specification declaring the type alias
+    pub span: Span,
+}
+
+/**
+An AST node representing the declaration of an enumeration, e.g., `enum Mode { Init, Armed, Flying }`.
+*/
+#[derive(Debug, Clone)]
+pub struct EnumDeclaration {
+    /// The name of the enumeration
+    pub name: Ident,
+    /// The names of the variants in the order of their declaration
+    pub variants: Vec<Ident>,
+    /// The ID of the node in the AST
+    pub id: NodeId,
+    /// The span in the specification declaring the enumeration
+    pub span: Span,
+}
+
 /**
 An AST node representing the declaration of a field of a user-defined type.
 */
@@ -293,6 +438,10 @@ impl Type {
         Type { id, kind: TypeKind::Tuple(tuple), span }
     }
 
+    pub(crate) fn new_array(id: NodeId, element: Type, size: usize, span: Span) -> Type {
+        Type { id, kind: TypeKind::Array(element.into(), size), span }
+    }
+
     pub(crate) fn new_optional(id: NodeId, name: Type, span: Span) -> Type {
         Type { id, kind: TypeKind::Optional(name.into()), span }
     }
@@ -309,7 +458,9 @@ pub enum TypeKind {
     Simple(String),
     /// A tuple type, e.g., `(Int32, Float32)`
     Tuple(Vec<Type>),
-    /// An optional type, e.g., `Int?`
+    /// An array type of fixed size, e.g., `[Float32; 4]`
+    Array(Box<Type>, usize),
+    /// An optional type, e.g., `Int?` or `Option<(Int, Bool)>`
     Optional(Box<Type>),
     /// Should be inferred, i.e., is not annotated
     Inferred,
@@ -373,15 +524,21 @@ pub enum ExpressionKind {
     MissingExpression,
     /// A tuple expression
     Tuple(Vec<Box<Expression>>),
-    /// Access of a named (`obj.foo`) or unnamed (`obj.0`) struct field
+    /// An array expression, e.g., `[x, y, z]`
+    Array(Vec<Box<Expression>>),
+    /// Access of a named (`obj.foo`) or unnamed (`obj.0`) struct field, or of an element of an array (`obj.0`)
     Field(Box<Expression>, Ident),
     /// A method call, e.g., `foo.bar(-1)`
     Method(Box<Expression>, FunctionName, Vec<Type>, Vec<Box<Expression>>),
     /// A function call
     Function(FunctionName, Vec<Type>, Vec<Box<Expression>>),
+    /// A checked cast of a number, e.g., `x as Int8`, which has no value if the type cannot represent the number
+    Cast(Box<Expression>, Type),
+    /// A let-expression naming an expression within the body, e.g., `let v := a + b in v * v`
+    Let(Rc<Binding>, Box<Expression>),
 }
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone)]
 /// The AST representation of the different aggregation functions
 pub enum WindowOperation {
     /// Aggregation function to count the number of updated values on the accessed stream
@@ -402,10 +559,20 @@ pub enum WindowOperation {
     Conjunction,
     /// Aggregation function to return the disjunction, i.e., the sliding window returns true iff AT LEAST ONE value on the accessed stream inside a window is assigned to true
     Disjunction,
+    /// Aggregation function to return the median, i.e., the 50th percentile
+    Median,
+    /// Aggregation function to return the given percentile between 0 and 100 by the nearest-rank method, i.e., the smallest value such that at least the given percentage of the values is less than or equal to it
+    Percentile(u8),
+    /// Aggregation function to return the oldest value inside the window
+    First,
+    /// Aggregation function to return the most recent value inside the window
+    Last,
+    /// Aggregation function with the given name added by the host application, see `FunctionRegistry::add_aggregation`
+    Custom(String),
 }
 
 /// Describes the operation used to access a stream
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum StreamAccessKind {
     /// Synchronous access
     Sync,
@@ -469,6 +636,10 @@ impl Literal {
     pub(crate) fn new_raw_str(id: NodeId, val: &str, span: Span) -> Literal {
         Literal { id, kind: LitKind::RawStr(val.to_string()), span }
     }
+
+    pub(crate) fn new_variant(id: NodeId, enumeration: &str, variant: &str, span: Span) -> Literal {
+        Literal { id, kind: LitKind::Variant(enumeration.to_string(), variant.to_string()), span }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -483,6 +654,8 @@ pub enum LitKind {
     Numeric(String, Option<String>),
     /// A boolean literal (`true`)
     Bool(bool),
+    /// A variant of an enumeration, given by the names of the enumeration and the variant (`Mode::Armed`)
+    Variant(String, String),
 }
 
 /**
@@ -29,42 +29,9 @@ impl Expression {
     }
 
     pub(crate) fn parse_duration(&self) -> Result<UOM_Time, String> {
-        let (val, unit) = match &self.kind {
-            ExpressionKind::Lit(l) => match &l.kind {
-                LitKind::Numeric(val, Some(unit)) => (parse_rational(val)?, unit),
-                _ => return Err(format!("expected numeric value with unit, found `{}`", l)),
-            },
-            _ => return Err(format!("expected numeric value with unit, found `{}`", self)),
-        };
-
-        match unit.as_str() {
-            "ns" | "μs" | "us" | "ms" | "s" | "min" | "h" | "d" | "w" | "a" => {
-                use uom::si::time::*;
-                let factor = match unit.as_str() {
-                    "ns" => UOM_Time::new::<nanosecond>(Rational::one()),
-                    "μs" | "us" => UOM_Time::new::<microsecond>(Rational::one()),
-                    "ms" => UOM_Time::new::<millisecond>(Rational::one()),
-                    "s" => UOM_Time::new::<second>(Rational::one()),
-                    "min" => UOM_Time::new::<minute>(Rational::one()),
-                    "h" => UOM_Time::new::<hour>(Rational::one()),
-                    "d" => UOM_Time::new::<day>(Rational::one()),
-                    "w" => UOM_Time::new::<day>(Rational::from_u64(7).unwrap()),
-                    "a" => UOM_Time::new::<day>(Rational::from_u64(365).unwrap()),
-                    u => unreachable!("'{}' should not have been catched by outer match", u),
-                };
-                let factor = factor.get::<second>();
-                let duration = match val.checked_mul(&factor) {
-                    Some(d) => d,
-                    _ => {
-                        return Err(format!(
-                            "parsing duration failed: rational {}*{} does not fit into Rational64",
-                            val, factor
-                        ))
-                    }
-                };
-                Ok(UOM_Time::new::<second>(duration))
-            }
-            u => Err(format!("expected duration unit, found `{}`", u)),
+        match &self.kind {
+            ExpressionKind::Lit(l) => l.parse_duration(),
+            _ => Err(format!("expected numeric value with unit, found `{}`", self)),
         }
     }
 
@@ -193,6 +160,43 @@ impl Expression {
 }
 
 impl Literal {
+    pub(crate) fn parse_duration(&self) -> Result<UOM_Time, String> {
+        let (val, unit) = match &self.kind {
+            LitKind::Numeric(val, Some(unit)) => (parse_rational(val)?, unit),
+            _ => return Err(format!("expected numeric value with unit, found `{}`", self)),
+        };
+
+        match unit.as_str() {
+            "ns" | "μs" | "us" | "ms" | "s" | "min" | "h" | "d" | "w" | "a" => {
+                use uom::si::time::*;
+                let factor = match unit.as_str() {
+                    "ns" => UOM_Time::new::<nanosecond>(Rational::one()),
+                    "μs" | "us" => UOM_Time::new::<microsecond>(Rational::one()),
+                    "ms" => UOM_Time::new::<millisecond>(Rational::one()),
+                    "s" => UOM_Time::new::<second>(Rational::one()),
+                    "min" => UOM_Time::new::<minute>(Rational::one()),
+                    "h" => UOM_Time::new::<hour>(Rational::one()),
+                    "d" => UOM_Time::new::<day>(Rational::one()),
+                    "w" => UOM_Time::new::<day>(Rational::from_u64(7).unwrap()),
+                    "a" => UOM_Time::new::<day>(Rational::from_u64(365).unwrap()),
+                    u => unreachable!("'{}' should not have been catched by outer match", u),
+                };
+                let factor = factor.get::<second>();
+                let duration = match val.checked_mul(&factor) {
+                    Some(d) => d,
+                    _ => {
+                        return Err(format!(
+                            "parsing duration failed: rational {}*{} does not fit into Rational64",
+                            val, factor
+                        ))
+                    }
+                };
+                Ok(UOM_Time::new::<second>(duration))
+            }
+            u => Err(format!("expected duration unit, found `{}`", u)),
+        }
+    }
+
     pub(crate) fn parse_numeric<T>(&self) -> Option<T>
     where
         T: FromStr,
@@ -267,7 +271,7 @@ impl Expression {
     pub(crate) fn get_expr_from_tuple(&self, idx: usize) -> Option<&Expression> {
         use ExpressionKind::*;
         match &self.kind {
-            Tuple(entries) => Some(entries[idx].as_ref()),
+            Tuple(entries) | Array(entries) => Some(entries[idx].as_ref()),
             _ => None,
         }
     }
@@ -280,6 +284,7 @@ impl Expression {
             Lit(_) | Ident(_) | MissingExpression => Box::new(std::iter::once(self)),
             Unary(_, inner)
             | Field(inner, _)
+            | Cast(inner, _)
             | StreamAccess(inner, _)
             | Offset(inner, _)
             | ParenthesizedExpression(_, inner, _) => Box::new(std::iter::once(self).chain(inner.iter())),
@@ -291,7 +296,8 @@ impl Expression {
             Ite(cond, normal, alternative) => {
                 Box::new(std::iter::once(self).chain(cond.iter()).chain(normal.iter()).chain(alternative.iter()))
             }
-            Tuple(entries) | Function(_, _, entries) => {
+            Let(binding, body) => Box::new(std::iter::once(self).chain(binding.expr.iter()).chain(body.iter())),
+            Tuple(entries) | Array(entries) | Function(_, _, entries) => {
                 Box::new(std::iter::once(self).chain(entries.iter().map(|entry| entry.iter()).flatten()))
             }
             Method(base, _, _, arguments) => Box::new(
@@ -32,6 +32,28 @@ fn format_opt<T: Display>(opt: &Option<T>, pref: &str, suff: &str) -> String {
     }
 }
 
+/// Escapes the quotes, backslashes, and line breaks of a string, so it parses to the same string
+fn escape(s: &str) -> String {
+    s.chars().fold(String::with_capacity(s.len()), |mut escaped, c| {
+        match c {
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '"' | '\\' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+        escaped
+    })
+}
+
+/// Formats an optional trigger message in quotes, escaping its special characters
+fn format_message(message: &Option<String>) -> String {
+    format_opt(&message.as_deref().map(escape), " \"", "\"")
+}
+
 /// Formats an optional type
 fn format_type(ty: &Option<Type>) -> String {
     format_opt(ty, ": ", "")
@@ -39,7 +61,10 @@ fn format_type(ty: &Option<Type>) -> String {
 
 impl Display for Constant {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "constant {}{} := {}", self.name, format_type(&self.ty), self.literal)
+        if self.configurable {
+            write!(f, "configurable ")?;
+        }
+        write!(f, "constant {}{} := {}", self.name, format_type(&self.ty), self.value)
     }
 }
 
@@ -55,7 +80,7 @@ impl Display for Input {
 
 impl Display for Output {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
-        write!(f, "output {}", self.name)?;
+        write!(f, "{} {}", if self.derived_input { "input" } else { "output" }, self.name)?;
         if !self.params.is_empty() {
             write_delim_list(f, &self.params, " (", ")", ", ")?;
         }
@@ -126,16 +151,26 @@ impl Display for TerminateSpec {
 
 impl Display for Trigger {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if self.assumption {
+            return write!(f, "assume {}{}", self.expression, format_message(&self.message));
+        }
         write!(
             f,
-            "trigger{} {}{}",
+            "trigger{} {}{}{}",
             format_opt(&self.name, " ", " :="),
             self.expression,
-            format_opt(&self.message, " \"", "\""),
+            format_opt(&self.escalation, " ", ""),
+            format_message(&self.message),
         )
     }
 }
 
+impl Display for Escalation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "escalate after {} within {}", self.after, self.within)
+    }
+}
+
 impl Display for Type {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(f, "{}", self.kind)
@@ -147,7 +182,11 @@ impl Display for TypeKind {
         match &self {
             TypeKind::Simple(name) => write!(f, "{}", name),
             TypeKind::Tuple(types) => write_delim_list(f, types, "(", ")", ", "),
-            TypeKind::Optional(ty) => write!(f, "{}?", ty),
+            TypeKind::Array(ty, size) => write!(f, "[{}; {}]", ty, size),
+            TypeKind::Optional(ty) => match ty.kind {
+                TypeKind::Simple(_) => write!(f, "{}?", ty),
+                _ => write!(f, "Option<{}>", ty),
+            },
             TypeKind::Inferred => write!(f, "_"),
         }
     }
@@ -159,6 +198,19 @@ impl Display for TypeDeclField {
     }
 }
 
+impl Display for TypeAlias {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "type {} := {}", self.name, self.ty)
+    }
+}
+
+impl Display for EnumDeclaration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "enum {} ", self.name)?;
+        write_delim_list(f, &self.variants, "{ ", " }", ", ")
+    }
+}
+
 impl Display for TypeDeclaration {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         write!(f, "type {}", format_opt(&self.name, "", ""))?;
@@ -190,6 +242,7 @@ impl Display for Expression {
             }
             ExpressionKind::MissingExpression => Ok(()),
             ExpressionKind::Tuple(exprs) => write_delim_list(f, exprs, "(", ")", ", "),
+            ExpressionKind::Array(exprs) => write_delim_list(f, exprs, "[", "]", ", "),
             ExpressionKind::Function(name, types, args) => {
                 write!(f, "{}", name.name)?;
                 if !types.is_empty() {
@@ -206,6 +259,8 @@ impl Display for Expression {
                 write_delim_list(f, &args, "(", ")", ", ")
             }
             ExpressionKind::Field(expr, ident) => write!(f, "{}.{}", expr, ident),
+            ExpressionKind::Cast(expr, ty) => write!(f, "{} as {}", expr, ty),
+            ExpressionKind::Let(binding, body) => write!(f, "let {} := {} in {}", binding.name, binding.expr, body),
             ExpressionKind::Method(expr, name, types, args) => {
                 write!(f, "{}.{}", expr, name.name)?;
                 if !types.is_empty() {
@@ -271,6 +326,11 @@ impl Display for TimeUnit {
 
 impl Display for WindowOperation {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            WindowOperation::Percentile(p) => return write!(f, "percentile({})", p),
+            WindowOperation::Custom(name) => return write!(f, "{}", name),
+            _ => {}
+        }
         write!(
             f,
             "{}",
@@ -284,6 +344,10 @@ impl Display for WindowOperation {
                 WindowOperation::Max => "max",
                 WindowOperation::Disjunction => "∃",
                 WindowOperation::Conjunction => "∀",
+                WindowOperation::Median => "median",
+                WindowOperation::First => "first",
+                WindowOperation::Last => "last",
+                WindowOperation::Percentile(_) | WindowOperation::Custom(_) => unreachable!("printed above"),
             }
         )
     }
@@ -308,7 +372,8 @@ impl Display for Literal {
         match &self.kind {
             LitKind::Bool(val) => write!(f, "{}", val),
             LitKind::Numeric(val, unit) => write!(f, "{}{}", val, unit.clone().unwrap_or_default()),
-            LitKind::Str(s) => write!(f, "\"{}\"", s),
+            LitKind::Str(s) => write!(f, "\"{}\"", escape(s)),
+            LitKind::Variant(enumeration, variant) => write!(f, "{}::{}", enumeration, variant),
             LitKind::RawStr(s) => {
                 // need to determine padding with `#`
                 let mut padding = 0;
@@ -372,6 +437,12 @@ impl Display for RTLolaAst {
         for decl in &self.type_declarations {
             writeln!(f, "{}", decl)?;
         }
+        for declaration in &self.enum_declarations {
+            writeln!(f, "{}", declaration)?;
+        }
+        for alias in &self.type_aliases {
+            writeln!(f, "{}", alias)?;
+        }
         for constant in &self.constants {
             writeln!(f, "{}", constant)?;
         }
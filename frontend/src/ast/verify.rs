@@ -1,5 +1,5 @@
 use super::*;
-use crate::reporting::{Handler, LabeledSpan};
+use crate::reporting::{Code, Handler, LabeledSpan};
 use num::Signed;
 use uom::si::time::second;
 
@@ -34,6 +34,7 @@ impl<'a, 'b> Verifier<'a, 'b> {
         expr.iter().for_each(|inner| Self::check_field_access(self.handler, inner));
         expr.iter().for_each(|inner| Self::check_valid_offset(self.handler, inner));
         expr.iter().for_each(|inner| Self::check_sliding_window_duration(self.handler, inner));
+        expr.iter().for_each(|inner| Self::check_regex_pattern(self.handler, inner));
     }
 
     fn check_missing_paranthesis(handler: &Handler, expr: &Expression) {
@@ -41,12 +42,14 @@ impl<'a, 'b> Verifier<'a, 'b> {
         if let ParenthesizedExpression(left, _, right) = &expr.kind {
             if left.is_none() {
                 handler.warn_with_span(
+                    Code::UnbalancedParentheses,
                     "missing opening parenthesis",
                     LabeledSpan::new(expr.span, "this expression is missing an opening parenthesis", true),
                 )
             }
             if right.is_none() {
                 handler.warn_with_span(
+                    Code::UnbalancedParentheses,
                     "missing closing parenthesis",
                     LabeledSpan::new(expr.span, "this expression is missing a closing parenthesis", true),
                 )
@@ -58,6 +61,7 @@ impl<'a, 'b> Verifier<'a, 'b> {
         use ExpressionKind::*;
         if let MissingExpression = &expr.kind {
             handler.error_with_span(
+                Code::MissingExpression,
                 "missing expression",
                 LabeledSpan::new(expr.span, "we expected an expression here.", true),
             );
@@ -72,29 +76,50 @@ impl<'a, 'b> Verifier<'a, 'b> {
                 if let Ident(_) = inner.kind {
                     // is a direct access
                 } else {
-                    handler.error_with_span(
+                    let mut builder = handler.build_error_with_span(
+                        Code::ExpectedStreamVariable,
                         "operation can be only applied to streams directly",
                         LabeledSpan::new(inner.span, "expected a stream variable", true),
                     );
+                    if let (Array(_), Offset(..)) = (&inner.kind, &expr.kind) {
+                        builder.add_help(
+                            "elements of arrays are accessed by constant indices like tuple fields, e.g., `.1`",
+                        );
+                    }
+                    builder.emit();
                 }
             }
             _ => {}
         }
     }
 
-    /// Currently, offsets can only be negative
+    /// Currently, offsets can only be negative; a zero offset is the current value, i.e., the plain stream.
     fn check_valid_offset(handler: &Handler, expr: &Expression) {
         use ExpressionKind::*;
-        if let Offset(_, offset) = &expr.kind {
+        if let Offset(stream, offset) = &expr.kind {
             if let super::Offset::Discrete(val) = offset {
                 if *val == 0 {
-                    handler
-                        .error_with_span("only non-zero offsets are permitted", LabeledSpan::new(expr.span, "", true));
+                    let mut builder = handler.build_error_with_span(
+                        Code::UnsupportedOffset,
+                        "only non-zero offsets are permitted",
+                        LabeledSpan::new(expr.span, "", true),
+                    );
+                    if let Ident(_) = stream.kind {
+                        builder.add_suggestion(
+                            "access the current value of the stream directly",
+                            expr.span,
+                            &stream.to_string(),
+                        );
+                    }
+                    builder.emit();
                 }
             } else if let super::Offset::RealTime(val, _) = offset {
                 if !val.is_negative() {
-                    handler
-                        .error_with_span("only negative offsets are supported", LabeledSpan::new(expr.span, "", true));
+                    handler.error_with_span(
+                        Code::UnsupportedOffset,
+                        "only negative offsets are supported",
+                        LabeledSpan::new(expr.span, "", true),
+                    );
                 }
             }
         }
@@ -105,6 +130,7 @@ impl<'a, 'b> Verifier<'a, 'b> {
         if let Field(_, ident) = &expr.kind {
             if ident.name.parse::<usize>().is_err() {
                 handler.error_with_span(
+                    Code::InvalidFieldAccess,
                     "field access has to be an integer",
                     LabeledSpan::new(ident.span, "expected an integer", true),
                 );
@@ -118,6 +144,7 @@ impl<'a, 'b> Verifier<'a, 'b> {
             match duration.parse_duration() {
                 Err(_) => {
                     handler.error_with_span(
+                        Code::InvalidWindowDuration,
                         "aggregation duration invalid",
                         LabeledSpan::new(duration.span, "duration invalid", true),
                     );
@@ -125,6 +152,7 @@ impl<'a, 'b> Verifier<'a, 'b> {
                 Ok(dur) => {
                     if !dur.get::<second>().is_positive() {
                         handler.error_with_span(
+                            Code::InvalidWindowDuration,
                             "only positive aggregation durations are supported",
                             LabeledSpan::new(duration.span, "duration non-positive", true),
                         );
@@ -133,6 +161,36 @@ impl<'a, 'b> Verifier<'a, 'b> {
             }
         }
     }
+
+    /// The pattern of `matches` has to be a valid regular expression given as string literal.
+    /// This way, it can be compiled once instead of in every evaluation.
+    fn check_regex_pattern(handler: &Handler, expr: &Expression) {
+        use ExpressionKind::*;
+        let pattern = match &expr.kind {
+            Function(name, _, args) if name.name.name == "matches" && args.len() == 2 => &args[1],
+            Method(_, name, _, args) if name.name.name == "matches" && args.len() == 1 => &args[0],
+            _ => return,
+        };
+        match &pattern.kind {
+            Lit(Literal { kind: LitKind::Str(s), .. }) | Lit(Literal { kind: LitKind::RawStr(s), .. }) => {
+                if let Err(e) = regex::Regex::new(s) {
+                    let reason = e.to_string();
+                    // The error message of `regex` repeats the pattern, its last line states the reason.
+                    let reason = reason.lines().last().unwrap_or_default().trim_start_matches("error: ");
+                    handler.error_with_span(
+                        Code::InvalidRegex,
+                        "invalid regular expression",
+                        LabeledSpan::new(pattern.span, reason, true),
+                    );
+                }
+            }
+            _ => handler.error_with_span(
+                Code::RegexNotLiteral,
+                "the pattern of `matches` has to be a string literal",
+                LabeledSpan::new(pattern.span, "expected a string literal", true),
+            ),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -191,4 +249,14 @@ mod tests {
         assert_eq!(1, number_of_errors("output a := (x+1).hold()"));
         assert_eq!(1, number_of_errors("output a := (x+1).aggregate(over: 1h, using: avg)"));
     }
+
+    #[test]
+    fn test_regex_pattern() {
+        assert_eq!(0, number_of_errors("output a := matches(x, \"^[a-z]+$\")"));
+        assert_eq!(0, number_of_errors("output a := x.matches(\"error|warning\")"));
+        assert_eq!(0, number_of_errors("output a := x.matches(regex: r\"\\d+\")"));
+        assert_eq!(1, number_of_errors("output a := matches(x, \"(unclosed\")"));
+        assert_eq!(1, number_of_errors("output a := x.matches(regex: \"[z-a]\")"));
+        assert_eq!(1, number_of_errors("output a := matches(x, y)"));
+    }
 }
@@ -0,0 +1,340 @@
+//! This module offers the candidates for completing the name at a position in a specification, see `completions`.
+//!
+//! The context of the position is recognized lexically, e.g., a type annotation or the aggregation function of a sliding window.
+//! The candidates come from the AST and the standard library; as the specification is usually incomplete while typing, the name at the position is replaced by a placeholder and, if the specification still does not parse, the line of the position is left out.
+
+use crate::ast::{RTLolaAst, TypeKind};
+use crate::diagnostics::DiagnosticCollector;
+use crate::parse::{SourceMapper, Span};
+use crate::reporting::Handler;
+use crate::stdlib::{FuncDecl, STANDARD_LIBRARY};
+use crate::ty::{TypeConstraint, ValueTy};
+use crate::FrontendConfig;
+use std::path::PathBuf;
+
+/// The class of a completion candidate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CompletionKind {
+    /// The name of a stream or constant.
+    Stream,
+    /// The name of a parameter of the enclosing stream.
+    Parameter,
+    /// A function of the standard library or an imported module.
+    Function,
+    /// An aggregation function of a sliding window, e.g., `sum`.
+    Aggregation,
+    /// The name of a type, e.g., `Int32`.
+    Type,
+}
+
+/// A candidate for completing the name at a position.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Completion {
+    /// The completed name.
+    pub label: String,
+    /// The class of the candidate.
+    pub kind: CompletionKind,
+    /// Details shown next to the name, e.g., the type of a stream or the signature of a function.
+    pub detail: Option<String>,
+}
+
+/// The values an aggregation function of a sliding window applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Aggregated {
+    Any,
+    Numeric,
+    Bool,
+}
+
+/// The aggregation functions of sliding windows.
+const AGGREGATIONS: [(&str, &str, Aggregated); 12] = [
+    ("count", "the number of values", Aggregated::Any),
+    ("sum", "the sum of the values", Aggregated::Numeric),
+    ("average", "the average of the values", Aggregated::Numeric),
+    ("integral", "the integral over the values", Aggregated::Numeric),
+    ("min", "the minimal value", Aggregated::Numeric),
+    ("max", "the maximal value", Aggregated::Numeric),
+    ("median", "the median of the values", Aggregated::Numeric),
+    ("percentile", "the given percentile of the values, e.g., `percentile(95)`", Aggregated::Numeric),
+    ("first", "the oldest value", Aggregated::Any),
+    ("last", "the most recent value", Aggregated::Any),
+    ("forall", "whether all values are true", Aggregated::Bool),
+    ("exists", "whether any value is true", Aggregated::Bool),
+];
+
+/// Where the completed name occurs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Context {
+    /// A type annotation of a declaration.
+    Type,
+    /// The `using` argument of a sliding window.
+    Aggregation,
+    /// A member access, e.g., after `a.`, whose methods are not completed.
+    Member,
+    /// Any other position in an expression.
+    Expression,
+}
+
+impl Context {
+    /// A name that is valid in this context, which replaces the incomplete name when parsing.
+    fn placeholder(self) -> &'static str {
+        match self {
+            Context::Type => "Bool",
+            Context::Aggregation => "count",
+            Context::Member | Context::Expression => "0",
+        }
+    }
+}
+
+pub(crate) fn completions(spec: &str, offset: usize, config: FrontendConfig) -> Vec<Completion> {
+    if offset > spec.len() || !spec.is_char_boundary(offset) {
+        return Vec::new();
+    }
+    let start = spec[..offset].trim_end_matches(|c: char| c.is_alphanumeric() || c == '_').len();
+    let prefix = &spec[start..offset];
+    let context = context(&spec[..start]);
+    let ast = parse(spec, Span { start, end: offset }, context, config);
+    let mut candidates = match context {
+        Context::Member => Vec::new(),
+        Context::Type => types(ast.as_ref(), config),
+        Context::Aggregation => aggregations(&spec[..start], ast.as_ref(), config),
+        Context::Expression => {
+            let mut candidates = ast.as_ref().map_or_else(Vec::new, |ast| names(ast, start));
+            candidates.extend(functions(ast.as_ref()));
+            candidates
+        }
+    };
+    candidates.retain(|c| c.label.starts_with(prefix));
+    candidates
+}
+
+/// Recognizes the context of a name from the text before it.
+fn context(before: &str) -> Context {
+    let before = before.trim_end();
+    let line = &before[before.rfind('\n').map_or(0, |ix| ix + 1)..];
+    let declaration = ["input", "output", "constant"].iter().any(|keyword| line.trim_start().starts_with(keyword));
+    if before.ends_with('.') {
+        Context::Member
+    } else if before.ends_with("using:") {
+        Context::Aggregation
+    } else if before.ends_with(':') && declaration && !line.contains(":=") {
+        Context::Type
+    } else {
+        Context::Expression
+    }
+}
+
+/// Parses the specification with the incomplete name replaced by a placeholder, or without the line of the name if this fails.
+fn parse(spec: &str, name: Span, context: Context, config: FrontendConfig) -> Option<RTLolaAst> {
+    let handler = Handler::with_emitter(SourceMapper::new(PathBuf::new(), spec), Box::new(DiagnosticCollector::new()));
+    let completed = format!("{}{}{}", &spec[..name.start], context.placeholder(), &spec[name.end..]);
+    crate::parse::parse(&completed, &handler, config).ok().or_else(|| {
+        let line_start = spec[..name.start].rfind('\n').map_or(0, |ix| ix + 1);
+        let line_end = spec[name.end..].find('\n').map_or(spec.len(), |ix| name.end + ix);
+        let without_line = format!("{}{}", &spec[..line_start], &spec[line_end..]);
+        crate::parse::parse(&without_line, &handler, config).ok()
+    })
+}
+
+/// The streams and constants, and the parameters of the stream declared at the offset.
+fn names(ast: &RTLolaAst, offset: usize) -> Vec<Completion> {
+    let candidate = |name: &str, kind, ty: &crate::ast::Type| Completion {
+        label: name.to_string(),
+        kind,
+        detail: if matches!(ty.kind, TypeKind::Inferred) { None } else { Some(ty.to_string()) },
+    };
+    let mut candidates = Vec::new();
+    if let Some(output) = ast.outputs.iter().find(|o| o.span.start <= offset && offset <= o.span.end) {
+        candidates.extend(output.params.iter().map(|p| candidate(&p.name.name, CompletionKind::Parameter, &p.ty)));
+        candidates.push(candidate("self", CompletionKind::Stream, &output.ty));
+    }
+    for constant in &ast.constants {
+        let detail = constant.ty.as_ref().map(ToString::to_string);
+        candidates.push(Completion { label: constant.name.name.clone(), kind: CompletionKind::Stream, detail });
+    }
+    candidates.extend(ast.inputs.iter().map(|i| candidate(&i.name.name, CompletionKind::Stream, &i.ty)));
+    candidates.extend(ast.outputs.iter().map(|o| candidate(&o.name.name, CompletionKind::Stream, &o.ty)));
+    candidates
+}
+
+/// The functions available without import and in the imported modules, sorted by name.
+fn functions(ast: Option<&RTLolaAst>) -> Vec<Completion> {
+    let imports = ast.map_or_else(Vec::new, |ast| ast.imports.iter().map(|i| Some(i.name.name.as_str())).collect());
+    let mut functions: Vec<Completion> = std::iter::once(None)
+        .chain(imports)
+        .flat_map(|module| STANDARD_LIBRARY.functions(module))
+        .map(|f| Completion {
+            label: f.name.name.name.clone(),
+            kind: CompletionKind::Function,
+            detail: Some(signature(f)),
+        })
+        .collect();
+    functions.sort_by(|a, b| a.label.cmp(&b.label));
+    functions
+}
+
+/// The signature of a function, e.g., `max<T: numeric type>(T, T) -> T`.
+fn signature(function: &FuncDecl) -> String {
+    let generics: Vec<String> = function
+        .generics
+        .iter()
+        .enumerate()
+        .map(|(ix, generic)| {
+            let name = function
+                .parameters
+                .iter()
+                .chain(std::iter::once(&function.return_type))
+                .find_map(|ty| match ty {
+                    ValueTy::Param(param, name) if *param as usize == ix => Some(name.as_str()),
+                    _ => None,
+                })
+                .unwrap_or("T");
+            match generic {
+                ValueTy::Constr(TypeConstraint::Unconstrained) => name.to_string(),
+                ValueTy::Constr(constraint) => format!("{}: {}", name, constraint),
+                ty => format!("{}: {}", name, ty),
+            }
+        })
+        .collect();
+    let parameters: Vec<String> = function
+        .name
+        .arg_names
+        .iter()
+        .zip(&function.parameters)
+        .map(|(arg_name, ty)| match arg_name {
+            Some(arg_name) => format!("{}: {}", arg_name.name, ty),
+            None => ty.to_string(),
+        })
+        .collect();
+    let generics = if generics.is_empty() { String::new() } else { format!("<{}>", generics.join(", ")) };
+    format!("{}{}({}) -> {}", function.name.name.name, generics, parameters.join(", "), function.return_type)
+}
+
+/// The primitive types and the types declared in the specification.
+fn types(ast: Option<&RTLolaAst>, config: FrontendConfig) -> Vec<Completion> {
+    let mut types: Vec<Completion> = ValueTy::primitive_types(config.ty)
+        .into_iter()
+        .map(|(name, ty)| Completion {
+            label: name.to_string(),
+            kind: CompletionKind::Type,
+            // aliases, e.g., `Int`, show the type they stand for
+            detail: if ty.to_string() == name { None } else { Some(ty.to_string()) },
+        })
+        .collect();
+    types.sort_by(|a, b| a.label.cmp(&b.label));
+    let declared = ast.iter().flat_map(|ast| &ast.type_declarations).filter_map(|t| t.name.as_ref());
+    types.extend(declared.map(|name| Completion {
+        label: name.name.clone(),
+        kind: CompletionKind::Type,
+        detail: None,
+    }));
+    let aliases = ast.iter().flat_map(|ast| &ast.type_aliases);
+    types.extend(aliases.map(|alias| Completion {
+        label: alias.name.name.clone(),
+        kind: CompletionKind::Type,
+        detail: Some(alias.ty.to_string()),
+    }));
+    let enums = ast.iter().flat_map(|ast| &ast.enum_declarations);
+    types.extend(enums.map(|declaration| Completion {
+        label: declaration.name.name.clone(),
+        kind: CompletionKind::Type,
+        detail: Some(format!("enum {}", declaration.name)),
+    }));
+    types
+}
+
+/// The aggregation functions applicable to the type of the aggregated stream, all of them if its type is unknown.
+fn aggregations(before: &str, ast: Option<&RTLolaAst>, config: FrontendConfig) -> Vec<Completion> {
+    let ty = before.rfind(".aggregate(").and_then(|ix| {
+        let target = &before[..ix];
+        let name = &target[target.trim_end_matches(|c: char| c.is_alphanumeric() || c == '_').len()..];
+        let ast = ast?;
+        let ty = ast
+            .inputs
+            .iter()
+            .map(|i| (&i.name, &i.ty))
+            .chain(ast.outputs.iter().map(|o| (&o.name, &o.ty)))
+            .find(|(ident, _)| ident.name == name)
+            .map(|(_, ty)| ty)?;
+        match &ty.kind {
+            TypeKind::Simple(ty) => {
+                ValueTy::primitive_types(config.ty).into_iter().find(|(n, _)| *n == ty.as_str()).map(|(_, ty)| ty)
+            }
+            _ => None,
+        }
+    });
+    AGGREGATIONS
+        .iter()
+        .filter(|(_, _, aggregated)| match (aggregated, ty) {
+            (Aggregated::Any, _) | (_, None) => true,
+            (Aggregated::Numeric, Some(ty)) => ty.satisfies(&TypeConstraint::Numeric),
+            (Aggregated::Bool, Some(ty)) => *ty == ValueTy::Bool,
+        })
+        .map(|(name, detail, _)| Completion {
+            label: name.to_string(),
+            kind: CompletionKind::Aggregation,
+            detail: Some(detail.to_string()),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the labels of the candidates at the end of the specification.
+    fn labels(spec: &str, kind: CompletionKind) -> Vec<String> {
+        completions(spec, spec.len(), FrontendConfig::default())
+            .into_iter()
+            .filter(|c| c.kind == kind)
+            .map(|c| c.label)
+            .collect()
+    }
+
+    #[test]
+    fn names_in_scope() {
+        let spec = "constant limit: Int32 := 5\ninput a: Int32\noutput b(p: Int32) @ a := p + ";
+        assert_eq!(labels(spec, CompletionKind::Parameter), vec!["p"]);
+        assert_eq!(labels(spec, CompletionKind::Stream), vec!["self", "limit", "a", "b"]);
+        let trigger = "input a: Int32\noutput b(p: Int32) @ a := p\ntrigger a > ";
+        assert_eq!(labels(trigger, CompletionKind::Stream), vec!["a", "b"]);
+        assert!(labels(trigger, CompletionKind::Parameter).is_empty());
+        // the incomplete line is left out if the specification does not parse otherwise
+        let incomplete = "input a: Int32\noutput b := a + ;\ninput c: Bool";
+        let candidates = completions(incomplete, incomplete.find("+ ").unwrap() + 2, FrontendConfig::default());
+        assert_eq!(candidates.iter().filter(|c| c.kind == CompletionKind::Stream).count(), 2);
+    }
+
+    #[test]
+    fn functions_with_signatures() {
+        let spec = "input a: Int32\noutput b := ma";
+        assert_eq!(labels(spec, CompletionKind::Function), vec!["map_range", "matches", "max"]);
+        let max = completions(spec, spec.len(), FrontendConfig::default()).pop().unwrap();
+        assert_eq!(max.detail.as_deref(), Some("max<T: numeric type>(T, T) -> T"));
+        let imported = "import math\ninput a: Float64\noutput b := sq";
+        assert_eq!(labels(imported, CompletionKind::Function), vec!["sqrt"]);
+        assert!(labels("input a: Float64\noutput b := sq", CompletionKind::Function).is_empty());
+    }
+
+    #[test]
+    fn aggregations() {
+        let numeric = "input a: Int32\noutput b @ 1Hz := a.aggregate(over: 5s, using: ";
+        assert_eq!(
+            labels(numeric, CompletionKind::Aggregation),
+            vec!["count", "sum", "average", "integral", "min", "max", "median", "percentile", "first", "last"]
+        );
+        let boolean = "input a: Bool\noutput b @ 1Hz := a.aggregate(over: 5s, using: ex";
+        assert_eq!(labels(boolean, CompletionKind::Aggregation), vec!["exists"]);
+        assert!(labels(boolean, CompletionKind::Stream).is_empty());
+    }
+
+    #[test]
+    fn types() {
+        let spec = "input a: Int";
+        let types = completions(spec, spec.len(), FrontendConfig::default());
+        assert!(types.iter().all(|c| c.kind == CompletionKind::Type && c.label.starts_with("Int")));
+        assert!(types.iter().any(|c| c.label == "Int32" && c.detail.is_none()));
+        assert!(labels("input a: Int32\noutput b: ", CompletionKind::Type).contains(&String::from("Bool")));
+        assert!(labels("input a: Int32\noutput b := a.", CompletionKind::Stream).is_empty());
+    }
+}
@@ -0,0 +1,215 @@
+//! This module provides the diagnostics of a specification as values, see `parse_with_diagnostics`.
+//!
+//! Host applications, e.g., language servers, web playgrounds, or bindings to other languages, present them in their own UI instead of reading them from stderr.
+
+use crate::parse::{SourceMapper, Span};
+use crate::reporting::{self, Emitter, Level};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The severity of a diagnostic or of a note attached to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// The specification is invalid.
+    Error,
+    /// The specification is valid, but likely not what was intended.
+    Warning,
+    /// Additional information, e.g., why a type was expected.
+    Note,
+    /// A suggestion how to resolve the diagnostic.
+    Help,
+}
+
+/// An error or warning of a specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// Whether the diagnostic is an error or a warning.
+    pub severity: Severity,
+    /// The stable code of the diagnostic, e.g., `E0106`; summaries such as aborting the analysis have none.
+    pub code: Option<String>,
+    /// The main message of the diagnostic.
+    pub message: String,
+    /// The locations in the specification the diagnostic refers to.
+    pub spans: Vec<DiagnosticSpan>,
+    /// Notes and help messages attached to the diagnostic.
+    pub notes: Vec<DiagnosticNote>,
+    /// Fixes that can be applied as is, e.g., as quick-fixes of an editor.
+    pub suggestions: Vec<Suggestion>,
+}
+
+/// A location in the specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticSpan {
+    /// The byte offset of the start in the specification.
+    pub start: usize,
+    /// The byte offset of the end in the specification, exclusive.
+    pub end: usize,
+    /// The line of the start, starting at 1.
+    pub line_start: usize,
+    /// The column of the start in characters, starting at 1.
+    pub column_start: usize,
+    /// The line of the end, starting at 1.
+    pub line_end: usize,
+    /// The column of the end in characters, starting at 1.
+    pub column_end: usize,
+    /// Explains the role of the location, e.g., `does not exist`.
+    pub label: Option<String>,
+    /// Whether this is the main location of the diagnostic.
+    pub primary: bool,
+}
+
+/// A note or help message attached to a diagnostic.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiagnosticNote {
+    /// Either `Severity::Note` or `Severity::Help`.
+    pub severity: Severity,
+    /// The message of the note.
+    pub message: String,
+    /// The location the note refers to, if any.
+    pub span: Option<DiagnosticSpan>,
+}
+
+/// A fix replacing a span of the specification by a text; empty spans insert the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suggestion {
+    /// Describes the fix, e.g., `add a default value`.
+    pub message: String,
+    /// The replaced part of the specification.
+    pub span: DiagnosticSpan,
+    /// The text replacing the span.
+    pub replacement: String,
+}
+
+/// Collects diagnostics instead of printing them; clones share the collected diagnostics.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct DiagnosticCollector {
+    diagnostics: Rc<RefCell<Vec<Diagnostic>>>,
+}
+
+impl Emitter for DiagnosticCollector {
+    fn emit(&mut self, mapper: &SourceMapper, diagnostic: &reporting::Diagnostic) {
+        let notes = diagnostic
+            .children
+            .iter()
+            .map(|child| DiagnosticNote {
+                severity: severity(child.level),
+                message: child.message.clone(),
+                span: child.span.map(|s| span(mapper, s, None, true)),
+            })
+            .collect();
+        let suggestions = diagnostic
+            .suggestions
+            .iter()
+            .map(|suggestion| Suggestion {
+                message: suggestion.message.clone(),
+                span: span(mapper, suggestion.span, None, true),
+                replacement: suggestion.replacement.clone(),
+            })
+            .collect();
+        self.diagnostics.borrow_mut().push(Diagnostic {
+            severity: severity(diagnostic.level),
+            code: diagnostic.code.map(|c| c.id().to_string()),
+            message: diagnostic.message.clone(),
+            spans: diagnostic.span.iter().map(|s| span(mapper, s.span, s.label.as_deref(), s.primary)).collect(),
+            notes,
+            suggestions,
+        });
+    }
+}
+
+impl DiagnosticCollector {
+    pub(crate) fn new() -> DiagnosticCollector {
+        DiagnosticCollector::default()
+    }
+
+    /// Returns the collected diagnostics in the order they were emitted.
+    pub(crate) fn diagnostics(&self) -> Vec<Diagnostic> {
+        self.diagnostics.borrow().clone()
+    }
+}
+
+fn severity(level: Level) -> Severity {
+    match level {
+        Level::Bug | Level::Fatal | Level::Error => Severity::Error,
+        Level::Warning => Severity::Warning,
+        Level::Note => Severity::Note,
+        Level::Help => Severity::Help,
+    }
+}
+
+fn span(mapper: &SourceMapper, span: Span, label: Option<&str>, primary: bool) -> DiagnosticSpan {
+    let (line_start, column_start) = mapper.get_position(span.start);
+    let (line_end, column_end) = mapper.get_position(span.end.max(span.start));
+    DiagnosticSpan {
+        start: span.start,
+        end: span.end.max(span.start),
+        line_start,
+        column_start,
+        line_end,
+        column_end,
+        label: label.filter(|l| !l.is_empty()).map(str::to_string),
+        primary,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrontendConfig;
+
+    fn diagnostics(spec: &str) -> Vec<Diagnostic> {
+        let (_, diagnostics) = crate::parse_with_diagnostics("spec.lola", spec, FrontendConfig::default());
+        diagnostics
+    }
+
+    #[test]
+    fn valid_specification() {
+        let (ir, diagnostics) =
+            crate::parse_with_diagnostics("spec.lola", "input a: Int32\noutput b := a", FrontendConfig::default());
+        assert!(ir.is_ok());
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn warnings() {
+        let diagnostics = diagnostics("input a: Int32\noutput b @ a := a.hold().defaults(to: 0)");
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("W0201"));
+    }
+
+    #[test]
+    fn errors_with_notes_and_suggestions() {
+        let diagnostics = diagnostics("input altitude: Float64\noutput b @ 1Hz := altitdue.get()");
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        let unknown = &diagnostics[0];
+        assert_eq!(unknown.severity, Severity::Error);
+        let location = DiagnosticSpan {
+            start: 42,
+            end: 50,
+            line_start: 2,
+            column_start: 19,
+            line_end: 2,
+            column_end: 27,
+            label: Some(String::from("does not exist")),
+            primary: true,
+        };
+        assert_eq!(unknown.spans, vec![location.clone()]);
+        assert_eq!(unknown.suggestions[0].span, DiagnosticSpan { label: None, ..location });
+        assert_eq!(unknown.suggestions[0].replacement, "altitude");
+        // the analysis is aborted after the error
+        assert_eq!(diagnostics[1].code, None);
+
+        let diagnostics = self::diagnostics("input a: Int32\noutput b @ 1Hz := a.get().defaults(to: 0)");
+        let notes = &diagnostics[0].notes;
+        assert_eq!(notes.iter().map(|n| n.severity).collect::<Vec<_>>(), vec![Severity::Note, Severity::Help]);
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let diagnostics = diagnostics("input a: Int32\noutput := a");
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].code.as_deref(), Some("E0001"));
+        assert_eq!((diagnostics[0].spans[0].line_start, diagnostics[0].spans[0].column_start), (2, 8));
+    }
+}
@@ -0,0 +1,198 @@
+//! This module compares two specifications declaration by declaration, see `diff`.
+//!
+//! Declarations are matched by their kind and name, triggers by their message or, without message, by their expression.
+//! Their parts are compared in their pretty-printed form, so changes of whitespace, comments, or the order of declarations are no differences.
+
+use crate::analysis;
+use crate::ast::{RTLolaAst, Type, TypeKind};
+use crate::diagnostics::DiagnosticCollector;
+use crate::parse::{NodeId, SourceMapper};
+use crate::reporting::Handler;
+use crate::stdlib::STANDARD_LIBRARY;
+use crate::FrontendConfig;
+use std::fmt::{Display, Formatter, Result};
+use std::path::PathBuf;
+
+/// How a declaration differs between the specifications.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChangeKind {
+    /// The declaration is only part of the new specification.
+    Added,
+    /// The declaration is only part of the old specification.
+    Removed,
+    /// Parts of the declaration differ, e.g., its type or expression.
+    Changed,
+}
+
+/// A difference of a declaration between the specifications.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// How the declaration differs.
+    pub kind: ChangeKind,
+    /// The declaration, e.g., `output b` or `trigger "too high"`.
+    pub declaration: String,
+    /// The changed parts of a changed declaration, e.g., ``type: `Int32` -> `Int64` ``.
+    pub details: Vec<String>,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let marker = match self.kind {
+            ChangeKind::Added => '+',
+            ChangeKind::Removed => '-',
+            ChangeKind::Changed => '~',
+        };
+        write!(f, "{} {}", marker, self.declaration)?;
+        for detail in &self.details {
+            write!(f, "\n    {}", detail)?;
+        }
+        Ok(())
+    }
+}
+
+/// A declaration with its parts, e.g., `type` and `expression`.
+struct Declaration {
+    name: String,
+    parts: Vec<(&'static str, String)>,
+}
+
+/// Returns the differences of the declarations of the specifications, or which specification does not parse.
+pub(crate) fn diff(old: &str, new: &str, config: FrontendConfig) -> std::result::Result<Vec<Change>, String> {
+    let old = declarations(old, config).ok_or("the old specification does not parse")?;
+    let new = declarations(new, config).ok_or("the new specification does not parse")?;
+    let mut changes = Vec::new();
+    for declaration in &old {
+        match new.iter().find(|d| d.name == declaration.name) {
+            None => changes.push(Change {
+                kind: ChangeKind::Removed,
+                declaration: declaration.name.clone(),
+                details: Vec::new(),
+            }),
+            Some(changed) => {
+                let details: Vec<String> = declaration
+                    .parts
+                    .iter()
+                    .zip(&changed.parts)
+                    .filter(|((_, old), (_, new))| old != new)
+                    .map(|((part, old), (_, new))| format!("{}: `{}` -> `{}`", part, old, new))
+                    .collect();
+                if !details.is_empty() {
+                    changes.push(Change { kind: ChangeKind::Changed, declaration: declaration.name.clone(), details });
+                }
+            }
+        }
+    }
+    for declaration in new.iter().filter(|d| old.iter().all(|o| o.name != d.name)) {
+        changes.push(Change { kind: ChangeKind::Added, declaration: declaration.name.clone(), details: Vec::new() });
+    }
+    Ok(changes)
+}
+
+/// Returns the declarations of the specification, or `None` if it does not parse.
+fn declarations(spec: &str, config: FrontendConfig) -> Option<Vec<Declaration>> {
+    let handler = Handler::with_emitter(SourceMapper::new(PathBuf::new(), spec), Box::new(DiagnosticCollector::new()));
+    let ast = crate::parse::parse(spec, &handler, config).ok()?;
+    let report = analysis::analyze(&ast, &handler, config, &STANDARD_LIBRARY).ok();
+    let inferred = |id: NodeId| report.as_ref().and_then(|r| r.type_table.find_value_type(id)).map(ToString::to_string);
+    // the inferred type if the specification is valid, the annotated type otherwise
+    let ty = |id: NodeId, annotated: Option<&Type>| {
+        inferred(id).unwrap_or_else(|| {
+            annotated.filter(|ty| !matches!(ty.kind, TypeKind::Inferred)).map_or_else(String::new, Type::to_string)
+        })
+    };
+    let joined = |parts: Vec<String>| parts.join(", ");
+    let optional = |part: Option<String>| part.unwrap_or_default();
+    let RTLolaAst { imports, constants, inputs, outputs, trigger, type_declarations, type_aliases, enum_declarations } =
+        &ast;
+    let mut declarations: Vec<Declaration> =
+        imports.iter().map(|i| Declaration { name: format!("import {}", i.name), parts: Vec::new() }).collect();
+    declarations.extend(type_declarations.iter().filter_map(|t| {
+        let fields = joined(t.fields.iter().map(ToString::to_string).collect());
+        Some(Declaration { name: format!("type {}", t.name.as_ref()?), parts: vec![("fields", fields)] })
+    }));
+    declarations.extend(
+        type_aliases
+            .iter()
+            .map(|t| Declaration { name: format!("type {}", t.name), parts: vec![("type", t.ty.to_string())] }),
+    );
+    declarations.extend(enum_declarations.iter().map(|e| Declaration {
+        name: format!("enum {}", e.name),
+        parts: vec![("variants", joined(e.variants.iter().map(ToString::to_string).collect()))],
+    }));
+    declarations.extend(constants.iter().map(|c| Declaration {
+        name: format!("constant {}", c.name),
+        parts: vec![("type", ty(c.id, c.ty.as_ref())), ("value", c.value.to_string())],
+    }));
+    declarations.extend(inputs.iter().map(|i| Declaration {
+        name: format!("input {}", i.name),
+        parts: vec![
+            ("type", ty(i.id, Some(&i.ty))),
+            ("parameters", joined(i.params.iter().map(|p| format!("{}: {}", p.name, ty(p.id, Some(&p.ty)))).collect())),
+        ],
+    }));
+    declarations.extend(outputs.iter().map(|o| Declaration {
+        name: format!("{} {}", if o.derived_input { "input" } else { "output" }, o.name),
+        parts: vec![
+            ("type", ty(o.id, Some(&o.ty))),
+            ("parameters", joined(o.params.iter().map(|p| format!("{}: {}", p.name, ty(p.id, Some(&p.ty)))).collect())),
+            ("activation condition", optional(o.extend.expr.as_ref().map(ToString::to_string))),
+            ("template", optional(o.template_spec.as_ref().map(ToString::to_string))),
+            ("termination", optional(o.termination.as_ref().map(ToString::to_string))),
+            ("expression", o.expression.to_string()),
+        ],
+    }));
+    declarations.extend(trigger.iter().map(|t| {
+        let keyword = if t.assumption { "assume" } else { "trigger" };
+        let name = match (&t.name, &t.message) {
+            (Some(name), _) => format!("{} {}", keyword, name),
+            (None, Some(message)) => format!("{} \"{}\"", keyword, message),
+            (None, None) => format!("{} {}", keyword, t.expression),
+        };
+        let escalation = optional(t.escalation.as_ref().map(ToString::to_string));
+        Declaration { name, parts: vec![("expression", t.expression.to_string()), ("escalation", escalation)] }
+    }));
+    Some(declarations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn changes(old: &str, new: &str) -> Vec<String> {
+        diff(old, new, FrontendConfig::default()).unwrap().iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn formatting_is_no_difference() {
+        let old = "input a: Int32\noutput b := a + 1\ntrigger b > 5 \"too high\"";
+        let new = "// reordered\ntrigger b>5 \"too high\"\ninput a:Int32\n\noutput b :=\n    a+1";
+        assert!(changes(old, new).is_empty());
+    }
+
+    #[test]
+    fn changed_declarations() {
+        let old = "input a: Int32\ninput c: Int32\noutput b := a + 1\ntrigger b > 5 \"too high\"";
+        let new = "input a: Int64\noutput b := a + 2\noutput d := b\ntrigger b > 6 \"too high\"";
+        assert_eq!(
+            changes(old, new),
+            vec![
+                "~ input a\n    type: `Int32` -> `Int64`",
+                "- input c",
+                "~ output b\n    type: `Int32` -> `Int64`\n    expression: `a + 1` -> `a + 2`",
+                "~ trigger \"too high\"\n    expression: `b > 5` -> `b > 6`",
+                "+ output d",
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_specifications() {
+        // annotated types are compared if a specification is invalid
+        assert_eq!(
+            changes("output b: Int32 := c", "output b: Int64 := c"),
+            vec!["~ output b\n    type: `Int32` -> `Int64`"]
+        );
+        let err = diff("input a: Int32", "input a:", FrontendConfig::default());
+        assert_eq!(err, Err(String::from("the new specification does not parse")));
+    }
+}
@@ -4,6 +4,7 @@ use std::path::PathBuf;
 use crate::analysis;
 use crate::parse::SourceMapper;
 use crate::reporting::Handler;
+use crate::stdlib::STANDARD_LIBRARY;
 use crate::FrontendConfig;
 
 #[rustfmt::skip]
@@ -20,11 +21,11 @@ pub fn analyze(filename: &str, config: FrontendConfig) {
     });
     let mapper = SourceMapper::new(PathBuf::from(filename), &contents);
     let handler = Handler::new(mapper);
-    let spec = crate::parse::parse(&contents, &handler, config).unwrap_or_else(|e| {
+    let spec = crate::parse::parse_with_features(&contents, &handler, config, &[]).unwrap_or_else(|e| {
         eprintln!("parse error:\n{}", e);
         std::process::exit(1)
     });
-    let _report = analysis::analyze(&spec, &handler, FrontendConfig::default());
+    let _report = analysis::analyze(&spec, &handler, FrontendConfig::default(), &STANDARD_LIBRARY);
     //println!("{:?}", report);
     //use crate::analysis::graph_based_analysis::MemoryBound;
     //report.graph_analysis_result.map(|r| match r.memory_requirements {
@@ -0,0 +1,516 @@
+//! This module classifies the tokens of a specification for syntax highlighting, see `semantic_tokens`.
+//!
+//! Keywords, literals, operators, and comments are recognized lexically, so they are classified even if the specification does not parse.
+//! Names are resolved against the AST, e.g., to distinguish streams from parameters; if the specification does not parse, names remain identifiers.
+
+use crate::analysis::naming::{Declaration, DeclarationTable, NamingAnalysis};
+use crate::ast::{Expression, ExpressionKind, LitKind, Literal, RTLolaAst, Type, TypeKind};
+use crate::diagnostics::DiagnosticCollector;
+use crate::parse::{SourceMapper, Span};
+use crate::reporting::Handler;
+use crate::stdlib::STANDARD_LIBRARY;
+use crate::FrontendConfig;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+/// The class of a token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+    /// A keyword, e.g., `output` or `if`.
+    Keyword,
+    /// The name of a stream or constant, both in its declaration and when accessing it.
+    Stream,
+    /// The name of a parameter of a parameterized stream.
+    Parameter,
+    /// The name of a type, e.g., `Int32`.
+    Type,
+    /// The name of a function or method, e.g., `sqrt` or `defaults`.
+    Function,
+    /// A name that could not be resolved, e.g., because the specification does not parse.
+    Identifier,
+    /// A numeric, string, or boolean literal, including units such as `1Hz`.
+    Literal,
+    /// An operator, e.g., `+`, `&&`, or `:=`.
+    Operator,
+    /// A line or block comment.
+    Comment,
+    /// An attribute of a declaration, e.g., `#[allow(W0201)]`.
+    Attribute,
+}
+
+/// A classified token of a specification; tokens do not span multiple lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    /// The class of the token.
+    pub kind: TokenKind,
+    /// The byte offset of the start in the specification.
+    pub start: usize,
+    /// The byte offset of the end in the specification, exclusive.
+    pub end: usize,
+    /// The line of the start, starting at 1.
+    pub line: usize,
+    /// The column of the start in characters, starting at 1.
+    pub column: usize,
+}
+
+const KEYWORDS: [&str; 20] = [
+    "import",
+    "type",
+    "enum",
+    "constant",
+    "input",
+    "output",
+    "trigger",
+    "assume",
+    "include",
+    "invoke",
+    "if",
+    "unless",
+    "extend",
+    "close",
+    "terminate",
+    "then",
+    "else",
+    "as",
+    "let",
+    "configurable",
+];
+
+/// Returns the classified tokens of the specification in the order of their occurrence.
+pub(crate) fn classify(spec: &str, config: FrontendConfig) -> Vec<SemanticToken> {
+    let names = resolve(spec, config);
+    let mut tokens = Vec::new();
+    let mut lines = Lines::new(spec);
+    for (mut kind, span) in Lexer::new(spec) {
+        if kind == TokenKind::Identifier {
+            kind = names.get(&span.start).copied().unwrap_or_else(|| {
+                // without resolution, a name followed by an argument list is a function or method, e.g., `defaults(to: 0)`
+                let called = spec[span.end..].trim_start().starts_with('(');
+                if called {
+                    TokenKind::Function
+                } else {
+                    TokenKind::Identifier
+                }
+            });
+        }
+        let (line, column) = lines.position(span.start);
+        tokens.push(SemanticToken { kind, start: span.start, end: span.end, line, column });
+    }
+    tokens
+}
+
+/// Classifies the names of the specification by their declaration, indexed by their start.
+/// Returns no names if the specification does not parse.
+fn resolve(spec: &str, config: FrontendConfig) -> HashMap<usize, TokenKind> {
+    let handler = Handler::with_emitter(SourceMapper::new(PathBuf::new(), spec), Box::new(DiagnosticCollector::new()));
+    let ast = match crate::parse::parse(spec, &handler, config) {
+        Ok(ast) => ast,
+        Err(_) => return HashMap::new(),
+    };
+    let declarations = NamingAnalysis::new(&handler, config, &STANDARD_LIBRARY).check(&ast);
+    let mut names = Names { declarations: &declarations, kinds: HashMap::new() };
+    names.spec(&ast);
+    names.kinds
+}
+
+struct Names<'a> {
+    declarations: &'a DeclarationTable,
+    kinds: HashMap<usize, TokenKind>,
+}
+
+impl<'a> Names<'a> {
+    fn spec(&mut self, ast: &RTLolaAst) {
+        for constant in &ast.constants {
+            self.kinds.insert(constant.name.span.start, TokenKind::Stream);
+            constant.ty.iter().for_each(|ty| self.ty(ty));
+            self.expression(&constant.value);
+        }
+        for input in &ast.inputs {
+            self.kinds.insert(input.name.span.start, TokenKind::Stream);
+            self.ty(&input.ty);
+            for param in &input.params {
+                self.kinds.insert(param.name.span.start, TokenKind::Parameter);
+                self.ty(&param.ty);
+            }
+        }
+        for output in &ast.outputs {
+            self.kinds.insert(output.name.span.start, TokenKind::Stream);
+            self.ty(&output.ty);
+            for param in &output.params {
+                self.kinds.insert(param.name.span.start, TokenKind::Parameter);
+                self.ty(&param.ty);
+            }
+            output.extend.expr.iter().for_each(|expr| self.expression(expr));
+            if let Some(template) = &output.template_spec {
+                if let Some(invoke) = &template.inv {
+                    self.expression(&invoke.target);
+                    invoke.condition.iter().for_each(|expr| self.expression(expr));
+                }
+                template.ext.iter().for_each(|extend| self.expression(&extend.target));
+                template.ter.iter().for_each(|terminate| self.expression(&terminate.target));
+            }
+            output.termination.iter().for_each(|expr| self.expression(expr));
+            self.expression(&output.expression);
+        }
+        for trigger in &ast.trigger {
+            if let Some(name) = &trigger.name {
+                self.kinds.insert(name.span.start, TokenKind::Stream);
+            }
+            self.expression(&trigger.expression);
+        }
+        for declaration in &ast.type_declarations {
+            if let Some(name) = &declaration.name {
+                self.kinds.insert(name.span.start, TokenKind::Type);
+            }
+            declaration.fields.iter().for_each(|field| self.ty(&field.ty));
+        }
+        for alias in &ast.type_aliases {
+            self.kinds.insert(alias.name.span.start, TokenKind::Type);
+            self.ty(&alias.ty);
+        }
+        for declaration in &ast.enum_declarations {
+            self.kinds.insert(declaration.name.span.start, TokenKind::Type);
+        }
+    }
+
+    fn ty(&mut self, ty: &Type) {
+        match &ty.kind {
+            TypeKind::Simple(_) => {
+                self.kinds.insert(ty.span.start, TokenKind::Type);
+            }
+            TypeKind::Tuple(types) => types.iter().for_each(|ty| self.ty(ty)),
+            TypeKind::Array(inner, _) | TypeKind::Optional(inner) => self.ty(inner),
+            TypeKind::Inferred => {}
+        }
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        for e in expr.iter() {
+            match &e.kind {
+                ExpressionKind::Let(binding, _) => {
+                    self.kinds.insert(binding.name.span.start, TokenKind::Parameter);
+                }
+                ExpressionKind::Ident(ident) => {
+                    if let Some(kind) = self.declaration(e) {
+                        self.kinds.insert(ident.span.start, kind);
+                    }
+                }
+                ExpressionKind::Function(name, types, _) => {
+                    let kind = self.declaration(e).unwrap_or(TokenKind::Function);
+                    self.kinds.insert(name.name.span.start, kind);
+                    types.iter().for_each(|ty| self.ty(ty));
+                }
+                ExpressionKind::Method(_, name, types, _) => {
+                    self.kinds.insert(name.name.span.start, TokenKind::Function);
+                    types.iter().for_each(|ty| self.ty(ty));
+                }
+                ExpressionKind::Lit(Literal { kind: LitKind::Variant(..), span, .. }) => {
+                    self.kinds.insert(span.start, TokenKind::Literal);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn declaration(&self, expr: &Expression) -> Option<TokenKind> {
+        match self.declarations.get(&expr.id)? {
+            Declaration::Const(_) | Declaration::In(_) | Declaration::Out(_) | Declaration::ParamOut(_) => {
+                Some(TokenKind::Stream)
+            }
+            Declaration::Param(_) | Declaration::Let(_) => Some(TokenKind::Parameter),
+            Declaration::Func(_) => Some(TokenKind::Function),
+            Declaration::Type(_) => Some(TokenKind::Type),
+        }
+    }
+}
+
+/// Splits a specification into keywords, names, literals, operators, comments, and attributes; names are classified as identifiers.
+/// Punctuation such as parentheses and commas is skipped.
+struct Lexer<'a> {
+    spec: &'a str,
+    pos: usize,
+    /// the remaining lines of a block comment
+    pending: Vec<Span>,
+}
+
+impl<'a> Lexer<'a> {
+    fn new(spec: &'a str) -> Lexer<'a> {
+        Lexer { spec, pos: 0, pending: Vec::new() }
+    }
+
+    fn rest(&self) -> &'a str {
+        &self.spec[self.pos..]
+    }
+
+    /// Advances while `pred` holds and returns the new position.
+    fn eat_while(&mut self, pred: impl Fn(char) -> bool) -> usize {
+        let len = self.rest().find(|c| !pred(c)).unwrap_or_else(|| self.rest().len());
+        self.pos += len;
+        self.pos
+    }
+
+    /// Advances to the end of `terminator` or to the end of the specification if it is missing.
+    fn eat_until(&mut self, terminator: &str) -> usize {
+        self.pos = self.rest().find(terminator).map_or(self.spec.len(), |ix| self.pos + ix + terminator.len());
+        self.pos
+    }
+
+    fn number(&mut self) -> usize {
+        // hexadecimal, octal, and binary literals, e.g., `0xFF`
+        if ["0x", "0o", "0b"].iter().any(|prefix| self.rest().starts_with(prefix)) {
+            self.pos += 2;
+            return self.eat_while(|c| c.is_ascii_alphanumeric() || c == '_');
+        }
+        // digits may be separated, e.g., `1_000_000`
+        let digit = |c: char| c.is_ascii_digit() || c == '_';
+        self.eat_while(digit);
+        let rest = self.rest();
+        if rest.starts_with('.') && !rest[1..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+            self.pos += 1;
+            self.eat_while(digit);
+        }
+        let rest = self.rest();
+        let exponent = rest.strip_prefix(['e', 'E']).map(|e| e.trim_start_matches(['+', '-']));
+        if let Some(exponent) = exponent.filter(|e| e.starts_with(|c: char| c.is_ascii_digit())) {
+            self.pos = self.spec.len() - exponent.len();
+            self.eat_while(digit);
+        }
+        // the unit, e.g., `Hz` in `1Hz` or `μs` in `10μs`
+        self.eat_while(char::is_alphabetic)
+    }
+
+    fn string(&mut self) -> usize {
+        self.pos += 1;
+        loop {
+            match self.rest().find(['"', '\\']) {
+                None => {
+                    self.pos = self.spec.len();
+                    return self.pos;
+                }
+                Some(ix) if self.rest()[ix..].starts_with("\\\"") => self.pos += ix + 2,
+                Some(ix) if self.rest()[ix..].starts_with('\\') => self.pos += ix + 1,
+                Some(ix) => {
+                    self.pos += ix + 1;
+                    return self.pos;
+                }
+            }
+        }
+    }
+
+    /// Lexes a raw string, e.g., `r#"a"b"#`, if one starts at the current position.
+    fn raw_string(&mut self) -> Option<usize> {
+        if !self.rest().starts_with('r') {
+            return None;
+        }
+        let hashes = self.rest()[1..].find(|c| c != '#')?;
+        if !self.rest()[1 + hashes..].starts_with('"') {
+            return None;
+        }
+        let terminator = format!("\"{}", "#".repeat(hashes));
+        self.pos += hashes + 2;
+        Some(self.eat_until(&terminator))
+    }
+
+    /// Lexes a block comment, splitting it at line breaks.
+    fn block_comment(&mut self, start: usize) -> Span {
+        let end = self.eat_until("*/");
+        let mut offset = start;
+        for line in self.spec[start..end].split('\n') {
+            if !line.trim().is_empty() {
+                self.pending.push(Span { start: offset, end: offset + line.trim_end_matches('\r').len() });
+            }
+            offset += line.len() + 1;
+        }
+        self.pending.reverse();
+        self.pending.pop().expect("a block comment is not empty")
+    }
+
+    fn operator(&self) -> Option<usize> {
+        const OPERATORS: [&str; 30] = [
+            ":=", "**", "&&", "||", "<<", ">>", "<=", ">=", "!=", "==", "+", "-", "*", "/", "%", "&", "|", "^", "!",
+            "~", "<", ">", "=", "@", "¬", "∧", "∨", "≤", "≥", "≠",
+        ];
+        OPERATORS.iter().find(|op| self.rest().starts_with(*op)).map(|op| op.len())
+    }
+}
+
+impl<'a> Iterator for Lexer<'a> {
+    type Item = (TokenKind, Span);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(span) = self.pending.pop() {
+            return Some((TokenKind::Comment, span));
+        }
+        loop {
+            self.eat_while(char::is_whitespace);
+            let start = self.pos;
+            let rest = self.rest();
+            let first = rest.chars().next()?;
+            let (kind, end) = if rest.starts_with("//") {
+                (TokenKind::Comment, self.eat_while(|c| c != '\n'))
+            } else if rest.starts_with("/*") {
+                return Some((TokenKind::Comment, self.block_comment(start)));
+            } else if rest.starts_with("#[") {
+                (TokenKind::Attribute, self.eat_until("]"))
+            } else if first == '"' {
+                (TokenKind::Literal, self.string())
+            } else if let Some(end) = self.raw_string() {
+                (TokenKind::Literal, end)
+            } else if first.is_ascii_digit() {
+                (TokenKind::Literal, self.number())
+            } else if first.is_ascii_alphabetic() || first == '_' {
+                let mut end = self.eat_while(|c| c.is_ascii_alphanumeric() || c == '_');
+                // paths such as `math::sqrt` are a single name
+                while self.rest().starts_with("::") && self.rest()[2..].starts_with(|c: char| c.is_ascii_alphabetic()) {
+                    self.pos += 2;
+                    end = self.eat_while(|c| c.is_ascii_alphanumeric() || c == '_');
+                }
+                let name = &self.spec[start..end];
+                let kind = match name {
+                    "true" | "false" => TokenKind::Literal,
+                    "and" | "or" => TokenKind::Operator,
+                    _ if KEYWORDS.contains(&name) => TokenKind::Keyword,
+                    _ => TokenKind::Identifier,
+                };
+                (kind, end)
+            } else if first == '⊤' || first == '⊥' {
+                self.pos += first.len_utf8();
+                (TokenKind::Literal, self.pos)
+            } else if let Some(len) = self.operator() {
+                self.pos += len;
+                (TokenKind::Operator, self.pos)
+            } else {
+                // punctuation
+                self.pos += first.len_utf8();
+                continue;
+            };
+            return Some((kind, Span { start, end }));
+        }
+    }
+}
+
+/// Computes lines and columns of increasing byte offsets.
+struct Lines<'a> {
+    spec: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Lines<'a> {
+    fn new(spec: &'a str) -> Lines<'a> {
+        Lines { spec, offset: 0, line: 1, column: 1 }
+    }
+
+    fn position(&mut self, offset: usize) -> (usize, usize) {
+        for c in self.spec[self.offset..offset].chars() {
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+        }
+        self.offset = offset;
+        (self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the text and kind of each token.
+    fn tokens(spec: &str) -> Vec<(&str, TokenKind)> {
+        classify(spec, FrontendConfig::default()).into_iter().map(|t| (&spec[t.start..t.end], t.kind)).collect()
+    }
+
+    #[test]
+    fn resolved_names() {
+        use TokenKind::*;
+        let spec = "input a: Int32\noutput b(p: Int32) @ a := a + p\noutput c @ 1Hz := b(a.hold().defaults(to: 0)).hold().defaults(to: 0.5)\n";
+        assert_eq!(
+            tokens(spec),
+            vec![
+                ("input", Keyword),
+                ("a", Stream),
+                ("Int32", Type),
+                ("output", Keyword),
+                ("b", Stream),
+                ("p", Parameter),
+                ("Int32", Type),
+                ("@", Operator),
+                ("a", Stream),
+                (":=", Operator),
+                ("a", Stream),
+                ("+", Operator),
+                ("p", Parameter),
+                ("output", Keyword),
+                ("c", Stream),
+                ("@", Operator),
+                ("1Hz", Literal),
+                (":=", Operator),
+                ("b", Stream),
+                ("a", Stream),
+                ("hold", Function),
+                ("defaults", Function),
+                ("to", Identifier),
+                ("0", Literal),
+                ("hold", Function),
+                ("defaults", Function),
+                ("to", Identifier),
+                ("0.5", Literal),
+            ]
+        );
+    }
+
+    #[test]
+    fn comments_literals_and_attributes() {
+        use TokenKind::*;
+        let spec = "// altitude\n/* multi\n   line */ import math\n#[allow(W0201)]\ntrigger sqrt(x) ≥ 1e3 ∧ true \"too \\\"high\\\"\"";
+        assert_eq!(
+            tokens(spec),
+            vec![
+                ("// altitude", Comment),
+                ("/* multi", Comment),
+                ("   line */", Comment),
+                ("import", Keyword),
+                ("math", Identifier),
+                ("#[allow(W0201)]", Attribute),
+                ("trigger", Keyword),
+                ("sqrt", Function),
+                ("x", Identifier),
+                ("≥", Operator),
+                ("1e3", Literal),
+                ("∧", Operator),
+                ("true", Literal),
+                ("\"too \\\"high\\\"\"", Literal),
+            ]
+        );
+    }
+
+    #[test]
+    fn unicode_units() {
+        use TokenKind::*;
+        let spec = "output d @ 1μHz := 10μs";
+        assert_eq!(
+            tokens(spec),
+            vec![
+                ("output", Keyword),
+                ("d", Stream),
+                ("@", Operator),
+                ("1μHz", Literal),
+                (":=", Operator),
+                ("10μs", Literal)
+            ]
+        );
+    }
+
+    #[test]
+    fn positions() {
+        let spec = "input a: Int32\n/* ä */ output b := a";
+        let tokens = classify(spec, FrontendConfig::default());
+        let positions: Vec<(usize, usize)> = tokens.iter().map(|t| (t.line, t.column)).collect();
+        assert_eq!(positions, vec![(1, 1), (1, 7), (1, 10), (2, 1), (2, 9), (2, 16), (2, 18), (2, 21)]);
+    }
+}
@@ -0,0 +1,272 @@
+//! This module provides the information shown when hovering over a position in a specification, see `hover`.
+
+use crate::analysis::naming::Declaration;
+use crate::analysis::{self, Report};
+use crate::ast::{Expression, ExpressionKind, RTLolaAst};
+use crate::diagnostics::DiagnosticCollector;
+use crate::ir::lowering::Lowering;
+use crate::ir::{Activation, MemorizationBound, RTLolaIR, StreamReference};
+use crate::parse::{SourceMapper, Span};
+use crate::reporting::Handler;
+use crate::stdlib::STANDARD_LIBRARY;
+use crate::FrontendConfig;
+use std::fmt::{Display, Formatter, Result};
+use std::path::PathBuf;
+use uom::si::frequency::hertz;
+
+/// The information about a declaration or expression of a specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hover {
+    /// The byte offset of the start of the hovered name or expression.
+    pub start: usize,
+    /// The byte offset of the end of the hovered name or expression, exclusive.
+    pub end: usize,
+    /// What is hovered, e.g., `output b` or `expression a + 1`.
+    pub subject: String,
+    /// The inferred value type, e.g., `Int32`.
+    pub ty: String,
+    /// When the stream or expression is evaluated, e.g., `periodic at 1 Hz`; constants and parameters have none.
+    pub pacing: Option<String>,
+    /// How many values of the stream are stored; only streams have one.
+    pub memory_bound: Option<String>,
+}
+
+impl Display for Hover {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{}: {}", self.subject, self.ty)?;
+        if let Some(pacing) = &self.pacing {
+            write!(f, "\npacing: {}", pacing)?;
+        }
+        if let Some(memory_bound) = &self.memory_bound {
+            write!(f, "\nmemory: {}", memory_bound)?;
+        }
+        Ok(())
+    }
+}
+
+pub(crate) fn hover(spec: &str, offset: usize, config: FrontendConfig) -> Option<Hover> {
+    let handler = Handler::with_emitter(SourceMapper::new(PathBuf::new(), spec), Box::new(DiagnosticCollector::new()));
+    let ast = crate::parse::parse(spec, &handler, config).ok()?;
+    let report = analysis::analyze(&ast, &handler, config, &STANDARD_LIBRARY).ok()?;
+    let ir = Lowering::new(&ast, &report).lower();
+    let info = Info { spec, ast: &ast, report: &report, ir: &ir };
+    info.declaration(offset).or_else(|| info.expression(offset))
+}
+
+struct Info<'a> {
+    spec: &'a str,
+    ast: &'a RTLolaAst,
+    report: &'a Report,
+    ir: &'a RTLolaIR,
+}
+
+impl<'a> Info<'a> {
+    /// The hover of the declaration whose name contains the offset.
+    fn declaration(&self, offset: usize) -> Option<Hover> {
+        let contains = |span: Span| span.start <= offset && offset < span.end;
+        if let Some(constant) = self.ast.constants.iter().find(|c| contains(c.name.span)) {
+            return self.constant(constant.id, &constant.name.name, constant.name.span);
+        }
+        for (ix, input) in self.ast.inputs.iter().enumerate() {
+            if contains(input.name.span) {
+                return Some(self.stream(StreamReference::InRef(ix), input.name.span));
+            }
+            if let Some(param) = input.params.iter().find(|p| contains(p.name.span)) {
+                return self.parameter(param.id, &param.name.name, param.name.span);
+            }
+        }
+        for (ix, output) in self.ast.outputs.iter().enumerate() {
+            if contains(output.name.span) {
+                return Some(self.stream(StreamReference::OutRef(ix), output.name.span));
+            }
+            if let Some(param) = output.params.iter().find(|p| contains(p.name.span)) {
+                return self.parameter(param.id, &param.name.name, param.name.span);
+            }
+        }
+        for (trigger, ir) in self.ast.trigger.iter().zip(&self.ir.triggers) {
+            if let Some(name) = trigger.name.as_ref().filter(|n| contains(n.span)) {
+                let mut hover = self.stream(ir.reference, name.span);
+                hover.subject = format!("trigger {}", name.name);
+                return Some(hover);
+            }
+        }
+        None
+    }
+
+    /// The hover of the innermost expression containing the offset, together with the pacing of its stream.
+    fn expression(&self, offset: usize) -> Option<Hover> {
+        let outputs = self.ast.outputs.iter().enumerate().map(|(ix, o)| (StreamReference::OutRef(ix), &o.expression));
+        let triggers = self.ast.trigger.iter().zip(&self.ir.triggers).map(|(t, ir)| (ir.reference, &t.expression));
+        let (reference, expr) = outputs
+            .chain(triggers)
+            .flat_map(|(reference, expr)| expr.iter().map(move |e| (reference, e)))
+            .filter(|(_, e)| e.span.start <= offset && offset < e.span.end)
+            .filter(|(_, e)| self.report.type_table.find_value_type(e.id).is_some())
+            .min_by_key(|(_, e)| e.span.end - e.span.start)?;
+        if let ExpressionKind::Ident(name) = &expr.kind {
+            if let Some(hover) = self.name(expr, &name.name) {
+                return Some(hover);
+            }
+        }
+        Some(Hover {
+            start: expr.span.start,
+            end: expr.span.end,
+            subject: format!("expression {}", &self.spec[expr.span.start..expr.span.end]),
+            ty: self.report.type_table.get_value_type(expr.id).to_string(),
+            pacing: Some(self.pacing(reference)),
+            memory_bound: None,
+        })
+    }
+
+    /// The hover of a name in an expression, i.e., of the declaration it refers to.
+    fn name(&self, expr: &Expression, name: &str) -> Option<Hover> {
+        match self.report.declaration_table.get(&expr.id)? {
+            Declaration::Const(constant) => self.constant(constant.id, name, expr.span),
+            Declaration::Param(param) => self.parameter(param.id, name, expr.span),
+            Declaration::Let(binding) => self.binding(binding.expr.id, name, expr.span),
+            Declaration::In(input) => {
+                let ix = self.ast.inputs.iter().position(|i| i.id == input.id)?;
+                Some(self.stream(StreamReference::InRef(ix), expr.span))
+            }
+            Declaration::Out(output) => {
+                let ix = self.ast.outputs.iter().position(|o| o.id == output.id)?;
+                Some(self.stream(StreamReference::OutRef(ix), expr.span))
+            }
+            Declaration::ParamOut(_) | Declaration::Type(_) | Declaration::Func(_) => None,
+        }
+    }
+
+    fn constant(&self, id: crate::parse::NodeId, name: &str, span: Span) -> Option<Hover> {
+        Some(Hover {
+            start: span.start,
+            end: span.end,
+            subject: format!("constant {}", name),
+            ty: self.report.type_table.find_value_type(id)?.to_string(),
+            pacing: None,
+            memory_bound: None,
+        })
+    }
+
+    fn parameter(&self, id: crate::parse::NodeId, name: &str, span: Span) -> Option<Hover> {
+        Some(Hover {
+            start: span.start,
+            end: span.end,
+            subject: format!("parameter {}", name),
+            ty: self.report.type_table.find_value_type(id)?.to_string(),
+            pacing: None,
+            memory_bound: None,
+        })
+    }
+
+    fn binding(&self, id: crate::parse::NodeId, name: &str, span: Span) -> Option<Hover> {
+        Some(Hover {
+            start: span.start,
+            end: span.end,
+            subject: format!("let-bound {}", name),
+            ty: self.report.type_table.find_value_type(id)?.to_string(),
+            pacing: None,
+            memory_bound: None,
+        })
+    }
+
+    fn stream(&self, reference: StreamReference, span: Span) -> Hover {
+        let (subject, ty, memory_bound) = match reference {
+            StreamReference::InRef(_) => {
+                let input = self.ir.get_in(reference);
+                (format!("input {}", input.name), &input.ty, input.memory_bound)
+            }
+            StreamReference::OutRef(_) => {
+                let output = self.ir.get_out(reference);
+                (format!("output {}", output.name), &output.ty, output.memory_bound)
+            }
+        };
+        let memory_bound = match memory_bound {
+            MemorizationBound::Unbounded => String::from("unbounded"),
+            MemorizationBound::Bounded(1) => String::from("1 value"),
+            MemorizationBound::Bounded(n) => format!("{} values", n),
+        };
+        Hover {
+            start: span.start,
+            end: span.end,
+            subject,
+            ty: ty.to_string(),
+            pacing: Some(self.pacing(reference)),
+            memory_bound: Some(memory_bound),
+        }
+    }
+
+    /// Describes when a stream is evaluated, e.g., `event-based on a & b`.
+    fn pacing(&self, reference: StreamReference) -> String {
+        if let Some(periodic) = self.ir.time_driven.iter().find(|s| s.reference == reference) {
+            let frequency = periodic.frequency.into_format_args(hertz, uom::fmt::DisplayStyle::Abbreviation);
+            return format!("periodic at {}", frequency);
+        }
+        let activation = match reference {
+            StreamReference::InRef(_) => None,
+            StreamReference::OutRef(_) => self.ir.get_out(reference).ac.as_ref(),
+        };
+        match activation {
+            None | Some(Activation::True) => String::from("event-based"),
+            Some(activation) => format!("event-based on {}", self.activation(activation, false)),
+        }
+    }
+
+    fn activation(&self, activation: &Activation<StreamReference>, nested: bool) -> String {
+        let (operands, separator) = match activation {
+            Activation::Stream(reference @ StreamReference::InRef(_)) => {
+                return self.ir.get_in(*reference).name.clone()
+            }
+            Activation::Stream(reference @ StreamReference::OutRef(_)) => {
+                return self.ir.get_out(*reference).name.clone()
+            }
+            Activation::True => return String::from("true"),
+            Activation::Conjunction(operands) => (operands, " & "),
+            Activation::Disjunction(operands) => (operands, " | "),
+        };
+        let joined = operands.iter().map(|a| self.activation(a, true)).collect::<Vec<_>>().join(separator);
+        if nested && operands.len() > 1 {
+            format!("({})", joined)
+        } else {
+            joined
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hover(spec: &str, hovered: &str) -> Hover {
+        let offset = spec.find(hovered).expect("hovered text is part of the specification");
+        super::hover(spec, offset, FrontendConfig::default()).expect("there is a hover")
+    }
+
+    #[test]
+    fn streams() {
+        let spec = "input a: Int32\ninput c: Int32\noutput b @ 2Hz := a.hold().defaults(to: 0)\noutput d := a + c.offset(by: -2).defaults(to: 0)";
+        let b = hover(spec, "b @");
+        assert_eq!((b.start, b.end), (37, 38));
+        assert_eq!(b.to_string(), "output b: Int32\npacing: periodic at 2 Hz\nmemory: 1 value");
+        let c = hover(spec, "c.offset");
+        assert_eq!(c.to_string(), "input c: Int32\npacing: event-based\nmemory: 3 values");
+        let d = hover(spec, "d :=");
+        assert_eq!(d.pacing.as_deref(), Some("event-based on a & c"));
+    }
+
+    #[test]
+    fn expressions() {
+        let spec = "constant limit: Float64 := 5.0\ninput a: Float64\noutput b @ a := a > limit";
+        let comparison = hover(spec, "> limit");
+        assert_eq!(comparison.to_string(), "expression a > limit: Bool\npacing: event-based on a");
+        assert_eq!(hover(spec, "limit").to_string(), "constant limit: Float64");
+        let access = super::hover(spec, spec.rfind("limit").unwrap(), FrontendConfig::default()).unwrap();
+        assert_eq!((access.start, access.end, access.to_string()), (68, 73, String::from("constant limit: Float64")));
+    }
+
+    #[test]
+    fn invalid_specifications() {
+        assert_eq!(super::hover("input a: Int32\noutput b := c", 27, FrontendConfig::default()), None);
+        assert_eq!(super::hover("input a: Int32\noutput b :=", 8, FrontendConfig::default()), None);
+        assert_eq!(super::hover("input a: Int32", 2, FrontendConfig::default()), None);
+    }
+}
@@ -2,14 +2,17 @@
 This module describes the intermediate representation of a specification.
 */
 
+mod arena;
 pub(crate) mod lowering;
+mod merge;
 mod print;
 mod schedule;
 
 pub use crate::ast::StreamAccessKind;
 pub use crate::ast::WindowOperation;
+pub use crate::ir::arena::{ExprId, ExpressionArena, Node, NodeKind};
 pub use crate::ir::schedule::{Deadline, Schedule};
-pub use crate::ty::{Activation, FloatTy, IntTy, UIntTy, ValueTy}; // Re-export needed for IR
+pub use crate::ty::{Activation, FloatTy, IntTy, Number, UIntTy, ValueTy}; // Re-export needed for IR
 
 use std::time::Duration;
 use uom::si::rational64::Frequency as UOM_Frequency;
@@ -31,10 +34,17 @@ pub struct RTLolaIR {
     pub sliding_windows: Vec<SlidingWindow>,
     /// A collection of triggers
     pub triggers: Vec<Trigger>,
+    /// The expressions of all output streams, where identical sub-expressions are shared.
+    /// The streams keep their expression trees in `OutputStream::expr` as well, so the arena adds to the size of the IR.
+    pub expressions: ExpressionArena,
+    /// The constants whose values can be overridden when the monitor starts, see `RTLolaIR::configure`.
+    pub constants: Vec<ConfigurableConstant>,
+    /// The names of the specifications merged into this one, see `RTLolaIR::merge`; empty for a single specification.
+    pub specifications: Vec<String>,
 }
 
 /// Represents a value type. Stream types are no longer relevant.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Type {
     /// A binary type
     Bool,
@@ -50,6 +60,10 @@ pub enum Type {
     Bytes,
     /// A n-ary tuples where n is the length of the contained vector.
     Tuple(Vec<Type>),
+    /// An array of the given number of values of the same type, its values are represented like tuples.
+    Array(Box<Type>, usize),
+    /// An enumeration with its name and the names of its variants, its values are represented by the index of the variant.
+    Enum(String, Vec<String>),
     /// An optional value type, e.g., resulting from accessing a stream with offset -1
     Option(Box<Type>),
     /// A type describing a function containing its argument types and return type. Resolve ambiguities in polymorphic functions and operations.
@@ -65,7 +79,11 @@ impl From<&ValueTy> for Type {
             ValueTy::Float(f) => Type::Float(*f),
             ValueTy::String => Type::String,
             ValueTy::Bytes => Type::Bytes,
+            // durations are represented by their length in seconds
+            ValueTy::Duration => Type::Float(FloatTy::F64),
             ValueTy::Tuple(t) => Type::Tuple(t.iter().map(|e| e.into()).collect()),
+            ValueTy::Array(t, size) => Type::Array(Box::new(t.as_ref().into()), *size),
+            ValueTy::Enum(name, variants) => Type::Enum(name.clone(), variants.clone()),
             ValueTy::Option(o) => Type::Option(Box::new(o.as_ref().into())),
             _ => unreachable!("cannot lower `ValueTy` {}", ty),
         }
@@ -127,6 +145,8 @@ pub struct InputStream {
     pub memory_bound: MemorizationBound,
     /// The reference pointing to this stream.
     pub reference: StreamReference,
+    /// The annotations of the stream in the specification.
+    pub annotations: Vec<Annotation>,
 }
 
 /// Represents an output stream in an RTLola specification.
@@ -136,8 +156,10 @@ pub struct OutputStream {
     pub name: String,
     /// The type of the stream.
     pub ty: Type,
-    /// The stream expression
+    /// The stream expression, also interned in `RTLolaIR::expressions` as `expr_id`
     pub expr: Expression,
+    /// The stream expression in `RTLolaIR::expressions`.
+    pub expr_id: ExprId,
     /// The input streams on which this stream depends.
     pub input_dependencies: Vec<StreamReference>,
     /// The output streams on which this stream depends.
@@ -150,10 +172,54 @@ pub struct OutputStream {
     pub memory_bound: MemorizationBound,
     /// Indicates in which evaluation layer the stream is.  
     pub layer: u32,
+    /// The number of its own activations by which the evaluation is deferred, because the stream (transitively) accesses future values.
+    /// The lowering rewrites the accesses of the expression relative to the deferred position, so they only refer to past and current values.
+    pub look_ahead: u32,
     /// The reference pointing to this stream.
     pub reference: StreamReference,
     /// The activation condition, which indicates when this stream needs to be evaluated.  Will be empty if the stream has a fixed frequency.
     pub ac: Option<Activation<StreamReference>>,
+    /// The parameters of a parameterized stream, empty for other streams.
+    pub params: Vec<Parameter>,
+    /// The template of a parameterized stream, which spawns, extends, and closes its instances.  Will be empty if the stream has no parameters.
+    pub template: Option<InstanceTemplate>,
+    /// The annotations of the stream in the specification, empty for triggers and hidden streams.
+    pub annotations: Vec<Annotation>,
+}
+
+/// Represents a parameter of a parameterized output stream.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Parameter {
+    /// The name of the parameter.
+    pub name: String,
+    /// The type of the parameter.
+    pub ty: Type,
+}
+
+/// The template of a parameterized output stream, whose clauses are evaluated whenever the stream is activated.
+/// First, the instance given by `invoke` is spawned unless it exists, then each instance whose `extend` condition holds computes a new value,
+/// and finally the instances whose `terminate` condition holds are closed.
+#[derive(Debug, PartialEq, Clone, Default)]
+pub struct InstanceTemplate {
+    /// The parameters of the instance to spawn, a tuple if the stream has several parameters.  No instance is spawned if empty.
+    pub invoke: Option<Expression>,
+    /// The condition for spawning the instance, e.g., `!c` for `invoke p unless c`.
+    pub invoke_condition: Option<Expression>,
+    /// The condition for evaluating an instance, which may access its parameters.  All instances are evaluated if empty.
+    pub extend: Option<Expression>,
+    /// The condition for closing an instance after its evaluation, which may access its parameters.  No instance is closed if empty.
+    pub terminate: Option<Expression>,
+}
+
+impl InstanceTemplate {
+    /// Returns the expressions of the clauses present in the template.
+    pub fn clauses(&self) -> impl Iterator<Item = &Expression> {
+        self.invoke.iter().chain(&self.invoke_condition).chain(&self.extend).chain(&self.terminate)
+    }
+
+    pub(crate) fn clauses_mut(&mut self) -> impl Iterator<Item = &mut Expression> {
+        self.invoke.iter_mut().chain(&mut self.invoke_condition).chain(&mut self.extend).chain(&mut self.terminate)
+    }
 }
 
 /// Wrapper for output streams providing additional information specific to timedriven streams.
@@ -185,6 +251,49 @@ pub struct Trigger {
     pub reference: StreamReference,
     /// The index of the trigger.
     pub trigger_idx: usize,
+    /// The escalation of the trigger, if it only notifies after a number of violations.
+    /// The message of an escalating trigger may contain `{count}`, the number of violations within the duration.
+    pub escalation: Option<Escalation>,
+    /// Whether the trigger reports the violation of an environment assumption rather than of a property.
+    /// Its stream is the negated expression of the `assume` declaration.
+    pub assumption: bool,
+    /// The index of the specification of the trigger in `RTLolaIR::specifications` if specifications are merged, 0 otherwise.
+    pub specification: usize,
+    /// The streams whose latest values replace the placeholders `{NAME}` in the message, see `Trigger::interpolate`.
+    pub placeholders: Vec<(String, StreamReference)>,
+    /// The annotations of the trigger in the specification.
+    pub annotations: Vec<Annotation>,
+}
+
+impl Trigger {
+    /// Returns the severity of the trigger given by the annotation `#[severity(...)]`, i.e., `info`, `warning`, or `error`.
+    pub fn severity(&self) -> Option<&str> {
+        self.annotations.iter().find(|a| a.name == "severity").map(|a| a.args[0].as_str())
+    }
+
+    /// Returns the message with each placeholder `{NAME}` replaced by `value(NAME)`, unless it is `None`,
+    /// and the escaped braces `{{` and `}}` replaced by single braces.
+    pub fn interpolate(&self, value: impl FnMut(&str) -> Option<String>) -> String {
+        crate::ast::interpolate(&self.message, value)
+    }
+}
+
+/// The escalation of a trigger, which only notifies once `after` violations occurred within the duration `within`.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Escalation {
+    /// The number of violations.
+    pub after: usize,
+    /// The duration in which the violations have to occur, including its start.
+    pub within: Duration,
+}
+
+/// An annotation of a stream or trigger, e.g., `#[severity(error)]`, which the frontend validated.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Annotation {
+    /// The name of the annotation.
+    pub name: String,
+    /// The arguments of the annotation, empty if it has none.
+    pub args: Vec<String>,
 }
 
 /// Represents an expression.
@@ -201,6 +310,9 @@ pub struct Expression {
 pub enum ExpressionKind {
     /// Loading a constant
     LoadConstant(Constant),
+    /// Loading a configurable constant, referring to `RTLolaIR::constants`
+    /// Replaced by `LoadConstant` in `RTLolaIR::configure`, so it never needs to be evaluated.
+    ConfigurableConstant(usize),
     /// Applying arithmetic or logic operation and its monomorphic type
     /// Arguments never need to be coerced, @see `Expression::Convert`.
     /// Unary: 1st argument -> operand
@@ -219,6 +331,8 @@ pub enum ExpressionKind {
     StreamAccess(StreamReference, StreamAccessKind),
     /// A window expression over a duration
     WindowLookup(WindowReference),
+    /// The time of the current evaluation in seconds, only produced by lowering builtins like `debounce`
+    Now,
     /// An if-then-else expression
     Ite {
         #[allow(missing_docs)]
@@ -228,9 +342,9 @@ pub enum ExpressionKind {
         #[allow(missing_docs)]
         alternative: Box<Expression>,
     },
-    /// A tuple expression
+    /// A tuple expression, also used for array literals
     Tuple(Vec<Expression>),
-    /// Represents an access to a specific tuple element.  The second argument indicates the index of the accessed element while the first produces the accessed tuple.
+    /// Represents an access to a specific tuple or array element.  The second argument indicates the index of the accessed element while the first produces the accessed tuple.
     TupleAccess(Box<Expression>, usize),
     /// A function call with its monomorphic type
     /// Argumentes never need to be coerced, @see `Expression::Convert`.
@@ -244,6 +358,15 @@ pub enum ExpressionKind {
         /// The expression that produces a value of type `from` which should be converted to `to`.
         expr: Box<Expression>,
     },
+    /// A checked cast of a number, which has no value if `to` cannot represent the number, @see `Number`
+    Cast {
+        /// The original type
+        from: Type,
+        /// The target type
+        to: Type,
+        /// The expression that produces a value of type `from` which should be cast to `to`.
+        expr: Box<Expression>,
+    },
     /// Transforms an optional value into a "normal" one
     Default {
         /// The expression that results in an optional value.
@@ -251,10 +374,16 @@ pub enum ExpressionKind {
         /// An infallible expression providing a default value of `expr` evaluates to `None`.
         default: Box<Expression>,
     },
+    /// Loading a parameter of the evaluated instance, referring to `OutputStream::params`
+    ParameterAccess(usize),
+    /// Accessing the latest value of an instance of a parameterized stream, which has no value if the instance does not exist
+    /// Arguments -> the parameters of the instance
+    InstanceAccess(StreamReference, Vec<Expression>),
 }
 
 /// Represents a constant value of a certain kind.
-#[derive(Debug, PartialEq, Clone)]
+/// Floats are equal if their bits are, see the implementation of `PartialEq` in the `arena` module.
+#[derive(Debug, Clone)]
 pub enum Constant {
     #[allow(missing_docs)]
     Str(String),
@@ -268,6 +397,31 @@ pub enum Constant {
     Float(f64),
 }
 
+/// A constant declared `configurable`, whose value can be overridden without re-running the frontend.
+#[derive(Debug, PartialEq, Clone)]
+pub struct ConfigurableConstant {
+    /// The name of the constant.
+    pub name: String,
+    /// The type of the constant.
+    pub ty: Type,
+    /// The value of the constant, initially the one in the specification.
+    pub value: Constant,
+}
+
+impl ConfigurableConstant {
+    /// Parses a value of the type of the constant, or returns `None` if the value does not have the type.
+    fn parse(&self, value: &str) -> Option<Constant> {
+        match &self.ty {
+            Type::Bool => value.parse().ok().map(Constant::Bool),
+            Type::UInt(_) => value.parse().ok().map(Constant::UInt),
+            Type::Int(_) => value.parse().ok().map(Constant::Int),
+            Type::Float(_) => value.parse().ok().filter(|f: &f64| f.is_finite()).map(Constant::Float),
+            Type::String => Some(Constant::Str(value.to_string())),
+            _ => None,
+        }
+    }
+}
+
 /// Contains information regarding the dependency between two streams which occurs due to a lookup expression.
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Dependency {
@@ -278,7 +432,7 @@ pub struct Dependency {
 }
 
 /// Offset used in the lookup expression
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Offset {
     /// A strictly positive discrete offset, e.g., `4`, or `42`
     FutureDiscreteOffset(u32),
@@ -291,7 +445,7 @@ pub enum Offset {
 }
 
 /// Contains all arithmetical and logical operations.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ArithLogOp {
     /// The `!` operator for logical inversion
     Not,
@@ -359,7 +513,7 @@ pub struct SlidingWindow {
 /////// Referencing Structures ///////
 
 /// Allows for referencing a window instance.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct WindowReference(usize);
 
 impl WindowReference {
@@ -515,6 +669,64 @@ impl Ord for Offset {
 }
 
 impl RTLolaIR {
+    /// Overrides the values of configurable constants, given as pairs of name and value, and replaces all accesses to configurable constants by their values.
+    /// Returns an error if there is no configurable constant with a name or a value does not have the type of the constant.
+    pub fn configure(&mut self, values: &[(String, String)]) -> Result<(), String> {
+        for (name, value) in values {
+            let constant = self
+                .constants
+                .iter_mut()
+                .find(|c| &c.name == name)
+                .ok_or_else(|| format!("there is no configurable constant `{}`", name))?;
+            constant.value = constant
+                .parse(value)
+                .ok_or_else(|| format!("`{}` is no value of type {} for constant `{}`", value, constant.ty, name))?;
+        }
+        fn substitute(expr: &mut Expression, constants: &[ConfigurableConstant]) {
+            use ExpressionKind::*;
+            match &mut expr.kind {
+                ConfigurableConstant(ix) => expr.kind = LoadConstant(constants[*ix].value.clone()),
+                ArithLog(_, args, _) | Tuple(args) | Function(_, args, _) => {
+                    args.iter_mut().for_each(|arg| substitute(arg, constants))
+                }
+                Ite { condition, consequence, alternative } => {
+                    substitute(condition, constants);
+                    substitute(consequence, constants);
+                    substitute(alternative, constants);
+                }
+                TupleAccess(expr, _) | Convert { expr, .. } | Cast { expr, .. } => substitute(expr, constants),
+                Default { expr, default } => {
+                    substitute(expr, constants);
+                    substitute(default, constants);
+                }
+                InstanceAccess(_, args) => args.iter_mut().for_each(|arg| substitute(arg, constants)),
+                LoadConstant(_)
+                | OffsetLookup { .. }
+                | StreamAccess(..)
+                | WindowLookup(_)
+                | Now
+                | ParameterAccess(_) => {}
+            }
+        }
+        let constants = &self.constants;
+        for output in &mut self.outputs {
+            substitute(&mut output.expr, constants);
+            if let Some(template) = &mut output.template {
+                template.clauses_mut().for_each(|clause| substitute(clause, constants));
+            }
+        }
+        self.intern_expressions();
+        Ok(())
+    }
+
+    /// Rebuilds `RTLolaIR::expressions` from the expressions of the output streams, so no stale sub-expressions remain.
+    fn intern_expressions(&mut self) {
+        self.expressions = ExpressionArena::default();
+        for output in &mut self.outputs {
+            output.expr_id = self.expressions.intern(&output.expr);
+        }
+    }
+
     /// Returns a `Vec` containing a reference for each input stream in the specification.
     pub fn input_refs(&self) -> Vec<InputReference> {
         (0..self.inputs.len()).collect()
@@ -673,6 +885,8 @@ impl Type {
                 let size = t.iter().map(|t| Type::size(t).unwrap().0).sum();
                 Some(ValSize(size))
             }
+            Type::Array(t, size) => Some(ValSize(Type::size(t).unwrap().0 * *size as u32)),
+            Type::Enum(_, _) => Some(ValSize(4)),
             Type::String | Type::Bytes => unimplemented!("Size of Strings not determined, yet."),
             Type::Function(_, _) => None,
         }
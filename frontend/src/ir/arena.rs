@@ -0,0 +1,288 @@
+//! This module interns the expressions of the IR, see `ExpressionArena`.
+//!
+//! Each distinct expression is stored once and referred to by an `ExprId`, so identical sub-expressions, e.g., the
+//! last value of a filter used several times in its expression, are shared within and across streams.
+
+use super::{
+    ArithLogOp, Constant, Expression, ExpressionKind, Offset, StreamAccessKind, StreamReference, Type, WindowReference,
+};
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Index;
+
+/// Allows for referencing an expression in an `ExpressionArena`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExprId(pub(crate) usize);
+
+impl ExprId {
+    /// Provides access to the index inside the reference.
+    pub fn idx(self) -> usize {
+        self.0
+    }
+}
+
+/// An interned expression, whose sub-expressions are referred to by their `ExprId`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node {
+    /// The kind of expression.
+    pub kind: NodeKind,
+    /// The type of the expression.
+    pub ty: Type,
+}
+
+/// The kinds of interned expressions, mirroring `ExpressionKind`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// Loading a constant
+    LoadConstant(Constant),
+    /// Loading a configurable constant, see `ExpressionKind::ConfigurableConstant`
+    ConfigurableConstant(usize),
+    /// Applying arithmetic or logic operation and its monomorphic type, see `ExpressionKind::ArithLog`
+    ArithLog(ArithLogOp, Vec<ExprId>, Type),
+    /// Accessing another stream with a potentially 0 offset
+    OffsetLookup {
+        /// The target of the lookup.
+        target: StreamReference,
+        /// The offset of the lookup.
+        offset: Offset,
+    },
+    /// Accessing another stream
+    StreamAccess(StreamReference, StreamAccessKind),
+    /// A window expression over a duration
+    WindowLookup(WindowReference),
+    /// The time of the current evaluation in seconds
+    Now,
+    /// An if-then-else expression
+    Ite {
+        #[allow(missing_docs)]
+        condition: ExprId,
+        #[allow(missing_docs)]
+        consequence: ExprId,
+        #[allow(missing_docs)]
+        alternative: ExprId,
+    },
+    /// A tuple expression
+    Tuple(Vec<ExprId>),
+    /// Represents an access to a specific tuple element
+    TupleAccess(ExprId, usize),
+    /// A function call with its monomorphic type
+    Function(String, Vec<ExprId>, Type),
+    /// Converting a value to a different type
+    Convert {
+        /// The original type
+        from: Type,
+        /// The target type
+        to: Type,
+        /// The expression that produces a value of type `from` which should be converted to `to`.
+        expr: ExprId,
+    },
+    /// A checked cast of a number, which has no value if `to` cannot represent the number
+    Cast {
+        /// The original type
+        from: Type,
+        /// The target type
+        to: Type,
+        /// The expression that produces a value of type `from` which should be cast to `to`.
+        expr: ExprId,
+    },
+    /// Transforms an optional value into a "normal" one
+    Default {
+        /// The expression that results in an optional value.
+        expr: ExprId,
+        /// An infallible expression providing a default value of `expr` evaluates to `None`.
+        default: ExprId,
+    },
+    /// Loading a parameter of the evaluated instance, see `ExpressionKind::ParameterAccess`
+    ParameterAccess(usize),
+    /// Accessing the latest value of an instance of a parameterized stream, see `ExpressionKind::InstanceAccess`
+    InstanceAccess(StreamReference, Vec<ExprId>),
+}
+
+/// A deduplicated store of expressions.
+/// Sub-expressions are interned before the expressions containing them, so their `ExprId` is smaller.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpressionArena {
+    nodes: Vec<Node>,
+    index: HashMap<Node, ExprId>,
+}
+
+impl ExpressionArena {
+    /// Interns the expression and its sub-expressions and returns the reference to it.
+    /// Interning an expression equal to an interned one returns the reference to the latter.
+    pub fn intern(&mut self, expr: &Expression) -> ExprId {
+        use ExpressionKind::*;
+        let kind = match &expr.kind {
+            LoadConstant(c) => NodeKind::LoadConstant(c.clone()),
+            ConfigurableConstant(ix) => NodeKind::ConfigurableConstant(*ix),
+            ArithLog(op, args, ty) => NodeKind::ArithLog(*op, self.intern_all(args), ty.clone()),
+            OffsetLookup { target, offset } => NodeKind::OffsetLookup { target: *target, offset: *offset },
+            StreamAccess(target, kind) => NodeKind::StreamAccess(*target, *kind),
+            WindowLookup(window) => NodeKind::WindowLookup(*window),
+            Now => NodeKind::Now,
+            Ite { condition, consequence, alternative } => NodeKind::Ite {
+                condition: self.intern(condition),
+                consequence: self.intern(consequence),
+                alternative: self.intern(alternative),
+            },
+            Tuple(entries) => NodeKind::Tuple(self.intern_all(entries)),
+            TupleAccess(expr, num) => NodeKind::TupleAccess(self.intern(expr), *num),
+            Function(name, args, ty) => NodeKind::Function(name.clone(), self.intern_all(args), ty.clone()),
+            Convert { from, to, expr } => {
+                NodeKind::Convert { from: from.clone(), to: to.clone(), expr: self.intern(expr) }
+            }
+            Cast { from, to, expr } => NodeKind::Cast { from: from.clone(), to: to.clone(), expr: self.intern(expr) },
+            Default { expr, default } => NodeKind::Default { expr: self.intern(expr), default: self.intern(default) },
+            ParameterAccess(ix) => NodeKind::ParameterAccess(*ix),
+            InstanceAccess(target, args) => NodeKind::InstanceAccess(*target, self.intern_all(args)),
+        };
+        let node = Node { kind, ty: expr.ty.clone() };
+        if let Some(&id) = self.index.get(&node) {
+            return id;
+        }
+        let id = ExprId(self.nodes.len());
+        self.nodes.push(node.clone());
+        self.index.insert(node, id);
+        id
+    }
+
+    fn intern_all(&mut self, exprs: &[Expression]) -> Vec<ExprId> {
+        exprs.iter().map(|expr| self.intern(expr)).collect()
+    }
+
+    /// Provides access to an interned expression.
+    pub fn get(&self, id: ExprId) -> &Node {
+        &self.nodes[id.0]
+    }
+
+    /// Returns the number of distinct expressions.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Indicates whether no expression is interned.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Rebuilds the expression tree of an interned expression.
+    pub fn expression(&self, id: ExprId) -> Expression {
+        let tree = |id: ExprId| Box::new(self.expression(id));
+        let trees = |ids: &[ExprId]| ids.iter().map(|id| self.expression(*id)).collect();
+        let node = self.get(id);
+        let kind = match &node.kind {
+            NodeKind::LoadConstant(c) => ExpressionKind::LoadConstant(c.clone()),
+            NodeKind::ConfigurableConstant(ix) => ExpressionKind::ConfigurableConstant(*ix),
+            NodeKind::ArithLog(op, args, ty) => ExpressionKind::ArithLog(*op, trees(args), ty.clone()),
+            NodeKind::OffsetLookup { target, offset } => {
+                ExpressionKind::OffsetLookup { target: *target, offset: *offset }
+            }
+            NodeKind::StreamAccess(target, kind) => ExpressionKind::StreamAccess(*target, *kind),
+            NodeKind::WindowLookup(window) => ExpressionKind::WindowLookup(*window),
+            NodeKind::Now => ExpressionKind::Now,
+            NodeKind::Ite { condition, consequence, alternative } => ExpressionKind::Ite {
+                condition: tree(*condition),
+                consequence: tree(*consequence),
+                alternative: tree(*alternative),
+            },
+            NodeKind::Tuple(entries) => ExpressionKind::Tuple(trees(entries)),
+            NodeKind::TupleAccess(expr, num) => ExpressionKind::TupleAccess(tree(*expr), *num),
+            NodeKind::Function(name, args, ty) => ExpressionKind::Function(name.clone(), trees(args), ty.clone()),
+            NodeKind::Convert { from, to, expr } => {
+                ExpressionKind::Convert { from: from.clone(), to: to.clone(), expr: tree(*expr) }
+            }
+            NodeKind::Cast { from, to, expr } => {
+                ExpressionKind::Cast { from: from.clone(), to: to.clone(), expr: tree(*expr) }
+            }
+            NodeKind::Default { expr, default } => {
+                ExpressionKind::Default { expr: tree(*expr), default: tree(*default) }
+            }
+            NodeKind::ParameterAccess(ix) => ExpressionKind::ParameterAccess(*ix),
+            NodeKind::InstanceAccess(target, args) => ExpressionKind::InstanceAccess(*target, trees(args)),
+        };
+        Expression::new(kind, node.ty.clone())
+    }
+}
+
+impl Index<ExprId> for ExpressionArena {
+    type Output = Node;
+
+    fn index(&self, id: ExprId) -> &Node {
+        self.get(id)
+    }
+}
+
+// Floats are compared by their bits, so interning keeps `0.0` and `-0.0` apart and equality is reflexive.
+impl PartialEq for Constant {
+    fn eq(&self, other: &Self) -> bool {
+        use Constant::*;
+        match (self, other) {
+            (Str(l), Str(r)) => l == r,
+            (Bool(l), Bool(r)) => l == r,
+            (UInt(l), UInt(r)) => l == r,
+            (Int(l), Int(r)) => l == r,
+            (Float(l), Float(r)) => l.to_bits() == r.to_bits(),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Constant {}
+
+impl Hash for Constant {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::mem::discriminant(self).hash(state);
+        match self {
+            Constant::Str(s) => s.hash(state),
+            Constant::Bool(b) => b.hash(state),
+            Constant::UInt(u) => u.hash(state),
+            Constant::Int(i) => i.hash(state),
+            Constant::Float(f) => f.to_bits().hash(state),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ty::FloatTy;
+    use crate::FrontendConfig;
+
+    fn ir(spec: &str) -> crate::ir::RTLolaIR {
+        crate::parse("stdin", spec, FrontendConfig::default()).unwrap_or_else(|e| panic!("spec is invalid: {}", e))
+    }
+
+    #[test]
+    fn identical_subexpressions_are_shared() {
+        let ir = ir("input a: Int64\noutput b := a + 1\noutput c := (a + 1) * (a + 1)\noutput d := a + 1");
+        let (b, c, d) = (ir.outputs[0].expr_id, ir.outputs[1].expr_id, ir.outputs[2].expr_id);
+        assert_eq!(b, d);
+        match &ir.expressions[c].kind {
+            NodeKind::ArithLog(ArithLogOp::Mul, args, _) => assert_eq!(args, &vec![b, b]),
+            kind => panic!("expected a multiplication, found {:?}", kind),
+        }
+        // `a`, `1`, `a + 1`, and `(a + 1) * (a + 1)`
+        assert_eq!(ir.expressions.len(), 4);
+    }
+
+    #[test]
+    fn expressions_are_rebuilt() {
+        let ir =
+            ir("input a: Int64\noutput b := if a > 0 then a.offset(by: -1).defaults(to: 0) else -a\ntrigger b > 5");
+        for output in &ir.outputs {
+            assert_eq!(ir.expressions.expression(output.expr_id), output.expr);
+        }
+        let mut arena = ExpressionArena::default();
+        let id = arena.intern(&ir.outputs[0].expr);
+        assert_eq!(arena.intern(&ir.outputs[0].expr), id);
+    }
+
+    #[test]
+    fn signed_zeros_are_distinct() {
+        let float = |f| Expression::new(ExpressionKind::LoadConstant(Constant::Float(f)), Type::Float(FloatTy::F64));
+        let mut arena = ExpressionArena::default();
+        let (positive, negative) = (arena.intern(&float(0.0)), arena.intern(&float(-0.0)));
+        assert_ne!(positive, negative);
+        assert_eq!(arena.intern(&float(-0.0)), negative);
+        assert_eq!(arena.expression(negative), float(-0.0));
+    }
+}
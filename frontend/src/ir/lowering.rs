@@ -20,8 +20,8 @@ use crate::analysis::graph_based_analysis::space_requirements::{
     SpaceRequirements as MemoryTable, TrackingRequirements,
 };
 use crate::analysis::{
-    graph_based_analysis::{ComputeStep, RequiredInputs, StorageRequirement, TrackingRequirement},
-    Report,
+    graph_based_analysis::{ComputeStep, LookAhead, RequiredInputs, StorageRequirement, TrackingRequirement},
+    ConstantTable, Report,
 };
 
 use num::{traits::Inv, Signed, ToPrimitive};
@@ -31,16 +31,27 @@ use uom::si::time::{nanosecond, second};
 
 type EvalTable = HashMap<NodeId, u32>;
 
+/// The builtins with state, which are lowered into hidden streams.
+const FILTERS: [&str; 4] = ["lowpass", "rate_limit", "debounce", "hysteresis"];
+
 pub(crate) struct Lowering<'a> {
     ast: &'a RTLolaAst,
     ref_lookup: HashMap<NodeId, StreamReference>,
     dt: &'a DeclarationTable,
     tt: &'a TypeTable,
+    ct: &'a ConstantTable,
     et: EvalTable,
     mt: &'a MemoryTable,
     tr: &'a TrackingRequirements,
     ir: RTLolaIR,
     ri: &'a RequiredInputs,
+    la: &'a LookAhead,
+    /// The stream whose expression is lowered, hidden streams of filters take over its pacing.
+    caller: Option<NodeId>,
+    /// The hidden streams accessed by the expression of the caller.
+    hidden: Vec<StreamReference>,
+    /// The evaluation layers from which on streams were moved up to make room for hidden streams.
+    layer_shifts: Vec<u32>,
 }
 
 impl<'a> Lowering<'a> {
@@ -52,6 +63,9 @@ impl<'a> Lowering<'a> {
             event_driven: Vec::new(),
             sliding_windows: Vec::new(),
             triggers: Vec::new(),
+            expressions: ir::ExpressionArena::default(),
+            constants: Vec::new(),
+            specifications: Vec::new(),
         };
 
         ir.inputs.reserve(ast.inputs.len());
@@ -62,20 +76,28 @@ impl<'a> Lowering<'a> {
             ref_lookup: Lowering::create_ref_lookup(&ast.inputs, &ast.outputs),
             dt: &analysis_result.declaration_table,
             tt: &analysis_result.type_table,
+            ct: &analysis_result.constants,
             et: Self::order_to_table(&analysis_result.graph_analysis_result.evaluation_order),
             mt: &analysis_result.graph_analysis_result.space_requirements,
             tr: &analysis_result.graph_analysis_result.tracking_requirements,
             ir,
             ri: &analysis_result.graph_analysis_result.input_dependencies,
+            la: &analysis_result.graph_analysis_result.look_ahead,
+            caller: None,
+            hidden: Vec::new(),
+            layer_shifts: Vec::new(),
         }
     }
 
     pub(crate) fn lower(mut self) -> RTLolaIR {
         self.lower_ast();
+        self.resolve_look_ahead();
+        self.intern_expressions();
         self.ir
     }
 
     fn lower_ast(&mut self) {
+        self.ast.constants.iter().filter(|c| c.configurable).for_each(|c| self.lower_constant(c));
         self.ast.inputs.iter().for_each(|i| self.lower_input(i));
         self.ast.outputs.iter().for_each(|o| self.lower_output_declaration(o));
         self.ast.outputs.iter().for_each(|o| self.lower_output_expression(o));
@@ -83,6 +105,91 @@ impl<'a> Lowering<'a> {
         self.ast.trigger.iter().for_each(|t| self.lower_trigger(t));
     }
 
+    fn lower_constant(&mut self, constant: &ast::Constant) {
+        let value = self.ct[&constant.id].clone();
+        let ty = self.lower_node_type(constant.id);
+        self.ir.constants.push(ir::ConfigurableConstant { name: constant.name.name.clone(), ty, value });
+    }
+
+    /// Interns the expressions of all output streams, sharing identical sub-expressions across streams.
+    fn intern_expressions(&mut self) {
+        let RTLolaIR { outputs, expressions, .. } = &mut self.ir;
+        for output in outputs {
+            output.expr_id = expressions.intern(&output.expr);
+        }
+    }
+
+    /// Rewrites the accesses of streams with a look-ahead relative to the positions they evaluate:
+    /// a stream with look-ahead `l` evaluates the position `l` activations back, so it accesses `s[k]` at the offset `k - l`,
+    /// relative to the latest position of `s` that is `s`'s own look-ahead back.
+    /// Afterwards, output expressions only access past and current values, and the accessed streams store enough values.
+    fn resolve_look_ahead(&mut self) {
+        fn rewrite(expr: &mut ir::Expression, look_ahead: &[i64], own: i64, bounds: &mut Vec<(StreamReference, u16)>) {
+            use ir::ExpressionKind::*;
+            let shift = |target: StreamReference| match target {
+                StreamReference::InRef(_) => -own,
+                StreamReference::OutRef(ix) => look_ahead[ix] - own,
+            };
+            let (target, offset) = match &mut expr.kind {
+                OffsetLookup { target, offset: ir::Offset::PastDiscreteOffset(k) } => {
+                    (*target, -i64::from(*k) + shift(*target))
+                }
+                OffsetLookup { target, offset: ir::Offset::FutureDiscreteOffset(k) } => {
+                    (*target, i64::from(*k) + shift(*target))
+                }
+                StreamAccess(target, StreamAccessKind::Sync) if shift(*target) != 0 => (*target, shift(*target)),
+                ArithLog(_, args, _) | Tuple(args) | Function(_, args, _) | InstanceAccess(_, args) => {
+                    args.iter_mut().for_each(|arg| rewrite(arg, look_ahead, own, bounds));
+                    return;
+                }
+                Ite { condition, consequence, alternative } => {
+                    rewrite(condition, look_ahead, own, bounds);
+                    rewrite(consequence, look_ahead, own, bounds);
+                    rewrite(alternative, look_ahead, own, bounds);
+                    return;
+                }
+                TupleAccess(expr, _) | Convert { expr, .. } | Cast { expr, .. } => {
+                    rewrite(expr, look_ahead, own, bounds);
+                    return;
+                }
+                Default { expr, default } => {
+                    rewrite(expr, look_ahead, own, bounds);
+                    rewrite(default, look_ahead, own, bounds);
+                    return;
+                }
+                LoadConstant(_)
+                | ConfigurableConstant(_)
+                | OffsetLookup { .. }
+                | StreamAccess(..)
+                | WindowLookup(_)
+                | Now
+                | ParameterAccess(_) => return,
+            };
+            debug_assert!(offset <= 0, "the look-ahead covers all future offsets");
+            let offset = offset.abs().to_u32().expect("offsets are bounded by i16");
+            expr.kind = OffsetLookup { target, offset: ir::Offset::PastDiscreteOffset(offset) };
+            bounds.push((target, offset as u16 + 1));
+        }
+
+        let look_ahead: Vec<i64> = self.ir.outputs.iter().map(|o| i64::from(o.look_ahead)).collect();
+        if look_ahead.iter().all(|l| *l == 0) {
+            return;
+        }
+        let mut bounds = Vec::new();
+        for output in &mut self.ir.outputs {
+            rewrite(&mut output.expr, &look_ahead, i64::from(output.look_ahead), &mut bounds);
+        }
+        for (target, required) in bounds {
+            let bound = match target {
+                StreamReference::InRef(_) => &mut self.ir.get_in_mut(target).memory_bound,
+                StreamReference::OutRef(_) => &mut self.ir.get_out_mut(target).memory_bound,
+            };
+            if let MemorizationBound::Bounded(b) = bound {
+                *b = (*b).max(required);
+            }
+        }
+    }
+
     /// Links streams to windows depending on them.
     /// Example:
     /// input in: Int8; output out Int8@5Hz := in.aggregate(5s, Σ)
@@ -123,6 +230,7 @@ impl<'a> Lowering<'a> {
             layer,
             memory_bound,
             reference,
+            annotations: Self::lower_annotations(&input.annotations),
         };
 
         let debug_clone = input.clone();
@@ -135,22 +243,44 @@ impl<'a> Lowering<'a> {
         );
     }
 
+    fn lower_annotations(annotations: &[ast::Annotation]) -> Vec<ir::Annotation> {
+        annotations
+            .iter()
+            .map(|a| ir::Annotation {
+                name: a.name.name.clone(),
+                args: a.args.iter().map(|arg| arg.name.clone()).collect(),
+            })
+            .collect()
+    }
+
     fn gather_dependent_inputs(&mut self, node_id: NodeId) -> Vec<StreamReference> {
         self.ri[&node_id].iter().map(|input_id| self.get_ref_for_stream(*input_id)).collect()
     }
 
     fn lower_trigger(&mut self, trigger: &ast::Trigger) {
-        let name = if let Some(msg) = trigger.message.as_ref() {
-            format!("trigger_{}", msg.clone().replace(" ", "_"))
+        let kind = if trigger.assumption { "assumption" } else { "trigger" };
+        let name = if let Some(name) = trigger.name.as_ref() {
+            name.name.clone()
+        } else if let Some(msg) = trigger.message.as_ref() {
+            format!("{}_{}", kind, msg.replace(char::is_whitespace, "_"))
         } else {
-            String::from("trigger")
+            String::from(kind)
         };
 
         let ty = ir::Type::Bool;
-        let expr = self.lower_stream_expression(&trigger.expression, &ty);
+        self.caller = Some(trigger.id);
+        let mut expr = self.lower_stream_expression(&trigger.expression, &ty);
+        self.caller = None;
+        if trigger.assumption {
+            // the stream of an assumption holds if it is violated
+            let fun_ty = ir::Type::Function(vec![ty.clone()], Box::new(ty.clone()));
+            expr =
+                ir::Expression::new(ir::ExpressionKind::ArithLog(ir::ArithLogOp::Not, vec![expr], fun_ty), ty.clone());
+        }
         let reference = StreamReference::OutRef(self.ir.outputs.len());
         let mut outgoing_dependencies = Vec::new();
         self.find_dependencies(&trigger.expression, &mut outgoing_dependencies);
+        outgoing_dependencies.extend(self.take_hidden_dependencies());
         let input_dependencies = self.gather_dependent_inputs(trigger.id);
         let ac = match self.check_time_driven(trigger.id, reference) {
             None => Some(self.tt.get_acti_cond(trigger.id).clone()),
@@ -160,20 +290,38 @@ impl<'a> Lowering<'a> {
             name,
             ty,
             expr,
+            // set when the expressions are interned
+            expr_id: ir::ExprId(0),
             dependent_streams: Vec::new(),
             dependent_windows: Vec::new(),
             memory_bound: MemorizationBound::Bounded(0),
             layer: self.get_layer(trigger.id),
+            look_ahead: self.la[&trigger.id],
             reference,
             outgoing_dependencies,
             input_dependencies,
             ac,
+            params: Vec::new(),
+            template: None,
+            annotations: Vec::new(),
         };
         self.ir.outputs.push(output);
         let trig = ir::Trigger {
             message: trigger.message.clone().unwrap_or_else(|| format!("{}", trigger.expression)),
             reference,
             trigger_idx: self.ir.triggers.len(),
+            escalation: trigger.escalation.as_ref().map(|escalation| ir::Escalation {
+                after: escalation.after.parse_numeric().expect("checked by type checker"),
+                within: Self::lower_literal_duration(&escalation.within),
+            }),
+            assumption: trigger.assumption,
+            specification: 0,
+            placeholders: trigger
+                .placeholders()
+                .into_iter()
+                .filter_map(|name| Some((name.to_string(), self.stream_named(name)?)))
+                .collect(),
+            annotations: Self::lower_annotations(&trigger.annotations),
         };
         match self.check_time_driven(trigger.id, reference) {
             None => self.ir.event_driven.push(EventDrivenStream { reference }),
@@ -182,6 +330,15 @@ impl<'a> Lowering<'a> {
         self.ir.triggers.push(trig);
     }
 
+    /// Returns the input or output stream with the name, except the streams of triggers.
+    fn stream_named(&self, name: &str) -> Option<StreamReference> {
+        let input = self.ir.inputs.iter().find(|i| i.name == name).map(|i| i.reference);
+        input.or_else(|| {
+            let trigger = |o: &&ir::OutputStream| self.ir.triggers.iter().any(|t| t.reference == o.reference);
+            self.ir.outputs.iter().filter(|o| !trigger(o)).find(|o| o.name == name).map(|o| o.reference)
+        })
+    }
+
     fn collect_tracking_info(&self, nid: NodeId, time_driven: Option<&TimeDrivenStream>) -> Vec<ir::Tracking> {
         let dependent = self.find_depending_streams(nid);
         assert!(
@@ -217,14 +374,25 @@ impl<'a> Lowering<'a> {
                 ir::ExpressionKind::LoadConstant(ir::Constant::Str(String::from("not yet initialized"))),
                 ir::Type::Bool,
             ),
+            // set when the expressions are interned
+            expr_id: ir::ExprId(0),
             outgoing_dependencies: Vec::new(),
             dependent_streams: trackings,
             dependent_windows: Vec::new(),
             memory_bound,
             layer,
+            look_ahead: self.la[&nid],
             reference,
             input_dependencies: Vec::new(),
             ac,
+            params: ast_output
+                .params
+                .iter()
+                .map(|p| ir::Parameter { name: p.name.name.clone(), ty: self.lower_node_type(p.id) })
+                .collect(),
+            // set when the expressions are lowered
+            template: None,
+            annotations: Self::lower_annotations(&ast_output.annotations),
         };
 
         let debug_clone = output.clone();
@@ -254,17 +422,53 @@ impl<'a> Lowering<'a> {
         outgoing_dependencies.into_iter().for_each(|dep| {
             dep_map.entry(dep.stream).or_insert_with(Vec::new).extend_from_slice(dep.offsets.as_slice())
         });
-        let outgoing_dependencies =
+        let mut outgoing_dependencies: Vec<ir::Dependency> =
             dep_map.into_iter().map(|(sr, offsets)| ir::Dependency { stream: sr, offsets }).collect();
 
         let output_type = self.lower_node_type(nid);
+        self.caller = Some(nid);
         let expr = self.lower_stream_expression(&ast_output.expression, &output_type);
+        let template = if ast_output.params.is_empty() { None } else { Some(self.lower_template(ast_output)) };
+        self.caller = None;
+        outgoing_dependencies.extend(self.take_hidden_dependencies());
         let output = self.ir.get_out_mut(reference);
 
         output.ty = output_type;
         output.input_dependencies = input_dependencies;
         output.outgoing_dependencies = outgoing_dependencies;
         output.expr = expr;
+        output.template = template;
+    }
+
+    /// Lowers the template of a parameterized output, where `invoke p unless c` spawns if `!c` holds.
+    /// The termination condition following the template, e.g., `close c`, is used if the template has none.
+    fn lower_template(&mut self, ast_output: &ast::Output) -> ir::InstanceTemplate {
+        let mut template = ir::InstanceTemplate::default();
+        let boolean = ir::Type::Bool;
+        let ast_template = ast_output.template_spec.as_ref();
+        if let Some(invoke) = ast_template.and_then(|t| t.inv.as_ref()) {
+            let ty = self.lower_node_type(invoke.target.id);
+            template.invoke = Some(self.lower_stream_expression(&invoke.target, &ty));
+            template.invoke_condition = invoke.condition.as_ref().map(|condition| {
+                let condition = self.lower_stream_expression(condition, &boolean);
+                if invoke.is_if {
+                    return condition;
+                }
+                let fun_ty = ir::Type::Function(vec![boolean.clone()], Box::new(boolean.clone()));
+                ir::Expression::new(
+                    ir::ExpressionKind::ArithLog(ir::ArithLogOp::Not, vec![condition], fun_ty),
+                    boolean.clone(),
+                )
+            });
+        }
+        if let Some(extend) = ast_template.and_then(|t| t.ext.as_ref()) {
+            template.extend = Some(self.lower_stream_expression(&extend.target, &boolean));
+        }
+        let terminate = ast_template.and_then(|t| t.ter.as_ref()).map(|t| &t.target);
+        if let Some(terminate) = terminate.or_else(|| ast_output.termination.as_ref()) {
+            template.terminate = Some(self.lower_stream_expression(terminate, &boolean));
+        }
+        template
     }
 
     /// Returns the flattened result of calling `map` on each node recursively in `pre_order` or post_order.
@@ -307,7 +511,7 @@ impl<'a> Lowering<'a> {
                     pre.chain(Lowering::collect_expression(e, map, filter, pre_order)).chain(post()).collect()
                 }
                 ExpressionKind::MissingExpression => unreachable!(),
-                ExpressionKind::Tuple(exprs) => {
+                ExpressionKind::Tuple(exprs) | ExpressionKind::Array(exprs) => {
                     let elems = exprs.iter().flat_map(|a| recursion(a));
                     pre.chain(elems).chain(post()).collect()
                 }
@@ -315,7 +519,12 @@ impl<'a> Lowering<'a> {
                     let args = args.iter().flat_map(|a| recursion(a));
                     pre.chain(args).chain(post()).collect()
                 }
-                ExpressionKind::Field(e, _) => pre.chain(recursion(e)).chain(post()).collect(),
+                ExpressionKind::Field(e, _) | ExpressionKind::Cast(e, _) => {
+                    pre.chain(recursion(e)).chain(post()).collect()
+                }
+                ExpressionKind::Let(binding, body) => {
+                    pre.chain(recursion(&binding.expr)).chain(recursion(body)).chain(post()).collect()
+                }
                 ExpressionKind::Method(_, _, _, _) => unimplemented!("Methods not supported, yet."),
             }
         } else {
@@ -349,13 +558,17 @@ impl<'a> Lowering<'a> {
                 }
                 _ => {}
             },
-            StreamAccess(e, _) | Unary(_, e) | ParenthesizedExpression(_, e, _) | Field(e, _) => {
+            StreamAccess(e, _) | Unary(_, e) | ParenthesizedExpression(_, e, _) | Field(e, _) | Cast(e, _) => {
                 self.find_dependencies(e, deps)
             }
             Default(left, right) | Binary(_, left, right) => {
                 self.find_dependencies(left, deps);
                 self.find_dependencies(right, deps);
             }
+            Let(binding, body) => {
+                self.find_dependencies(&binding.expr, deps);
+                self.find_dependencies(body, deps);
+            }
             SlidingWindowAggregation { .. } => {
                 // ignore sliding windows
             }
@@ -365,7 +578,11 @@ impl<'a> Lowering<'a> {
                 self.find_dependencies(alt, deps);
             }
             MissingExpression => unreachable!("checked in AST verification"),
-            Tuple(exprs) | Function(_, _, exprs) => {
+            Tuple(exprs) | Array(exprs) | Function(_, _, exprs) => {
+                if let Some(Declaration::ParamOut(output)) = self.dt.get(&expr.id) {
+                    let sr = self.get_ref_for_stream(output.id);
+                    deps.push(ir::Dependency { stream: sr, offsets: vec![ir::Offset::PastDiscreteOffset(0)] })
+                }
                 exprs.iter().for_each(|e| self.find_dependencies(e, deps));
             }
             Method(inner, _, _, params) => {
@@ -398,7 +615,7 @@ impl<'a> Lowering<'a> {
             if let ExpressionKind::Ident(_) = &expr.kind {
                 let target = self.get_ref_for_ident(expr.id);
                 let duration = self.lower_duration(duration.as_ref());
-                let op = *aggregation;
+                let op = aggregation.clone();
                 let reference = WindowReference(self.ir.sliding_windows.len());
                 let ty = self.lower_node_type(win_expr.id);
                 let window = ir::SlidingWindow { target, duration, wait: *wait, op, reference, ty };
@@ -413,6 +630,13 @@ impl<'a> Lowering<'a> {
     }
 
     fn lower_duration(&self, duration: &ast::Expression) -> Duration {
+        match &duration.kind {
+            ExpressionKind::Lit(lit) => Self::lower_literal_duration(lit),
+            _ => panic!("Duration literal needs to be a duration specification."),
+        }
+    }
+
+    fn lower_literal_duration(duration: &ast::Literal) -> Duration {
         let exact_duration = duration.parse_duration().expect("Duration literal needs to be a duration specification.");
         Duration::from_nanos(
             exact_duration.get::<nanosecond>().to_integer().to_u64().expect("Period [ns] too large for u64!"),
@@ -473,13 +697,31 @@ impl<'a> Lowering<'a> {
                     ),
                     Declaration::Const(constant) => {
                         let node_type = self.lower_node_type(constant.id);
-                        (
-                            node_type.clone(),
-                            ir::Expression::new(
-                                ir::ExpressionKind::LoadConstant(self.lower_literal(&constant.literal, constant.id)),
-                                node_type,
-                            ),
-                        )
+                        let kind = if constant.configurable {
+                            let ix = self.ir.constants.iter().position(|c| c.name == constant.name.name);
+                            ir::ExpressionKind::ConfigurableConstant(
+                                ix.expect("configurable constants are lowered first"),
+                            )
+                        } else {
+                            ir::ExpressionKind::LoadConstant(self.ct[&constant.id].clone())
+                        };
+                        (node_type.clone(), ir::Expression::new(kind, node_type))
+                    }
+                    // let-bound names are inlined, the arena shares the repeated subexpression
+                    Declaration::Let(binding) => {
+                        let binding = binding.clone();
+                        let (expr, ty) = self.lower_expression(&binding.expr);
+                        (ty, expr)
+                    }
+                    Declaration::Param(param) => {
+                        let ix = self
+                            .ast
+                            .outputs
+                            .iter()
+                            .find_map(|o| o.params.iter().position(|p| p.id == param.id))
+                            .expect("parameters are declared by outputs");
+                        let node_type = self.lower_node_type(param.id);
+                        (node_type.clone(), ir::Expression::new(ir::ExpressionKind::ParameterAccess(ix), node_type))
                     }
                     _ => unreachable!(),
                 };
@@ -565,12 +807,28 @@ impl<'a> Lowering<'a> {
             }
             ExpressionKind::ParenthesizedExpression(_, e, _) => self.lower_expression(e).0,
             ExpressionKind::MissingExpression => unreachable!(),
-            ExpressionKind::Tuple(exprs) => {
+            ExpressionKind::Tuple(exprs) | ExpressionKind::Array(exprs) => {
                 let exprs = exprs.iter().map(|e| self.lower_expression(e).0).collect();
                 ir::Expression::new(ir::ExpressionKind::Tuple(exprs), result_type.clone())
             }
             ExpressionKind::Function(name, _, args) => {
-                let args: Vec<&ast::Expression> = args.iter().map(Box::as_ref).collect();
+                let ast_args: Vec<&ast::Expression> = args.iter().map(Box::as_ref).collect();
+
+                if let Declaration::ParamOut(output) = self.get_decl(expr.id) {
+                    let output = output.clone();
+                    let param_types: Vec<ir::Type> = output.params.iter().map(|p| self.lower_node_type(p.id)).collect();
+                    let args = self.handle_func_args(&param_types, &ast_args[..]);
+                    let target = self.get_ref_for_stream(output.id);
+                    let ty = self.lower_node_type(output.id);
+                    let access = ir::Expression::new(ir::ExpressionKind::InstanceAccess(target, args), ty.clone());
+                    return if ty != result_type {
+                        let kind =
+                            ir::ExpressionKind::Convert { from: ty, to: result_type.clone(), expr: access.into() };
+                        (ir::Expression::new(kind, result_type.clone()), result_type)
+                    } else {
+                        (access, result_type)
+                    };
+                }
 
                 let generics = self.tt.get_func_arg_types(expr.id);
                 let (arg_types, ret_type) = if let Declaration::Func(fd) = self.get_decl(expr.id) {
@@ -579,13 +837,16 @@ impl<'a> Lowering<'a> {
                     unreachable!("Function not declared as such.")
                 };
                 let arg_types: Vec<ir::Type> = arg_types.into_iter().map(|ty| (&ty).into()).collect();
-                let args = self.handle_func_args(&arg_types, &args[..]);
+                let args = self.handle_func_args(&arg_types, &ast_args[..]);
 
                 let (func_expr, ret_type) = if name.name.name == "cast" {
                     // cast is no actual function
                     assert!(!args.is_empty());
                     assert!(!arg_types.is_empty());
                     (args[0].clone(), arg_types[0].clone())
+                } else if FILTERS.contains(&name.name.name.as_str()) {
+                    let ret_type: ir::Type = (&ret_type).into();
+                    (self.lower_filter(&name.name.name, args, &ast_args, ret_type.clone()), ret_type)
                 } else {
                     let ret_type: ir::Type = (&ret_type).into();
                     let fun_ty = ir::Type::Function(arg_types, Box::new(ret_type.clone()));
@@ -607,7 +868,7 @@ impl<'a> Lowering<'a> {
                 }
             }
             ExpressionKind::Method(inner, name, _, args) => {
-                let args: Vec<&ast::Expression> = std::iter::once(inner).chain(args).map(Box::as_ref).collect();
+                let ast_args: Vec<&ast::Expression> = std::iter::once(inner).chain(args).map(Box::as_ref).collect();
 
                 let generics = self.tt.get_func_arg_types(expr.id);
                 let (arg_types, ret_type) = if let Declaration::Func(fd) = self.get_decl(expr.id) {
@@ -618,13 +879,17 @@ impl<'a> Lowering<'a> {
                 let arg_types: Vec<ir::Type> = arg_types.into_iter().map(|ty| (&ty).into()).collect();
                 let ret_type: ir::Type = (&ret_type).into();
 
-                let args = self.handle_func_args(&arg_types, &args[..]);
+                let args = self.handle_func_args(&arg_types, &ast_args[..]);
                 let fun_ty = ir::Type::Function(arg_types, Box::new(ret_type.clone()));
 
-                let func_expr = ir::Expression::new(
-                    ir::ExpressionKind::Function(name.name.name.clone(), args, fun_ty),
-                    ret_type.clone(),
-                );
+                let func_expr = if FILTERS.contains(&name.name.name.as_str()) {
+                    self.lower_filter(&name.name.name, args, &ast_args, ret_type.clone())
+                } else {
+                    ir::Expression::new(
+                        ir::ExpressionKind::Function(name.name.name.clone(), args, fun_ty),
+                        ret_type.clone(),
+                    )
+                };
                 if ret_type != result_type {
                     ir::Expression::new(
                         ir::ExpressionKind::Convert { from: ret_type, to: result_type.clone(), expr: func_expr.into() },
@@ -641,10 +906,166 @@ impl<'a> Lowering<'a> {
                     result_type.clone(),
                 )
             }
+            ExpressionKind::Cast(expr, _) => {
+                let (expr, from) = self.lower_expression(expr);
+                let to = match &result_type {
+                    ir::Type::Option(to) => to.as_ref().clone(),
+                    ty => unreachable!("checked casts have an optional type, found {}", ty),
+                };
+                ir::Expression::new(ir::ExpressionKind::Cast { from, to, expr: expr.into() }, result_type.clone())
+            }
+            ExpressionKind::Let(_, body) => self.lower_expression(body).0,
         };
         (expr, result_type)
     }
 
+    /// Lowers a call of a filter into a hidden output stream, which refers to its own last value.
+    /// The hidden stream has the pacing of the calling stream and is evaluated right before it.
+    /// Returns a synchronous access to the hidden stream, or for `debounce`, whether the condition held long enough.
+    fn lower_filter(
+        &mut self,
+        name: &str,
+        args: Vec<ir::Expression>,
+        ast_args: &[&ast::Expression],
+        ty: ir::Type,
+    ) -> ir::Expression {
+        use crate::ir::ArithLogOp::*;
+        let caller = self.caller.expect("filters are only called in stream expressions");
+        let reference = StreamReference::OutRef(self.ir.outputs.len());
+        let layer = self.get_layer(caller);
+        self.shift_layers(layer);
+
+        let (boolean, float) = (ir::Type::Bool, ir::Type::Float(ir::FloatTy::F64));
+        let arith = |op, args: Vec<ir::Expression>, ret: &ir::Type| {
+            let fun_ty = ir::Type::Function(args.iter().map(|a| a.ty.clone()).collect(), Box::new(ret.clone()));
+            ir::Expression::new(ir::ExpressionKind::ArithLog(op, args, fun_ty), ret.clone())
+        };
+        let ite = |condition, consequence: ir::Expression, alternative| {
+            let ty = consequence.ty.clone();
+            ir::Expression::new(
+                ir::ExpressionKind::Ite {
+                    condition: Box::new(condition),
+                    consequence: Box::new(consequence),
+                    alternative: Box::new(alternative),
+                },
+                ty,
+            )
+        };
+        // The last value of the hidden stream, or the default in its first evaluation.
+        let last = |default: ir::Expression| {
+            let ty = default.ty.clone();
+            let lookup = ir::Expression::new(
+                ir::ExpressionKind::OffsetLookup { target: reference, offset: ir::Offset::PastDiscreteOffset(1) },
+                ir::Type::Option(Box::new(ty.clone())),
+            );
+            ir::Expression::new(ir::ExpressionKind::Default { expr: Box::new(lookup), default: Box::new(default) }, ty)
+        };
+        let float_constant =
+            |f: f64| ir::Expression::new(ir::ExpressionKind::LoadConstant(ir::Constant::Float(f)), float.clone());
+        let now = || ir::Expression::new(ir::ExpressionKind::Now, float.clone());
+        let (x, param) = (&args[0], &args[1]);
+        let (hidden_ty, expr) = match name {
+            // last + alpha * (x - last)
+            "lowpass" => {
+                // The filter starts with the first value of its input.
+                let last = last(x.clone());
+                let delta = arith(Sub, vec![x.clone(), last.clone()], &ty);
+                (ty.clone(), arith(Add, vec![last, arith(Mul, vec![param.clone(), delta], &ty)], &ty))
+            }
+            // if x - last > max_delta then last + max_delta else if last - x > max_delta then last - max_delta else x
+            "rate_limit" => {
+                let last = last(x.clone());
+                let rising = arith(Sub, vec![x.clone(), last.clone()], &ty);
+                let falling = arith(Sub, vec![last.clone(), x.clone()], &ty);
+                let expr = ite(
+                    arith(Gt, vec![rising, param.clone()], &boolean),
+                    arith(Add, vec![last.clone(), param.clone()], &ty),
+                    ite(
+                        arith(Gt, vec![falling, param.clone()], &boolean),
+                        arith(Sub, vec![last, param.clone()], &ty),
+                        x.clone(),
+                    ),
+                );
+                (ty.clone(), expr)
+            }
+            // The time of the first evaluation since which the condition holds, or -1 if it does not hold:
+            // if x then (if last < 0 then now else last) else -1
+            "debounce" => {
+                let since = last(float_constant(-1.0));
+                let started = ite(arith(Lt, vec![since.clone(), float_constant(0.0)], &boolean), now(), since);
+                (float.clone(), ite(x.clone(), started, float_constant(-1.0)))
+            }
+            // Whether the first condition held in an evaluation since which `off` did not hold:
+            // if last then !off else x
+            "hysteresis" => {
+                let active = last(ir::Expression::new(
+                    ir::ExpressionKind::LoadConstant(ir::Constant::Bool(false)),
+                    boolean.clone(),
+                ));
+                (boolean.clone(), ite(active, arith(Not, vec![param.clone()], &boolean), x.clone()))
+            }
+            _ => unreachable!("`{}` is no filter", name),
+        };
+
+        let mut outgoing_dependencies =
+            vec![ir::Dependency { stream: reference, offsets: vec![ir::Offset::PastDiscreteOffset(1)] }];
+        ast_args.iter().for_each(|arg| self.find_dependencies(arg, &mut outgoing_dependencies));
+        let time_driven = self.check_time_driven(caller, reference);
+        let ac = match time_driven.as_ref() {
+            None => Some(self.tt.get_acti_cond(caller).clone()),
+            Some(_tds) => None,
+        };
+        let output = ir::OutputStream {
+            name: format!("{}#{}", name, reference.out_ix()),
+            ty: hidden_ty.clone(),
+            expr,
+            // set when the expressions are interned
+            expr_id: ir::ExprId(0),
+            input_dependencies: self.gather_dependent_inputs(caller),
+            outgoing_dependencies,
+            dependent_streams: Vec::new(),
+            dependent_windows: Vec::new(),
+            memory_bound: MemorizationBound::Bounded(1),
+            layer,
+            // evaluated right before the caller, so it waits for the same future values
+            look_ahead: self.la[&caller],
+            reference,
+            ac,
+            params: Vec::new(),
+            template: None,
+            annotations: Vec::new(),
+        };
+        self.ir.outputs.push(output);
+        match time_driven {
+            None => self.ir.event_driven.push(EventDrivenStream { reference }),
+            Some(tds) => self.ir.time_driven.push(tds),
+        }
+        self.hidden.push(reference);
+        let access =
+            ir::Expression::new(ir::ExpressionKind::StreamAccess(reference, StreamAccessKind::Sync), hidden_ty);
+        if name != "debounce" {
+            return access;
+        }
+        // since >= 0 && now - since >= duration
+        let held = arith(Sub, vec![now(), access.clone()], &float);
+        let holds = arith(Ge, vec![access, float_constant(0.0)], &boolean);
+        arith(And, vec![holds, arith(Ge, vec![held, param.clone()], &boolean)], &boolean)
+    }
+
+    /// Moves all output streams from the given evaluation layer on one layer up to make room for a hidden stream.
+    fn shift_layers(&mut self, from: u32) {
+        self.layer_shifts.push(from);
+        self.ir.outputs.iter_mut().filter(|o| o.layer >= from).for_each(|o| o.layer += 1);
+    }
+
+    /// Returns the dependencies on the hidden streams accessed by the caller.
+    fn take_hidden_dependencies(&mut self) -> Vec<ir::Dependency> {
+        self.hidden
+            .drain(..)
+            .map(|stream| ir::Dependency { stream, offsets: vec![ir::Offset::PastDiscreteOffset(0)] })
+            .collect()
+    }
+
     /// Handles arithmetic-logic operations.
     /// `nid` is the node id of the expression.
     /// `op` is the operation.
@@ -727,20 +1148,25 @@ impl<'a> Lowering<'a> {
         let expected_type = self.lower_node_type(nid);
         match &lit.kind {
             LitKind::Str(s) | LitKind::RawStr(s) => ir::Constant::Str(s.clone()),
-            LitKind::Numeric(_, unit) => {
-                assert!(unit.is_none());
-                match expected_type {
-                    ir::Type::Float(_) => {
-                        ir::Constant::Float(lit.parse_numeric::<f64>().expect("checked by type checker"))
-                    }
-                    ir::Type::UInt(_) => {
-                        ir::Constant::UInt(lit.parse_numeric::<u64>().expect("checked by type checker"))
-                    }
-                    ir::Type::Int(_) => ir::Constant::Int(lit.parse_numeric::<i64>().expect("checked by type checker")),
-                    _ => unreachable!("checked by type checker {}", expected_type),
-                }
+            LitKind::Numeric(_, Some(_)) => {
+                // durations are represented by their length in seconds
+                let seconds = lit.parse_duration().expect("checked by type checker").get::<second>();
+                ir::Constant::Float(*seconds.numer() as f64 / *seconds.denom() as f64)
             }
+            LitKind::Numeric(_, None) => match expected_type {
+                ir::Type::Float(_) => ir::Constant::Float(lit.parse_numeric::<f64>().expect("checked by type checker")),
+                ir::Type::UInt(_) => ir::Constant::UInt(lit.parse_numeric::<u64>().expect("checked by type checker")),
+                ir::Type::Int(_) => ir::Constant::Int(lit.parse_numeric::<i64>().expect("checked by type checker")),
+                _ => unreachable!("checked by type checker {}", expected_type),
+            },
             LitKind::Bool(b) => ir::Constant::Bool(*b),
+            LitKind::Variant(_, variant) => match expected_type {
+                // variants are represented by their index
+                ir::Type::Enum(_, variants) => ir::Constant::UInt(
+                    variants.iter().position(|v| v == variant).expect("checked by naming analysis") as u64,
+                ),
+                _ => unreachable!("checked by type checker {}", expected_type),
+            },
         }
     }
 
@@ -836,7 +1262,8 @@ impl<'a> Lowering<'a> {
     }
 
     fn get_layer(&self, nid: NodeId) -> u32 {
-        *self.et.get(&nid).expect("Bug in EvaluationOrder.")
+        let layer = *self.et.get(&nid).expect("Bug in EvaluationOrder.");
+        self.layer_shifts.iter().fold(layer, |layer, from| if layer >= *from { layer + 1 } else { layer })
     }
 
     fn get_memory(&self, nid: NodeId) -> StorageRequirement {
@@ -853,7 +1280,7 @@ impl<'a> Lowering<'a> {
             Declaration::Out(out) => self.get_ref_for_stream(out.id),
             Declaration::Param(_) | Declaration::Const(_) => unimplemented!(),
             Declaration::Type(_) | Declaration::Func(_) => unreachable!("Types and functions are not streams."),
-            Declaration::ParamOut(_) => unreachable!(),
+            Declaration::ParamOut(_) | Declaration::Let(_) => unreachable!(),
         }
     }
 }
@@ -927,6 +1354,55 @@ mod tests {
         check_stream_number(&ir, 1, 1, 1, 0, 1, 0);
     }
 
+    #[test]
+    fn lower_filters() {
+        let ir = spec_to_ir(
+            "input a: Float64\n\
+             output b := lowpass(a, alpha: 0.2)\n\
+             output c @1Hz := a.hold().defaults(to: 0.0).rate_limit(max_delta: 1.0)\n\
+             trigger lowpass(b, alpha: 0.5) > 10.0",
+        );
+        // Each filter is a hidden output stream with the pacing of the calling stream.
+        check_stream_number(&ir, 1, 6, 2, 4, 0, 1);
+        let hidden = &ir.outputs[2];
+        assert_eq!(hidden.name, "lowpass#2");
+        assert_eq!(hidden.memory_bound, MemorizationBound::Bounded(1));
+        assert_eq!(ir.outputs[0].expr.kind, ExpressionKind::StreamAccess(hidden.reference, StreamAccessKind::Sync));
+        assert_eq!(ir.outputs[3].name, "rate_limit#3");
+        assert!(ir.time_driven.iter().any(|tds| tds.reference == ir.outputs[3].reference));
+        let layer = |name: &str| ir.outputs.iter().find(|o| o.name == name).unwrap().layer;
+        assert!(layer("lowpass#2") < layer("b"));
+        assert!(layer("rate_limit#3") < layer("c"));
+        assert!(layer("b") < layer("lowpass#4"));
+        assert!(layer("lowpass#4") < layer("trigger"));
+    }
+
+    #[test]
+    fn lower_assumption() {
+        let ir = spec_to_ir("input a: Int64\nassume a > 0 \"positive\"\ntrigger a > 5");
+        check_stream_number(&ir, 1, 2, 0, 2, 0, 2);
+        assert!(ir.triggers[0].assumption);
+        assert!(!ir.triggers[1].assumption);
+        let assumption = &ir.outputs[ir.triggers[0].reference.out_ix()];
+        assert_eq!(assumption.name, "assumption_positive");
+        match &assumption.expr.kind {
+            ExpressionKind::ArithLog(ArithLogOp::Not, args, _) => assert_eq!(args.len(), 1),
+            kind => panic!("expected a negation, found {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn lower_trigger_hysteresis() {
+        let ir = spec_to_ir("input a: Float64\ntrigger a > 80.0 for 2s until a < 70.0 \"overheated\"");
+        check_stream_number(&ir, 1, 3, 0, 3, 0, 1);
+        let hidden = |name: &str| ir.outputs.iter().find(|o| o.name == name).unwrap();
+        // The hidden stream of `debounce` holds the time since which the condition is true.
+        assert_eq!(hidden("debounce#0").ty, Type::Float(FloatTy::F64));
+        assert_eq!(hidden("hysteresis#1").ty, Type::Bool);
+        assert!(hidden("debounce#0").layer < hidden("hysteresis#1").layer);
+        assert!(hidden("hysteresis#1").layer < ir.outputs[ir.triggers[0].reference.out_ix()].layer);
+    }
+
     #[test]
     #[ignore] // Trigger needs to be periodic, and if it were event based, the type checker needs to reject the access w/o s&h or default.
     fn lower_multiple_streams_with_windows() {
@@ -0,0 +1,231 @@
+//! This module merges the IRs of independent specifications sharing their inputs into one, see `RTLolaIR::merge`.
+//!
+//! Inputs with the same name are shared, so each event is decoded once for all specifications.
+//! All other streams, sliding windows, triggers, and configurable constants are kept per specification, where the names of streams and constants are prefixed with the name of their specification, e.g., `cruise::deviation`.
+//! The specifications do not access each other's streams, so their streams keep their evaluation layers.
+
+use super::*;
+
+impl RTLolaIR {
+    /// Merges the IRs of named specifications into one, which is evaluated by a single monitor.
+    /// The triggers refer to their specification in `RTLolaIR::specifications`.
+    /// Returns an error if there is no specification, two specifications have the same name, a specification is merged already, or inputs with the same name have different types.
+    pub fn merge(specifications: Vec<(String, RTLolaIR)>) -> Result<RTLolaIR, String> {
+        if specifications.is_empty() {
+            return Err(String::from("there are no specifications to merge"));
+        }
+        let mut merged = RTLolaIR {
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            time_driven: Vec::new(),
+            event_driven: Vec::new(),
+            sliding_windows: Vec::new(),
+            triggers: Vec::new(),
+            expressions: ExpressionArena::default(),
+            constants: Vec::new(),
+            specifications: Vec::new(),
+        };
+        for (specification, (name, ir)) in specifications.into_iter().enumerate() {
+            if merged.specifications.contains(&name) {
+                return Err(format!("there are several specifications named `{}`", name));
+            }
+            if !ir.specifications.is_empty() {
+                return Err(format!("the specification `{}` is merged already", name));
+            }
+            let mut inputs = Vec::with_capacity(ir.inputs.len());
+            for input in &ir.inputs {
+                match merged.inputs.iter_mut().find(|i| i.name == input.name) {
+                    Some(shared) if shared.ty != input.ty => {
+                        return Err(format!(
+                            "the input `{}` has type {} in `{}`, but type {} before",
+                            input.name, input.ty, name, shared.ty
+                        ));
+                    }
+                    Some(shared) => {
+                        shared.memory_bound = match (shared.memory_bound, input.memory_bound) {
+                            (MemorizationBound::Bounded(a), MemorizationBound::Bounded(b)) => {
+                                MemorizationBound::Bounded(a.max(b))
+                            }
+                            _ => MemorizationBound::Unbounded,
+                        };
+                        inputs.push(shared.reference.in_ix());
+                    }
+                    None => {
+                        let reference = StreamReference::InRef(merged.inputs.len());
+                        inputs.push(reference.in_ix());
+                        merged.inputs.push(InputStream {
+                            reference,
+                            dependent_streams: Vec::new(),
+                            dependent_windows: Vec::new(),
+                            ..input.clone()
+                        });
+                    }
+                }
+            }
+            let map = Remapping {
+                inputs,
+                outputs: merged.outputs.len(),
+                windows: merged.sliding_windows.len(),
+                constants: merged.constants.len(),
+            };
+            for (input, &ix) in ir.inputs.iter().zip(&map.inputs) {
+                let shared = &mut merged.inputs[ix];
+                shared.dependent_streams.extend(input.dependent_streams.iter().map(|t| map.tracking(t)));
+                shared.dependent_windows.extend(input.dependent_windows.iter().map(|w| map.window(*w)));
+            }
+            merged.outputs.extend(ir.outputs.into_iter().map(|mut output| {
+                output.name = format!("{}::{}", name, output.name);
+                map.expression(&mut output.expr);
+                if let Some(template) = &mut output.template {
+                    template.clauses_mut().for_each(|clause| map.expression(clause));
+                }
+                output.input_dependencies.iter_mut().for_each(|i| *i = map.stream(*i));
+                output.outgoing_dependencies.iter_mut().for_each(|d| d.stream = map.stream(d.stream));
+                output.dependent_streams.iter_mut().for_each(|t| *t = map.tracking(t));
+                output.dependent_windows.iter_mut().for_each(|w| *w = map.window(*w));
+                output.reference = map.stream(output.reference);
+                output.ac = output.ac.as_ref().map(|ac| map.activation(ac));
+                output
+            }));
+            merged.time_driven.extend(ir.time_driven.into_iter().map(|mut stream| {
+                stream.reference = map.stream(stream.reference);
+                stream
+            }));
+            merged.event_driven.extend(ir.event_driven.into_iter().map(|mut stream| {
+                stream.reference = map.stream(stream.reference);
+                stream
+            }));
+            merged.sliding_windows.extend(ir.sliding_windows.into_iter().map(|mut window| {
+                window.target = map.stream(window.target);
+                window.reference = map.window(window.reference);
+                window
+            }));
+            let triggers = merged.triggers.len();
+            merged.triggers.extend(ir.triggers.into_iter().map(|mut trigger| {
+                trigger.reference = map.stream(trigger.reference);
+                trigger.placeholders.iter_mut().for_each(|(_, stream)| *stream = map.stream(*stream));
+                trigger.trigger_idx += triggers;
+                trigger.specification = specification;
+                trigger
+            }));
+            merged.constants.extend(ir.constants.into_iter().map(|mut constant| {
+                constant.name = format!("{}::{}", name, constant.name);
+                constant
+            }));
+            merged.specifications.push(name);
+        }
+        merged.intern_expressions();
+        Ok(merged)
+    }
+}
+
+/// Maps the references of a specification to those in the merged specification.
+struct Remapping {
+    /// The merged input of each input.
+    inputs: Vec<InputReference>,
+    /// The number of output streams of the preceding specifications.
+    outputs: usize,
+    /// The number of sliding windows of the preceding specifications.
+    windows: usize,
+    /// The number of configurable constants of the preceding specifications.
+    constants: usize,
+}
+
+impl Remapping {
+    fn stream(&self, reference: StreamReference) -> StreamReference {
+        match reference {
+            StreamReference::InRef(ix) => StreamReference::InRef(self.inputs[ix]),
+            StreamReference::OutRef(ix) => StreamReference::OutRef(ix + self.outputs),
+        }
+    }
+
+    fn window(&self, window: WindowReference) -> WindowReference {
+        WindowReference(window.0 + self.windows)
+    }
+
+    fn tracking(&self, tracking: &Tracking) -> Tracking {
+        match *tracking {
+            Tracking::All(reference) => Tracking::All(self.stream(reference)),
+            Tracking::Bounded { trackee, num, rate } => Tracking::Bounded { trackee: self.stream(trackee), num, rate },
+        }
+    }
+
+    fn activation(&self, ac: &Activation<StreamReference>) -> Activation<StreamReference> {
+        match ac {
+            Activation::Conjunction(args) => Activation::Conjunction(args.iter().map(|a| self.activation(a)).collect()),
+            Activation::Disjunction(args) => Activation::Disjunction(args.iter().map(|a| self.activation(a)).collect()),
+            Activation::Stream(reference) => Activation::Stream(self.stream(*reference)),
+            Activation::True => Activation::True,
+        }
+    }
+
+    fn expression(&self, expr: &mut Expression) {
+        use ExpressionKind::*;
+        match &mut expr.kind {
+            StreamAccess(target, _) | OffsetLookup { target, .. } => *target = self.stream(*target),
+            InstanceAccess(target, args) => {
+                *target = self.stream(*target);
+                args.iter_mut().for_each(|arg| self.expression(arg))
+            }
+            WindowLookup(window) => *window = self.window(*window),
+            ConfigurableConstant(ix) => *ix += self.constants,
+            ArithLog(_, args, _) | Tuple(args) | Function(_, args, _) => {
+                args.iter_mut().for_each(|arg| self.expression(arg))
+            }
+            Ite { condition, consequence, alternative } => {
+                self.expression(condition);
+                self.expression(consequence);
+                self.expression(alternative);
+            }
+            TupleAccess(expr, _) | Convert { expr, .. } | Cast { expr, .. } => self.expression(expr),
+            Default { expr, default } => {
+                self.expression(expr);
+                self.expression(default);
+            }
+            LoadConstant(_) | Now | ParameterAccess(_) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrontendConfig;
+
+    fn ir(spec: &str) -> RTLolaIR {
+        crate::parse("stdin", spec, FrontendConfig::default()).unwrap_or_else(|e| panic!("spec is invalid: {}", e))
+    }
+
+    #[test]
+    fn inputs_are_shared() {
+        let cruise = ir(
+            "input speed: Float64\ninput target: Float64\noutput deviation := target - speed\ntrigger deviation > 5.0",
+        );
+        let brakes = ir("input pressure: Float64\ninput speed: Float64\ntrigger speed > 10.0 && pressure < 1.0");
+        let merged = RTLolaIR::merge(vec![(String::from("cruise"), cruise), (String::from("brakes"), brakes)]).unwrap();
+        let names: Vec<&str> = merged.inputs.iter().map(|i| i.name.as_str()).collect();
+        assert_eq!(names, vec!["speed", "target", "pressure"]);
+        assert_eq!(merged.outputs[0].name, "cruise::deviation");
+        assert_eq!(merged.outputs[2].input_dependencies.len(), 2);
+        assert!(merged.outputs[2].input_dependencies.contains(&StreamReference::InRef(0)));
+        assert!(merged.outputs.iter().all(|o| o.input_dependencies.contains(&StreamReference::InRef(0))));
+        let triggers: Vec<(usize, usize)> = merged.triggers.iter().map(|t| (t.trigger_idx, t.specification)).collect();
+        assert_eq!(triggers, vec![(0, 0), (1, 1)]);
+        assert_eq!(merged.triggers[1].reference, StreamReference::OutRef(2));
+        for output in &merged.outputs {
+            assert_eq!(merged.expressions.expression(output.expr_id), output.expr);
+        }
+    }
+
+    #[test]
+    fn conflicting_specifications() {
+        let merge = |a: &str, b: &str| RTLolaIR::merge(vec![(String::from("a"), ir(a)), (String::from("b"), ir(b))]);
+        assert!(merge("input x: Float64", "input x: Int64").is_err());
+        assert!(RTLolaIR::merge(vec![
+            (String::from("a"), ir("input x: Bool")),
+            (String::from("a"), ir("input y: Bool"))
+        ])
+        .is_err());
+        assert!(RTLolaIR::merge(Vec::new()).is_err());
+    }
+}
@@ -6,6 +6,7 @@ impl Display for Expression {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result {
         match &self.kind {
             ExpressionKind::LoadConstant(c) => write!(f, "{}", c),
+            ExpressionKind::ConfigurableConstant(ix) => write!(f, "Const({})", ix),
             ExpressionKind::Function(name, args, ty) => {
                 write!(f, "{}(", name)?;
                 if let Type::Function(arg_tys, res) = ty {
@@ -20,6 +21,7 @@ impl Display for Expression {
                 }
             }
             ExpressionKind::Convert { from, to, expr } => write!(f, "cast<{},{}>({})", from, to, expr),
+            ExpressionKind::Cast { from, to, expr } => write!(f, "checked_cast<{},{}>({})", from, to, expr),
             ExpressionKind::Tuple(elems) => write_delim_list(f, elems, "(", ")", ","),
             ExpressionKind::Ite { condition, consequence, alternative, .. } => {
                 write!(f, "if {} then {} else {}", condition, consequence, alternative)
@@ -28,6 +30,7 @@ impl Display for Expression {
                 write_delim_list(f, args, &format!("{}(", op), &format!(") : [{}]", ty), ",")
             }
             ExpressionKind::WindowLookup(wr) => write!(f, "{}", wr),
+            ExpressionKind::Now => write!(f, "now"),
             ExpressionKind::Default { expr, default, .. } => write!(f, "{}.default({})", expr, default),
             ExpressionKind::OffsetLookup { target, offset } => write!(f, "{}.offset({})", target, offset),
             ExpressionKind::StreamAccess(sr, access) => match access {
@@ -36,6 +39,8 @@ impl Display for Expression {
                 StreamAccessKind::Optional => write!(f, "{}.get()", sr),
             },
             ExpressionKind::TupleAccess(expr, num) => write!(f, "{}.{}", expr, num),
+            ExpressionKind::ParameterAccess(ix) => write!(f, "Param({})", ix),
+            ExpressionKind::InstanceAccess(sr, args) => write_delim_list(f, args, &format!("{}(", sr), ")", ","),
         }
     }
 }
@@ -99,6 +104,8 @@ impl Display for Type {
             Type::Int(_) => write!(f, "Int{}", self.size().expect("Ints are sized.").0 * 8),
             Type::Function(args, res) => write_delim_list(f, args, "(", &format!(") -> {}", res), ","),
             Type::Tuple(elems) => write_delim_list(f, elems, "(", ")", ","),
+            Type::Array(elem, size) => write!(f, "[{}; {}]", elem, size),
+            Type::Enum(name, _) => write!(f, "{}", name),
             Type::String => write!(f, "String"),
             Type::Bytes => write!(f, "Bytes"),
             Type::Option(inner) => write!(f, "Option<{}>", inner),
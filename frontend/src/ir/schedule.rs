@@ -197,6 +197,7 @@ mod tests {
     use super::*;
     use crate::ir::RTLolaIR;
     use crate::FrontendConfig;
+    use log::warn;
     #[allow(unused_imports)]
     use num::FromPrimitive;
     use num::ToPrimitive;
@@ -241,7 +242,7 @@ mod tests {
         let representable = lhs % rhs == 0;
         let mut div = lhs / rhs;
         if !representable {
-            println!("Warning: Spec unstable: Cannot accurately represent extend periods.");
+            warn!("Spec unstable: Cannot accurately represent extend periods.");
             // TODO: Introduce better mechanism for emitting such warnings.
             if round_up {
                 div += 1;
@@ -1,6 +1,7 @@
 //! Parser for the RTLola language.
 
 #![forbid(unused_must_use)] // disallow discarding errors
+#![recursion_limit = "256"] // the declarations of the standard library exceed the default of `lazy_static!`
 #![warn(
     missing_docs,
     missing_debug_implementations,
@@ -15,11 +16,20 @@
 
 mod analysis;
 pub mod ast;
+pub mod completion;
+pub mod diagnostics;
+pub mod diff;
 mod export;
+pub mod highlighting;
+pub mod hover;
 pub mod ir;
+pub mod navigation;
+pub mod outline;
 mod parse;
+pub mod reorder;
 mod reporting;
 mod stdlib;
+pub mod translation;
 pub mod ty;
 
 #[cfg(test)]
@@ -29,6 +39,8 @@ mod tests;
 pub use ast::RTLolaAst;
 pub use export::analyze;
 pub use ir::RTLolaIR;
+pub use reporting::WarningConfig;
+pub use stdlib::{FuncDecl, FunctionRegistry};
 pub use ty::TypeConfig;
 
 /**
@@ -36,6 +48,7 @@ This module contains a module for each binary that this crate provides.
 */
 pub mod app {
     pub mod analyze;
+    pub mod translate;
 }
 
 /**
@@ -69,18 +82,404 @@ The filename is only used for printing locations.
 See the `FrontendConfig` documentation on more information about the parser options.  
 */
 pub fn parse(filename: &str, spec_str: &str, config: FrontendConfig) -> Result<RTLolaIR, String> {
-    let mapper = crate::parse::SourceMapper::new(std::path::PathBuf::from(filename), spec_str);
-    let handler = reporting::Handler::new(mapper);
+    parse_with_functions(filename, spec_str, config, &stdlib::STANDARD_LIBRARY)
+}
+
+/**
+Parses a RTLola specification that may call the functions in `functions` in addition to the standard library.
+
+Host applications use this to provide domain specific functions; the backend needs to know how to evaluate them.
+
+```
+use rtlola_frontend::ty::{TypeConstraint, ValueTy};
+use rtlola_frontend::{FrontendConfig, FuncDecl, FunctionRegistry};
+
+let mut functions = FunctionRegistry::default();
+// fn knots_to_mps<T: FloatingPoint>(T) -> T
+let t = ValueTy::Param(0, "T".to_string());
+let generics = vec![ValueTy::Constr(TypeConstraint::FloatingPoint)];
+functions.add_function(Some("aero"), FuncDecl::new("knots_to_mps", &[None], generics, vec![t.clone()], t));
+
+let spec = "import aero\ninput airspeed: Float64\noutput speed := knots_to_mps(airspeed)";
+assert!(rtlola_frontend::parse_with_functions("spec.lola", spec, FrontendConfig::default(), &functions).is_ok());
+```
+*/
+pub fn parse_with_functions(
+    filename: &str,
+    spec_str: &str,
+    config: FrontendConfig,
+    functions: &FunctionRegistry,
+) -> Result<RTLolaIR, String> {
+    parse_with(filename, spec_str, config, functions, &WarningConfig::default(), &[])
+}
+
+/**
+Parses a RTLola specification like `parse`, but reports warnings as configured by `warnings`.
+
+In particular, continuous integration can enforce warning-free specifications by promoting all warnings to errors.
+
+```
+use rtlola_frontend::{FrontendConfig, WarningConfig};
+
+// the access `a.hold()` is unnecessary, as `a` has a value whenever `b` is evaluated
+let spec = "input a: Int64\noutput b @ a := a.hold().defaults(to: 0)";
+let deny = WarningConfig { deny_all: true, ..WarningConfig::default() };
+assert!(rtlola_frontend::parse_with_warnings("spec.lola", spec, FrontendConfig::default(), &deny).is_err());
+let allow = WarningConfig { allow: vec!["W0201".to_string()], ..deny };
+assert!(rtlola_frontend::parse_with_warnings("spec.lola", spec, FrontendConfig::default(), &allow).is_ok());
+```
+*/
+pub fn parse_with_warnings(
+    filename: &str,
+    spec_str: &str,
+    config: FrontendConfig,
+    warnings: &WarningConfig,
+) -> Result<RTLolaIR, String> {
+    parse_with(filename, spec_str, config, &stdlib::STANDARD_LIBRARY, warnings, &[])
+}
 
-    let spec = match crate::parse::parse(&spec_str, &handler, config) {
+/**
+Parses a RTLola specification like `parse_with_warnings`, but only keeps the declarations whose `cfg` attributes hold for the enabled `features`.
+
+In particular, one specification can serve both a simulation and a flight configuration.
+
+```
+use rtlola_frontend::{FrontendConfig, WarningConfig};
+
+let spec = r#"
+input altitude: Float64
+#[cfg(feature = "simulation")]
+input sim_time: Float64
+#[cfg(not(feature = "simulation"))]
+trigger altitude < 10.0 "too low"
+"#;
+let (config, warnings) = (FrontendConfig::default(), WarningConfig::default());
+let features = vec!["simulation".to_string()];
+let ir = rtlola_frontend::parse_with_features("spec.lola", spec, config, &features, &warnings).unwrap();
+assert_eq!((ir.inputs.len(), ir.triggers.len()), (2, 0));
+```
+*/
+pub fn parse_with_features(
+    filename: &str,
+    spec_str: &str,
+    config: FrontendConfig,
+    features: &[String],
+    warnings: &WarningConfig,
+) -> Result<RTLolaIR, String> {
+    parse_with(filename, spec_str, config, &stdlib::STANDARD_LIBRARY, warnings, features)
+}
+
+fn parse_with(
+    filename: &str,
+    spec_str: &str,
+    config: FrontendConfig,
+    functions: &FunctionRegistry,
+    warnings: &WarningConfig,
+    features: &[String],
+) -> Result<RTLolaIR, String> {
+    let mapper = parse::SourceMapper::new(std::path::PathBuf::from(filename), spec_str);
+    let mut handler = reporting::Handler::new(mapper);
+    handler.configure_warnings(warnings)?;
+
+    let spec = match parse::parse_with_features(spec_str, &handler, config, features) {
         Ok(spec) => spec,
         Err(e) => {
-            return Err(format!("error: invalid syntax:\n{}", e));
+            handler.syntax_error(&e);
+            return Err("Parsing failed due to a syntax error in the specification".to_string());
         }
     };
 
-    let analysis_result = analysis::analyze(&spec, &handler, config);
+    let analysis_result = analysis::analyze(&spec, &handler, config, functions);
     analysis_result
         .map(|report| ir::lowering::Lowering::new(&spec, &report).lower())
         .map_err(|_| "Analysis failed due to errors in the specification".to_string())
 }
+
+/**
+Parses a RTLola specification like `parse` but returns the diagnostics as JSON objects instead of printing them to stderr.
+
+Each diagnostic is a single-line JSON object with the fields `severity`, `code`, `message`, `spans`, and `notes`.
+Spans give the file, the line and column of their start and end, both starting at 1, their label, and whether they are the primary span.
+The code is a stable identifier of the kind of diagnostic, e.g., `"E0106"` for unknown names; errors have codes `E0xxx` and warnings `W0xxx`.
+
+```
+use rtlola_frontend::FrontendConfig;
+
+let (ir, diagnostics) =
+    rtlola_frontend::parse_with_json_diagnostics("spec.lola", "output a := b", FrontendConfig::default());
+assert!(ir.is_err());
+assert!(diagnostics[0].starts_with(r#"{"severity": "error", "code": "E0106""#));
+```
+*/
+pub fn parse_with_json_diagnostics(
+    filename: &str,
+    spec_str: &str,
+    config: FrontendConfig,
+) -> (Result<RTLolaIR, String>, Vec<String>) {
+    let mapper = || parse::SourceMapper::new(std::path::PathBuf::from(filename), spec_str);
+    let diagnostics = reporting::JsonDiagnostics::new();
+    let handler = reporting::Handler::with_emitter(mapper(), Box::new(diagnostics.clone()));
+
+    let result = match parse::parse_with_features(spec_str, &handler, config, &[]) {
+        Ok(spec) => analysis::analyze(&spec, &handler, config, &stdlib::STANDARD_LIBRARY)
+            .map(|report| ir::lowering::Lowering::new(&spec, &report).lower())
+            .map_err(|_| "Analysis failed due to errors in the specification".to_string()),
+        Err(e) => {
+            diagnostics.add_parse_error(&mapper(), &e);
+            Err(format!("error: invalid syntax:\n{}", e))
+        }
+    };
+    (result, diagnostics.diagnostics())
+}
+
+/**
+Parses a RTLola specification like `parse` but returns the diagnostics instead of printing them to stderr.
+
+Host applications, e.g., language servers or web playgrounds, present the diagnostics in their own UI.
+
+```
+use rtlola_frontend::diagnostics::Severity;
+use rtlola_frontend::FrontendConfig;
+
+let (ir, diagnostics) =
+    rtlola_frontend::parse_with_diagnostics("spec.lola", "input a: Int32\noutput b := c", FrontendConfig::default());
+assert!(ir.is_err());
+assert_eq!(diagnostics[0].severity, Severity::Error);
+assert_eq!(diagnostics[0].code.as_deref(), Some("E0106"));
+assert_eq!((diagnostics[0].spans[0].line_start, diagnostics[0].spans[0].column_start), (2, 13));
+```
+*/
+pub fn parse_with_diagnostics(
+    filename: &str,
+    spec_str: &str,
+    config: FrontendConfig,
+) -> (Result<RTLolaIR, String>, Vec<diagnostics::Diagnostic>) {
+    let mapper = parse::SourceMapper::new(std::path::PathBuf::from(filename), spec_str);
+    let diagnostics = diagnostics::DiagnosticCollector::new();
+    let handler = reporting::Handler::with_emitter(mapper, Box::new(diagnostics.clone()));
+
+    let result = match parse::parse_with_features(spec_str, &handler, config, &[]) {
+        Ok(spec) => analysis::analyze(&spec, &handler, config, &stdlib::STANDARD_LIBRARY)
+            .map(|report| ir::lowering::Lowering::new(&spec, &report).lower())
+            .map_err(|_| "Analysis failed due to errors in the specification".to_string()),
+        Err(e) => {
+            handler.syntax_error(&e);
+            Err("Parsing failed due to a syntax error in the specification".to_string())
+        }
+    };
+    (result, diagnostics.diagnostics())
+}
+
+/**
+Runs the frontend on a RTLola specification and returns its diagnostics rendered as by `rtlola-analyze`, but without colors and trailing whitespace.
+
+Tests use this to lock in the diagnostics of a specification, e.g., the golden tests in `tests/diagnostics`.
+
+```
+use rtlola_frontend::FrontendConfig;
+
+let rendered = rtlola_frontend::render_diagnostics("spec.lola", "output a := b", FrontendConfig::default());
+assert!(rendered.starts_with("error[E0106]: name `b` does not exist in current scope\n --> spec.lola:1:13\n"));
+```
+*/
+pub fn render_diagnostics(filename: &str, spec_str: &str, config: FrontendConfig) -> String {
+    let mapper = parse::SourceMapper::new(std::path::PathBuf::from(filename), spec_str);
+    let diagnostics = reporting::RenderedDiagnostics::new();
+    let handler = reporting::Handler::with_emitter(mapper, Box::new(diagnostics.clone()));
+
+    match parse::parse_with_features(spec_str, &handler, config, &[]) {
+        Ok(spec) => {
+            let _ = analysis::analyze(&spec, &handler, config, &stdlib::STANDARD_LIBRARY);
+        }
+        Err(e) => handler.syntax_error(&e),
+    }
+    diagnostics.rendered()
+}
+
+/**
+Classifies the tokens of a RTLola specification for syntax highlighting, e.g., by a language server or the web playground.
+
+Keywords, literals, operators, and comments are classified even if the specification is invalid; names are resolved against the AST if the specification parses, e.g., into streams and parameters.
+No diagnostics are reported.
+
+```
+use rtlola_frontend::highlighting::TokenKind;
+use rtlola_frontend::FrontendConfig;
+
+let spec = "input a: Int32\noutput b := a + 1 // increment";
+let tokens = rtlola_frontend::semantic_tokens(spec, FrontendConfig::default());
+let kinds: Vec<TokenKind> = tokens.iter().map(|t| t.kind).collect();
+assert_eq!(kinds[..3], [TokenKind::Keyword, TokenKind::Stream, TokenKind::Type]);
+assert_eq!((tokens[4].line, tokens[4].column, tokens[4].kind), (2, 8, TokenKind::Stream));
+assert_eq!(tokens.last().unwrap().kind, TokenKind::Comment);
+```
+*/
+pub fn semantic_tokens(spec_str: &str, config: FrontendConfig) -> Vec<highlighting::SemanticToken> {
+    highlighting::classify(spec_str, config)
+}
+
+/**
+Returns the information about the declaration or expression at a byte offset of a RTLola specification, e.g., for the hover of a language server.
+
+Hovering over the name of a stream, either in its declaration or when accessing it, shows its type, pacing, and memory bound; hovering over any other expression shows the innermost expression with its type and the pacing of the stream it belongs to.
+Returns `None` if the specification is invalid or nothing is declared at the offset. No diagnostics are reported.
+
+```
+use rtlola_frontend::FrontendConfig;
+
+let spec = "input a: Int32\noutput b @ 1Hz := a.hold().defaults(to: 0) + 1";
+let hover = rtlola_frontend::hover(spec, spec.find("b @").unwrap(), FrontendConfig::default()).unwrap();
+assert_eq!(hover.to_string(), "output b: Int32\npacing: periodic at 1 Hz\nmemory: 1 value");
+let hover = rtlola_frontend::hover(spec, spec.find("+").unwrap(), FrontendConfig::default()).unwrap();
+assert_eq!(hover.subject, "expression a.hold().defaults(to: 0) + 1");
+```
+*/
+pub fn hover(spec_str: &str, offset: usize, config: FrontendConfig) -> Option<hover::Hover> {
+    hover::hover(spec_str, offset, config)
+}
+
+/**
+Returns the declaration of the stream, constant, or parameter named at a byte offset of a RTLola specification, e.g., for go-to-definition in a language server.
+
+The offset may point into an access of the name or into its declaration; names are resolved if the specification parses, even if it has other errors.
+No diagnostics are reported.
+
+```
+use rtlola_frontend::FrontendConfig;
+
+let spec = "input a: Int32\noutput b := a + 1";
+let definition = rtlola_frontend::definition(spec, spec.rfind('a').unwrap(), FrontendConfig::default()).unwrap();
+assert_eq!((definition.start, definition.line, definition.column), (6, 1, 7));
+```
+*/
+pub fn definition(spec_str: &str, offset: usize, config: FrontendConfig) -> Option<navigation::Location> {
+    navigation::definition(spec_str, offset, config)
+}
+
+/**
+Returns all accesses of the stream, constant, or parameter named at a byte offset of a RTLola specification in the order they appear, e.g., for find-references in a language server.
+
+As for `definition`, the offset may point into an access or into the declaration, which is not part of the result.
+
+```
+use rtlola_frontend::FrontendConfig;
+
+let spec = "input a: Int32\noutput b := a + 1\ntrigger a > b";
+let references = rtlola_frontend::references(spec, 6, FrontendConfig::default());
+assert_eq!(references.iter().map(|r| r.line).collect::<Vec<_>>(), vec![2, 3]);
+```
+*/
+pub fn references(spec_str: &str, offset: usize, config: FrontendConfig) -> Vec<navigation::Location> {
+    navigation::references(spec_str, offset, config)
+}
+
+/**
+Returns the edits renaming the stream, constant, parameter, or trigger named at a byte offset of a RTLola specification to a new name, e.g., for renaming in a language server.
+
+The edits replace the declaration and all accesses in the order they appear.
+Renaming fails with a message if the new name is not a valid identifier, is a keyword, is already declared, or makes the specification invalid, e.g., by shadowing a function of the standard library.
+
+```
+use rtlola_frontend::FrontendConfig;
+
+let spec = "input a: Int32\noutput b := a + 1";
+let edits = rtlola_frontend::rename(spec, 6, "altitude", FrontendConfig::default()).unwrap();
+assert_eq!(edits.iter().map(|e| e.location.start).collect::<Vec<_>>(), vec![6, 27]);
+assert!(rtlola_frontend::rename(spec, 6, "b", FrontendConfig::default()).is_err());
+```
+*/
+pub fn rename(
+    spec_str: &str,
+    offset: usize,
+    new_name: &str,
+    config: FrontendConfig,
+) -> Result<Vec<navigation::TextEdit>, String> {
+    navigation::rename(spec_str, offset, new_name, config)
+}
+
+/**
+Returns the candidates for completing the name at a byte offset of a RTLola specification, e.g., for code completion in a language server.
+
+Depending on the position, the candidates are the streams, constants, and parameters in scope together with the functions of the standard library and the imported modules, the aggregation functions of a sliding window after `using:`, or the types in a type annotation.
+Only candidates starting with the part of the name before the offset are returned; the specification may be incomplete.
+
+```
+use rtlola_frontend::completion::CompletionKind;
+use rtlola_frontend::FrontendConfig;
+
+let spec = "input altitude: Float64\noutput b := al";
+let completions = rtlola_frontend::completions(spec, spec.len(), FrontendConfig::default());
+assert_eq!(completions[0].label, "altitude");
+assert_eq!(completions[0].kind, CompletionKind::Stream);
+```
+*/
+pub fn completions(spec_str: &str, offset: usize, config: FrontendConfig) -> Vec<completion::Completion> {
+    completion::completions(spec_str, offset, config)
+}
+
+/**
+Returns the constants, inputs, outputs, and triggers of a RTLola specification in the order they appear, e.g., for the outline of a language server.
+
+Each symbol has the span of its declaration and of its name, and the type of the stream, which is inferred if the specification is valid; the parameters of a stream are its children.
+If the specification does not parse, the outline is empty.
+
+```
+use rtlola_frontend::outline::SymbolKind;
+use rtlola_frontend::FrontendConfig;
+
+let outline = rtlola_frontend::outline("input a: Int32\noutput b := a + 1", FrontendConfig::default());
+assert_eq!(outline[1].kind, SymbolKind::Output);
+assert_eq!((outline[1].name.as_str(), outline[1].ty.as_deref()), ("b", Some("Int32")));
+```
+*/
+pub fn outline(spec_str: &str, config: FrontendConfig) -> Vec<outline::Symbol> {
+    outline::outline(spec_str, config)
+}
+
+/**
+Compares two RTLola specifications declaration by declaration, e.g., for reviewing changes of a specification.
+
+Constants, streams, and triggers are added, removed, or changed, where changes name the differing parts, e.g., the type or the expression; the types are inferred if a specification is valid.
+Differences in whitespace, comments, and the order of declarations are ignored.
+Fails if one of the specifications does not parse.
+
+```
+use rtlola_frontend::FrontendConfig;
+
+let old = "input a: Int32\noutput b := a + 1";
+let new = "input a: Int32\n\noutput b := a+2";
+let changes = rtlola_frontend::diff(old, new, FrontendConfig::default()).unwrap();
+assert_eq!(changes[0].to_string(), "~ output b\n    expression: `a + 1` -> `a + 2`");
+```
+*/
+pub fn diff(old_spec: &str, new_spec: &str, config: FrontendConfig) -> Result<Vec<diff::Change>, String> {
+    diff::diff(old_spec, new_spec, config)
+}
+
+/**
+Returns the conjunctions and disjunctions of a RTLola specification whose operands can be swapped without changing the value, outer ones first, e.g., for ordering operands by how often they decide the result.
+
+Operands can be swapped if evaluating them cannot fail, i.e., they contain no division, remainder, power, shift, or call of a function or method.
+If the specification does not parse, there are none.
+
+```
+use rtlola_frontend::FrontendConfig;
+
+let spec = "input a: Int32\ninput b: Bool\ntrigger a > 5 && b";
+let short_circuits = rtlola_frontend::short_circuits(spec, FrontendConfig::default());
+assert_eq!(&spec[short_circuits[0].rhs.start..short_circuits[0].rhs.end], "b");
+assert_eq!(rtlola_frontend::swap_operands(spec, &short_circuits), "input a: Int32\ninput b: Bool\ntrigger b && a > 5");
+```
+*/
+pub fn short_circuits(spec_str: &str, config: FrontendConfig) -> Vec<reorder::ShortCircuit> {
+    reorder::short_circuits(spec_str, config)
+}
+
+/**
+Swaps the operands of the given conjunctions and disjunctions of a RTLola specification, see `short_circuits`.
+
+A short circuit overlapping an earlier one in the slice is skipped, e.g., of nested conjunctions only the first one given is swapped.
+*/
+pub fn swap_operands(spec_str: &str, short_circuits: &[reorder::ShortCircuit]) -> String {
+    reorder::swap(spec_str, short_circuits)
+}
@@ -0,0 +1,295 @@
+//! This module resolves names of a specification to their declarations and accesses, see `definition`, `references`, and `rename`.
+
+use crate::analysis::naming::{Declaration, DeclarationTable, NamingAnalysis, KEYWORDS};
+use crate::ast::{Expression, ExpressionKind, RTLolaAst};
+use crate::diagnostics::{DiagnosticCollector, Severity};
+use crate::parse::{NodeId, SourceMapper, Span};
+use crate::reporting::Handler;
+use crate::stdlib::STANDARD_LIBRARY;
+use crate::FrontendConfig;
+use std::path::PathBuf;
+
+/// The location of a name in the specification.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    /// The byte offset of the start in the specification.
+    pub start: usize,
+    /// The byte offset of the end in the specification, exclusive.
+    pub end: usize,
+    /// The line of the start, starting at 1.
+    pub line: usize,
+    /// The column of the start in characters, starting at 1.
+    pub column: usize,
+}
+
+/// A replacement of a name in the specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// The replaced name.
+    pub location: Location,
+    /// The text replacing the name.
+    pub new_text: String,
+}
+
+/// An occurrence of the name of a stream, constant, or parameter.
+#[derive(Debug, Clone, Copy)]
+struct Occurrence {
+    span: Span,
+    /// The node of the declaration the name refers to.
+    declaration: NodeId,
+    /// Whether the occurrence declares the name rather than accessing it.
+    declares: bool,
+}
+
+pub(crate) fn definition(spec: &str, offset: usize, config: FrontendConfig) -> Option<Location> {
+    let occurrences = occurrences(spec, config);
+    let declaration = at(&occurrences, offset)?;
+    let mapper = SourceMapper::new(PathBuf::new(), spec);
+    occurrences.iter().find(|o| o.declares && o.declaration == declaration).map(|o| location(&mapper, o.span))
+}
+
+pub(crate) fn references(spec: &str, offset: usize, config: FrontendConfig) -> Vec<Location> {
+    let occurrences = occurrences(spec, config);
+    let declaration = match at(&occurrences, offset) {
+        Some(declaration) => declaration,
+        None => return Vec::new(),
+    };
+    let mapper = SourceMapper::new(PathBuf::new(), spec);
+    let mut references: Vec<Location> = occurrences
+        .iter()
+        .filter(|o| !o.declares && o.declaration == declaration)
+        .map(|o| location(&mapper, o.span))
+        .collect();
+    references.sort_by_key(|l| l.start);
+    references
+}
+
+pub(crate) fn rename(
+    spec: &str,
+    offset: usize,
+    new_name: &str,
+    config: FrontendConfig,
+) -> Result<Vec<TextEdit>, String> {
+    let (occurrences, errors) = resolve(spec, config).ok_or("the specification does not parse")?;
+    let declaration =
+        at(&occurrences, offset).ok_or("there is no stream, constant, or parameter at the given position")?;
+    let name = occurrences
+        .iter()
+        .find(|o| o.declares && o.declaration == declaration)
+        .map(|o| &spec[o.span.start..o.span.end])
+        .expect("accessed names are declared");
+    if name == new_name {
+        return Ok(Vec::new());
+    }
+    let mut chars = new_name.chars();
+    let valid = chars.next().map_or(false, |c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!("`{}` is not a valid name", new_name));
+    }
+    if KEYWORDS.contains(&new_name.to_lowercase().as_str()) {
+        return Err(format!("`{}` is a reserved keyword", new_name));
+    }
+    let mapper = SourceMapper::new(PathBuf::new(), spec);
+    // parameters of different streams may share a name, but are rejected to rule out shadowing a stream
+    if let Some(other) = occurrences.iter().find(|o| o.declares && &spec[o.span.start..o.span.end] == new_name) {
+        let (line, column) = mapper.get_position(other.span.start);
+        return Err(format!("the name `{}` is already declared at {}:{}", new_name, line, column));
+    }
+    let mut edits: Vec<TextEdit> = occurrences
+        .iter()
+        .filter(|o| o.declaration == declaration)
+        .map(|o| TextEdit { location: location(&mapper, o.span), new_text: new_name.to_string() })
+        .collect();
+    edits.sort_by_key(|e| e.location.start);
+    match resolve(&apply(spec, &edits), config) {
+        Some((_, renamed_errors)) if renamed_errors <= errors => Ok(edits),
+        _ => Err(format!("renaming `{}` to `{}` makes the specification invalid", name, new_name)),
+    }
+}
+
+/// Returns the declarations of streams, constants, parameters, and triggers with the given name.
+pub(crate) fn declarations_named(spec: &str, name: &str, config: FrontendConfig) -> Vec<Location> {
+    let mapper = SourceMapper::new(PathBuf::new(), spec);
+    occurrences(spec, config)
+        .iter()
+        .filter(|o| o.declares && &spec[o.span.start..o.span.end] == name)
+        .map(|o| location(&mapper, o.span))
+        .collect()
+}
+
+/// Applies non-overlapping edits to the specification.
+pub(crate) fn apply(spec: &str, edits: &[TextEdit]) -> String {
+    let mut edits: Vec<&TextEdit> = edits.iter().collect();
+    edits.sort_by_key(|e| e.location.start);
+    let mut result = String::with_capacity(spec.len());
+    let mut pos = 0;
+    for edit in edits {
+        result.push_str(&spec[pos..edit.location.start]);
+        result.push_str(&edit.new_text);
+        pos = edit.location.end;
+    }
+    result.push_str(&spec[pos..]);
+    result
+}
+
+/// Returns the declaration of the name at the offset.
+fn at(occurrences: &[Occurrence], offset: usize) -> Option<NodeId> {
+    occurrences.iter().find(|o| o.span.start <= offset && offset < o.span.end).map(|o| o.declaration)
+}
+
+pub(crate) fn location(mapper: &SourceMapper, span: Span) -> Location {
+    let (line, column) = mapper.get_position(span.start);
+    Location { start: span.start, end: span.end, line, column }
+}
+
+fn occurrences(spec: &str, config: FrontendConfig) -> Vec<Occurrence> {
+    resolve(spec, config).map(|(occurrences, _)| occurrences).unwrap_or_default()
+}
+
+/// Returns the occurrences of names and the number of naming errors, or `None` if the specification does not parse.
+fn resolve(spec: &str, config: FrontendConfig) -> Option<(Vec<Occurrence>, usize)> {
+    let diagnostics = DiagnosticCollector::new();
+    let handler = Handler::with_emitter(SourceMapper::new(PathBuf::new(), spec), Box::new(diagnostics.clone()));
+    let ast = crate::parse::parse(spec, &handler, config).ok()?;
+    let declarations = NamingAnalysis::new(&handler, config, &STANDARD_LIBRARY).check(&ast);
+    let mut names = Names { declarations: &declarations, occurrences: Vec::new() };
+    names.spec(&ast);
+    let errors = diagnostics.diagnostics().iter().filter(|d| d.severity == Severity::Error).count();
+    Some((names.occurrences, errors))
+}
+
+struct Names<'a> {
+    declarations: &'a DeclarationTable,
+    occurrences: Vec<Occurrence>,
+}
+
+impl<'a> Names<'a> {
+    fn spec(&mut self, ast: &RTLolaAst) {
+        for constant in &ast.constants {
+            self.declare(constant.name.span, constant.id);
+            self.expression(&constant.value);
+        }
+        for input in &ast.inputs {
+            self.declare(input.name.span, input.id);
+            input.params.iter().for_each(|param| self.declare(param.name.span, param.id));
+        }
+        for output in &ast.outputs {
+            self.declare(output.name.span, output.id);
+            output.params.iter().for_each(|param| self.declare(param.name.span, param.id));
+            output.extend.expr.iter().for_each(|expr| self.expression(expr));
+            if let Some(template) = &output.template_spec {
+                if let Some(invoke) = &template.inv {
+                    self.expression(&invoke.target);
+                    invoke.condition.iter().for_each(|expr| self.expression(expr));
+                }
+                template.ext.iter().for_each(|extend| self.expression(&extend.target));
+                template.ter.iter().for_each(|terminate| self.expression(&terminate.target));
+            }
+            output.termination.iter().for_each(|expr| self.expression(expr));
+            self.expression(&output.expression);
+        }
+        for trigger in &ast.trigger {
+            if let Some(name) = &trigger.name {
+                self.declare(name.span, trigger.id);
+            }
+            self.expression(&trigger.expression);
+        }
+    }
+
+    fn declare(&mut self, span: Span, declaration: NodeId) {
+        self.occurrences.push(Occurrence { span, declaration, declares: true });
+    }
+
+    fn expression(&mut self, expr: &Expression) {
+        for e in expr.iter() {
+            let span = match &e.kind {
+                ExpressionKind::Let(binding, _) => {
+                    self.declare(binding.name.span, binding.id);
+                    continue;
+                }
+                ExpressionKind::Ident(ident) => ident.span,
+                ExpressionKind::Function(name, _, _) => name.name.span,
+                _ => continue,
+            };
+            let declaration = match self.declarations.get(&e.id) {
+                Some(Declaration::Const(constant)) => constant.id,
+                Some(Declaration::In(input)) => input.id,
+                Some(Declaration::Out(output)) | Some(Declaration::ParamOut(output)) => output.id,
+                Some(Declaration::Param(param)) => param.id,
+                Some(Declaration::Let(binding)) => binding.id,
+                Some(Declaration::Type(_)) | Some(Declaration::Func(_)) | None => continue,
+            };
+            self.occurrences.push(Occurrence { span, declaration, declares: false });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPEC: &str = "constant limit: Int32 := 5\ninput a: Int32\noutput b := a + limit\ntrigger a > limit && b > 0";
+
+    fn starts(locations: &[Location]) -> Vec<usize> {
+        locations.iter().map(|l| l.start).collect()
+    }
+
+    #[test]
+    fn definitions() {
+        let definition = |offset| super::definition(SPEC, offset, FrontendConfig::default());
+        let limit = Location { start: 9, end: 14, line: 1, column: 10 };
+        assert_eq!(definition(SPEC.rfind("limit").unwrap()), Some(limit));
+        assert_eq!(definition(10), Some(limit));
+        assert_eq!(definition(SPEC.rfind("b >").unwrap()), Some(Location { start: 49, end: 50, line: 3, column: 8 }));
+        // keywords, operators, and literals have no declaration
+        assert_eq!(definition(0), None);
+        assert_eq!(definition(SPEC.find('+').unwrap()), None);
+    }
+
+    #[test]
+    fn all_references() {
+        let references = |offset| super::references(SPEC, offset, FrontendConfig::default());
+        assert_eq!(starts(&references(SPEC.find("a:").unwrap())), vec![54, 72]);
+        let limit = references(SPEC.rfind("limit").unwrap());
+        assert_eq!(starts(&limit), vec![58, 76]);
+        assert_eq!((limit[1].line, limit[1].column), (4, 13));
+        assert!(references(SPEC.find('+').unwrap()).is_empty());
+    }
+
+    #[test]
+    fn parameters() {
+        let spec = "input a: Int32\noutput b(p: Int32): Int32 @ a := p + a\noutput c @ a := b(a).offset(by: -1).defaults(to: 0)";
+        let config = FrontendConfig::default();
+        assert_eq!(starts(&super::references(spec, spec.find("p:").unwrap(), config)), vec![48]);
+        assert_eq!(super::definition(spec, spec.find("b(a)").unwrap(), config).map(|l| l.start), Some(22));
+    }
+
+    #[test]
+    fn renaming() {
+        let rename = |offset, new_name| super::rename(SPEC, offset, new_name, FrontendConfig::default());
+        let edits = rename(SPEC.find("a:").unwrap(), "altitude").unwrap();
+        assert_eq!(starts(&edits.iter().map(|e| e.location).collect::<Vec<_>>()), vec![33, 54, 72]);
+        assert_eq!(
+            apply(SPEC, &edits),
+            "constant limit: Int32 := 5\ninput altitude: Int32\noutput b := altitude + limit\ntrigger altitude > limit && b > 0"
+        );
+        assert_eq!(apply(SPEC, &rename(SPEC.rfind("b").unwrap(), "c").unwrap()).matches("c :=").count(), 1);
+        assert_eq!(rename(33, "a"), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn invalid_renamings() {
+        let rename = |offset, new_name| super::rename(SPEC, offset, new_name, FrontendConfig::default());
+        assert_eq!(rename(33, "b"), Err(String::from("the name `b` is already declared at 3:8")));
+        assert_eq!(rename(33, "output"), Err(String::from("`output` is a reserved keyword")));
+        assert_eq!(rename(33, "1a"), Err(String::from("`1a` is not a valid name")));
+        // `constant` is not reserved, but starts a declaration, so the trigger does not parse
+        assert_eq!(
+            rename(33, "constant"),
+            Err(String::from("renaming `a` to `constant` makes the specification invalid"))
+        );
+        assert!(rename(0, "c").is_err());
+        assert!(super::rename("input a: Int32\noutput", 6, "c", FrontendConfig::default()).is_err());
+    }
+}
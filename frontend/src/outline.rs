@@ -0,0 +1,149 @@
+//! This module lists the declarations of a specification as outline, see `outline`.
+
+use crate::analysis;
+use crate::ast::{Type, TypeKind};
+use crate::diagnostics::DiagnosticCollector;
+use crate::navigation::Location;
+use crate::parse::{Ident, NodeId, SourceMapper, Span};
+use crate::reporting::Handler;
+use crate::stdlib::STANDARD_LIBRARY;
+use crate::FrontendConfig;
+use std::path::PathBuf;
+
+/// The kind of a declaration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SymbolKind {
+    /// A constant stream.
+    Constant,
+    /// An input stream.
+    Input,
+    /// An output stream.
+    Output,
+    /// A parameter of an input or output stream.
+    Parameter,
+    /// A trigger.
+    Trigger,
+}
+
+/// A declaration of a specification.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// The kind of the declaration.
+    pub kind: SymbolKind,
+    /// The name of the declaration; unnamed triggers are named by their message or, without message, by their expression, e.g., `a > 5`.
+    pub name: String,
+    /// The value type, inferred if the specification is valid and as annotated otherwise; triggers have none.
+    pub ty: Option<String>,
+    /// The whole declaration.
+    pub span: Location,
+    /// The name in the declaration, or the expression of an unnamed trigger.
+    pub name_span: Location,
+    /// The parameters of a parameterized stream.
+    pub children: Vec<Symbol>,
+}
+
+/// Returns the declarations of the specification in the order they appear, or none if the specification does not parse.
+pub(crate) fn outline(spec: &str, config: FrontendConfig) -> Vec<Symbol> {
+    let handler = Handler::with_emitter(SourceMapper::new(PathBuf::new(), spec), Box::new(DiagnosticCollector::new()));
+    let ast = match crate::parse::parse(spec, &handler, config) {
+        Ok(ast) => ast,
+        Err(_) => return Vec::new(),
+    };
+    let report = analysis::analyze(&ast, &handler, config, &STANDARD_LIBRARY).ok();
+    let mapper = SourceMapper::new(PathBuf::new(), spec);
+    let location = |span: Span| {
+        let (line, column) = mapper.get_position(span.start);
+        Location { start: span.start, end: span.end, line, column }
+    };
+    // the inferred type if the specification is valid, the annotated type otherwise
+    let ty = |id: NodeId, annotated: Option<&Type>| match report.as_ref().and_then(|r| r.type_table.find_value_type(id))
+    {
+        Some(ty) => Some(ty.to_string()),
+        None => annotated.filter(|ty| !matches!(ty.kind, TypeKind::Inferred)).map(ToString::to_string),
+    };
+    let symbol = |kind, name: &Ident, ty, span, children| Symbol {
+        kind,
+        name: name.name.clone(),
+        ty,
+        span: location(span),
+        name_span: location(name.span),
+        children,
+    };
+    let parameters = |params: &[std::rc::Rc<crate::ast::Parameter>]| -> Vec<Symbol> {
+        params
+            .iter()
+            .map(|p| symbol(SymbolKind::Parameter, &p.name, ty(p.id, Some(&p.ty)), p.span, Vec::new()))
+            .collect()
+    };
+    let mut symbols = Vec::new();
+    for constant in &ast.constants {
+        let value_ty = ty(constant.id, constant.ty.as_ref());
+        symbols.push(symbol(SymbolKind::Constant, &constant.name, value_ty, constant.span, Vec::new()));
+    }
+    for input in &ast.inputs {
+        let value_ty = ty(input.id, Some(&input.ty));
+        symbols.push(symbol(SymbolKind::Input, &input.name, value_ty, input.span, parameters(&input.params)));
+    }
+    for output in &ast.outputs {
+        let value_ty = ty(output.id, Some(&output.ty));
+        let kind = if output.derived_input { SymbolKind::Input } else { SymbolKind::Output };
+        symbols.push(symbol(kind, &output.name, value_ty, output.span, parameters(&output.params)));
+    }
+    for trigger in &ast.trigger {
+        let (name, name_span) = match (&trigger.name, &trigger.message) {
+            (Some(name), _) => (name.name.clone(), name.span),
+            (None, Some(message)) => (message.clone(), trigger.expression.span),
+            (None, None) => (trigger.expression.to_string(), trigger.expression.span),
+        };
+        symbols.push(Symbol {
+            kind: SymbolKind::Trigger,
+            name,
+            ty: None,
+            span: location(trigger.span),
+            name_span: location(name_span),
+            children: Vec::new(),
+        });
+    }
+    symbols.sort_by_key(|s| s.span.start);
+    symbols
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the kind, name, and type of each symbol.
+    fn symbols(spec: &str) -> Vec<(SymbolKind, String, Option<String>)> {
+        outline(spec, FrontendConfig::default()).into_iter().map(|s| (s.kind, s.name, s.ty)).collect()
+    }
+
+    #[test]
+    fn declarations() {
+        use SymbolKind::*;
+        let spec = "input a: Int32\nconstant limit: Int32 := 5\noutput b(p: Int32) @ a := p + a\ntrigger a > limit\ntrigger a > 10 \"too high\"";
+        assert_eq!(
+            symbols(spec),
+            vec![
+                (Input, String::from("a"), Some(String::from("Int32"))),
+                (Constant, String::from("limit"), Some(String::from("Int32"))),
+                (Output, String::from("b"), Some(String::from("Int32"))),
+                (Trigger, String::from("a > limit"), None),
+                (Trigger, String::from("too high"), None),
+            ]
+        );
+        let outline = outline(spec, FrontendConfig::default());
+        let b = &outline[2];
+        assert_eq!((b.name_span.start, b.name_span.line, b.name_span.column), (49, 3, 8));
+        assert_eq!(b.children.len(), 1);
+        assert_eq!((b.children[0].name.as_str(), b.children[0].ty.as_deref()), ("p", Some("Int32")));
+    }
+
+    #[test]
+    fn invalid_specifications() {
+        // the types are the annotated ones if the analysis fails
+        let spec = "input a: Int32\noutput b: Float64 := a + c\noutput d := a";
+        let types: Vec<Option<String>> = symbols(spec).into_iter().map(|(_, _, ty)| ty).collect();
+        assert_eq!(types, vec![Some(String::from("Int32")), Some(String::from("Float64")), None]);
+        assert!(outline("input a: Int32\noutput b := a;", FrontendConfig::default()).is_empty());
+    }
+}
@@ -1,14 +1,19 @@
 //! This module contains the parser for the Lola Language.
 
 use super::ast::*;
-use crate::reporting::{Handler, LabeledSpan};
+use crate::reporting::{Code, Handler, LabeledSpan};
 use crate::FrontendConfig;
 use lazy_static::lazy_static;
 use pest::iterators::{Pair, Pairs};
 use pest::prec_climber::{Assoc, Operator, PrecClimber};
 use pest::Parser;
 use pest_derive::Parser;
-use std::{cell::RefCell, path::PathBuf, rc::Rc};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    path::{Path, PathBuf},
+    rc::Rc,
+};
 
 #[derive(Parser)]
 #[grammar = "lola.pest"]
@@ -20,9 +25,25 @@ pub(crate) struct RTLolaParser<'a, 'b> {
     spec: RTLolaAst,
     handler: &'b Handler,
     config: FrontendConfig,
+    /// The enabled features deciding which declarations with `cfg` attributes are kept.
+    features: &'b [String],
     node_id: RefCell<NodeId>,
+    /// The offset of the content in the source mapper, which is non-zero for imported files.
+    offset: usize,
+    /// The parsed file and the files importing it, innermost last, or empty if file imports are not resolved.
+    importing: Vec<PathBuf>,
+    /// The names of the declared enumerations, whose paths, e.g., `Mode::Armed`, are variants rather than stream names, e.g., `ATTITUDE::roll`.
+    enumerations: HashSet<String>,
 }
 
+/// The annotations of streams and triggers, given by their name, the rules of the declarations they annotate, and their possible arguments, of which exactly one is given if there are any.
+/// Tools processing the AST or the IR can rely on annotations being valid, new ones are added here.
+const ANNOTATIONS: [(&str, &[Rule], &[&str]); 3] = [
+    ("deprecated", &[Rule::InputStream, Rule::OutputStream, Rule::DerivedInput, Rule::DestructuringOutput], &[]),
+    ("memory", &[Rule::InputStream, Rule::OutputStream, Rule::DerivedInput, Rule::DestructuringOutput], &["bounded"]),
+    ("severity", &[Rule::Trigger, Rule::Assumption], &["info", "warning", "error"]),
+];
+
 lazy_static! {
     // precedence taken from C/C++: https://en.wikipedia.org/wiki/Operators_in_C_and_C++
     // Precedence climber can be used to build the AST, see https://pest-parser.github.io/book/ for more details
@@ -42,7 +63,7 @@ lazy_static! {
             Operator::new(Add, Left) | Operator::new(Subtract, Left),
             Operator::new(Multiply, Left) | Operator::new(Divide, Left) | Operator::new(Mod, Left),
             Operator::new(Power, Right),
-            Operator::new(Dot, Left),
+            Operator::new(Dot, Left) | Operator::new(As, Left),
             Operator::new(OpeningBracket, Left),
         ])
     };
@@ -50,7 +71,22 @@ lazy_static! {
 
 impl<'a, 'b> RTLolaParser<'a, 'b> {
     pub(crate) fn new(content: &'a str, handler: &'b Handler, config: FrontendConfig) -> Self {
-        RTLolaParser { content, spec: RTLolaAst::new(), handler, config, node_id: RefCell::new(NodeId::new(0)) }
+        RTLolaParser {
+            content,
+            spec: RTLolaAst::new(),
+            handler,
+            config,
+            features: &[],
+            node_id: RefCell::new(NodeId::new(0)),
+            offset: 0,
+            importing: Vec::new(),
+            enumerations: HashSet::new(),
+        }
+    }
+
+    /// Converts a span of the parsed content into a span of the source mapper.
+    fn span(&self, span: pest::Span<'_>) -> Span {
+        Span { start: self.offset + span.start(), end: self.offset + span.end() }
     }
 
     fn next_id(&self) -> NodeId {
@@ -60,46 +96,284 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
     }
 
     pub(crate) fn parse(mut self) -> Result<RTLolaAst, pest::error::Error<Rule>> {
+        self.parse_declarations()?;
+        Ok(self.spec)
+    }
+
+    fn parse_declarations(&mut self) -> Result<(), pest::error::Error<Rule>> {
         let mut pairs = LolaParser::parse(Rule::Spec, self.content)?;
-        assert!(pairs.clone().count() == 1, "Spec must not be empty.");
-        let spec_pair = pairs.next().unwrap();
-        assert!(spec_pair.as_rule() == Rule::Spec);
+        let spec_pair = pairs.next().expect("Spec must not be empty.");
+        assert!(pairs.next().is_none() && spec_pair.as_rule() == Rule::Spec);
+        // enumerations can be used before their declaration
+        self.enumerations.extend(
+            spec_pair
+                .clone()
+                .into_inner()
+                .filter(|pair| pair.as_rule() == Rule::EnumDecl)
+                .map(|pair| pair.into_inner().next().expect("mismatch between grammar and AST").as_str().to_string()),
+        );
+        let mut attributes = Vec::new();
         for pair in spec_pair.into_inner() {
+            if pair.as_rule() == Rule::Attribute || pair.as_rule() == Rule::CfgAttribute {
+                attributes.push(pair);
+                continue;
+            }
+            let span = self.span(pair.as_span());
+            let mut enabled = true;
+            let mut annotations = Vec::new();
+            for attribute in attributes.drain(..) {
+                if attribute.as_rule() == Rule::CfgAttribute {
+                    let predicate = attribute.into_inner().next().expect("mismatch between grammar and AST");
+                    enabled &= self.holds(predicate);
+                } else {
+                    annotations.extend(self.parse_attribute(attribute, span, pair.as_rule()));
+                }
+            }
+            // declarations of disabled features are removed before the analysis
+            if !enabled {
+                continue;
+            }
             match pair.as_rule() {
                 Rule::ImportStmt => {
                     let import = self.parse_import(pair);
                     self.spec.imports.push(import);
                 }
+                Rule::FileImport => self.import_file(pair),
                 Rule::ConstantStream => {
                     let constant = self.parse_constant(pair);
                     self.spec.constants.push(Rc::new(constant));
                 }
                 Rule::InputStream => {
                     let inputs = self.parse_inputs(pair);
-                    self.spec.inputs.extend(inputs.into_iter().map(Rc::new));
+                    // the annotations apply to all inputs of the declaration
+                    let annotated = inputs.into_iter().map(|input| Input { annotations: annotations.clone(), ..input });
+                    self.spec.inputs.extend(annotated.map(Rc::new));
                 }
-                Rule::OutputStream => {
-                    let output = self.parse_output(pair);
-                    self.spec.outputs.push(Rc::new(output));
+                Rule::OutputStream | Rule::DerivedInput | Rule::DestructuringOutput => {
+                    let outputs = if pair.as_rule() == Rule::DestructuringOutput {
+                        self.parse_destructuring_output(pair)
+                    } else {
+                        self.parse_outputs(pair)
+                    };
+                    // the annotations apply to all outputs of the declaration
+                    let annotated =
+                        outputs.into_iter().map(|output| Output { annotations: annotations.clone(), ..output });
+                    self.spec.outputs.extend(annotated.map(Rc::new));
                 }
-                Rule::Trigger => {
+                Rule::Trigger | Rule::Assumption => {
                     let trigger = self.parse_trigger(pair);
-                    self.spec.trigger.push(Rc::new(trigger));
+                    self.spec.trigger.push(Rc::new(Trigger { annotations, ..trigger }));
                 }
                 Rule::TypeDecl => {
                     let type_decl = self.parse_type_declaration(pair);
                     self.spec.type_declarations.push(type_decl);
                 }
+                Rule::TypeAlias => {
+                    let alias = self.parse_type_alias(pair);
+                    self.spec.type_aliases.push(alias);
+                }
+                Rule::EnumDecl => {
+                    let declaration = self.parse_enum_declaration(pair);
+                    self.spec.enum_declarations.push(declaration);
+                }
                 Rule::EOI => {}
                 _ => unreachable!(),
             }
         }
-        Ok(self.spec)
+        Ok(())
+    }
+
+    /**
+     * Applies a `Rule::Attribute` to the declaration spanning `declaration` with the given rule.
+     * The attribute `allow` suppresses the warnings with the given codes within the declaration; `cfg` attributes are `Rule::CfgAttribute`s.
+     * All other attributes are annotations of streams and triggers, see `ANNOTATIONS`, which are returned if they are valid.
+     */
+    fn parse_attribute(&self, pair: Pair<'_, Rule>, declaration: Span, rule: Rule) -> Option<Annotation> {
+        assert_eq!(pair.as_rule(), Rule::Attribute);
+        let span = self.span(pair.as_span());
+        let mut pairs = pair.into_inner();
+        let name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
+        if name.name == "cfg" {
+            self.handler.error_with_span(
+                Code::InvalidAttribute,
+                "invalid condition of `cfg` attribute",
+                LabeledSpan::new(name.span, "expected `feature = \"NAME\"`, `not`, `any`, or `all`", true),
+            );
+            return None;
+        }
+        if name.name != "allow" {
+            return self.parse_annotation(name, pairs, span, rule);
+        }
+        let mut pairs = pairs.peekable();
+        if pairs.peek().is_none() {
+            self.handler.error_with_span(
+                Code::InvalidAttribute,
+                "missing codes of `allow` attribute",
+                LabeledSpan::new(span, "expected codes, e.g., `#[allow(W0201)]`", true),
+            );
+        }
+        for arg in pairs {
+            let span = self.span(arg.as_span());
+            match arg.as_str().parse::<Code>() {
+                Ok(code) if code.is_warning() => self.handler.allow_in(declaration, code),
+                Ok(code) => self.handler.error_with_span(
+                    Code::InvalidAttribute,
+                    &format!("only warnings can be allowed, but `{}` is an error", code),
+                    LabeledSpan::new(span, "not a warning", true),
+                ),
+                Err(message) => self.handler.error_with_span(
+                    Code::InvalidAttribute,
+                    &message,
+                    LabeledSpan::new(span, "unknown code", true),
+                ),
+            }
+        }
+        None
+    }
+
+    /**
+     * Checks that the attribute with the given name and arguments is an annotation of the declaration with the given rule, see `ANNOTATIONS`.
+     */
+    fn parse_annotation(&self, name: Ident, args: Pairs<'_, Rule>, span: Span, rule: Rule) -> Option<Annotation> {
+        let args: Vec<Ident> = args.map(|arg| Ident::new(arg.as_str().to_string(), self.span(arg.as_span()))).collect();
+        let (_, rules, values) = match ANNOTATIONS.iter().find(|(annotation, ..)| *annotation == name.name) {
+            Some(annotation) => annotation,
+            None => {
+                let known: Vec<String> =
+                    ANNOTATIONS.iter().map(|(annotation, ..)| format!("`{}`", annotation)).collect();
+                self.handler.error_with_span(
+                    Code::InvalidAttribute,
+                    &format!("unknown attribute `{}`", name.name),
+                    LabeledSpan::new(name.span, &format!("expected one of `allow`, `cfg`, {}", known.join(", ")), true),
+                );
+                return None;
+            }
+        };
+        if !rules.contains(&rule) {
+            let annotated = if rules.contains(&Rule::Trigger) { "triggers" } else { "streams" };
+            self.handler.error_with_span(
+                Code::InvalidAttribute,
+                &format!("the attribute `{}` only annotates {}", name.name, annotated),
+                LabeledSpan::new(name.span, "not allowed here", true),
+            );
+            return None;
+        }
+        let valid = if values.is_empty() {
+            args.is_empty()
+        } else {
+            args.len() == 1 && values.contains(&args[0].name.as_str())
+        };
+        if !valid {
+            let expected = if values.is_empty() {
+                String::from("expected no arguments")
+            } else {
+                let values: Vec<String> = values.iter().map(|value| format!("`{}`", value)).collect();
+                format!("expected one of {}", values.join(", "))
+            };
+            self.handler.error_with_span(
+                Code::InvalidAttribute,
+                &format!("invalid arguments of attribute `{}`", name.name),
+                LabeledSpan::new(span, &expected, true),
+            );
+            return None;
+        }
+        Some(Annotation { name, args, span })
+    }
+
+    /**
+     * Evaluates the predicate of a `Rule::CfgAttribute` over the enabled features.
+     */
+    fn holds(&self, pair: Pair<'_, Rule>) -> bool {
+        match pair.as_rule() {
+            Rule::CfgFeature => {
+                let name = pair.into_inner().next().expect("mismatch between grammar and AST").as_str();
+                self.features.iter().any(|feature| feature == name)
+            }
+            Rule::CfgNot => !self.holds(pair.into_inner().next().expect("mismatch between grammar and AST")),
+            Rule::CfgAny => pair.into_inner().any(|p| self.holds(p)),
+            Rule::CfgAll => pair.into_inner().all(|p| self.holds(p)),
+            _ => unreachable!(),
+        }
+    }
+
+    /**
+     * Resolves a `Rule::FileImport` relative to the importing file and adds the declarations of the imported file to the specification.
+     * A file imported more than once is only added once, a file importing itself is reported as cyclic.
+     */
+    fn import_file(&mut self, pair: Pair<'_, Rule>) {
+        assert_eq!(pair.as_rule(), Rule::FileImport);
+        let importing = match self.importing.last() {
+            Some(importing) => importing,
+            None => return,
+        };
+        let span = self.span(pair.as_span());
+        let name = pair.into_inner().next().expect("mismatch between grammar and AST").as_str();
+        let path = importing.parent().unwrap_or_else(|| Path::new("")).join(name);
+        if let Some(ix) = self.importing.iter().position(|file| canonical(file) == canonical(&path)) {
+            let cycle: Vec<String> = self.importing[ix..].iter().map(|file| file.display().to_string()).collect();
+            let mut builder = self.handler.build_error_with_span(
+                Code::CyclicImport,
+                &format!("cyclic import of `{}`", name),
+                LabeledSpan::new(span, "imported here", true),
+            );
+            builder.add_note(&format!("the import cycle is {} -> {}", cycle.join(" -> "), path.display()));
+            builder.emit();
+            return;
+        }
+        if self.handler.mapper().contains(&path) {
+            return;
+        }
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(e) => {
+                self.handler.error_with_span(
+                    Code::UnresolvedImport,
+                    &format!("cannot read `{}`: {}", path.display(), e),
+                    LabeledSpan::new(span, "imported here", true),
+                );
+                return;
+            }
+        };
+        let offset = self.handler.mapper().add_file(path.clone(), &content);
+        let mut importing = self.importing.clone();
+        importing.push(path);
+        // the node ids continue in the imported file, so they are unique in the merged specification
+        let node_id = RefCell::new(*self.node_id.borrow());
+        let mut parser = RTLolaParser {
+            features: self.features,
+            node_id,
+            offset,
+            importing,
+            ..RTLolaParser::new(&content, self.handler, self.config)
+        };
+        if let Err(e) = parser.parse_declarations() {
+            self.handler.syntax_error_at(&e, offset);
+        }
+        *self.node_id.borrow_mut() = *parser.node_id.borrow();
+        let RTLolaAst {
+            imports,
+            constants,
+            inputs,
+            outputs,
+            trigger,
+            type_declarations,
+            type_aliases,
+            enum_declarations,
+        } = parser.spec;
+        self.spec.imports.extend(imports);
+        self.spec.constants.extend(constants);
+        self.spec.inputs.extend(inputs);
+        self.spec.outputs.extend(outputs);
+        self.spec.trigger.extend(trigger);
+        self.spec.type_declarations.extend(type_declarations);
+        self.spec.type_aliases.extend(type_aliases);
+        self.enumerations.extend(parser.enumerations);
+        self.spec.enum_declarations.extend(enum_declarations);
     }
 
     fn parse_import(&self, pair: Pair<Rule>) -> Import {
         assert_eq!(pair.as_rule(), Rule::ImportStmt);
-        let span = pair.as_span().into();
+        let span = self.span(pair.as_span());
         let mut pairs = pair.into_inner();
         let name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
         Import { name, id: self.next_id(), span }
@@ -111,16 +385,20 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
      * The constant rule consists of the following tokens:
      * - `Rule::Ident`
      * - `Rule::Type`
-     * - `Rule::Literal`
+     * - `Rule::Expr`
      */
     fn parse_constant(&self, pair: Pair<'_, Rule>) -> Constant {
         assert_eq!(pair.as_rule(), Rule::ConstantStream);
-        let span = pair.as_span().into();
-        let mut pairs = pair.into_inner();
+        let span = self.span(pair.as_span());
+        let mut pairs = pair.into_inner().peekable();
+        let configurable = pairs.peek().map_or(false, |p| p.as_rule() == Rule::Configurable);
+        if configurable {
+            pairs.next();
+        }
         let name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
         let ty = self.parse_type(pairs.next().expect("mismatch between grammar and AST"));
-        let literal = self.parse_literal(pairs.next().expect("mismatch between grammar and AST"));
-        Constant { id: self.next_id(), name, ty: Some(ty), literal, span }
+        let value = self.build_expression_ast(pairs.next().expect("mismatch between grammar and AST").into_inner());
+        Constant { id: self.next_id(), name, ty: Some(ty), value, configurable, span }
     }
 
     /**
@@ -136,7 +414,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
         let mut inputs = Vec::new();
         let mut pairs = pair.into_inner();
         while let Some(pair) = pairs.next() {
-            let start = pair.as_span().start();
+            let start = self.offset + pair.as_span().start();
             let name = self.parse_ident(&pair);
 
             let mut pair = pairs.next().expect("mismatch between grammar and AST");
@@ -146,6 +424,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
 
                 if !self.config.allow_parameters {
                     self.handler.error_with_span(
+                        Code::ParametersDisabled,
                         "Parameterization is disabled",
                         LabeledSpan::new(res[0].span, "found parameter", true),
                     )
@@ -155,13 +434,14 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
             } else {
                 Vec::new()
             };
-            let end = pair.as_span().end();
+            let end = self.offset + pair.as_span().end();
             let ty = self.parse_type(pair);
             inputs.push(Input {
                 id: self.next_id(),
                 name,
                 params: params.into_iter().map(Rc::new).collect(),
                 ty,
+                annotations: Vec::new(),
                 span: Span { start, end },
             })
         }
@@ -171,18 +451,38 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
     }
 
     /**
-     * Transforms a `Rule::OutputStream` into `Output` AST node.
-     * Panics if input is not `Rule::OutputStream`.
-     * The output rule consists of the following tokens:
+     * Transforms a `Rule::OutputStream` or `Rule::DerivedInput` into `Output` AST nodes, one for each comma-separated declaration.
+     * Panics if input is neither `Rule::OutputStream` nor `Rule::DerivedInput`.
+     * The output rule consists of non-empty sequences of the following tokens:
      * - `Rule::Ident`
-     * - `Rule::Type`
+     * - (`Rule::ParamList`)?
+     * - (`Rule::Type`)?
+     * - (`Rule::ActivationCondition`)?
+     * - (`Rule::TemplateSpec`)?
+     * - (`Rule::TerminateDecl`)?
      * - `Rule::Expr`
      */
-    fn parse_output(&self, pair: Pair<'_, Rule>) -> Output {
-        assert_eq!(pair.as_rule(), Rule::OutputStream);
-        let span = pair.as_span().into();
+    fn parse_outputs(&self, pair: Pair<'_, Rule>) -> Vec<Output> {
+        assert!(pair.as_rule() == Rule::OutputStream || pair.as_rule() == Rule::DerivedInput);
+        let derived_input = pair.as_rule() == Rule::DerivedInput;
+        // the first output spans the keyword of the declaration
+        let start = self.offset + pair.as_span().start();
+        let mut outputs = Vec::new();
         let mut pairs = pair.into_inner();
-        let name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
+        while let Some(pair) = pairs.next() {
+            let output = self.parse_output(pair, &mut pairs, derived_input);
+            let start = if outputs.is_empty() { start } else { output.span.start };
+            outputs.push(Output { span: Span { start, ..output.span }, ..output });
+        }
+
+        assert!(!outputs.is_empty());
+        outputs
+    }
+
+    /// Transforms the tokens of a single output declaration starting with its name into an `Output` AST node.
+    fn parse_output(&self, name: Pair<'_, Rule>, pairs: &mut Pairs<'_, Rule>, derived_input: bool) -> Output {
+        let start = self.offset + name.as_span().start();
+        let name = self.parse_ident(&name);
 
         let mut pair = pairs.next().expect("mismatch between grammar and AST");
         let params = if let Rule::ParamList = pair.as_rule() {
@@ -191,6 +491,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
 
             if !self.config.allow_parameters {
                 self.handler.error_with_span(
+                    Code::ParametersDisabled,
                     "Parameterization is disabled",
                     LabeledSpan::new(res[0].span, "found parameter", true),
                 )
@@ -211,7 +512,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
 
         // Parse the `@ [Expr]` part of output declaration
         let extend = if let Rule::ActivationCondition = pair.as_rule() {
-            let span: Span = pair.as_span().into();
+            let span: Span = self.span(pair.as_span());
             let expr = self.build_expression_ast(pair.into_inner());
             pair = pairs.next().expect("mismatch between grammar and AST");
             ActivationCondition { expr: Some(expr), id: self.next_id(), span }
@@ -221,8 +522,16 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
 
         let mut tspec = None;
         if let Rule::TemplateSpec = pair.as_rule() {
-            tspec = Some(self.parse_template_spec(pair));
+            let template = self.parse_template_spec(pair);
             pair = pairs.next().expect("mismatch between grammar and AST");
+            if params.is_empty() {
+                self.handler.error_with_span(
+                    Code::TemplateWithoutParameters,
+                    "Templates are only allowed for parameterized streams",
+                    LabeledSpan::new(template.span, "found template", true),
+                )
+            }
+            tspec = Some(template);
         };
 
         // Parse termination condition `close EXPRESSION`
@@ -233,12 +542,14 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
 
             if !self.config.allow_parameters {
                 self.handler.error_with_span(
+                    Code::ParametersDisabled,
                     "Parameterization is disabled",
                     LabeledSpan::new(expr.span, "found termination condition", true),
                 )
             }
             if params.is_empty() {
                 self.handler.error_with_span(
+                    Code::TerminationWithoutParameters,
                     "Termination condition is only allowed for parameterized streams",
                     LabeledSpan::new(expr.span, "found termination condition", true),
                 )
@@ -250,6 +561,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
         };
 
         // Parse expression
+        let span = Span { start, end: self.offset + pair.as_span().end() };
         let expression = self.build_expression_ast(pair.into_inner());
         Output {
             id: self.next_id(),
@@ -260,15 +572,74 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
             template_spec: tspec,
             termination,
             expression,
+            derived_input,
+            annotations: Vec::new(),
             span,
         }
     }
 
+    /**
+     * Transforms a `Rule::DestructuringOutput` into `Output` AST nodes, one for each component of the tuple.
+     * The component `i` is declared as `NAME := (EXPRESSION).i` with the activation condition of the declaration.
+     */
+    fn parse_destructuring_output(&self, pair: Pair<'_, Rule>) -> Vec<Output> {
+        assert_eq!(pair.as_rule(), Rule::DestructuringOutput);
+        let span = self.span(pair.as_span());
+        let mut pairs = pair.into_inner();
+        let mut names = Vec::new();
+        let mut pair = pairs.next().expect("mismatch between grammar and AST");
+        while let Rule::Ident = pair.as_rule() {
+            names.push(self.parse_ident(&pair));
+            pair = pairs.next().expect("mismatch between grammar and AST");
+        }
+        let activation = if let Rule::ActivationCondition = pair.as_rule() {
+            let activation = pair;
+            pair = pairs.next().expect("mismatch between grammar and AST");
+            Some(activation)
+        } else {
+            None
+        };
+
+        // each component parses the shared expression anew, so all nodes have distinct ids
+        let mut outputs = Vec::new();
+        for (ix, name) in names.into_iter().enumerate() {
+            let extend = match &activation {
+                Some(activation) => ActivationCondition {
+                    expr: Some(self.build_expression_ast(activation.clone().into_inner())),
+                    id: self.next_id(),
+                    span: self.span(activation.as_span()),
+                },
+                None => ActivationCondition { expr: None, id: self.next_id(), span: Span::unknown() },
+            };
+            let tuple = self.build_expression_ast(pair.clone().into_inner());
+            let tuple_span = tuple.span;
+            let parenthesis = || Some(Box::new(Parenthesis::new(self.next_id(), tuple_span)));
+            let kind = ExpressionKind::ParenthesizedExpression(parenthesis(), Box::new(tuple), parenthesis());
+            let tuple = Expression::new(self.next_id(), kind, tuple_span);
+            let field = Ident::new(ix.to_string(), name.span);
+            let expression = Expression::new(self.next_id(), ExpressionKind::Field(Box::new(tuple), field), tuple_span);
+            outputs.push(Output {
+                id: self.next_id(),
+                name,
+                ty: Type::new_inferred(self.next_id()),
+                extend,
+                params: Vec::new(),
+                template_spec: None,
+                termination: None,
+                expression,
+                derived_input: false,
+                annotations: Vec::new(),
+                span,
+            });
+        }
+        outputs
+    }
+
     fn parse_parameter_list(&self, param_list: Pairs<'_, Rule>) -> Vec<Parameter> {
         let mut params = Vec::new();
         for param_decl in param_list {
             assert_eq!(Rule::ParameterDecl, param_decl.as_rule());
-            let span = param_decl.as_span().into();
+            let span = self.span(param_decl.as_span());
             let mut decl = param_decl.into_inner();
             let name = self.parse_ident(&decl.next().expect("mismatch between grammar and AST"));
             let ty = if let Some(type_pair) = decl.next() {
@@ -283,7 +654,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
     }
 
     fn parse_template_spec(&self, pair: Pair<'_, Rule>) -> TemplateSpec {
-        let span = pair.as_span().into();
+        let span = self.span(pair.as_span());
         let mut decls = pair.into_inner();
         let mut pair = decls.next();
         let mut rule = pair.as_ref().map(Pair::as_rule);
@@ -303,7 +674,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
         let mut ter_spec = None;
         if let Some(Rule::TerminateDecl) = rule {
             let exp = pair.unwrap();
-            let span_ter = exp.as_span().into();
+            let span_ter = self.span(exp.as_span());
             let expr = exp.into_inner().next().expect("mismatch between grammar and AST");
             let expr = self.build_expression_ast(expr.into_inner());
             ter_spec = Some(TerminateSpec { target: expr, id: self.next_id(), span: span_ter });
@@ -312,7 +683,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
     }
 
     fn parse_ext_spec(&self, ext_pair: Pair<'_, Rule>) -> ExtendSpec {
-        let span_ext = ext_pair.as_span().into();
+        let span_ext = self.span(ext_pair.as_span());
         let mut children = ext_pair.into_inner();
 
         let first_child = children.next().expect("mismatch between grammar and ast");
@@ -324,7 +695,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
     }
 
     fn parse_inv_spec(&self, inv_pair: Pair<'_, Rule>) -> InvokeSpec {
-        let span_inv = inv_pair.as_span().into();
+        let span_inv = self.span(inv_pair.as_span());
         let mut inv_children = inv_pair.into_inner();
         let expr_pair = inv_children.next().expect("mismatch between grammar and AST");
         let inv_target = self.build_expression_ast(expr_pair.into_inner());
@@ -344,20 +715,25 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
     }
 
     /**
-     * Transforms a `Rule::Trigger` into `Trigger` AST node.
-     * Panics if input is not `Rule::Trigger`.
+     * Transforms a `Rule::Trigger` or `Rule::Assumption` into `Trigger` AST node.
+     * Panics if input is neither `Rule::Trigger` nor `Rule::Assumption`.
      * The output rule consists of the following tokens:
      * - (`Rule::Ident`)?
      * - `Rule::Expr`
+     * - (`Rule::TriggerHold`)?
+     * - (`Rule::TriggerRelease`)?
+     * - (`Rule::TriggerEscalation`)?
      * - (`Rule::StringLiteral`)?
      */
     fn parse_trigger(&self, pair: Pair<'_, Rule>) -> Trigger {
-        assert_eq!(pair.as_rule(), Rule::Trigger);
-        let span = pair.as_span().into();
+        assert!(pair.as_rule() == Rule::Trigger || pair.as_rule() == Rule::Assumption);
+        let assumption = pair.as_rule() == Rule::Assumption;
+        let span = self.span(pair.as_span());
         let mut pairs = pair.into_inner();
 
         let mut name = None;
         let mut message = None;
+        let mut escalation = None;
 
         let mut pair = pairs.next().expect("mismatch between grammar and AST");
         // first token is either expression or identifier
@@ -365,14 +741,70 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
             name = Some(self.parse_ident(&pair));
             pair = pairs.next().expect("mismatch between grammar and AST");
         }
-        let expression = self.build_expression_ast(pair.into_inner());
+        let mut expression = self.build_expression_ast(pair.into_inner());
 
-        if let Some(pair) = pairs.next() {
-            assert_eq!(pair.as_rule(), Rule::String);
-            message = Some(pair.as_str().to_string());
+        // `for` and `until` are lowered as the builtins `debounce` and `hysteresis`
+        for pair in pairs {
+            match pair.as_rule() {
+                Rule::TriggerHold | Rule::TriggerRelease => {
+                    let (function, label) =
+                        if pair.as_rule() == Rule::TriggerHold { ("debounce", "for") } else { ("hysteresis", "off") };
+                    let clause: Span = self.span(pair.as_span());
+                    let inner = pair.into_inner().next().expect("mismatch between grammar and AST");
+                    let arg = self.build_expression_ast(inner.into_inner());
+                    let name = FunctionName {
+                        name: Ident::new(function.to_string(), clause),
+                        arg_names: vec![None, Some(Ident::new(label.to_string(), clause))],
+                    };
+                    let call_span = Span { start: expression.span.start, end: clause.end };
+                    let kind = ExpressionKind::Function(name, Vec::new(), vec![Box::new(expression), Box::new(arg)]);
+                    expression = Expression::new(self.next_id(), kind, call_span);
+                }
+                Rule::TriggerEscalation => {
+                    let span = self.span(pair.as_span());
+                    let mut literals = pair.into_inner().map(|literal| self.parse_literal(literal));
+                    let after = literals.next().expect("mismatch between grammar and AST");
+                    let within = literals.next().expect("mismatch between grammar and AST");
+                    escalation = Some(Escalation { after, within, span });
+                }
+                Rule::String => message = Some(self.unescape(&pair)),
+                _ => unreachable!("mismatch between grammar and AST"),
+            }
         }
 
-        Trigger { id: self.next_id(), name, expression, message, span }
+        Trigger { id: self.next_id(), name, expression, message, escalation, assumption, annotations: Vec::new(), span }
+    }
+
+    /// Replaces the escape sequences `\n`, `\r`, `\t`, `\"`, and `\\` in a string literal or trigger message by the characters they denote.
+    /// Unknown escape sequences are reported and kept as they are.
+    fn unescape(&self, pair: &Pair<'_, Rule>) -> String {
+        assert_eq!(pair.as_rule(), Rule::String);
+        let start = self.span(pair.as_span()).start;
+        let mut unescaped = String::with_capacity(pair.as_str().len());
+        let mut chars = pair.as_str().char_indices();
+        while let Some((ix, c)) = chars.next() {
+            if c != '\\' {
+                unescaped.push(c);
+                continue;
+            }
+            match chars.next().expect("the grammar ensures that a backslash escapes a character") {
+                (_, 'n') => unescaped.push('\n'),
+                (_, 'r') => unescaped.push('\r'),
+                (_, 't') => unescaped.push('\t'),
+                (_, c @ '"') | (_, c @ '\\') => unescaped.push(c),
+                (_, c) => {
+                    let span = Span { start: start + ix, end: start + ix + 1 + c.len_utf8() };
+                    self.handler.error_with_span(
+                        Code::InvalidEscape,
+                        &format!("unknown escape sequence `\\{}`", c),
+                        LabeledSpan::new(span, "expected `\\n`, `\\r`, `\\t`, `\\\"`, or `\\\\`", true),
+                    );
+                    unescaped.push('\\');
+                    unescaped.push(c);
+                }
+            }
+        }
+        unescaped
     }
 
     /**
@@ -382,7 +814,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
     fn parse_ident(&self, pair: &Pair<'_, Rule>) -> Ident {
         assert_eq!(pair.as_rule(), Rule::Ident);
         let name = pair.as_str().to_string();
-        Ident::new(name, pair.as_span().into())
+        Ident::new(name, self.span(pair.as_span()))
     }
 
     /**
@@ -391,7 +823,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
      */
     fn parse_type_declaration(&self, pair: Pair<'_, Rule>) -> TypeDeclaration {
         assert_eq!(pair.as_rule(), Rule::TypeDecl);
-        let span = pair.as_span().into();
+        let span = self.span(pair.as_span());
         let mut pairs = pair.into_inner();
         let name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
         let mut fields = Vec::new();
@@ -402,38 +834,88 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                 name: field_name,
                 ty,
                 id: self.next_id(),
-                span: pair.as_span().into(),
+                span: self.span(pair.as_span()),
             }));
         }
 
         TypeDeclaration { name: Some(name), span, id: self.next_id(), fields }
     }
 
+    /**
+     * Transforms a `Rule::TypeAlias` into `TypeAlias` AST node.
+     * Panics if input is not `Rule::TypeAlias`.
+     */
+    fn parse_type_alias(&self, pair: Pair<'_, Rule>) -> TypeAlias {
+        assert_eq!(pair.as_rule(), Rule::TypeAlias);
+        let span = self.span(pair.as_span());
+        let mut pairs = pair.into_inner();
+        let name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
+        let ty = self.parse_type(pairs.next().expect("mismatch between grammar and AST"));
+        TypeAlias { name, ty, id: self.next_id(), span }
+    }
+
+    /**
+     * Transforms a `Rule::EnumDecl` into `EnumDeclaration` AST node.
+     * Panics if input is not `Rule::EnumDecl`.
+     */
+    fn parse_enum_declaration(&self, pair: Pair<'_, Rule>) -> EnumDeclaration {
+        assert_eq!(pair.as_rule(), Rule::EnumDecl);
+        let span = self.span(pair.as_span());
+        let mut pairs = pair.into_inner();
+        let name = self.parse_ident(&pairs.next().expect("mismatch between grammar and AST"));
+        let variants = pairs.map(|pair| self.parse_ident(&pair)).collect();
+        EnumDeclaration { name, variants, id: self.next_id(), span }
+    }
+
     /**
      * Transforms a `Rule::Type` into `Type` AST node.
      * Panics if input is not `Rule::Type`.
      */
     fn parse_type(&self, pair: Pair<'_, Rule>) -> Type {
         assert_eq!(pair.as_rule(), Rule::Type);
-        let span = pair.as_span();
+        let span = self.span(pair.as_span());
         let mut tuple = Vec::new();
         for pair in pair.into_inner() {
             match pair.as_rule() {
                 Rule::Ident => {
-                    return Type::new_simple(self.next_id(), pair.as_str().to_string(), pair.as_span().into());
+                    return Type::new_simple(self.next_id(), pair.as_str().to_string(), self.span(pair.as_span()));
                 }
                 Rule::Type => tuple.push(self.parse_type(pair)),
                 Rule::Optional => {
-                    let span = pair.as_span();
+                    let span = self.span(pair.as_span());
                     let inner =
                         pair.into_inner().next().expect("mismatch between grammar and AST: first argument is a type");
-                    let inner_ty = Type::new_simple(self.next_id(), inner.as_str().to_string(), inner.as_span().into());
-                    return Type::new_optional(self.next_id(), inner_ty, span.into());
+                    let inner_ty =
+                        Type::new_simple(self.next_id(), inner.as_str().to_string(), self.span(inner.as_span()));
+                    return Type::new_optional(self.next_id(), inner_ty, span);
+                }
+                Rule::ArrayType => {
+                    let mut pairs = pair.into_inner();
+                    let element = self.parse_type(pairs.next().expect("mismatch between grammar and AST"));
+                    let size = pairs.next().expect("mismatch between grammar and AST");
+                    let size = match size.as_str().parse() {
+                        Ok(size) => size,
+                        Err(_) => {
+                            self.handler.error_with_span(
+                                Code::ExpectedUnsignedInteger,
+                                &format!("invalid size of array `{}`", size.as_str()),
+                                LabeledSpan::new(self.span(size.as_span()), "expected the number of elements", true),
+                            );
+                            std::process::exit(1);
+                        }
+                    };
+                    return Type::new_array(self.next_id(), element, size, span);
+                }
+                Rule::OptionType => {
+                    let span = self.span(pair.as_span());
+                    let inner =
+                        pair.into_inner().next().expect("mismatch between grammar and AST: first argument is a type");
+                    return Type::new_optional(self.next_id(), self.parse_type(inner), span);
                 }
                 _ => unreachable!("{:?} is not a type, ensured by grammar", pair.as_rule()),
             }
         }
-        Type::new_tuple(self.next_id(), tuple, span.into())
+        Type::new_tuple(self.next_id(), tuple, span)
     }
 
     /**
@@ -445,28 +927,28 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
         let inner = pair.into_inner().next().expect("Rule::Literal has exactly one child");
         match inner.as_rule() {
             Rule::String => {
-                let str_rep = inner.as_str();
-                Literal::new_str(self.next_id(), str_rep, inner.as_span().into())
+                let str_rep = self.unescape(&inner);
+                Literal::new_str(self.next_id(), &str_rep, self.span(inner.as_span()))
             }
             Rule::RawString => {
                 let str_rep = inner.as_str();
-                Literal::new_raw_str(self.next_id(), str_rep, inner.as_span().into())
+                Literal::new_raw_str(self.next_id(), str_rep, self.span(inner.as_span()))
             }
             Rule::NumberLiteral => {
-                let span = inner.as_span();
+                let span = self.span(inner.as_span());
                 let mut pairs = inner.into_inner();
                 let value = pairs.next().expect("Mismatch between AST and grammar");
 
-                let str_rep: &str = value.as_str();
+                let str_rep = normalize(value.as_str());
                 let unit = match pairs.next() {
                     None => None,
-                    Some(unit) => Some(unit.as_str().to_string()),
+                    Some(unit) => Some(normalize_unit(unit.as_str())),
                 };
 
-                Literal::new_numeric(self.next_id(), str_rep, unit, span.into())
+                Literal::new_numeric(self.next_id(), &str_rep, unit, span)
             }
-            Rule::True => Literal::new_bool(self.next_id(), true, inner.as_span().into()),
-            Rule::False => Literal::new_bool(self.next_id(), false, inner.as_span().into()),
+            Rule::True => Literal::new_bool(self.next_id(), true, self.span(inner.as_span())),
+            Rule::False => Literal::new_bool(self.next_id(), false, self.span(inner.as_span())),
             _ => unreachable!(),
         }
     }
@@ -519,7 +1001,16 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
     fn build_expression_ast(&self, pairs: Pairs<'_, Rule>) -> Expression {
         PREC_CLIMBER.climb(
             pairs,
-            |pair: Pair<'_, Rule>| self.build_term_ast(pair),
+            |pair: Pair<'_, Rule>| match pair.as_rule() {
+                // the target type of a cast, the casted expression is filled in when reducing `As`
+                Rule::Type => {
+                    let span: Span = self.span(pair.as_span());
+                    let missing = Expression::new(self.next_id(), ExpressionKind::MissingExpression, span);
+                    let ty = self.parse_type(pair);
+                    Expression::new(self.next_id(), ExpressionKind::Cast(Box::new(missing), ty), span)
+                }
+                _ => self.build_term_ast(pair),
+            },
             |lhs: Expression, op: Pair<'_, Rule>, rhs: Expression| {
                 // Reduce function combining `Expression`s to `Expression`s with the correct precs
                 let span = Span { start: lhs.span.start, end: rhs.span.end };
@@ -565,6 +1056,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                                     }
                                     _ => {
                                         self.handler.error_with_span(
+                                            Code::ExpectedUnsignedInteger,
                                             &format!("expected unsigned integer, found {}", l),
                                             LabeledSpan::new(rhs.span, "unexpected", true),
                                         );
@@ -590,7 +1082,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                                 let kind = match signature.as_str() {
                                     "defaults(to:)" => {
                                         assert_eq!(args.len(), 1);
-                                        ExpressionKind::Default(inner, args[0].clone())
+                                        ExpressionKind::Default(inner, args.into_iter().next().unwrap())
                                     }
                                     "offset(by:)" => {
                                         assert_eq!(args.len(), 1);
@@ -599,6 +1091,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                                             Ok(offset) => offset,
                                             Err(reason) => {
                                                 self.handler.error_with_span(
+                                                    Code::InvalidOffset,
                                                     "failed to parse offset",
                                                     LabeledSpan::new(rhs.span, &reason, true),
                                                 );
@@ -618,12 +1111,22 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                                             ExpressionKind::StreamAccess(inner, StreamAccessKind::Hold),
                                             span,
                                         );
-                                        ExpressionKind::Default(Box::new(lhs), args[0].clone())
+                                        ExpressionKind::Default(Box::new(lhs), args.into_iter().next().unwrap())
                                     }
                                     "get()" => {
                                         assert_eq!(args.len(), 0);
                                         ExpressionKind::StreamAccess(inner, StreamAccessKind::Optional)
                                     }
+                                    "unwrap_or(_:)" => {
+                                        assert_eq!(args.len(), 1);
+                                        ExpressionKind::Default(inner, args.into_iter().next().unwrap())
+                                    }
+                                    "is_some()" => {
+                                        // the receiver becomes the only argument of the builtin `is_some`
+                                        assert_eq!(args.len(), 0);
+                                        let name = FunctionName { name: name.name, arg_names: vec![None] };
+                                        ExpressionKind::Function(name, types, vec![inner])
+                                    }
                                     "aggregate(over:using:)" | "aggregate(over_exactly:using:)" => {
                                         assert_eq!(args.len(), 2);
                                         let window_op = match &args[1].kind {
@@ -635,30 +1138,44 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                                                 "avg" | "average" => WindowOperation::Average,
                                                 "min" => WindowOperation::Min,
                                                 "max" => WindowOperation::Max,
+                                                "median" => WindowOperation::Median,
+                                                "first" => WindowOperation::First,
+                                                "last" => WindowOperation::Last,
                                                 "∃" | "disjunction" | "∨" | "exists" => {
                                                     WindowOperation::Disjunction
                                                 }
                                                 "∀" | "conjunction" | "∧" | "forall" => {
                                                     WindowOperation::Conjunction
                                                 }
-                                                fun => {
-                                                    self.handler.error_with_span(
-                                                        &format!("unknown aggregation function {}", fun),
-                                                        LabeledSpan::new(
-                                                            i.span,
-                                                            "available: count, min, max, sum, average, integral",
-                                                            true,
-                                                        ),
-                                                    );
-                                                    std::process::exit(1);
-                                                }
+                                                // unknown names are reported by the type checker, which knows the registered aggregations
+                                                fun => WindowOperation::Custom(fun.to_string()),
                                             },
+                                            ExpressionKind::Function(name, _, percentile)
+                                                if name.as_string() == "percentile(_:)" =>
+                                            {
+                                                match percentile[0].parse_literal::<u8>() {
+                                                    Some(p) if p <= 100 => WindowOperation::Percentile(p),
+                                                    _ => {
+                                                        self.handler.error_with_span(
+                                                            Code::ExpectedAggregation,
+                                                            "expected percentile between 0 and 100",
+                                                            LabeledSpan::new(
+                                                                percentile[0].span,
+                                                                "expected integer literal between 0 and 100",
+                                                                true,
+                                                            ),
+                                                        );
+                                                        std::process::exit(1);
+                                                    }
+                                                }
+                                            }
                                             _ => {
                                                 self.handler.error_with_span(
+                                                    Code::ExpectedAggregation,
                                                     "expected aggregation function",
                                                     LabeledSpan::new(
                                                         args[1].span,
-                                                        "available: count, min, max, sum, average, integral",
+                                                        "available: count, min, max, sum, average, integral, median, percentile(p), first, last",
                                                         true,
                                                     ),
                                                 );
@@ -667,7 +1184,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                                         };
                                         ExpressionKind::SlidingWindowAggregation {
                                             expr: inner,
-                                            duration: args[0].clone(),
+                                            duration: args.into_iter().next().unwrap(),
                                             wait: signature.contains("over_exactly"),
                                             aggregation: window_op,
                                         }
@@ -688,6 +1205,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                             }
                             _ => {
                                 self.handler.error_with_span(
+                                    Code::ExpectedMethodOrTupleAccess,
                                     &format!("expected method call or tuple access, found {}", rhs),
                                     LabeledSpan::new(rhs.span, "unexpected", true),
                                 );
@@ -695,11 +1213,23 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                             }
                         }
                     }
+                    Rule::As => match rhs.kind {
+                        ExpressionKind::Cast(_, ty) => {
+                            return Expression::new(self.next_id(), ExpressionKind::Cast(Box::new(lhs), ty), span)
+                        }
+                        _ => unreachable!("the grammar ensures a type after `as`"),
+                    },
                     Rule::OpeningBracket => {
+                        // the span of the offset includes the closing bracket, which is not part of `rhs`
+                        let end = self.content[rhs.span.end - self.offset..]
+                            .find(']')
+                            .map_or(rhs.span.end, |i| rhs.span.end + i + 1);
+                        let span = Span { start: lhs.span.start, end };
                         let offset = match rhs.parse_offset() {
                             Ok(offset) => offset,
                             Err(reason) => {
                                 self.handler.error_with_span(
+                                    Code::InvalidOffset,
                                     "failed to parse offset expression",
                                     LabeledSpan::new(rhs.span, &reason, true),
                                 );
@@ -708,7 +1238,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                         };
                         match lhs.kind {
                             ExpressionKind::Unary(unop, inner) => {
-                                let inner_span = Span { start: inner.span.start, end: rhs.span.end };
+                                let inner_span = Span { start: inner.span.start, end };
                                 let new_inner =
                                     Expression::new(self.next_id(), ExpressionKind::Offset(inner, offset), inner_span);
                                 return Expression::new(
@@ -734,20 +1264,26 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
     }
 
     fn build_term_ast(&self, pair: Pair<'_, Rule>) -> Expression {
-        let span = pair.as_span();
+        let span = self.span(pair.as_span());
         match pair.as_rule() {
             // Map function from `Pair` to AST data structure `Expression`
-            Rule::Literal => {
-                Expression::new(self.next_id(), ExpressionKind::Lit(self.parse_literal(pair)), span.into())
-            }
-            Rule::Ident => Expression::new(self.next_id(), ExpressionKind::Ident(self.parse_ident(&pair)), span.into()),
+            Rule::Literal => Expression::new(self.next_id(), ExpressionKind::Lit(self.parse_literal(pair)), span),
+            Rule::Ident => match pair.as_str().rfind("::") {
+                // a path `Mode::Armed` names a variant of an enumeration
+                Some(pos) if self.enumerations.contains(&pair.as_str()[..pos]) => {
+                    let (enumeration, variant) = (&pair.as_str()[..pos], &pair.as_str()[pos + 2..]);
+                    let literal = Literal::new_variant(self.next_id(), enumeration, variant, span);
+                    Expression::new(self.next_id(), ExpressionKind::Lit(literal), span)
+                }
+                _ => Expression::new(self.next_id(), ExpressionKind::Ident(self.parse_ident(&pair)), span),
+            },
             Rule::ParenthesizedExpression => {
                 let mut inner = pair.into_inner();
                 let opp = inner.next().expect(
                     "Rule::ParenthesizedExpression has a token for the (potentialy missing) opening parenthesis",
                 );
                 let opening_parenthesis = if let Rule::OpeningParenthesis = opp.as_rule() {
-                    Some(Box::new(Parenthesis::new(self.next_id(), opp.as_span().into())))
+                    Some(Box::new(Parenthesis::new(self.next_id(), self.span(opp.as_span()))))
                 } else {
                     None
                 };
@@ -759,7 +1295,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                     "Rule::ParenthesizedExpression has a token for the (potentialy missing) closing parenthesis",
                 );
                 let closing_parenthesis = if let Rule::ClosingParenthesis = closing.as_rule() {
-                    Some(Box::new(Parenthesis::new(self.next_id(), closing.as_span().into())))
+                    Some(Box::new(Parenthesis::new(self.next_id(), self.span(closing.as_span()))))
                 } else {
                     None
                 };
@@ -771,7 +1307,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                         Box::new(self.build_expression_ast(inner_expression.into_inner())),
                         closing_parenthesis,
                     ),
-                    span.into(),
+                    span,
                 )
             }
             Rule::UnaryExpr => {
@@ -787,7 +1323,7 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                     Rule::BitNot => UnOp::BitNot,
                     _ => unreachable!(),
                 };
-                Expression::new(self.next_id(), ExpressionKind::Unary(operator, Box::new(operand)), span.into())
+                Expression::new(self.next_id(), ExpressionKind::Unary(operator, Box::new(operand)), span)
             }
             Rule::TernaryExpr => {
                 let mut children = self.parse_vec_of_expressions(pair.into_inner());
@@ -795,28 +1331,37 @@ impl<'a, 'b> RTLolaParser<'a, 'b> {
                 Expression::new(
                     self.next_id(),
                     ExpressionKind::Ite(children.remove(0), children.remove(0), children.remove(0)),
-                    span.into(),
+                    span,
                 )
             }
+            Rule::LetExpr => {
+                let mut children = pair.into_inner().filter(|p| !matches!(p.as_rule(), Rule::Let | Rule::In));
+                let name = self.parse_ident(&children.next().expect("mismatch between grammar and AST"));
+                let expr =
+                    self.build_expression_ast(children.next().expect("mismatch between grammar and AST").into_inner());
+                let body =
+                    self.build_expression_ast(children.next().expect("mismatch between grammar and AST").into_inner());
+                let binding_span = Span { start: name.span.start, end: expr.span.end };
+                let binding = Binding { name, expr, id: self.next_id(), span: binding_span };
+                Expression::new(self.next_id(), ExpressionKind::Let(Rc::new(binding), Box::new(body)), span)
+            }
             Rule::Tuple => {
                 let elements = self.parse_vec_of_expressions(pair.into_inner());
                 assert!(elements.len() != 1, "Tuples may not have exactly one element.");
-                Expression::new(self.next_id(), ExpressionKind::Tuple(elements), span.into())
+                Expression::new(self.next_id(), ExpressionKind::Tuple(elements), span)
             }
-            Rule::Expr => self.build_expression_ast(pair.into_inner()),
-            Rule::FunctionExpr => self.build_function_expression(pair, span.into()),
-            Rule::IntegerLiteral => {
-                let span = span.into();
-                Expression::new(
-                    self.next_id(),
-                    ExpressionKind::Lit(Literal::new_numeric(self.next_id(), pair.as_str(), None, span)),
-                    span,
-                )
-            }
-            Rule::MissingExpression => {
-                let span = span.into();
-                Expression::new(self.next_id(), ExpressionKind::MissingExpression, span)
+            Rule::Array => {
+                let elements = self.parse_vec_of_expressions(pair.into_inner());
+                Expression::new(self.next_id(), ExpressionKind::Array(elements), span)
             }
+            Rule::Expr => self.build_expression_ast(pair.into_inner()),
+            Rule::FunctionExpr => self.build_function_expression(pair, span),
+            Rule::IntegerLiteral => Expression::new(
+                self.next_id(),
+                ExpressionKind::Lit(Literal::new_numeric(self.next_id(), pair.as_str(), None, span)),
+                span,
+            ),
+            Rule::MissingExpression => Expression::new(self.next_id(), ExpressionKind::MissingExpression, span),
             _ => unreachable!("Unexpected rule when parsing expression ast: {:?}", pair.as_rule()),
         }
     }
@@ -834,6 +1379,57 @@ pub(crate) fn parse<'a, 'b>(
     RTLolaParser::new(content, handler, config).parse()
 }
 
+/**
+ * Transforms a textual representation of a Lola specification into an AST representation like `parse`,
+ * but removes the declarations whose `cfg` attributes do not hold for the enabled features.
+ * In addition, the declarations of imported files are added, which `parse` ignores as it does not know the file of the specification.
+ */
+pub(crate) fn parse_with_features<'a, 'b>(
+    content: &'a str,
+    handler: &'b Handler,
+    config: FrontendConfig,
+    features: &'b [String],
+) -> Result<RTLolaAst, pest::error::Error<Rule>> {
+    let importing = vec![handler.mapper().path(0)];
+    RTLolaParser { features, importing, ..RTLolaParser::new(content, handler, config) }.parse()
+}
+
+/// Normalizes a numeric literal, so it can be parsed by `str::parse`:
+/// digit separators are removed, e.g., `1000000` for `1_000_000`, hexadecimal, octal, and binary literals are converted to decimal, e.g., `255` for `0xFF`,
+/// and a fraction is added to a mantissa without one, e.g., `1.0e3` for `1e3`, so the literal is a floating point number.
+/// Literals too large to convert are unchanged, so the type checker reports them as out of range.
+fn normalize(literal: &str) -> String {
+    let literal = literal.replace('_', "");
+    let (sign, digits) = match literal.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", literal.strip_prefix('+').unwrap_or(&literal)),
+    };
+    let radix = match digits.get(..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => match literal.find(|c| c == 'e' || c == 'E') {
+            Some(exponent) if !literal.contains('.') => {
+                return format!("{}.0{}", &literal[..exponent], &literal[exponent..])
+            }
+            _ => return literal,
+        },
+    };
+    match u128::from_str_radix(&digits[2..], radix) {
+        Ok(value) => format!("{}{}", sign, value),
+        Err(_) => literal,
+    }
+}
+
+/// Normalizes the unit of a numeric literal to its ASCII spelling, e.g., `us` for `μs` or `µs`,
+/// so literals written with either spelling are identical.
+fn normalize_unit(unit: &str) -> String {
+    match unit.strip_prefix('μ').or_else(|| unit.strip_prefix('µ')) {
+        Some(rest) => format!("u{}", rest),
+        None => unit.to_string(),
+    }
+}
+
 #[derive(Debug, Clone, Eq)]
 pub struct Ident {
     pub name: String,
@@ -874,7 +1470,7 @@ impl std::fmt::Display for NodeId {
 
 /// A span marks a range in a file.
 /// Start and end positions are *byte* offsets.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Span {
     pub start: usize,
     pub end: usize,
@@ -898,10 +1494,21 @@ impl<'a> From<pest::Span<'a>> for Span {
 }
 
 /// A mapper from `Span` to actual source code
+///
+/// Imported files are placed after the specification in the order of their import, so the offsets of a span determine its file.
 #[derive(Debug)]
 pub(crate) struct SourceMapper {
     path: PathBuf,
     content: String,
+    imported: RefCell<Vec<SourceFile>>,
+}
+
+/// A file imported by the specification, whose spans start at `offset`
+#[derive(Debug)]
+struct SourceFile {
+    path: PathBuf,
+    content: String,
+    offset: usize,
 }
 
 #[derive(Debug, Eq, Ord)]
@@ -935,57 +1542,84 @@ pub(crate) struct CharSpan {
 
 impl SourceMapper {
     pub(crate) fn new(path: PathBuf, content: &str) -> SourceMapper {
-        SourceMapper { path, content: content.to_string() }
+        SourceMapper { path, content: content.to_string(), imported: RefCell::new(Vec::new()) }
     }
 
-    #[allow(dead_code)]
+    /// Adds an imported file and returns the offset at which its spans start.
+    pub(crate) fn add_file(&self, path: PathBuf, content: &str) -> usize {
+        let mut imported = self.imported.borrow_mut();
+        // the offsets are separated by one, so the end of a file is not the start of the next one
+        let offset = imported.last().map_or(self.content.len(), |file| file.offset + file.content.len()) + 1;
+        imported.push(SourceFile { path, content: content.to_string(), offset });
+        offset
+    }
+
+    /// Returns whether the file is the specification or one of the imported files.
+    pub(crate) fn contains(&self, path: &Path) -> bool {
+        let path = canonical(path);
+        canonical(&self.path) == path || self.imported.borrow().iter().any(|file| canonical(&file.path) == path)
+    }
+
+    /// Returns the path of the file containing the byte offset.
+    pub(crate) fn path(&self, offset: usize) -> PathBuf {
+        self.with_file(offset, |path, _, _| path.to_path_buf())
+    }
+
+    /// Calls `f` with the path, content, and offset of the file containing the byte offset.
+    fn with_file<T>(&self, offset: usize, f: impl FnOnce(&Path, &str, usize) -> T) -> T {
+        let imported = self.imported.borrow();
+        match imported.iter().rev().find(|file| file.offset <= offset) {
+            Some(file) => f(&file.path, &file.content, file.offset),
+            None => f(&self.path, &self.content, 0),
+        }
+    }
+
+    /// Returns the line and column of a byte offset, both starting at 1; columns count characters.
+    pub(crate) fn get_position(&self, offset: usize) -> (usize, usize) {
+        self.with_file(offset, |_, content, base| {
+            let before = &content[..(offset - base).min(content.len())];
+            let line_start = before.rfind('\n').map_or(0, |ix| ix + 1);
+            (before.matches('\n').count() + 1, before[line_start..].chars().count() + 1)
+        })
+    }
+
+    /// Returns the first line of the span with the highlighted columns; spans over multiple lines are highlighted up to the end of their first line.
     pub(crate) fn get_line(&self, span: Span) -> Option<CodeLine> {
+        self.with_file(span.start, |path, content, base| {
+            let span = Span { start: span.start - base, end: span.end.max(span.start) - base };
+            SourceMapper::line_in(path, content, span)
+        })
+    }
+
+    fn line_in(path: &Path, content: &str, span: Span) -> Option<CodeLine> {
         let mut byte_offset = 0;
-        for (num, line) in self.content.split('\n').enumerate() {
-            assert!(byte_offset <= span.start);
+        for (num, line) in content.split('\n').enumerate() {
             let line_end = byte_offset + line.len() + 1; // +1 as it is excluding newline character
-
             if span.start < line_end {
-                if span.end > line_end {
-                    // not a single line
-                    return None;
-                } else {
-                    // get column
-                    let mut column = 0;
-                    let mut start: Option<usize> = None;
-                    let mut end: Option<usize> = None;
-                    let mut i = 0;
-                    for (index, _) in line.char_indices() {
-                        i = index;
-                        if index < span.start - byte_offset {
-                            column += 1;
-                        } else if index == span.start - byte_offset {
-                            start = Some(index);
-                        } else if index == span.end - byte_offset {
-                            end = Some(index);
-                            break;
-                        }
-                    }
-                    let (start, end) = (
-                        start.unwrap_or(i), // we might hit the end of the line/EOI
-                        end.unwrap_or(i + 1),
-                    );
-
-                    return Some(CodeLine {
-                        path: self.path.clone(),
-                        line_number: num + 1,
-                        column_number: column + 1,
-                        line: line.to_string(),
-                        highlight: CharSpan { start, end },
-                    });
-                }
+                let start = (span.start - byte_offset).min(line.len());
+                let end = (span.end.max(span.start) - byte_offset).min(line.len());
+                let columns = |offset: usize| line.get(..offset).map(|prefix| prefix.chars().count());
+                let (start, end) = (columns(start)?, columns(end)?);
+                return Some(CodeLine {
+                    path: path.to_path_buf(),
+                    line_number: num + 1,
+                    column_number: start + 1,
+                    line: line.to_string(),
+                    // empty spans, e.g., the position of a syntax error, are highlighted by a single character
+                    highlight: CharSpan { start, end: end.max(start + 1) },
+                });
             }
             byte_offset = line_end;
         }
-        unreachable!();
+        None
     }
 }
 
+/// Returns the canonical form of the path, or the path itself if it does not exist, e.g., for specifications read from stdin.
+fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1017,9 +1651,11 @@ mod tests {
                     Type(16, 19, [
                         Ident(16, 19, []),
                     ]),
-                    Literal(23, 24, [
-                        NumberLiteral(23, 24, [
-                            NumberLiteralValue(23, 24, [])
+                    Expr(23, 24, [
+                        Literal(23, 24, [
+                            NumberLiteral(23, 24, [
+                                NumberLiteralValue(23, 24, [])
+                            ]),
                         ]),
                     ]),
                 ]),
@@ -1119,8 +1755,8 @@ mod tests {
         let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
         let parser = RTLolaParser::new(spec, &handler, FrontendConfig::default());
         let pair = LolaParser::parse(Rule::OutputStream, spec).unwrap_or_else(|e| panic!("{}", e)).next().unwrap();
-        let ast = parser.parse_output(pair);
-        assert_eq!(format!("{}", ast), spec)
+        let ast = parser.parse_outputs(pair);
+        assert_eq!(format!("{}", ast[0]), spec)
     }
 
     #[allow(clippy::cognitive_complexity)]
@@ -1264,6 +1900,168 @@ mod tests {
         cmp_ast_spec(&ast, spec);
     }
 
+    #[test]
+    fn build_derived_input() {
+        let spec = "input raw: Int32\ninput alt: Float64 := to_float(raw) / 100.0\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        cmp_ast_spec(&ast, spec);
+        assert_eq!(ast.inputs.len(), 1);
+        assert!(ast.outputs[0].derived_input);
+    }
+
+    #[test]
+    fn build_cfg_attributes() {
+        let spec =
+            "input a: Int\n#[cfg(feature = \"sim\")]\noutput b := a\n#[cfg(not(feature = \"sim\"))]\noutput c := a\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let features = vec![String::from("sim")];
+        let ast = parse_with_features(spec, &handler, FrontendConfig::default(), &features).unwrap_or_else(throw);
+        assert_eq!(ast.outputs.len(), 1);
+        assert_eq!(ast.outputs[0].name.name, "b");
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        assert_eq!(ast.outputs.len(), 1);
+        assert_eq!(ast.outputs[0].name.name, "c");
+    }
+
+    #[test]
+    fn build_configurable_constant() {
+        let spec = "configurable constant limit: Int := 5\nconstant step: Int := 1\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        cmp_ast_spec(&ast, spec);
+        assert!(ast.constants[0].configurable);
+        assert!(!ast.constants[1].configurable);
+    }
+
+    #[test]
+    fn build_assumption() {
+        let spec = "input in: Int\nassume in > 0 \"positive\"\ntrigger in > 5\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        cmp_ast_spec(&ast, spec);
+        assert!(ast.trigger[0].assumption);
+    }
+
+    #[test]
+    fn build_named_trigger() {
+        let spec = "input in: Int\ntrigger too_high := in > 5 \"too high\"\ntrigger in < 0\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        cmp_ast_spec(&ast, spec);
+        assert_eq!(ast.trigger[0].name.as_ref().map(|n| n.name.as_str()), Some("too_high"));
+        assert!(ast.trigger[1].name.is_none());
+    }
+
+    #[test]
+    fn build_radix_literals() {
+        let spec = "input in: UInt8\noutput out := in & 0xFF + 0o17 * 0b1010\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        assert_eq!(ast.outputs[0].expression.to_string(), "in & 255 + 15 * 10");
+        assert_eq!(normalize("-0x10"), "-16");
+        assert_eq!(normalize("12.5"), "12.5");
+        assert_eq!(normalize("0x1000000000000000000000000000000000"), "0x1000000000000000000000000000000000");
+    }
+
+    #[test]
+    fn build_separated_and_scientific_literals() {
+        let spec = "input in: Float64\noutput out := in * 1_000.5 + 1.5e-3 - 2E3\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        assert_eq!(ast.outputs[0].expression.to_string(), "in * 1000.5 + 1.5e-3 - 2.0E3");
+        assert_eq!(normalize("1_000_000"), "1000000");
+        assert_eq!(normalize("0xFF_FF"), "65535");
+        assert_eq!(normalize("-1e3"), "-1.0e3");
+    }
+
+    #[test]
+    fn build_unicode_and_ascii_operators() {
+        let print = |spec: &str| {
+            let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+            parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e)).to_string()
+        };
+        let unicode = "input a: Int64\ninput b: Bool\n\
+            output c @ 1μHz := ¬b ∧ ⊤ ∨ a ≤ 1 ∧ a ≥ 0 ∧ a ≠ 2 ∨ ⊥\n\
+            output d @ 1Hz := a.aggregate(over: 10μs, using: Σ) + a.aggregate(over: 1s, using: ∫)\n\
+            output e @ 1Hz := b.aggregate(over: 1s, using: ∀) ∧ b.aggregate(over: 1s, using: ∃)\n";
+        let ascii = "input a: Int64\ninput b: Bool\n\
+            output c @ 1uHz := !b && true || a <= 1 && a >= 0 && a != 2 || false\n\
+            output d @ 1Hz := a.aggregate(over: 10us, using: sum) + a.aggregate(over: 1s, using: integral)\n\
+            output e @ 1Hz := b.aggregate(over: 1s, using: forall) && b.aggregate(over: 1s, using: exists)\n";
+        assert_eq!(print(unicode), print(ascii));
+        // the micro sign of many keyboards is accepted like the Greek letter mu
+        assert_eq!(print("output c @ 1µHz := 10µs"), print("output c @ 1uHz := 10us"));
+        assert_eq!(normalize_unit("μs"), "us");
+        assert_eq!(normalize_unit("ms"), "ms");
+    }
+
+    #[test]
+    fn build_string_escapes() {
+        let spec = "input a: Int64\noutput b := \"x\\ny\\\\\"\ntrigger a > 1 \"a is\\n\\t\\\"{a}\\\" \\\\ 1\"\n";
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(|e| panic!("{}", e));
+        match &ast.outputs[0].expression.kind {
+            ExpressionKind::Lit(Literal { kind: LitKind::Str(s), .. }) => assert_eq!(s, "x\ny\\"),
+            kind => panic!("expected a string literal, found {:?}", kind),
+        }
+        assert_eq!(ast.trigger[0].message.as_deref(), Some("a is\n\t\"{a}\" \\ 1"));
+        assert_eq!(ast.trigger[0].placeholders(), vec!["a"]);
+        cmp_ast_spec(&ast, spec);
+    }
+
+    #[test]
+    fn build_cast() {
+        let spec = "input in: Int64\noutput out := (in + 1) as Int8\noutput abs := -in.abs() as UInt8 + 1\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        cmp_ast_spec(&ast, spec);
+        match &ast.outputs[1].expression.kind {
+            ExpressionKind::Binary(BinOp::Add, lhs, _) => match &lhs.kind {
+                ExpressionKind::Cast(inner, ty) => {
+                    assert_eq!(inner.to_string(), "-in.abs()");
+                    assert_eq!(ty.to_string(), "UInt8");
+                }
+                _ => panic!("expected a cast, found {}", lhs),
+            },
+            _ => panic!("expected an addition, found {}", ast.outputs[1].expression),
+        }
+    }
+
+    #[test]
+    fn build_let() {
+        let spec = "input in: Int64\noutput out := let d := in - 1 in d * d\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        cmp_ast_spec(&ast, spec);
+        match &ast.outputs[0].expression.kind {
+            ExpressionKind::Let(binding, body) => {
+                assert_eq!(binding.name.name, "d");
+                assert_eq!(binding.expr.to_string(), "in - 1");
+                assert_eq!(body.to_string(), "d * d");
+            }
+            _ => panic!("expected a let-expression, found {}", ast.outputs[0].expression),
+        }
+    }
+
+    #[test]
+    fn build_trigger_escalation() {
+        let spec = "input in: Int\ntrigger in > 5 escalate after 3 within 10s \"flaky {count}\"\n";
+        let throw = |e| panic!("{}", e);
+        let handler = Handler::new(SourceMapper::new(PathBuf::new(), spec));
+        let ast = parse(spec, &handler, FrontendConfig::default()).unwrap_or_else(throw);
+        cmp_ast_spec(&ast, spec);
+    }
+
     #[test]
     fn build_complex_expression() {
         let spec =
@@ -1336,6 +2134,21 @@ mod tests {
         cmp_ast_spec(&ast, spec);
     }
 
+    #[test]
+    fn map_imported_files() {
+        let mapper = SourceMapper::new(PathBuf::from("spec.lola"), "import \"common.lola\"\n");
+        let offset = mapper.add_file(PathBuf::from("common.lola"), "input a: Int64\ninput b: Int64\n");
+        assert_eq!(offset, 22);
+        assert_eq!(mapper.path(21), PathBuf::from("spec.lola"));
+        assert_eq!(mapper.path(offset + 15), PathBuf::from("common.lola"));
+        assert_eq!(mapper.get_position(offset + 21), (2, 7));
+        let line = mapper.get_line(Span { start: offset + 21, end: offset + 22 }).expect("the span is in the file");
+        assert_eq!(
+            (line.path, line.line_number, line.line),
+            (PathBuf::from("common.lola"), 2, "input b: Int64".to_string())
+        );
+    }
+
     #[test]
     fn parse_max() {
         let spec = "import math\ninput a: Int32\ninput b: Int32\noutput maxres: Int32 := max<Int32>(a, b)\n";
@@ -0,0 +1,117 @@
+//! This module finds the conjunctions and disjunctions whose operands can be swapped, see `short_circuits`, e.g., to evaluate the operand that decides the result more often first.
+
+use crate::ast::{BinOp, Expression, ExpressionKind};
+use crate::diagnostics::DiagnosticCollector;
+use crate::navigation::{location, Location, TextEdit};
+use crate::parse::SourceMapper;
+use crate::reporting::Handler;
+use crate::FrontendConfig;
+use std::path::PathBuf;
+
+/// A conjunction or disjunction whose operands can be swapped without changing the value of the expression.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShortCircuit {
+    /// Whether the operator is `&&`, otherwise it is `||`.
+    pub conjunction: bool,
+    /// The left operand.
+    pub lhs: Location,
+    /// The right operand.
+    pub rhs: Location,
+}
+
+/// Returns the conjunctions and disjunctions of the specification whose operands can be swapped, outer ones first, or none if the specification does not parse.
+///
+/// Operands can be swapped if evaluating them cannot fail, i.e., they contain no division, remainder, power, shift, or call of a function or method.
+pub(crate) fn short_circuits(spec: &str, config: FrontendConfig) -> Vec<ShortCircuit> {
+    let handler = Handler::with_emitter(SourceMapper::new(PathBuf::new(), spec), Box::new(DiagnosticCollector::new()));
+    let ast = match crate::parse::parse(spec, &handler, config) {
+        Ok(ast) => ast,
+        Err(_) => return Vec::new(),
+    };
+    let mapper = SourceMapper::new(PathBuf::new(), spec);
+    let expressions = ast
+        .outputs
+        .iter()
+        .flat_map(|o| o.extend.expr.iter().chain(o.termination.iter()).chain(std::iter::once(&o.expression)))
+        .chain(ast.trigger.iter().map(|t| &t.expression));
+    let mut short_circuits = Vec::new();
+    for expression in expressions {
+        for expr in expression.iter() {
+            if let ExpressionKind::Binary(op @ BinOp::And, lhs, rhs)
+            | ExpressionKind::Binary(op @ BinOp::Or, lhs, rhs) = &expr.kind
+            {
+                if total(lhs) && total(rhs) {
+                    short_circuits.push(ShortCircuit {
+                        conjunction: *op == BinOp::And,
+                        lhs: location(&mapper, lhs.span),
+                        rhs: location(&mapper, rhs.span),
+                    });
+                }
+            }
+        }
+    }
+    short_circuits
+}
+
+/// Returns the specification with the operands of the short circuits swapped.
+/// A short circuit overlapping an earlier one is skipped, so the order determines which of nested short circuits are swapped.
+pub(crate) fn swap(spec: &str, short_circuits: &[ShortCircuit]) -> String {
+    let mut edits: Vec<TextEdit> = Vec::new();
+    for sc in short_circuits {
+        let overlaps = |l: &Location| edits.iter().any(|e| l.start < e.location.end && e.location.start < l.end);
+        if overlaps(&sc.lhs) || overlaps(&sc.rhs) {
+            continue;
+        }
+        edits.push(TextEdit { location: sc.lhs, new_text: spec[sc.rhs.start..sc.rhs.end].to_string() });
+        edits.push(TextEdit { location: sc.rhs, new_text: spec[sc.lhs.start..sc.lhs.end].to_string() });
+    }
+    crate::navigation::apply(spec, &edits)
+}
+
+/// Returns whether evaluating the expression cannot fail.
+fn total(expr: &Expression) -> bool {
+    expr.iter().all(|e| match &e.kind {
+        ExpressionKind::Binary(op, _, _) => {
+            !matches!(op, BinOp::Div | BinOp::Rem | BinOp::Pow | BinOp::Shl | BinOp::Shr)
+        }
+        ExpressionKind::Function(..) | ExpressionKind::Method(..) | ExpressionKind::MissingExpression => false,
+        _ => true,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn operands(spec: &str) -> Vec<(bool, &str, &str)> {
+        short_circuits(spec, FrontendConfig::default())
+            .into_iter()
+            .map(|sc| (sc.conjunction, &spec[sc.lhs.start..sc.lhs.end], &spec[sc.rhs.start..sc.rhs.end]))
+            .collect()
+    }
+
+    #[test]
+    fn swappable_operands() {
+        let spec = "input a: Int32\ninput b: Bool\ntrigger a > 5 && (b || a.hold().defaults(to: 0) == 3)\ntrigger a != 0 && 10 / a > 1";
+        assert_eq!(
+            operands(spec),
+            vec![
+                (true, "a > 5", "(b || a.hold().defaults(to: 0) == 3)"),
+                (false, "b", "a.hold().defaults(to: 0) == 3"),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_short_circuits() {
+        let spec = "input a: Bool\ninput b: Bool\ninput c: Bool\ntrigger a && b && c";
+        let short_circuits = short_circuits(spec, FrontendConfig::default());
+        assert_eq!(short_circuits.len(), 2);
+        // the inner conjunction is part of the left operand of the outer one
+        assert_eq!(swap(spec, &short_circuits), "input a: Bool\ninput b: Bool\ninput c: Bool\ntrigger c && a && b");
+        assert_eq!(
+            swap(spec, &short_circuits[1..]),
+            "input a: Bool\ninput b: Bool\ninput c: Bool\ntrigger b && a && c"
+        );
+    }
+}
@@ -2,33 +2,148 @@
 
 use self::Level::*;
 use crate::parse::Span;
-use crate::parse::{CodeLine, SourceMapper};
+use crate::parse::{CodeLine, Rule, SourceMapper};
 use std::cell::RefCell;
+use std::collections::HashSet;
 #[cfg(not(test))]
-use std::io::Write;
+use std::io::{IsTerminal, Write};
+use std::rc::Rc;
 use termcolor::{Color, ColorSpec};
 #[cfg(not(test))]
 use termcolor::{ColorChoice, StandardStream, WriteColor};
 
+mod codes;
+mod json;
+mod sarif;
+
+pub(crate) use self::codes::Code;
+pub(crate) use self::json::JsonDiagnostics;
+pub(crate) use self::sarif::SarifLog;
+
+/**
+Decides which warnings are reported and which are promoted to errors.
+
+Warnings are identified by their code, e.g., `W0201`; the setting for a code takes precedence over `deny_all`.
+Specifications can allow warnings for a single declaration with an attribute, e.g., `#[allow(W0201)]`, which takes precedence over both.
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WarningConfig {
+    /// Promotes all warnings to errors.
+    pub deny_all: bool,
+    /// The codes of warnings promoted to errors.
+    pub deny: Vec<String>,
+    /// The codes of suppressed warnings.
+    pub allow: Vec<String>,
+}
+
+/// Decides whether diagnostics printed to stderr are colored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ColorMode {
+    /// Colors if stderr is a terminal, unless the environment disables colors, e.g., by setting `NO_COLOR`
+    Auto,
+    Always,
+    Never,
+}
+
+/// The number of errors displayed, further errors are only counted to keep the output readable.
+const ERROR_LIMIT: usize = 20;
+
 /// A handler is responsible for emitting warnings and errors
 #[derive(Debug)]
 pub(crate) struct Handler {
     error_count: RefCell<usize>,
     warning_count: RefCell<usize>,
+    /// Errors beyond `ERROR_LIMIT`, which were counted but not displayed.
+    omitted_errors: RefCell<usize>,
+    /// Displayed diagnostics, emitting the same diagnostic again has no effect.
+    emitted: RefCell<HashSet<Diagnostic>>,
     emitter: RefCell<Box<dyn Emitter>>,
     mapper: SourceMapper,
+    /// Warnings with these codes are suppressed.
+    allowed: HashSet<Code>,
+    /// Warnings with these codes are promoted to errors.
+    denied: HashSet<Code>,
+    /// Whether warnings without a setting for their code are promoted to errors.
+    deny_all: bool,
+    /// Warnings with the code are suppressed within the span, given by attributes of declarations.
+    allowed_in: RefCell<Vec<(Span, Code)>>,
 }
 
 impl Handler {
     pub(crate) fn new(mapper: SourceMapper) -> Self {
+        Handler::with_color(mapper, ColorMode::Auto)
+    }
+
+    /// Creates a handler printing diagnostics to stderr, colored as given by `color`.
+    pub(crate) fn with_color(mapper: SourceMapper, color: ColorMode) -> Self {
+        Handler::with_emitter(mapper, Box::new(StderrEmitter::new(color)))
+    }
+
+    /// Creates a handler passing diagnostics to the given emitter instead of printing them to stderr.
+    pub(crate) fn with_emitter(mapper: SourceMapper, emitter: Box<dyn Emitter>) -> Self {
         Handler {
             error_count: RefCell::new(0),
             warning_count: RefCell::new(0),
-            emitter: RefCell::new(Box::new(StderrEmitter::new())),
+            omitted_errors: RefCell::new(0),
+            emitted: RefCell::new(HashSet::new()),
+            emitter: RefCell::new(emitter),
             mapper,
+            allowed: HashSet::new(),
+            denied: HashSet::new(),
+            deny_all: false,
+            allowed_in: RefCell::new(Vec::new()),
         }
     }
 
+    /// Suppresses the warnings with the given code.
+    pub(crate) fn allow(&mut self, code: Code) {
+        assert!(code.is_warning(), "only warnings can be suppressed, but {} is an error", code);
+        self.denied.remove(&code);
+        self.allowed.insert(code);
+    }
+
+    /// Promotes the warnings with the given code to errors.
+    pub(crate) fn deny(&mut self, code: Code) {
+        assert!(code.is_warning(), "only warnings can be denied, but {} is an error", code);
+        self.allowed.remove(&code);
+        self.denied.insert(code);
+    }
+
+    /// Suppresses the warnings with the given code within `span`, e.g., a declaration with the attribute `#[allow(W0201)]`.
+    pub(crate) fn allow_in(&self, span: Span, code: Code) {
+        assert!(code.is_warning(), "only warnings can be suppressed, but {} is an error", code);
+        self.allowed_in.borrow_mut().push((span, code));
+    }
+
+    /// Applies the given configuration, fails if it refers to unknown codes or errors.
+    pub(crate) fn configure_warnings(&mut self, config: &WarningConfig) -> Result<(), String> {
+        fn parse(code: &str) -> Result<Code, String> {
+            let code = code.parse::<Code>()?;
+            if code.is_warning() {
+                Ok(code)
+            } else {
+                Err(format!("only warnings can be allowed or denied, but `{}` is an error", code))
+            }
+        }
+        self.deny_all = config.deny_all;
+        for code in &config.deny {
+            self.deny(parse(code)?);
+        }
+        for code in &config.allow {
+            let code = parse(code)?;
+            if self.denied.contains(&code) {
+                return Err(format!("the warning `{}` is both allowed and denied", code));
+            }
+            self.allow(code);
+        }
+        Ok(())
+    }
+
+    /// The source mapper of the specification, which also maps the imported files.
+    pub(crate) fn mapper(&self) -> &SourceMapper {
+        &self.mapper
+    }
+
     pub(crate) fn contains_error(&self) -> bool {
         self.emitted_errors() > 0
     }
@@ -42,11 +157,54 @@ impl Handler {
         *self.warning_count.borrow()
     }
 
-    /// Displays diagnostic to user
+    /// Displays diagnostic to user, unless it is a suppressed warning; denied warnings are displayed as errors.
     fn emit(&self, diagnostic: &Diagnostic) {
+        if let (Warning, Some(code)) = (diagnostic.level, diagnostic.code) {
+            if self.is_allowed(code, diagnostic) {
+                return;
+            }
+            if self.deny_all || self.denied.contains(&code) {
+                let mut promoted = diagnostic.clone();
+                promoted.level = Error;
+                promoted.children.push(SubDiagnostic {
+                    level: Note,
+                    message: format!("the warning `{}` is denied", code),
+                    span: None,
+                });
+                return self.emit_diagnostic(&promoted);
+            }
+        }
+        self.emit_diagnostic(diagnostic)
+    }
+
+    fn is_allowed(&self, code: Code, diagnostic: &Diagnostic) -> bool {
+        if self.allowed.contains(&code) {
+            return true;
+        }
+        // warnings are located by their primary span, or their first span if they have none
+        let location = diagnostic.span.iter().find(|s| s.primary).or_else(|| diagnostic.span.first());
+        match location {
+            Some(location) => self
+                .allowed_in
+                .borrow()
+                .iter()
+                .any(|(span, c)| *c == code && span.start <= location.span.start && location.span.end <= span.end),
+            None => false,
+        }
+    }
+
+    fn emit_diagnostic(&self, diagnostic: &Diagnostic) {
+        if !self.emitted.borrow_mut().insert(diagnostic.clone()) {
+            return;
+        }
         if diagnostic.is_error() {
             let mut count = self.error_count.borrow_mut();
             *count += 1;
+            // summaries, e.g., that the analysis was aborted, have no code and are always displayed
+            if *count > ERROR_LIMIT && diagnostic.code.is_some() {
+                *self.omitted_errors.borrow_mut() += 1;
+                return;
+            }
         }
         if diagnostic.is_warning() {
             let mut count = self.warning_count.borrow_mut();
@@ -55,23 +213,59 @@ impl Handler {
         self.emitter.borrow_mut().emit(&self.mapper, &diagnostic)
     }
 
+    /// Reports a syntax error, which is detected by the parser instead of the analysis.
+    pub(crate) fn syntax_error(&self, error: &pest::error::Error<Rule>) {
+        self.syntax_error_at(error, 0)
+    }
+
+    /// Reports a syntax error in an imported file, whose spans start at `offset`.
+    pub(crate) fn syntax_error_at(&self, error: &pest::error::Error<Rule>, offset: usize) {
+        use pest::error::InputLocation;
+        let span = match error.location {
+            InputLocation::Pos(pos) => Span { start: offset + pos, end: offset + pos },
+            InputLocation::Span((start, end)) => Span { start: offset + start, end: offset + end },
+        };
+        self.error_with_span(Code::SyntaxError, &error.variant.message(), LabeledSpan::new(span, "", true));
+    }
+
+    /// Reports that the analysis stops due to the previous errors, including how many of them were not displayed.
+    pub(crate) fn abort(&self) {
+        let omitted = *self.omitted_errors.borrow();
+        if omitted > 0 {
+            self.emit(&Diagnostic {
+                level: Note,
+                code: None,
+                message: format!("{} more error{} omitted", omitted, if omitted == 1 { "" } else { "s" }),
+                span: Vec::new(),
+                children: vec![],
+                suggestions: vec![],
+                sort_spans: true,
+            });
+        }
+        self.error("aborting due to previous error");
+    }
+
     #[allow(dead_code)]
     pub(crate) fn warn(&self, message: &str) {
         self.emit(&Diagnostic {
             level: Warning,
+            code: None,
             message: message.to_owned(),
             span: Vec::new(),
             children: vec![],
+            suggestions: vec![],
             sort_spans: true,
         });
     }
 
-    pub(crate) fn warn_with_span(&self, message: &str, span: LabeledSpan) {
+    pub(crate) fn warn_with_span(&self, code: Code, message: &str, span: LabeledSpan) {
         self.emit(&Diagnostic {
             level: Warning,
+            code: Some(code),
             message: message.to_owned(),
             span: vec![span],
             children: vec![],
+            suggestions: vec![],
             sort_spans: true,
         });
     }
@@ -79,40 +273,57 @@ impl Handler {
     pub(crate) fn error(&self, message: &str) {
         self.emit(&Diagnostic {
             level: Error,
+            code: None,
             message: message.to_owned(),
             span: Vec::new(),
             children: vec![],
+            suggestions: vec![],
             sort_spans: true,
         });
     }
 
-    pub(crate) fn error_with_span(&self, message: &str, span: LabeledSpan) {
+    pub(crate) fn error_with_span(&self, code: Code, message: &str, span: LabeledSpan) {
         self.emit(&Diagnostic {
             level: Error,
+            code: Some(code),
             message: message.to_owned(),
             span: vec![span],
             children: vec![],
+            suggestions: vec![],
             sort_spans: true,
         });
     }
 
-    pub(crate) fn build_error_with_span(&self, message: &str, span: LabeledSpan) -> DiagnosticBuilder<'_> {
-        let mut builder = DiagnosticBuilder::new(&self, Error, message);
+    pub(crate) fn build_error_with_span(&self, code: Code, message: &str, span: LabeledSpan) -> DiagnosticBuilder<'_> {
+        let mut builder = DiagnosticBuilder::new(&self, Error, Some(code), message);
         builder.add_labeled_span(span);
         builder
     }
 
-    pub(crate) fn build_diagnostic(&self, message: &str, level: Level) -> DiagnosticBuilder<'_> {
-        DiagnosticBuilder::new(&self, level, message)
+    pub(crate) fn build_warning_with_span(
+        &self,
+        code: Code,
+        message: &str,
+        span: LabeledSpan,
+    ) -> DiagnosticBuilder<'_> {
+        let mut builder = DiagnosticBuilder::new(&self, Warning, Some(code), message);
+        builder.add_labeled_span(span);
+        builder
+    }
+
+    pub(crate) fn build_diagnostic(&self, code: Code, message: &str, level: Level) -> DiagnosticBuilder<'_> {
+        DiagnosticBuilder::new(&self, level, Some(code), message)
     }
 
     #[allow(dead_code)]
     pub(crate) fn bug_with_span(&self, message: &str, span: LabeledSpan) {
         self.emit(&Diagnostic {
             level: Bug,
+            code: None,
             message: message.to_owned(),
             span: vec![span],
             children: vec![],
+            suggestions: vec![],
             sort_spans: true,
         });
     }
@@ -126,11 +337,14 @@ pub(crate) trait Emitter: std::fmt::Debug {
 
 /// Emits errors to stderr
 #[derive(Debug)]
-struct StderrEmitter {}
+struct StderrEmitter {
+    #[cfg_attr(test, allow(dead_code))]
+    color: ColorMode,
+}
 
 impl StderrEmitter {
-    fn new() -> Self {
-        StderrEmitter {}
+    fn new(color: ColorMode) -> Self {
+        StderrEmitter { color }
     }
 }
 
@@ -140,7 +354,13 @@ impl Emitter for StderrEmitter {
     /// standard emit implementation
     #[cfg(not(test))]
     fn emit(&mut self, mapper: &SourceMapper, diagnostic: &Diagnostic) {
-        let mut stderr = StandardStream::stderr(ColorChoice::Always);
+        let choice = match self.color {
+            // `ColorChoice::Auto` only respects the environment, e.g., `NO_COLOR`, but not redirections
+            ColorMode::Auto if std::io::stderr().is_terminal() => ColorChoice::Auto,
+            ColorMode::Always => ColorChoice::Always,
+            ColorMode::Auto | ColorMode::Never => ColorChoice::Never,
+        };
+        let mut stderr = StandardStream::stderr(choice);
         for line in self.render(mapper, diagnostic) {
             for part in &line.strings {
                 stderr.set_color(&part.color).expect("cannot set output color");
@@ -157,14 +377,45 @@ impl Emitter for StderrEmitter {
     fn emit(&mut self, _mapper: &SourceMapper, _diagnostic: &Diagnostic) {}
 }
 
+/// Renders diagnostics like `StderrEmitter` into a buffer, without colors and trailing whitespace.
+/// Clones share the buffer.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct RenderedDiagnostics {
+    rendered: Rc<RefCell<String>>,
+}
+
+impl Emitter for RenderedDiagnostics {
+    fn emit(&mut self, mapper: &SourceMapper, diagnostic: &Diagnostic) {
+        let mut rendered = self.rendered.borrow_mut();
+        for line in StderrEmitter::new(ColorMode::Never).render(mapper, diagnostic) {
+            let line: String = line.strings.iter().map(|s| s.string.as_str()).collect();
+            rendered.push_str(line.trim_end());
+            rendered.push('\n');
+        }
+    }
+}
+
+impl RenderedDiagnostics {
+    pub(crate) fn new() -> RenderedDiagnostics {
+        RenderedDiagnostics::default()
+    }
+
+    /// Returns the diagnostics rendered so far.
+    pub(crate) fn rendered(&self) -> String {
+        self.rendered.borrow().clone()
+    }
+}
+
 impl StderrEmitter {
-    #[allow(dead_code)]
     fn render(&mut self, mapper: &SourceMapper, diagnostic: &Diagnostic) -> Vec<ColoredLine> {
         let mut lines = Vec::new();
 
-        // write header, e.g., `error: some error message`
+        // write header, e.g., `error[E0106]: some error message`
         let mut line = ColoredLine::new();
         line.push(&diagnostic.level.to_str(), diagnostic.level.to_color());
+        if let Some(code) = diagnostic.code {
+            line.push(&format!("[{}]", code), diagnostic.level.to_color());
+        }
         line.push(": ", ColorSpec::new());
         line.push(&diagnostic.message, ColorSpec::new().set_bold(true).clone());
         lines.push(line);
@@ -176,13 +427,12 @@ impl StderrEmitter {
             .iter()
             .flat_map(|s| mapper.get_line(s.span).map(|l| (l, s.label.clone(), s.primary)))
             .collect();
+        let line_number_length =
+            snippets.iter().map(|(s, _, _)| format!("{}", s.line_number).len()).fold(0, std::cmp::max);
 
         if !snippets.is_empty() && snippets.len() == diagnostic.span.len() {
-            let line_number_length =
-                snippets.iter().map(|(s, _, _)| format!("{}", s.line_number).len()).fold(0, std::cmp::max);
-
             // we assume the first span is the main one, i.e., we output path information
-            let path = {
+            let mut path = {
                 let (main, _, _) = snippets.first().unwrap();
 
                 // emit path information
@@ -203,7 +453,6 @@ impl StderrEmitter {
             }
 
             let mut prev_line_number = None;
-            let mut num_messages = 0;
 
             for (snippet, label, primary) in snippets {
                 fn render_source_line(snippet: &CodeLine) -> ColoredLine {
@@ -216,9 +465,22 @@ impl StderrEmitter {
                     rendered_line
                 }
 
-                assert_eq!(path, snippet.path, "assume snippets to be in same source file, use `SubDiagnostic` if not");
+                if path != snippet.path {
+                    // spans in another file, e.g., an imported one, are rendered below its path
+                    let mut rendered_line = ColoredLine::new();
+                    rendered_line.push(&" ".repeat(line_number_length), ColorSpec::new());
+                    rendered_line.push("::: ", ColorSpec::new().set_fg(Some(Color::Blue)).clone());
+                    rendered_line.push(
+                        &format!("{}:{}:{}", snippet.path.display(), snippet.line_number, snippet.column_number),
+                        ColorSpec::new(),
+                    );
+                    lines.push(rendered_line);
+                    path = snippet.path.clone();
+                    prev_line_number = None;
+                }
 
                 // source code snippet
+                let same_line = prev_line_number == Some(snippet.line_number);
                 if let Some(prev_line_number) = prev_line_number {
                     //                    assert!(prev_line_number.unwrap() <= snippet.line_number);
                     if diagnostic.sort_spans && prev_line_number + 1 < snippet.line_number {
@@ -236,7 +498,7 @@ impl StderrEmitter {
                     // print leading space
                     let mut rendered_line = ColoredLine::new();
                     rendered_line.push(
-                        &format!("{} | ", " ".repeat(line_number_length)),
+                        &format!("{} |", " ".repeat(line_number_length)),
                         ColorSpec::new().set_fg(Some(Color::Blue)).clone(),
                     );
                     lines.push(rendered_line);
@@ -253,8 +515,8 @@ impl StderrEmitter {
                     colorspec
                 };
 
-                if num_messages > 0 {
-                    // add an empty line
+                if same_line {
+                    // add an empty line connecting the label to the previous one
                     let mut empty_line = ColoredLine::new();
                     empty_line.push(
                         &format!("{} | ", " ".repeat(line_number_length)),
@@ -269,8 +531,8 @@ impl StderrEmitter {
                     &format!("{} | ", " ".repeat(line_number_length)),
                     ColorSpec::new().set_fg(Some(Color::Blue)).clone(),
                 );
-                let highlight_char: String =
-                    if primary && num_messages == 0 { String::from("^") } else { "-".repeat(num_messages + 1) };
+                // primary spans are underlined by carets, secondary spans by dashes
+                let highlight_char = if primary { "^" } else { "-" };
 
                 rendered_line.push(
                     &format!(
@@ -280,27 +542,45 @@ impl StderrEmitter {
                     ),
                     color.clone(),
                 );
-                if let Some(label) = label {
+                if let Some(label) = label.filter(|l| !l.is_empty()) {
                     rendered_line.push(&format!(" {}", label), color);
                 }
                 lines.push(rendered_line);
-                num_messages += 1;
             }
         }
 
-        /*for child in &diagnostic.children {
-            eprintln!("| {}: {}", child.level.to_str(), child.message);
+        // notes, help messages, and suggestions, e.g., `  = help: consider ...`
+        for child in &diagnostic.children {
+            let mut rendered_line = ColoredLine::new();
+            rendered_line.push(
+                &format!("{} = ", " ".repeat(line_number_length)),
+                ColorSpec::new().set_fg(Some(Color::Blue)).clone(),
+            );
+            rendered_line.push(child.level.to_str(), ColorSpec::new().set_bold(true).clone());
+            rendered_line.push(&format!(": {}", child.message), ColorSpec::new());
             if let Some(span) = child.span {
-                // TODO: actually map back to source code
-                eprintln!("| {:?}", span);
+                let (line, column) = mapper.get_position(span.start);
+                let path = mapper.path(span.start);
+                rendered_line.push(&format!(" (at {}:{}:{})", path.display(), line, column), ColorSpec::new());
             }
-        }*/
+            lines.push(rendered_line);
+        }
+        for suggestion in &diagnostic.suggestions {
+            let mut rendered_line = ColoredLine::new();
+            rendered_line.push(
+                &format!("{} = ", " ".repeat(line_number_length)),
+                ColorSpec::new().set_fg(Some(Color::Blue)).clone(),
+            );
+            rendered_line.push(Help.to_str(), ColorSpec::new().set_bold(true).clone());
+            rendered_line.push(&format!(": {}: `{}`", suggestion.message, suggestion.replacement), ColorSpec::new());
+            lines.push(rendered_line);
+        }
         lines.push(ColoredLine::new());
         lines
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) enum Level {
     /// A compiler bug
     #[allow(dead_code)]
@@ -310,19 +590,20 @@ pub(crate) enum Level {
     Fatal,
     Error,
     Warning,
-    #[allow(dead_code)]
     Note,
-    #[allow(dead_code)]
     Help,
 }
 
 /// A structured representation of a user-facing diagnostic.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct Diagnostic {
     pub(crate) level: Level,
+    /// The code of the diagnostic, only summaries such as aborting the analysis have none
+    pub(crate) code: Option<Code>,
     pub(crate) message: String,
     pub(crate) span: Vec<LabeledSpan>,
     pub(crate) children: Vec<SubDiagnostic>,
+    pub(crate) suggestions: Vec<Suggestion>,
     pub(crate) sort_spans: bool,
 }
 
@@ -341,14 +622,22 @@ impl Diagnostic {
     }
 }
 
-/// For example a note attached to an error.
-#[derive(Debug, Clone)]
+/// For example a note attached to an error, optionally pointing to a location in the source code.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct SubDiagnostic {
     pub(crate) level: Level,
     pub(crate) message: String,
     pub(crate) span: Option<Span>,
 }
 
+/// A fix of a diagnostic that tools can apply without further input, i.e., replacing the span by the replacement.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Suggestion {
+    pub(crate) message: String,
+    pub(crate) span: Span,
+    pub(crate) replacement: String,
+}
+
 impl Level {
     pub(crate) fn to_str(self) -> &'static str {
         match self {
@@ -374,11 +663,11 @@ impl Level {
 }
 
 /// Show a label (message) next to the position in source code
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub(crate) struct LabeledSpan {
-    span: Span,
-    label: Option<String>,
-    primary: bool,
+    pub(crate) span: Span,
+    pub(crate) label: Option<String>,
+    pub(crate) primary: bool,
 }
 
 impl LabeledSpan {
@@ -397,14 +686,16 @@ pub(crate) struct DiagnosticBuilder<'a> {
 }
 
 impl<'a> DiagnosticBuilder<'a> {
-    fn new(handler: &'a Handler, level: Level, messgage: &str) -> Self {
+    fn new(handler: &'a Handler, level: Level, code: Option<Code>, messgage: &str) -> Self {
         DiagnosticBuilder {
             handler,
             diagnostic: Diagnostic {
                 level,
+                code,
                 message: messgage.to_string(),
                 span: Vec::new(),
                 children: Vec::new(),
+                suggestions: Vec::new(),
                 sort_spans: true,
             },
             status: DiagnosticBuilderStatus::Building,
@@ -435,6 +726,31 @@ impl<'a> DiagnosticBuilder<'a> {
     pub(crate) fn add_labeled_span(&mut self, span: LabeledSpan) {
         self.diagnostic.span.push(span)
     }
+
+    /// Adds a note explaining the diagnostic, e.g., why a type was expected.
+    pub(crate) fn add_note(&mut self, message: &str) {
+        self.add_child(Note, message, None)
+    }
+
+    /// Adds a help message suggesting how to resolve the diagnostic.
+    pub(crate) fn add_help(&mut self, message: &str) {
+        self.add_child(Help, message, None)
+    }
+
+    /// Adds a fix replacing the span by the replacement, an empty span inserts the replacement.
+    pub(crate) fn add_suggestion(&mut self, message: &str, span: Span, replacement: &str) {
+        assert_eq!(self.status, DiagnosticBuilderStatus::Building);
+        self.diagnostic.suggestions.push(Suggestion {
+            message: message.to_string(),
+            span,
+            replacement: replacement.to_string(),
+        })
+    }
+
+    fn add_child(&mut self, level: Level, message: &str, span: Option<Span>) {
+        assert_eq!(self.status, DiagnosticBuilderStatus::Building);
+        self.diagnostic.children.push(SubDiagnostic { level, message: message.to_string(), span })
+    }
 }
 
 impl<'a> Drop for DiagnosticBuilder<'a> {
@@ -473,3 +789,74 @@ impl ColoredLine {
         self.strings.push(ColoredString { string: string.to_owned(), color })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// Renders a diagnostic with the given spans without colors.
+    fn render(spec: &str, message: &str, spans: Vec<LabeledSpan>) -> String {
+        let mapper = SourceMapper::new(PathBuf::from("spec.lola"), spec);
+        let diagnostic = Diagnostic {
+            level: Error,
+            code: Some(Code::UnknownName),
+            message: message.to_string(),
+            span: spans,
+            children: vec![],
+            suggestions: vec![],
+            sort_spans: true,
+        };
+        let lines = StderrEmitter::new(ColorMode::Never).render(&mapper, &diagnostic);
+        let lines: Vec<String> = lines.iter().map(|l| l.strings.iter().map(|s| s.string.as_str()).collect()).collect();
+        lines.join("\n")
+    }
+
+    /// The span of the first occurrence of `part` in `spec`.
+    fn span(spec: &str, part: &str) -> Span {
+        let start = spec.find(part).unwrap();
+        Span { start, end: start + part.len() }
+    }
+
+    #[test]
+    fn snippet_with_caret() {
+        let spec = "input a: Int32\noutput c := a ≠ x";
+        let rendered = render(spec, "unknown", vec![LabeledSpan::new(span(spec, "x"), "does not exist", true)]);
+        assert_eq!(
+            rendered,
+            "error[E0106]: unknown\n --> spec.lola:2:17\n  |\n2 | output c := a ≠ x\n  |                 ^ does not exist\n"
+        );
+    }
+
+    #[test]
+    fn secondary_spans() {
+        let spec = "input a: Int32\noutput b @ 1Hz := a.get()";
+        let spans = vec![
+            LabeledSpan::new(span(spec, "a.get()"), "not possible here", true),
+            LabeledSpan::new(span(spec, "a"), "declared here", false),
+            LabeledSpan::new(span(spec, "1Hz"), "periodic", false),
+        ];
+        let expected = "\
+error[E0106]: get
+ --> spec.lola:2:19
+  |
+1 | input a: Int32
+  |       - declared here
+2 | output b @ 1Hz := a.get()
+  |            --- periodic
+  |                   |
+  |                   ^^^^^^^ not possible here
+";
+        assert_eq!(render(spec, "get", spans), expected);
+    }
+
+    #[test]
+    fn empty_and_multi_line_spans() {
+        let spec = "output a :=\n  1 +\n  2";
+        let end = Span { start: spec.len(), end: spec.len() };
+        let rendered = render(spec, "missing", vec![LabeledSpan::new(end, "", true)]);
+        assert!(rendered.ends_with("3 |   2\n  |    ^\n"), "{}", rendered);
+        let rendered = render(spec, "sum", vec![LabeledSpan::new(span(spec, "1 +\n  2"), "here", true)]);
+        assert!(rendered.ends_with("2 |   1 +\n  |   ^^^ here\n"), "{}", rendered);
+    }
+}
@@ -0,0 +1,323 @@
+//! This module assigns stable codes to the diagnostics of the frontend.
+//!
+//! Errors have codes `E0xxx` and warnings `W0xxx`, grouped by the phase reporting them:
+//! `xx0xx` for parsing, `xx1xx` for naming, `xx2xx` for type checking, and `xx3xx` for the dependency analysis.
+//! Codes are never reused, so tools and documentation can rely on them instead of the message text.
+
+use std::fmt;
+use std::str::FromStr;
+
+/// The code of a kind of diagnostic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Code {
+    SyntaxError,
+    ParametersDisabled,
+    TerminationWithoutParameters,
+    ExpectedUnsignedInteger,
+    InvalidOffset,
+    UnknownAggregation,
+    ExpectedAggregation,
+    ExpectedMethodOrTupleAccess,
+    MissingExpression,
+    ExpectedStreamVariable,
+    UnsupportedOffset,
+    InvalidFieldAccess,
+    InvalidWindowDuration,
+    InvalidRegex,
+    RegexNotLiteral,
+    InvalidAttribute,
+    TemplateWithoutParameters,
+    InvalidEscape,
+    ReservedKeyword,
+    DuplicateDefinition,
+    UnknownType,
+    DuplicateParameter,
+    UnresolvedImport,
+    UnknownName,
+    UnknownFunction,
+    CyclicImport,
+    UnknownPlaceholder,
+    InvalidSampleAndHold,
+    SlidingWindowInEventStream,
+    ExpectedDuration,
+    IncompatibleStreamTypes,
+    InvalidFrequency,
+    SelfReferenceInActivation,
+    ExpectedStream,
+    InvalidActivationCondition,
+    UnexpectedUnit,
+    WrongNumberOfArguments,
+    UnknownMethod,
+    CannotInferType,
+    TupleIndexOutOfRange,
+    UnknownField,
+    LiteralOutOfRange,
+    TypeMismatch,
+    ConflictingConstraints,
+    InvalidEscalation,
+    MissingParameters,
+    CycleWithPeriodicStream,
+    ZeroWeightCycle,
+    NonConstantExpression,
+    ConstantEvaluationFailed,
+    UnboundedMemory,
+    UnboundedLookAhead,
+    UnsupportedLookAhead,
+    UnbalancedParentheses,
+    DeprecatedStream,
+    UnnecessarySampleAndHold,
+    FloatEquality,
+    WideningDefault,
+    PositiveWeightCycle,
+}
+
+impl Code {
+    /// All codes in the order of their identifiers.
+    pub(crate) const ALL: [Code; 59] = [
+        Code::SyntaxError,
+        Code::ParametersDisabled,
+        Code::TerminationWithoutParameters,
+        Code::ExpectedUnsignedInteger,
+        Code::InvalidOffset,
+        Code::UnknownAggregation,
+        Code::ExpectedAggregation,
+        Code::ExpectedMethodOrTupleAccess,
+        Code::MissingExpression,
+        Code::ExpectedStreamVariable,
+        Code::UnsupportedOffset,
+        Code::InvalidFieldAccess,
+        Code::InvalidWindowDuration,
+        Code::InvalidRegex,
+        Code::RegexNotLiteral,
+        Code::InvalidAttribute,
+        Code::TemplateWithoutParameters,
+        Code::InvalidEscape,
+        Code::ReservedKeyword,
+        Code::DuplicateDefinition,
+        Code::UnknownType,
+        Code::DuplicateParameter,
+        Code::UnresolvedImport,
+        Code::UnknownName,
+        Code::UnknownFunction,
+        Code::CyclicImport,
+        Code::UnknownPlaceholder,
+        Code::InvalidSampleAndHold,
+        Code::SlidingWindowInEventStream,
+        Code::ExpectedDuration,
+        Code::IncompatibleStreamTypes,
+        Code::InvalidFrequency,
+        Code::SelfReferenceInActivation,
+        Code::ExpectedStream,
+        Code::InvalidActivationCondition,
+        Code::UnexpectedUnit,
+        Code::WrongNumberOfArguments,
+        Code::UnknownMethod,
+        Code::CannotInferType,
+        Code::TupleIndexOutOfRange,
+        Code::UnknownField,
+        Code::LiteralOutOfRange,
+        Code::TypeMismatch,
+        Code::ConflictingConstraints,
+        Code::InvalidEscalation,
+        Code::MissingParameters,
+        Code::CycleWithPeriodicStream,
+        Code::ZeroWeightCycle,
+        Code::NonConstantExpression,
+        Code::ConstantEvaluationFailed,
+        Code::UnboundedMemory,
+        Code::UnboundedLookAhead,
+        Code::UnsupportedLookAhead,
+        Code::UnbalancedParentheses,
+        Code::DeprecatedStream,
+        Code::UnnecessarySampleAndHold,
+        Code::FloatEquality,
+        Code::WideningDefault,
+        Code::PositiveWeightCycle,
+    ];
+
+    /// Returns the identifier of the code, e.g., `E0101`.
+    pub(crate) fn id(self) -> &'static str {
+        use self::Code::*;
+        match self {
+            SyntaxError => "E0001",
+            ParametersDisabled => "E0002",
+            TerminationWithoutParameters => "E0003",
+            ExpectedUnsignedInteger => "E0004",
+            InvalidOffset => "E0005",
+            UnknownAggregation => "E0006",
+            ExpectedAggregation => "E0007",
+            ExpectedMethodOrTupleAccess => "E0008",
+            MissingExpression => "E0009",
+            ExpectedStreamVariable => "E0010",
+            UnsupportedOffset => "E0011",
+            InvalidFieldAccess => "E0012",
+            InvalidWindowDuration => "E0013",
+            InvalidRegex => "E0014",
+            RegexNotLiteral => "E0015",
+            InvalidAttribute => "E0016",
+            TemplateWithoutParameters => "E0017",
+            InvalidEscape => "E0018",
+            ReservedKeyword => "E0101",
+            DuplicateDefinition => "E0102",
+            UnknownType => "E0103",
+            DuplicateParameter => "E0104",
+            UnresolvedImport => "E0105",
+            UnknownName => "E0106",
+            UnknownFunction => "E0107",
+            CyclicImport => "E0108",
+            UnknownPlaceholder => "E0109",
+            InvalidSampleAndHold => "E0201",
+            SlidingWindowInEventStream => "E0202",
+            ExpectedDuration => "E0203",
+            IncompatibleStreamTypes => "E0204",
+            InvalidFrequency => "E0205",
+            SelfReferenceInActivation => "E0206",
+            ExpectedStream => "E0207",
+            InvalidActivationCondition => "E0208",
+            UnexpectedUnit => "E0209",
+            WrongNumberOfArguments => "E0210",
+            UnknownMethod => "E0211",
+            CannotInferType => "E0212",
+            TupleIndexOutOfRange => "E0213",
+            UnknownField => "E0214",
+            LiteralOutOfRange => "E0215",
+            TypeMismatch => "E0216",
+            ConflictingConstraints => "E0217",
+            InvalidEscalation => "E0218",
+            MissingParameters => "E0219",
+            CycleWithPeriodicStream => "E0301",
+            ZeroWeightCycle => "E0302",
+            NonConstantExpression => "E0303",
+            ConstantEvaluationFailed => "E0304",
+            UnboundedMemory => "E0305",
+            UnboundedLookAhead => "E0306",
+            UnsupportedLookAhead => "E0307",
+            UnbalancedParentheses => "W0001",
+            DeprecatedStream => "W0102",
+            UnnecessarySampleAndHold => "W0201",
+            FloatEquality => "W0202",
+            WideningDefault => "W0203",
+            PositiveWeightCycle => "W0301",
+        }
+    }
+
+    /// Returns whether diagnostics with this code are warnings.
+    pub(crate) fn is_warning(self) -> bool {
+        self.id().starts_with('W')
+    }
+
+    /// Returns a short description of the diagnostics with this code.
+    pub(crate) fn description(self) -> &'static str {
+        use self::Code::*;
+        match self {
+            SyntaxError => "The specification is not syntactically valid.",
+            ParametersDisabled => "Parameterized streams are disabled in the configuration of the frontend.",
+            TerminationWithoutParameters => "Only parameterized streams can have a termination condition.",
+            ExpectedUnsignedInteger => "Tuple fields are accessed by unsigned integer literals.",
+            InvalidOffset => "The offset of a stream access is not an integer or a duration.",
+            UnknownAggregation => "The aggregation function of a sliding window does not exist.",
+            ExpectedAggregation => "The second argument of a sliding window is not an aggregation function.",
+            ExpectedMethodOrTupleAccess => "Only methods and tuple fields can follow a dot.",
+            MissingExpression => "An expression is missing.",
+            ExpectedStreamVariable => "Stream operations such as offsets and windows only apply to streams.",
+            UnsupportedOffset => "Offsets have to be non-zero and real-time offsets negative.",
+            InvalidFieldAccess => "Tuple fields are accessed by integers.",
+            InvalidWindowDuration => "The duration of a sliding window is not a positive duration.",
+            InvalidRegex => "The pattern of `matches` is not a valid regular expression.",
+            RegexNotLiteral => "The pattern of `matches` is not a string literal.",
+            InvalidAttribute => "An attribute is unknown or malformed, or refers to a code that is not a warning.",
+            TemplateWithoutParameters => "Only parameterized streams can have a template of their instances.",
+            InvalidEscape => "A string literal or trigger message contains an unknown escape sequence.",
+            ReservedKeyword => "A keyword is used as a name.",
+            DuplicateDefinition => "A name is defined multiple times.",
+            UnknownType => "A type does not exist.",
+            DuplicateParameter => "A parameter name is used more than once in a parameter list.",
+            UnresolvedImport => "An imported module or file does not exist.",
+            UnknownName => "A stream, parameter, or constant does not exist.",
+            UnknownFunction => "A function does not exist, maybe its module has not been imported.",
+            CyclicImport => "A file imports itself, directly or through other imported files.",
+            UnknownPlaceholder => {
+                "A placeholder in a trigger message refers to no stream, braces are escaped as `{{` and `}}`."
+            }
+            InvalidSampleAndHold => "`get()` accesses a stream with a different kind of timing.",
+            SlidingWindowInEventStream => "Sliding windows are only allowed in real-time streams.",
+            ExpectedDuration => "A duration is expected, e.g., `1s`.",
+            IncompatibleStreamTypes => "The timing of the accessed streams is incompatible.",
+            InvalidFrequency => "The frequency of a real-time stream is invalid.",
+            SelfReferenceInActivation => "The activation condition of a stream refers to the stream itself.",
+            ExpectedStream => "An activation condition refers to something other than a stream.",
+            InvalidActivationCondition => "Activation conditions are conjunctions and disjunctions of event streams.",
+            UnexpectedUnit => "A unit is not allowed for this numeric value.",
+            WrongNumberOfArguments => "A function is called with the wrong number of arguments.",
+            UnknownMethod => "A method does not exist for the type.",
+            CannotInferType => "The type cannot be inferred, a type annotation is needed.",
+            TupleIndexOutOfRange => "The tuple has no field with this index.",
+            UnknownField => "The type has no field with this name.",
+            LiteralOutOfRange => "A numeric literal does not fit into its type.",
+            TypeMismatch => "The types of an expression do not match.",
+            ConflictingConstraints => "No type satisfies all requirements of an expression.",
+            InvalidEscalation => {
+                "The escalation of a trigger is not a positive number of violations within a positive duration."
+            }
+            MissingParameters => "A parameterized stream is accessed without parameters, e.g., by a sliding window.",
+            CycleWithPeriodicStream => "A periodic stream is part of a cycle of real-time offsets.",
+            ZeroWeightCycle => "Streams depend on each other without offset, so they cannot be evaluated.",
+            NonConstantExpression => {
+                "A constant is defined by an expression that is not constant, e.g., it refers to a stream or to itself."
+            }
+            ConstantEvaluationFailed => {
+                "The evaluation of a constant fails, e.g., by an overflow or a division by zero."
+            }
+            UnboundedMemory => "A stream annotated with `#[memory(bounded)]` needs an unbounded amount of memory.",
+            UnboundedLookAhead => "A stream depends on its own future values, so its evaluation is deferred forever.",
+            UnsupportedLookAhead => {
+                "Future values are only resolved for event-driven streams accessing streams with the same activation."
+            }
+            UnbalancedParentheses => "An expression is missing an opening or a closing parenthesis.",
+            DeprecatedStream => "A stream annotated with `#[deprecated]` is accessed by another declaration.",
+            UnnecessarySampleAndHold => "`hold()` or `get()` is not needed to access the stream.",
+            FloatEquality => {
+                "Floating point values are compared with `=` or `!=`, which is sensitive to rounding errors."
+            }
+            WideningDefault => "The default of a stream access implicitly widens the type of the accessed stream.",
+            PositiveWeightCycle => "Streams depend on future values of each other, which delays verdicts.",
+        }
+    }
+}
+
+impl fmt::Display for Code {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.id())
+    }
+}
+
+impl FromStr for Code {
+    type Err = String;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        Code::ALL.iter().copied().find(|c| c.id() == id).ok_or_else(|| format!("unknown diagnostic code `{}`", id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_are_unique_and_ordered() {
+        let ids: Vec<&str> = Code::ALL.iter().map(|c| c.id()).collect();
+        let unique: HashSet<&str> = ids.iter().copied().collect();
+        assert_eq!(unique.len(), ids.len());
+        let (errors, warnings): (Vec<&str>, Vec<&str>) = ids.iter().partition(|id| id.starts_with('E'));
+        assert!(errors.windows(2).all(|w| w[0] < w[1]), "{:?}", errors);
+        assert!(warnings.windows(2).all(|w| w[0] < w[1]), "{:?}", warnings);
+    }
+
+    #[test]
+    fn parse_codes() {
+        assert_eq!("E0106".parse::<Code>(), Ok(Code::UnknownName));
+        assert_eq!("W0301".parse::<Code>(), Ok(Code::PositiveWeightCycle));
+        assert!("E9999".parse::<Code>().is_err());
+    }
+}
@@ -0,0 +1,269 @@
+//! This module renders diagnostics as JSON objects, one per diagnostic, for tools such as IDEs and CI bots.
+//!
+//! A diagnostic has the fields `severity`, `code`, `message`, `spans`, `notes`, and `suggestions`.
+//! Spans give the file, the line and column of their start and end, both starting at 1, their label, and whether they are the primary span.
+//! Suggestions are fixes that editors can apply as is, replacing their `span` by the `replacement`.
+
+use super::sarif::string;
+use super::{Code, Diagnostic, Emitter, Level};
+use crate::parse::{Rule, SourceMapper, Span};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// Collects diagnostics as JSON objects; clones share the collected diagnostics.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct JsonDiagnostics {
+    diagnostics: Rc<RefCell<Vec<String>>>,
+}
+
+impl Emitter for JsonDiagnostics {
+    fn emit(&mut self, mapper: &SourceMapper, diagnostic: &Diagnostic) {
+        let spans: Vec<String> =
+            diagnostic.span.iter().map(|s| span(mapper, s.span, s.label.as_deref(), s.primary)).collect();
+        let notes: Vec<String> = diagnostic
+            .children
+            .iter()
+            .map(|child| {
+                let spans: Vec<String> = child.span.iter().map(|s| span(mapper, *s, None, true)).collect();
+                format!(
+                    r#"{{"severity": {}, "message": {}, "spans": [{}]}}"#,
+                    string(severity(child.level)),
+                    string(&child.message),
+                    spans.join(", ")
+                )
+            })
+            .collect();
+        let suggestions: Vec<String> = diagnostic
+            .suggestions
+            .iter()
+            .map(|suggestion| {
+                format!(
+                    r#"{{"message": {}, "span": {}, "replacement": {}}}"#,
+                    string(&suggestion.message),
+                    span(mapper, suggestion.span, None, true),
+                    string(&suggestion.replacement)
+                )
+            })
+            .collect();
+        let object = self::diagnostic(
+            severity(diagnostic.level),
+            diagnostic.code,
+            &diagnostic.message,
+            &spans,
+            &notes,
+            &suggestions,
+        );
+        self.diagnostics.borrow_mut().push(object);
+    }
+}
+
+impl JsonDiagnostics {
+    pub(crate) fn new() -> JsonDiagnostics {
+        JsonDiagnostics::default()
+    }
+
+    /// Adds a diagnostic for a syntax error, which is reported by the parser instead of the handler.
+    pub(crate) fn add_parse_error(&self, mapper: &SourceMapper, error: &pest::error::Error<Rule>) {
+        use pest::error::InputLocation;
+        let location = match error.location {
+            InputLocation::Pos(pos) => Span { start: pos, end: pos },
+            InputLocation::Span((start, end)) => Span { start, end },
+        };
+        let spans = [span(mapper, location, None, true)];
+        let message = error.variant.message();
+        self.diagnostics.borrow_mut().push(diagnostic("error", Some(Code::SyntaxError), &message, &spans, &[], &[]));
+    }
+
+    /// Returns the collected diagnostics in the order they were emitted.
+    pub(crate) fn diagnostics(&self) -> Vec<String> {
+        self.diagnostics.borrow().clone()
+    }
+}
+
+fn severity(level: Level) -> &'static str {
+    match level {
+        Level::Bug | Level::Fatal | Level::Error => "error",
+        Level::Warning => "warning",
+        Level::Note => "note",
+        Level::Help => "help",
+    }
+}
+
+fn diagnostic(
+    severity: &str,
+    code: Option<Code>,
+    message: &str,
+    spans: &[String],
+    notes: &[String],
+    suggestions: &[String],
+) -> String {
+    format!(
+        r#"{{"severity": {}, "code": {}, "message": {}, "spans": [{}], "notes": [{}], "suggestions": [{}]}}"#,
+        string(severity),
+        code.map_or("null".to_string(), |c| string(c.id())),
+        string(message),
+        spans.join(", "),
+        notes.join(", "),
+        suggestions.join(", ")
+    )
+}
+
+fn span(mapper: &SourceMapper, span: Span, label: Option<&str>, primary: bool) -> String {
+    let (line_start, column_start) = mapper.get_position(span.start);
+    let (line_end, column_end) = mapper.get_position(span.end.max(span.start));
+    format!(
+        r#"{{"file": {}, "line_start": {}, "column_start": {}, "line_end": {}, "column_end": {}, "label": {}, "primary": {}}}"#,
+        string(&mapper.path(span.start).to_string_lossy()),
+        line_start,
+        column_start,
+        line_end,
+        column_end,
+        label.filter(|l| !l.is_empty()).map_or("null".to_string(), string),
+        primary
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporting::Handler;
+    use crate::FrontendConfig;
+    use std::path::PathBuf;
+
+    fn analyze(spec: &str, allowed: &[Code]) -> Vec<String> {
+        let diagnostics = JsonDiagnostics::new();
+        let mapper = || SourceMapper::new(PathBuf::from("specs/example.lola"), spec);
+        let mut handler = Handler::with_emitter(mapper(), Box::new(diagnostics.clone()));
+        allowed.iter().for_each(|code| handler.allow(*code));
+        match crate::parse::parse(spec, &handler, FrontendConfig::default()) {
+            Ok(ast) => {
+                let _ = crate::analysis::analyze(
+                    &ast,
+                    &handler,
+                    FrontendConfig::default(),
+                    &crate::stdlib::STANDARD_LIBRARY,
+                );
+            }
+            Err(e) => diagnostics.add_parse_error(&mapper(), &e),
+        }
+        diagnostics.diagnostics()
+    }
+
+    #[test]
+    fn analysis_errors() {
+        let diagnostics = analyze("input a: Int32\noutput b: Int32 := c", &[]);
+        // the name error is followed by the note that the analysis was aborted, which has no code
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert!(diagnostics[1].contains(r#""code": null"#), "{}", diagnostics[1]);
+        let diagnostic = &diagnostics[0];
+        assert!(diagnostic.starts_with(r#"{"severity": "error", "code": "E0106", "message": "#), "{}", diagnostic);
+        assert!(diagnostic.contains(r#""file": "specs/example.lola""#), "{}", diagnostic);
+        assert!(
+            diagnostic.contains(r#""line_start": 2, "column_start": 20, "line_end": 2, "column_end": 21"#),
+            "{}",
+            diagnostic
+        );
+        assert!(diagnostic.contains(r#""label": "does not exist", "primary": true"#), "{}", diagnostic);
+        assert!(!diagnostic.contains('\n'));
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let diagnostics = analyze("input a: Int32\noutput := a", &[]);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert!(diagnostics[0].contains(r#""code": "E0001""#), "{}", diagnostics[0]);
+        assert!(diagnostics[0].contains(r#""line_start": 2"#), "{}", diagnostics[0]);
+    }
+
+    #[test]
+    fn suppressed_warnings() {
+        let spec = "input a: Int32\noutput b @ a := a.hold().defaults(to: 0)";
+        let diagnostics = analyze(spec, &[]);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert!(diagnostics[0].starts_with(r#"{"severity": "warning", "code": "W0201""#), "{}", diagnostics[0]);
+        assert!(analyze(spec, &[Code::UnnecessarySampleAndHold]).is_empty());
+    }
+
+    #[test]
+    fn notes_and_secondary_spans() {
+        let diagnostics = analyze("input a: Int32\noutput b @ 1Hz := a.get().defaults(to: 0)", &[]);
+        let diagnostic = &diagnostics[0];
+        assert!(diagnostic.contains(r#""code": "E0201""#), "{}", diagnostic);
+        assert!(
+            diagnostic.contains(r#""line_start": 1, "column_start": 7, "line_end": 1, "column_end": 8, "label": "stream declared here", "primary": false"#),
+            "{}",
+            diagnostic
+        );
+        assert!(diagnostic.contains(r#"{"severity": "note", "message": "the accessed stream is"#), "{}", diagnostic);
+        assert!(diagnostic.contains(r#"{"severity": "help", "message": "use `.hold()`"#), "{}", diagnostic);
+
+        let diagnostics = analyze("output a @ 1Hz := sqrt(2.0)", &[]);
+        assert!(
+            diagnostics[0].contains(r#""notes": [{"severity": "help", "message": "the module `math` declares `sqrt`, consider `import math`", "spans": []}]"#),
+            "{}",
+            diagnostics[0]
+        );
+    }
+
+    #[test]
+    fn suggestions() {
+        let diagnostics = analyze("input a: Int32\noutput b: Int32 := a.offset(by: -1)", &[]);
+        assert!(
+            diagnostics[0].contains(r#""suggestions": [{"message": "add a default value for the case that the stream has no value", "span": {"file": "specs/example.lola", "line_start": 2, "column_start": 36, "line_end": 2, "column_end": 36, "label": null, "primary": true}, "replacement": ".defaults(to: 0)"}]"#),
+            "{}",
+            diagnostics[0]
+        );
+
+        let diagnostics = analyze("input altitude: Float64\noutput b: Float64 := altitdue", &[]);
+        assert!(diagnostics[0].contains(r#""replacement": "altitude""#), "{}", diagnostics[0]);
+
+        let diagnostics = analyze("output c @ 1Hz := c.offset(by: -1).defaults(to: 0).abs()", &[]);
+        assert!(diagnostics[0].contains(r#""message": "annotate the type of `c`""#), "{}", diagnostics[0]);
+        assert!(diagnostics[0].contains(r#""replacement": ": Int64""#), "{}", diagnostics[0]);
+
+        let diagnostics = analyze("input a: Int32\noutput b: Int32 := a[0]", &[]);
+        assert!(diagnostics[0].contains(r#""column_start": 20, "line_end": 2, "column_end": 24, "label": null, "primary": true}, "replacement": "a"}"#), "{}", diagnostics[0]);
+
+        let diagnostics = analyze("input a: Float64\ninput b: Float64\noutput c := a = b - 1.0", &[]);
+        assert!(diagnostics[0].contains(r#""replacement": "abs(a - (b - 1.0)) < 0.000001""#), "{}", diagnostics[0]);
+
+        let diagnostics = analyze("input a: Int8\noutput b: Int64 := a.offset(by: -1).defaults(to: 0)", &[]);
+        assert!(diagnostics[0].contains(r#""code": "W0203""#), "{}", diagnostics[0]);
+        assert!(
+            diagnostics[0].contains(r#""replacement": "cast(a.offset(by: -1).defaults(to: 0))""#),
+            "{}",
+            diagnostics[0]
+        );
+
+        let diagnostics = analyze("input a: Int32\noutput b: Int32 := a", &[]);
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+    }
+
+    #[test]
+    fn cascading_errors() {
+        // `c` and the trigger only fail because of the unknown name in `b`, `d` fails on its own
+        let spec = "input a: Int64\noutput b := x + 1\noutput c := b + 1\noutput d: Bool := a\ntrigger c > 2 \"c\"";
+        let diagnostics = analyze(spec, &[]);
+        assert_eq!(diagnostics.len(), 3, "{:?}", diagnostics);
+        assert!(diagnostics[0].contains(r#""code": "E0106""#), "{}", diagnostics[0]);
+        assert!(diagnostics[1].contains(r#""code": "E0216""#), "{}", diagnostics[1]);
+        assert!(diagnostics[1].contains(r#""line_start": 4"#), "{}", diagnostics[1]);
+    }
+
+    #[test]
+    fn duplicate_errors() {
+        // both the naming and the type analysis resolve imports
+        let diagnostics = analyze("import foo\ninput a: Int64\noutput b := a + 1", &[]);
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert!(diagnostics[0].contains(r#""code": "E0105""#), "{}", diagnostics[0]);
+    }
+
+    #[test]
+    fn error_limit() {
+        let spec: Vec<String> = (0..25).map(|i| format!("output o{} := x{}", i, i)).collect();
+        let diagnostics = analyze(&spec.join("\n"), &[]);
+        assert_eq!(diagnostics.len(), 22, "{:?}", diagnostics);
+        assert!(diagnostics[19].contains(r#""message": "name `x19` does not exist in current scope""#));
+        assert!(diagnostics[20].starts_with(r#"{"severity": "note", "code": null, "message": "5 more errors omitted""#));
+    }
+}
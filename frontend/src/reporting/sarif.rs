@@ -0,0 +1,224 @@
+//! This module renders diagnostics in the Static Analysis Results Interchange Format (SARIF) 2.1.0.
+//!
+//! Code scanning UIs, e.g., of GitHub or GitLab, read SARIF logs and display the findings inline.
+
+use super::{Code, Diagnostic, Emitter, Level};
+use crate::parse::{Rule, SourceMapper, Span};
+use std::cell::RefCell;
+use std::rc::Rc;
+
+/// The rule of diagnostics without code, e.g., the note that the analysis was aborted.
+const GENERIC_RULE: (&str, &str) = ("generic", "Summary of the analysis.");
+
+/// Collects diagnostics as SARIF results; clones share the collected results.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SarifLog {
+    results: Rc<RefCell<Vec<String>>>,
+}
+
+impl Emitter for SarifLog {
+    fn emit(&mut self, mapper: &SourceMapper, diagnostic: &Diagnostic) {
+        let rule = diagnostic.code.map_or(GENERIC_RULE.0, Code::id);
+        let level = match diagnostic.level {
+            Level::Bug | Level::Fatal | Level::Error => "error",
+            Level::Warning => "warning",
+            Level::Note | Level::Help => "note",
+        };
+        let primary = diagnostic.span.iter().position(|s| s.primary).unwrap_or(0);
+        let mut message = diagnostic.message.clone();
+        let mut locations = vec![];
+        let mut related = vec![];
+        for (ix, span) in diagnostic.span.iter().enumerate() {
+            let label = span.label.as_deref().filter(|l| !l.is_empty());
+            if ix == primary {
+                if let Some(label) = label {
+                    message = format!("{}: {}", message, label);
+                }
+                locations.push(location(mapper, span.span, None));
+            } else {
+                related.push(location(mapper, span.span, label));
+            }
+        }
+        // notes and help messages have no dedicated place in results, so they are appended to the message
+        for child in &diagnostic.children {
+            message = format!("{}\n{}: {}", message, child.level.to_str(), child.message);
+        }
+        let fixes: Vec<String> =
+            diagnostic.suggestions.iter().map(|s| fix(mapper, &s.message, s.span, &s.replacement)).collect();
+        self.results.borrow_mut().push(result(rule, level, &message, &locations, &related, &fixes));
+    }
+}
+
+impl SarifLog {
+    pub(crate) fn new() -> SarifLog {
+        SarifLog::default()
+    }
+
+    /// Adds a result for a syntax error, which is reported by the parser instead of the handler.
+    pub(crate) fn add_parse_error(&self, mapper: &SourceMapper, error: &pest::error::Error<Rule>) {
+        use pest::error::InputLocation;
+        let span = match error.location {
+            InputLocation::Pos(pos) => Span { start: pos, end: pos },
+            InputLocation::Span((start, end)) => Span { start, end },
+        };
+        let locations = [location(mapper, span, None)];
+        let message = error.variant.message();
+        self.results.borrow_mut().push(result(Code::SyntaxError.id(), "error", &message, &locations, &[], &[]));
+    }
+
+    /// Renders the log containing all collected results.
+    pub(crate) fn render(&self) -> String {
+        let rules: Vec<String> = Code::ALL
+            .iter()
+            .map(|code| (code.id(), code.description()))
+            .chain(std::iter::once(GENERIC_RULE))
+            .map(|(id, text)| format!(r#"{{"id": {}, "shortDescription": {{"text": {}}}}}"#, string(id), string(text)))
+            .collect();
+        let driver = format!(
+            r#"{{"name": "rtlola-analyze", "version": {}, "informationUri": "http://rtlola.org", "rules": [{}]}}"#,
+            string(env!("CARGO_PKG_VERSION")),
+            rules.join(", ")
+        );
+        format!(
+            r#"{{"$schema": {}, "version": "2.1.0", "runs": [{{"tool": {{"driver": {}}}, "results": [{}]}}]}}"#,
+            string("https://json.schemastore.org/sarif-2.1.0.json"),
+            driver,
+            self.results.borrow().join(", ")
+        )
+    }
+}
+
+fn result(
+    rule: &str,
+    level: &str,
+    message: &str,
+    locations: &[String],
+    related: &[String],
+    fixes: &[String],
+) -> String {
+    format!(
+        r#"{{"ruleId": {}, "level": {}, "message": {{"text": {}}}, "locations": [{}], "relatedLocations": [{}], "fixes": [{}]}}"#,
+        string(rule),
+        string(level),
+        string(message),
+        locations.join(", "),
+        related.join(", "),
+        fixes.join(", ")
+    )
+}
+
+fn location(mapper: &SourceMapper, span: Span, label: Option<&str>) -> String {
+    let message = label.map_or(String::new(), |l| format!(r#", "message": {{"text": {}}}"#, string(l)));
+    format!(
+        r#"{{"physicalLocation": {{"artifactLocation": {}, "region": {}}}{}}}"#,
+        artifact(mapper, span),
+        region(mapper, span),
+        message
+    )
+}
+
+/// Renders a fix replacing the span, an empty span is an insertion.
+fn fix(mapper: &SourceMapper, description: &str, span: Span, replacement: &str) -> String {
+    let replacement = format!(
+        r#"{{"deletedRegion": {}, "insertedContent": {{"text": {}}}}}"#,
+        region(mapper, span),
+        string(replacement)
+    );
+    format!(
+        r#"{{"description": {{"text": {}}}, "artifactChanges": [{{"artifactLocation": {}, "replacements": [{}]}}]}}"#,
+        string(description),
+        artifact(mapper, span),
+        replacement
+    )
+}
+
+fn region(mapper: &SourceMapper, span: Span) -> String {
+    let (start_line, start_column) = mapper.get_position(span.start);
+    let (end_line, end_column) = mapper.get_position(span.end.max(span.start));
+    format!(
+        r#"{{"startLine": {}, "startColumn": {}, "endLine": {}, "endColumn": {}}}"#,
+        start_line, start_column, end_line, end_column
+    )
+}
+
+fn artifact(mapper: &SourceMapper, span: Span) -> String {
+    // SARIF uses forward slashes in URIs, relative paths are resolved against the root of the repository.
+    let uri = mapper.path(span.start).to_string_lossy().replace('\\', "/");
+    format!(r#"{{"uri": {}}}"#, string(&uri))
+}
+
+/// Renders a JSON string literal.
+pub(super) fn string(s: &str) -> String {
+    let mut res = String::with_capacity(s.len() + 2);
+    res.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            '\n' => res.push_str("\\n"),
+            '\r' => res.push_str("\\r"),
+            '\t' => res.push_str("\\t"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reporting::Handler;
+    use crate::FrontendConfig;
+    use std::path::PathBuf;
+
+    fn analyze(spec: &str) -> String {
+        let log = SarifLog::new();
+        let mapper = || SourceMapper::new(PathBuf::from("specs/example.lola"), spec);
+        let handler = Handler::with_emitter(mapper(), Box::new(log.clone()));
+        match crate::parse::parse(spec, &handler, FrontendConfig::default()) {
+            Ok(ast) => {
+                let _ = crate::analysis::analyze(
+                    &ast,
+                    &handler,
+                    FrontendConfig::default(),
+                    &crate::stdlib::STANDARD_LIBRARY,
+                );
+            }
+            Err(e) => log.add_parse_error(&mapper(), &e),
+        }
+        log.render()
+    }
+
+    #[test]
+    fn analysis_errors() {
+        let log = analyze("input a: Int32\noutput b: Int32 := c");
+        assert!(log.contains(r#""ruleId": "E0106", "level": "error""#), "{}", log);
+        assert!(log.contains(r#""artifactLocation": {"uri": "specs/example.lola"}"#), "{}", log);
+        assert!(log.contains(r#""startLine": 2, "startColumn": 20, "endLine": 2, "endColumn": 21"#), "{}", log);
+    }
+
+    #[test]
+    fn syntax_errors() {
+        let log = analyze("input a: Int32\noutput := a");
+        assert!(log.contains(r#""ruleId": "E0001""#), "{}", log);
+        assert!(log.contains(r#""startLine": 2"#), "{}", log);
+    }
+
+    #[test]
+    fn fixes() {
+        let log = analyze("input a: Bool\noutput b: Bool := a.offset(by: -1)");
+        assert!(
+            log.contains(r#""replacements": [{"deletedRegion": {"startLine": 2, "startColumn": 35, "endLine": 2, "endColumn": 35}, "insertedContent": {"text": ".defaults(to: false)"}}]"#),
+            "{}",
+            log
+        );
+    }
+
+    #[test]
+    fn escaping() {
+        assert_eq!(string("a \"b\"\n\\"), r#""a \"b\"\n\\""#);
+        assert_eq!(string("\u{1}"), r#""\u0001""#);
+    }
+}
@@ -8,7 +8,7 @@ use std::collections::HashMap;
 
 /// A (possibly generic) function declaration
 #[derive(Debug, Clone)]
-pub(crate) struct FuncDecl {
+pub struct FuncDecl {
     pub(crate) name: FunctionName,
     pub(crate) generics: Vec<ValueTy>,
     pub(crate) parameters: Vec<ValueTy>,
@@ -16,6 +16,22 @@ pub(crate) struct FuncDecl {
 }
 
 impl FuncDecl {
+    /**
+    Declares the function `name`, the arguments are named by `arg_names` where `None` marks a positional argument.
+
+    The generic parameters are given as constraints, e.g., `TypeConstraint::Numeric`, and are referred to in the types of the parameters and the return type by `ValueTy::Param` with their index.
+    */
+    pub fn new(
+        name: &str,
+        arg_names: &[Option<&str>],
+        generics: Vec<ValueTy>,
+        parameters: Vec<ValueTy>,
+        return_type: ValueTy,
+    ) -> Self {
+        let arg_names: Vec<Option<String>> = arg_names.iter().map(|n| n.map(str::to_string)).collect();
+        FuncDecl { name: FunctionName::new(name.to_string(), &arg_names), generics, parameters, return_type }
+    }
+
     /// Given the instantiation of the generic parameters, this function returns the instantiated types of the arguments and return type.
     /// For example `sqrt<T>(_: T) -> T` is `sqrt<T>(_: Float32) -> Float32` when `T` is instantiated by `Float32`.
     pub(crate) fn get_types_for_args_and_ret(&self, generics: &[ValueTy]) -> (Vec<ValueTy>, ValueTy) {
@@ -28,7 +44,13 @@ impl BinOp {
     pub(crate) fn get_func_decl(self) -> FuncDecl {
         use self::BinOp::*;
         match self {
-            Add | Sub | Mul | Div | Rem | Pow => FuncDecl {
+            Add | Sub => FuncDecl {
+                name: FunctionName::new(format!("{}", self), &[None, None]),
+                generics: vec![ValueTy::Constr(TypeConstraint::Additive)],
+                parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+                return_type: ValueTy::Param(0, "T".to_string()),
+            },
+            Mul | Div | Rem | Pow => FuncDecl {
                 name: FunctionName::new(format!("{}", self), &[None, None]),
                 generics: vec![ValueTy::Constr(TypeConstraint::Numeric)],
                 parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
@@ -136,14 +158,29 @@ lazy_static! {
         parameters: vec![ValueTy::Param(0, "T".to_string())],
         return_type: ValueTy::Param(0, "T".to_string()),
     };
-     // fn arctan<T: FloatingPoint>(T) -> T
+    // fn arctan<T: FloatingPoint>(T) -> T
     static ref ARCTAN: FuncDecl = FuncDecl {
         name: FunctionName::new("arctan".to_string(), &[None]),
-        generics: vec![ ValueTy::Constr(TypeConstraint::FloatingPoint),
-        ],
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
         parameters: vec![ValueTy::Param(0, "T".to_string())],
         return_type: ValueTy::Param(0, "T".to_string()),
     };
+    /// fn atan2<T: FloatingPoint>(T, T) -> T
+    /// the angle of the point `(x, y)` given as `atan2(y, x)`, in the range from -pi to pi
+    static ref ATAN2: FuncDecl = FuncDecl {
+        name: FunctionName::new("atan2".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    /// fn hypot<T: FloatingPoint>(T, T) -> T
+    /// the length of the hypotenuse, computed without intermediate overflow
+    static ref HYPOT: FuncDecl = FuncDecl {
+        name: FunctionName::new("hypot".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
     // fn abs<T: Numeric>(T) -> T
     static ref ABS: FuncDecl = FuncDecl {
         name: FunctionName::new("abs".to_string(), &[None]),
@@ -152,6 +189,54 @@ lazy_static! {
         parameters: vec![ValueTy::Param(0, "T".to_string())],
         return_type: ValueTy::Param(0, "T".to_string()),
     };
+    // fn clamp<T: Numeric>(T, lo: T, hi: T) -> T
+    static ref CLAMP: FuncDecl = FuncDecl {
+        name: FunctionName::new("clamp".to_string(), &[None, Some("lo".to_string()), Some("hi".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Numeric)],
+        parameters: vec![
+            ValueTy::Param(0, "T".to_string()),
+            ValueTy::Param(0, "T".to_string()),
+            ValueTy::Param(0, "T".to_string()),
+        ],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // fn ln<T: FloatingPoint>(T) -> T
+    static ref LN: FuncDecl = FuncDecl {
+        name: FunctionName::new("ln".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn log2<T: FloatingPoint>(T) -> T
+    static ref LOG2: FuncDecl = FuncDecl {
+        name: FunctionName::new("log2".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn log10<T: FloatingPoint>(T) -> T
+    static ref LOG10: FuncDecl = FuncDecl {
+        name: FunctionName::new("log10".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn exp2<T: FloatingPoint>(T) -> T
+    static ref EXP2: FuncDecl = FuncDecl {
+        name: FunctionName::new("exp2".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    /// fn log<T: FloatingPoint>(T, T) -> T
+    /// the logarithm of the second argument to the base given as first argument
+    static ref LOG: FuncDecl = FuncDecl {
+        name: FunctionName::new("log".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
 
     // fn matches(String, regex: String) -> Bool
     static ref MATCHES_STRING_REGEX: FuncDecl = FuncDecl {
@@ -169,6 +254,473 @@ lazy_static! {
         return_type: ValueTy::Bool,
     };
 
+    /// fn matches(String, String) -> Bool
+    /// the pattern has to be a string literal, it is compiled once when the specification is lowered
+    static ref MATCHES_STRING: FuncDecl = FuncDecl {
+        name: FunctionName::new("matches".to_string(), &[None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+
+    // fn matches(Bytes, String) -> Bool
+    static ref MATCHES_BYTES: FuncDecl = FuncDecl {
+        name: FunctionName::new("matches".to_string(), &[None, None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+
+    // fn length(String) -> UInt64
+    static ref LENGTH: FuncDecl = FuncDecl {
+        name: FunctionName::new("length".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+
+    // fn contains(String, pattern: String) -> Bool
+    static ref CONTAINS: FuncDecl = FuncDecl {
+        name: FunctionName::new("contains".to_string(), &[None, Some("pattern".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+
+    // fn starts_with(String, prefix: String) -> Bool
+    static ref STARTS_WITH: FuncDecl = FuncDecl {
+        name: FunctionName::new("starts_with".to_string(), &[None, Some("prefix".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+
+    // fn ends_with(String, suffix: String) -> Bool
+    static ref ENDS_WITH: FuncDecl = FuncDecl {
+        name: FunctionName::new("ends_with".to_string(), &[None, Some("suffix".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::String],
+        return_type: ValueTy::Bool,
+    };
+
+    // fn to_upper(String) -> String
+    static ref TO_UPPER: FuncDecl = FuncDecl {
+        name: FunctionName::new("to_upper".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::String,
+    };
+
+    // fn to_lower(String) -> String
+    static ref TO_LOWER: FuncDecl = FuncDecl {
+        name: FunctionName::new("to_lower".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::String,
+    };
+
+    /// fn substring(String, from: UInt64, to: UInt64) -> String
+    /// the characters from index `from` up to, but excluding, index `to`
+    static ref SUBSTRING: FuncDecl = FuncDecl {
+        name: FunctionName::new("substring".to_string(), &[None, Some("from".to_string()), Some("to".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::String, ValueTy::UInt(UIntTy::U64), ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::String,
+    };
+
+    /// fn parse_int(String) -> Int64?
+    /// the decimal integer in the string, surrounding whitespace is ignored
+    static ref PARSE_INT: FuncDecl = FuncDecl {
+        name: FunctionName::new("parse_int".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::Option(ValueTy::Int(IntTy::I64).into()),
+    };
+
+    /// fn parse_float(String) -> Float64?
+    /// the decimal number in the string, surrounding whitespace is ignored and `NaN` yields no value
+    static ref PARSE_FLOAT: FuncDecl = FuncDecl {
+        name: FunctionName::new("parse_float".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::String],
+        return_type: ValueTy::Option(ValueTy::Float(FloatTy::F64).into()),
+    };
+
+    /// fn to_int<T: Integer>(T) -> Int64
+    /// unsigned integers beyond the range of `Int64` saturate at its maximum
+    static ref TO_INT: FuncDecl = FuncDecl {
+        name: FunctionName::new("to_int".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Int(IntTy::I64),
+    };
+
+    /// fn to_float<T: Numeric>(T) -> Float64
+    /// integers are converted to the nearest representable float
+    static ref TO_FLOAT: FuncDecl = FuncDecl {
+        name: FunctionName::new("to_float".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Numeric)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Float(FloatTy::F64),
+    };
+
+    /// fn round_to_int<T: FloatingPoint>(T) -> Int64
+    /// rounds halfway cases away from zero, values beyond the range of `Int64` saturate at its bounds
+    static ref ROUND_TO_INT: FuncDecl = FuncDecl {
+        name: FunctionName::new("round_to_int".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Int(IntTy::I64),
+    };
+
+    /// fn trunc_to_int<T: FloatingPoint>(T) -> Int64
+    /// rounds towards zero, values beyond the range of `Int64` saturate at its bounds
+    static ref TRUNC_TO_INT: FuncDecl = FuncDecl {
+        name: FunctionName::new("trunc_to_int".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Int(IntTy::I64),
+    };
+
+    /// fn haversine<T: FloatingPoint>(lat1: T, lon1: T, lat2: T, lon2: T) -> T
+    /// the great-circle distance in meters between two positions given in degrees
+    static ref HAVERSINE: FuncDecl = FuncDecl {
+        name: FunctionName::new("haversine".to_string(), &[None, None, None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()); 4],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn bearing<T: FloatingPoint>(lat1: T, lon1: T, lat2: T, lon2: T) -> T
+    /// the initial bearing in degrees from the first to the second position, clockwise from north in [0, 360)
+    static ref BEARING: FuncDecl = FuncDecl {
+        name: FunctionName::new("bearing".to_string(), &[None, None, None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()); 4],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn wrap_angle_deg<T: FloatingPoint>(T) -> T
+    /// the equivalent angle in degrees in (-180, 180]
+    static ref WRAP_ANGLE_DEG: FuncDecl = FuncDecl {
+        name: FunctionName::new("wrap_angle_deg".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn wrap_angle_rad<T: FloatingPoint>(T) -> T
+    /// the equivalent angle in radians in (-pi, pi]
+    static ref WRAP_ANGLE_RAD: FuncDecl = FuncDecl {
+        name: FunctionName::new("wrap_angle_rad".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn angle_diff<T: FloatingPoint>(a: T, b: T) -> T
+    /// the shortest signed rotation in degrees from `b` to `a`, in (-180, 180]
+    static ref ANGLE_DIFF: FuncDecl = FuncDecl {
+        name: FunctionName::new("angle_diff".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()); 2],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn lerp<T: FloatingPoint>(a: T, b: T, t: T) -> T
+    /// linear interpolation between `a` and `b`, `t` outside of [0, 1] extrapolates
+    static ref LERP: FuncDecl = FuncDecl {
+        name: FunctionName::new("lerp".to_string(), &[None, None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()); 3],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn map_range<T: FloatingPoint>(x: T, in_lo: T, in_hi: T, out_lo: T, out_hi: T) -> T
+    /// maps `x` linearly from the range [in_lo, in_hi] to [out_lo, out_hi], values outside are extrapolated
+    static ref MAP_RANGE: FuncDecl = FuncDecl {
+        name: FunctionName::new("map_range".to_string(), &[None, None, None, None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()); 5],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn zscore<T: FloatingPoint>(x: T, mean: T, stddev: T) -> T
+    /// the number of standard deviations `x` is away from the mean
+    static ref ZSCORE: FuncDecl = FuncDecl {
+        name: FunctionName::new("zscore".to_string(), &[None, Some("mean".to_string()), Some("stddev".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()); 3],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // Filters keep state, every call is evaluated as separate stream whenever the calling stream is evaluated.
+
+    /// fn lowpass<T: FloatingPoint>(T, alpha: T) -> T
+    /// exponential smoothing, moves the last filtered value by the factor `alpha` towards the new value
+    static ref LOWPASS: FuncDecl = FuncDecl {
+        name: FunctionName::new("lowpass".to_string(), &[None, Some("alpha".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()); 2],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn rate_limit<T: FloatingPoint>(T, max_delta: T) -> T
+    /// follows the value while changing by at most `max_delta` per evaluation
+    static ref RATE_LIMIT: FuncDecl = FuncDecl {
+        name: FunctionName::new("rate_limit".to_string(), &[None, Some("max_delta".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()); 2],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn debounce(Bool, for: Duration) -> Bool
+    /// holds once the condition held in all evaluations for at least the duration, e.g., `trigger x > 80 for 2s`
+    static ref DEBOUNCE: FuncDecl = FuncDecl {
+        name: FunctionName::new("debounce".to_string(), &[None, Some("for".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bool, ValueTy::Duration],
+        return_type: ValueTy::Bool,
+    };
+
+    /// fn hysteresis(Bool, off: Bool) -> Bool
+    /// holds from an evaluation in which the first condition holds up to one in which `off` holds, e.g., `trigger x > 80 until x < 70`
+    static ref HYSTERESIS: FuncDecl = FuncDecl {
+        name: FunctionName::new("hysteresis".to_string(), &[None, Some("off".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bool, ValueTy::Bool],
+        return_type: ValueTy::Bool,
+    };
+
+    // Rounding to a number of decimal digits, negative digits round to tens, hundreds, etc.
+    // `round` rounds halfway cases away from zero.
+
+    // fn floor<T: FloatingPoint>(T) -> T
+    static ref FLOOR: FuncDecl = FuncDecl {
+        name: FunctionName::new("floor".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // fn floor<T: FloatingPoint>(T, digits: Int64) -> T
+    static ref FLOOR_DIGITS: FuncDecl = FuncDecl {
+        name: FunctionName::new("floor".to_string(), &[None, Some("digits".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Int(IntTy::I64)],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // fn ceil<T: FloatingPoint>(T) -> T
+    static ref CEIL: FuncDecl = FuncDecl {
+        name: FunctionName::new("ceil".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // fn ceil<T: FloatingPoint>(T, digits: Int64) -> T
+    static ref CEIL_DIGITS: FuncDecl = FuncDecl {
+        name: FunctionName::new("ceil".to_string(), &[None, Some("digits".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Int(IntTy::I64)],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // fn round<T: FloatingPoint>(T) -> T
+    static ref ROUND: FuncDecl = FuncDecl {
+        name: FunctionName::new("round".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // fn round<T: FloatingPoint>(T, digits: Int64) -> T
+    static ref ROUND_DIGITS: FuncDecl = FuncDecl {
+        name: FunctionName::new("round".to_string(), &[None, Some("digits".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::FloatingPoint)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Int(IntTy::I64)],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // Integers are checksummed as 8 bytes in little-endian order, strings in UTF-8.
+
+    /// fn crc16<T: Hashable>(T) -> UInt16
+    /// the CRC-16/CCITT-FALSE checksum
+    static ref CRC16: FuncDecl = FuncDecl {
+        name: FunctionName::new("crc16".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Hashable)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::UInt(UIntTy::U16),
+    };
+
+    /// fn crc32<T: Hashable>(T) -> UInt32
+    /// the CRC-32 checksum as used by Ethernet and zlib
+    static ref CRC32: FuncDecl = FuncDecl {
+        name: FunctionName::new("crc32".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Hashable)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::UInt(UIntTy::U32),
+    };
+
+    /// fn hash64<T: Hashable>(T) -> UInt64
+    /// the 64-bit FNV-1a hash, which is stable across platforms and releases
+    static ref HASH64: FuncDecl = FuncDecl {
+        name: FunctionName::new("hash64".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Hashable)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+
+    // Arithmetic that handles overflows within the integer type locally:
+    // checked variants have no value on overflow or division by zero, saturating variants clamp to the bounds.
+
+    // fn checked_add<T: Integer>(T, T) -> T?
+    static ref CHECKED_ADD: FuncDecl = FuncDecl {
+        name: FunctionName::new("checked_add".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()),
+    };
+    // fn checked_sub<T: Integer>(T, T) -> T?
+    static ref CHECKED_SUB: FuncDecl = FuncDecl {
+        name: FunctionName::new("checked_sub".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()),
+    };
+    // fn checked_mul<T: Integer>(T, T) -> T?
+    static ref CHECKED_MUL: FuncDecl = FuncDecl {
+        name: FunctionName::new("checked_mul".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()),
+    };
+    // fn checked_div<T: Integer>(T, T) -> T?
+    static ref CHECKED_DIV: FuncDecl = FuncDecl {
+        name: FunctionName::new("checked_div".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Option(ValueTy::Param(0, "T".to_string()).into()),
+    };
+    // fn saturating_add<T: Integer>(T, T) -> T
+    static ref SATURATING_ADD: FuncDecl = FuncDecl {
+        name: FunctionName::new("saturating_add".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn saturating_sub<T: Integer>(T, T) -> T
+    static ref SATURATING_SUB: FuncDecl = FuncDecl {
+        name: FunctionName::new("saturating_sub".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+    // fn saturating_mul<T: Integer>(T, T) -> T
+    static ref SATURATING_MUL: FuncDecl = FuncDecl {
+        name: FunctionName::new("saturating_mul".to_string(), &[None, None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // Bits are indexed from the least significant bit, starting at 0, within the width of the integer type.
+
+    /// fn bit<T: Integer>(T, index: UInt64) -> Bool
+    /// whether the bit at `index` is set, false for indices beyond the width
+    static ref BIT: FuncDecl = FuncDecl {
+        name: FunctionName::new("bit".to_string(), &[None, Some("index".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Bool,
+    };
+
+    /// fn bits<T: Integer>(T, hi: UInt64, lo: UInt64) -> T
+    /// the bits from `hi` down to `lo`, both inclusive, shifted to the least significant bits
+    static ref BITS: FuncDecl = FuncDecl {
+        name: FunctionName::new("bits".to_string(), &[None, Some("hi".to_string()), Some("lo".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::UInt(UIntTy::U64), ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    /// fn popcount<T: Integer>(T) -> UInt64
+    /// the number of set bits, negative numbers are counted in two's complement
+    static ref POPCOUNT: FuncDecl = FuncDecl {
+        name: FunctionName::new("popcount".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+
+    /// fn set_bit<T: Integer>(T, index: UInt64, to: Bool) -> T
+    /// the value with the bit at `index` set or cleared, unchanged for indices beyond the width
+    static ref SET_BIT: FuncDecl = FuncDecl {
+        name: FunctionName::new("set_bit".to_string(), &[None, Some("index".to_string()), Some("to".to_string())]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Integer)],
+        parameters: vec![ValueTy::Param(0, "T".to_string()), ValueTy::UInt(UIntTy::U64), ValueTy::Bool],
+        return_type: ValueTy::Param(0, "T".to_string()),
+    };
+
+    // Timestamps are seconds since the Unix epoch, 1970-01-01 00:00:00 UTC.
+
+    /// fn hour_of<T: Numeric>(T) -> UInt64
+    /// the hour of a timestamp in UTC, from 0 to 23
+    static ref HOUR_OF: FuncDecl = FuncDecl {
+        name: FunctionName::new("hour_of".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Numeric)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+
+    /// fn day_of_week<T: Numeric>(T) -> UInt64
+    /// the day of the week of a timestamp in UTC, from 1 for Monday to 7 for Sunday
+    static ref DAY_OF_WEEK: FuncDecl = FuncDecl {
+        name: FunctionName::new("day_of_week".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Numeric)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::UInt(UIntTy::U64),
+    };
+
+    /// fn since_midnight<T: Numeric>(T) -> Float64
+    /// the seconds elapsed since midnight UTC of a timestamp
+    static ref SINCE_MIDNIGHT: FuncDecl = FuncDecl {
+        name: FunctionName::new("since_midnight".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Numeric)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Float(FloatTy::F64),
+    };
+
+    // Durations are written as numeric literals with a time unit, e.g., `250ms`, and measured in seconds.
+
+    /// fn seconds(Duration) -> Float64
+    /// the length of a duration in seconds
+    static ref SECONDS: FuncDecl = FuncDecl {
+        name: FunctionName::new("seconds".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Duration],
+        return_type: ValueTy::Float(FloatTy::F64),
+    };
+
+    /// fn millis(Duration) -> Float64
+    /// the length of a duration in milliseconds
+    static ref MILLIS: FuncDecl = FuncDecl {
+        name: FunctionName::new("millis".to_string(), &[None]),
+        generics: vec![],
+        parameters: vec![ValueTy::Duration],
+        return_type: ValueTy::Float(FloatTy::F64),
+    };
+
+    /// fn to_duration<T: Numeric>(T) -> Duration
+    /// interprets a measured number of seconds, e.g., a difference of timestamps, as a duration
+    static ref TO_DURATION: FuncDecl = FuncDecl {
+        name: FunctionName::new("to_duration".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Numeric)],
+        parameters: vec![ValueTy::Param(0, "T".to_string())],
+        return_type: ValueTy::Duration,
+    };
+
     /// fn cast<T: Numeric, U: Numeric>(T) -> U
     /// allows for arbitrary conversion of numeric types T -> U
     static ref CAST: FuncDecl = FuncDecl {
@@ -180,6 +732,15 @@ lazy_static! {
         return_type: ValueTy::Param(1, "U".to_string()),
     };
 
+    /// fn is_some<T>(T?) -> Bool
+    /// whether an optional value, e.g., the result of an offset, is present; also written as method `x.is_some()`
+    static ref IS_SOME: FuncDecl = FuncDecl {
+        name: FunctionName::new("is_some".to_string(), &[None]),
+        generics: vec![ValueTy::Constr(TypeConstraint::Unconstrained)],
+        parameters: vec![ValueTy::Option(ValueTy::Param(0, "T".to_string()).into())],
+        return_type: ValueTy::Bool,
+    };
+
     /// access index of byte array
     static ref BYTES_AT: FuncDecl = FuncDecl {
         name: FunctionName::new("at".to_string(), &[None, Some("index".to_string())]),
@@ -187,75 +748,405 @@ lazy_static! {
         parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64)],
         return_type: ValueTy::Option( ValueTy::UInt(UIntTy::U8).into() ),
     };
+
+    // Decoding of numbers in binary frames, the result is absent if the frame ends before the number.
+
+    /// fn u16_le(Bytes, offset: UInt64) -> UInt16?
+    /// the unsigned 16-bit integer stored little-endian at byte `offset`
+    static ref U16_LE: FuncDecl = FuncDecl {
+        name: FunctionName::new("u16_le".to_string(), &[None, Some("offset".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::UInt(UIntTy::U16).into()),
+    };
+
+    /// fn u16_be(Bytes, offset: UInt64) -> UInt16?
+    /// the unsigned 16-bit integer stored big-endian at byte `offset`
+    static ref U16_BE: FuncDecl = FuncDecl {
+        name: FunctionName::new("u16_be".to_string(), &[None, Some("offset".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::UInt(UIntTy::U16).into()),
+    };
+
+    /// fn i32_le(Bytes, offset: UInt64) -> Int32?
+    /// the signed 32-bit integer stored little-endian at byte `offset`
+    static ref I32_LE: FuncDecl = FuncDecl {
+        name: FunctionName::new("i32_le".to_string(), &[None, Some("offset".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::Int(IntTy::I32).into()),
+    };
+
+    /// fn i32_be(Bytes, offset: UInt64) -> Int32?
+    /// the signed 32-bit integer stored big-endian at byte `offset`
+    static ref I32_BE: FuncDecl = FuncDecl {
+        name: FunctionName::new("i32_be".to_string(), &[None, Some("offset".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::Int(IntTy::I32).into()),
+    };
+
+    /// fn f32_le(Bytes, offset: UInt64) -> Float32?
+    /// the 32-bit float stored little-endian at byte `offset`, NaN yields no value
+    static ref F32_LE: FuncDecl = FuncDecl {
+        name: FunctionName::new("f32_le".to_string(), &[None, Some("offset".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::Float(FloatTy::F32).into()),
+    };
+
+    /// fn f32_be(Bytes, offset: UInt64) -> Float32?
+    /// the 32-bit float stored big-endian at byte `offset`, NaN yields no value
+    static ref F32_BE: FuncDecl = FuncDecl {
+        name: FunctionName::new("f32_be".to_string(), &[None, Some("offset".to_string())]),
+        generics: vec![],
+        parameters: vec![ValueTy::Bytes, ValueTy::UInt(UIntTy::U64)],
+        return_type: ValueTy::Option(ValueTy::Float(FloatTy::F32).into()),
+    };
 }
 
-pub(crate) fn import_implicit_module(fun_scope: &mut ScopedDecl) {
-    fun_scope.add_fun_decl(&CAST);
+/// The numeric types, which provide the builtin numeric functions as methods.
+const NUMERIC_TYPES: [ValueTy; 11] = [
+    ValueTy::Int(IntTy::I8),
+    ValueTy::Int(IntTy::I16),
+    ValueTy::Int(IntTy::I32),
+    ValueTy::Int(IntTy::I64),
+    ValueTy::UInt(UIntTy::U8),
+    ValueTy::UInt(UIntTy::U16),
+    ValueTy::UInt(UIntTy::U32),
+    ValueTy::UInt(UIntTy::U64),
+    ValueTy::Float(FloatTy::F16),
+    ValueTy::Float(FloatTy::F32),
+    ValueTy::Float(FloatTy::F64),
+];
+
+fn implicit_functions(module: &mut Module) {
+    module.add_function(&CAST);
+    module.add_function(&IS_SOME);
+    module.add_function(&TO_INT);
+    module.add_function(&TO_FLOAT);
+    module.add_function(&ROUND_TO_INT);
+    module.add_function(&TRUNC_TO_INT);
+    module.add_function(&HAVERSINE);
+    module.add_function(&BEARING);
+    module.add_function(&WRAP_ANGLE_DEG);
+    module.add_function(&WRAP_ANGLE_RAD);
+    module.add_function(&ANGLE_DIFF);
+    module.add_function(&LERP);
+    module.add_function(&MAP_RANGE);
+    module.add_function(&ZSCORE);
+    module.add_function(&LOWPASS);
+    module.add_function(&RATE_LIMIT);
+    module.add_function(&DEBOUNCE);
+    module.add_function(&HYSTERESIS);
+    module.add_function(&HOUR_OF);
+    module.add_function(&DAY_OF_WEEK);
+    module.add_function(&SINCE_MIDNIGHT);
+    module.add_function(&SECONDS);
+    module.add_function(&MILLIS);
+    module.add_function(&TO_DURATION);
+    module.add_function(&FLOOR);
+    module.add_function(&FLOOR_DIGITS);
+    module.add_function(&CEIL);
+    module.add_function(&CEIL_DIGITS);
+    module.add_function(&ROUND);
+    module.add_function(&ROUND_DIGITS);
+    module.add_function(&CRC16);
+    module.add_function(&CRC32);
+    module.add_function(&HASH64);
+    module.add_function(&CHECKED_ADD);
+    module.add_function(&CHECKED_SUB);
+    module.add_function(&CHECKED_MUL);
+    module.add_function(&CHECKED_DIV);
+    module.add_function(&SATURATING_ADD);
+    module.add_function(&SATURATING_SUB);
+    module.add_function(&SATURATING_MUL);
+    module.add_function(&BIT);
+    module.add_function(&BITS);
+    module.add_function(&POPCOUNT);
+    module.add_function(&SET_BIT);
+    module.add_function(&ABS);
+    module.add_function(&MIN);
+    module.add_function(&MAX);
+    module.add_function(&CLAMP);
+    module.add_function(&LENGTH);
+    module.add_function(&CONTAINS);
+    module.add_function(&STARTS_WITH);
+    module.add_function(&ENDS_WITH);
+    module.add_function(&TO_UPPER);
+    module.add_function(&TO_LOWER);
+    module.add_function(&SUBSTRING);
+    module.add_function(&MATCHES_STRING);
+    module.add_function(&PARSE_INT);
+    module.add_function(&PARSE_FLOAT);
+    module.add_function(&U16_LE);
+    module.add_function(&U16_BE);
+    module.add_function(&I32_LE);
+    module.add_function(&I32_BE);
+    module.add_function(&F32_LE);
+    module.add_function(&F32_BE);
 }
 
-pub(crate) fn import_implicit_method(lookup: &mut MethodLookup) {
-    lookup.add(ValueTy::Bytes, &BYTES_AT);
+fn implicit_methods(module: &mut Module) {
+    module.add_method(ValueTy::Bytes, &BYTES_AT);
+    for ty in &NUMERIC_TYPES {
+        module.add_method(ty.clone(), &ABS);
+        module.add_method(ty.clone(), &MIN);
+        module.add_method(ty.clone(), &MAX);
+        module.add_method(ty.clone(), &CLAMP);
+        module.add_method(ty.clone(), &TO_FLOAT);
+        module.add_method(ty.clone(), &TO_DURATION);
+        if let ValueTy::Float(_) = ty {
+            module.add_method(ty.clone(), &FLOOR);
+            module.add_method(ty.clone(), &FLOOR_DIGITS);
+            module.add_method(ty.clone(), &CEIL);
+            module.add_method(ty.clone(), &CEIL_DIGITS);
+            module.add_method(ty.clone(), &ROUND);
+            module.add_method(ty.clone(), &ROUND_DIGITS);
+            module.add_method(ty.clone(), &ROUND_TO_INT);
+            module.add_method(ty.clone(), &TRUNC_TO_INT);
+            module.add_method(ty.clone(), &WRAP_ANGLE_DEG);
+            module.add_method(ty.clone(), &WRAP_ANGLE_RAD);
+            module.add_method(ty.clone(), &ZSCORE);
+            module.add_method(ty.clone(), &LOWPASS);
+            module.add_method(ty.clone(), &RATE_LIMIT);
+        } else {
+            module.add_method(ty.clone(), &TO_INT);
+            module.add_method(ty.clone(), &CRC16);
+            module.add_method(ty.clone(), &CRC32);
+            module.add_method(ty.clone(), &HASH64);
+            module.add_method(ty.clone(), &CHECKED_ADD);
+            module.add_method(ty.clone(), &CHECKED_SUB);
+            module.add_method(ty.clone(), &CHECKED_MUL);
+            module.add_method(ty.clone(), &CHECKED_DIV);
+            module.add_method(ty.clone(), &SATURATING_ADD);
+            module.add_method(ty.clone(), &SATURATING_SUB);
+            module.add_method(ty.clone(), &SATURATING_MUL);
+            module.add_method(ty.clone(), &BIT);
+            module.add_method(ty.clone(), &BITS);
+            module.add_method(ty.clone(), &POPCOUNT);
+            module.add_method(ty.clone(), &SET_BIT);
+        }
+    }
+    for ty in &[ValueTy::String, ValueTy::Bytes] {
+        module.add_method(ty.clone(), &CRC16);
+        module.add_method(ty.clone(), &CRC32);
+        module.add_method(ty.clone(), &HASH64);
+    }
+    module.add_method(ValueTy::Bool, &DEBOUNCE);
+    module.add_method(ValueTy::Bool, &HYSTERESIS);
+    module.add_method(ValueTy::Duration, &SECONDS);
+    module.add_method(ValueTy::Duration, &MILLIS);
+    module.add_method(ValueTy::String, &LENGTH);
+    module.add_method(ValueTy::String, &CONTAINS);
+    module.add_method(ValueTy::String, &STARTS_WITH);
+    module.add_method(ValueTy::String, &ENDS_WITH);
+    module.add_method(ValueTy::String, &TO_UPPER);
+    module.add_method(ValueTy::String, &TO_LOWER);
+    module.add_method(ValueTy::String, &SUBSTRING);
+    module.add_method(ValueTy::String, &MATCHES_STRING);
+    module.add_method(ValueTy::String, &PARSE_INT);
+    module.add_method(ValueTy::String, &PARSE_FLOAT);
+    module.add_method(ValueTy::Bytes, &MATCHES_BYTES);
+    module.add_method(ValueTy::Bytes, &U16_LE);
+    module.add_method(ValueTy::Bytes, &U16_BE);
+    module.add_method(ValueTy::Bytes, &I32_LE);
+    module.add_method(ValueTy::Bytes, &I32_BE);
+    module.add_method(ValueTy::Bytes, &F32_LE);
+    module.add_method(ValueTy::Bytes, &F32_BE);
 }
 
-pub(crate) fn import_math_module(fun_scope: &mut ScopedDecl) {
-    fun_scope.add_fun_decl(&SQRT);
-    fun_scope.add_fun_decl(&COS);
-    fun_scope.add_fun_decl(&SIN);
-    fun_scope.add_fun_decl(&ABS);
-    fun_scope.add_fun_decl(&ARCTAN);
-    fun_scope.add_fun_decl(&MIN);
-    fun_scope.add_fun_decl(&MAX);
+/// The functions `abs`, `min`, and `max` are builtins and available without importing `math`.
+fn math_functions(module: &mut Module) {
+    module.add_function(&SQRT);
+    module.add_function(&COS);
+    module.add_function(&SIN);
+    module.add_function(&ARCTAN);
+    module.add_function(&ATAN2);
+    module.add_function(&HYPOT);
+    module.add_function(&LN);
+    module.add_function(&LOG2);
+    module.add_function(&LOG10);
+    module.add_function(&LOG);
+    module.add_function(&EXP2);
+}
+
+fn regex_functions(module: &mut Module) {
+    module.add_function(&MATCHES_STRING_REGEX);
+}
+
+fn math_methods(module: &mut Module) {
+    for fty in &[FloatTy::F16, FloatTy::F32, FloatTy::F64] {
+        let ty = ValueTy::Float(*fty);
+        module.add_method(ty.clone(), &SQRT);
+        module.add_method(ty.clone(), &COS);
+        module.add_method(ty.clone(), &SIN);
+        module.add_method(ty.clone(), &ARCTAN);
+        module.add_method(ty.clone(), &ATAN2);
+        module.add_method(ty.clone(), &HYPOT);
+        module.add_method(ty.clone(), &LN);
+        module.add_method(ty.clone(), &LOG2);
+        module.add_method(ty.clone(), &LOG10);
+        module.add_method(ty, &EXP2);
+    }
 }
 
-pub(crate) fn import_regex_module(fun_scope: &mut ScopedDecl) {
-    fun_scope.add_fun_decl(&MATCHES_STRING_REGEX);
+fn regex_methods(module: &mut Module) {
+    module.add_method(ValueTy::String, &MATCHES_STRING_REGEX);
+    module.add_method(ValueTy::Bytes, &MATCHES_BYTES_REGEX);
 }
 
-pub(crate) fn import_math_method(lookup: &mut MethodLookup) {
-    lookup.add(ValueTy::Float(FloatTy::F16), &SQRT);
-    lookup.add(ValueTy::Float(FloatTy::F16), &COS);
-    lookup.add(ValueTy::Float(FloatTy::F16), &SIN);
-    lookup.add(ValueTy::Float(FloatTy::F16), &ABS);
-    lookup.add(ValueTy::Float(FloatTy::F16), &ARCTAN);
-
-    lookup.add(ValueTy::Float(FloatTy::F32), &SQRT);
-    lookup.add(ValueTy::Float(FloatTy::F32), &COS);
-    lookup.add(ValueTy::Float(FloatTy::F32), &SIN);
-    lookup.add(ValueTy::Float(FloatTy::F32), &ABS);
-    lookup.add(ValueTy::Float(FloatTy::F32), &ARCTAN);
-    lookup.add(ValueTy::Float(FloatTy::F32), &MIN);
-    lookup.add(ValueTy::Float(FloatTy::F32), &MAX);
-
-    lookup.add(ValueTy::Float(FloatTy::F64), &SQRT);
-    lookup.add(ValueTy::Float(FloatTy::F64), &COS);
-    lookup.add(ValueTy::Float(FloatTy::F64), &SIN);
-    lookup.add(ValueTy::Float(FloatTy::F64), &ABS);
-    lookup.add(ValueTy::Float(FloatTy::F64), &ARCTAN);
-    lookup.add(ValueTy::Float(FloatTy::F64), &MIN);
-    lookup.add(ValueTy::Float(FloatTy::F64), &MAX);
-
-    lookup.add(ValueTy::Int(IntTy::I8), &MIN);
-    lookup.add(ValueTy::Int(IntTy::I8), &MAX);
-    lookup.add(ValueTy::Int(IntTy::I16), &MIN);
-    lookup.add(ValueTy::Int(IntTy::I16), &MAX);
-    lookup.add(ValueTy::Int(IntTy::I32), &MIN);
-    lookup.add(ValueTy::Int(IntTy::I32), &MAX);
-    lookup.add(ValueTy::Int(IntTy::I64), &MIN);
-    lookup.add(ValueTy::Int(IntTy::I64), &MAX);
-
-    lookup.add(ValueTy::UInt(UIntTy::U8), &MIN);
-    lookup.add(ValueTy::UInt(UIntTy::U8), &MAX);
-    lookup.add(ValueTy::UInt(UIntTy::U16), &MIN);
-    lookup.add(ValueTy::UInt(UIntTy::U16), &MAX);
-    lookup.add(ValueTy::UInt(UIntTy::U32), &MIN);
-    lookup.add(ValueTy::UInt(UIntTy::U32), &MAX);
-    lookup.add(ValueTy::UInt(UIntTy::U64), &MIN);
-    lookup.add(ValueTy::UInt(UIntTy::U64), &MAX);
+/// The functions and methods provided by a module.
+#[derive(Debug, Clone, Default)]
+struct Module {
+    functions: Vec<FuncDecl>,
+    methods: Vec<(ValueTy, FuncDecl)>,
 }
 
-pub(crate) fn import_regex_method(lookup: &mut MethodLookup) {
-    lookup.add(ValueTy::String, &MATCHES_STRING_REGEX);
-    lookup.add(ValueTy::Bytes, &MATCHES_BYTES_REGEX);
+impl Module {
+    fn add_function(&mut self, decl: &FuncDecl) {
+        let name = decl.name.to_string();
+        assert!(self.functions.iter().all(|f| f.name.to_string() != name), "function `{}` is already declared", name);
+        self.functions.push(decl.clone());
+    }
+
+    fn add_method(&mut self, receiver: ValueTy, decl: &FuncDecl) {
+        assert!(
+            decl.name.arg_names.first() == Some(&None),
+            "the receiver of method `{}` is a named argument",
+            decl.name
+        );
+        let name = decl.name.to_string();
+        assert!(
+            self.methods.iter().all(|(ty, m)| ty != &receiver || m.name.to_string() != name),
+            "method `{}` is already declared for `{}`",
+            name,
+            receiver
+        );
+        self.methods.push((receiver, decl.clone()));
+    }
+}
+
+lazy_static! {
+    /// The functions available without import and the modules `math` and `regex`.
+    pub(crate) static ref STANDARD_LIBRARY: FunctionRegistry = {
+        let mut modules = HashMap::new();
+        let mut implicit = Module::default();
+        implicit_functions(&mut implicit);
+        implicit_methods(&mut implicit);
+        modules.insert(None, implicit);
+        let mut math = Module::default();
+        math_functions(&mut math);
+        math_methods(&mut math);
+        modules.insert(Some("math".to_string()), math);
+        let mut regex = Module::default();
+        regex_functions(&mut regex);
+        regex_methods(&mut regex);
+        modules.insert(Some("regex".to_string()), regex);
+        FunctionRegistry { modules, aggregations: Vec::new() }
+    };
+}
+
+/**
+The functions that can be called in a specification, grouped into modules which are imported by `import <module>`.
+
+The default registry contains the standard library.
+Host applications can add their own functions before parsing a specification with `parse_with_functions`; evaluating them is up to the backend.
+*/
+#[derive(Debug, Clone)]
+pub struct FunctionRegistry {
+    /// The functions available without import are stored under `None`.
+    modules: HashMap<Option<String>, Module>,
+    /// The aggregation functions of sliding windows in addition to the built-in ones, which are available without import.
+    aggregations: Vec<FuncDecl>,
+}
+
+impl Default for FunctionRegistry {
+    fn default() -> Self {
+        STANDARD_LIBRARY.clone()
+    }
+}
+
+impl FunctionRegistry {
+    /**
+    Adds the function `decl` to `module`, or makes it available without import if `module` is `None`.
+
+    Panics if the module already contains a function with the same name and argument names.
+    */
+    pub fn add_function(&mut self, module: Option<&str>, decl: FuncDecl) {
+        self.modules.entry(module.map(str::to_string)).or_default().add_function(&decl);
+    }
+
+    /**
+    Adds the function `decl` to `module` as method on values of type `receiver`, which is passed as the first argument.
+
+    Panics if the first argument is named or the module already contains the method for `receiver`.
+    */
+    pub fn add_method(&mut self, module: Option<&str>, receiver: ValueTy, decl: FuncDecl) {
+        self.modules.entry(module.map(str::to_string)).or_default().add_method(receiver, &decl);
+    }
+
+    /**
+    Adds the aggregation function `decl` of sliding windows, e.g., `x.aggregate(over: 10s, using: ewma)` for `ewma`.
+
+    The only parameter of `decl` is the type of the aggregated values and the return type is the type of the window, which is optional for `aggregate(over_exactly:using:)`.
+    Panics if `decl` does not have exactly one positional parameter or an aggregation with the same name is already declared.
+    */
+    pub fn add_aggregation(&mut self, decl: FuncDecl) {
+        assert!(
+            decl.name.arg_names == [None],
+            "the aggregation `{}` does not have exactly one positional parameter",
+            decl.name
+        );
+        let name = &decl.name.name.name;
+        assert!(self.aggregation(name).is_none(), "aggregation `{}` is already declared", name);
+        self.aggregations.push(decl);
+    }
+
+    /// Returns the declaration of the aggregation function with the given name, if it was added by `add_aggregation`.
+    pub(crate) fn aggregation(&self, name: &str) -> Option<&FuncDecl> {
+        self.aggregations.iter().find(|a| a.name.name.name == name)
+    }
+
+    /// Declares the functions of `module` in `fun_scope`, returns `false` if there is no such module.
+    pub(crate) fn import_functions(&self, module: Option<&str>, fun_scope: &mut ScopedDecl) -> bool {
+        match self.modules.get(&module.map(str::to_string)) {
+            Some(m) => {
+                m.functions.iter().for_each(|f| fun_scope.add_fun_decl(f));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Returns the functions of `module`, which are none if there is no such module.
+    pub(crate) fn functions(&self, module: Option<&str>) -> &[FuncDecl] {
+        self.modules.get(&module.map(str::to_string)).map_or(&[], |m| &m.functions)
+    }
+
+    /// Returns the modules declaring a function with the given name, sorted by name.
+    pub(crate) fn modules_declaring(&self, name: &str) -> Vec<&str> {
+        let mut modules: Vec<&str> = self
+            .modules
+            .iter()
+            .filter(|(_, m)| m.functions.iter().any(|f| f.name.name.name == name))
+            .filter_map(|(module, _)| module.as_deref())
+            .collect();
+        modules.sort_unstable();
+        modules
+    }
+
+    /// Adds the methods of `module` to `lookup`, returns `false` if there is no such module.
+    pub(crate) fn import_methods<'a>(&'a self, module: Option<&str>, lookup: &mut MethodLookup<'a>) -> bool {
+        match self.modules.get(&module.map(str::to_string)) {
+            Some(m) => {
+                m.methods.iter().for_each(|(ty, f)| lookup.add(ty.clone(), f));
+                true
+            }
+            None => false,
+        }
+    }
 }
 
 pub(crate) struct MethodLookup<'a> {
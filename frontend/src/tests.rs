@@ -184,6 +184,458 @@ fn min_incompatible() {
     assert!(parse("import math\n input a: Int32\n input b: Float64\n output minres := min(a, b)").is_err());
 }
 
+#[test]
+fn numeric_builtins() {
+    assert!(parse("input a: Int32\n input b: Int32\n output c := clamp(abs(a), lo: min(a, b), hi: max(a, b))").is_ok());
+    assert!(parse("input a: UInt8\n output b := a.abs().clamp(lo: 2, hi: 10).max(3)").is_ok());
+    assert!(parse("input a: Float16\n output b: Float16 := a.clamp(lo: -1.0, hi: 1.0).min(0.5)").is_ok());
+    assert!(parse("import math\n input a: Float64\n output b := sqrt(abs(a))").is_ok());
+}
+
+#[test]
+fn logarithm_builtins() {
+    let spec = "import math\n input a: Float64\n output b := ln(a) + log2(a) + a.log10() + log(10.0, a) + exp2(a).ln()";
+    assert!(parse(spec).is_ok());
+    assert!(parse("import math\n input a: Float32\n output b: Float32 := a.log2().exp2()").is_ok());
+    assert!(parse("import math\n input a: Int64\n output b := ln(a)").is_err());
+    assert!(parse("input a: Float64\n output b := log2(a)").is_err());
+}
+
+#[test]
+fn navigation_builtins() {
+    let spec = "import math\n input lat: Float64\n input lon: Float64\n\
+                output heading := atan2(lat, lon)\n output distance := hypot(lat, lon) + lat.hypot(lon).atan2(1.0)";
+    assert!(parse(spec).is_ok());
+    assert!(parse("import math\n input a: Float32\n input b: Float64\n output c := atan2(a, b)").is_err());
+    assert!(parse("import math\n input a: Int64\n output c := hypot(a, a)").is_err());
+}
+
+#[test]
+fn conversion_builtins() {
+    let spec = "input a: UInt32\n input b: Float32\n\
+                output c: Int64 := to_int(a) + b.round_to_int() + trunc_to_int(b)\n\
+                output d: Float64 := a.to_float() + to_float(b)";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n output b := to_int(a)").is_err());
+    assert!(parse("input a: Int64\n output b := a.round_to_int()").is_err());
+    assert!(parse("input a: String\n output b := to_float(a)").is_err());
+}
+
+#[test]
+fn geospatial_builtins() {
+    let spec = "input lat: Float64\n input lon: Float64\n\
+                output dist := haversine(lat, lon, 49.2575, 7.0451)\n\
+                output course := bearing(lat.offset(by: -1).defaults(to: lat), lon.offset(by: -1).defaults(to: lon), lat, lon)\n\
+                trigger dist > 500.0 \"left geofence\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input lat: Float64\n output dist := haversine(lat, lat, lat)").is_err());
+    assert!(parse("input lat: Int64\n output dist := bearing(lat, lat, lat, lat)").is_err());
+}
+
+#[test]
+fn timestamp_builtins() {
+    let spec = "input time: UInt64\n input command: String\n\
+                output working_hours := 8 <= hour_of(time) && hour_of(time) < 17 && day_of_week(time) <= 5\n\
+                output late := since_midnight(time) > 61200.0\n\
+                trigger command = \"maintenance\" && !working_hours \"maintenance outside working hours\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input time: Float64\n output h: UInt64 := hour_of(time)").is_ok());
+    assert!(parse("input time: String\n output h := hour_of(time)").is_err());
+}
+
+#[test]
+fn duration_builtins() {
+    let spec = "input sent: Float64\n input received: Float64\n\
+                constant bound: Duration := 250ms\n\
+                output latency := to_duration(received - sent)\n\
+                output slack: Float64 := (bound - latency).millis()\n\
+                trigger latency > bound + 50ms \"response too slow\"\n\
+                trigger seconds(latency) > 1.0 \"response timed out\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("output a: Bool @ 1Hz := 1min < 90s").is_ok());
+    assert!(parse("output a @ 1Hz := 2s * 3s").is_err());
+    assert!(parse("output a @ 1Hz := 2s + 3").is_err());
+    assert!(parse("output a @ 1Hz := 2s + 3Hz").is_err());
+    assert!(parse("input a: Float64\n output b := a < 1s").is_err());
+}
+
+#[test]
+fn checksum_builtins() {
+    let spec = "input frame: Bytes\n input checksum: UInt32\n input id: String\n input seq: UInt16\n\
+                trigger crc32(frame) != checksum \"corrupted frame\"\n\
+                output key: UInt64 := id.hash64() + hash64(seq)\n\
+                output short: UInt16 := frame.crc16()";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n output b := crc32(a)").is_err());
+    assert!(parse("input a: Bool\n output b := hash64(a)").is_err());
+}
+
+#[test]
+fn interpolation_builtins() {
+    let spec = "input raw: UInt16\n input level: Float64\n\
+                output pressure := map_range(to_float(raw), 0.0, 4095.0, 0.0, 10.0)\n\
+                output smoothed := lerp(smoothed.offset(by: -1).defaults(to: level), level, 0.1)\n\
+                trigger pressure > 8.0 \"pressure too high\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n output b := lerp(a, 1.0)").is_err());
+    assert!(parse("input a: Int64\n output b := map_range(a, 0, 1, 0, 10)").is_err());
+}
+
+#[test]
+fn registered_functions() {
+    use crate::ty::{FloatTy, TypeConstraint, ValueTy};
+    let mut functions = FunctionRegistry::default();
+    let t = ValueTy::Param(0, "T".to_string());
+    let float = vec![ValueTy::Constr(TypeConstraint::FloatingPoint)];
+    let to_mps = FuncDecl::new("knots_to_mps", &[None], float.clone(), vec![t.clone()], t.clone());
+    functions.add_function(Some("aero"), to_mps.clone());
+    functions.add_method(Some("aero"), ValueTy::Float(FloatTy::F64), to_mps);
+    let in_band = FuncDecl::new("in_band", &[None, Some("lo"), Some("hi")], float, vec![t; 3], ValueTy::Bool);
+    functions.add_function(None, in_band);
+    let parse = |spec| super::parse_with_functions("stdin", spec, FrontendConfig::default(), &functions);
+
+    let spec = "import aero\n input airspeed: Float64\n\
+                output speed := knots_to_mps(airspeed)\n\
+                output speed2 := airspeed.knots_to_mps()\n\
+                trigger !in_band(speed, lo: 20.0, hi: 80.0) \"airspeed out of band\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n output b := knots_to_mps(a)").is_err());
+    assert!(parse("import aero\n input a: Int64\n output b := knots_to_mps(a)").is_err());
+    assert!(parse("import avionics\n input a: Float64\n output b := a").is_err());
+    assert!(super::parse(
+        "stdin",
+        "input a: Float64\n output b := in_band(a, lo: 0.0, hi: 1.0)",
+        FrontendConfig::default()
+    )
+    .is_err());
+}
+
+#[test]
+fn angle_builtins() {
+    let spec = "input heading: Float64\n input yaw: Float32\n input target: Float64\n\
+                output deviation := angle_diff(heading, target)\n\
+                output normalized := wrap_angle_deg(heading)\n\
+                output yaw_wrapped := yaw.wrap_angle_rad()\n\
+                trigger abs(deviation) > 30.0 \"heading deviates from target\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Int64\n output b := wrap_angle_deg(a)").is_err());
+    assert!(parse("input a: Float64\n output b := a.angle_diff(a)").is_err());
+}
+
+#[test]
+fn zscore_builtin() {
+    let spec = "input reading: Float64\n input spread: Float64\n\
+                output mean @ 1Hz := reading.aggregate(over: 60s, using: avg).defaults(to: 0.0)\n\
+                output z := zscore(reading, mean: mean.hold().defaults(to: reading), stddev: spread)\n\
+                output score := reading.zscore(mean: 0.0, stddev: 1.0)\n\
+                trigger abs(z) > 3.0 \"reading is 3 standard deviations from the mean\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n output b := zscore(a, 0.0, 1.0)").is_err());
+    assert!(parse("input a: Int64\n output b := zscore(a, mean: 0, stddev: 1)").is_err());
+}
+
+#[test]
+fn filter_builtins() {
+    let spec = "input raw: Float64\n\
+                output smooth := lowpass(raw, alpha: 0.1)\n\
+                output throttle @ 10Hz := raw.hold().defaults(to: 0.0).rate_limit(max_delta: 0.5)\n\
+                trigger smooth - lowpass(smooth, alpha: 0.5) > 2.0 \"sudden change\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Int64\n output b := lowpass(a, alpha: 1)").is_err());
+    assert!(parse("input a: Float64\n output b := rate_limit(a, 0.5)").is_err());
+}
+
+#[test]
+fn trigger_hysteresis() {
+    let spec = "input temp: Float64\n\
+                output hot := (temp > 90.0).debounce(for: 500ms)\n\
+                output alarm := hysteresis(temp > 85.0, off: temp < 75.0)\n\
+                trigger temp > 80.0 for 2s until temp < 70.0 \"overheated\"\n\
+                trigger temp < 0.0 for 10s\n\
+                trigger hot until !alarm";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n trigger a for 2s").is_err());
+    assert!(parse("input a: Float64\n trigger a > 1.0 for 2").is_err());
+    assert!(parse("input a: Float64\n trigger a > 1.0 until a").is_err());
+}
+
+#[test]
+fn trigger_escalation() {
+    let spec = "input temp: Float64\n\
+                trigger temp > 80.0 escalate after 3 within 10s \"{count} readings above 80 within 10s\"\n\
+                trigger temp < 0.0 for 1s escalate after 2 within 1min";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n trigger a > 1.0 escalate after 0 within 10s").is_err());
+    assert!(parse("input a: Float64\n trigger a > 1.0 escalate after 2.5 within 10s").is_err());
+    assert!(parse("input a: Float64\n trigger a > 1.0 escalate after 3 within 10").is_err());
+}
+
+#[test]
+fn named_triggers() {
+    let spec = "input temp: Float64\n\
+                trigger overheated := temp > 80.0 \"overheated\"\n\
+                trigger frozen := temp < 0.0";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!(ir.outputs[0].name, "overheated");
+    assert_eq!(ir.triggers[0].message, "overheated");
+    assert_eq!(ir.outputs[1].name, "frozen");
+    assert!(parse("input a: Float64\n trigger t := a > 1.0\n trigger t := a < 0.0").is_err());
+    assert!(parse("input a: Float64\n trigger a := a > 1.0").is_err());
+}
+
+#[test]
+fn trigger_message_placeholders() {
+    let spec = "input altitude: Float64\n\
+                output low := altitude < 200.0\n\
+                trigger low \"too low: {altitude}m, {low}, {{altitude}}, {1}\"\n\
+                trigger altitude < 0.0 escalate after 2 within 1s \"{count} times {altitude}\"";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let placeholders = |ix: usize| -> Vec<(&str, ir::StreamReference)> {
+        ir.triggers[ix].placeholders.iter().map(|(name, stream)| (name.as_str(), *stream)).collect()
+    };
+    assert_eq!(
+        placeholders(0),
+        vec![("altitude", ir::StreamReference::InRef(0)), ("low", ir::StreamReference::OutRef(0))]
+    );
+    assert_eq!(placeholders(1), vec![("altitude", ir::StreamReference::InRef(0))]);
+    assert!(parse("input a: Float64\n trigger a > 1.0 \"{unknown}\"").is_err());
+    assert!(parse("input a: Float64\n trigger a > 1.0 \"{count}\"").is_err());
+}
+
+#[test]
+fn trigger_message_escapes() {
+    let spec = "input altitude: Float64\n\
+                trigger altitude < 200.0 \"\\\"low\\\" at\\n{altitude}m\"";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!(ir.triggers[0].message, "\"low\" at\n{altitude}m");
+    assert_eq!(ir.triggers[0].placeholders, vec![(String::from("altitude"), ir::StreamReference::InRef(0))]);
+    assert!(parse("input a: Float64\n trigger a > 1.0 \"\\q\"").is_err());
+    assert!(parse("input a: String\n output b := a == \"C:\\dir\"").is_err());
+    assert!(parse("input a: String\n output b := a == \"C:\\\\dir\" || a == r\"C:\\dir\"").is_ok());
+}
+
+#[test]
+fn radix_literals() {
+    let spec = "input flags: UInt8\n\
+                output mode := flags & 0x0F\n\
+                trigger mode == 0b1010 || flags > 0o170 \"invalid mode\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("output a: UInt8 := 0x100").is_err());
+    assert!(parse("output a: UInt8 := 0b102").is_err());
+}
+
+#[test]
+fn checked_casts() {
+    let spec = "input wide: Int64\n\
+                output narrow: Int8 := (wide as Int8).defaults(to: 0)\n\
+                output ratio := (wide as Float32).defaults(to: 0.0) / 2.0\n\
+                trigger (-wide.abs() as UInt16).defaults(to: 0) > 5";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Int64\noutput b: Int8 := a as Int8").is_err());
+    assert!(parse("input a: Int64\noutput b := (a as Bool).defaults(to: false)").is_err());
+    assert!(parse("input a: String\noutput b := (a as Int8).defaults(to: 0)").is_err());
+}
+
+#[test]
+fn frequency_annotations() {
+    let config =
+        FrontendConfig { ty: TypeConfig { type_aliases: true, ..TypeConfig::default() }, ..FrontendConfig::default() };
+    let spec = "input speed: Float\n\
+                output avg: Float @ 10Hz := speed.aggregate(over: 1s, using: avg).defaults(to: 0.0)";
+    let ir = super::parse("stdin", spec, config).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!(ir.time_driven.len(), 1);
+    assert_eq!(ir.time_driven[0].extend_rate, std::time::Duration::from_millis(100));
+    assert!(super::parse("stdin", "output avg: Float @ 0Hz := 0.0", config).is_err());
+}
+
+#[test]
+fn percentile_windows() {
+    let spec = "input altitude: Float64\n\
+                output median @ 1Hz := altitude.aggregate(over: 1min, using: median).defaults(to: 0.0)\n\
+                output p95 @ 1Hz := altitude.aggregate(over: 1min, using: percentile(95)).defaults(to: 0.0)";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let ops: Vec<ir::WindowOperation> = ir.sliding_windows.iter().map(|window| window.op.clone()).collect();
+    assert_eq!(ops, vec![ir::WindowOperation::Median, ir::WindowOperation::Percentile(95)]);
+    assert!(parse("input a: Bool\noutput b @ 1Hz := a.aggregate(over: 1s, using: median)").is_err());
+}
+
+#[test]
+fn first_last_windows() {
+    let spec = "input mode: String\n\
+                output changed @ 1Hz := mode.aggregate(over: 10s, using: first).defaults(to: \"\") != mode.aggregate(over: 10s, using: last).defaults(to: \"\")";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let ops: Vec<ir::WindowOperation> = ir.sliding_windows.iter().map(|window| window.op.clone()).collect();
+    assert_eq!(ops, vec![ir::WindowOperation::First, ir::WindowOperation::Last]);
+}
+
+#[test]
+fn let_bindings() {
+    let spec = "input a: Float64\n\
+                input b: Float64\n\
+                output distance := let dx := a - b in let sq := dx * dx in sq + sq\n\
+                trigger let d := a - b in d > 1.0 || d < -1.0";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Int64\noutput b := (let d := a + 1 in d) + d").is_err());
+    assert!(parse("input a: Int64\noutput b := let d := a + 1 in d.offset(by: -1).defaults(to: 0)").is_err());
+    assert!(parse("input a: Int64\noutput b: Bool := let d := a + 1 in d").is_err());
+}
+
+#[test]
+fn numeric_literal_notation() {
+    let spec = "input distance: Float64\n\
+                output limit: Int64 := 1_000_000\n\
+                trigger distance > 1e3 || distance < 1.5e-3 \"out of range\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("output a: Int64 := 1__000").is_err());
+    assert!(parse("output a: Float64 := 1e400").is_err());
+    assert!(parse("output a: Float32 := 1e39").is_err());
+    assert!(parse("output a: Float32 := 1e38").is_ok());
+}
+
+#[test]
+fn assumptions() {
+    let spec = "input altitude: Float64\n\
+                assume altitude >= 0.0 \"the altimeter works\"\n\
+                assume altitude < 10000.0\n\
+                trigger altitude < 200.0 \"too low\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n assume a").is_err());
+}
+
+#[test]
+fn derived_inputs() {
+    let spec = "input raw_alt: Int32\n\
+                input alt: Float64 := to_float(raw_alt) / 100.0\n\
+                output climb := alt - alt.offset(by: -1).defaults(to: alt)\n\
+                trigger alt < 10.0 \"too low\"";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!(ir.inputs.len(), 1);
+    assert_eq!(ir.outputs[0].name, "alt");
+    assert!(parse("input raw_alt: Int32\n input alt: Bool := to_float(raw_alt)").is_err());
+    assert!(parse("input raw_alt: Int32\n input alt := to_float(raw_alt)").is_err());
+}
+
+#[test]
+fn configurable_constants() {
+    let spec = "configurable constant max_speed: Float64 := 100.0\n\
+                constant min_speed: Float64 := 10.0\n\
+                input speed: Float64\n\
+                trigger speed > max_speed || speed < min_speed \"speed out of range\"";
+    let mut ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!(ir.constants.len(), 1);
+    assert_eq!(ir.constants[0].value, ir::Constant::Float(100.0));
+    assert!(ir.configure(&[(String::from("min_speed"), String::from("5.0"))]).is_err());
+    assert!(ir.configure(&[(String::from("max_speed"), String::from("fast"))]).is_err());
+    ir.configure(&[(String::from("max_speed"), String::from("120"))]).unwrap();
+    assert_eq!(ir.constants[0].value, ir::Constant::Float(120.0));
+    assert_eq!(format!("{}", ir.outputs[0].expr).matches("120").count(), 1);
+}
+
+#[test]
+fn constant_expressions() {
+    let spec = "constant limit: Int64 := 3 * base + 1\n\
+                constant base: Int64 := let half := 10 / 2 in half * half\n\
+                constant strict: Bool := limit > 50 && !false\n\
+                input a: Int64\n\
+                trigger if strict then a > limit else a > base \"too large\"";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!(format!("{}", ir.outputs[0].expr).matches("76").count(), 1);
+    assert!(parse("input a: Int64\nconstant b: Int64 := a + 1").is_err());
+    assert!(parse("constant a: Int64 := b\nconstant b: Int64 := a").is_err());
+    assert!(parse("constant a: UInt8 := 200 + 100").is_err());
+    assert!(parse("constant a: Int64 := 1 / 0").is_err());
+    assert!(parse("configurable constant a: Int64 := 1\nconstant b: Int64 := a").is_err());
+}
+
+#[test]
+fn rounding_builtins() {
+    let spec = "input temp: Float64\n input limit: Float32\n\
+                output quantized := round(temp, digits: 1)\n\
+                output bounds := floor(temp) + temp.ceil(digits: -1) + temp.round()\n\
+                output other: Float32 := limit.floor(digits: 2)\n\
+                trigger quantized != quantized.offset(by: -1).defaults(to: 0.0) \"temperature changed\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Int64\n output b := round(a)").is_err());
+    assert!(parse("input a: Float64\n output b := round(a, 2)").is_err());
+    assert!(parse("input a: Float64\n output b := a.round(digits: 0.5)").is_err());
+}
+
+#[test]
+fn checked_and_saturating_builtins() {
+    let spec = "input count: UInt8\n input delta: Int16\n\
+                output total: UInt8 := saturating_add(count, total.offset(by: -1).defaults(to: 0))\n\
+                output scaled := delta.checked_mul(100).defaults(to: 0)\n\
+                output remaining := checked_sub(count, 10).defaults(to: 0)\n\
+                trigger checked_div(delta, delta.offset(by: -1).defaults(to: 0)).defaults(to: 0) > 2 \"fast growth\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n output b := saturating_add(a, a)").is_err());
+    assert!(parse("input a: UInt8\n output b: UInt8 := checked_add(a, a)").is_err());
+}
+
+#[test]
+fn bit_builtins() {
+    let spec = "input status: UInt16\n input flags: Int8\n\
+                output armed := bit(status, index: 3)\n\
+                output mode: UInt16 := status.bits(hi: 7, lo: 4)\n\
+                output active := flags.popcount()\n\
+                output cleared: Int8 := set_bit(flags, index: 0, to: false)\n\
+                trigger armed && mode == 2 \"armed in manual mode\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Float64\n output b := popcount(a)").is_err());
+    assert!(parse("input a: UInt8\n output b := a.bit(index: true)").is_err());
+    assert!(parse("input a: UInt8\n output b := set_bit(a, index: 1, to: 1)").is_err());
+}
+
+#[test]
+fn string_builtins() {
+    let spec = "input msg: String\n\
+                output short := msg.length() < 10 && msg.to_lower().contains(pattern: \"error\")\n\
+                output code: String := substring(msg, from: 0, to: 4).to_upper()\n\
+                trigger msg.starts_with(prefix: \"[\") && ends_with(msg, suffix: \"]\") \"bracketed\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Bytes\n output b := a.length()").is_err());
+    assert!(parse("input a: String\n output b := a.substring(from: -1, to: 2)").is_err());
+    assert!(parse("input a: String\n output b := a.contains(\"x\")").is_err());
+}
+
+#[test]
+fn byte_decoding_builtins() {
+    let spec = "input frame: Bytes\n\
+                output rpm := u16_le(frame, offset: 2).defaults(to: 0)\n\
+                output temperature := frame.f32_be(offset: 4).defaults(to: 0.0)\n\
+                output position: Int32? := frame.i32_le(offset: 8)\n\
+                trigger rpm > 6000 \"engine overspeed\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: Bytes\n output b := u16_be(a, 2)").is_err());
+    assert!(parse("input a: String\n output b := a.i32_be(offset: 0)").is_err());
+}
+
+#[test]
+fn parse_builtins() {
+    let spec = "input payload: String\n\
+                output temperature := parse_float(payload).defaults(to: 0.0)\n\
+                output count: Int64 := payload.parse_int().defaults(to: 0)\n\
+                trigger temperature > 80.0 \"overheating\"";
+    assert!(parse(spec).is_ok());
+    assert!(parse("input a: String\n output b: Float64 := parse_float(a)").is_err());
+    assert!(parse("input a: Int64\n output b := parse_int(a)").is_err());
+}
+
+#[test]
+fn regex_builtin() {
+    assert!(parse("input msg: String\n trigger matches(msg, \"^ERROR\") || msg.matches(r\"\\d{3}\") \"error\"").is_ok());
+    assert!(parse("input raw: Bytes\n output b := raw.matches(\"^sub\")").is_ok());
+    assert!(parse("input msg: String\n output b := matches(msg, \"[unclosed\")").is_err());
+    assert!(parse("input msg: String\n output b := matches(msg, msg)").is_err());
+}
+
+#[test]
+fn clamp_incompatible() {
+    assert!(parse("input a: Int32\n output b := clamp(a, lo: 0.0, hi: 1.0)").is_err());
+    assert!(parse("input a: Int32\n output b := clamp(a, 0, 1)").is_err());
+    assert!(parse("input a: Bool\n output b := a.clamp(lo: false, hi: true)").is_err());
+}
+
 #[test]
 fn test_float16() {
     assert!(parse("input in: Float16\noutput count := in + 3.5").is_ok());
@@ -193,3 +645,240 @@ fn test_float16() {
 fn future_offset() {
     assert!(parse("input a: Int8\noutput b := a.offset(by: 1).defaults(to: 3)").is_ok());
 }
+
+#[test]
+fn look_ahead() {
+    let spec = "input a: Int8\noutput b := a.offset(by: 2).defaults(to: 0)\noutput c := b[+1].defaults(to: 0) + b[-1].defaults(to: 0)\noutput d := c[-3].defaults(to: 0)";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let look_ahead: Vec<u32> = ir.outputs.iter().map(|o| o.look_ahead).collect();
+    assert_eq!(look_ahead, vec![2, 3, 1]);
+    assert!(parse("input a: Int8\noutput b := b[+1].defaults(to: 0) + a").is_err());
+    assert!(
+        parse("input a: Int8\noutput b := a[+1].defaults(to: 0)\noutput c @ 1Hz := b.hold().defaults(to: 0)").is_err()
+    );
+}
+
+#[test]
+fn instance_templates() {
+    let spec = "input id: Int64\noutput cnt(p: Int64) @id { invoke id if id > 0 extend id == p terminate id == -p } := self.offset(by: -1).defaults(to: 0) + 1\ntrigger id > 0 && cnt(id) > 2";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let cnt = &ir.outputs[0];
+    assert_eq!(cnt.params, vec![ir::Parameter { name: String::from("p"), ty: ir::Type::Int(ir::IntTy::I64) }]);
+    assert_eq!(cnt.template.as_ref().map(|t| t.clauses().count()), Some(4));
+    assert!(ir.outputs[1].layer > cnt.layer);
+    assert!(parse("input id: Int64\noutput cnt(p: Int64) @id close id == -p := 0").is_ok());
+    assert!(parse("input id: Int64\noutput cnt @id { invoke id } := 0").is_err());
+    assert!(parse("input id: Int64\noutput cnt(p: Int64) @id { invoke id > 0 } := 0").is_err());
+}
+
+#[test]
+fn multiple_outputs() {
+    let ir = parse("input a: Int64\noutput b: Int64 := a + 1, c := b * 2\noutput (lo, hi) := (a - 1, a + 1)")
+        .unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let names: Vec<&str> = ir.outputs.iter().map(|o| o.name.as_str()).collect();
+    assert_eq!(names, vec!["b", "c", "lo", "hi"]);
+    assert!(ir.outputs.iter().all(|o| o.ty == ir::Type::Int(ir::IntTy::I64)));
+    assert!(parse("input a: Int64\noutput (lo, hi, mid) := (a - 1, a + 1)").is_err());
+    assert!(parse("input a: Int64\noutput b := a, b := a").is_err());
+}
+
+#[test]
+fn type_aliases() {
+    let spec =
+        "type Temperature := Celsius\ntype Celsius := Float64\ninput t: Temperature\noutput c: Celsius := t * 2.0";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!(ir.inputs[0].ty, ir::Type::Float(ir::FloatTy::F64));
+    assert_eq!(ir.outputs[0].ty, ir::Type::Float(ir::FloatTy::F64));
+    assert!(parse("type A := B\ntype B := A\ninput a: A").is_err());
+    assert!(parse("type T := Unknown\ninput a: T").is_err());
+    assert!(parse("type Int64 := Bool\ninput a: Int64").is_err());
+}
+
+#[test]
+fn optional_values() {
+    let spec =
+        "input a: (Int64, Bool)\noutput b := a[-1].is_some()\noutput c := a.offset(by: -1).unwrap_or((0, false)).1";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!(ir.outputs[0].ty, ir::Type::Bool);
+    assert_eq!(ir.outputs[1].ty, ir::Type::Bool);
+    assert!(parse("input a: (Int64, Bool)\noutput b: (Int64, Bool) := a[-1]").is_err());
+}
+
+#[test]
+fn arrays() {
+    let spec = "input q: [Float64; 4]\noutput r := q + [1.0, 0.0, 0.0, 0.0]\noutput w: Float64 := r.0";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!(ir.inputs[0].ty, ir::Type::Array(ir::Type::Float(ir::FloatTy::F64).into(), 4));
+    assert_eq!(ir.outputs[0].ty, ir.inputs[0].ty);
+    assert_eq!(ir.outputs[1].ty, ir::Type::Float(ir::FloatTy::F64));
+    assert!(parse("input q: [Float64; 4]\noutput w: Float64 := q.4").is_err());
+    assert!(parse("output w: Float64 := [1.0, 2.0].1").is_ok());
+    // elements are only accessed by constant indices, brackets denote offsets
+    assert!(parse("output w: Float64 := [1.0, 2.0][1]").is_err());
+}
+
+#[test]
+fn enums() {
+    let spec = "enum Mode { Init, Armed, Flying }
+        input m: Mode
+        constant start: Mode := Mode::Init
+        output armed := m == Mode::Armed
+        output last := m.offset(by: -1).defaults(to: start)";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let mode = ir::Type::Enum("Mode".into(), vec!["Init".into(), "Armed".into(), "Flying".into()]);
+    assert_eq!(ir.inputs[0].ty, mode);
+    assert_eq!(ir.outputs[0].ty, ir::Type::Bool);
+    assert_eq!(ir.outputs[1].ty, mode);
+    assert!(parse("enum Mode { Init }\ninput m: Mode\noutput b := m == Mode::Armed").is_err());
+    // paths of other names are streams, e.g., MAVLink fields
+    assert!(parse("enum Mode { Init }\ninput ATTITUDE::roll: Float64\noutput b := ATTITUDE::roll > 0.5").is_ok());
+}
+
+#[test]
+fn denied_warnings() {
+    let spec = "input a: Int32\noutput b @ a := a.hold().defaults(to: 0)";
+    let warnings = |deny_all, deny: &[&str], allow: &[&str]| WarningConfig {
+        deny_all,
+        deny: deny.iter().map(|c| c.to_string()).collect(),
+        allow: allow.iter().map(|c| c.to_string()).collect(),
+    };
+    let parse = |warnings| parse_with_warnings("stdin", spec, FrontendConfig::default(), &warnings);
+    assert!(parse(warnings(false, &[], &[])).is_ok());
+    assert!(parse(warnings(true, &[], &[])).is_err());
+    assert!(parse(warnings(false, &["W0201"], &[])).is_err());
+    assert!(parse(warnings(true, &[], &["W0201"])).is_ok());
+    assert!(parse(warnings(false, &["E0106"], &[])).is_err());
+    assert!(parse(warnings(false, &["W9999"], &[])).is_err());
+    assert!(parse(warnings(false, &["W0201"], &["W0201"])).is_err());
+}
+
+#[test]
+fn allow_attribute() {
+    let deny_all = WarningConfig { deny_all: true, ..WarningConfig::default() };
+    let parse = |spec| parse_with_warnings("stdin", spec, FrontendConfig::default(), &deny_all);
+    assert!(parse("input a: Int32\n#[allow(W0201)]\noutput b @ a := a.hold().defaults(to: 0)").is_ok());
+    assert!(parse("input a: Int32\n#[allow(W0201)]\noutput c := a\noutput b @ a := a.hold().defaults(to: 0)").is_err());
+    assert!(parse("input a: Int32\n#[allow(W0301, W0201)]\noutput b @ a := a.hold().defaults(to: 0)").is_ok());
+    assert!(parse("input a: Int32\n#[allow(W9999)]\noutput b := a").is_err());
+    assert!(parse("input a: Int32\n#[allow(E0106)]\noutput b := a").is_err());
+    assert!(parse("input a: Int32\n#[deny(W0201)]\noutput b := a").is_err());
+}
+
+#[test]
+fn cfg_attribute() {
+    let spec = "input altitude: Float64\n\
+                #[cfg(feature = \"simulation\")]\n\
+                input sim_time: Float64\n\
+                #[cfg(all(not(feature = \"simulation\"), any(feature = \"flight\", feature = \"hil\")))]\n\
+                #[allow(W0201)]\n\
+                trigger altitude < sim_time \"too low\"";
+    let parse = |features: &[&str]| {
+        let features: Vec<String> = features.iter().map(|f| f.to_string()).collect();
+        parse_with_features("stdin", spec, FrontendConfig::default(), &features, &WarningConfig::default())
+    };
+    let ir = parse(&["simulation"]).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!((ir.inputs.len(), ir.triggers.len()), (2, 0));
+    let ir = parse(&[]).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    assert_eq!((ir.inputs.len(), ir.triggers.len()), (1, 0));
+    // the trigger accesses the input of the simulation
+    assert!(parse(&["flight"]).is_err());
+    assert!(parse(&["simulation", "flight"]).is_ok());
+    assert!(parse_with_features(
+        "stdin",
+        "#[cfg(simulation)]\ninput a: Int32",
+        FrontendConfig::default(),
+        &[],
+        &WarningConfig::default()
+    )
+    .is_err());
+}
+
+#[test]
+fn annotations() {
+    let spec = "#[memory(bounded)]\n\
+                input a: Int64\n\
+                #[deprecated]\n\
+                output b := a + 1\n\
+                #[severity(error)]\n\
+                trigger a > 10 \"too large\"";
+    let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let bounded = ir::Annotation { name: String::from("memory"), args: vec![String::from("bounded")] };
+    assert_eq!(ir.inputs[0].annotations, vec![bounded]);
+    assert_eq!(ir.outputs[0].annotations[0].name, "deprecated");
+    assert_eq!(ir.triggers[0].severity(), Some("error"));
+    let deny_all = WarningConfig { deny_all: true, ..WarningConfig::default() };
+    let strict = |spec| parse_with_warnings("stdin", spec, FrontendConfig::default(), &deny_all);
+    assert!(strict("input a: Int64\n#[deprecated]\noutput b := a\noutput c := b").is_err());
+    assert!(strict("input a: Int64\n#[deprecated]\noutput b := a\n#[allow(W0102)]\noutput c := b").is_ok());
+    assert!(strict("input a: Int64\n#[deprecated]\noutput b := b.offset(by: -1).defaults(to: a)").is_ok());
+    assert!(parse("#[memory(bounded)]\ninput a: String\noutput b := a").is_err());
+    assert!(parse("input a: Bool\n#[severity(fatal)]\ntrigger a").is_err());
+    assert!(parse("#[severity(error)]\ninput a: Bool").is_err());
+    assert!(parse("#[deprecated(now)]\ninput a: Bool").is_err());
+    assert!(parse("#[deprecated]\nconstant a: Bool := true").is_err());
+    assert!(parse("#[allow]\ninput a: Bool").is_err());
+}
+
+#[test]
+fn file_imports() {
+    use std::fs;
+    use std::path::Path;
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/imports");
+    let parse_file = |name: &str| {
+        let path = dir.join(name);
+        let spec = fs::read_to_string(&path).expect("cannot read specification");
+        super::parse(&path.to_string_lossy(), &spec, FrontendConfig::default())
+    };
+    // `common.lola` is imported both directly and through `derived.lola`, but only added once
+    let ir = parse_file("monitor.lola").unwrap_or_else(|e| panic!("{}", e));
+    assert_eq!(ir.inputs.iter().map(|i| i.name.as_str()).collect::<Vec<_>>(), vec!["altitude", "velocity"]);
+    assert!(ir.outputs.iter().any(|o| o.name == "high"));
+    assert!(parse_file("redefinition.lola").is_err());
+    assert!(parse_file("cyclic.lola").is_err());
+    assert!(parse_file("missing.lola").is_err());
+}
+
+/// Golden tests of the rendered diagnostics.
+/// Each specification `tests/diagnostics/<name>.lola` is checked against the expected diagnostics in `<name>.stderr`;
+/// a missing `.stderr` file means that the specification has no diagnostics.
+/// After intended changes, run the tests with `RTLOLA_BLESS=1` to update the expected diagnostics.
+#[test]
+fn diagnostics_corpus() {
+    use std::fs;
+    use std::path::Path;
+
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/diagnostics");
+    let bless = std::env::var_os("RTLOLA_BLESS").is_some();
+    let mut specs: Vec<_> = fs::read_dir(&corpus)
+        .expect("cannot read the corpus")
+        .map(|entry| entry.expect("cannot read the corpus").path())
+        .filter(|path| path.extension().map_or(false, |ext| ext == "lola"))
+        .collect();
+    specs.sort();
+    assert!(!specs.is_empty(), "no specifications in {}", corpus.display());
+
+    let mut failures = Vec::new();
+    for spec in specs {
+        let name = spec.file_name().unwrap().to_string_lossy().into_owned();
+        let content = fs::read_to_string(&spec).expect("cannot read specification");
+        let rendered = render_diagnostics(&name, &content, FrontendConfig::default());
+        let expected_path = spec.with_extension("stderr");
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if rendered == expected {
+            continue;
+        }
+        if bless && rendered.is_empty() {
+            fs::remove_file(&expected_path).expect("cannot remove expected diagnostics");
+        } else if bless {
+            fs::write(&expected_path, &rendered).expect("cannot write expected diagnostics");
+        } else {
+            failures.push(format!("{}\n--- expected\n{}--- found\n{}", name, expected, rendered));
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "diagnostics differ, run with `RTLOLA_BLESS=1` to accept them:\n\n{}",
+        failures.join("\n")
+    );
+}
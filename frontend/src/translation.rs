@@ -0,0 +1,22 @@
+//! This module provides translations of other specification formalisms into RTLola specifications.
+//!
+//! In detail,
+//! * `formula` parses the textual syntax of temporal logic formulas shared by the translators
+//! * `past_ltl` compiles past-time LTL formulas into event-based streams and triggers
+//! * `stl` compiles Signal Temporal Logic formulas into periodic streams and triggers
+
+mod formula;
+pub mod past_ltl;
+pub mod stl;
+
+/**
+Formats a number as RTLola float literal, i.e., always with a fractional part.
+*/
+fn float_literal(f: f64) -> String {
+    let literal = f.to_string();
+    if literal.contains('.') {
+        literal
+    } else {
+        literal + ".0"
+    }
+}
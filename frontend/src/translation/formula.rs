@@ -0,0 +1,427 @@
+//! A parser for the temporal logic formulas accepted by the translators.
+//!
+//! The syntax is shared by all translators; temporal operators unsupported by a logic are rejected when compiling.
+
+/// A token of a formula.
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    /// A number literal as written in the formula.
+    Number(String),
+    /// Operators and delimiters, e.g., `&&`, `<=`, or `[`.
+    Symbol(&'static str),
+}
+
+const SYMBOLS: [&str; 24] = [
+    "<->", "->", "&&", "||", "<=", ">=", "==", "!=", "<", ">", "=", "!", "&", "|", "(", ")", "[", "]", ",", "+", "-",
+    "*", "/", "~",
+];
+
+const KEYWORDS: [&str; 24] = [
+    "G",
+    "F",
+    "always",
+    "eventually",
+    "U",
+    "until",
+    "X",
+    "next",
+    "Y",
+    "yesterday",
+    "previously",
+    "O",
+    "once",
+    "H",
+    "historically",
+    "S",
+    "since",
+    "true",
+    "false",
+    "not",
+    "and",
+    "or",
+    "implies",
+    "iff",
+];
+
+/// Splits a formula into tokens, returns an error message for unknown characters.
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut rest = input.trim_start();
+    while let Some(c) = rest.chars().next() {
+        let len = if c.is_ascii_alphabetic() || c == '_' {
+            let len = rest.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == ':')).unwrap_or(rest.len());
+            tokens.push(Token::Ident(rest[..len].to_string()));
+            len
+        } else if c.is_ascii_digit() || c == '.' {
+            let len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.')).unwrap_or(rest.len());
+            if rest[..len].parse::<f64>().is_err() {
+                return Err(format!("invalid number `{}`", &rest[..len]));
+            }
+            tokens.push(Token::Number(rest[..len].to_string()));
+            len
+        } else if let Some(sym) = SYMBOLS.iter().find(|s| rest.starts_with(*s)) {
+            tokens.push(Token::Symbol(sym));
+            sym.len()
+        } else {
+            return Err(format!("unexpected character `{}`", c));
+        };
+        rest = rest[len..].trim_start();
+    }
+    Ok(tokens)
+}
+
+/// A comparison between two terms.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum CompOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CompOp {
+    pub(crate) fn symbol(self) -> &'static str {
+        match self {
+            CompOp::Lt => "<",
+            CompOp::Le => "<=",
+            CompOp::Gt => ">",
+            CompOp::Ge => ">=",
+            CompOp::Eq => "==",
+            CompOp::Ne => "!=",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Term {
+    /// A number literal as written in the formula.
+    Const(String),
+    Signal(String),
+    Neg(Box<Term>),
+    Binary(Box<Term>, &'static str, Box<Term>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Formula {
+    Bool(bool),
+    /// A signal used as proposition.
+    Atom(String),
+    Predicate(Term, CompOp, Term),
+    Not(Box<Formula>),
+    And(Box<Formula>, Box<Formula>),
+    Or(Box<Formula>, Box<Formula>),
+    Implies(Box<Formula>, Box<Formula>),
+    Iff(Box<Formula>, Box<Formula>),
+    /// Globally within the interval `[a, b]`.
+    Globally(f64, f64, Box<Formula>),
+    /// Eventually within the interval `[a, b]`.
+    Eventually(f64, f64, Box<Formula>),
+    /// Held in the previous step.
+    Yesterday(Box<Formula>),
+    /// Held in some step so far.
+    Once(Box<Formula>),
+    /// Held in all steps so far.
+    Historically(Box<Formula>),
+    /// The right formula held at some step and the left one in all steps since then.
+    Since(Box<Formula>, Box<Formula>),
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn eat_symbol(&mut self, sym: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(s)) if *s == sym => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn eat_ident(&mut self, names: &[&str]) -> bool {
+        match self.peek() {
+            Some(Token::Ident(id)) if names.contains(&id.as_str()) => {
+                self.pos += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_symbol(&mut self, sym: &str) -> Result<(), String> {
+        if self.eat_symbol(sym) {
+            Ok(())
+        } else {
+            Err(format!("expected `{}` but found {}", sym, describe(self.peek())))
+        }
+    }
+
+    fn formula(&mut self) -> Result<Formula, String> {
+        let lhs = self.implication()?;
+        if self.eat_symbol("<->") || self.eat_ident(&["iff"]) {
+            let rhs = self.implication()?;
+            return Ok(Formula::Iff(lhs.into(), rhs.into()));
+        }
+        Ok(lhs)
+    }
+
+    fn implication(&mut self) -> Result<Formula, String> {
+        let lhs = self.disjunction()?;
+        if self.eat_symbol("->") || self.eat_ident(&["implies"]) {
+            let rhs = self.implication()?;
+            return Ok(Formula::Implies(lhs.into(), rhs.into()));
+        }
+        Ok(lhs)
+    }
+
+    fn disjunction(&mut self) -> Result<Formula, String> {
+        let mut lhs = self.conjunction()?;
+        while self.eat_symbol("||") || self.eat_symbol("|") || self.eat_ident(&["or"]) {
+            let rhs = self.conjunction()?;
+            lhs = Formula::Or(lhs.into(), rhs.into());
+        }
+        Ok(lhs)
+    }
+
+    fn conjunction(&mut self) -> Result<Formula, String> {
+        let mut lhs = self.binary_temporal()?;
+        while self.eat_symbol("&&") || self.eat_symbol("&") || self.eat_ident(&["and"]) {
+            let rhs = self.binary_temporal()?;
+            lhs = Formula::And(lhs.into(), rhs.into());
+        }
+        Ok(lhs)
+    }
+
+    fn binary_temporal(&mut self) -> Result<Formula, String> {
+        let lhs = self.unary()?;
+        if self.eat_ident(&["S", "since"]) {
+            let rhs = self.binary_temporal()?;
+            return Ok(Formula::Since(lhs.into(), rhs.into()));
+        }
+        if self.eat_ident(&["U", "until"]) {
+            return Err(String::from("the until operator is not supported"));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Formula, String> {
+        if self.eat_symbol("!") || self.eat_symbol("~") || self.eat_ident(&["not"]) {
+            return Ok(Formula::Not(self.unary()?.into()));
+        }
+        if self.eat_ident(&["G", "always"]) {
+            let (a, b) = self.interval("G")?;
+            return Ok(Formula::Globally(a, b, self.unary()?.into()));
+        }
+        if self.eat_ident(&["F", "eventually"]) {
+            let (a, b) = self.interval("F")?;
+            return Ok(Formula::Eventually(a, b, self.unary()?.into()));
+        }
+        if self.eat_ident(&["X", "next"]) {
+            return Err(String::from("the next operator is not supported, use F[a,a] instead"));
+        }
+        if self.eat_ident(&["Y", "yesterday", "previously"]) {
+            return Ok(Formula::Yesterday(self.unary()?.into()));
+        }
+        if self.eat_ident(&["O", "once"]) {
+            return Ok(Formula::Once(self.unary()?.into()));
+        }
+        if self.eat_ident(&["H", "historically"]) {
+            return Ok(Formula::Historically(self.unary()?.into()));
+        }
+        if self.eat_ident(&["true"]) {
+            return Ok(Formula::Bool(true));
+        }
+        if self.eat_ident(&["false"]) {
+            return Ok(Formula::Bool(false));
+        }
+        if let Some(Token::Symbol("(")) = self.peek() {
+            // Parentheses either enclose a formula or a term of a predicate, e.g., `(x + 1) < 2`.
+            let start = self.pos;
+            self.pos += 1;
+            if let Ok(inner) = self.formula() {
+                if self.eat_symbol(")") && !self.at_term_operator() {
+                    return Ok(inner);
+                }
+            }
+            self.pos = start;
+        }
+        self.predicate()
+    }
+
+    fn at_term_operator(&self) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(s)) => ["+", "-", "*", "/", "<", "<=", ">", ">=", "=", "==", "!="].contains(s),
+            _ => false,
+        }
+    }
+
+    fn interval(&mut self, op: &str) -> Result<(f64, f64), String> {
+        if !self.eat_symbol("[") {
+            return Err(format!("the operator `{}` requires a time bound, e.g., `{}[0,10]`", op, op));
+        }
+        let a = self.number()?;
+        self.expect_symbol(",")?;
+        let b = self.number()?;
+        self.expect_symbol("]")?;
+        if a > b {
+            return Err(format!("the interval [{}, {}] is empty", a, b));
+        }
+        Ok((a, b))
+    }
+
+    fn number(&mut self) -> Result<f64, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(n.parse().expect("checked when tokenizing")),
+            t => Err(format!("expected a number but found {}", describe(t.as_ref()))),
+        }
+    }
+
+    fn predicate(&mut self) -> Result<Formula, String> {
+        let lhs = self.sum()?;
+        let op = match self.peek() {
+            Some(Token::Symbol("<")) => CompOp::Lt,
+            Some(Token::Symbol("<=")) => CompOp::Le,
+            Some(Token::Symbol(">")) => CompOp::Gt,
+            Some(Token::Symbol(">=")) => CompOp::Ge,
+            Some(Token::Symbol("=")) | Some(Token::Symbol("==")) => CompOp::Eq,
+            Some(Token::Symbol("!=")) => CompOp::Ne,
+            t => {
+                return match lhs {
+                    Term::Signal(name) => Ok(Formula::Atom(name)),
+                    _ => Err(format!("expected a comparison but found {}", describe(t))),
+                }
+            }
+        };
+        self.pos += 1;
+        let rhs = self.sum()?;
+        Ok(Formula::Predicate(lhs, op, rhs))
+    }
+
+    fn sum(&mut self) -> Result<Term, String> {
+        let mut lhs = self.product()?;
+        loop {
+            let op = if self.eat_symbol("+") {
+                "+"
+            } else if self.eat_symbol("-") {
+                "-"
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Term::Binary(lhs.into(), op, self.product()?.into());
+        }
+    }
+
+    fn product(&mut self) -> Result<Term, String> {
+        let mut lhs = self.atom()?;
+        loop {
+            let op = if self.eat_symbol("*") {
+                "*"
+            } else if self.eat_symbol("/") {
+                "/"
+            } else {
+                return Ok(lhs);
+            };
+            lhs = Term::Binary(lhs.into(), op, self.atom()?.into());
+        }
+    }
+
+    fn atom(&mut self) -> Result<Term, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Term::Const(n)),
+            Some(Token::Ident(id)) if !KEYWORDS.contains(&id.as_str()) => Ok(Term::Signal(id)),
+            Some(Token::Symbol("-")) => Ok(Term::Neg(self.atom()?.into())),
+            Some(Token::Symbol("(")) => {
+                let term = self.sum()?;
+                self.expect_symbol(")")?;
+                Ok(term)
+            }
+            t => Err(format!("expected a signal or a number but found {}", describe(t.as_ref()))),
+        }
+    }
+}
+
+fn describe(token: Option<&Token>) -> String {
+    match token {
+        None => String::from("the end of the formula"),
+        Some(Token::Ident(id)) | Some(Token::Number(id)) => format!("`{}`", id),
+        Some(Token::Symbol(s)) => format!("`{}`", s),
+    }
+}
+
+/// Parses a formula, returns an error message if it is malformed.
+pub(crate) fn parse(formula: &str) -> Result<Formula, String> {
+    let mut parser = Parser { tokens: tokenize(formula)?, pos: 0 };
+    let formula = parser.formula()?;
+    match parser.peek() {
+        None => Ok(formula),
+        t => Err(format!("unexpected {} after the formula", describe(t))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn atom(name: &str) -> Box<Formula> {
+        Formula::Atom(name.to_string()).into()
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("a && b S c || !d -> e").unwrap(),
+            Formula::Implies(
+                Formula::Or(
+                    Formula::And(atom("a"), Formula::Since(atom("b"), atom("c")).into()).into(),
+                    Formula::Not(atom("d")).into()
+                )
+                .into(),
+                atom("e")
+            )
+        );
+    }
+
+    #[test]
+    fn parenthesized_terms() {
+        assert_eq!(
+            parse("(x + 1) * 2 < y").unwrap(),
+            Formula::Predicate(
+                Term::Binary(
+                    Term::Binary(Term::Signal("x".into()).into(), "+", Term::Const("1".into()).into()).into(),
+                    "*",
+                    Term::Const("2".into()).into()
+                ),
+                CompOp::Lt,
+                Term::Signal("y".into())
+            )
+        );
+        assert_eq!(parse("(G[0,1] a)").unwrap(), Formula::Globally(0.0, 1.0, atom("a")));
+    }
+
+    #[test]
+    fn malformed() {
+        assert!(parse("a &&").is_err());
+        assert!(parse("x + 1").is_err());
+        assert!(parse("a b").is_err());
+        assert!(parse("G[1,0] a").is_err());
+        assert!(parse("a U b").is_err());
+        assert!(parse("x $ 1").is_err());
+    }
+}
@@ -0,0 +1,162 @@
+//! Translation of past-time Linear Temporal Logic (LTL) formulas into RTLola output streams and triggers.
+//!
+//! The accepted formulas consist of
+//! * propositions, i.e., boolean streams of the specification, and predicates over streams, e.g., `altitude < 200`,
+//! * the boolean connectives `!`, `&&`, `||`, `->`, and `<->`,
+//! * the past-time operators `Y` (yesterday), `O` (once), `H` (historically), and `S` (since).
+//!
+//! A step of the formula corresponds to an evaluation of the generated streams, i.e., the streams referred to by the
+//! formula are accessed synchronously, and the formula is evaluated whenever all of them receive a new value.
+//! The result is a list of definitions to be appended to the specification declaring the referenced streams.
+
+use super::formula::{parse, Formula, Term};
+
+struct Compiler {
+    name: String,
+    signals: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl Compiler {
+    fn fresh(&self) -> String {
+        format!("{}_{}", self.name, self.outputs.len())
+    }
+
+    fn signal(&mut self, signal: &str) -> String {
+        if !self.signals.iter().any(|s| s == signal) {
+            self.signals.push(signal.to_string());
+        }
+        signal.to_string()
+    }
+
+    fn term(&mut self, term: &Term) -> String {
+        match term {
+            Term::Const(c) => c.clone(),
+            Term::Signal(s) => self.signal(s),
+            Term::Neg(t) => format!("-({})", self.term(t)),
+            Term::Binary(lhs, op, rhs) => format!("({} {} {})", self.term(lhs), op, self.term(rhs)),
+        }
+    }
+
+    fn output(&mut self, definition: String) -> String {
+        let name = self.fresh();
+        self.outputs.push(format!("output {}: Bool := {}", name, definition));
+        name
+    }
+
+    /// Defines a stream referring to its own previous value, `{prev}` in the definition stands for the access.
+    fn recursive(&mut self, definition: &str, default: bool) -> String {
+        let prev = format!("{}.offset(by: -1).defaults(to: {})", self.fresh(), default);
+        self.output(definition.replace("{prev}", &prev))
+    }
+
+    fn is_generated(&self, stream: &str) -> bool {
+        stream == self.name
+            || stream
+                .strip_prefix(self.name.as_str())
+                .and_then(|rest| rest.strip_prefix('_'))
+                .map_or(false, |ix| ix.parse::<usize>().is_ok())
+    }
+
+    /// Returns an expression computing the formula.
+    fn compile(&mut self, formula: &Formula) -> Result<String, String> {
+        Ok(match formula {
+            Formula::Bool(b) => b.to_string(),
+            Formula::Atom(signal) => self.signal(signal),
+            Formula::Predicate(lhs, op, rhs) => format!("({} {} {})", self.term(lhs), op.symbol(), self.term(rhs)),
+            Formula::Not(inner) => format!("!({})", self.compile(inner)?),
+            Formula::And(lhs, rhs) => format!("({} && {})", self.compile(lhs)?, self.compile(rhs)?),
+            Formula::Or(lhs, rhs) => format!("({} || {})", self.compile(lhs)?, self.compile(rhs)?),
+            Formula::Implies(lhs, rhs) => format!("(!({}) || {})", self.compile(lhs)?, self.compile(rhs)?),
+            Formula::Iff(lhs, rhs) => format!("({} == {})", self.compile(lhs)?, self.compile(rhs)?),
+            Formula::Yesterday(inner) => {
+                let inner = self.compile(inner)?;
+                // Offsets can only be applied to streams.
+                let stream =
+                    if self.signals.contains(&inner) || self.is_generated(&inner) { inner } else { self.output(inner) };
+                format!("{}.offset(by: -1).defaults(to: false)", stream)
+            }
+            Formula::Once(inner) => {
+                let inner = self.compile(inner)?;
+                self.recursive(&format!("{} || {{prev}}", inner), false)
+            }
+            Formula::Historically(inner) => {
+                let inner = self.compile(inner)?;
+                self.recursive(&format!("{} && {{prev}}", inner), true)
+            }
+            Formula::Since(lhs, rhs) => {
+                let (lhs, rhs) = (self.compile(lhs)?, self.compile(rhs)?);
+                self.recursive(&format!("{} || ({} && {{prev}})", rhs, lhs), false)
+            }
+            Formula::Globally(..) | Formula::Eventually(..) => {
+                return Err(String::from("future-time operators are not supported in past-time LTL formulas"))
+            }
+        })
+    }
+}
+
+/**
+Translates a past-time LTL formula into output stream and trigger definitions.
+
+The formula is computed by an output stream called `name`, auxiliary streams are prefixed with `name`.
+A trigger reports each step in which the formula does not hold.
+Returns an error message if the formula is malformed or contains future-time operators.
+*/
+pub fn translate(formula: &str, name: &str) -> Result<String, String> {
+    let parsed = parse(formula)?;
+    let mut compiler = Compiler { name: name.to_string(), signals: vec![], outputs: vec![] };
+    let expr = compiler.compile(&parsed)?;
+    if let Some(clash) = compiler.signals.iter().find(|s| compiler.is_generated(s)) {
+        return Err(format!("the stream name `{}` clashes with the generated streams `{}_*`", clash, name));
+    }
+
+    let mut definitions = format!("// Generated from the past-time LTL formula `{}`.\n", formula.trim());
+    for output in &compiler.outputs {
+        definitions += output;
+        definitions.push('\n');
+    }
+    definitions += &format!("output {}: Bool := {}\n", name, expr);
+    definitions += &format!("trigger !{} \"violation of {}\"\n", name, formula.trim());
+    Ok(definitions)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrontendConfig;
+
+    const SPEC: &str = "input request: Bool\ninput grant: Bool\ninput altitude: Float64\ninput gear: Bool\n";
+
+    fn check(formula: &str) -> String {
+        let definitions = translate(formula, "ltl").unwrap_or_else(|e| panic!("{}: {}", formula, e));
+        let spec = format!("{}\n{}", SPEC, definitions);
+        if let Err(e) = crate::parse("ltl", &spec, FrontendConfig::default()) {
+            panic!("{}\n{}", spec, e);
+        }
+        definitions
+    }
+
+    #[test]
+    fn propositional() {
+        let definitions = check("altitude < 200.0 -> gear");
+        assert!(definitions.contains("output ltl: Bool := (!((altitude < 200.0)) || gear)"));
+        assert!(definitions.contains("trigger !ltl \"violation of altitude < 200.0 -> gear\""));
+    }
+
+    #[test]
+    fn past_operators() {
+        let definitions = check("grant -> Y (!grant S request)");
+        assert!(definitions
+            .contains("output ltl_0: Bool := request || (!(grant) && ltl_0.offset(by: -1).defaults(to: false))"));
+        assert!(definitions.contains("output ltl: Bool := (!(grant) || ltl_0.offset(by: -1).defaults(to: false))"));
+        check("H (grant -> O request)");
+        check("Y Y request && historically (altitude >= 0.0)");
+    }
+
+    #[test]
+    fn errors() {
+        assert!(translate("G[0,1] request", "ltl").is_err());
+        assert!(translate("request S", "ltl").is_err());
+        assert!(translate("O ltl_0", "ltl").is_err());
+    }
+}
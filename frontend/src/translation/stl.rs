@@ -0,0 +1,214 @@
+//! Translation of Signal Temporal Logic (STL) formulas into RTLola specifications.
+//!
+//! The accepted formulas consist of
+//! * predicates over signals, e.g., `speed - limit <= 2.5`, using `+`, `-`, `*`, and `/`,
+//! * the boolean connectives `!`, `&&`, `||`, `->`, and `<->`,
+//! * the bounded temporal operators `G[a,b]` (globally) and `F[a,b]` (eventually), bounds are given in seconds.
+//!
+//! Each signal becomes a `Float64` input stream and each subformula a periodic output stream sampled at a fixed
+//! frequency, i.e., the translation implements the discrete-time semantics of STL.
+//! Future operators cannot be decided before their time bound has passed, so the verdict of a formula with
+//! horizon `h` for time `t` is computed at time `t + h`.
+//! A trigger reports every violation of the formula.
+
+use super::float_literal;
+use super::formula::{parse, Formula, Term};
+
+/// Generates one output stream per subformula.
+struct Compiler {
+    frequency: f64,
+    inputs: Vec<String>,
+    outputs: Vec<String>,
+}
+
+impl Compiler {
+    /// Converts a duration into a number of samples.
+    fn samples(&self, seconds: f64) -> Result<u64, String> {
+        let samples = seconds * self.frequency;
+        if (samples - samples.round()).abs() > 1e-6 {
+            return Err(format!(
+                "the time bound {}s is not a multiple of the sampling period {}s",
+                seconds,
+                1.0 / self.frequency
+            ));
+        }
+        Ok(samples.round() as u64)
+    }
+
+    fn output(&mut self, expression: String) -> String {
+        let name = format!("stl_{}", self.outputs.len());
+        self.outputs.push(format!("output {}: Bool @ {}Hz := {}", name, self.frequency, expression));
+        name
+    }
+
+    fn term(&mut self, term: &Term) -> String {
+        match term {
+            Term::Const(c) => float_literal(c.parse().expect("checked when parsing")),
+            Term::Signal(s) => {
+                if !self.inputs.contains(s) {
+                    self.inputs.push(s.clone());
+                }
+                format!("{}.hold().defaults(to: 0.0)", s)
+            }
+            Term::Neg(t) => format!("-({})", self.term(t)),
+            Term::Binary(lhs, op, rhs) => format!("({} {} {})", self.term(lhs), op, self.term(rhs)),
+        }
+    }
+
+    /// Returns the stream computing the formula and the number of samples its verdicts are delayed by.
+    fn compile(&mut self, formula: &Formula) -> Result<(String, u64), String> {
+        Ok(match formula {
+            Formula::Bool(b) => (self.output(b.to_string()), 0),
+            Formula::Atom(signal) => {
+                return Err(format!("the signal `{}` is real-valued and cannot be used as proposition", signal))
+            }
+            Formula::Predicate(lhs, op, rhs) => {
+                let expr = format!("{} {} {}", self.term(lhs), op.symbol(), self.term(rhs));
+                (self.output(expr), 0)
+            }
+            Formula::Not(inner) => {
+                let (inner, delay) = self.compile(inner)?;
+                (self.output(format!("!{}", inner)), delay)
+            }
+            Formula::And(lhs, rhs) => self.binary(lhs, "&&", rhs)?,
+            Formula::Or(lhs, rhs) => self.binary(lhs, "||", rhs)?,
+            Formula::Implies(lhs, rhs) => self.binary(lhs, "->", rhs)?,
+            Formula::Iff(lhs, rhs) => self.binary(lhs, "==", rhs)?,
+            Formula::Globally(a, b, inner) => self.temporal(*a, *b, "forall", inner)?,
+            Formula::Eventually(a, b, inner) => self.temporal(*a, *b, "exists", inner)?,
+            Formula::Yesterday(_) | Formula::Once(_) | Formula::Historically(_) | Formula::Since(_, _) => {
+                return Err(String::from("past-time operators are not supported in STL formulas"))
+            }
+        })
+    }
+
+    fn binary(&mut self, lhs: &Formula, op: &str, rhs: &Formula) -> Result<(String, u64), String> {
+        let (lhs, lhs_delay) = self.compile(lhs)?;
+        let (rhs, rhs_delay) = self.compile(rhs)?;
+        // Align both operands to the verdicts of the same point in time.
+        let delay = lhs_delay.max(rhs_delay);
+        let align = |stream: String, d: u64| {
+            if d == delay {
+                stream
+            } else {
+                format!("{}.offset(by: -{}).defaults(to: false)", stream, delay - d)
+            }
+        };
+        let expr = if op == "->" {
+            format!("!({}) || {}", align(lhs, lhs_delay), align(rhs, rhs_delay))
+        } else {
+            format!("{} {} {}", align(lhs, lhs_delay), op, align(rhs, rhs_delay))
+        };
+        Ok((self.output(expr), delay))
+    }
+
+    fn temporal(&mut self, a: f64, b: f64, op: &str, inner: &Formula) -> Result<(String, u64), String> {
+        let (inner, delay) = self.compile(inner)?;
+        let (a, b) = (self.samples(a)?, self.samples(b)?);
+        if a == b {
+            // A punctual interval only shifts the verdicts.
+            return Ok((inner, delay + b));
+        }
+        // The window ending at the current sample covers the samples of the interval [t + a, t + b].
+        let duration = (b - a + 1) as f64 / self.frequency;
+        let expr = format!("{}.aggregate(over: {}s, using: {})", inner, duration, op);
+        Ok((self.output(expr), delay + b))
+    }
+}
+
+/**
+Translates an STL formula into an RTLola specification sampling the signals with the given frequency in Hz.
+
+The specification contains a trigger reporting each violation of the formula.
+Returns an error message if the formula is malformed or its time bounds are not multiples of the sampling period.
+*/
+pub fn translate(formula: &str, frequency: f64) -> Result<String, String> {
+    if !(frequency.is_finite() && frequency > 0.0) {
+        return Err(format!("invalid sampling frequency {}Hz", frequency));
+    }
+    let parsed = parse(formula)?;
+    let mut compiler = Compiler { frequency, inputs: vec![], outputs: vec![] };
+    let (top, delay) = compiler.compile(&parsed)?;
+    if let Some(clash) = compiler.inputs.iter().find(|i| i.starts_with("stl_")) {
+        return Err(format!("the signal name `{}` is reserved for generated streams", clash));
+    }
+
+    let mut spec = format!("// Generated from the STL formula `{}` sampled at {}Hz.\n\n", formula.trim(), frequency);
+    for input in &compiler.inputs {
+        spec += &format!("input {}: Float64\n", input);
+    }
+    spec.push('\n');
+    for output in &compiler.outputs {
+        spec += output;
+        spec.push('\n');
+    }
+    if delay == 0 {
+        spec += &format!("\ntrigger !{} \"violation of {}\"\n", top, formula.trim());
+    } else {
+        // The verdicts of the first samples refer to points in time before the start of the monitor.
+        spec += &format!(
+            "output stl_samples: UInt64 @ {}Hz := stl_samples.offset(by: -1).defaults(to: 0) + 1\n",
+            frequency
+        );
+        spec += &format!(
+            "\ntrigger stl_samples > {} && !{} \"violation of {} {}s ago\"\n",
+            delay,
+            top,
+            formula.trim(),
+            delay as f64 / frequency
+        );
+    }
+    Ok(spec)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FrontendConfig;
+
+    fn check(formula: &str) -> String {
+        let spec = translate(formula, 10.0).unwrap_or_else(|e| panic!("{}: {}", formula, e));
+        if let Err(e) = crate::parse("stl", &spec, FrontendConfig::default()) {
+            panic!("{}\n{}", spec, e);
+        }
+        spec
+    }
+
+    #[test]
+    fn predicates() {
+        let spec = check("speed < 30 && (altitude - ground) / 2 >= 0.5");
+        assert!(spec.contains("input speed: Float64"));
+        assert!(spec.contains("input altitude: Float64"));
+        assert!(spec.contains("input ground: Float64"));
+        assert!(spec.contains("trigger !stl_2"));
+    }
+
+    #[test]
+    fn temporal_operators() {
+        let spec = check("G[0,5] (speed > 10 -> F[1,2](brake == 1))");
+        assert!(spec.contains("stl_1.aggregate(over: 1.1s, using: exists)"));
+        assert!(spec.contains("using: forall"));
+        assert!(spec.contains("trigger stl_samples > 70"));
+        check("always[0, 0.5] !(x > 1 || y <= -2.5) <-> eventually[0.1,0.1] true");
+    }
+
+    #[test]
+    fn alignment() {
+        let spec = check("a > 0 && F[0,1] b > 0");
+        assert!(spec.contains("stl_0.offset(by: -10).defaults(to: false) && stl_2"));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(translate("G (x > 0)", 10.0).is_err());
+        assert!(translate("G[0,0.15] (x > 0)", 10.0).is_err());
+        assert!(translate("F[2,1] (x > 0)", 10.0).is_err());
+        assert!(translate("x > 0 U y > 0", 10.0).is_err());
+        assert!(translate("x > ", 10.0).is_err());
+        assert!(translate("x > 0 y", 10.0).is_err());
+        assert!(translate("stl_0 > 0", 10.0).is_err());
+        assert!(translate("x > 0", 0.0).is_err());
+        assert!(translate("G[0,1] x", 10.0).is_err());
+        assert!(translate("O (x > 0)", 10.0).is_err());
+    }
+}
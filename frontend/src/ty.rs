@@ -62,8 +62,14 @@ pub enum ValueTy {
     String,
     /// A byte string type.
     Bytes,
+    /// A span of time, written as a numeric literal with a time unit, e.g., `250ms`.
+    Duration,
     /// A tuple of value types.
     Tuple(Vec<ValueTy>),
+    /// An array of the given number of values of the same type, e.g., `[Float64; 4]`.
+    Array(Box<ValueTy>, usize),
+    /// A user-declared enumeration with its name and the names of its variants, e.g., `enum Mode { Init, Armed }`.
+    Enum(String, Vec<String>),
     /// an optional value type, e.g., resulting from accessing a stream with offset -1
     Option(Box<ValueTy>),
     /// Used during type inference
@@ -223,6 +229,9 @@ impl StreamTy {
             Activation::Conjunction(args) | Activation::Disjunction(args) => {
                 args.sort();
                 args.dedup();
+                if args.len() == 1 {
+                    *ac = args.pop().expect("the condition has one argument");
+                }
             }
             _ => {}
         }
@@ -316,6 +325,7 @@ lazy_static! {
         ("Float64", &ValueTy::Float(F64)),
         ("String", &ValueTy::String),
         ("Bytes", &ValueTy::Bytes),
+        ("Duration", &ValueTy::Duration),
     ];
     static ref REDUCED_PRIMITIVE_TYPES: Vec<(&'static str, &'static ValueTy)> = vec![
         ("Bool", &ValueTy::Bool),
@@ -324,6 +334,7 @@ lazy_static! {
         ("Float64", &ValueTy::Float(F64)),
         ("String", &ValueTy::String),
         ("Bytes", &ValueTy::Bytes),
+        ("Duration", &ValueTy::Duration),
     ];
     static ref PRIMITIVE_TYPES_ALIASES: Vec<(&'static str, &'static ValueTy)> =
         vec![("Int", &ValueTy::Int(I64)), ("UInt", &ValueTy::UInt(U64)), ("Float", &ValueTy::Float(F64)),];
@@ -348,8 +359,14 @@ impl ValueTy {
         use self::ValueTy::*;
         match constraint {
             Unconstrained => true,
-            Comparable | Equatable => self.is_primitive(),
+            Comparable => self.is_primitive(),
+            Equatable => self.is_primitive() || matches!(self, Enum(..)),
+            Hashable => match self {
+                String | Bytes => true,
+                _ => self.satisfies(&Integer),
+            },
             Numeric => self.satisfies(&Integer) || self.satisfies(&FloatingPoint),
+            Additive => self.satisfies(&Numeric) || *self == Duration,
             FloatingPoint => match self {
                 Float(_) => true,
                 _ => false,
@@ -372,7 +389,7 @@ impl ValueTy {
         match self {
             Error => true,
             Tuple(args) => args.iter().any(|el| el.is_error()),
-            Option(ty) => ty.is_error(),
+            Array(ty, _) | Option(ty) => ty.is_error(),
             _ => false,
         }
     }
@@ -385,7 +402,7 @@ impl ValueTy {
     pub fn is_primitive(&self) -> bool {
         use self::ValueTy::*;
         match self {
-            Bool | Int(_) | UInt(_) | Float(_) | String | Bytes => true,
+            Bool | Int(_) | UInt(_) | Float(_) | String | Bytes | Duration => true,
             _ => false,
         }
     }
@@ -395,7 +412,8 @@ impl ValueTy {
         match self {
             &ValueTy::Param(id, _) => ValueTy::Infer(infer_vars[id as usize]),
             ValueTy::Option(t) => ValueTy::Option(t.replace_params(infer_vars).into()),
-            ValueTy::Infer(_) | ValueTy::Constr(_) => self.clone(),
+            ValueTy::Array(t, size) => ValueTy::Array(t.replace_params(infer_vars).into(), *size),
+            ValueTy::Infer(_) | ValueTy::Constr(_) | ValueTy::Enum(..) => self.clone(),
             _ if self.is_primitive() => self.clone(),
             _ => unreachable!("replace_param for {}", self),
         }
@@ -406,7 +424,8 @@ impl ValueTy {
         match self {
             &ValueTy::Param(id, _) => generics[id as usize].clone(),
             ValueTy::Option(t) => ValueTy::Option(t.replace_params_with_ty(generics).into()),
-            ValueTy::Infer(_) | ValueTy::Constr(_) => self.clone(),
+            ValueTy::Array(t, size) => ValueTy::Array(t.replace_params_with_ty(generics).into(), *size),
+            ValueTy::Infer(_) | ValueTy::Constr(_) | ValueTy::Enum(..) => self.clone(),
             _ if self.is_primitive() => self.clone(),
             _ => unreachable!("replace_param for {}", self),
         }
@@ -417,17 +436,94 @@ impl ValueTy {
         match &self {
             ValueTy::Tuple(t) => ValueTy::Tuple(t.iter().map(|el| el.replace_constr()).collect()),
             ValueTy::Option(ty) => ValueTy::Option(ty.replace_constr().into()),
+            ValueTy::Array(ty, size) => ValueTy::Array(ty.replace_constr().into(), *size),
             ValueTy::Constr(c) => match c.has_default() {
                 Some(d) => d,
                 None => ValueTy::Error,
             },
-            ValueTy::Param(_, _) => self.clone(),
+            ValueTy::Param(_, _) | ValueTy::Enum(..) => self.clone(),
             _ if self.is_primitive() => self.clone(),
             _ => unreachable!("cannot replace_constr for {}", self),
         }
     }
 }
 
+impl IntTy {
+    /// Returns the smallest and the largest value of the type.
+    pub fn bounds(self) -> (i128, i128) {
+        let bits = match self {
+            I8 => 8,
+            I16 => 16,
+            I32 => 32,
+            I64 => 64,
+        };
+        (-(1 << (bits - 1)), (1 << (bits - 1)) - 1)
+    }
+}
+
+impl UIntTy {
+    /// Returns the smallest and the largest value of the type.
+    pub fn bounds(self) -> (i128, i128) {
+        let bits = match self {
+            U8 => 8,
+            U16 => 16,
+            U32 => 32,
+            U64 => 64,
+        };
+        (0, (1 << bits) - 1)
+    }
+}
+
+impl FloatTy {
+    /// Returns the largest finite value of the type.
+    pub fn max(self) -> f64 {
+        match self {
+            F16 => 65504.0,
+            F32 => f64::from(f32::MAX),
+            F64 => f64::MAX,
+        }
+    }
+}
+
+/**
+A number in a checked cast, e.g., `x as Int8`, which has no value if the target type cannot represent the number.
+
+Floats cast to integer types are truncated towards zero, and integers have to lie within the bounds of the integer type.
+Numbers cast to float types may lose precision, but finite numbers must not exceed the largest value of the float type in magnitude.
+*/
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    /**
+    A signed or unsigned integer.
+    */
+    Integer(i128),
+    /**
+    A floating-point number.
+    */
+    Float(f64),
+}
+
+impl Number {
+    /// Casts the number to the integer type with the given bounds, see `IntTy::bounds` and `UIntTy::bounds`.
+    pub fn to_integer(self, (min, max): (i128, i128)) -> Option<i128> {
+        let int = match self {
+            Number::Integer(int) => int,
+            Number::Float(float) if float.is_finite() => float.trunc() as i128,
+            Number::Float(_) => return None,
+        };
+        Some(int).filter(|int| min <= *int && *int <= max)
+    }
+
+    /// Casts the number to the float type.
+    pub fn to_float(self, ty: FloatTy) -> Option<f64> {
+        let float = match self {
+            Number::Integer(int) => int as f64,
+            Number::Float(float) => float,
+        };
+        Some(float).filter(|float| !float.is_finite() || float.abs() <= ty.max())
+    }
+}
+
 impl std::fmt::Display for ValueTy {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
         match self {
@@ -445,7 +541,10 @@ impl std::fmt::Display for ValueTy {
             ValueTy::Float(F64) => write!(f, "Float64"),
             ValueTy::String => write!(f, "String"),
             ValueTy::Bytes => write!(f, "Bytes"),
+            ValueTy::Duration => write!(f, "Duration"),
             ValueTy::Option(ty) => write!(f, "{}?", ty),
+            ValueTy::Array(ty, size) => write!(f, "[{}; {}]", ty, size),
+            ValueTy::Enum(name, _) => write!(f, "{}", name),
             ValueTy::Tuple(inner) => {
                 let joined: Vec<String> = inner.iter().map(|e| format!("{}", e)).collect();
                 write!(f, "({})", joined.join(", "))
@@ -503,10 +602,14 @@ pub enum TypeConstraint {
     Integer,
     /// integer + floating point
     Numeric,
+    /// numeric + duration, i.e., types that implement `+` and `-`
+    Additive,
     /// Types that can be compared, i.e., implement `==`
     Equatable,
     /// Types that can be ordered, i.e., implement `<`, `>`,
     Comparable,
+    /// Types with a stable byte representation for checksums, i.e., integers, strings, and bytes
+    Hashable,
     /**
     The type is unconstrained.
     */
@@ -517,7 +620,7 @@ impl TypeConstraint {
     pub(crate) fn has_default(&self) -> Option<ValueTy> {
         use self::TypeConstraint::*;
         match self {
-            Integer | SignedInteger | Numeric => Some(ValueTy::Int(I64)),
+            Integer | SignedInteger | Numeric | Additive => Some(ValueTy::Int(I64)),
             UnsignedInteger => Some(ValueTy::UInt(U64)),
             FloatingPoint => Some(ValueTy::Float(F64)),
             _ => None,
@@ -534,7 +637,13 @@ impl TypeConstraint {
         }
         assert!(self < other);
         match other {
-            Unconstrained | Comparable | Equatable | Numeric => Some(self),
+            Unconstrained | Comparable | Equatable | Numeric | Additive => Some(self),
+            Hashable => match self {
+                FloatingPoint => None,
+                Numeric | Additive => Some(&Integer),
+                Equatable | Comparable => Some(other),
+                _ => Some(self),
+            },
             Integer => match self {
                 FloatingPoint => None,
                 _ => Some(self),
@@ -553,8 +662,10 @@ impl std::fmt::Display for TypeConstraint {
             Integer => write!(f, "integer"),
             FloatingPoint => write!(f, "floating point"),
             Numeric => write!(f, "numeric type"),
+            Additive => write!(f, "additive type"),
             Equatable => write!(f, "equatable type"),
             Comparable => write!(f, "comparable type"),
+            Hashable => write!(f, "hashable type"),
             Unconstrained => write!(f, "unconstrained type"),
         }
     }
@@ -572,4 +683,38 @@ mod tests {
         let c = Freq::new(UOM_Frequency::new::<hertz>(Rational::from_i64(2).unwrap()));
         assert_eq!(a.conjunction(&b), c)
     }
+
+    #[test]
+    fn test_hashable_conjunction() {
+        use self::TypeConstraint::*;
+        assert_eq!(Numeric.conjunction(&Hashable), Some(&Integer));
+        assert_eq!(Hashable.conjunction(&Equatable), Some(&Hashable));
+        assert_eq!(Hashable.conjunction(&UnsignedInteger), Some(&UnsignedInteger));
+        assert_eq!(FloatingPoint.conjunction(&Hashable), None);
+    }
+
+    #[test]
+    fn test_additive_conjunction() {
+        use self::TypeConstraint::*;
+        assert_eq!(Additive.conjunction(&Comparable), Some(&Additive));
+        assert_eq!(FloatingPoint.conjunction(&Additive), Some(&FloatingPoint));
+        assert_eq!(Additive.conjunction(&Hashable), Some(&Integer));
+        assert!(ValueTy::Duration.satisfies(&Additive));
+        assert!(!ValueTy::Duration.satisfies(&Numeric));
+    }
+
+    #[test]
+    fn test_checked_cast() {
+        assert_eq!(Number::Integer(127).to_integer(I8.bounds()), Some(127));
+        assert_eq!(Number::Integer(128).to_integer(I8.bounds()), None);
+        assert_eq!(Number::Integer(-1).to_integer(U64.bounds()), None);
+        assert_eq!(Number::Integer(i128::from(u64::MAX)).to_integer(U64.bounds()), Some(i128::from(u64::MAX)));
+        assert_eq!(Number::Float(-2.7).to_integer(I16.bounds()), Some(-2));
+        assert_eq!(Number::Float(9.3e18).to_integer(I64.bounds()), None);
+        assert_eq!(Number::Float(f64::NAN).to_integer(U8.bounds()), None);
+        assert_eq!(Number::Integer(70_000).to_float(F16), None);
+        assert_eq!(Number::Float(1e300).to_float(F32), None);
+        assert_eq!(Number::Float(1e300).to_float(F64), Some(1e300));
+        assert_eq!(Number::Float(f64::INFINITY).to_float(F32), Some(f64::INFINITY));
+    }
 }
@@ -10,19 +10,19 @@ use super::unifier::{InferError, UnifiableTy, Unifier, ValueUnifier, ValueVar};
 use super::{Activation, Freq, StreamTy, TypeConstraint, ValueTy};
 use crate::analysis::naming::{Declaration, DeclarationTable};
 use crate::ast::{
-    BinOp, Constant, Expression, ExpressionKind, FunctionName, Input, Literal, Offset, Output, RTLolaAst,
-    StreamAccessKind, Trigger, Type, TypeKind, WindowOperation,
+    BinOp, Constant, Escalation, Expression, ExpressionKind, FunctionName, Input, Literal, Offset, Output, RTLolaAst,
+    StreamAccessKind, TimeUnit, Trigger, Type, TypeKind, WindowOperation,
 };
 use crate::parse::{NodeId, Span};
-use crate::reporting::{Handler, LabeledSpan};
-use crate::stdlib;
-use crate::stdlib::{FuncDecl, MethodLookup};
+use crate::reporting::{Code, Handler, LabeledSpan, Level};
+use crate::stdlib::{FuncDecl, FunctionRegistry, MethodLookup};
 use log::{debug, trace};
 use num::traits::ops::inv::Inv;
 use num::Signed;
 use std::{
     collections::{HashMap, HashSet},
     rc::Rc,
+    str::FromStr,
 };
 use uom::si::frequency::hertz;
 use uom::si::rational64::Frequency as UOM_Frequency;
@@ -31,6 +31,7 @@ use uom::si::time::second;
 pub(crate) struct TypeAnalysis<'a, 'b, 'c> {
     handler: &'b Handler,
     declarations: &'c mut DeclarationTable,
+    functions: &'a FunctionRegistry,
     method_lookup: MethodLookup<'a>,
     unifier: ValueUnifier<ValueTy>,
     /// maps `NodeId`'s to the variables used in `unifier`
@@ -53,6 +54,11 @@ impl TypeTable {
         &self.value_tt[&nid]
     }
 
+    /// Returns the value type of a node, if it has one, e.g., unlike the duration of a window.
+    pub(crate) fn find_value_type(&self, nid: NodeId) -> Option<&ValueTy> {
+        self.value_tt.get(&nid)
+    }
+
     pub(crate) fn get_stream_type(&self, nid: NodeId) -> &StreamTy {
         &self.stream_tt[&nid]
     }
@@ -67,10 +73,15 @@ impl TypeTable {
 }
 
 impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
-    pub(crate) fn new(handler: &'b Handler, declarations: &'c mut DeclarationTable) -> TypeAnalysis<'a, 'b, 'c> {
+    pub(crate) fn new(
+        handler: &'b Handler,
+        declarations: &'c mut DeclarationTable,
+        functions: &'a FunctionRegistry,
+    ) -> TypeAnalysis<'a, 'b, 'c> {
         TypeAnalysis {
             handler,
             declarations,
+            functions,
             method_lookup: MethodLookup::new(),
             unifier: ValueUnifier::new(),
             value_vars: HashMap::new(),
@@ -124,15 +135,15 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
     }
 
     fn imports(&mut self, spec: &'a RTLolaAst) {
-        stdlib::import_implicit_method(&mut self.method_lookup);
+        self.functions.import_methods(None, &mut self.method_lookup);
         for import in &spec.imports {
-            match import.name.name.as_str() {
-                "math" => stdlib::import_math_method(&mut self.method_lookup),
-                "regex" => stdlib::import_regex_method(&mut self.method_lookup),
-                n => self.handler.error_with_span(
+            let n = import.name.name.as_str();
+            if !self.functions.import_methods(Some(n), &mut self.method_lookup) {
+                self.handler.error_with_span(
+                    Code::UnresolvedImport,
                     &format!("unresolved import `{}`", n),
                     LabeledSpan::new(import.name.span, &format!("no `{}` in the root", n), true),
-                ),
+                )
             }
         }
     }
@@ -157,6 +168,12 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             });
         }
 
+        for constant in &spec.constants {
+            self.infer_constant_expression(constant).unwrap_or_else(|_| {
+                debug!("type inference failed for {}", constant);
+            });
+        }
+
         for output in &spec.outputs {
             self.infer_output_expression(output).unwrap_or_else(|_| {
                 debug!("type inference failed for {}", output);
@@ -201,6 +218,28 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 .unwrap_or_else(|_| {
                     debug!("stream type check for {}", trigger);
                 });
+            if let Some(escalation) = &trigger.escalation {
+                self.check_escalation(escalation);
+            }
+        }
+    }
+
+    /// Checks that a trigger escalates after a positive number of violations within a positive duration.
+    fn check_escalation(&self, escalation: &Escalation) {
+        if !escalation.after.parse_numeric::<u32>().map_or(false, |after| after > 0) {
+            self.handler.error_with_span(
+                Code::InvalidEscalation,
+                "invalid number of violations",
+                LabeledSpan::new(escalation.after.span, "expected a positive integer", true),
+            );
+        }
+        let duration = escalation.within.parse_duration().map(|d| d.get::<second>());
+        if !duration.map_or(false, |d| d.is_positive()) {
+            self.handler.error_with_span(
+                Code::InvalidEscalation,
+                "invalid escalation duration",
+                LabeledSpan::new(escalation.within.span, "expected a positive duration, e.g., `10s`", true),
+            );
         }
     }
 
@@ -220,14 +259,14 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             let ty_var = self.value_vars[&ast_ty.id];
             self.unifier.unify_var_var(var, ty_var).expect("cannot fail as `var` is a fresh var");
         }
+        Ok(())
+    }
 
-        // generate constraint from literal
-        if let Some(constraint) = self.get_constraint_for_literal(&constant.literal) {
-            self.unifier.unify_var_ty(var, constraint).map_err(|err| self.handle_error(err, constant.literal.span))?;
-            Ok(())
-        } else {
-            Err(())
-        }
+    /// Infers the type of the expression defining the constant, which may refer to constants declared later.
+    fn infer_constant_expression(&mut self, constant: &'a Constant) -> Result<(), ()> {
+        trace!("infer type for {}", constant);
+        let var = self.value_vars[&constant.id];
+        self.infer_expression(&constant.value, Some(ValueTy::Infer(var)))
     }
 
     fn infer_input(&mut self, input: &'a Input) -> Result<(), ()> {
@@ -307,8 +346,8 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         } else {
             // stream type should be inferred
             let mut inner = Vec::new();
-            if let Some(termination) = output.termination.as_ref() {
-                self.infer_stream_ty_from_expression(termination, &mut inner);
+            for clause in Self::template_clauses(output) {
+                self.infer_stream_ty_from_expression(clause, &mut inner);
             }
             self.infer_stream_ty_from_expression(&output.expression, &mut inner);
             self.stream_ty.insert(output.id, StreamTy::Infer(inner));
@@ -333,8 +372,11 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         // stream type
                         inner.push(output.id)
                     }
-                    Declaration::Param(_) => {}
-                    Declaration::Type(_) | Declaration::Func(_) | Declaration::ParamOut(_) => {
+                    // the streams of a binding are those of its expression, see `Let`
+                    Declaration::Param(_) | Declaration::Let(_) => {}
+                    // accessing a parameterized stream without parameters is reported by the value type inference
+                    Declaration::ParamOut(_) => {}
+                    Declaration::Type(_) | Declaration::Func(_) => {
                         unreachable!("ensured by naming analysis {:?}", decl)
                     }
                 }
@@ -373,7 +415,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     self.infer_stream_ty_from_expression(&param, inner);
                 }
             }
-            Tuple(expressions) => {
+            Tuple(expressions) | Array(expressions) => {
                 for element in expressions {
                     self.infer_stream_ty_from_expression(&element, inner);
                 }
@@ -381,6 +423,13 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             Field(base, _) => {
                 self.infer_stream_ty_from_expression(&base, inner);
             }
+            Cast(expr, _) => {
+                self.infer_stream_ty_from_expression(&expr, inner);
+            }
+            Let(binding, body) => {
+                self.infer_stream_ty_from_expression(&binding.expr, inner);
+                self.infer_stream_ty_from_expression(&body, inner);
+            }
             ParenthesizedExpression(_, expr, _) => {
                 self.infer_stream_ty_from_expression(&expr, inner);
             }
@@ -477,13 +526,28 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             unreachable!("stream types should be concrete at this point");
         }
 
-        if let Some(termination) = output.termination.as_ref() {
-            self.check_output_clock_expression(&self.stream_ty[&output.id].clone(), termination)?;
+        for clause in Self::template_clauses(output) {
+            self.check_output_clock_expression(&self.stream_ty[&output.id].clone(), clause)?;
         }
 
         self.check_output_clock_expression(&self.stream_ty[&output.id].clone(), &output.expression)
     }
 
+    /// Returns the expressions of the template and the termination condition of a parameterized output, which are evaluated with its expression.
+    fn template_clauses(output: &'a Output) -> Vec<&'a Expression> {
+        let mut clauses = Vec::new();
+        if let Some(template) = &output.template_spec {
+            if let Some(invoke) = &template.inv {
+                clauses.push(&invoke.target);
+                clauses.extend(&invoke.condition);
+            }
+            clauses.extend(template.ext.as_ref().map(|extend| &extend.target));
+            clauses.extend(template.ter.as_ref().map(|terminate| &terminate.target));
+        }
+        clauses.extend(&output.termination);
+        clauses
+    }
+
     fn check_output_clock_expression(&mut self, stream_ty: &StreamTy, expr: &'a Expression) -> Result<(), ()> {
         use crate::ast::ExpressionKind::*;
 
@@ -504,15 +568,18 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         let out_ty = &self.stream_ty[&output.id];
                         self.check_stream_types_are_compatible(stream_ty, out_ty, expr.span)?;
                     }
-                    Declaration::Param(_) => {}
-                    Declaration::Type(_) | Declaration::Func(_) | Declaration::ParamOut(_) => {
+                    // the streams of a binding are those of its expression, see `Let`
+                    Declaration::Param(_) | Declaration::Let(_) => {}
+                    // accessing a parameterized stream without parameters is reported by the value type inference
+                    Declaration::ParamOut(_) => {}
+                    Declaration::Type(_) | Declaration::Func(_) => {
                         unreachable!("ensured by naming analysis {:?}", decl)
                     }
                 }
             }
             Offset(inner, offset) => self.check_offset_expr(stream_ty, expr.span, inner, offset)?,
             SlidingWindowAggregation { expr: inner, duration, aggregation, .. } => {
-                self.check_sliding_window_expression(stream_ty, expr.span, inner, duration, *aggregation)?;
+                self.check_sliding_window_expression(stream_ty, expr.span, inner, duration, aggregation)?;
             }
             Ite(cond, left, right) => {
                 self.check_output_clock_expression(stream_ty, cond)?;
@@ -531,9 +598,9 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 self.check_output_clock_expression(stream_ty, right)?;
             }
             StreamAccess(inner, access_type) => {
-                let inner_ty = match self.declarations[&inner.id].clone() {
-                    Declaration::In(input) => &self.stream_ty[&input.id],
-                    Declaration::Out(output) => &self.stream_ty[&output.id],
+                let (inner_ty, inner_span) = match self.declarations[&inner.id].clone() {
+                    Declaration::In(input) => (&self.stream_ty[&input.id], input.name.span),
+                    Declaration::Out(output) => (&self.stream_ty[&output.id], output.name.span),
                     _ => unreachable!(),
                 };
 
@@ -547,26 +614,28 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 match (stream_ty, inner_ty) {
                     (StreamTy::Event(left), StreamTy::Event(right)) => {
                         if left.implies_valid(&right) {
-                            self.handler.warn_with_span(
-                                &format!("Unnecessary `.{}`", function),
-                                LabeledSpan::new(expr.span, &format!("remove `.{}`", function), true),
-                            )
+                            self.warn_unnecessary_access(expr.span, function)
                         }
                     }
                     (StreamTy::RealTime(left), StreamTy::RealTime(right)) => {
                         if right.is_multiple_of(&left) == Ok(true) {
-                            self.handler.warn_with_span(
-                                &format!("Unnecessary `.{}`", function),
-                                LabeledSpan::new(expr.span, &format!("remove `.{}`", function), true),
-                            )
+                            self.warn_unnecessary_access(expr.span, function)
                         }
                     }
                     _ => {
                         if let StreamAccessKind::Optional = access_type {
-                            self.handler.error_with_span(
+                            let mut builder = self.handler.build_error_with_span(
+                                Code::InvalidSampleAndHold,
                                 "`get()` can be only used when both streams are event-based or periodic",
                                 LabeledSpan::new(expr.span, "`get()` not possible here", true),
-                            )
+                            );
+                            builder.add_span_with_label(inner_span, "stream declared here", false);
+                            builder.add_note(&format!(
+                                "the accessed stream is `{}` but the accessing stream is `{}`",
+                                inner_ty, stream_ty
+                            ));
+                            builder.add_help("use `.hold()` to access the latest value of the stream");
+                            builder.emit();
                         }
                     }
                 }
@@ -583,7 +652,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     self.check_output_clock_expression(stream_ty, param)?;
                 }
             }
-            Tuple(expressions) => {
+            Tuple(expressions) | Array(expressions) => {
                 for expression in expressions {
                     self.check_output_clock_expression(stream_ty, expression)?;
                 }
@@ -591,6 +660,13 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             Field(base, _) => {
                 self.check_output_clock_expression(stream_ty, base)?;
             }
+            Cast(expr, _) => {
+                self.check_output_clock_expression(stream_ty, expr)?;
+            }
+            Let(binding, body) => {
+                self.check_output_clock_expression(stream_ty, &binding.expr)?;
+                self.check_output_clock_expression(stream_ty, body)?;
+            }
             ParenthesizedExpression(_, expr, _) => {
                 self.check_output_clock_expression(stream_ty, expr)?;
             }
@@ -601,6 +677,17 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         Ok(())
     }
 
+    /// Warns about a `.hold()` or `.get()` access where the accessed stream is always available.
+    fn warn_unnecessary_access(&self, span: Span, function: &str) {
+        let mut builder = self.handler.build_warning_with_span(
+            Code::UnnecessarySampleAndHold,
+            &format!("Unnecessary `.{}`", function),
+            LabeledSpan::new(span, &format!("remove `.{}`", function), true),
+        );
+        builder.add_note("the accessed stream has a value whenever this stream is evaluated");
+        builder.emit();
+    }
+
     fn check_offset_expr(
         &mut self,
         stream_ty: &StreamTy,
@@ -644,13 +731,14 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         span: Span,
         _expr: &'a Expression,
         duration: &'a Expression,
-        _window_op: WindowOperation,
+        _window_op: &WindowOperation,
     ) -> Result<(), ()> {
         // the stream variable has to be real-time
         let _f = match stream_ty {
             StreamTy::RealTime(f) => f,
             _ => {
                 self.handler.error_with_span(
+                    Code::SlidingWindowInEventStream,
                     "Sliding windows are only allowed in real-time streams",
                     LabeledSpan::new(span, "unexpected sliding window", true),
                 );
@@ -661,7 +749,11 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         // check duration
         let _duration = match duration.parse_duration() {
             Err(message) => {
-                self.handler.error_with_span("expected duration", LabeledSpan::new(duration.span, &message, true));
+                self.handler.error_with_span(
+                    Code::ExpectedDuration,
+                    "expected duration",
+                    LabeledSpan::new(duration.span, &message, true),
+                );
                 return Err(());
             }
             Ok(d) => d,
@@ -679,13 +771,18 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             Ok(true) => Ok(()),
             Ok(false) => {
                 self.handler.error_with_span(
+                    Code::IncompatibleStreamTypes,
                     "stream types are incompatible",
                     LabeledSpan::new(span, &format!("expected `{}`, found `{}`", left, right), true),
                 );
                 Err(())
             }
             Err(s) => {
-                self.handler.error_with_span("stream types are incompatible", LabeledSpan::new(span, s.as_ref(), true));
+                self.handler.error_with_span(
+                    Code::IncompatibleStreamTypes,
+                    "stream types are incompatible",
+                    LabeledSpan::new(span, s.as_ref(), true),
+                );
                 Err(())
             }
         }
@@ -697,7 +794,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 ExpressionKind::Lit(_) => match expr.parse_freqspec() {
                     Ok(f) => Ok((Some(Freq::new(f)), None)),
                     Err(s) => {
-                        self.handler.error_with_span(&s, LabeledSpan::new(expr.span, "", true));
+                        self.handler.error_with_span(Code::InvalidFrequency, &s, LabeledSpan::new(expr.span, "", true));
                         Err(())
                     }
                 },
@@ -718,6 +815,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 Declaration::Out(output) => {
                     if output.id == out_id {
                         self.handler.error_with_span(
+                            Code::SelfReferenceInActivation,
                             "self-references are not allowed in activation conditions",
                             LabeledSpan::new(expr.span, "", true),
                         );
@@ -727,7 +825,11 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     }
                 }
                 _ => {
-                    self.handler.error_with_span("expected stream", LabeledSpan::new(expr.span, "", true));
+                    self.handler.error_with_span(
+                        Code::ExpectedStream,
+                        "expected stream",
+                        LabeledSpan::new(expr.span, "", true),
+                    );
                     Err(())
                 }
             },
@@ -745,6 +847,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     BinOp::Or => Ok(Activation::Disjunction(vec![left, right])),
                     _ => {
                         self.handler.error_with_span(
+                            Code::InvalidActivationCondition,
                             "only disjunctions and conjunctions are allowed for activation conditions",
                             LabeledSpan::new(expr.span, "", true),
                         );
@@ -755,6 +858,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
             ExpressionKind::ParenthesizedExpression(_, expr, _) => self.parse_activation_condition(out_id, expr),
             _ => {
                 self.handler.error_with_span(
+                    Code::InvalidActivationCondition,
                     "only variables, disjunctions, and conjunctions are allowed for activation conditions",
                     LabeledSpan::new(expr.span, "", true),
                 );
@@ -784,6 +888,13 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 // ?ty_var = `ty`
                 self.unifier.unify_var_ty(ty_var, ty).expect("cannot fail as `ty_var` is fresh");
             }
+            TypeKind::Array(ty, size) => {
+                let inner = self.infer_type(ty)?;
+                // ?ty_var = `[ty; size]`
+                self.unifier
+                    .unify_var_ty(ty_var, ValueTy::Array(ValueTy::Infer(inner).into(), *size))
+                    .expect("cannot fail as `ty_var` is fresh");
+            }
             TypeKind::Optional(ty) => {
                 self.infer_type(ty)?;
                 let inner = self.value_vars[&ty.id];
@@ -801,58 +912,25 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         let out_var = self.value_vars[&output.id];
 
         // check template specification
-        /*if let Some(template_spec) = &output.template_spec {
+        if let Some(template_spec) = &output.template_spec {
             if let Some(invoke) = &template_spec.inv {
-                self.infer_expression(&invoke.target, None, StreamVarOrTy::Ty(StreamTy::new(TimingInfo::Event)))?;
-                let inv_var = self.value_vars[&invoke.target.id];
-                if output.params.len() == 1 {
-                    // ?param_var = ?inv_bar
-                    let param_var = self.value_vars[&output.params[0].id];
-                    self.unifier
-                        .unify_var_ty(inv_var, ValueTy::Infer(param_var))
-                        .map_err(|err| self.handle_error(err, invoke.target.span))?;
-                } else {
-                    let target_ty = ValueTy::Tuple(
-                        output
-                            .params
-                            .iter()
-                            .map(|p| {
-                                let param_var = self.value_vars[&p.id];
-                                ValueTy::Infer(param_var)
-                            })
-                            .collect(),
-                    );
-                    self.unifier
-                        .unify_var_ty(inv_var, target_ty)
-                        .map_err(|err| self.handle_error(err, invoke.target.span))?;
-                }
-
-                // check that condition is boolean
+                // the invoke expression computes the parameters, a tuple if there are several
+                let target = match output.params.as_slice() {
+                    [param] => ValueTy::Infer(self.value_vars[&param.id]),
+                    params => ValueTy::Tuple(params.iter().map(|p| ValueTy::Infer(self.value_vars[&p.id])).collect()),
+                };
+                self.infer_expression(&invoke.target, Some(target))?;
                 if let Some(cond) = &invoke.condition {
-                    self.infer_expression(
-                        cond,
-                        Some(ValueTy::Bool),
-                        StreamVarOrTy::Ty(StreamTy::new(TimingInfo::Event)),
-                    )?;
+                    self.infer_expression(cond, Some(ValueTy::Bool))?;
                 }
             }
             if let Some(extend) = &template_spec.ext {
-                // check that condition is boolean
-                self.infer_expression(
-                    &extend.target,
-                    Some(ValueTy::Bool),
-                    StreamVarOrTy::Ty(StreamTy::new(TimingInfo::Event)),
-                )?;
+                self.infer_expression(&extend.target, Some(ValueTy::Bool))?;
             }
             if let Some(terminate) = &template_spec.ter {
-                // check that condition is boolean
-                self.infer_expression(
-                    &terminate.target,
-                    Some(ValueTy::Bool),
-                    StreamVarOrTy::Ty(StreamTy::new(TimingInfo::Event)),
-                )?;
+                self.infer_expression(&terminate.target, Some(ValueTy::Bool))?;
             }
-        }*/
+        }
 
         if let Some(terminate) = output.termination.as_ref() {
             // check that condition is boolean
@@ -883,15 +961,32 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         Some(match &lit.kind {
             Str(_) | RawStr(_) => ValueTy::String,
             Bool(_) => ValueTy::Bool,
+            // the naming analysis resolves the enumeration of the variant
+            Variant(..) => match self.declarations.get(&lit.id)? {
+                Declaration::Type(ty) => ty.as_ref().clone(),
+                _ => unreachable!("variants are resolved to enumerations"),
+            },
             Numeric(val, unit) => {
                 if let Some(unit) = unit {
-                    self.handler.error_with_span(
-                        &format!("unexpected unit `{}`", unit),
-                        LabeledSpan::new(lit.span, "remove unit from numeric value", true),
-                    );
-                    return None;
+                    if TimeUnit::from_str(unit).is_err() {
+                        self.handler.error_with_span(
+                            Code::UnexpectedUnit,
+                            &format!("unexpected unit `{}`", unit),
+                            LabeledSpan::new(lit.span, "remove unit from numeric value", true),
+                        );
+                        return None;
+                    }
+                    // numeric values with a time unit denote durations
+                    if let Err(message) = lit.parse_duration() {
+                        self.handler.error_with_span(
+                            Code::ExpectedDuration,
+                            &message,
+                            LabeledSpan::new(lit.span, "invalid duration", true),
+                        );
+                        return None;
+                    }
+                    return Some(ValueTy::Duration);
                 }
-                assert!(unit.is_none());
                 if val.contains('.') {
                     // Floating Point
                     ValueTy::Constr(TypeConstraint::FloatingPoint)
@@ -947,14 +1042,28 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         let param_var = self.value_vars[&param.id];
                         self.unifier.unify_var_var(var, param_var).map_err(|err| self.handle_error(err, expr.span))?;
                     }
-                    Declaration::Type(_) | Declaration::Func(_) | Declaration::ParamOut(_) => {
+                    Declaration::Let(binding) => {
+                        // value type of the bound expression, which is inferred before the body
+                        let bound_var = self.value_vars[&binding.expr.id];
+                        self.unifier.unify_var_var(var, bound_var).map_err(|err| self.handle_error(err, expr.span))?;
+                    }
+                    Declaration::ParamOut(output) => {
+                        // only instances have values, so offsets and windows cannot refer to the stream itself
+                        self.handler.error_with_span(
+                            Code::MissingParameters,
+                            &format!("the parameterized stream `{}` is accessed without parameters", output.name),
+                            LabeledSpan::new(expr.span, "expected an instance, e.g., `NAME(ARGUMENTS)`", true),
+                        );
+                        return Err(());
+                    }
+                    Declaration::Type(_) | Declaration::Func(_) => {
                         unreachable!("ensured by naming analysis {:?}", decl)
                     }
                 }
             }
             Offset(inner, offset) => self.infer_offset_expr(var, expr.span, inner, offset)?,
             SlidingWindowAggregation { expr: inner, duration, wait, aggregation } => {
-                self.infer_sliding_window_expression(var, expr.span, inner, duration, *wait, *aggregation)?;
+                self.infer_sliding_window_expression(var, expr.id, expr.span, inner, duration, *wait, aggregation)?;
             }
             Ite(cond, left, right) => {
                 // value type constraints
@@ -970,7 +1079,9 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 self.infer_function_application(expr.id, var, expr.span, &op.get_func_decl(), &[], &[appl])?;
             }
             Binary(op, left, right) => {
-                self.infer_function_application(expr.id, var, expr.span, &op.get_func_decl(), &[], &[left, right])?;
+                if !self.infer_elementwise(expr, var, *op, left, right)? {
+                    self.infer_function_application(expr.id, var, expr.span, &op.get_func_decl(), &[], &[left, right])?;
+                }
             }
             Default(left, right) => {
                 self.infer_expression(left, Some(ValueTy::Option(ValueTy::Infer(var).into())))?;
@@ -981,7 +1092,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 let target_var = self.unifier.new_var();
                 self.unifier
                     .unify_var_ty(var, ValueTy::Option(ValueTy::Infer(target_var).into()))
-                    .map_err(|err| self.handle_error(err, inner.span))?;
+                    .map_err(|err| self.handle_missing_default(err, inner.span, expr.span))?;
 
                 // the stream type of `inner` is unconstrained
                 self.infer_expression(inner, Some(ValueTy::Infer(target_var)))?;
@@ -1008,6 +1119,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 };
                 if params.len() != fun_decl.parameters.len() {
                     self.handler.error_with_span(
+                        Code::WrongNumberOfArguments,
                         &format!(
                             "this function takes {} parameters but {} parameters were supplied",
                             fun_decl.parameters.len(),
@@ -1064,13 +1176,30 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
 
                         self.declarations.insert(expr.id, Declaration::Func(Rc::new(fun_decl.clone())));
                     } else {
-                        self.handler.error_with_span(
+                        let mut builder = self.handler.build_error_with_span(
+                            Code::UnknownMethod,
                             &format!("unknown method `{}`", name),
                             LabeledSpan::new(expr.span, &format!("no method `{}` for `{}`", name, inferred), true),
                         );
+                        // the method may exist for the type the constraint defaults to, e.g., `abs` for `Int64`
+                        if let ValueTy::Constr(constraint) = &inferred {
+                            let output = self.unannotated_output(base);
+                            if let (Some(ty), Some(output)) = (constraint.has_default(), output) {
+                                if self.method_lookup.get(&ty, &name).is_some() {
+                                    let end = output.name.span.end;
+                                    builder.add_suggestion(
+                                        &format!("annotate the type of `{}`", output.name.name),
+                                        Span { start: end, end },
+                                        &format!(": {}", ty),
+                                    );
+                                }
+                            }
+                        }
+                        builder.emit();
                     }
                 } else {
                     self.handler.error_with_span(
+                        Code::CannotInferType,
                         &format!("could not determine type of `{}`", base),
                         LabeledSpan::new(base.span, "consider giving a type annotation", true),
                     );
@@ -1089,6 +1218,17 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     .unify_var_ty(var, ValueTy::Tuple(tuples))
                     .map_err(|err| self.handle_error(err, expr.span))?;
             }
+            Array(elements) => {
+                // all elements have the same type
+                let element_var = self.unifier.new_var();
+                for element in elements {
+                    self.infer_expression(element, Some(ValueTy::Infer(element_var)))?;
+                }
+                // ?var = [?element; n]
+                self.unifier
+                    .unify_var_ty(var, ValueTy::Array(ValueTy::Infer(element_var).into(), elements.len()))
+                    .map_err(|err| self.handle_error(err, expr.span))?;
+            }
             Field(base, ident) => {
                 // recursion
                 self.infer_expression(base, None)?;
@@ -1108,6 +1248,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         let num: usize = ident.name.parse::<usize>().expect("checked in AST verifier");
                         if num >= inner.len() {
                             self.handler.error_with_span(
+                                Code::TupleIndexOutOfRange,
                                 &format!("Try to access tuple at position {}", num),
                                 LabeledSpan::new(ident.span, "", true),
                             );
@@ -1118,8 +1259,22 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                             .unify_var_ty(var, inner[num].clone())
                             .map_err(|err| self.handle_error(err, expr.span))?;
                     }
+                    ValueTy::Array(element, size) => {
+                        let num: usize = ident.name.parse::<usize>().expect("checked in AST verifier");
+                        if num >= size {
+                            self.handler.error_with_span(
+                                Code::TupleIndexOutOfRange,
+                                &format!("Try to access array of size {} at position {}", size, num),
+                                LabeledSpan::new(ident.span, "", true),
+                            );
+                            return Err(());
+                        }
+                        // ?var = element
+                        self.unifier.unify_var_ty(var, *element).map_err(|err| self.handle_error(err, expr.span))?;
+                    }
                     _ => {
                         self.handler.error_with_span(
+                            Code::UnknownField,
                             &format!("Type `{}` has no field `{}`", infered, ident.name),
                             LabeledSpan::new(ident.span, "unknown field", true),
                         );
@@ -1127,6 +1282,27 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     }
                 }
             }
+            Cast(inner, ty) => {
+                // value type constraints
+                // * `inner` is a number
+                // * `ty` is a numeric type
+                // * `var` = `ty?` as the cast is checked
+                self.infer_expression(inner, Some(ValueTy::Constr(TypeConstraint::Numeric)))?;
+                let ty_var = self.infer_type(ty)?;
+                self.unifier
+                    .unify_var_ty(ty_var, ValueTy::Constr(TypeConstraint::Numeric))
+                    .map_err(|err| self.handle_error(err, ty.span))?;
+                self.unifier
+                    .unify_var_ty(var, ValueTy::Option(ValueTy::Infer(ty_var).into()))
+                    .map_err(|err| self.handle_error(err, expr.span))?;
+            }
+            Let(binding, body) => {
+                // value type constraints
+                // * the bound expression is inferred once, each use of the name has its type
+                // * `body` = `var`
+                self.infer_expression(&binding.expr, None)?;
+                self.infer_expression(body, Some(ValueTy::Infer(var)))?;
+            }
             ParenthesizedExpression(_, expr, _) => {
                 self.infer_expression(expr, target)?;
                 self.unifier
@@ -1160,7 +1336,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 } else {
                     self.unifier
                         .unify_var_ty(var, ValueTy::Option(ValueTy::Infer(target_var).into()))
-                        .map_err(|err| self.handle_error(err, span))?;
+                        .map_err(|err| self.handle_missing_default(err, span, span))?;
                 }
 
                 // As the recursion checks that the stream types match, any integer offset will match as well.
@@ -1172,7 +1348,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 if time.is_negative() {
                     self.unifier
                         .unify_var_ty(var, ValueTy::Option(ValueTy::Infer(target_value_var).into()))
-                        .map_err(|err| self.handle_error(err, span))?;
+                        .map_err(|err| self.handle_missing_default(err, span, span))?;
                 } else {
                     self.unifier.unify_var_var(var, target_value_var).map_err(|err| self.handle_error(err, span))?;
                 }
@@ -1184,18 +1360,24 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn infer_sliding_window_expression(
         &mut self,
         var: ValueVar,
+        node_id: NodeId,
         span: Span,
         expr: &'a Expression,
         duration: &'a Expression,
         wait: bool,
-        window_op: WindowOperation,
+        window_op: &WindowOperation,
     ) -> Result<(), ()> {
         // check duration
         if let Err(message) = duration.parse_duration() {
-            self.handler.error_with_span("expected duration", LabeledSpan::new(duration.span, &message, true));
+            self.handler.error_with_span(
+                Code::ExpectedDuration,
+                "expected duration",
+                LabeledSpan::new(duration.span, &message, true),
+            );
         }
 
         // value type depends on the aggregation function
@@ -1237,7 +1419,7 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         .map_err(|err| self.handle_error(err, span))
                 }
             }
-            Min | Max | Average => {
+            Min | Max | Average | Median | Percentile(_) => {
                 // The value type of the inner stream has to be numeric
                 self.infer_expression(expr, Some(ValueTy::Constr(TypeConstraint::Numeric)))?;
                 // resulting type depends on the inner type
@@ -1246,6 +1428,40 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     .unify_var_ty(var, ValueTy::Option(ValueTy::Infer(inner_var).into()))
                     .map_err(|err| self.handle_error(err, span))
             }
+            Custom(name) => {
+                let functions = self.functions;
+                let decl = match functions.aggregation(name) {
+                    Some(decl) => decl,
+                    None => {
+                        self.handler.error_with_span(
+                            Code::UnknownAggregation,
+                            &format!("unknown aggregation function {}", name),
+                            LabeledSpan::new(span, "neither a built-in nor a registered aggregation", true),
+                        );
+                        return Err(());
+                    }
+                };
+                // the aggregated values are the argument and the window is the result of the declaration, optional if wait
+                let result_var = self.unifier.new_var();
+                self.infer_function_application(node_id, result_var, span, decl, &[], &[expr])?;
+                let result_ty = ValueTy::Infer(result_var);
+                if wait {
+                    self.unifier
+                        .unify_var_ty(var, ValueTy::Option(result_ty.into()))
+                        .map_err(|err| self.handle_error(err, span))
+                } else {
+                    self.unifier.unify_var_ty(var, result_ty).map_err(|err| self.handle_error(err, span))
+                }
+            }
+            First | Last => {
+                // The value type of the inner stream is not restricted
+                self.infer_expression(expr, None)?;
+                // resulting type is the inner type, optional since the window may be empty
+                let inner_var = self.value_vars[&expr.id];
+                self.unifier
+                    .unify_var_ty(var, ValueTy::Option(ValueTy::Infer(inner_var).into()))
+                    .map_err(|err| self.handle_error(err, span))
+            }
             Disjunction | Conjunction => {
                 // The value type of the inner stream has to be boolean
                 self.infer_expression(expr, Some(ValueTy::Bool))?;
@@ -1275,6 +1491,62 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         }
     }
 
+    /// Infers arithmetic on arrays, which is applied elementwise, e.g., `[1.0, 2.0] + [3.0, 4.0]`.
+    /// Returns `false` if neither operand is known to be an array, so the operator is inferred as usual.
+    fn infer_elementwise(
+        &mut self,
+        expr: &'a Expression,
+        var: ValueVar,
+        op: BinOp,
+        left: &'a Expression,
+        right: &'a Expression,
+    ) -> Result<bool, ()> {
+        if !matches!(op, BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div) {
+            return Ok(false);
+        }
+        let size = match self.array_size(left).or_else(|| self.array_size(right)) {
+            Some(size) => size,
+            None => return Ok(false),
+        };
+        let element = self.unifier.new_var();
+        self.unifier
+            .unify_var_ty(element, ValueTy::Constr(TypeConstraint::Numeric))
+            .expect("cannot fail as `element` is fresh");
+        let array = ValueTy::Array(ValueTy::Infer(element).into(), size);
+        // ?var = ?left = ?right = [?element; size]
+        self.unifier.unify_var_ty(var, array.clone()).map_err(|err| self.handle_error(err, expr.span))?;
+        self.infer_expression(left, Some(array.clone()))?;
+        self.infer_expression(right, Some(array))?;
+        // the lowering looks up the operand type like for the generic operator
+        self.generic_function_vars.insert(expr.id, vec![var]);
+        Ok(true)
+    }
+
+    /// Returns the size of the array `expr` evaluates to, if this is known before inferring `expr`.
+    fn array_size(&mut self, expr: &Expression) -> Option<usize> {
+        use crate::ast::ExpressionKind::*;
+        let declaration = match &expr.kind {
+            Array(elements) => return Some(elements.len()),
+            Binary(op, inner, _) if matches!(op, BinOp::Add | BinOp::Sub | BinOp::Mul | BinOp::Div) => {
+                return self.array_size(inner)
+            }
+            ParenthesizedExpression(_, inner, _) | Default(_, inner) => return self.array_size(inner),
+            Ident(_) => match self.declarations.get(&expr.id)? {
+                Declaration::Const(constant) => constant.id,
+                Declaration::In(input) => input.id,
+                Declaration::Out(output) => output.id,
+                Declaration::Param(param) => param.id,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        let var = *self.value_vars.get(&declaration)?;
+        match self.unifier.get_normalized_type(var)? {
+            ValueTy::Array(_, size) => Some(size),
+            _ => None,
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     fn infer_function_application(
         &mut self,
@@ -1339,6 +1611,8 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                 constant,
                 self.unifier.get_normalized_type(self.value_vars[&constant.id]).unwrap()
             );
+            self.check_literal_sizes(&constant.value);
+            self.check_float_equality(&constant.value);
         }
         for input in &spec.inputs {
             debug!("{} has type {}", input, self.unifier.get_normalized_type(self.value_vars[&input.id]).unwrap());
@@ -1346,16 +1620,20 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
         for output in &spec.outputs {
             debug!("{} has type {}", output, self.unifier.get_normalized_type(self.value_vars[&output.id]).unwrap());
             self.check_literal_sizes(&output.expression);
+            self.check_float_equality(&output.expression);
+            self.check_widening_defaults(&output.expression);
         }
         for trigger in &spec.trigger {
             self.check_literal_sizes(&trigger.expression);
+            self.check_float_equality(&trigger.expression);
+            self.check_widening_defaults(&trigger.expression);
         }
     }
 
     /// Check if literals fit the infered bit-width
     fn check_literal_sizes(&mut self, expression: &Expression) {
         use crate::ast::LitKind::*;
-        use crate::ty::{IntTy, UIntTy};
+        use crate::ty::{FloatTy, IntTy, UIntTy};
         expression.iter().for_each(|e| {
             if let ExpressionKind::Lit(l) = &e.kind {
                 if let Numeric(val, unit) = &l.kind {
@@ -1367,94 +1645,241 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                         match self.get_type(e.id) {
                             ValueTy::Int(IntTy::I8) if val.parse::<i8>().is_err() => {
                                 self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
                                     "literal out of range for `Int8`",
                                     LabeledSpan::new(e.span, "", true),
                                 );
                             }
                             ValueTy::Int(IntTy::I16) if val.parse::<i16>().is_err() => {
                                 self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
                                     "literal out of range for `Int16`",
                                     LabeledSpan::new(e.span, "", true),
                                 );
                             }
                             ValueTy::Int(IntTy::I32) if val.parse::<i32>().is_err() => {
                                 self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
                                     "literal out of range for `Int32`",
                                     LabeledSpan::new(e.span, "", true),
                                 );
                             }
                             ValueTy::Int(IntTy::I64) if val.parse::<i64>().is_err() => {
                                 self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
                                     "literal out of range for `Int64`",
                                     LabeledSpan::new(e.span, "", true),
                                 );
                             }
                             ValueTy::UInt(UIntTy::U8) if val.parse::<u8>().is_err() => {
                                 self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
                                     "literal out of range for `UInt8`",
                                     LabeledSpan::new(e.span, "", true),
                                 );
                             }
                             ValueTy::UInt(UIntTy::U16) if val.parse::<u16>().is_err() => {
                                 self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
                                     "literal out of range for `UInt16`",
                                     LabeledSpan::new(e.span, "", true),
                                 );
                             }
                             ValueTy::UInt(UIntTy::U32) if val.parse::<u32>().is_err() => {
                                 self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
                                     "literal out of range for `UInt32`",
                                     LabeledSpan::new(e.span, "", true),
                                 );
                             }
                             ValueTy::UInt(UIntTy::U64) if val.parse::<u64>().is_err() => {
                                 self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
                                     "literal out of range for `UInt64`",
                                     LabeledSpan::new(e.span, "", true),
                                 );
                             }
                             _ => {}
                         }
+                    } else {
+                        // floating point literals too large for their type would be infinite
+                        match self.get_type(e.id) {
+                            ValueTy::Float(FloatTy::F32) if !val.parse::<f32>().map_or(false, f32::is_finite) => {
+                                self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
+                                    "literal out of range for `Float32`",
+                                    LabeledSpan::new(e.span, "", true),
+                                );
+                            }
+                            ValueTy::Float(FloatTy::F64) if !val.parse::<f64>().map_or(false, f64::is_finite) => {
+                                self.handler.error_with_span(
+                                    Code::LiteralOutOfRange,
+                                    "literal out of range for `Float64`",
+                                    LabeledSpan::new(e.span, "", true),
+                                );
+                            }
+                            _ => {}
+                        }
                     }
                 }
             }
         })
     }
 
+    /// Warns about comparisons of floating point values with `=` or `!=` and suggests comparing with a tolerance.
+    fn check_float_equality(&mut self, expression: &Expression) {
+        expression.iter().for_each(|e| {
+            let (comparison, left, right) = match &e.kind {
+                ExpressionKind::Binary(BinOp::Eq, left, right) => ("<", left, right),
+                ExpressionKind::Binary(BinOp::Ne, left, right) => (">=", left, right),
+                _ => return,
+            };
+            if let ValueTy::Float(_) = self.get_type(left.id) {
+                let mut builder = self.handler.build_warning_with_span(
+                    Code::FloatEquality,
+                    "floating point values are compared exactly",
+                    LabeledSpan::new(e.span, "sensitive to rounding errors", true),
+                );
+                let operand = |e: &Expression, nested: bool| match &e.kind {
+                    ExpressionKind::Ite(..) => format!("({})", e),
+                    ExpressionKind::Binary(..) | ExpressionKind::Unary(..) if nested => format!("({})", e),
+                    _ => e.to_string(),
+                };
+                builder.add_suggestion(
+                    "compare with a tolerance instead",
+                    e.span,
+                    &format!("abs({} - {}) {} 0.000001", operand(left, false), operand(right, true), comparison),
+                );
+                builder.emit();
+            }
+        })
+    }
+
+    /// Warns about defaults of stream accesses whose type is wider than the type of the accessed stream.
+    /// The value of the stream is converted implicitly in this case, which is easily overlooked.
+    fn check_widening_defaults(&mut self, expression: &Expression) {
+        use crate::ast::ExpressionKind::*;
+        expression.iter().for_each(|e| {
+            let (access, default) = match &e.kind {
+                Default(access, default) => (access, default),
+                _ => return,
+            };
+            let stream = match &access.kind {
+                Offset(stream, _) | StreamAccess(stream, _) => stream,
+                _ => return,
+            };
+            let declaration = match self.declarations.get(&stream.id) {
+                Some(Declaration::In(input)) => input.id,
+                Some(Declaration::Out(output)) => output.id,
+                _ => return,
+            };
+            let (from, to) = (self.get_type(declaration), self.get_type(e.id));
+            let widened = match (&from, &to) {
+                (ValueTy::Int(_), ValueTy::Int(_))
+                | (ValueTy::UInt(_), ValueTy::UInt(_))
+                | (ValueTy::Float(_), ValueTy::Float(_)) => from != to,
+                _ => false,
+            };
+            if !widened {
+                return;
+            }
+            let mut builder = self.handler.build_warning_with_span(
+                Code::WideningDefault,
+                &format!("the default implicitly converts `{}` from `{}` to `{}`", stream, from, to),
+                LabeledSpan::new(default.span, &format!("this default has type `{}`", to), true),
+            );
+            builder.add_span_with_label(stream.span, &format!("`{}` has type `{}`", stream, from), false);
+            if let Lit(_) = default.kind {
+                builder.add_suggestion("make the conversion explicit", e.span, &format!("cast({})", e));
+            }
+            builder.emit();
+        })
+    }
+
     fn handle_error(&mut self, mut err: InferError, span: Span) {
         err.normalize_types(&mut self.unifier);
         match err {
             InferError::ValueTypeMismatch(ty_l, ty_r) => {
                 self.handler.error_with_span(
+                    Code::TypeMismatch,
                     &format!("Type mismatch between `{}` and `{}`", ty_l, ty_r),
                     LabeledSpan::new(span, &format!("expected `{}`, found `{}`", ty_l, ty_r), true),
                 );
             }
             InferError::ConflictingConstraint(left, right) => {
                 self.handler.error_with_span(
+                    Code::ConflictingConstraints,
                     &format!("Conflicting constraints `{}` and `{}`", left, right),
                     LabeledSpan::new(span, &format!("no concrete type satisfies `{}` and `{}`", left, right), true),
                 );
             }
             InferError::CyclicDependency => {
                 self.handler.error_with_span(
+                    Code::CannotInferType,
                     "Cannot infer type",
                     LabeledSpan::new(span, "consider using a type annotation", true),
                 );
             }
             InferError::StreamTypeMismatch(ty_l, ty_r, hint) => {
                 let mut diagnostics = self.handler.build_error_with_span(
+                    Code::TypeMismatch,
                     &format!("Type mismatch between `{}` and `{}`", ty_l, ty_r),
                     LabeledSpan::new(span, &format!("expected `{}`, found `{}`", ty_l, ty_r), true),
                 );
                 if let Some(hint) = hint {
-                    diagnostics.add_labeled_span(LabeledSpan::new(span, &format!("hint: {}", hint), false))
+                    diagnostics.add_help(&hint)
                 }
                 diagnostics.emit();
             }
         }
     }
 
+    /// Reports that an optional value, e.g., of an offset access, is used where a value is expected.
+    /// If the expected type has an obvious default value, the diagnostic suggests appending it to the `access`.
+    fn handle_missing_default(&mut self, mut err: InferError, span: Span, access: Span) {
+        err.normalize_types(&mut self.unifier);
+        let expected = match &err {
+            // a mismatch between two optional values is an ordinary type error
+            InferError::ValueTypeMismatch(ValueTy::Option(_), _) | InferError::ValueTypeMismatch(ValueTy::Error, _) => {
+                return self.handle_error(err, span)
+            }
+            InferError::ValueTypeMismatch(ty_l, _) => ty_l.clone(),
+            _ => return self.handle_error(err, span),
+        };
+        let mut diagnostics = self.handler.build_error_with_span(
+            Code::TypeMismatch,
+            &format!("Type mismatch between `{}` and an optional value", expected),
+            LabeledSpan::new(span, &format!("expected `{}`, found an optional value", expected), true),
+        );
+        match default_literal(&expected) {
+            Some(literal) => diagnostics.add_suggestion(
+                "add a default value for the case that the stream has no value",
+                Span { start: access.end, end: access.end },
+                &format!(".defaults(to: {})", literal),
+            ),
+            None => diagnostics.add_help(
+                "add a default value with `.defaults(to: _)` or test for a value with `.is_some()` before using it",
+            ),
+        }
+        diagnostics.emit();
+    }
+
+    /// Returns the output stream without type annotation that determines the value type of `expr`, if any.
+    fn unannotated_output(&self, expr: &Expression) -> Option<Rc<Output>> {
+        use crate::ast::ExpressionKind::*;
+        match &expr.kind {
+            Ident(_) => match self.declarations.get(&expr.id) {
+                Some(Declaration::Out(output)) => match output.ty.kind {
+                    TypeKind::Inferred => Some(output.clone()),
+                    _ => None,
+                },
+                _ => None,
+            },
+            Offset(inner, _) | StreamAccess(inner, _) | Default(inner, _) => self.unannotated_output(inner),
+            _ => None,
+        }
+    }
+
     pub(crate) fn get_type(&mut self, id: NodeId) -> ValueTy {
         if let Some(&var) = self.value_vars.get(&id) {
             if let Some(t) = self.unifier.get_normalized_type(var) {
@@ -1522,7 +1947,13 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
                     }
                     StreamTy::Event(ac) => ac.clone(),
                     StreamTy::RealTime(_) => {
-                        self.handler.error("real-time streams cannot be used in activation conditions");
+                        self.handler
+                            .build_diagnostic(
+                                Code::InvalidActivationCondition,
+                                "real-time streams cannot be used in activation conditions",
+                                Level::Error,
+                            )
+                            .emit();
                         return false;
                     }
                 };
@@ -1557,6 +1988,18 @@ impl<'a, 'b, 'c> TypeAnalysis<'a, 'b, 'c> {
     }
 }
 
+/// The literal used to suggest a default value of the given type.
+fn default_literal(ty: &ValueTy) -> Option<&'static str> {
+    match ty {
+        ValueTy::Bool => Some("false"),
+        ValueTy::Int(_) | ValueTy::UInt(_) => Some("0"),
+        ValueTy::Float(_) => Some("0.0"),
+        ValueTy::String => Some("\"\""),
+        ValueTy::Constr(constraint) => constraint.has_default().and_then(|ty| default_literal(&ty)),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -1564,6 +2007,7 @@ mod tests {
     use crate::analysis::naming::*;
     use crate::parse::*;
     use crate::reporting::Handler;
+    use crate::stdlib::STANDARD_LIBRARY;
     use crate::ty::{FloatTy, IntTy, UIntTy};
     use crate::FrontendConfig;
     use num::rational::Rational64 as Rational;
@@ -1577,10 +2021,10 @@ mod tests {
             Err(e) => panic!("Spec {} cannot be parsed: {}.", spec, e),
             Ok(s) => s,
         };
-        let mut na = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut na = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = na.check(&spec);
         assert!(!handler.contains_error(), "Spec produces errors in naming analysis.");
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         type_analysis.check(&spec);
         handler.emitted_errors()
     }
@@ -1592,10 +2036,10 @@ mod tests {
             Err(e) => panic!("Spec {} cannot be parsed: {}.", spec, e),
             Ok(s) => s,
         };
-        let mut na = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut na = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = na.check(&spec);
         assert!(!handler.contains_error(), "Spec produces errors in naming analysis.");
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         type_analysis.check(&spec);
         handler.emitted_warnings()
     }
@@ -1608,10 +2052,10 @@ mod tests {
             Err(e) => panic!("Spec {} cannot be parsed: {}.", spec, e),
             Ok(s) => s,
         };
-        let mut na = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut na = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = na.check(&spec);
         assert!(!handler.contains_error(), "Spec produces errors in naming analysis.");
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         type_analysis.check(&spec);
         type_analysis.get_type(spec.outputs.last().expect("spec needs at least one output").id)
     }
@@ -1623,10 +2067,10 @@ mod tests {
             Err(e) => panic!("Spec {} cannot be parsed: {}.", spec, e),
             Ok(s) => s,
         };
-        let mut na = NamingAnalysis::new(&handler, FrontendConfig::default());
+        let mut na = NamingAnalysis::new(&handler, FrontendConfig::default(), &STANDARD_LIBRARY);
         let mut decl_table = na.check(&spec);
         assert!(!handler.contains_error(), "Spec produces errors in naming analysis.");
-        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table);
+        let mut type_analysis = TypeAnalysis::new(&handler, &mut decl_table, &STANDARD_LIBRARY);
         type_analysis.check(&spec);
         type_analysis.extract_type_table(&spec)
     }
@@ -1890,17 +2334,48 @@ mod tests {
     }
 
     #[test]
-    #[ignore] // paramertic streams need new design after syntax revision
+    fn test_stream_lookup_without_default() {
+        let spec = "input a: (UInt8, Bool)\n output b: (UInt8, Bool) := a[-1]";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_stream_lookup_unwrap_or() {
+        let spec = "input a: UInt8\n output b: UInt8 := a[-1].unwrap_or(3)";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::UInt(UIntTy::U8));
+    }
+
+    #[test]
+    fn test_stream_lookup_is_some() {
+        let spec = "input a: (UInt8, Bool)\n output b := a[-1].is_some() && is_some(a.offset(by: -2))";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Bool);
+    }
+
+    #[test]
+    fn test_is_some_of_total_value() {
+        let spec = "input a: UInt8\n output b := a.is_some()";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_option_type_annotation() {
+        let spec = "input a: UInt8\n output b: Option<UInt8> := a[-1]";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Option(ValueTy::UInt(UIntTy::U8).into()));
+    }
+
+    #[test]
     fn test_extend_type() {
-        let spec = "input in: Bool\n output a: Int8 { extend in } := 3";
+        let spec = "input in: Bool\n output a(p: Int8): Int8 { extend in } := 3";
         assert_eq!(0, num_type_errors(spec));
         assert_eq!(get_type(spec), ValueTy::Int(IntTy::I8));
     }
 
     #[test]
-    #[ignore] // paramertic streams need new design after syntax revision
     fn test_extend_type_faulty() {
-        let spec = "input in: Int8\n output a: Int8 { extend in } := 3";
+        let spec = "input in: Int8\n output a(p: Int8): Int8 { extend in } := 3";
         assert_eq!(1, num_type_errors(spec));
     }
 
@@ -1924,6 +2399,19 @@ mod tests {
         assert_eq!(1, num_type_errors(spec));
     }
 
+    #[test]
+    fn test_invoke_type() {
+        let spec = "input i: Int8, j: Bool\n output a(p, q): Int8 { invoke (i, j) if j } := p";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Int(IntTy::I8));
+    }
+
+    #[test]
+    fn test_invoke_type_faulty() {
+        let spec = "input i: Int8, j: Bool\n output a(p: Int8, q: Bool): Int8 { invoke (j, i) } := p";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
     #[test]
     fn test_param_spec() {
         let spec = "output a(p1: Int8): Int8 := 3 output b: Int8 := a(3)";
@@ -1951,6 +2439,16 @@ mod tests {
         assert_eq!(get_type(spec), ValueTy::Int(IntTy::I8));
     }
 
+    #[test]
+    fn test_param_without_arguments() {
+        let spec = "input i: Int8 output x(p: Int8) @i := p output y @ 1Hz := x.aggregate(over: 1s, using: count)";
+        assert_eq!(1, num_type_errors(spec));
+        assert_eq!(
+            1,
+            num_type_errors("input i: Int8 output x(p: Int8) @i := p output y := x.offset(by: -1).defaults(to: 0)")
+        );
+    }
+
     #[test]
     fn test_lookup_incomp() {
         let spec = "output a(p1: Int8): Int8 := 3\n output b: UInt8 := a(3)";
@@ -2015,6 +2513,72 @@ mod tests {
         assert_eq!(get_type(spec), ValueTy::Int(IntTy::I16));
     }
 
+    #[test]
+    fn test_array() {
+        let spec = "output out: [Float64; 2] := [1.0, 0.5]";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Array(ValueTy::Float(FloatTy::F64).into(), 2));
+    }
+
+    #[test]
+    fn test_array_faulty_element() {
+        let spec = "output out: [Int8; 2] := [1, false]";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_array_faulty_size() {
+        let spec = "output out: [Int8; 3] := [1, 2]";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_array_access() {
+        let spec = "input q: [Float32; 4]\noutput out: Float32 := q.3";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Float(FloatTy::F32));
+    }
+
+    #[test]
+    fn test_array_access_faulty_len() {
+        let spec = "input q: [Float32; 4]\noutput out: Float32 := q.4";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_array_elementwise() {
+        let spec = "input q: [Float64; 2]\noutput out := q + [1.0, 2.0] * q";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Array(ValueTy::Float(FloatTy::F64).into(), 2));
+    }
+
+    #[test]
+    fn test_array_elementwise_faulty_size() {
+        let spec = "input q: [Float64; 2]\noutput out := q - [1.0, 2.0, 3.0]";
+        assert_eq!(1, num_type_errors(spec));
+    }
+
+    #[test]
+    fn test_enum() {
+        let spec = "enum Mode { Init, Armed, Flying }\ninput m: Mode\noutput armed := m != Mode::Init";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Bool);
+    }
+
+    #[test]
+    fn test_enum_literal() {
+        let spec = "enum Mode { Init, Armed }\noutput out: Mode := Mode::Init";
+        assert_eq!(0, num_type_errors(spec));
+        assert_eq!(get_type(spec), ValueTy::Enum("Mode".into(), vec!["Init".into(), "Armed".into()]));
+    }
+
+    #[test]
+    fn test_enum_faulty() {
+        let spec = "enum Mode { Init }\nenum Phase { Init }\ninput m: Mode\noutput out := m == Phase::Init";
+        assert_eq!(1, num_type_errors(spec));
+        assert_eq!(1, num_type_errors("enum Mode { Init, Armed }\ninput m: Mode\noutput out := m < Mode::Armed"));
+    }
+
     #[test]
     fn test_tuple_of_tuples2() {
         let spec = "input in: (Int8, (UInt8, Bool))\noutput out: Bool := in[0].1.1";
@@ -2294,6 +2858,25 @@ mod tests {
         assert_eq!(1, num_type_warnings(spec));
     }
 
+    #[test]
+    fn test_warn_float_equality() {
+        assert_eq!(1, num_type_warnings("input a: Float64\noutput x := a = 0.5"));
+        assert_eq!(
+            1,
+            num_type_warnings("input a: Float32\ntrigger a != a.offset(by: -1).defaults(to: a) \"a changed\"")
+        );
+        assert_eq!(0, num_type_warnings("input a: Int64\noutput x := a = 5"));
+        assert_eq!(0, num_type_warnings("input a: Float64\noutput x := abs(a - 0.5) < 0.000001"));
+    }
+
+    #[test]
+    fn test_warn_widening_default() {
+        assert_eq!(1, num_type_warnings("input a: Int8\noutput x: Int64 := a.offset(by: -1).defaults(to: 0)"));
+        assert_eq!(1, num_type_warnings("input a: Float32\noutput x: Float64 @ 1Hz := a.hold().defaults(to: 0.0)"));
+        assert_eq!(0, num_type_warnings("input a: Int8\noutput x: Int8 := a.offset(by: -1).defaults(to: 0)"));
+        assert_eq!(0, num_type_warnings("input a: Int8\noutput x: Int64 := cast(a.offset(by: -1).defaults(to: 0))"));
+    }
+
     #[test]
     fn test_get_not_possible() {
         // it should not be possible to use get with RealTime and EventBased streams
@@ -171,9 +171,10 @@ impl UnifiableTy for ValueTy {
             },
             ValueTy::Tuple(t) => ValueTy::Tuple(t.iter().map(|el| el.normalize_ty(unifier)).collect()),
             ValueTy::Option(ty) => ValueTy::Option(Box::new(ty.normalize_ty(unifier))),
+            ValueTy::Array(ty, size) => ValueTy::Array(Box::new(ty.normalize_ty(unifier)), *size),
             _ if self.is_primitive() => self.clone(),
             ValueTy::Constr(_) => self.clone(),
-            ValueTy::Param(_, _) => self.clone(),
+            ValueTy::Param(_, _) | ValueTy::Enum(..) => self.clone(),
             _ => unreachable!("cannot normalize {}", self),
         }
     }
@@ -255,6 +256,9 @@ impl UnifiableTy for ValueTy {
                 }
             }
             (ValueTy::Option(l), ValueTy::Option(r)) => l.equal_to(unifier, r).map(|ty| ValueTy::Option(ty.into())),
+            (ValueTy::Array(l, l_size), ValueTy::Array(r, r_size)) if l_size == r_size => {
+                l.equal_to(unifier, r).map(|ty| ValueTy::Array(ty.into(), *l_size))
+            }
             (ValueTy::Tuple(l), ValueTy::Tuple(r)) => {
                 if l.len() != r.len() {
                     return None;
@@ -281,7 +285,7 @@ impl UnifiableTy for ValueTy {
         match self {
             ValueTy::Infer(t) => unifier.vars_equal(var, *t),
             ValueTy::Tuple(t) => t.iter().any(|e| e.contains_var(unifier, var)),
-            ValueTy::Option(t) => t.contains_var(unifier, var),
+            ValueTy::Array(t, _) | ValueTy::Option(t) => t.contains_var(unifier, var),
             _ => false,
         }
     }
@@ -0,0 +1,5 @@
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // only the server is needed, the generated client requires the 2021 prelude
+    tonic_build::configure().build_client(false).compile(&["proto/rtlola.proto"], &["proto"])?;
+    Ok(())
+}
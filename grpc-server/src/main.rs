@@ -0,0 +1,239 @@
+//! A gRPC server mode for the RTLola interpreter.
+//!
+//! The server offers a streaming RPC receiving events and sending verdicts as well as unary RPCs for loading
+//! specifications and querying statistics, see `proto/rtlola.proto`.
+
+use clap::{App, Arg};
+use ordered_float::NotNan;
+use proto::monitoring_server::{Monitoring, MonitoringServer};
+use proto::value::Kind;
+use rtlola_frontend::ir::{RTLolaIR, Type};
+use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_interpreter::{Config, EvalConfig, StateSlice, TimeRepresentation, Value};
+use std::pin::Pin;
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedSender};
+use tokio_stream::wrappers::UnboundedReceiverStream;
+use tokio_stream::Stream;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status, Streaming};
+
+mod proto {
+    tonic::include_proto!("rtlola");
+}
+
+const CONFIG: FrontendConfig =
+    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+
+#[derive(Debug, Default)]
+struct State {
+    ir: Option<RTLolaIR>,
+    stats: proto::Statistics,
+}
+
+#[derive(Debug, Default)]
+struct MonitoringService {
+    state: Arc<Mutex<State>>,
+}
+
+#[tonic::async_trait]
+impl Monitoring for MonitoringService {
+    type MonitorStream = Pin<Box<dyn Stream<Item = Result<proto::Verdict, Status>> + Send>>;
+
+    async fn load_spec(
+        &self,
+        request: Request<proto::Specification>,
+    ) -> Result<Response<proto::SpecificationInfo>, Status> {
+        let ir =
+            rtlola_frontend::parse("grpc", &request.into_inner().source, CONFIG).map_err(Status::invalid_argument)?;
+        let info = proto::SpecificationInfo {
+            inputs: ir.inputs.iter().map(|i| format!("{}: {}", i.name, i.ty)).collect(),
+            outputs: ir.outputs.iter().map(|o| format!("{}: {}", o.name, o.ty)).collect(),
+            triggers: ir.triggers.iter().map(|t| t.message.clone()).collect(),
+        };
+        self.state.lock().unwrap().ir = Some(ir);
+        Ok(Response::new(info))
+    }
+
+    async fn monitor(
+        &self,
+        request: Request<Streaming<proto::Event>>,
+    ) -> Result<Response<Self::MonitorStream>, Status> {
+        let ir = self.state.lock().unwrap().ir.clone();
+        let ir = ir.ok_or_else(|| Status::failed_precondition("no specification loaded"))?;
+        let mut events = request.into_inner();
+        let (event_tx, event_rx) = mpsc::channel();
+        let (verdict_tx, verdict_rx) = unbounded_channel();
+
+        // The monitor is not `Send`, so it lives in a dedicated thread for the duration of the session.
+        let state = self.state.clone();
+        let errors = verdict_tx.clone();
+        std::thread::spawn(move || run_monitor(ir, event_rx, verdict_tx, state));
+        tokio::spawn(async move {
+            loop {
+                match events.message().await {
+                    Ok(Some(event)) => {
+                        if event_tx.send(event).is_err() {
+                            break;
+                        }
+                    }
+                    Ok(None) => break,
+                    Err(status) => {
+                        let _ = errors.send(Err(status));
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Response::new(Box::pin(UnboundedReceiverStream::new(verdict_rx))))
+    }
+
+    async fn get_statistics(
+        &self,
+        _request: Request<proto::StatisticsRequest>,
+    ) -> Result<Response<proto::Statistics>, Status> {
+        Ok(Response::new(self.state.lock().unwrap().stats.clone()))
+    }
+}
+
+fn run_monitor(
+    ir: RTLolaIR,
+    events: mpsc::Receiver<proto::Event>,
+    verdicts: UnboundedSender<Result<proto::Verdict, Status>>,
+    state: Arc<Mutex<State>>,
+) {
+    let inputs: Vec<(String, Type)> = ir.inputs.iter().map(|i| (i.name.clone(), i.ty.clone())).collect();
+    let mut monitor = match Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir).into_monitor() {
+        Ok(monitor) => monitor,
+        Err(e) => {
+            let _ = verdicts.send(Err(Status::internal(e.to_string())));
+            return;
+        }
+    };
+    // the time of the last accepted event, as the monitor requires monotonic time
+    let mut last = Duration::default();
+    for event in events {
+        let values = inputs
+            .iter()
+            .map(|(name, ty)| event.values.get(name).map_or(Ok(Value::None), |v| to_value(v, ty)))
+            .collect::<Result<Vec<Value>, String>>();
+        let values = match values {
+            Ok(values) => values,
+            Err(e) => {
+                let _ = verdicts.send(Err(Status::invalid_argument(e)));
+                return;
+            }
+        };
+        if !event.time.is_finite() || event.time < 0.0 {
+            let _ = verdicts.send(Err(Status::invalid_argument(format!("invalid timestamp {}", event.time))));
+            return;
+        }
+        let ts = Duration::from_secs_f64(event.time);
+        if ts < last {
+            let msg = format!("event at {}s arrived after an event at {}s", event.time, last.as_secs_f64());
+            let _ = verdicts.send(Err(Status::invalid_argument(msg)));
+            return;
+        }
+        last = ts;
+        let update = monitor.accept_event(values, ts);
+
+        let mut state = state.lock().unwrap();
+        state.stats.events += 1;
+        for (ts, slice) in update.timed.into_iter().chain(std::iter::once((ts, update.event))) {
+            if slice.is_empty() {
+                continue;
+            }
+            let verdict = to_verdict(&monitor, ts, slice);
+            state.stats.verdicts += 1;
+            state.stats.triggers += verdict.triggers.len() as u64;
+            if verdicts.send(Ok(verdict)).is_err() {
+                return;
+            }
+        }
+    }
+}
+
+fn to_verdict(monitor: &rtlola_interpreter::Monitor, ts: Duration, slice: StateSlice) -> proto::Verdict {
+    let mut verdict = proto::Verdict { time: ts.as_secs_f64(), ..Default::default() };
+    for (out, v) in slice {
+        match monitor.trigger_message(out) {
+            Some(msg) => {
+                if let Value::Bool(true) = v {
+                    verdict.triggers.push(msg.to_string());
+                }
+            }
+            None => {
+                if let Some(v) = from_value(v) {
+                    verdict.outputs.insert(monitor.name_for_output(out).to_string(), v);
+                }
+            }
+        }
+    }
+    verdict
+}
+
+fn to_value(v: &proto::Value, ty: &Type) -> Result<Value, String> {
+    let mismatch = || format!("expected a value of type {}", ty);
+    Ok(match (&v.kind, ty) {
+        (None, _) => Value::None,
+        (Some(Kind::Bool(b)), Type::Bool) => Value::Bool(*b),
+        (Some(Kind::Signed(i)), Type::Int(_)) => Value::Signed(*i),
+        (Some(Kind::Unsigned(u)), Type::UInt(_)) => Value::Unsigned(*u),
+        (Some(Kind::Float(f)), Type::Float(_)) => {
+            Value::Float(NotNan::new(*f).map_err(|_| String::from("NaN is not a valid input value"))?)
+        }
+        (Some(Kind::Str(s)), Type::String) => Value::Str(s.clone().into_boxed_str()),
+        (Some(Kind::Bytes(b)), Type::Bytes) => Value::Bytes(b.clone().into_boxed_slice()),
+        (Some(Kind::Tuple(t)), Type::Tuple(tys)) if t.elements.len() == tys.len() => Value::Tuple(
+            t.elements.iter().zip(tys).map(|(v, ty)| to_value(v, ty)).collect::<Result<Vec<_>, _>>()?.into(),
+        ),
+        // arrays are sent as tuples of their elements, like they are stored
+        (Some(Kind::Tuple(t)), Type::Array(elem_ty, size)) if t.elements.len() == *size => {
+            Value::Tuple(t.elements.iter().map(|v| to_value(v, elem_ty)).collect::<Result<Vec<_>, _>>()?.into())
+        }
+        // values of enumerations are sent as the name of their variant
+        (Some(Kind::Str(s)), Type::Enum(_, variants)) => {
+            Value::Unsigned(variants.iter().position(|v| v == s).ok_or_else(mismatch)? as u64)
+        }
+        _ => return Err(mismatch()),
+    })
+}
+
+fn from_value(v: Value) -> Option<proto::Value> {
+    let kind = match v {
+        Value::None => return None,
+        Value::Bool(b) => Kind::Bool(b),
+        Value::Unsigned(u) => Kind::Unsigned(u),
+        Value::Signed(i) => Kind::Signed(i),
+        Value::Float(f) => Kind::Float(f.into_inner()),
+        Value::Str(s) => Kind::Str(s.into()),
+        Value::Bytes(b) => Kind::Bytes(b.into()),
+        Value::Tuple(vals) => Kind::Tuple(proto::Tuple {
+            elements: vals.into_vec().into_iter().map(|v| from_value(v).unwrap_or_default()).collect(),
+        }),
+    };
+    Some(proto::Value { kind: Some(kind) })
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = App::new("RTLola gRPC Server")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Serves the RTLola interpreter via gRPC.")
+        .arg(Arg::with_name("ADDR").help("The address to listen on").default_value("127.0.0.1:50051"))
+        .arg(Arg::with_name("SPEC").long("spec").takes_value(true).help("A specification to load on startup"))
+        .get_matches();
+
+    let service = MonitoringService::default();
+    if let Some(path) = matches.value_of("SPEC") {
+        let source = std::fs::read_to_string(path)?;
+        let ir = rtlola_frontend::parse(path, &source, CONFIG)?;
+        service.state.lock().unwrap().ir = Some(ir);
+    }
+
+    let addr = matches.value_of("ADDR").unwrap().parse()?;
+    Server::builder().add_service(MonitoringServer::new(service)).serve(addr).await?;
+    Ok(())
+}
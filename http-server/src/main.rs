@@ -0,0 +1,195 @@
+//! A simple HTTP mode for the RTLola interpreter.
+//!
+//! * `POST /events` accepts a JSON event `{"time": 1.5, "values": {"alt": 10.2}}` or an array of such events.
+//!   The response contains the trigger firings caused by the events; a batch with an invalid event is rejected as a whole.
+//! * `GET /verdicts` returns the latest trigger firings; `GET /verdicts?since=n` skips the first `n` firings.
+
+use clap::{App, Arg};
+use rtlola_frontend::ir::{RTLolaIR, Type};
+use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_interpreter::{Config, EvalConfig, Monitor, TimeRepresentation, Value};
+use serde_json::{json, Value as Json};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const CONFIG: FrontendConfig =
+    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+
+/// The number of trigger firings kept for `GET /verdicts`, older ones are dropped.
+const MAX_VERDICTS: usize = 10_000;
+
+struct Endpoint {
+    monitor: Monitor,
+    inputs: Vec<(String, Type)>,
+    /// The time of the last accepted event.
+    last: Duration,
+    /// The latest trigger firings, at most `MAX_VERDICTS`.
+    verdicts: VecDeque<Json>,
+    /// The number of trigger firings dropped from `verdicts`, so `since` keeps counting from the first firing.
+    dropped: usize,
+    /// The body of the current request, reused across requests.
+    body: Vec<u8>,
+}
+
+impl Endpoint {
+    fn new(ir: RTLolaIR) -> Result<Endpoint, String> {
+        let inputs = ir.inputs.iter().map(|i| (i.name.clone(), i.ty.clone())).collect();
+        let monitor =
+            Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir).into_monitor().map_err(|e| e.to_string())?;
+        Ok(Endpoint { monitor, inputs, last: Duration::default(), verdicts: VecDeque::new(), dropped: 0, body: vec![] })
+    }
+
+    fn handle(&mut self, request: &mut Request) -> (u16, Json) {
+        let url = request.url().to_string();
+        let (path, query) = match url.find('?') {
+            Some(ix) => (&url[..ix], &url[ix + 1..]),
+            None => (url.as_str(), ""),
+        };
+        let method = request.method().clone();
+        match (&method, path) {
+            (Method::Post, "/events") => {
+                let mut body = std::mem::take(&mut self.body);
+                body.clear();
+                if let Err(e) = request.as_reader().read_to_end(&mut body) {
+                    return (400, json!({ "error": e.to_string() }));
+                }
+                let result = self.accept(&body);
+                self.body = body;
+                match result {
+                    Ok(fired) => (200, Json::Array(fired)),
+                    Err(e) => (400, json!({ "error": e })),
+                }
+            }
+            (Method::Get, "/verdicts") => {
+                let since = query
+                    .split('&')
+                    .find_map(|kv| kv.strip_prefix("since="))
+                    .and_then(|n| n.parse::<usize>().ok())
+                    .unwrap_or(0);
+                (200, Json::Array(self.verdicts_since(since)))
+            }
+            _ => (404, json!({ "error": format!("no route for {} {}", method, path) })),
+        }
+    }
+
+    /// Returns the trigger firings after the first `since` ones, of which the dropped ones are left out.
+    fn verdicts_since(&self, since: usize) -> Vec<Json> {
+        self.verdicts.iter().skip(since.saturating_sub(self.dropped)).cloned().collect()
+    }
+
+    fn accept(&mut self, body: &[u8]) -> Result<Vec<Json>, String> {
+        // the body is parsed from its bytes, without copying it into a `String` first
+        let events = match serde_json::from_slice(body).map_err(|e| e.to_string())? {
+            Json::Array(events) => events,
+            event => vec![event],
+        };
+        // the whole batch is converted first, so the monitor sees none of its events if one is invalid
+        let mut last = self.last;
+        let events = events
+            .iter()
+            .map(|event| {
+                let time =
+                    event["time"].as_f64().filter(|t| t.is_finite() && *t >= 0.0).ok_or("missing or invalid time")?;
+                let ts = Duration::from_secs_f64(time);
+                if ts < last {
+                    return Err(format!("event at {}s arrived after an event at {}s", time, last.as_secs_f64()));
+                }
+                last = ts;
+                let values = self
+                    .inputs
+                    .iter()
+                    .map(|(name, ty)| {
+                        Value::from_json(&event["values"][name], ty).ok_or(format!("invalid value for `{}`", name))
+                    })
+                    .collect::<Result<Vec<Value>, String>>()?;
+                Ok((values, ts))
+            })
+            .collect::<Result<Vec<(Vec<Value>, Duration)>, String>>()?;
+        self.last = last;
+        let mut fired = vec![];
+        for (values, ts) in events {
+            let update = self.monitor.accept_event(values, ts);
+            for (ts, slice) in update.timed.into_iter().chain(std::iter::once((ts, update.event))) {
+                for (out, v) in slice {
+                    if let (Some(msg), Value::Bool(true)) = (self.monitor.trigger_message(out), v) {
+                        fired.push(json!({ "time": ts.as_secs_f64(), "trigger": msg }));
+                    }
+                }
+            }
+        }
+        self.verdicts.extend(fired.iter().cloned());
+        let excess = self.verdicts.len().saturating_sub(MAX_VERDICTS);
+        self.verdicts.drain(..excess);
+        self.dropped += excess;
+        Ok(fired)
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = App::new("RTLola HTTP Server")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Accepts JSON events via HTTP and reports trigger firings.")
+        .arg(Arg::with_name("SPEC").help("Sets the specification file to use").required(true))
+        .arg(Arg::with_name("ADDR").help("The address to listen on").default_value("127.0.0.1:8080"))
+        .get_matches();
+
+    let path = matches.value_of("SPEC").unwrap();
+    let ir = rtlola_frontend::parse(path, &std::fs::read_to_string(path)?, CONFIG)?;
+    let mut endpoint = Endpoint::new(ir)?;
+
+    let server = Server::http(matches.value_of("ADDR").unwrap()).map_err(|e| e.to_string())?;
+    let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+    for mut request in server.incoming_requests() {
+        let (status, body) = endpoint.handle(&mut request);
+        let response =
+            Response::from_string(body.to_string()).with_status_code(status).with_header(content_type.clone());
+        if let Err(e) = request.respond(response) {
+            eprintln!("failed to respond: {}", e);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn endpoint() -> Endpoint {
+        let spec = "input alt: Float64\ntrigger alt < 200.0 \"too low\"";
+        Endpoint::new(rtlola_frontend::parse("spec", spec, CONFIG).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn invalid_batches_are_rejected_as_a_whole() {
+        let mut endpoint = endpoint();
+        let batch = br#"[{"time": 1.0, "values": {"alt": 100.0}}, {"time": 2.0, "values": {"alt": "high"}}]"#;
+        assert_eq!(endpoint.accept(batch), Err(String::from("invalid value for `alt`")));
+        assert!(endpoint.verdicts.is_empty());
+        let fired = endpoint.accept(br#"{"time": 1.0, "values": {"alt": 100.0}}"#).unwrap();
+        assert_eq!(fired, vec![json!({ "time": 1.0, "trigger": "too low" })]);
+    }
+
+    #[test]
+    fn time_is_monotonic() {
+        let mut endpoint = endpoint();
+        assert!(endpoint.accept(br#"{"time": 2.0, "values": {}}"#).is_ok());
+        assert!(endpoint.accept(br#"{"time": 1.0, "values": {"alt": 100.0}}"#).is_err());
+        assert!(endpoint.accept(br#"[{"time": 3.0, "values": {}}, {"time": 2.5, "values": {}}]"#).is_err());
+        assert_eq!(endpoint.last, Duration::from_secs(2));
+    }
+
+    #[test]
+    fn old_verdicts_are_dropped() {
+        let mut endpoint = endpoint();
+        let events: Vec<Json> =
+            (0..MAX_VERDICTS + 5).map(|t| json!({ "time": t as f64, "values": { "alt": 100.0 } })).collect();
+        endpoint.accept(Json::Array(events).to_string().as_bytes()).unwrap();
+        assert_eq!((endpoint.verdicts.len(), endpoint.dropped), (MAX_VERDICTS, 5));
+        assert_eq!(endpoint.verdicts_since(0).len(), MAX_VERDICTS);
+        assert_eq!(
+            endpoint.verdicts_since(MAX_VERDICTS)[0],
+            json!({ "time": MAX_VERDICTS as f64, "trigger": "too low" })
+        );
+    }
+}
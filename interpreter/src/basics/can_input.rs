@@ -0,0 +1,525 @@
+use crate::basics::io_handler::EventSource;
+use crate::basics::Time;
+use crate::storage::Value;
+use ordered_float::NotNan;
+use rtlola_frontend::ir::{RTLolaIR, Type};
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Flag marking extended (29 bit) identifiers, both in DBC files and SocketCAN frames.
+const CAN_EFF_FLAG: u32 = 0x8000_0000;
+const CAN_EFF_MASK: u32 = 0x1FFF_FFFF;
+const CAN_SFF_MASK: u32 = 0x0000_07FF;
+
+fn normalize_id(id: u32) -> u32 {
+    if id & CAN_EFF_FLAG != 0 {
+        id & (CAN_EFF_FLAG | CAN_EFF_MASK)
+    } else {
+        id & CAN_SFF_MASK
+    }
+}
+
+// ################################
+// DBC Parsing
+// ################################
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ByteOrder {
+    LittleEndian,
+    BigEndian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Multiplexing {
+    None,
+    Multiplexor,
+    Multiplexed(u64),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Signal {
+    name: String,
+    start: u32,
+    len: u32,
+    order: ByteOrder,
+    signed: bool,
+    factor: f64,
+    offset: f64,
+    unit: String,
+    mux: Multiplexing,
+}
+
+impl Signal {
+    fn raw(&self, data: &[u8; 8]) -> u64 {
+        match self.order {
+            ByteOrder::LittleEndian => {
+                let word = u64::from_le_bytes(*data) >> self.start;
+                if self.len == 64 {
+                    word
+                } else {
+                    word & ((1 << self.len) - 1)
+                }
+            }
+            ByteOrder::BigEndian => {
+                // The start bit denotes the most significant bit, counting continues in the next byte at bit 7.
+                let mut raw = 0u64;
+                let mut bit = self.start;
+                for _ in 0..self.len {
+                    let (byte, bit_in_byte) = ((bit / 8) as usize, bit % 8);
+                    raw = (raw << 1) | u64::from(data.get(byte).map_or(0, |b| (b >> bit_in_byte) & 1));
+                    bit = if bit_in_byte == 0 { bit + 15 } else { bit - 1 };
+                }
+                raw
+            }
+        }
+    }
+
+    /// Returns the physical value of the signal, i.e., the scaled raw value.
+    fn physical(&self, data: &[u8; 8]) -> f64 {
+        let raw = self.raw(data);
+        let raw = if self.signed && self.len < 64 && (raw >> (self.len - 1)) & 1 == 1 {
+            (raw | !((1 << self.len) - 1)) as i64 as f64
+        } else if self.signed {
+            raw as i64 as f64
+        } else {
+            raw as f64
+        };
+        raw * self.factor + self.offset
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Message {
+    id: u32,
+    name: String,
+    signals: Vec<Signal>,
+}
+
+/// The CAN database, i.e., the messages and signals of a DBC file.
+#[derive(Debug, Clone, PartialEq)]
+struct Dbc {
+    messages: Vec<Message>,
+}
+
+impl Dbc {
+    fn parse(content: &str) -> Result<Dbc, String> {
+        let mut messages: Vec<Message> = Vec::new();
+        for (line_ix, line) in content.lines().enumerate() {
+            let line = line.trim();
+            let err = |msg: &str| format!("line {}: {}: `{}`", line_ix + 1, msg, line);
+            if let Some(rest) = line.strip_prefix("BO_ ") {
+                let mut parts = rest.split_whitespace();
+                let id = parts.next().and_then(|id| id.parse::<u32>().ok()).ok_or_else(|| err("invalid message id"))?;
+                let name = parts.next().map(|n| n.trim_end_matches(':')).ok_or_else(|| err("missing message name"))?;
+                messages.push(Message { id: normalize_id(id), name: name.to_string(), signals: Vec::new() });
+            } else if let Some(rest) = line.strip_prefix("SG_ ") {
+                let signal = Self::parse_signal(rest).ok_or_else(|| err("invalid signal definition"))?;
+                messages.last_mut().ok_or_else(|| err("signal outside of message"))?.signals.push(signal);
+            }
+        }
+        Ok(Dbc { messages })
+    }
+
+    /// Parses `Name [M|mX] : start|len@order sign (factor,offset) [min|max] "unit" receivers`.
+    fn parse_signal(def: &str) -> Option<Signal> {
+        let colon = def.find(':')?;
+        let mut head = def[..colon].split_whitespace();
+        let name = head.next()?.to_string();
+        let mux = match head.next() {
+            None => Multiplexing::None,
+            Some("M") => Multiplexing::Multiplexor,
+            Some(m) => Multiplexing::Multiplexed(m.strip_prefix('m')?.parse().ok()?),
+        };
+        let mut body = def[colon + 1..].trim().splitn(2, ' ');
+        let layout = body.next()?;
+        let rest = body.next()?.trim();
+
+        let (start, layout) = layout.split_at(layout.find('|')?);
+        let (len, layout) = layout[1..].split_at(layout[1..].find('@')?);
+        let mut flags = layout[1..].chars();
+        let order = match flags.next()? {
+            '0' => ByteOrder::BigEndian,
+            '1' => ByteOrder::LittleEndian,
+            _ => return None,
+        };
+        let signed = flags.next()? == '-';
+
+        let scaling = &rest[rest.find('(')? + 1..rest.find(')')?];
+        let mut scaling = scaling.split(',');
+        let factor = scaling.next()?.trim().parse().ok()?;
+        let offset = scaling.next()?.trim().parse().ok()?;
+        let unit = rest.split('"').nth(1).unwrap_or("").to_string();
+
+        Some(Signal {
+            name,
+            start: start.parse().ok().filter(|s| *s < 64)?,
+            len: len.parse().ok().filter(|l| *l > 0 && *l <= 64)?,
+            order,
+            signed,
+            factor,
+            offset,
+            unit,
+            mux,
+        })
+    }
+}
+
+// ################################
+// Frame Sources
+// ################################
+
+/// A CAN frame with normalized identifier and data padded to eight bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Frame {
+    id: u32,
+    data: [u8; 8],
+}
+
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+mod socket {
+    use super::{normalize_id, Frame};
+    use std::ffi::CString;
+    use std::io::{Error, Result};
+    use std::mem;
+
+    const PF_CAN: libc::c_int = 29;
+    const CAN_RAW: libc::c_int = 1;
+
+    // Layout of `struct sockaddr_can`, the fields are only read by the kernel.
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct SockAddrCan {
+        can_family: libc::sa_family_t,
+        can_ifindex: libc::c_int,
+        rx_id: u32,
+        tx_id: u32,
+    }
+
+    // Layout of `struct can_frame`.
+    #[repr(C)]
+    #[allow(dead_code)]
+    struct CanFrame {
+        can_id: u32,
+        can_dlc: u8,
+        pad: u8,
+        res0: u8,
+        res1: u8,
+        data: [u8; 8],
+    }
+
+    /// A raw SocketCAN socket bound to an interface.
+    #[derive(Debug)]
+    pub(super) struct CanSocket {
+        fd: libc::c_int,
+    }
+
+    impl CanSocket {
+        pub(super) fn open(iface: &str) -> Result<CanSocket> {
+            let name = CString::new(iface)?;
+            let ifindex = unsafe { libc::if_nametoindex(name.as_ptr()) };
+            if ifindex == 0 {
+                return Err(Error::last_os_error());
+            }
+            let fd = unsafe { libc::socket(PF_CAN, libc::SOCK_RAW, CAN_RAW) };
+            if fd < 0 {
+                return Err(Error::last_os_error());
+            }
+            let addr = SockAddrCan {
+                can_family: PF_CAN as libc::sa_family_t,
+                can_ifindex: ifindex as i32,
+                rx_id: 0,
+                tx_id: 0,
+            };
+            let addr: *const SockAddrCan = &addr;
+            let res = unsafe {
+                libc::bind(fd, addr as *const libc::sockaddr, mem::size_of::<SockAddrCan>() as libc::socklen_t)
+            };
+            if res < 0 {
+                let err = Error::last_os_error();
+                unsafe { libc::close(fd) };
+                return Err(err);
+            }
+            Ok(CanSocket { fd })
+        }
+
+        pub(super) fn read(&mut self) -> Result<Frame> {
+            let mut frame = CanFrame { can_id: 0, can_dlc: 0, pad: 0, res0: 0, res1: 0, data: [0; 8] };
+            let size = mem::size_of::<CanFrame>();
+            let buf: *mut CanFrame = &mut frame;
+            let read = unsafe { libc::read(self.fd, buf as *mut libc::c_void, size) };
+            if read < 0 {
+                return Err(Error::last_os_error());
+            }
+            let mut data = frame.data;
+            data.iter_mut().skip(usize::from(frame.can_dlc)).for_each(|b| *b = 0);
+            Ok(Frame { id: normalize_id(frame.can_id), data })
+        }
+    }
+
+    impl Drop for CanSocket {
+        fn drop(&mut self) {
+            unsafe { libc::close(self.fd) };
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod socket {
+    use super::Frame;
+    use std::io::{Error, ErrorKind, Result};
+
+    #[derive(Debug)]
+    pub(super) struct CanSocket {}
+
+    impl CanSocket {
+        pub(super) fn open(_iface: &str) -> Result<CanSocket> {
+            Err(Error::new(ErrorKind::Other, "SocketCAN is only available on Linux"))
+        }
+
+        pub(super) fn read(&mut self) -> Result<Frame> {
+            unreachable!("The socket cannot be opened.")
+        }
+    }
+}
+
+/// Parses a line of a `candump -l` log, e.g., `(1436509052.249713) vcan0 044#2A366C2BBA`.
+fn parse_candump_line(line: &str) -> Option<(SystemTime, Frame)> {
+    let mut parts = line.split_whitespace();
+    let time = parts.next()?.trim_start_matches('(').trim_end_matches(')');
+    let _iface = parts.next()?;
+    let frame = parts.next()?;
+
+    let mut time = time.split('.');
+    let secs: u64 = time.next()?.parse().ok()?;
+    let micros: u32 = time.next().map_or(Some(0), |m| m.parse().ok())?;
+    let time = UNIX_EPOCH + Duration::new(secs, micros * 1000);
+
+    let hash = frame.find('#')?;
+    let (id_str, data_str) = (&frame[..hash], &frame[hash + 1..]);
+    let id = u32::from_str_radix(id_str, 16).ok()?;
+    let id = if id_str.len() > 3 { id | CAN_EFF_FLAG } else { id };
+    let mut data = [0u8; 8];
+    for (ix, byte) in data_str.as_bytes().chunks(2).take(8).enumerate() {
+        data[ix] = u8::from_str_radix(std::str::from_utf8(byte).ok()?, 16).ok()?;
+    }
+    Some((time, Frame { id: normalize_id(id), data }))
+}
+
+// ################################
+// Event Source Handling
+// ################################
+
+#[derive(Debug, Clone)]
+pub enum CANInputSource {
+    /// Reads frames from a SocketCAN interface, e.g., `can0`.
+    Interface { name: String, dbc: String },
+    /// Replays a log file recorded with `candump -l`.
+    Log { path: String, dbc: String },
+}
+
+#[derive(Debug)]
+enum Connection {
+    Socket(socket::CanSocket),
+    Log(BufReader<File>),
+}
+
+/// An event source decoding CAN frames according to a DBC file.
+///
+/// Input streams are named `MESSAGE::SIGNAL` after the definitions in the DBC file and receive the physical value
+/// of the signal, i.e., the raw value scaled by factor and offset and given in the signal's unit, whenever the
+/// message arrives.
+#[derive(Debug)]
+pub struct CANEventSource {
+    connection: Connection,
+    dbc: Dbc,
+    /// For each input stream the index of the message and the signal.
+    mapping: Vec<(usize, usize)>,
+    in_types: Vec<Type>,
+    start: Instant,
+    first_timestamp: Option<SystemTime>,
+    last_timestamp: Option<SystemTime>,
+    event: Option<(Vec<Value>, Time)>,
+}
+
+impl CANEventSource {
+    pub(crate) fn setup(
+        src: &CANInputSource,
+        ir: &RTLolaIR,
+        start_time: Instant,
+    ) -> Result<Box<dyn EventSource>, Box<dyn Error>> {
+        let (connection, dbc) = match src {
+            CANInputSource::Interface { name, dbc } => (Connection::Socket(socket::CanSocket::open(name)?), dbc),
+            CANInputSource::Log { path, dbc } => (Connection::Log(BufReader::new(File::open(path)?)), dbc),
+        };
+        let dbc = Dbc::parse(&std::fs::read_to_string(dbc)?)?;
+
+        let mut mapping = Vec::with_capacity(ir.inputs.len());
+        for input in &ir.inputs {
+            let layers: Vec<&str> = input.name.split("::").collect();
+            if layers.len() != 2 {
+                eprintln!("Malformed input name: {}", input.name);
+                std::process::exit(1);
+            }
+            let signal = dbc.messages.iter().enumerate().find(|(_, m)| m.name == layers[0]).and_then(|(msg_ix, m)| {
+                m.signals.iter().position(|s| s.name == layers[1]).map(|sig_ix| (msg_ix, sig_ix))
+            });
+            match signal {
+                Some(signal) => mapping.push(signal),
+                None => {
+                    eprintln!("Unknown input name: {}", input.name);
+                    std::process::exit(1);
+                }
+            }
+        }
+        let in_types = ir.inputs.iter().map(|i| i.ty.clone()).collect();
+
+        Ok(Box::new(CANEventSource {
+            connection,
+            dbc,
+            mapping,
+            in_types,
+            start: start_time,
+            first_timestamp: None,
+            last_timestamp: None,
+            event: None,
+        }))
+    }
+
+    /// Reads the next frame belonging to a message of the DBC file.
+    fn next_frame(&mut self) -> Result<Option<(usize, Frame)>, Box<dyn Error>> {
+        loop {
+            let frame = match &mut self.connection {
+                Connection::Socket(socket) => {
+                    self.last_timestamp = Some(SystemTime::now());
+                    socket.read()?
+                }
+                Connection::Log(reader) => {
+                    let mut line = String::new();
+                    if reader.read_line(&mut line)? == 0 {
+                        return Ok(None);
+                    }
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let (time, frame) = parse_candump_line(&line)
+                        .ok_or_else(|| format!("failed to parse candump line `{}`", line.trim()))?;
+                    self.last_timestamp = Some(time);
+                    frame
+                }
+            };
+            if let Some(msg_ix) = self.dbc.messages.iter().position(|m| m.id == frame.id) {
+                return Ok(Some((msg_ix, frame)));
+            }
+        }
+    }
+
+    fn convert(signal: &Signal, value: f64, ty: &Type) -> Value {
+        match ty {
+            Type::Float(_) => NotNan::new(value).map(Value::Float).unwrap_or(Value::None),
+            Type::Int(_) if value.fract() == 0.0 => Value::Signed(value as i64),
+            Type::UInt(_) if value.fract() == 0.0 && value >= 0.0 => Value::Unsigned(value as u64),
+            Type::Bool if signal.len == 1 => Value::Bool(value != 0.0),
+            _ => {
+                eprintln!(
+                    "error: problem with data source; failed to convert {} {} of signal {} to value of type {}.",
+                    value, signal.unit, signal.name, ty
+                );
+                std::process::exit(1)
+            }
+        }
+    }
+}
+
+impl EventSource for CANEventSource {
+    fn has_event(&mut self) -> bool {
+        let (msg_ix, frame) = match self.next_frame() {
+            Ok(Some(frame)) => frame,
+            Ok(None) => return false,
+            Err(e) => {
+                eprintln!("error: failed to read CAN frame. {}", e);
+                std::process::exit(1)
+            }
+        };
+        let msg = &self.dbc.messages[msg_ix];
+        let mux_value = msg.signals.iter().find(|s| s.mux == Multiplexing::Multiplexor).map(|s| s.raw(&frame.data));
+        let event = self
+            .mapping
+            .iter()
+            .zip(&self.in_types)
+            .map(|((m, s), ty)| {
+                let signal = &msg.signals[*s];
+                match signal.mux {
+                    _ if *m != msg_ix => Value::None,
+                    Multiplexing::Multiplexed(v) if Some(v) != mux_value => Value::None,
+                    _ => Self::convert(signal, signal.physical(&frame.data), ty),
+                }
+            })
+            .collect();
+
+        let time = match &self.connection {
+            Connection::Socket(_) => Instant::now() - self.start,
+            Connection::Log(_) => {
+                let now = self.last_timestamp.unwrap();
+                let first = *self.first_timestamp.get_or_insert(now);
+                now.duration_since(first).expect("Time did not behave monotonically!")
+            }
+        };
+        self.event = Some((event, time));
+        true
+    }
+
+    fn get_event(&mut self) -> (Vec<Value>, Time) {
+        self.event.take().unwrap_or_else(|| {
+            eprintln!("No event available!");
+            std::process::exit(1);
+        })
+    }
+
+    fn read_time(&self) -> Option<SystemTime> {
+        self.last_timestamp
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DBC: &str = r#"
+VERSION ""
+
+BO_ 100 EngineData: 8 Vector__XXX
+ SG_ EngineSpeed : 0|16@1+ (0.125,0) [0|8031.875] "rpm" Vector__XXX
+ SG_ Temperature : 16|8@1- (1,-40) [-40|215] "degC" Vector__XXX
+ SG_ Pressure : 31|12@0+ (0.5,0) [0|2047.5] "kPa" Vector__XXX
+
+BO_ 2147484672 Extended: 8 Vector__XXX
+ SG_ Mode M : 0|8@1+ (1,0) [0|255] "" Vector__XXX
+ SG_ Level m1 : 8|8@1+ (1,0) [0|255] "%" Vector__XXX
+"#;
+
+    #[test]
+    fn parse_dbc() {
+        let dbc = Dbc::parse(DBC).unwrap();
+        assert_eq!(dbc.messages.len(), 2);
+        assert_eq!(dbc.messages[0].id, 100);
+        assert_eq!(dbc.messages[1].id, 0x400 | CAN_EFF_FLAG);
+        let temp = &dbc.messages[0].signals[1];
+        assert_eq!((temp.start, temp.len, temp.signed, temp.offset), (16, 8, true, -40.0));
+        assert_eq!(temp.unit, "degC");
+        assert_eq!(dbc.messages[1].signals[1].mux, Multiplexing::Multiplexed(1));
+    }
+
+    #[test]
+    fn decode_signals() {
+        let dbc = Dbc::parse(DBC).unwrap();
+        let (_, frame) = parse_candump_line("(1436509052.249713) vcan0 064#401F1E0AB0000000").unwrap();
+        assert_eq!(frame.id, 100);
+        let signals = &dbc.messages[0].signals;
+        assert_eq!(signals[0].physical(&frame.data), 1000.0);
+        assert_eq!(signals[1].physical(&frame.data), -10.0);
+        assert_eq!(signals[2].physical(&frame.data), 85.5);
+    }
+}
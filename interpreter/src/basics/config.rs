@@ -1,4 +1,9 @@
-use super::{CSVInputSource, EventSourceConfig, OutputChannel};
+use super::{CSVInputSource, EventSourceConfig, InfluxConfig, OutputChannel};
+use crate::storage::{Value, WindowAggregation};
+use rtlola_frontend::WarningConfig;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
 
 #[derive(Clone, Debug)]
 pub struct EvalConfig {
@@ -9,6 +14,60 @@ pub struct EvalConfig {
     pub evaluator: EvaluatorChoice,
     pub mode: ExecutionMode,
     pub time_presentation: TimeRepresentation,
+    pub influx: Option<InfluxConfig>,
+    /// Computes the robustness of trigger conditions alongside their verdicts.
+    pub robustness: bool,
+    /// Reports the values of sub-expressions and upstream streams that determined the verdict whenever a trigger fires.
+    pub explain: bool,
+    /// Determines which warnings of the specification are suppressed or promoted to errors, see `Config::from_spec`.
+    pub warnings: WarningConfig,
+    /// The enabled features of the specification, deciding which declarations with `cfg` attributes are kept, see `Config::from_spec`.
+    pub features: Vec<String>,
+    /// Overrides the values of configurable constants of the specification, given as pairs of name and value.
+    pub constants: Vec<(String, String)>,
+    pub(crate) functions: Functions,
+    pub(crate) aggregations: Aggregations,
+}
+
+/// The implementation of a function that is not part of the standard library, see `EvalConfig::add_function`.
+pub type FunctionImpl = Arc<dyn Fn(&[Value]) -> Value + Send + Sync>;
+
+/// The implementations of functions registered by the host application, indexed by function name.
+#[derive(Clone, Default)]
+pub(crate) struct Functions(HashMap<String, FunctionImpl>);
+
+impl fmt::Debug for Functions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl Functions {
+    pub(crate) fn call(&self, name: &str, args: &[Value]) -> Value {
+        match self.0.get(name) {
+            Some(function) => function(args),
+            None => unreachable!("Unknown function: {}, args: {:?}", name, args),
+        }
+    }
+}
+
+/// The implementations of aggregation functions of sliding windows registered by the host application, indexed by name.
+#[derive(Clone, Default)]
+pub(crate) struct Aggregations(HashMap<String, Arc<dyn WindowAggregation>>);
+
+impl fmt::Debug for Aggregations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl Aggregations {
+    pub(crate) fn get(&self, name: &str) -> Arc<dyn WindowAggregation> {
+        match self.0.get(name) {
+            Some(aggregation) => Arc::clone(aggregation),
+            None => unreachable!("Unknown aggregation: {}", name),
+        }
+    }
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
@@ -72,7 +131,42 @@ impl EvalConfig {
         mode: ExecutionMode,
         time_presentation: TimeRepresentation,
     ) -> Self {
-        EvalConfig { source, statistics, verbosity, output_channel: output, evaluator, mode, time_presentation }
+        EvalConfig {
+            source,
+            statistics,
+            verbosity,
+            output_channel: output,
+            evaluator,
+            mode,
+            time_presentation,
+            influx: None,
+            robustness: false,
+            explain: false,
+            warnings: WarningConfig::default(),
+            features: Vec::new(),
+            constants: Vec::new(),
+            functions: Functions::default(),
+            aggregations: Aggregations::default(),
+        }
+    }
+
+    /**
+    Provides the implementation of a function that the specification was parsed with, see `rtlola_frontend::parse_with_functions`.
+
+    The implementation receives the values of all arguments, including the receiver of a method call, and returns `Value::None` for no value.
+    */
+    pub fn add_function(&mut self, name: &str, function: impl Fn(&[Value]) -> Value + Send + Sync + 'static) {
+        self.functions.0.insert(name.to_string(), Arc::new(function));
+    }
+
+    /// Provides the implementation of an aggregation function of sliding windows that the specification was parsed with, see `rtlola_frontend::FunctionRegistry::add_aggregation`.
+    pub fn add_aggregation(&mut self, name: &str, aggregation: impl WindowAggregation + 'static) {
+        self.aggregations.0.insert(name.to_string(), Arc::new(aggregation));
+    }
+
+    /// Overrides the value of the configurable constant with the name when the monitor starts, see `RTLolaIR::configure`.
+    pub fn set_constant(&mut self, name: &str, value: &str) {
+        self.constants.push((name.to_string(), value.to_string()));
     }
 
     pub fn debug() -> Self {
@@ -123,6 +217,14 @@ impl Default for EvalConfig {
             evaluator: EvaluatorChoice::ClosureBased,
             mode: ExecutionMode::Offline,
             time_presentation: TimeRepresentation::Hide,
+            influx: None,
+            robustness: false,
+            explain: false,
+            warnings: WarningConfig::default(),
+            features: Vec::new(),
+            constants: Vec::new(),
+            functions: Functions::default(),
+            aggregations: Aggregations::default(),
         }
     }
 }
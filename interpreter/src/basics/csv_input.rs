@@ -38,6 +38,9 @@ pub(crate) struct CSVColumnMapping {
     /// Mapping from column index to input stream index/reference
     pub(crate) col2str: Vec<Option<usize>>,
 
+    /// The columns of input streams with the index of the stream, in the order of the columns
+    columns: Vec<(usize, usize)>,
+
     /// Column index of time (if existent)
     time_ix: Option<usize>,
 }
@@ -65,7 +68,8 @@ impl CSVColumnMapping {
                 name == "time" || name == "ts" || name == "timestamp"
             })
         });
-        CSVColumnMapping { col2str, time_ix }
+        let columns = col2str.iter().enumerate().filter_map(|(col, str_ix)| Some((col, (*str_ix)?))).collect();
+        CSVColumnMapping { col2str, columns, time_ix }
     }
 
     fn input_to_stream(&self, input_ix: usize) -> Option<usize> {
@@ -196,26 +200,23 @@ impl CSVEventSource {
 
     fn read_event(&self) -> Vec<Value> {
         let mut buffer = vec![Value::None; self.in_types.len()];
-        for (col_ix, s) in self.record.iter().enumerate() {
-            if let Some(str_ix) = self.mapping.col2str[col_ix] {
-                // utf8-encoding (as [u8]) of string "#"
-                if s != [35] {
-                    let t = &self.in_types[str_ix];
-                    buffer[str_ix] = Value::try_from(s, t).unwrap_or_else(|| {
-                        if let Ok(s) = std::str::from_utf8(s) {
-                            eprintln!(
-                                "error: problem with data source; failed to parse {} as value of type {:?}.",
-                                s, t
-                            );
-                        } else {
-                            eprintln!(
-                                "error: problem with data source; failed to parse non-utf8 {:?} as value of type {:?}.",
-                                s, t
-                            );
-                        }
-                        std::process::exit(1)
-                    })
-                }
+        // fields are decoded from the bytes of the record, columns without input stream are skipped
+        for &(col_ix, str_ix) in &self.mapping.columns {
+            let s = &self.record[col_ix];
+            // `#` marks the absence of a value
+            if s != b"#" {
+                let t = &self.in_types[str_ix];
+                buffer[str_ix] = Value::try_from(s, t).unwrap_or_else(|| {
+                    if let Ok(s) = std::str::from_utf8(s) {
+                        eprintln!("error: problem with data source; failed to parse {} as value of type {:?}.", s, t);
+                    } else {
+                        eprintln!(
+                            "error: problem with data source; failed to parse non-utf8 {:?} as value of type {:?}.",
+                            s, t
+                        );
+                    }
+                    std::process::exit(1)
+                })
             }
         }
         buffer
@@ -238,37 +239,39 @@ impl EventSource for CSVEventSource {
 
     fn read_time(&self) -> Option<SystemTime> {
         let time_str = self.str_for_time()?;
-        let mut time_str_split = time_str.split('.');
-        let secs_str: &str = match time_str_split.next() {
-            Some(s) => s,
-            None => {
-                eprintln!("error: problem with data source; failed to parse time string {}.", time_str);
-                std::process::exit(1)
-            }
-        };
-        let secs = match secs_str.parse::<u64>() {
-            Ok(u) => u,
-            Err(e) => {
-                eprintln!("error: problem with data source; failed to parse time string {}: {}", time_str, e);
-                std::process::exit(1)
-            }
-        };
-        let d: Duration = if let Some(nanos_str) = time_str_split.next() {
-            let mut chars = nanos_str.chars();
-            let mut nanos: u32 = 0;
-            for _ in 1..=9 {
-                nanos *= 10;
-                if let Some(c) = chars.next() {
-                    if let Some(d) = c.to_digit(10) {
-                        nanos += d;
-                    }
+        let d = parse_time(time_str).unwrap_or_else(|e| {
+            eprintln!("error: problem with data source; {}", e);
+            std::process::exit(1)
+        });
+        Some(UNIX_EPOCH + d)
+    }
+}
+
+/// Parses a time column entry into the time since the Unix epoch, given in seconds with a fraction, or in nanoseconds without.
+pub(crate) fn parse_time(time_str: &str) -> Result<Duration, String> {
+    let mut time_str_split = time_str.split('.');
+    let secs_str: &str = match time_str_split.next() {
+        Some(s) => s,
+        None => return Err(format!("failed to parse time string {}.", time_str)),
+    };
+    let secs = match secs_str.parse::<u64>() {
+        Ok(u) => u,
+        Err(e) => return Err(format!("failed to parse time string {}: {}", time_str, e)),
+    };
+    if let Some(nanos_str) = time_str_split.next() {
+        let mut chars = nanos_str.chars();
+        let mut nanos: u32 = 0;
+        for _ in 1..=9 {
+            nanos *= 10;
+            if let Some(c) = chars.next() {
+                if let Some(d) = c.to_digit(10) {
+                    nanos += d;
                 }
             }
-            assert!(time_str_split.next().is_none());
-            Duration::new(secs, nanos)
-        } else {
-            Duration::from_nanos(secs)
-        };
-        Some(UNIX_EPOCH + d)
+        }
+        assert!(time_str_split.next().is_none());
+        Ok(Duration::new(secs, nanos))
+    } else {
+        Ok(Duration::from_nanos(secs))
     }
 }
@@ -0,0 +1,409 @@
+use crate::basics::io_handler::EventSource;
+use crate::basics::Time;
+use crate::storage::Value;
+use ordered_float::NotNan;
+use rtlola_frontend::ir::{RTLolaIR, Type};
+use std::error::Error;
+use std::fs;
+use std::time::{Duration, Instant, SystemTime};
+
+/// Size of a sector in `/sys/block/*/stat`, independent of the actual sector size of the device.
+const SECTOR_SIZE: f64 = 512.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Metric {
+    CpuUsage,
+    Load1,
+    Load5,
+    Load15,
+    Processes,
+    ProcessesRunning,
+    MemTotal,
+    MemAvailable,
+    MemUsed,
+    SwapUsed,
+    DiskRead,
+    DiskWritten,
+    NetReceived,
+    NetSent,
+    ProcessCpuUsage,
+    ProcessMemory,
+    ProcessThreads,
+}
+
+/// The input stream names of the metrics.
+const METRICS: [(&str, Metric); 17] = [
+    ("host::cpu_usage", Metric::CpuUsage),
+    ("host::load_1", Metric::Load1),
+    ("host::load_5", Metric::Load5),
+    ("host::load_15", Metric::Load15),
+    ("host::processes", Metric::Processes),
+    ("host::processes_running", Metric::ProcessesRunning),
+    ("host::mem_total", Metric::MemTotal),
+    ("host::mem_available", Metric::MemAvailable),
+    ("host::mem_used", Metric::MemUsed),
+    ("host::swap_used", Metric::SwapUsed),
+    ("host::disk_read", Metric::DiskRead),
+    ("host::disk_written", Metric::DiskWritten),
+    ("host::net_received", Metric::NetReceived),
+    ("host::net_sent", Metric::NetSent),
+    ("process::cpu_usage", Metric::ProcessCpuUsage),
+    ("process::memory", Metric::ProcessMemory),
+    ("process::threads", Metric::ProcessThreads),
+];
+
+impl Metric {
+    fn of_process(self) -> bool {
+        match self {
+            Metric::ProcessCpuUsage | Metric::ProcessMemory | Metric::ProcessThreads => true,
+            _ => false,
+        }
+    }
+}
+
+// ################################
+// Parsing of /proc and /sys
+// ################################
+
+/// Returns the busy and total jiffies of all CPUs from the content of `/proc/stat`.
+fn parse_cpu_times(stat: &str) -> Option<(u64, u64)> {
+    let line = stat.lines().find(|l| l.starts_with("cpu "))?;
+    let times = line.split_whitespace().skip(1).map(str::parse).collect::<Result<Vec<u64>, _>>().ok()?;
+    // user nice system idle iowait irq softirq steal; guest times are already contained in user and nice.
+    let total: u64 = times.iter().take(8).sum();
+    let idle = times.get(3)? + times.get(4).unwrap_or(&0);
+    Some((total - idle, total))
+}
+
+/// Returns the load averages and the number of running and existing processes from `/proc/loadavg`.
+fn parse_loadavg(loadavg: &str) -> Option<([f64; 3], u64, u64)> {
+    let mut parts = loadavg.split_whitespace();
+    let mut load = [0.0; 3];
+    for l in load.iter_mut() {
+        *l = parts.next()?.parse().ok()?;
+    }
+    let mut procs = parts.next()?.split('/');
+    let running = procs.next()?.parse().ok()?;
+    let total = procs.next()?.parse().ok()?;
+    Some((load, running, total))
+}
+
+/// Returns the value of a field of `/proc/meminfo` in bytes.
+fn parse_meminfo(meminfo: &str, field: &str) -> Option<u64> {
+    let line = meminfo.lines().find(|l| l.split(':').next() == Some(field))?;
+    let kib: u64 = line.split(':').nth(1)?.trim().trim_end_matches("kB").trim().parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// Returns the received and sent bytes summed over all interfaces but the loopback from `/proc/net/dev`.
+fn parse_net_dev(dev: &str) -> Option<(u64, u64)> {
+    let mut sum = (0, 0);
+    // The first two lines are headers.
+    for line in dev.lines().skip(2) {
+        let colon = line.find(':')?;
+        if line[..colon].trim() == "lo" {
+            continue;
+        }
+        let fields: Vec<&str> = line[colon + 1..].split_whitespace().collect();
+        sum.0 += fields.first()?.parse::<u64>().ok()?;
+        sum.1 += fields.get(8)?.parse::<u64>().ok()?;
+    }
+    Some(sum)
+}
+
+/// Returns the read and written sectors from a `/sys/block/*/stat` file.
+fn parse_block_stat(stat: &str) -> Option<(u64, u64)> {
+    let fields: Vec<&str> = stat.split_whitespace().collect();
+    Some((fields.get(2)?.parse().ok()?, fields.get(6)?.parse().ok()?))
+}
+
+/// Returns the consumed CPU time in clock ticks, the number of threads, and the resident pages from `/proc/[pid]/stat`.
+fn parse_process_stat(stat: &str) -> Option<(u64, u64, u64)> {
+    // The command name is enclosed in parentheses and may contain spaces.
+    let fields: Vec<&str> = stat[stat.rfind(')')? + 1..].split_whitespace().collect();
+    // Fields are numbered from 1 in proc(5), the remainder starts at field 3.
+    let field = |n: usize| fields.get(n - 3).and_then(|f| f.parse::<u64>().ok());
+    Some((field(14)? + field(15)?, field(20)?, field(24)?))
+}
+
+// ################################
+// Sampling
+// ################################
+
+/// The raw counters and gauges read at one point in time.
+#[derive(Debug, Clone)]
+struct Sample {
+    time: Instant,
+    cpu: (u64, u64),
+    load: [f64; 3],
+    processes: (u64, u64),
+    mem_total: u64,
+    mem_available: u64,
+    swap: (u64, u64),
+    disk: (u64, u64),
+    net: (u64, u64),
+    /// The CPU time in clock ticks, the number of threads, and the resident memory in bytes of the watched process.
+    process: Option<(u64, u64, u64)>,
+}
+
+fn read_file(path: &str) -> Result<String, Box<dyn Error>> {
+    fs::read_to_string(path).map_err(|e| format!("cannot read {}: {}", path, e).into())
+}
+
+fn malformed(path: &str) -> Box<dyn Error> {
+    format!("unexpected content of {}", path).into()
+}
+
+impl Sample {
+    fn read(pid: Option<u32>, page_size: u64) -> Result<Sample, Box<dyn Error>> {
+        let stat = read_file("/proc/stat")?;
+        let cpu = parse_cpu_times(&stat).ok_or_else(|| malformed("/proc/stat"))?;
+        let loadavg = read_file("/proc/loadavg")?;
+        let (load, running, total) = parse_loadavg(&loadavg).ok_or_else(|| malformed("/proc/loadavg"))?;
+        let meminfo = read_file("/proc/meminfo")?;
+        let mem = |field| parse_meminfo(&meminfo, field).ok_or_else(|| malformed("/proc/meminfo"));
+        let net = parse_net_dev(&read_file("/proc/net/dev")?).ok_or_else(|| malformed("/proc/net/dev"))?;
+
+        let mut disk = (0, 0);
+        for entry in fs::read_dir("/sys/block")? {
+            let name = entry?.file_name().to_string_lossy().to_string();
+            // Virtual devices would count the accesses of the underlying disks twice.
+            if ["loop", "ram", "zram", "dm-", "md"].iter().any(|p| name.starts_with(p)) {
+                continue;
+            }
+            let path = format!("/sys/block/{}/stat", name);
+            let (sectors_read, sectors_written) =
+                parse_block_stat(&read_file(&path)?).ok_or_else(|| malformed(&path))?;
+            disk = (disk.0 + sectors_read, disk.1 + sectors_written);
+        }
+
+        let process = match pid {
+            Some(pid) => {
+                let path = format!("/proc/{}/stat", pid);
+                let (ticks, threads, pages) = parse_process_stat(&read_file(&path)?).ok_or_else(|| malformed(&path))?;
+                Some((ticks, threads, pages * page_size))
+            }
+            None => None,
+        };
+
+        Ok(Sample {
+            time: Instant::now(),
+            cpu,
+            load,
+            processes: (running, total),
+            mem_total: mem("MemTotal")?,
+            mem_available: mem("MemAvailable")?,
+            swap: (mem("SwapTotal")?, mem("SwapFree")?),
+            disk,
+            net,
+            process,
+        })
+    }
+}
+
+/// Returns the page size in bytes and the number of clock ticks per second.
+#[cfg(target_os = "linux")]
+#[allow(unsafe_code)]
+fn system_config() -> (u64, f64) {
+    let (page_size, clock_ticks) = unsafe { (libc::sysconf(libc::_SC_PAGESIZE), libc::sysconf(libc::_SC_CLK_TCK)) };
+    (page_size as u64, clock_ticks as f64)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn system_config() -> (u64, f64) {
+    unreachable!("Host metrics are only available on Linux.")
+}
+
+// ################################
+// Event Source Handling
+// ################################
+
+#[derive(Debug, Clone, Copy)]
+pub struct HostMetricsInputSource {
+    /// The time between two samples.
+    pub rate: Duration,
+    /// The process whose statistics are provided to the `process::*` inputs.
+    pub pid: Option<u32>,
+}
+
+/// An event source sampling metrics of the host the monitor runs on (Linux only).
+///
+/// Input streams are named after the metrics, e.g., `host::cpu_usage` or `process::memory`, and all receive a new
+/// value with each sample.
+/// Metrics describing the activity between two samples, e.g., the CPU usage or the received network bytes, refer to
+/// the previous sample or, for the first one, to the start of the monitor.
+#[derive(Debug)]
+pub struct HostMetricsEventSource {
+    src: HostMetricsInputSource,
+    metrics: Vec<Metric>,
+    in_types: Vec<Type>,
+    page_size: u64,
+    clock_ticks: f64,
+    start: Instant,
+    samples: u32,
+    last: Sample,
+    event: Option<(Vec<Value>, Time)>,
+}
+
+impl HostMetricsEventSource {
+    pub(crate) fn setup(
+        src: &HostMetricsInputSource,
+        ir: &RTLolaIR,
+        start_time: Instant,
+    ) -> Result<Box<dyn EventSource>, Box<dyn Error>> {
+        if !cfg!(target_os = "linux") {
+            return Err("host metrics are only available on Linux".into());
+        }
+        if src.rate == Duration::default() {
+            return Err("the sampling rate of host metrics must be positive".into());
+        }
+        let mut metrics = Vec::with_capacity(ir.inputs.len());
+        for input in &ir.inputs {
+            let metric = METRICS.iter().find(|(name, _)| *name == input.name).map(|(_, m)| *m).ok_or_else(|| {
+                let names: Vec<&str> = METRICS.iter().map(|(name, _)| *name).collect();
+                format!("unknown host metric `{}`, expected one of {}", input.name, names.join(", "))
+            })?;
+            if metric.of_process() && src.pid.is_none() {
+                return Err(format!("the input `{}` requires a process id", input.name).into());
+            }
+            match input.ty {
+                Type::Float(_) | Type::UInt(_) | Type::Int(_) => {}
+                _ => return Err(format!("the host metric `{}` cannot be of type {}", input.name, input.ty).into()),
+            }
+            metrics.push(metric);
+        }
+        let in_types = ir.inputs.iter().map(|i| i.ty.clone()).collect();
+        let (page_size, clock_ticks) = system_config();
+
+        // Reading the baseline also fails early, e.g., if the process does not exist.
+        let last = Sample::read(src.pid, page_size)?;
+        Ok(Box::new(HostMetricsEventSource {
+            src: src.clone(),
+            metrics,
+            in_types,
+            page_size,
+            clock_ticks,
+            start: start_time,
+            samples: 0,
+            last,
+            event: None,
+        }))
+    }
+
+    /// Returns the value of a metric given the current sample, the previous one is used for counters.
+    fn metric(&self, metric: Metric, now: &Sample) -> Option<f64> {
+        let delta = |f: fn(&Sample) -> u64| f(now).saturating_sub(f(&self.last)) as f64;
+        Some(match metric {
+            Metric::CpuUsage => {
+                let total = delta(|s| s.cpu.1);
+                if total == 0.0 {
+                    0.0
+                } else {
+                    100.0 * delta(|s| s.cpu.0) / total
+                }
+            }
+            Metric::Load1 => now.load[0],
+            Metric::Load5 => now.load[1],
+            Metric::Load15 => now.load[2],
+            Metric::Processes => now.processes.1 as f64,
+            Metric::ProcessesRunning => now.processes.0 as f64,
+            Metric::MemTotal => now.mem_total as f64,
+            Metric::MemAvailable => now.mem_available as f64,
+            Metric::MemUsed => now.mem_total.saturating_sub(now.mem_available) as f64,
+            Metric::SwapUsed => now.swap.0.saturating_sub(now.swap.1) as f64,
+            Metric::DiskRead => delta(|s| s.disk.0) * SECTOR_SIZE,
+            Metric::DiskWritten => delta(|s| s.disk.1) * SECTOR_SIZE,
+            Metric::NetReceived => delta(|s| s.net.0),
+            Metric::NetSent => delta(|s| s.net.1),
+            Metric::ProcessCpuUsage => {
+                let ticks = delta(|s| s.process.map_or(0, |p| p.0));
+                let elapsed = now.time.duration_since(self.last.time).as_secs_f64();
+                100.0 * ticks / self.clock_ticks / elapsed
+            }
+            Metric::ProcessMemory => now.process?.2 as f64,
+            Metric::ProcessThreads => now.process?.1 as f64,
+        })
+    }
+
+    fn convert(value: Option<f64>, ty: &Type) -> Value {
+        match (value, ty) {
+            (None, _) => Value::None,
+            (Some(v), Type::Float(_)) => NotNan::new(v).map(Value::Float).unwrap_or(Value::None),
+            (Some(v), Type::UInt(_)) => Value::Unsigned(v.round() as u64),
+            (Some(v), Type::Int(_)) => Value::Signed(v.round() as i64),
+            _ => unreachable!("rejected in setup"),
+        }
+    }
+}
+
+impl EventSource for HostMetricsEventSource {
+    fn has_event(&mut self) -> bool {
+        self.samples += 1;
+        let next = self.start + self.src.rate * self.samples;
+        let now = Instant::now();
+        if next > now {
+            std::thread::sleep(next - now);
+        }
+        let sample = match Sample::read(self.src.pid, self.page_size) {
+            Ok(sample) => sample,
+            Err(e) => {
+                eprintln!("error: failed to sample host metrics. {}", e);
+                std::process::exit(1)
+            }
+        };
+        let event = self
+            .metrics
+            .iter()
+            .zip(&self.in_types)
+            .map(|(metric, ty)| Self::convert(self.metric(*metric, &sample), ty))
+            .collect();
+        self.event = Some((event, Instant::now() - self.start));
+        self.last = sample;
+        true
+    }
+
+    fn get_event(&mut self) -> (Vec<Value>, Time) {
+        self.event.take().unwrap_or_else(|| {
+            eprintln!("No event available!");
+            std::process::exit(1);
+        })
+    }
+
+    fn read_time(&self) -> Option<SystemTime> {
+        Some(SystemTime::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proc_files() {
+        let stat = "cpu  100 5 50 800 20 3 2 0 0 0\ncpu0 50 2 25 400 10 1 1 0 0 0\nintr 12345\n";
+        assert_eq!(parse_cpu_times(stat), Some((160, 980)));
+
+        let loadavg = "0.52 0.58 0.59 2/613 12345\n";
+        assert_eq!(parse_loadavg(loadavg), Some(([0.52, 0.58, 0.59], 2, 613)));
+
+        let meminfo = "MemTotal:       16303596 kB\nMemFree:         1234567 kB\nMemAvailable:    8151798 kB\n";
+        assert_eq!(parse_meminfo(meminfo, "MemTotal"), Some(16_303_596 * 1024));
+        assert_eq!(parse_meminfo(meminfo, "MemAvailable"), Some(8_151_798 * 1024));
+        assert_eq!(parse_meminfo(meminfo, "SwapFree"), None);
+
+        let dev = "Inter-|   Receive                                                |  Transmit\n \
+                   face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo \
+                   colls carrier compressed\n    \
+                   lo: 1000 10 0 0 0 0 0 0 1000 10 0 0 0 0 0 0\n  \
+                   eth0: 2000 20 0 0 0 0 0 0 300 3 0 0 0 0 0 0\n \
+                   wlan0:50 1 0 0 0 0 0 0 70 1 0 0 0 0 0 0\n";
+        assert_eq!(parse_net_dev(dev), Some((2050, 370)));
+
+        let block = "    4711        0    81234     1000     2342        0    65432     2000        0     3000\n";
+        assert_eq!(parse_block_stat(block), Some((81234, 65432)));
+
+        let process = "4242 (rtlola (worker)) S 1 4242 4242 0 -1 4194560 500 0 0 0 \
+                       120 30 0 0 20 0 7 0 1234 123456789 2048 18446744073709551615";
+        assert_eq!(parse_process_stat(process), Some((150, 7, 2048)));
+    }
+}
@@ -0,0 +1,197 @@
+use crate::storage::Value;
+use std::error::Error;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Number of lines buffered before they are written to the target.
+const BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, Clone)]
+pub enum InfluxTarget {
+    /// Writes the lines to a file.
+    File(String),
+    /// Posts the lines to a write endpoint, e.g., `http://localhost:8086/write?db=telemetry`.
+    Http(String),
+}
+
+/// Emits stream values and trigger firings in the InfluxDB line protocol.
+#[derive(Debug, Clone)]
+pub struct InfluxConfig {
+    pub target: InfluxTarget,
+    pub measurement: String,
+}
+
+impl InfluxConfig {
+    pub fn new(target: InfluxTarget) -> InfluxConfig {
+        InfluxConfig { target, measurement: String::from("rtlola") }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct InfluxSink {
+    config: InfluxConfig,
+    file: Option<File>,
+    buffer: Vec<String>,
+}
+
+impl InfluxSink {
+    pub(crate) fn new(config: InfluxConfig) -> Result<InfluxSink, Box<dyn Error>> {
+        let file = match &config.target {
+            InfluxTarget::File(path) => Some(File::create(path)?),
+            InfluxTarget::Http(url) => {
+                parse_url(url)?;
+                None
+            }
+        };
+        Ok(InfluxSink { config, file, buffer: Vec::with_capacity(BATCH_SIZE) })
+    }
+
+    /// Records the new value of an output stream, values without line protocol representation are skipped.
+    pub(crate) fn value(&mut self, stream: &str, value: &Value, time: SystemTime) {
+        if let Some(field) = field_value(value) {
+            let line = format!(
+                "{},stream={} value={} {}",
+                escape_key(&self.config.measurement),
+                escape_key(stream),
+                field,
+                timestamp(time)
+            );
+            self.push(line);
+        }
+    }
+
+    /// Records the firing of a trigger.
+    pub(crate) fn trigger(&mut self, stream: &str, message: &str, time: SystemTime) {
+        let line = format!(
+            "{}_trigger,trigger={} message={} {}",
+            escape_key(&self.config.measurement),
+            escape_key(stream),
+            escape_string(message),
+            timestamp(time)
+        );
+        self.push(line);
+    }
+
+    fn push(&mut self, line: String) {
+        self.buffer.push(line);
+        if self.buffer.len() >= BATCH_SIZE {
+            self.flush();
+        }
+    }
+
+    pub(crate) fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        let mut body = self.buffer.join("\n");
+        body.push('\n');
+        self.buffer.clear();
+        let res = match (&self.config.target, &mut self.file) {
+            (InfluxTarget::File(_), Some(file)) => file.write_all(body.as_bytes()).map_err(|e| e.into()),
+            (InfluxTarget::Http(url), _) => post(url, &body),
+            (InfluxTarget::File(_), None) => unreachable!("The file is opened on creation."),
+        };
+        if let Err(e) = res {
+            eprintln!("warning: failed to write InfluxDB lines: {}", e);
+        }
+    }
+}
+
+impl Drop for InfluxSink {
+    fn drop(&mut self) {
+        self.flush();
+    }
+}
+
+fn timestamp(time: SystemTime) -> u128 {
+    time.duration_since(UNIX_EPOCH).map(|d| d.as_nanos()).unwrap_or(0)
+}
+
+fn field_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Unsigned(u) => Some(format!("{}u", u)),
+        Value::Signed(i) => Some(format!("{}i", i)),
+        Value::Float(f) => Some(f.to_string()),
+        Value::Str(s) => Some(escape_string(s)),
+        Value::None | Value::Tuple(_) | Value::Bytes(_) => None,
+    }
+}
+
+/// Escapes measurement names, tag keys, and tag values.
+fn escape_key(key: &str) -> String {
+    key.replace('\\', "\\\\").replace(',', "\\,").replace('=', "\\=").replace(' ', "\\ ")
+}
+
+/// Escapes and quotes string field values.
+fn escape_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Splits an `http://host[:port]/path` url into host, port, and path.
+fn parse_url(url: &str) -> Result<(&str, u16, &str), String> {
+    let rest = url.strip_prefix("http://").ok_or_else(|| format!("only http urls are supported: {}", url))?;
+    let (authority, path) = match rest.find('/') {
+        Some(ix) => (&rest[..ix], &rest[ix..]),
+        None => (rest, "/"),
+    };
+    match authority.rfind(':') {
+        Some(ix) => {
+            let port = authority[ix + 1..].parse().map_err(|_| format!("invalid port in url: {}", url))?;
+            Ok((&authority[..ix], port, path))
+        }
+        None => Ok((authority, 80, path)),
+    }
+}
+
+fn post(url: &str, body: &str) -> Result<(), Box<dyn Error>> {
+    let (host, port, path) = parse_url(url)?;
+    let mut stream = TcpStream::connect((host, port))?;
+    write!(stream, "POST {} HTTP/1.1\r\nHost: {}\r\nContent-Type: text/plain; charset=utf-8\r\n", path, host)?;
+    write!(stream, "Content-Length: {}\r\nConnection: close\r\n\r\n{}", body.len(), body)?;
+    let mut status = String::new();
+    BufReader::new(stream).read_line(&mut status)?;
+    match status.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(()),
+        _ => Err(format!("unexpected response `{}`", status.trim()).into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ordered_float::NotNan;
+    use std::time::Duration;
+
+    #[test]
+    fn line_protocol() {
+        let time = UNIX_EPOCH + Duration::from_secs(2);
+        let mut sink = InfluxSink {
+            config: InfluxConfig::new(InfluxTarget::Http(String::from("http://localhost:8086/write?db=test"))),
+            file: None,
+            buffer: vec![],
+        };
+        sink.value("a b", &Value::Float(NotNan::new(1.5).unwrap()), time);
+        sink.value("c", &Value::Unsigned(3), time);
+        sink.value("d", &Value::None, time);
+        sink.trigger("trigger_0", "too \"high\"", time);
+        assert_eq!(
+            sink.buffer,
+            vec![
+                "rtlola,stream=a\\ b value=1.5 2000000000",
+                "rtlola,stream=c value=3u 2000000000",
+                "rtlola_trigger,trigger=trigger_0 message=\"too \\\"high\\\"\" 2000000000",
+            ]
+        );
+        sink.buffer.clear();
+    }
+
+    #[test]
+    fn urls() {
+        assert_eq!(parse_url("http://localhost:8086/write?db=x"), Ok(("localhost", 8086, "/write?db=x")));
+        assert_eq!(parse_url("http://influx"), Ok(("influx", 80, "/")));
+        assert!(parse_url("https://influx").is_err());
+    }
+}
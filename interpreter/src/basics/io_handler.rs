@@ -1,8 +1,12 @@
 #![allow(clippy::mutex_atomic)]
 
 use super::{EvalConfig, TimeFormat, TimeRepresentation, Verbosity};
-use crate::basics::{CSVEventSource, CSVInputSource, PCAPEventSource, PCAPInputSource, Time};
+use crate::basics::{
+    CANEventSource, CANInputSource, CSVEventSource, CSVInputSource, HostMetricsEventSource, HostMetricsInputSource,
+    InfluxSink, MAVLinkEventSource, MAVLinkInputSource, PCAPEventSource, PCAPInputSource, Time,
+};
 use crate::storage::Value;
+#[cfg(not(target_arch = "wasm32"))]
 use crossterm::{cursor, terminal, ClearType};
 use rtlola_frontend::ir::RTLolaIR;
 use std::error::Error;
@@ -19,6 +23,9 @@ use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 pub enum EventSourceConfig {
     CSV { src: CSVInputSource },
     PCAP { src: PCAPInputSource },
+    MAVLink { src: MAVLinkInputSource },
+    CAN { src: CANInputSource },
+    HostMetrics { src: HostMetricsInputSource },
     API,
 }
 
@@ -45,6 +52,9 @@ pub(crate) fn create_event_source(
     match config {
         CSV { src } => CSVEventSource::setup(&src, ir, start_time),
         PCAP { src } => PCAPEventSource::setup(&src, ir, start_time),
+        MAVLink { src } => MAVLinkEventSource::setup(&src, ir, start_time),
+        CAN { src } => CANEventSource::setup(&src, ir, start_time),
+        HostMetrics { src } => HostMetricsEventSource::setup(&src, ir, start_time),
         API => unimplemented!("Currently, there is no need to create an event source for the API."),
     }
 }
@@ -67,6 +77,7 @@ pub struct OutputHandler {
     pub(crate) statistics: Option<Statistics>,
     pub(crate) start_time: Mutex<SystemTime>,
     time_representation: TimeRepresentation,
+    influx: Option<Mutex<InfluxSink>>,
 }
 
 impl OutputHandler {
@@ -80,13 +91,22 @@ impl OutputHandler {
         } else {
             None
         };
+        let influx = config.influx.clone().map(|cfg| {
+            let sink = InfluxSink::new(cfg).unwrap_or_else(|e| {
+                eprintln!("error: failed to set up InfluxDB output: {}", e);
+                std::process::exit(1)
+            });
+            Mutex::new(sink)
+        });
         OutputHandler {
             verbosity: config.verbosity,
             channel: config.output_channel.clone(),
             file: None,
             statistics,
-            start_time: Mutex::new(SystemTime::now()),
+            // there is no system clock on the web, so absolute times start at the epoch there
+            start_time: Mutex::new(if cfg!(target_arch = "wasm32") { UNIX_EPOCH } else { SystemTime::now() }),
             time_representation: config.time_presentation,
+            influx,
         }
     }
 
@@ -132,6 +152,46 @@ impl OutputHandler {
 
     #[allow(dead_code)]
     pub(crate) fn trigger<F, T: Into<String>>(&self, msg: F, trigger_idx: usize, time: Time)
+    where
+        F: FnOnce() -> T,
+    {
+        self.emit_timed(msg, time);
+        if let Some(statistics) = &self.statistics {
+            statistics.trigger(trigger_idx);
+        }
+    }
+
+    /// Reports a trigger that fired after an assumption it depends on was violated, so its verdict is vacuous.
+    pub(crate) fn vacuous_trigger<F, T: Into<String>>(&self, msg: F, trigger_idx: usize, time: Time)
+    where
+        F: FnOnce() -> T,
+    {
+        self.emit_timed(msg, time);
+        if let Some(statistics) = &self.statistics {
+            statistics.vacuous_trigger(trigger_idx);
+        }
+    }
+
+    /// Reports the violation of an assumption, which is counted separately from the triggers.
+    pub(crate) fn assumption<F, T: Into<String>>(&self, msg: F, trigger_idx: usize, time: Time)
+    where
+        F: FnOnce() -> T,
+    {
+        self.emit_timed(msg, time);
+        if let Some(statistics) = &self.statistics {
+            statistics.assumption(trigger_idx);
+        }
+    }
+
+    /// Reports the explanation of a notification, which already contains its time.
+    pub(crate) fn explanation<F, T: Into<String>>(&self, msg: F)
+    where
+        F: FnOnce() -> T,
+    {
+        self.emit(Verbosity::Triggers, msg);
+    }
+
+    fn emit_timed<F, T: Into<String>>(&self, msg: F, time: Time)
     where
         F: FnOnce() -> T,
     {
@@ -143,9 +203,6 @@ impl OutputHandler {
             }
         };
         self.emit(Verbosity::Triggers, msg);
-        if let Some(statistics) = &self.statistics {
-            statistics.trigger(trigger_idx);
-        }
     }
 
     #[allow(dead_code)]
@@ -164,6 +221,24 @@ impl OutputHandler {
         self.emit(Verbosity::Outputs, msg);
     }
 
+    /// Records the new value of an output stream for the InfluxDB output, if configured.
+    pub(crate) fn record_value(&self, stream: &str, value: &Value, time: Time) {
+        if let Some(influx) = &self.influx {
+            influx.lock().unwrap().value(stream, value, self.absolute_time(time));
+        }
+    }
+
+    /// Records the firing of a trigger for the InfluxDB output, if configured.
+    pub(crate) fn record_trigger(&self, stream: &str, message: &str, time: Time) {
+        if let Some(influx) = &self.influx {
+            influx.lock().unwrap().trigger(stream, message, self.absolute_time(time));
+        }
+    }
+
+    fn absolute_time(&self, time: Time) -> SystemTime {
+        *self.start_time.lock().unwrap() + time
+    }
+
     /// Accepts a message and forwards it to the appropriate output channel.
     /// If the configuration prohibits printing the message, `msg` is never called.
     fn emit<F, T: Into<String>>(&self, kind: Verbosity, msg: F)
@@ -191,6 +266,9 @@ impl OutputHandler {
     }
 
     pub(crate) fn terminate(&self) {
+        if let Some(influx) = &self.influx {
+            influx.lock().unwrap().flush();
+        }
         if let Some(statistics) = &self.statistics {
             if self.verbosity == Verbosity::Progress {
                 statistics.terminate();
@@ -204,6 +282,10 @@ struct StatisticsData {
     start: SystemTime,
     num_events: AtomicU64,
     num_triggers: Vec<AtomicU64>,
+    // Included in `num_triggers`.
+    num_vacuous: AtomicU64,
+    // Included in `num_triggers`.
+    num_assumption_violations: AtomicU64,
     done: Mutex<bool>,
 }
 
@@ -213,6 +295,8 @@ impl StatisticsData {
             start: SystemTime::now(),
             num_events: AtomicU64::new(0),
             num_triggers: (0..num_trigger).map(|_| AtomicU64::new(0)).collect(),
+            num_vacuous: AtomicU64::new(0),
+            num_assumption_violations: AtomicU64::new(0),
             done: Mutex::new(false),
         }
     }
@@ -257,6 +341,16 @@ impl Statistics {
         self.data.num_triggers[trigger_idx].fetch_add(1, Ordering::Relaxed);
     }
 
+    fn vacuous_trigger(&self, trigger_idx: usize) {
+        self.trigger(trigger_idx);
+        self.data.num_vacuous.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn assumption(&self, trigger_idx: usize) {
+        self.trigger(trigger_idx);
+        self.data.num_assumption_violations.fetch_add(1, Ordering::Relaxed);
+    }
+
     #[allow(clippy::mutex_atomic)]
     pub(crate) fn terminate(&self) {
         let mut done = self.data.done.lock().unwrap();
@@ -288,9 +382,23 @@ impl Statistics {
         // write trigger statistics
         let num_triggers =
             data.num_triggers.iter().fold(0, |val, num_trigger| val + num_trigger.load(Ordering::Relaxed));
-        writeln!(out, "  {} triggers", num_triggers).unwrap_or_else(|_| {});
+        let num_vacuous = data.num_vacuous.load(Ordering::Relaxed);
+        let num_assumption_violations = data.num_assumption_violations.load(Ordering::Relaxed);
+        if num_assumption_violations == 0 {
+            writeln!(out, "  {} triggers", num_triggers).unwrap_or_else(|_| {});
+        } else {
+            writeln!(
+                out,
+                "  {} triggers ({} vacuous), {} assumption violations",
+                num_triggers - num_assumption_violations,
+                num_vacuous,
+                num_assumption_violations
+            )
+            .unwrap_or_else(|_| {});
+        }
     }
 
+    #[cfg(not(target_arch = "wasm32"))]
     fn clear_progress_info() {
         let terminal = terminal();
         // clear screen as much as written in `print_progress_info`
@@ -300,8 +408,11 @@ impl Statistics {
         terminal.clear(ClearType::CurrentLine).unwrap_or_else(|_| {});
     }
 
-    #[cfg(test)]
+    /// There is no terminal to clear on the web.
+    #[cfg(target_arch = "wasm32")]
+    fn clear_progress_info() {}
+
     pub(crate) fn get_num_trigger(&self, trigger_idx: usize) -> u64 {
-        self.data.num_triggers[trigger_idx].fetch_add(1, Ordering::Relaxed)
+        self.data.num_triggers[trigger_idx].load(Ordering::Relaxed)
     }
 }
@@ -0,0 +1,406 @@
+use crate::basics::io_handler::EventSource;
+use crate::basics::Time;
+use crate::storage::Value;
+use ordered_float::NotNan;
+use rtlola_frontend::ir::{RTLolaIR, Type};
+use std::error::Error;
+use std::fs::File;
+use std::io::Read;
+use std::net::UdpSocket;
+use std::time::{Instant, SystemTime};
+
+// ################################
+// MAVLink Message Definitions
+// ################################
+
+/// A raw field value of a MAVLink message before conversion into the declared input type.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Field {
+    Unsigned(u64),
+    Signed(i64),
+    Float(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldTy {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    F32,
+}
+
+impl FieldTy {
+    fn size(self) -> usize {
+        use FieldTy::*;
+        match self {
+            U8 | I8 => 1,
+            U16 | I16 => 2,
+            U32 | I32 | F32 => 4,
+            U64 => 8,
+        }
+    }
+
+    fn read(self, bytes: &[u8]) -> Field {
+        use std::convert::TryInto;
+        use FieldTy::*;
+        match self {
+            U8 => Field::Unsigned(u64::from(bytes[0])),
+            I8 => Field::Signed(i64::from(bytes[0] as i8)),
+            U16 => Field::Unsigned(u64::from(u16::from_le_bytes(bytes[..2].try_into().unwrap()))),
+            I16 => Field::Signed(i64::from(i16::from_le_bytes(bytes[..2].try_into().unwrap()))),
+            U32 => Field::Unsigned(u64::from(u32::from_le_bytes(bytes[..4].try_into().unwrap()))),
+            I32 => Field::Signed(i64::from(i32::from_le_bytes(bytes[..4].try_into().unwrap()))),
+            U64 => Field::Unsigned(u64::from_le_bytes(bytes[..8].try_into().unwrap())),
+            F32 => Field::Float(f64::from(f32::from_le_bytes(bytes[..4].try_into().unwrap()))),
+        }
+    }
+}
+
+/// A supported MAVLink message; fields are given in wire order, i.e., sorted by size.
+#[derive(Debug)]
+struct MessageDef {
+    name: &'static str,
+    id: u32,
+    crc_extra: u8,
+    fields: &'static [(&'static str, FieldTy)],
+}
+
+impl MessageDef {
+    fn payload_len(&self) -> usize {
+        self.fields.iter().map(|(_, ty)| ty.size()).sum()
+    }
+
+    fn offset_of(&self, field: &str) -> Option<(usize, FieldTy)> {
+        let mut offset = 0;
+        for (name, ty) in self.fields {
+            if *name == field {
+                return Some((offset, *ty));
+            }
+            offset += ty.size();
+        }
+        None
+    }
+}
+
+const MESSAGES: &[MessageDef] = &[
+    MessageDef {
+        name: "SYS_STATUS",
+        id: 1,
+        crc_extra: 124,
+        fields: &[
+            ("onboard_control_sensors_present", FieldTy::U32),
+            ("onboard_control_sensors_enabled", FieldTy::U32),
+            ("onboard_control_sensors_health", FieldTy::U32),
+            ("load", FieldTy::U16),
+            ("voltage_battery", FieldTy::U16),
+            ("current_battery", FieldTy::I16),
+            ("drop_rate_comm", FieldTy::U16),
+            ("errors_comm", FieldTy::U16),
+            ("errors_count1", FieldTy::U16),
+            ("errors_count2", FieldTy::U16),
+            ("errors_count3", FieldTy::U16),
+            ("errors_count4", FieldTy::U16),
+            ("battery_remaining", FieldTy::I8),
+        ],
+    },
+    MessageDef {
+        name: "GPS_RAW_INT",
+        id: 24,
+        crc_extra: 24,
+        fields: &[
+            ("time_usec", FieldTy::U64),
+            ("lat", FieldTy::I32),
+            ("lon", FieldTy::I32),
+            ("alt", FieldTy::I32),
+            ("eph", FieldTy::U16),
+            ("epv", FieldTy::U16),
+            ("vel", FieldTy::U16),
+            ("cog", FieldTy::U16),
+            ("fix_type", FieldTy::U8),
+            ("satellites_visible", FieldTy::U8),
+        ],
+    },
+    MessageDef {
+        name: "ATTITUDE",
+        id: 30,
+        crc_extra: 39,
+        fields: &[
+            ("time_boot_ms", FieldTy::U32),
+            ("roll", FieldTy::F32),
+            ("pitch", FieldTy::F32),
+            ("yaw", FieldTy::F32),
+            ("rollspeed", FieldTy::F32),
+            ("pitchspeed", FieldTy::F32),
+            ("yawspeed", FieldTy::F32),
+        ],
+    },
+];
+
+// ################################
+// Frame Parsing
+// ################################
+
+const MAGIC_V1: u8 = 0xFE;
+const MAGIC_V2: u8 = 0xFD;
+const SIGNATURE_LEN: usize = 13;
+
+/// Computes the X.25 checksum used by MAVLink.
+fn crc_accumulate(crc: u16, byte: u8) -> u16 {
+    let tmp = byte ^ (crc & 0xFF) as u8;
+    let tmp = tmp ^ (tmp << 4);
+    (crc >> 8) ^ (u16::from(tmp) << 8) ^ (u16::from(tmp) << 3) ^ (u16::from(tmp) >> 4)
+}
+
+fn checksum(bytes: &[u8], crc_extra: u8) -> u16 {
+    let crc = bytes.iter().fold(0xFFFF, |crc, b| crc_accumulate(crc, *b));
+    crc_accumulate(crc, crc_extra)
+}
+
+/// A decoded MAVLink message with its payload padded to the full length.
+#[derive(Debug, PartialEq)]
+struct Message {
+    def: usize,
+    payload: Vec<u8>,
+}
+
+/// Tries to decode a single frame at the start of `buf`.
+/// Returns the number of consumed bytes and the message, if the frame is complete, valid, and supported.
+/// Returns `None` if more bytes are required.
+fn decode_frame(buf: &[u8]) -> Option<(usize, Option<Message>)> {
+    let (header_len, payload_len, msg_id, signed) = match *buf.first()? {
+        MAGIC_V1 => {
+            if buf.len() < 6 {
+                return None;
+            }
+            (6, usize::from(buf[1]), u32::from(buf[5]), false)
+        }
+        MAGIC_V2 => {
+            if buf.len() < 10 {
+                return None;
+            }
+            let id = u32::from(buf[7]) | (u32::from(buf[8]) << 8) | (u32::from(buf[9]) << 16);
+            (10, usize::from(buf[1]), id, buf[2] & 0x01 != 0)
+        }
+        // Skip garbage until the next start marker.
+        _ => return Some((1, None)),
+    };
+    let frame_len = header_len + payload_len + 2 + if signed { SIGNATURE_LEN } else { 0 };
+    if buf.len() < frame_len {
+        return None;
+    }
+    let def = match MESSAGES.iter().position(|m| m.id == msg_id) {
+        Some(def) => def,
+        None => return Some((frame_len, None)),
+    };
+    let crc_ix = header_len + payload_len;
+    let expected = u16::from_le_bytes([buf[crc_ix], buf[crc_ix + 1]]);
+    if checksum(&buf[1..crc_ix], MESSAGES[def].crc_extra) != expected {
+        // Corrupted frame; resynchronize after the start marker.
+        return Some((1, None));
+    }
+    // MAVLink 2 truncates trailing zeros of the payload, extension fields are ignored.
+    let mut payload = buf[header_len..crc_ix].to_vec();
+    payload.resize(MESSAGES[def].payload_len().max(payload_len), 0);
+    Some((frame_len, Some(Message { def, payload })))
+}
+
+// ################################
+// Event Source Handling
+// ################################
+
+#[derive(Debug, Clone)]
+pub enum MAVLinkInputSource {
+    /// Receives MAVLink frames as UDP datagrams on the given address, e.g., `0.0.0.0:14550`.
+    Udp { addr: String },
+    /// Reads MAVLink frames from a serial device that is already configured, e.g., `/dev/ttyUSB0`.
+    Serial { path: String },
+}
+
+#[derive(Debug)]
+enum Connection {
+    Udp(UdpSocket),
+    Serial(File),
+}
+
+impl Connection {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            Connection::Udp(socket) => socket.recv(buf),
+            Connection::Serial(file) => file.read(buf),
+        }
+    }
+}
+
+/// An event source decoding MAVLink telemetry.
+///
+/// Input streams are named `MESSAGE::field`, e.g., `ATTITUDE::roll`, `GPS_RAW_INT::lat`, or
+/// `SYS_STATUS::voltage_battery`, and receive a value whenever the respective message arrives.
+#[derive(Debug)]
+pub struct MAVLinkEventSource {
+    connection: Connection,
+    buffer: Vec<u8>,
+    /// For each input stream the index of the message and the offset and type of the field.
+    mapping: Vec<(usize, usize, FieldTy)>,
+    in_types: Vec<Type>,
+    start: Instant,
+    event: Option<(Vec<Value>, Time)>,
+}
+
+impl MAVLinkEventSource {
+    pub(crate) fn setup(
+        src: &MAVLinkInputSource,
+        ir: &RTLolaIR,
+        start_time: Instant,
+    ) -> Result<Box<dyn EventSource>, Box<dyn Error>> {
+        let connection = match src {
+            MAVLinkInputSource::Udp { addr } => Connection::Udp(UdpSocket::bind(addr)?),
+            MAVLinkInputSource::Serial { path } => Connection::Serial(File::open(path)?),
+        };
+
+        let mut mapping = Vec::with_capacity(ir.inputs.len());
+        for input in &ir.inputs {
+            let layers: Vec<&str> = input.name.split("::").collect();
+            if layers.len() != 2 {
+                eprintln!("Malformed input name: {}", input.name);
+                std::process::exit(1);
+            }
+            let field = MESSAGES.iter().enumerate().find(|(_, m)| m.name == layers[0]).and_then(|(ix, m)| {
+                let (offset, ty) = m.offset_of(layers[1])?;
+                Some((ix, offset, ty))
+            });
+            match field {
+                Some(field) => mapping.push(field),
+                None => {
+                    eprintln!("Unknown input name: {}", input.name);
+                    std::process::exit(1);
+                }
+            }
+        }
+        let in_types = ir.inputs.iter().map(|i| i.ty.clone()).collect();
+
+        Ok(Box::new(MAVLinkEventSource {
+            connection,
+            buffer: Vec::new(),
+            mapping,
+            in_types,
+            start: start_time,
+            event: None,
+        }))
+    }
+
+    fn next_message(&mut self) -> Result<Option<Message>, Box<dyn Error>> {
+        let mut chunk = [0u8; 2048];
+        loop {
+            while let Some((consumed, msg)) = decode_frame(&self.buffer) {
+                self.buffer.drain(..consumed);
+                if msg.is_some() {
+                    return Ok(msg);
+                }
+            }
+            let read = self.connection.read(&mut chunk)?;
+            if read == 0 {
+                return Ok(None);
+            }
+            self.buffer.extend_from_slice(&chunk[..read]);
+        }
+    }
+
+    fn convert(field: Field, ty: &Type) -> Value {
+        match (field, ty) {
+            (Field::Unsigned(u), Type::UInt(_)) => Value::Unsigned(u),
+            (Field::Unsigned(u), Type::Int(_)) => Value::Signed(u as i64),
+            (Field::Signed(i), Type::Int(_)) => Value::Signed(i),
+            (Field::Signed(i), Type::UInt(_)) if i >= 0 => Value::Unsigned(i as u64),
+            (Field::Unsigned(u), Type::Float(_)) => Value::Float(NotNan::new(u as f64).unwrap()),
+            (Field::Signed(i), Type::Float(_)) => Value::Float(NotNan::new(i as f64).unwrap()),
+            (Field::Float(f), Type::Float(_)) => NotNan::new(f).map(Value::Float).unwrap_or(Value::None),
+            (Field::Unsigned(u), Type::Bool) => Value::Bool(u != 0),
+            (field, ty) => {
+                eprintln!("error: problem with data source; failed to convert {:?} to value of type {}.", field, ty);
+                std::process::exit(1)
+            }
+        }
+    }
+}
+
+impl EventSource for MAVLinkEventSource {
+    fn has_event(&mut self) -> bool {
+        let msg = match self.next_message() {
+            Ok(Some(msg)) => msg,
+            Ok(None) => return false,
+            Err(e) => {
+                eprintln!("error: failed to read MAVLink data. {}", e);
+                std::process::exit(1)
+            }
+        };
+        let event =
+            self.mapping
+                .iter()
+                .zip(&self.in_types)
+                .map(|((def, offset, field_ty), ty)| {
+                    if *def == msg.def {
+                        Self::convert(field_ty.read(&msg.payload[*offset..]), ty)
+                    } else {
+                        Value::None
+                    }
+                })
+                .collect();
+        self.event = Some((event, Instant::now() - self.start));
+        true
+    }
+
+    fn get_event(&mut self) -> (Vec<Value>, Time) {
+        self.event.take().unwrap_or_else(|| {
+            eprintln!("No event available!");
+            std::process::exit(1);
+        })
+    }
+
+    fn read_time(&self) -> Option<SystemTime> {
+        Some(SystemTime::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame_v1(msg_id: u8, crc_extra: u8, payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![MAGIC_V1, payload.len() as u8, 0, 1, 1, msg_id];
+        frame.extend_from_slice(payload);
+        let crc = checksum(&frame[1..], crc_extra);
+        frame.extend_from_slice(&crc.to_le_bytes());
+        frame
+    }
+
+    #[test]
+    fn decode_attitude() {
+        let mut payload = vec![];
+        payload.extend_from_slice(&1000u32.to_le_bytes());
+        for v in &[0.5f32, -0.25, 1.0, 0.0, 0.0, 0.0] {
+            payload.extend_from_slice(&v.to_le_bytes());
+        }
+        let mut buf = vec![0x42, 0x17];
+        buf.extend(frame_v1(30, 39, &payload));
+        let (consumed, msg) = decode_frame(&buf).unwrap();
+        assert_eq!((consumed, msg), (1, None));
+        let (_, msg) = decode_frame(&buf[2..]).unwrap();
+        let msg = msg.unwrap();
+        let def = &MESSAGES[msg.def];
+        assert_eq!(def.name, "ATTITUDE");
+        let (offset, ty) = def.offset_of("pitch").unwrap();
+        assert_eq!(ty.read(&msg.payload[offset..]), Field::Float(-0.25));
+    }
+
+    #[test]
+    fn reject_corrupted_frame() {
+        let mut frame = frame_v1(30, 39, &[0; 28]);
+        frame[10] ^= 0xFF;
+        assert_eq!(decode_frame(&frame), Some((1, None)));
+        assert_eq!(decode_frame(&frame[..4]), None);
+    }
+}
@@ -1,17 +1,32 @@
+mod can_input;
 mod config;
 mod csv_input;
+mod host_metrics_input;
+mod influx_output;
 mod io_handler;
+mod mavlink_input;
 mod pcap_input;
 
 pub(crate) type Time = Duration;
 
+pub(crate) use self::config::{Aggregations, Functions};
 pub use self::config::{
-    EvalConfig, EvaluatorChoice, ExecutionMode, Statistics, TimeFormat, TimeRepresentation, Verbosity,
+    EvalConfig, EvaluatorChoice, ExecutionMode, FunctionImpl, Statistics, TimeFormat, TimeRepresentation, Verbosity,
 };
 pub use self::io_handler::OutputChannel;
 pub(crate) use self::io_handler::{create_event_source, EventSource, EventSourceConfig, OutputHandler};
 
+pub(crate) use self::csv_input::parse_time;
 pub use self::csv_input::{CSVEventSource, CSVInputSource};
 
+pub(crate) use self::influx_output::InfluxSink;
+pub use self::influx_output::{InfluxConfig, InfluxTarget};
+
+pub use self::can_input::{CANEventSource, CANInputSource};
+
+pub use self::host_metrics_input::{HostMetricsEventSource, HostMetricsInputSource};
+
+pub use self::mavlink_input::{MAVLinkEventSource, MAVLinkInputSource};
+
 pub use self::pcap_input::{PCAPEventSource, PCAPInputSource};
 use std::time::Duration;
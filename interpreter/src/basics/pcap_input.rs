@@ -8,7 +8,6 @@ use etherparse::{
     UdpHeader,
 };
 use ip_network::IpNetwork;
-use pcap_on_demand::{Activated, Capture, Device, Error as PCAPError};
 use rtlola_frontend::ir::RTLolaIR;
 use std::error::Error;
 use std::net::IpAddr;
@@ -413,6 +412,88 @@ fn get_packet_protocol(packet: &SlicedPacket) -> Value {
     }
 }
 
+// ################################
+// Packet Capture
+// ################################
+
+#[cfg(not(target_arch = "wasm32"))]
+#[allow(unsafe_code)]
+mod capture {
+    use super::PCAPInputSource;
+    use pcap_on_demand::{Activated, Capture, Device, Error as PCAPError};
+    use std::error::Error;
+    use std::time::Duration;
+
+    pub(super) fn load_library() -> Result<(), Box<dyn Error>> {
+        unsafe { pcap_on_demand::load_pcap_library() }.map_err(|e| e.to_string().into())
+    }
+
+    pub(super) struct PacketCapture(Capture<dyn Activated>);
+
+    impl PacketCapture {
+        pub(super) fn open(src: &PCAPInputSource) -> Result<PacketCapture, Box<dyn Error>> {
+            let capture_handle = match src {
+                PCAPInputSource::Device { name, .. } => {
+                    let all_devices = Device::list()?;
+                    let dev: Device = all_devices.into_iter().filter(|d| d.name == *name).nth(0).unwrap_or_else(|| {
+                        eprintln!("Could not find network interface with name: {}", *name);
+                        std::process::exit(1);
+                    });
+
+                    let capture_handle = Capture::from_device(dev)?.promisc(true).snaplen(65535).open()?;
+                    capture_handle.into()
+                }
+                PCAPInputSource::File { path, .. } => {
+                    let capture_handle = Capture::from_file(path)?;
+                    capture_handle.into()
+                }
+            };
+            Ok(PacketCapture(capture_handle))
+        }
+
+        /// Returns the time stamp and the data of the next packet, `None` if there are no more packets.
+        pub(super) fn next(&mut self) -> Result<Option<(Duration, &[u8])>, Box<dyn Error>> {
+            let raw_packet = match self.0.next() {
+                Ok(pkt) => pkt,
+                Err(e) => match e {
+                    PCAPError::NoMorePackets => return Ok(None),
+                    _ => return Err(e.into()),
+                },
+            };
+
+            use std::convert::TryInto;
+            let d = Duration::new(
+                raw_packet.header.ts.tv_sec.try_into().unwrap(),
+                (raw_packet.header.ts.tv_usec * 1000).try_into().unwrap(),
+            );
+            Ok(Some((d, raw_packet.data)))
+        }
+    }
+}
+
+#[cfg(target_arch = "wasm32")]
+mod capture {
+    use super::PCAPInputSource;
+    use std::error::Error;
+    use std::time::Duration;
+
+    pub(super) fn load_library() -> Result<(), Box<dyn Error>> {
+        Err("libpcap is not available on WebAssembly".into())
+    }
+
+    pub(super) struct PacketCapture {}
+
+    impl PacketCapture {
+        pub(super) fn open(_src: &PCAPInputSource) -> Result<PacketCapture, Box<dyn Error>> {
+            Err("libpcap is not available on WebAssembly".into())
+        }
+
+        pub(super) fn next(&mut self) -> Result<Option<(Duration, &[u8])>, Box<dyn Error>> {
+            unreachable!("The capture cannot be opened.")
+        }
+    }
+}
+
 // ################################
 // Event Source Handling
 // ################################
@@ -430,7 +511,7 @@ enum TimeHandling {
 
 #[allow(missing_debug_implementations)] // Capture -> PcapOnDemand does not implement Debug.
 pub struct PCAPEventSource {
-    capture_handle: Capture<dyn Activated>,
+    capture_handle: capture::PacketCapture,
     timer: TimeHandling,
     mapping: Vec<Box<dyn Fn(&SlicedPacket) -> Value>>,
 
@@ -439,27 +520,17 @@ pub struct PCAPEventSource {
 }
 
 impl PCAPEventSource {
+    /// Loads libpcap, which is needed before any capture is opened.
+    pub(crate) fn load_library() -> Result<(), Box<dyn Error>> {
+        capture::load_library()
+    }
+
     pub(crate) fn setup(
         src: &PCAPInputSource,
         ir: &RTLolaIR,
         start_time: Instant,
     ) -> Result<Box<dyn EventSource>, Box<dyn Error>> {
-        let capture_handle = match src {
-            PCAPInputSource::Device { name, .. } => {
-                let all_devices = Device::list()?;
-                let dev: Device = all_devices.into_iter().filter(|d| d.name == *name).nth(0).unwrap_or_else(|| {
-                    eprintln!("Could not find network interface with name: {}", *name);
-                    std::process::exit(1);
-                });
-
-                let capture_handle = Capture::from_device(dev)?.promisc(true).snaplen(65535).open()?;
-                capture_handle.into()
-            }
-            PCAPInputSource::File { path, .. } => {
-                let capture_handle = Capture::from_file(path)?;
-                capture_handle.into()
-            }
-        };
+        let capture_handle = capture::PacketCapture::open(src)?;
 
         let local_network_range = match src {
             PCAPInputSource::Device { local_network, .. } => local_network,
@@ -648,22 +719,13 @@ impl PCAPEventSource {
     }
 
     fn process_packet(&mut self) -> Result<bool, Box<dyn Error>> {
-        let raw_packet = match self.capture_handle.next() {
-            Ok(pkt) => pkt,
-            Err(e) => match e {
-                PCAPError::NoMorePackets => return Ok(false),
-                _ => return Err(e.into()),
-            },
+        let (d, data) = match self.capture_handle.next()? {
+            Some(packet) => packet,
+            None => return Ok(false),
         };
-
-        use std::convert::TryInto;
-        let d = Duration::new(
-            raw_packet.header.ts.tv_sec.try_into().unwrap(),
-            (raw_packet.header.ts.tv_usec * 1000).try_into().unwrap(),
-        );
         self.last_timestamp = Some(UNIX_EPOCH + d);
 
-        let p = SlicedPacket::from_ethernet(raw_packet.data);
+        let p = SlicedPacket::from_ethernet(data);
         //Todo (Florian): Track underlying error
         if p.is_err() {
             return Ok(false);
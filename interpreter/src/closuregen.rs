@@ -42,6 +42,7 @@ impl Expr for Expression {
                 };
                 CompiledExpr::new(move |_| v.clone())
             }
+            ConfigurableConstant(_) => unreachable!("configurable constants are substituted before the compilation"),
 
             ArithLog(op, operands, _ty) => {
                 let f_operands: Vec<CompiledExpr> = operands.into_iter().map(|e| e.compile()).collect();
@@ -116,7 +117,8 @@ impl Expr for Expression {
 
             OffsetLookup { target, offset } => {
                 let offset = match offset {
-                    Offset::FutureDiscreteOffset(_) | Offset::FutureRealTimeOffset(_) => unimplemented!(),
+                    Offset::FutureDiscreteOffset(_) => unreachable!("resolved by the lowering"),
+                    Offset::FutureRealTimeOffset(_) => unimplemented!(),
                     Offset::PastDiscreteOffset(u) => -(u as i16),
                     Offset::PastRealTimeOffset(_dur) => unimplemented!(),
                 };
@@ -152,6 +154,8 @@ impl Expr for Expression {
 
             WindowLookup(win_ref) => CompiledExpr::new(move |ctx| ctx.lookup_window(win_ref)),
 
+            Now => CompiledExpr::new(|ctx| ctx.now()),
+
             Ite { condition, consequence, alternative, .. } => {
                 let f_condition = condition.compile();
                 let f_consequence = consequence.compile();
@@ -172,6 +176,16 @@ impl Expr for Expression {
                 CompiledExpr::new(move |ctx| Value::Tuple(f_entries.iter().map(|f| f.execute(ctx)).collect()))
             }
 
+            ParameterAccess(ix) => CompiledExpr::new(move |ctx| ctx.parameters[ix].clone()),
+
+            InstanceAccess(target, args) => {
+                let f_args: Vec<CompiledExpr> = args.into_iter().map(|e| e.compile()).collect();
+                CompiledExpr::new(move |ctx| {
+                    let parameters: Vec<Value> = f_args.iter().map(|f| f.execute(ctx)).collect();
+                    ctx.lookup_instance(target, &parameters)
+                })
+            }
+
             Function(name, args, ty) => {
                 //TODO(marvin): handle type
                 assert!(!args.is_empty());
@@ -179,10 +193,13 @@ impl Expr for Expression {
 
                 macro_rules! create_floatfn {
                     ($fn:ident) => {
+                        create_floatfn!($fn, new_float)
+                    };
+                    ($fn:ident, $new:ident) => {
                         CompiledExpr::new(move |ctx| {
                             let arg = f_arg.execute(ctx);
                             match arg {
-                                Value::Float(f) => Value::new_float(f.$fn()),
+                                Value::Float(f) => Value::$new(f.$fn()),
                                 _ => unreachable!(),
                             }
                         })
@@ -207,21 +224,215 @@ impl Expr for Expression {
                     }};
                 }
 
+                macro_rules! create_binary_floatfn {
+                    ($fn:ident) => {{
+                        assert_eq!(args.len(), 2);
+                        let f_snd = args[1].clone().compile();
+                        CompiledExpr::new(move |ctx| match (f_arg.execute(ctx), f_snd.execute(ctx)) {
+                            (Value::Float(f1), Value::Float(f2)) => Value::new_float(f1.$fn(*f2)),
+                            (v1, v2) => unreachable!("wrong Value types of {:?}, {:?} for function $fn", v1, v2),
+                        })
+                    }};
+                }
+
                 match name.as_ref() {
                     "sqrt" => create_floatfn!(sqrt),
                     "sin" => create_floatfn!(sin),
                     "cos" => create_floatfn!(cos),
                     "arctan" => create_floatfn!(atan),
+                    "atan2" => create_binary_floatfn!(atan2),
+                    "hypot" => create_binary_floatfn!(hypot),
+                    "ln" => create_floatfn!(ln, new_log),
+                    "log2" => create_floatfn!(log2, new_log),
+                    "log10" => create_floatfn!(log10, new_log),
+                    "exp2" => create_floatfn!(exp2),
+                    "log" => {
+                        assert_eq!(args.len(), 2);
+                        let f_x = args[1].clone().compile();
+                        CompiledExpr::new(move |ctx| match (f_arg.execute(ctx), f_x.execute(ctx)) {
+                            (Value::Float(base), Value::Float(x)) => Value::new_log(x.into_inner().log(*base)),
+                            (base, x) => unreachable!("wrong Value types of {:?}, {:?} for function log", base, x),
+                        })
+                    }
                     "abs" => CompiledExpr::new(move |ctx| {
                         let arg = f_arg.execute(ctx);
                         match arg {
                             Value::Float(f) => Value::new_float(f.abs()),
                             Value::Signed(i) => Value::Signed(i.abs()),
+                            Value::Unsigned(u) => Value::Unsigned(u),
                             v => unreachable!("wrong Value type of {:?}, for function abs", v),
                         }
                     }),
+                    "is_some" => CompiledExpr::new(move |ctx| Value::Bool(f_arg.execute(ctx) != Value::None)),
                     "min" => create_binary_arith!(min),
                     "max" => create_binary_arith!(max),
+                    "clamp" => {
+                        assert_eq!(args.len(), 3);
+                        let f_lo = args[1].clone().compile();
+                        let f_hi = args[2].clone().compile();
+                        CompiledExpr::new(move |ctx| match (f_arg.execute(ctx), f_lo.execute(ctx), f_hi.execute(ctx)) {
+                            (Value::Float(f), Value::Float(lo), Value::Float(hi)) => Value::Float(f.max(lo).min(hi)),
+                            (Value::Signed(s), Value::Signed(lo), Value::Signed(hi)) => {
+                                Value::Signed(s.max(lo).min(hi))
+                            }
+                            (Value::Unsigned(u), Value::Unsigned(lo), Value::Unsigned(hi)) => {
+                                Value::Unsigned(u.max(lo).min(hi))
+                            }
+                            (v, lo, hi) => {
+                                unreachable!("wrong Value types of {:?}, {:?}, {:?} for function clamp", v, lo, hi)
+                            }
+                        })
+                    }
+                    "to_int" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).to_int()),
+                    "to_float" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).to_float()),
+                    "round_to_int" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).round_to_int()),
+                    "trunc_to_int" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).trunc_to_int()),
+                    "haversine" | "bearing" => {
+                        assert_eq!(args.len(), 4);
+                        let f_rest: Vec<CompiledExpr> = args[1..].iter().map(|a| a.clone().compile()).collect();
+                        let is_haversine = name == "haversine";
+                        CompiledExpr::new(move |ctx| {
+                            let fst = f_arg.execute(ctx);
+                            let rest: Vec<Value> = f_rest.iter().map(|f| f.execute(ctx)).collect();
+                            if is_haversine {
+                                Value::haversine(&fst, &rest[0], &rest[1], &rest[2])
+                            } else {
+                                Value::bearing(&fst, &rest[0], &rest[1], &rest[2])
+                            }
+                        })
+                    }
+                    "wrap_angle_deg" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).wrap_angle_deg()),
+                    "wrap_angle_rad" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).wrap_angle_rad()),
+                    "angle_diff" => {
+                        assert_eq!(args.len(), 2);
+                        let f_snd = args[1].clone().compile();
+                        CompiledExpr::new(move |ctx| f_arg.execute(ctx).angle_diff(&f_snd.execute(ctx)))
+                    }
+                    "checked_add" | "checked_sub" | "checked_mul" | "checked_div" => {
+                        assert_eq!(args.len(), 2);
+                        let f_snd = args[1].clone().compile();
+                        let width = Value::width_of_arg(&ty);
+                        let op: fn(i128, i128) -> Option<i128> = match name.as_ref() {
+                            "checked_add" => i128::checked_add,
+                            "checked_sub" => i128::checked_sub,
+                            "checked_mul" => i128::checked_mul,
+                            _ => i128::checked_div,
+                        };
+                        CompiledExpr::new(move |ctx| f_arg.execute(ctx).checked_arith(&f_snd.execute(ctx), op, width))
+                    }
+                    "saturating_add" | "saturating_sub" | "saturating_mul" => {
+                        assert_eq!(args.len(), 2);
+                        let f_snd = args[1].clone().compile();
+                        let width = Value::width_of_arg(&ty);
+                        let op: fn(i128, i128) -> i128 = match name.as_ref() {
+                            "saturating_add" => |a, b| a + b,
+                            "saturating_sub" => |a, b| a - b,
+                            // Products of unsigned 64-bit integers may exceed `i128`.
+                            _ => |a, b| {
+                                a.checked_mul(b).unwrap_or(if (a < 0) == (b < 0) { i128::MAX } else { i128::MIN })
+                            },
+                        };
+                        CompiledExpr::new(move |ctx| {
+                            f_arg.execute(ctx).saturating_arith(&f_snd.execute(ctx), op, width)
+                        })
+                    }
+                    "lerp" | "map_range" => {
+                        let f_rest: Vec<CompiledExpr> = args[1..].iter().map(|a| a.clone().compile()).collect();
+                        if name == "lerp" {
+                            assert_eq!(args.len(), 3);
+                            CompiledExpr::new(move |ctx| {
+                                Value::lerp(&f_arg.execute(ctx), &f_rest[0].execute(ctx), &f_rest[1].execute(ctx))
+                            })
+                        } else {
+                            assert_eq!(args.len(), 5);
+                            CompiledExpr::new(move |ctx| {
+                                let rest: Vec<Value> = f_rest.iter().map(|f| f.execute(ctx)).collect();
+                                Value::map_range(&f_arg.execute(ctx), &rest[0], &rest[1], &rest[2], &rest[3])
+                            })
+                        }
+                    }
+                    "zscore" => {
+                        assert_eq!(args.len(), 3);
+                        let f_mean = args[1].clone().compile();
+                        let f_stddev = args[2].clone().compile();
+                        CompiledExpr::new(move |ctx| {
+                            f_arg.execute(ctx).zscore(&f_mean.execute(ctx), &f_stddev.execute(ctx))
+                        })
+                    }
+                    "hour_of" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).hour_of()),
+                    "day_of_week" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).day_of_week()),
+                    "since_midnight" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).since_midnight()),
+                    // durations are represented by their length in seconds
+                    "seconds" => f_arg,
+                    "millis" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).millis()),
+                    "to_duration" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).to_float()),
+                    "crc16" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).crc16()),
+                    "crc32" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).crc32()),
+                    "hash64" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).hash64()),
+                    "bit" | "bits" | "popcount" | "set_bit" => {
+                        let width = Value::width_of_arg(&ty);
+                        let f_rest: Vec<CompiledExpr> = args[1..].iter().map(|a| a.clone().compile()).collect();
+                        match name.as_ref() {
+                            "bit" => {
+                                CompiledExpr::new(move |ctx| f_arg.execute(ctx).bit(&f_rest[0].execute(ctx), width))
+                            }
+                            "bits" => CompiledExpr::new(move |ctx| {
+                                f_arg.execute(ctx).bits(&f_rest[0].execute(ctx), &f_rest[1].execute(ctx), width)
+                            }),
+                            "popcount" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).popcount(width)),
+                            _ => CompiledExpr::new(move |ctx| {
+                                f_arg.execute(ctx).set_bit(&f_rest[0].execute(ctx), &f_rest[1].execute(ctx), width)
+                            }),
+                        }
+                    }
+                    "floor" | "ceil" | "round" => {
+                        let f_digits = args.get(1).map(|d| d.clone().compile());
+                        let op: fn(&Value, Option<&Value>) -> Value = match name.as_ref() {
+                            "floor" => Value::floor,
+                            "ceil" => Value::ceil,
+                            _ => Value::round,
+                        };
+                        CompiledExpr::new(move |ctx| {
+                            let digits = f_digits.as_ref().map(|f| f.execute(ctx));
+                            op(&f_arg.execute(ctx), digits.as_ref())
+                        })
+                    }
+                    "length" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).length()),
+                    "contains" | "starts_with" | "ends_with" => {
+                        assert_eq!(args.len(), 2);
+                        let f_pattern = args[1].clone().compile();
+                        let op: fn(&Value, &Value) -> Value = match name.as_ref() {
+                            "contains" => Value::contains,
+                            "starts_with" => Value::starts_with,
+                            _ => Value::ends_with,
+                        };
+                        CompiledExpr::new(move |ctx| op(&f_arg.execute(ctx), &f_pattern.execute(ctx)))
+                    }
+                    "u16_le" | "u16_be" | "i32_le" | "i32_be" | "f32_le" | "f32_be" => {
+                        assert_eq!(args.len(), 2);
+                        let f_offset = args[1].clone().compile();
+                        let decode: fn(&Value, &Value) -> Value = match name.as_ref() {
+                            "u16_le" => Value::u16_le,
+                            "u16_be" => Value::u16_be,
+                            "i32_le" => Value::i32_le,
+                            "i32_be" => Value::i32_be,
+                            "f32_le" => Value::f32_le,
+                            _ => Value::f32_be,
+                        };
+                        CompiledExpr::new(move |ctx| decode(&f_arg.execute(ctx), &f_offset.execute(ctx)))
+                    }
+                    "to_upper" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).to_upper()),
+                    "to_lower" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).to_lower()),
+                    "parse_int" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).parse_int()),
+                    "parse_float" => CompiledExpr::new(move |ctx| f_arg.execute(ctx).parse_float()),
+                    "substring" => {
+                        assert_eq!(args.len(), 3);
+                        let f_from = args[1].clone().compile();
+                        let f_to = args[2].clone().compile();
+                        CompiledExpr::new(move |ctx| {
+                            f_arg.execute(ctx).substring(&f_from.execute(ctx), &f_to.execute(ctx))
+                        })
+                    }
                     "matches" => {
                         assert!(args.len() >= 2);
                         let operand_ty = match &ty {
@@ -273,7 +484,14 @@ impl Expr for Expression {
                             }
                         })
                     }
-                    f => unreachable!("Unknown function: {}, args: {:?}", f, args),
+                    _ => {
+                        let f_rest: Vec<CompiledExpr> = args[1..].iter().map(|a| a.clone().compile()).collect();
+                        CompiledExpr::new(move |ctx| {
+                            let mut values = vec![f_arg.execute(ctx)];
+                            values.extend(f_rest.iter().map(|f| f.execute(ctx)));
+                            ctx.functions.call(&name, &values)
+                        })
+                    }
                 }
             }
 
@@ -338,6 +556,11 @@ impl Expr for Expression {
                 }
             }
 
+            Cast { to, expr, .. } => {
+                let f_expr = expr.compile();
+                CompiledExpr::new(move |ctx| f_expr.execute(ctx).checked_cast(&to))
+            }
+
             Default { expr, default, .. } => {
                 let f_expr = expr.compile();
                 let f_default = default.compile();
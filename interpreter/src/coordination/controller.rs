@@ -29,7 +29,8 @@ impl Controller {
         Self { ir, config, output_handler }
     }
 
-    pub(crate) fn start(self) -> Result<Either<Monitor, Arc<OutputHandler>>, Box<dyn Error>> {
+    pub(crate) fn start(mut self) -> Result<Either<Monitor, Arc<OutputHandler>>, Box<dyn Error>> {
+        self.ir.configure(&self.config.constants)?;
         // TODO: Returning the Arc here makes no sense, fix asap.
         match self.config.mode {
             Offline => self.evaluate_offline().map(|_| Either::Right(self.output_handler)),
@@ -72,7 +73,7 @@ impl Controller {
         });
 
         let copy_output_handler = self.output_handler.clone();
-        let evaluatordata = EvaluatorData::new(self.ir.clone(), self.config.clone(), copy_output_handler, now);
+        let evaluatordata = EvaluatorData::new(self.ir.clone(), self.config.clone(), copy_output_handler, Some(now));
 
         let mut evaluator = evaluatordata.into_evaluator();
 
@@ -138,8 +139,7 @@ impl Controller {
         let mut deadline_cycle = time_manager.get_deadline_cycle();
 
         let output_copy_handler = self.output_handler.clone();
-        let evaluatordata =
-            EvaluatorData::new(self.ir.clone(), self.config.clone(), output_copy_handler, Instant::now());
+        let evaluatordata = EvaluatorData::new(self.ir.clone(), self.config.clone(), output_copy_handler, None);
 
         let mut evaluator = evaluatordata.into_evaluator();
 
@@ -6,7 +6,7 @@ mod time_driven_manager;
 // Re-exports
 pub(crate) use self::controller::Controller;
 pub(crate) use self::event_driven_manager::EventEvaluation;
-pub use self::monitor::Monitor;
+pub use self::monitor::{Monitor, StateSlice, Update};
 pub(crate) use self::time_driven_manager::TimeEvaluation;
 use crate::basics::Time;
 use crate::storage::Value;
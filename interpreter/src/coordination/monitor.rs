@@ -1,10 +1,11 @@
 use crate::basics::{EvalConfig, OutputHandler, Time};
 use crate::coordination::Event;
 use crate::evaluator::{Evaluator, EvaluatorData};
+use crate::explanation::Explanation;
 use crate::storage::Value;
 use rtlola_frontend::ir::{Deadline, InputReference, OutputReference, RTLolaIR};
 use std::sync::Arc;
-use std::time::{Duration, Instant};
+use std::time::Duration;
 
 pub type StateSlice = Vec<(OutputReference, Value)>;
 
@@ -27,14 +28,18 @@ pub struct Monitor {
     eval: Evaluator,
     pub(crate) output_handler: Arc<OutputHandler>,
     deadlines: Vec<Deadline>,
+    /// The index of the deadline due at `next_deadline`, the last deadline is due at time 0.
+    due_ix: usize,
+    /// The time of the next evaluation of periodic streams, earlier ones have already been evaluated.
+    next_deadline: Time,
     current_time: Duration,
 }
 
 // Crate-public interface
 impl Monitor {
     pub(crate) fn setup(ir: RTLolaIR, output_handler: Arc<OutputHandler>, config: EvalConfig) -> Monitor {
-        // Note: start_time only accessed in online mode.
-        let eval_data = EvaluatorData::new(ir.clone(), config.clone(), output_handler.clone(), Instant::now());
+        // the host provides the time of each event, so the monitor reads no clock, which is unavailable on the web
+        let eval_data = EvaluatorData::new(ir.clone(), config.clone(), output_handler.clone(), None);
 
         let deadlines: Vec<Deadline> = if ir.time_driven.is_empty() {
             vec![]
@@ -42,7 +47,16 @@ impl Monitor {
             ir.compute_schedule().expect("Creation of schedule failed.").deadlines
         };
 
-        Monitor { ir, eval: eval_data.into_evaluator(), output_handler, deadlines, current_time: Time::default() }
+        let due_ix = deadlines.len().saturating_sub(1);
+        Monitor {
+            ir,
+            eval: eval_data.into_evaluator(),
+            output_handler,
+            deadlines,
+            due_ix,
+            next_deadline: Time::default(),
+            current_time: Time::default(),
+        }
     }
 }
 
@@ -72,28 +86,27 @@ impl Monitor {
     /**
     Computes all periodic streams up through the new timestamp.
 
+    Periodic streams are evaluated at each deadline before the timestamp that has not been evaluated by an earlier call.
     */
     pub fn accept_time(&mut self, ts: Time) -> Vec<(Time, StateSlice)> {
-        let mut next_deadline = Duration::default();
         let mut timed_changes: Vec<(Time, StateSlice)> = vec![];
 
         if self.deadlines.is_empty() {
             return timed_changes;
         }
-        assert!(self.deadlines.len() > 0);
-        let mut due_ix = self.deadlines.len() - 1;
 
-        while ts > next_deadline {
+        while ts > self.next_deadline {
             // Go back in time and evaluate,...
-            let dl = &self.deadlines[due_ix];
+            let next_deadline = self.next_deadline;
+            let dl = &self.deadlines[self.due_ix];
             self.output_handler.debug(|| format!("Schedule Timed-Event {:?}.", (&dl.due, next_deadline)));
             self.output_handler.new_event();
-            self.eval.eval_time_driven_outputs(&dl.due, ts);
-            due_ix = (due_ix + 1) % self.deadlines.len();
-            let dl = &self.deadlines[due_ix];
+            self.eval.eval_time_driven_outputs(&dl.due, next_deadline);
+            self.due_ix = (self.due_ix + 1) % self.deadlines.len();
+            let dl = &self.deadlines[self.due_ix];
             timed_changes.push((next_deadline, self.eval.peek_fresh()));
             assert!(dl.pause > Duration::from_secs(0));
-            next_deadline += dl.pause;
+            self.next_deadline += dl.pause;
         }
         timed_changes
     }
@@ -115,4 +128,43 @@ impl Monitor {
     pub fn name_for_output(&self, id: OutputReference) -> &str {
         self.ir.outputs[id].name.as_str()
     }
+
+    /**
+    Get the message of a trigger based on the `OutputReference` of its stream, or `None` if the stream is no trigger.
+
+    The reference is valid for the lifetime of the monitor.
+    */
+    pub fn trigger_message(&self, id: OutputReference) -> Option<&str> {
+        self.ir.triggers.iter().find(|t| t.reference.out_ix() == id).map(|t| t.message.as_str())
+    }
+
+    /**
+    Get the name of the specification of a trigger based on the `OutputReference` of its stream, or `None` if the stream is no trigger or the specifications are not merged.
+
+    Hosts monitoring several specifications together use this to route the verdicts of each specification, see `Config::from_specs`.
+    */
+    pub fn trigger_specification(&self, id: OutputReference) -> Option<&str> {
+        let trigger = self.ir.triggers.iter().find(|t| t.reference.out_ix() == id)?;
+        self.ir.specifications.get(trigger.specification).map(String::as_str)
+    }
+
+    /**
+    Get the robustness of the last evaluation of a trigger based on the `OutputReference` of its stream.
+
+    Positive values indicate that the trigger fired; the magnitude states how far the compared values are from flipping the verdict.
+    Returns `None` if the stream is no trigger, it was not evaluated yet, or the robustness mode of the `EvalConfig` is disabled.
+    */
+    pub fn robustness(&self, id: OutputReference) -> Option<f64> {
+        self.eval.robustness(id)
+    }
+
+    /**
+    Get the explanation of the last notification of a trigger based on the `OutputReference` of its stream.
+
+    The explanation contains the values of the sub-expressions of the trigger condition and of the streams it depends on.
+    Returns `None` if the stream is no trigger, it did not fire yet, or the `explain` option of the `EvalConfig` is disabled.
+    */
+    pub fn explanation(&self, id: OutputReference) -> Option<&Explanation> {
+        self.eval.explanation(id)
+    }
 }
@@ -0,0 +1,216 @@
+//! This module searches for an input trace on which a trigger fires, see `search`.
+//!
+//! The search is bounded: it enumerates all traces of up to a given number of events, shortest first, where consecutive events are one second apart.
+//! The values of an input are taken from the literals of the specification, their neighbors, and zero, e.g., `5`, `4`, `6`, and `0` for `a > 5`.
+//! Inputs on which the trigger does not depend are always absent.
+
+use crate::basics::{EvaluatorChoice, EventSourceConfig, ExecutionMode, OutputChannel, Statistics, Verbosity};
+use crate::{Config, EvalConfig, TimeRepresentation, Value, CONFIG};
+use ordered_float::NotNan;
+use rtlola_frontend::ir::{Constant, Expression, ExpressionKind, RTLolaIR, Type};
+use std::time::Duration;
+
+/// The maximal number of traces run before the search gives up.
+const MAX_TRACES: usize = 100_000;
+
+/// Returns a trace in CSV format on which the trigger with the message fires, or `None` if there is no such trace of at most `max_length` events.
+/// Returns an error if the specification is invalid, it has no such trigger, or the search gives up.
+pub(crate) fn search(spec: &str, message: &str, max_length: usize) -> Result<Option<String>, String> {
+    let ir = rtlola_frontend::parse("spec.lola", spec, CONFIG)?;
+    let trigger = ir.triggers.iter().find(|t| t.message == message).ok_or("the specification has no such trigger")?;
+    let relevant = &ir.outputs[trigger.reference.out_ix()].input_dependencies;
+    let mut literals: Vec<Constant> = ir.constants.iter().map(|c| c.value.clone()).collect();
+    ir.outputs.iter().for_each(|o| collect_literals(&o.expr, &mut literals));
+    let mut candidates = Vec::new();
+    for input in &ir.inputs {
+        if relevant.is_empty() || relevant.contains(&input.reference) {
+            candidates.push(
+                values(&input.ty, &literals).ok_or_else(|| format!("inputs of type {} are not supported", input.ty))?,
+            );
+        } else {
+            candidates.push(Vec::new());
+        }
+    }
+    let events = events(&candidates);
+    let mut runs = 0;
+    for length in 1..=max_length {
+        let count = events.len().checked_pow(length as u32).filter(|count| runs + count <= MAX_TRACES);
+        let count = count.ok_or_else(|| {
+            format!("gave up after {} traces, no trace of at most {} events fires the trigger", runs, length - 1)
+        })?;
+        runs += count;
+        for mut index in 0..count {
+            // the trace with the index in the mixed radix system of the events
+            let trace: Vec<&Vec<Value>> = (0..length)
+                .map(|_| {
+                    let event = &events[index % events.len()];
+                    index /= events.len();
+                    event
+                })
+                .collect();
+            if fires(&ir, trigger.trigger_idx, &trace)? {
+                return Ok(Some(csv(&ir, &trace)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Returns whether the trigger fires on the trace.
+fn fires(ir: &RTLolaIR, trigger: usize, trace: &[&Vec<Value>]) -> Result<bool, String> {
+    let cfg = EvalConfig::new(
+        EventSourceConfig::API,
+        Statistics::Debug,
+        Verbosity::Silent,
+        OutputChannel::None,
+        EvaluatorChoice::ClosureBased,
+        ExecutionMode::API,
+        TimeRepresentation::Hide,
+    );
+    let mut monitor = Config::new_api(cfg, ir.clone()).into_monitor().map_err(|e| e.to_string())?;
+    for (step, event) in trace.iter().enumerate() {
+        monitor.accept_event((*event).clone(), Duration::from_secs(step as u64));
+        if monitor.output_handler.statistics.as_ref().map_or(false, |s| s.get_num_trigger(trigger) > 0) {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Collects the numeric and string constants of the expression.
+fn collect_literals(expr: &Expression, literals: &mut Vec<Constant>) {
+    use ExpressionKind::*;
+    match &expr.kind {
+        LoadConstant(constant) => literals.push(constant.clone()),
+        ArithLog(_, args, _) | Tuple(args) | Function(_, args, _) | InstanceAccess(_, args) => {
+            args.iter().for_each(|arg| collect_literals(arg, literals))
+        }
+        Ite { condition, consequence, alternative } => {
+            collect_literals(condition, literals);
+            collect_literals(consequence, literals);
+            collect_literals(alternative, literals);
+        }
+        TupleAccess(expr, _) | Convert { expr, .. } | Cast { expr, .. } => collect_literals(expr, literals),
+        Default { expr, default } => {
+            collect_literals(expr, literals);
+            collect_literals(default, literals);
+        }
+        ConfigurableConstant(_)
+        | OffsetLookup { .. }
+        | StreamAccess(..)
+        | WindowLookup(_)
+        | Now
+        | ParameterAccess(_) => {}
+    }
+}
+
+/// Returns the candidate values of an input of the type, or `None` if the type is not supported.
+fn values(ty: &Type, literals: &[Constant]) -> Option<Vec<Value>> {
+    let numbers: Vec<f64> = literals
+        .iter()
+        .filter_map(|literal| match literal {
+            Constant::UInt(u) => Some(*u as f64),
+            Constant::Int(i) => Some(*i as f64),
+            Constant::Float(f) => Some(*f),
+            _ => None,
+        })
+        .collect();
+    let mut neighbors: Vec<f64> = vec![0.0];
+    for number in &numbers {
+        neighbors.extend(&[*number, number - 1.0, number + 1.0]);
+    }
+    let values: Vec<Value> = match ty {
+        Type::Bool => vec![Value::Bool(false), Value::Bool(true)],
+        Type::UInt(_) => neighbors.iter().filter(|n| **n >= 0.0).map(|n| Value::Unsigned(*n as u64)).collect(),
+        Type::Int(_) => neighbors.iter().map(|n| Value::Signed(*n as i64)).collect(),
+        Type::Float(_) => {
+            // the midpoints of the literals hit ranges narrower than 1
+            let mut sorted = numbers.clone();
+            sorted.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            neighbors.extend(sorted.windows(2).map(|w| (w[0] + w[1]) / 2.0));
+            neighbors.iter().map(|n| Value::Float(NotNan::new(*n).unwrap())).collect()
+        }
+        Type::String => std::iter::once(String::new())
+            .chain(literals.iter().filter_map(|literal| match literal {
+                Constant::Str(s) => Some(s.clone()),
+                _ => None,
+            }))
+            .map(|s| Value::Str(s.into_boxed_str()))
+            .collect(),
+        _ => return None,
+    };
+    let mut unique = Vec::new();
+    for value in values {
+        if !unique.contains(&value) {
+            unique.push(value);
+        }
+    }
+    Some(unique)
+}
+
+/// Returns all events assigning each input one of its candidates or no value, except the event without any value.
+fn events(candidates: &[Vec<Value>]) -> Vec<Vec<Value>> {
+    let mut events: Vec<Vec<Value>> = vec![Vec::new()];
+    for values in candidates {
+        events = events
+            .into_iter()
+            .flat_map(|event| {
+                std::iter::once(Value::None).chain(values.iter().cloned()).map(move |value| {
+                    let mut event = event.clone();
+                    event.push(value);
+                    event
+                })
+            })
+            .collect();
+    }
+    events.retain(|event| event.iter().any(|value| *value != Value::None));
+    events
+}
+
+/// Renders the trace as CSV with a `time` column, where `#` marks the absence of a value.
+fn csv(ir: &RTLolaIR, trace: &[&Vec<Value>]) -> String {
+    let mut csv: String = ir.inputs.iter().map(|i| format!("{},", i.name)).collect();
+    csv += "time\n";
+    for (step, event) in trace.iter().enumerate() {
+        for value in event.iter() {
+            let value = match value {
+                Value::Bool(b) => b.to_string(),
+                Value::Unsigned(u) => u.to_string(),
+                Value::Signed(i) => i.to_string(),
+                Value::Float(f) => f.to_string(),
+                Value::Str(s) => s.to_string(),
+                _ => String::from("#"),
+            };
+            csv += &value;
+            csv += ",";
+        }
+        csv += &format!("{}.0\n", step);
+    }
+    csv
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trigger_fires_on_found_trace() {
+        let spec = "input a: Int64\ninput b: Bool\ninput c: Int64\noutput d := a - 10\ntrigger d > 0 && b \"too high\"";
+        assert_eq!(search(spec, "too high", 3), Ok(Some(String::from("a,b,c,time\n11,true,#,0.0\n"))));
+    }
+
+    #[test]
+    fn traces_with_several_events() {
+        let spec = "input a: Int64\noutput b := a - a.offset(by: -1).defaults(to: a)\ntrigger b > 3 \"jump\"";
+        let trace = search(spec, "jump", 2).unwrap().unwrap();
+        assert_eq!(trace.lines().count(), 3);
+        assert_eq!(search(spec, "jump", 1), Ok(None));
+    }
+
+    #[test]
+    fn unsatisfiable_triggers() {
+        let spec = "input a: Bool\ntrigger a && !a \"never\"";
+        assert_eq!(search(spec, "never", 3), Ok(None));
+        assert!(search(spec, "unknown", 3).is_err());
+    }
+}
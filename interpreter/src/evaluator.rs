@@ -1,12 +1,15 @@
-use crate::basics::{EvalConfig, EvaluatorChoice::*, ExecutionMode, OutputHandler, Time};
+use crate::basics::{EvalConfig, EvaluatorChoice::*, ExecutionMode, Functions, OutputHandler, Time};
 use crate::closuregen::{CompiledExpr, Expr};
-use crate::storage::{GlobalStore, Value};
+use crate::explanation::Explanation;
+use crate::storage::{GlobalStore, InstanceStore, Value};
 use bit_set::BitSet;
+use regex::bytes::Regex as BytesRegex;
 use regex::Regex;
 use rtlola_frontend::ir::{
-    Activation, Constant, Expression, InputReference, Offset, OutputReference, RTLolaIR, StreamAccessKind,
-    StreamReference, Trigger, Type, WindowReference,
+    Activation, Constant, ExprId, Expression, ExpressionArena, InputReference, InstanceTemplate, Offset,
+    OutputReference, RTLolaIR, StreamAccessKind, StreamReference, Trigger, Type, WindowReference,
 };
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -19,19 +22,60 @@ pub(crate) enum ActivationCondition {
     General(Activation<StreamReference>),
 }
 
+/// The regular expressions of `matches` calls, compiled once when the evaluator is created.
+#[derive(Debug, Default)]
+struct Patterns {
+    strings: HashMap<String, Regex>,
+    bytes: HashMap<String, BytesRegex>,
+}
+
+/// The clauses of the template of a parameterized stream, see `InstanceTemplate`.
+#[derive(Debug)]
+struct Template<E> {
+    invoke: Option<E>,
+    invoke_condition: Option<E>,
+    extend: Option<E>,
+    terminate: Option<E>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Clause {
+    Invoke,
+    InvokeCondition,
+    Extend,
+    Terminate,
+}
+
 pub(crate) struct EvaluatorData {
     // Evaluation order of output streams
     layers: Vec<Vec<OutputReference>>,
     // Indexed by stream reference.
     activation_conditions: Vec<ActivationCondition>,
     // Indexed by stream reference.
-    exprs: Vec<Expression>,
+    exprs: Vec<ExprId>,
+    // The expressions of the IR extended by the clauses of the templates.
+    expressions: ExpressionArena,
+    // Indexed by stream reference, only present for parameterized streams.
+    templates: Vec<Option<Template<ExprId>>>,
+    patterns: Patterns,
     global_store: GlobalStore,
-    start_time: Instant,           // only valid in online mode
+    start_time: Option<Instant>,   // only set in online mode
     time_last_event: Option<Time>, // only valid in offline mode
     fresh_inputs: BitSet,
     fresh_outputs: BitSet,
     triggers: Vec<Option<Trigger>>,
+    // Indexed by output reference, only computed for triggers in robustness mode.
+    robustness: Vec<Option<f64>>,
+    // Indexed by output reference, only computed for triggers that fired if explanations are enabled.
+    explanations: Vec<Option<Explanation>>,
+    // Indexed by output reference, the times of the recent violations of escalating triggers.
+    violations: Vec<VecDeque<Time>>,
+    // Indexed by output reference, the assumptions sharing inputs with each trigger.
+    assumptions: Vec<Vec<OutputReference>>,
+    // The output references of the violated assumptions.
+    violated: BitSet,
+    // Indexed by output reference, the activations to skip until the future values of the first position arrived.
+    pending: Vec<u32>,
     ir: RTLolaIR,
     handler: Arc<OutputHandler>,
     config: EvalConfig,
@@ -44,15 +88,26 @@ pub(crate) struct Evaluator {
     // Indexed by stream reference.
     activation_conditions: &'static Vec<ActivationCondition>,
     // Indexed by stream reference.
-    exprs: &'static Vec<Expression>,
+    exprs: &'static Vec<ExprId>,
     // Indexed by stream reference.
     compiled_exprs: Vec<CompiledExpr>,
+    expressions: &'static ExpressionArena,
+    templates: &'static Vec<Option<Template<ExprId>>>,
+    // Indexed by stream reference.
+    compiled_templates: Vec<Option<Template<CompiledExpr>>>,
+    patterns: &'static Patterns,
     global_store: &'static mut GlobalStore,
-    start_time: &'static Instant,               // only valid in online mode
+    start_time: &'static Option<Instant>,       // only set in online mode
     time_last_event: &'static mut Option<Time>, // only valid in offline mode
     fresh_inputs: &'static mut BitSet,
     fresh_outputs: &'static mut BitSet,
     triggers: &'static Vec<Option<Trigger>>,
+    robustness: &'static mut Vec<Option<f64>>,
+    explanations: &'static mut Vec<Option<Explanation>>,
+    violations: &'static mut Vec<VecDeque<Time>>,
+    assumptions: &'static Vec<Vec<OutputReference>>,
+    violated: &'static mut BitSet,
+    pending: &'static mut Vec<u32>,
     ir: &'static RTLolaIR,
     handler: &'static OutputHandler,
     config: &'static EvalConfig,
@@ -60,6 +115,11 @@ pub(crate) struct Evaluator {
 }
 
 struct ExpressionEvaluator<'e> {
+    expressions: &'e ExpressionArena,
+    // The parameters of the instance being evaluated, empty for streams without parameters.
+    parameters: &'e [Value],
+    patterns: &'e Patterns,
+    functions: &'e Functions,
     global_store: &'e GlobalStore,
     fresh_inputs: &'e BitSet,
     fresh_outputs: &'e BitSet,
@@ -70,10 +130,18 @@ pub(crate) struct EvaluationContext<'e> {
     pub(crate) global_store: &'e GlobalStore,
     pub(crate) fresh_inputs: &'e BitSet,
     pub(crate) fresh_outputs: &'e BitSet,
+    pub(crate) functions: &'e Functions,
+    // The parameters of the instance being evaluated, empty for streams without parameters.
+    pub(crate) parameters: &'e [Value],
 }
 
 impl EvaluatorData {
-    pub(crate) fn new(ir: RTLolaIR, config: EvalConfig, handler: Arc<OutputHandler>, start_time: Instant) -> Self {
+    pub(crate) fn new(
+        ir: RTLolaIR,
+        config: EvalConfig,
+        handler: Arc<OutputHandler>,
+        start_time: Option<Instant>,
+    ) -> Self {
         // Layers of event based output streams
         let layers = ir.get_event_driven_layers();
         handler.debug(|| format!("Evaluation layers: {:?}", layers));
@@ -88,24 +156,62 @@ impl EvaluatorData {
                 }
             })
             .collect();
-        let exprs = ir.outputs.iter().map(|o| o.expr.clone()).collect();
-        let global_store = GlobalStore::new(&ir, Time::default());
+        let exprs: Vec<ExprId> = ir.outputs.iter().map(|o| o.expr_id).collect();
+        let mut expressions = ir.expressions.clone();
+        let templates: Vec<Option<Template<ExprId>>> = ir
+            .outputs
+            .iter()
+            .map(|o| o.template.as_ref().map(|t| Template::new(t, |clause| expressions.intern(clause))))
+            .collect();
+        let mut patterns = Patterns::default();
+        ir.outputs.iter().for_each(|o| patterns.collect(&o.expr));
+        ir.outputs
+            .iter()
+            .flat_map(|o| &o.template)
+            .flat_map(InstanceTemplate::clauses)
+            .for_each(|c| patterns.collect(c));
+        let global_store = GlobalStore::new(&ir, &config.aggregations, Time::default());
         let fresh_inputs = BitSet::with_capacity(ir.inputs.len());
         let fresh_outputs = BitSet::with_capacity(ir.outputs.len());
         let mut triggers = vec![None; ir.outputs.len()];
         for t in &ir.triggers {
             triggers[t.reference.out_ix()] = Some(t.clone());
         }
+        let robustness = vec![None; ir.outputs.len()];
+        let explanations = vec![None; ir.outputs.len()];
+        let violations = vec![VecDeque::new(); ir.outputs.len()];
+        let mut assumptions = vec![Vec::new(); ir.outputs.len()];
+        for t in ir.triggers.iter().filter(|t| !t.assumption) {
+            let inputs = &ir.outputs[t.reference.out_ix()].input_dependencies;
+            assumptions[t.reference.out_ix()] = ir
+                .triggers
+                .iter()
+                .filter(|a| a.assumption && a.specification == t.specification)
+                .filter(|a| ir.outputs[a.reference.out_ix()].input_dependencies.iter().any(|i| inputs.contains(i)))
+                .map(|a| a.reference.out_ix())
+                .collect();
+        }
+        let violated = BitSet::with_capacity(ir.outputs.len());
+        let pending = ir.outputs.iter().map(|o| o.look_ahead).collect();
         EvaluatorData {
             layers,
             activation_conditions,
             exprs,
+            expressions,
+            templates,
+            patterns,
             global_store,
             start_time,
             time_last_event: None,
             fresh_inputs,
             fresh_outputs,
             triggers,
+            robustness,
+            explanations,
+            violations,
+            assumptions,
+            violated,
+            pending,
             ir,
             handler,
             config,
@@ -123,18 +229,34 @@ impl EvaluatorData {
         } else {
             vec![]
         };
+        let compiled_templates: Vec<Option<Template<CompiledExpr>>> = if leaked_data.config.evaluator == ClosureBased {
+            let outputs = leaked_data.ir.outputs.iter();
+            outputs.map(|o| o.template.as_ref().map(|t| Template::new(t, |clause| clause.clone().compile()))).collect()
+        } else {
+            vec![]
+        };
 
         Evaluator {
             layers: &leaked_data.layers,
             activation_conditions: &leaked_data.activation_conditions,
             exprs: &leaked_data.exprs,
             compiled_exprs,
+            expressions: &leaked_data.expressions,
+            templates: &leaked_data.templates,
+            compiled_templates,
+            patterns: &leaked_data.patterns,
             global_store: &mut leaked_data.global_store,
             start_time: &leaked_data.start_time,
             time_last_event: &mut leaked_data.time_last_event,
             fresh_inputs: &mut leaked_data.fresh_inputs,
             fresh_outputs: &mut leaked_data.fresh_outputs,
             triggers: &leaked_data.triggers,
+            robustness: &mut leaked_data.robustness,
+            explanations: &mut leaked_data.explanations,
+            violations: &mut leaked_data.violations,
+            assumptions: &leaked_data.assumptions,
+            violated: &mut leaked_data.violated,
+            pending: &mut leaked_data.pending,
             ir: &leaked_data.ir,
             handler: &leaked_data.handler,
             config: &leaked_data.config,
@@ -143,6 +265,73 @@ impl EvaluatorData {
     }
 }
 
+impl<E> Template<E> {
+    fn new(template: &InstanceTemplate, mut clause: impl FnMut(&Expression) -> E) -> Self {
+        Template {
+            invoke: template.invoke.as_ref().map(&mut clause),
+            invoke_condition: template.invoke_condition.as_ref().map(&mut clause),
+            extend: template.extend.as_ref().map(&mut clause),
+            terminate: template.terminate.as_ref().map(&mut clause),
+        }
+    }
+
+    fn get(&self, clause: Clause) -> Option<&E> {
+        match clause {
+            Clause::Invoke => self.invoke.as_ref(),
+            Clause::InvokeCondition => self.invoke_condition.as_ref(),
+            Clause::Extend => self.extend.as_ref(),
+            Clause::Terminate => self.terminate.as_ref(),
+        }
+    }
+}
+
+impl Patterns {
+    /// Compiles the patterns of all `matches` calls in the expression.
+    fn collect(&mut self, expr: &Expression) {
+        use rtlola_frontend::ir::ExpressionKind::*;
+        match &expr.kind {
+            LoadConstant(_)
+            | ConfigurableConstant(_)
+            | OffsetLookup { .. }
+            | StreamAccess(..)
+            | WindowLookup(_)
+            | Now
+            | ParameterAccess(_) => {}
+            ArithLog(_, args, _) | Tuple(args) | InstanceAccess(_, args) => args.iter().for_each(|e| self.collect(e)),
+            Ite { condition, consequence, alternative } => {
+                self.collect(condition);
+                self.collect(consequence);
+                self.collect(alternative);
+            }
+            TupleAccess(expr, _) | Convert { expr, .. } | Cast { expr, .. } => self.collect(expr),
+            Default { expr, default } => {
+                self.collect(expr);
+                self.collect(default);
+            }
+            Function(name, args, ty) => {
+                args.iter().for_each(|e| self.collect(e));
+                if name != "matches" {
+                    return;
+                }
+                let re_str = match &args[1].kind {
+                    LoadConstant(Constant::Str(s)) => s,
+                    _ => unreachable!("regex should be a string literal"),
+                };
+                match ty {
+                    Type::Function(args, _) if args[0] == Type::Bytes => {
+                        let re = BytesRegex::new(re_str).expect("Given regular expression was invalid");
+                        self.bytes.insert(re_str.clone(), re);
+                    }
+                    _ => {
+                        let re = Regex::new(re_str).expect("Given regular expression was invalid");
+                        self.strings.insert(re_str.clone(), re);
+                    }
+                }
+            }
+        }
+    }
+}
+
 impl Drop for Evaluator {
     #[allow(unsafe_code)]
     fn drop(&mut self) {
@@ -159,16 +348,19 @@ impl Evaluator {
             );
             *self.time_last_event = Some(ts);
         } else {
-            ts = self.start_time.elapsed();
+            ts = self.start_time.expect("online mode has a start time").elapsed();
         }
         self.clear_freshness();
         self.accept_inputs(event, ts);
         self.eval_all_event_driven_outputs(ts);
     }
 
+    /// Returns the fresh values of the streams without parameters, the instances of parameterized streams are
+    /// reported by the handler.
     pub(crate) fn peek_fresh(&self) -> Vec<(OutputReference, Value)> {
         self.fresh_outputs
             .iter()
+            .filter(|&elem| self.ir.outputs[elem].params.is_empty())
             .map(|elem| (elem, self.peek_value(StreamReference::OutRef(elem), &[], 0).expect("Marked as fresh.")))
             .collect()
     }
@@ -206,9 +398,15 @@ impl Evaluator {
     }
 
     fn eval_event_driven_output(&mut self, output: OutputReference, ts: Time) {
-        if self.activation_conditions[output].eval(self.fresh_inputs) {
-            self.eval_stream(output, ts);
+        if !self.activation_conditions[output].eval(self.fresh_inputs) {
+            return;
         }
+        // A stream with a look-ahead evaluates the position that many activations back, which does not exist yet.
+        if self.pending[output] > 0 {
+            self.pending[output] -= 1;
+            return;
+        }
+        self.eval_stream(output, ts);
     }
 
     pub(crate) fn eval_time_driven_outputs(&mut self, outputs: &[OutputReference], mut ts: Time) {
@@ -219,14 +417,14 @@ impl Evaluator {
             );
             *self.time_last_event = Some(ts);
         } else {
-            ts = self.start_time.elapsed();
+            ts = self.start_time.expect("online mode has a start time").elapsed();
         }
         self.clear_freshness();
         self.prepare_evaluation(ts);
         for output in outputs {
             self.eval_stream(*output, ts);
         }
-        self.clear_freshness();
+        // the outputs stay fresh until the next evaluation, so the API reports them, see `Monitor::accept_time`
     }
 
     fn prepare_evaluation(&mut self, ts: Time) {
@@ -242,30 +440,53 @@ impl Evaluator {
         self.handler
             .debug(|| format!("Evaluating stream {}: {}.", ix, self.ir.get_out(StreamReference::OutRef(ix)).name));
 
-        let res = match self.config.evaluator {
-            ClosureBased => {
-                let (ctx, compiled_exprs) = self.as_EvaluationContext(ts);
-                compiled_exprs[ix].execute(&ctx)
-            }
-            Interpreted => {
-                let (expr_eval, exprs) = self.as_ExpressionEvaluator();
-                expr_eval.eval_expr(&exprs[ix], ts)
-            }
-        };
+        if !self.ir.outputs[ix].params.is_empty() {
+            self.eval_instances(output, ts);
+            return;
+        }
+        let res = self.eval_expression(output, &[], ts);
+
+        // Register value in global store.
+        self.global_store.get_out_instance_mut(output).unwrap().push_value(res.clone()); // TODO: unsafe unwrap.
+        self.fresh_outputs.insert(ix);
+        self.emit(output, &[], res, ts);
+    }
 
-        match self.is_trigger(output) {
+    /// Reports a new value of an output stream, or of the instance with the given parameters, and passes it to the
+    /// windows over the stream.
+    fn emit(&mut self, output: OutputReference, parameters: &[Value], res: Value, ts: Time) {
+        let ix = output;
+        // the references outlive the borrow of `self`, which is needed to report triggers
+        let (ir, triggers) = (self.ir, self.triggers);
+        // instances are named by their parameters, e.g., `count(3)`
+        let args = if parameters.is_empty() {
+            String::new()
+        } else {
+            let args: Vec<String> = parameters.iter().map(ToString::to_string).collect();
+            format!("({})", args.join(", "))
+        };
+        let name = &format!("{}{}", ir.outputs[ix].name, args);
+        match triggers[ix].as_ref() {
             None => {
-                // Register value in global store.
-                self.global_store.get_out_instance_mut(output).unwrap().push_value(res.clone()); // TODO: unsafe unwrap.
-                self.fresh_outputs.insert(ix);
+                let ty = &ir.outputs[ix].ty;
+                self.handler.output(|| format!("OutputStream[{}]{} := {}.", ix, args, res.render(ty)));
+                self.handler.record_value(name, &res, ts);
+            }
 
-                self.handler.output(|| format!("OutputStream[{}] := {:?}.", ix, res.clone()));
+            Some(trig) if self.config.robustness => {
+                let (expr_eval, exprs) = self.as_ExpressionEvaluator(parameters);
+                let rob = expr_eval.eval_robustness(exprs[ix], ts);
+                match self.notification(trig, &res, ts) {
+                    Some(message) => self.report(trig, name, &message, &format!(" (robustness: {})", rob), ts),
+                    None => self.handler.output(|| format!("Robustness of trigger `{}` := {}.", trig.message, rob)),
+                }
+                self.robustness[ix] = Some(rob);
             }
 
             Some(trig) => {
                 // Check if we have to emit a warning.
-                if let Value::Bool(true) = res {
-                    self.handler.trigger(|| format!("Trigger: {}", trig.message), trig.trigger_idx, ts)
+                if let Some(message) = self.notification(trig, &res, ts) {
+                    self.report(trig, name, &message, "", ts);
                 }
             }
         }
@@ -278,15 +499,171 @@ impl Evaluator {
         // TODO: Dependent streams?
     }
 
+    /// Evaluates the instances of a parameterized stream according to its template.
+    /// First, the instance given by the invoke clause is spawned, then the instances whose extend condition holds compute
+    /// their next value, and finally the instances whose terminate condition holds are closed.
+    fn eval_instances(&mut self, output: OutputReference, ts: Time) {
+        let ix = output;
+        let holds = |condition: Option<Value>| condition.map(|c| c == Value::Bool(true));
+        if holds(self.eval_clause(ix, Clause::InvokeCondition, &[], ts)).unwrap_or(true) {
+            let several = self.ir.outputs[ix].params.len() > 1;
+            let parameters = match self.eval_clause(ix, Clause::Invoke, &[], ts) {
+                None | Some(Value::None) => None,
+                Some(Value::Tuple(ref values)) if several => Some(values.to_vec()),
+                Some(value) => Some(vec![value]),
+            };
+            if let Some(parameters) = parameters {
+                if self.global_store.spawn_instance(ix, parameters.clone()) {
+                    self.handler.debug(|| format!("Spawned instance {:?} of stream {}.", parameters, ix));
+                }
+            }
+        }
+
+        // an instance may access its own previous values, so the stream becomes fresh after all instances evaluated
+        let mut evaluated = false;
+        for parameters in self.global_store.instances(ix) {
+            if holds(self.eval_clause(ix, Clause::Extend, &parameters, ts)).unwrap_or(true) {
+                let res = self.eval_expression(output, &parameters, ts);
+                let instance = self.global_store.get_param_instance_mut(ix, &parameters).expect("spawned instance");
+                instance.push_value(res.clone());
+                self.emit(output, &parameters, res, ts);
+                evaluated = true;
+            }
+            if holds(self.eval_clause(ix, Clause::Terminate, &parameters, ts)).unwrap_or(false) {
+                self.global_store.terminate_instance(ix, &parameters);
+                self.handler.debug(|| format!("Closed instance {:?} of stream {}.", parameters, ix));
+            }
+        }
+        if evaluated {
+            self.fresh_outputs.insert(ix);
+        }
+    }
+
+    /// Evaluates the expression of an output stream for the instance with the given parameters.
+    fn eval_expression(&self, output: OutputReference, parameters: &[Value], ts: Time) -> Value {
+        match self.config.evaluator {
+            ClosureBased => {
+                let (ctx, compiled_exprs) = self.as_EvaluationContext(ts, parameters);
+                compiled_exprs[output].execute(&ctx)
+            }
+            Interpreted => {
+                let (expr_eval, exprs) = self.as_ExpressionEvaluator(parameters);
+                expr_eval.eval_expr(exprs[output], ts)
+            }
+        }
+    }
+
+    /// Evaluates a clause of the template of a parameterized stream for the instance with the given parameters.
+    /// Returns `None` if the template has no such clause.
+    fn eval_clause(&self, output: OutputReference, clause: Clause, parameters: &[Value], ts: Time) -> Option<Value> {
+        match self.config.evaluator {
+            ClosureBased => {
+                let expr = self.compiled_templates[output].as_ref()?.get(clause)?;
+                let (ctx, _) = self.as_EvaluationContext(ts, parameters);
+                Some(expr.execute(&ctx))
+            }
+            Interpreted => {
+                let expr = *self.templates[output].as_ref()?.get(clause)?;
+                let (expr_eval, _) = self.as_ExpressionEvaluator(parameters);
+                Some(expr_eval.eval_expr(expr, ts))
+            }
+        }
+    }
+
+    /// Returns the message if the trigger notifies about its value.
+    /// Escalating triggers record each violation and only notify once enough violations occurred within their duration.
+    fn notification(&mut self, trig: &Trigger, res: &Value, ts: Time) -> Option<String> {
+        if *res != Value::Bool(true) {
+            return None;
+        }
+        let escalation = match trig.escalation {
+            None => return Some(self.interpolate(trig, None)),
+            Some(escalation) => escalation,
+        };
+        let violations = &mut self.violations[trig.reference.out_ix()];
+        violations.push_back(ts);
+        let start = ts.checked_sub(escalation.within).unwrap_or_default();
+        while violations.front().map_or(false, |&t| t < start) {
+            violations.pop_front();
+        }
+        if violations.len() < escalation.after {
+            return None;
+        }
+        let count = violations.len();
+        Some(self.interpolate(trig, Some(count)))
+    }
+
+    /// Replaces the placeholders `{NAME}` in the message by the latest values of their streams, and `{count}` by the
+    /// number of violations of escalating triggers.
+    fn interpolate(&self, trig: &Trigger, count: Option<usize>) -> String {
+        trig.interpolate(|name| {
+            if let (Some(count), "count") = (count, name) {
+                return Some(count.to_string());
+            }
+            let stream = trig.placeholders.iter().find(|(n, _)| n == name)?.1;
+            let value = self.peek_value(stream, &[], 0).unwrap_or(Value::None);
+            let ty = match stream {
+                StreamReference::InRef(_) => &self.ir.get_in(stream).ty,
+                StreamReference::OutRef(_) => &self.ir.get_out(stream).ty,
+            };
+            Some(value.render(ty))
+        })
+    }
+
+    /// Reports the notification of a trigger with the details appended to its message.
+    /// Once an assumption is violated, the triggers sharing inputs with it report vacuous verdicts.
+    /// The triggers of merged specifications are reported with the name of their specification, annotated triggers with their severity.
+    /// If explanations are enabled, the explanation of the verdict is reported after the notification.
+    fn report(&mut self, trig: &Trigger, name: &str, message: &str, details: &str, ts: Time) {
+        let ix = trig.reference.out_ix();
+        let mut context =
+            self.ir.specifications.get(trig.specification).map_or_else(String::new, |s| format!(" [{}]", s));
+        if let Some(severity) = trig.severity() {
+            context.push_str(&format!(" ({})", severity));
+        }
+        if trig.assumption {
+            self.violated.insert(ix);
+            let msg = || format!("Assumption violated{}: {}{}", context, message, details);
+            self.handler.assumption(msg, trig.trigger_idx, ts);
+        } else if let Some(&assumption) = self.assumptions[ix].iter().find(|a| self.violated.contains(**a)) {
+            let assumption = &self.triggers[assumption].as_ref().expect("assumptions are triggers").message;
+            let msg = || {
+                format!("Trigger{} (vacuous, assumption `{}` violated): {}{}", context, assumption, message, details)
+            };
+            self.handler.vacuous_trigger(msg, trig.trigger_idx, ts);
+        } else {
+            self.handler.trigger(|| format!("Trigger{}: {}{}", context, message, details), trig.trigger_idx, ts);
+        }
+        self.handler.record_trigger(name, message, ts);
+        if self.config.explain {
+            let (expr_eval, _) = self.as_ExpressionEvaluator(&[]);
+            let explanation = Explanation::new(
+                self.ir,
+                trig,
+                ts,
+                |expr| expr_eval.eval_expr(expr, ts),
+                |stream| expr_eval.lookup_latest(stream),
+            );
+            self.handler.explanation(|| format!("Explanation: {}", explanation));
+            self.explanations[ix] = Some(explanation);
+        }
+    }
+
+    /// Returns the robustness of the last evaluation of a trigger, if computed.
+    pub(crate) fn robustness(&self, output: OutputReference) -> Option<f64> {
+        self.robustness[output]
+    }
+
+    /// Returns the explanation of the last notification of a trigger, if explanations are enabled.
+    pub(crate) fn explanation(&self, output: OutputReference) -> Option<&Explanation> {
+        self.explanations[output].as_ref()
+    }
+
     fn clear_freshness(&mut self) {
         self.fresh_inputs.clear();
         self.fresh_outputs.clear();
     }
 
-    fn is_trigger(&self, ix: OutputReference) -> Option<&Trigger> {
-        self.triggers[ix].as_ref()
-    }
-
     fn peek_value(&self, sr: StreamReference, args: &[Value], offset: i16) -> Option<Value> {
         match sr {
             StreamReference::InRef(ix) => {
@@ -294,17 +671,22 @@ impl Evaluator {
                 self.global_store.get_in_instance(ix).get_value(offset)
             }
             StreamReference::OutRef(ix) => {
-                //let inst = (ix, Vec::from(args));
-                let inst = ix;
-                self.global_store.get_out_instance(inst).and_then(|st| st.get_value(offset))
+                let store = &self.global_store;
+                let inst =
+                    if args.is_empty() { store.get_out_instance(ix) } else { store.get_param_instance(ix, args) };
+                inst.and_then(|st| st.get_value(offset))
             }
         }
     }
 
     #[allow(non_snake_case)]
-    fn as_ExpressionEvaluator<'n>(&'n self) -> (ExpressionEvaluator<'n>, &Vec<Expression>) {
+    fn as_ExpressionEvaluator<'n>(&'n self, parameters: &'n [Value]) -> (ExpressionEvaluator<'n>, &Vec<ExprId>) {
         (
             ExpressionEvaluator {
+                expressions: self.expressions,
+                parameters,
+                patterns: self.patterns,
+                functions: &self.config.functions,
                 global_store: &self.global_store,
                 fresh_inputs: &self.fresh_inputs,
                 fresh_outputs: &self.fresh_outputs,
@@ -314,13 +696,19 @@ impl Evaluator {
     }
 
     #[allow(non_snake_case)]
-    fn as_EvaluationContext(&self, ts: Time) -> (EvaluationContext, &Vec<CompiledExpr>) {
+    fn as_EvaluationContext<'n>(
+        &'n self,
+        ts: Time,
+        parameters: &'n [Value],
+    ) -> (EvaluationContext<'n>, &Vec<CompiledExpr>) {
         (
             EvaluationContext {
                 ts,
                 global_store: &self.global_store,
                 fresh_inputs: &self.fresh_inputs,
                 fresh_outputs: &self.fresh_outputs,
+                functions: &self.config.functions,
+                parameters,
             },
             &self.compiled_exprs,
         )
@@ -328,9 +716,9 @@ impl Evaluator {
 }
 
 impl<'a> ExpressionEvaluator<'a> {
-    fn eval_expr(&self, expr: &Expression, ts: Time) -> Value {
-        use rtlola_frontend::ir::ExpressionKind::*;
-        match &expr.kind {
+    fn eval_expr(&self, expr: ExprId, ts: Time) -> Value {
+        use rtlola_frontend::ir::NodeKind::*;
+        match &self.expressions[expr].kind {
             LoadConstant(c) => match c {
                 Constant::Bool(b) => Value::Bool(*b),
                 Constant::UInt(u) => Value::Unsigned(*u),
@@ -338,6 +726,7 @@ impl<'a> ExpressionEvaluator<'a> {
                 Constant::Float(f) => Value::Float((*f).into()),
                 Constant::Str(s) => Value::Str(s.clone().into_boxed_str()),
             },
+            ConfigurableConstant(_) => unreachable!("configurable constants are substituted before the evaluation"),
 
             ArithLog(op, operands, _ty) => {
                 use rtlola_frontend::ir::ArithLogOp::*;
@@ -350,7 +739,7 @@ impl<'a> ExpressionEvaluator<'a> {
                 };
                 match arity {
                     1 => {
-                        let operand = self.eval_expr(&operands[0], ts);
+                        let operand = self.eval_expr(operands[0], ts);
                         match *op {
                             Not => !operand,
                             Neg => -operand,
@@ -359,18 +748,18 @@ impl<'a> ExpressionEvaluator<'a> {
                         }
                     }
                     2 => {
-                        let lhs = self.eval_expr(&operands[0], ts);
+                        let lhs = self.eval_expr(operands[0], ts);
 
                         if *op == And {
                             // evaluate lazy
-                            return if lhs.get_bool() { self.eval_expr(&operands[1], ts) } else { Value::Bool(false) };
+                            return if lhs.get_bool() { self.eval_expr(operands[1], ts) } else { Value::Bool(false) };
                         }
                         if *op == Or {
                             // evaluate lazy
-                            return if lhs.get_bool() { Value::Bool(true) } else { self.eval_expr(&operands[1], ts) };
+                            return if lhs.get_bool() { Value::Bool(true) } else { self.eval_expr(operands[1], ts) };
                         }
 
-                        let rhs = self.eval_expr(&operands[1], ts);
+                        let rhs = self.eval_expr(operands[1], ts);
 
                         match *op {
                             Add => lhs + rhs,
@@ -398,15 +787,16 @@ impl<'a> ExpressionEvaluator<'a> {
             }
 
             Ite { condition, consequence, alternative, .. } => {
-                if self.eval_expr(condition, ts).get_bool() {
-                    self.eval_expr(consequence, ts)
+                if self.eval_expr(*condition, ts).get_bool() {
+                    self.eval_expr(*consequence, ts)
                 } else {
-                    self.eval_expr(alternative, ts)
+                    self.eval_expr(*alternative, ts)
                 }
             }
 
             OffsetLookup { target: str_ref, offset } => match offset {
-                Offset::FutureDiscreteOffset(_) | Offset::FutureRealTimeOffset(_) => unimplemented!(),
+                Offset::FutureDiscreteOffset(_) => unreachable!("resolved by the lowering"),
+                Offset::FutureRealTimeOffset(_) => unimplemented!(),
                 Offset::PastDiscreteOffset(u) => self.lookup_with_offset(*str_ref, -(*u as i16)),
                 Offset::PastRealTimeOffset(_dur) => unimplemented!(),
             },
@@ -440,14 +830,19 @@ impl<'a> ExpressionEvaluator<'a> {
 
             WindowLookup(win_ref) => self.lookup_window(*win_ref, ts),
 
-            Function(name, args, _ty) => {
+            Now => Value::new_float(ts.as_secs_f64()),
+
+            Function(name, args, ty) => {
                 assert!(!args.is_empty());
-                let fst = self.eval_expr(&args[0], ts);
+                let fst = self.eval_expr(args[0], ts);
 
                 macro_rules! create_float_arith {
                     ($fn:ident) => {
+                        create_float_arith!($fn, new_float)
+                    };
+                    ($fn:ident, $new:ident) => {
                         match fst {
-                            Value::Float(f) => Value::new_float(f.$fn()),
+                            Value::Float(f) => Value::$new(f.$fn()),
                             v => unreachable!("wrong Value type of {:?} for function $fn", v),
                         }
                     };
@@ -458,7 +853,7 @@ impl<'a> ExpressionEvaluator<'a> {
                         if args.len() != 2 {
                             unreachable!("wrong number of arguments for function $fn")
                         }
-                        let snd = self.eval_expr(&args[1], ts);
+                        let snd = self.eval_expr(args[1], ts);
                         match (fst, snd) {
                             (Value::Float(f1), Value::Float(f2)) => Value::Float(f1.$fn(f2)),
                             (Value::Signed(s1), Value::Signed(s2)) => Value::Signed(s1.$fn(s2)),
@@ -468,46 +863,192 @@ impl<'a> ExpressionEvaluator<'a> {
                     }};
                 }
 
+                macro_rules! create_binary_float_arith {
+                    ($fn:ident) => {{
+                        if args.len() != 2 {
+                            unreachable!("wrong number of arguments for function $fn")
+                        }
+                        match (fst, self.eval_expr(args[1], ts)) {
+                            (Value::Float(f1), Value::Float(f2)) => Value::new_float(f1.$fn(*f2)),
+                            (v1, v2) => unreachable!("wrong Value types of {:?}, {:?} for function $fn", v1, v2),
+                        }
+                    }};
+                }
+
                 match name.as_ref() {
                     "sqrt" => create_float_arith!(sqrt),
                     "sin" => create_float_arith!(sin),
                     "cos" => create_float_arith!(cos),
                     "arctan" => create_float_arith!(atan),
+                    "atan2" => create_binary_float_arith!(atan2),
+                    "hypot" => create_binary_float_arith!(hypot),
+                    "ln" => create_float_arith!(ln, new_log),
+                    "log2" => create_float_arith!(log2, new_log),
+                    "log10" => create_float_arith!(log10, new_log),
+                    "exp2" => create_float_arith!(exp2),
+                    "log" => {
+                        if args.len() != 2 {
+                            unreachable!("wrong number of arguments for function log")
+                        }
+                        match (fst, self.eval_expr(args[1], ts)) {
+                            (Value::Float(base), Value::Float(x)) => Value::new_log(x.into_inner().log(*base)),
+                            (base, x) => unreachable!("wrong Value types of {:?}, {:?} for function log", base, x),
+                        }
+                    }
                     "abs" => match fst {
                         Value::Float(f) => Value::new_float(f.abs()),
                         Value::Signed(i) => Value::Signed(i.abs()),
+                        Value::Unsigned(u) => Value::Unsigned(u),
                         _ => {
                             unreachable!();
                         }
                     },
+                    "is_some" => Value::Bool(fst != Value::None),
                     "min" => create_binary_arith!(min),
                     "max" => create_binary_arith!(max),
+                    "clamp" => {
+                        if args.len() != 3 {
+                            unreachable!("wrong number of arguments for function clamp")
+                        }
+                        let (lo, hi) = (self.eval_expr(args[1], ts), self.eval_expr(args[2], ts));
+                        // Unlike `Ord::clamp`, an empty range yields the upper bound instead of panicking.
+                        match (fst, lo, hi) {
+                            (Value::Float(f), Value::Float(lo), Value::Float(hi)) => Value::Float(f.max(lo).min(hi)),
+                            (Value::Signed(s), Value::Signed(lo), Value::Signed(hi)) => {
+                                Value::Signed(s.max(lo).min(hi))
+                            }
+                            (Value::Unsigned(u), Value::Unsigned(lo), Value::Unsigned(hi)) => {
+                                Value::Unsigned(u.max(lo).min(hi))
+                            }
+                            (v, lo, hi) => {
+                                unreachable!("wrong Value types of {:?}, {:?}, {:?} for function clamp", v, lo, hi)
+                            }
+                        }
+                    }
+                    "to_int" => fst.to_int(),
+                    "to_float" => fst.to_float(),
+                    "round_to_int" => fst.round_to_int(),
+                    "trunc_to_int" => fst.trunc_to_int(),
+                    "haversine" | "bearing" => {
+                        if args.len() != 4 {
+                            unreachable!("wrong number of arguments for function {}", name)
+                        }
+                        let rest: Vec<Value> = args[1..].iter().map(|a| self.eval_expr(*a, ts)).collect();
+                        if name == "haversine" {
+                            Value::haversine(&fst, &rest[0], &rest[1], &rest[2])
+                        } else {
+                            Value::bearing(&fst, &rest[0], &rest[1], &rest[2])
+                        }
+                    }
+                    "wrap_angle_deg" => fst.wrap_angle_deg(),
+                    "wrap_angle_rad" => fst.wrap_angle_rad(),
+                    "angle_diff" => fst.angle_diff(&self.eval_expr(args[1], ts)),
+                    "checked_add" | "checked_sub" | "checked_mul" | "checked_div" => {
+                        let op: fn(i128, i128) -> Option<i128> = match name.as_ref() {
+                            "checked_add" => i128::checked_add,
+                            "checked_sub" => i128::checked_sub,
+                            "checked_mul" => i128::checked_mul,
+                            _ => i128::checked_div,
+                        };
+                        fst.checked_arith(&self.eval_expr(args[1], ts), op, Value::width_of_arg(ty))
+                    }
+                    "saturating_add" | "saturating_sub" | "saturating_mul" => {
+                        let op: fn(i128, i128) -> i128 = match name.as_ref() {
+                            "saturating_add" => |a, b| a + b,
+                            "saturating_sub" => |a, b| a - b,
+                            // Products of unsigned 64-bit integers may exceed `i128`.
+                            _ => |a, b| {
+                                a.checked_mul(b).unwrap_or(if (a < 0) == (b < 0) { i128::MAX } else { i128::MIN })
+                            },
+                        };
+                        fst.saturating_arith(&self.eval_expr(args[1], ts), op, Value::width_of_arg(ty))
+                    }
+                    "lerp" => Value::lerp(&fst, &self.eval_expr(args[1], ts), &self.eval_expr(args[2], ts)),
+                    "map_range" => {
+                        if args.len() != 5 {
+                            unreachable!("wrong number of arguments for function {}", name)
+                        }
+                        let rest: Vec<Value> = args[1..].iter().map(|a| self.eval_expr(*a, ts)).collect();
+                        Value::map_range(&fst, &rest[0], &rest[1], &rest[2], &rest[3])
+                    }
+                    "zscore" => fst.zscore(&self.eval_expr(args[1], ts), &self.eval_expr(args[2], ts)),
+                    "hour_of" => fst.hour_of(),
+                    "day_of_week" => fst.day_of_week(),
+                    "since_midnight" => fst.since_midnight(),
+                    // durations are represented by their length in seconds
+                    "seconds" => fst,
+                    "millis" => fst.millis(),
+                    "to_duration" => fst.to_float(),
+                    "crc16" => fst.crc16(),
+                    "crc32" => fst.crc32(),
+                    "hash64" => fst.hash64(),
+                    "bit" | "bits" | "popcount" | "set_bit" => {
+                        let width = Value::width_of_arg(ty);
+                        match name.as_ref() {
+                            "bit" => fst.bit(&self.eval_expr(args[1], ts), width),
+                            "bits" => fst.bits(&self.eval_expr(args[1], ts), &self.eval_expr(args[2], ts), width),
+                            "popcount" => fst.popcount(width),
+                            _ => fst.set_bit(&self.eval_expr(args[1], ts), &self.eval_expr(args[2], ts), width),
+                        }
+                    }
+                    "floor" | "ceil" | "round" => {
+                        let digits = args.get(1).map(|d| self.eval_expr(*d, ts));
+                        match name.as_ref() {
+                            "floor" => fst.floor(digits.as_ref()),
+                            "ceil" => fst.ceil(digits.as_ref()),
+                            _ => fst.round(digits.as_ref()),
+                        }
+                    }
+                    "length" => fst.length(),
+                    "contains" => fst.contains(&self.eval_expr(args[1], ts)),
+                    "starts_with" => fst.starts_with(&self.eval_expr(args[1], ts)),
+                    "ends_with" => fst.ends_with(&self.eval_expr(args[1], ts)),
+                    "u16_le" => fst.u16_le(&self.eval_expr(args[1], ts)),
+                    "u16_be" => fst.u16_be(&self.eval_expr(args[1], ts)),
+                    "i32_le" => fst.i32_le(&self.eval_expr(args[1], ts)),
+                    "i32_be" => fst.i32_be(&self.eval_expr(args[1], ts)),
+                    "f32_le" => fst.f32_le(&self.eval_expr(args[1], ts)),
+                    "f32_be" => fst.f32_be(&self.eval_expr(args[1], ts)),
+                    "to_upper" => fst.to_upper(),
+                    "to_lower" => fst.to_lower(),
+                    "parse_int" => fst.parse_int(),
+                    "parse_float" => fst.parse_float(),
+                    "substring" => fst.substring(&self.eval_expr(args[1], ts), &self.eval_expr(args[2], ts)),
                     "matches" => {
                         if args.len() != 2 {
                             unreachable!("wrong number of arguments for match")
                         }
-                        if let Value::Str(s) = fst {
-                            let re_str = match &args[1].kind {
-                                LoadConstant(Constant::Str(s)) => s,
-                                _ => unreachable!("regex should be a string literal"),
-                            };
-                            // compiling regex every time it is used is a performance problem
-                            // TODO: move it out of the eval loop
-                            let re = Regex::new(&re_str).expect("Given regular expression was invalid");
-                            Value::Bool(re.is_match(&s))
-                        } else {
-                            unreachable!()
+                        let re_str = match &self.expressions[args[1]].kind {
+                            LoadConstant(Constant::Str(s)) => s,
+                            _ => unreachable!("regex should be a string literal"),
+                        };
+                        match fst {
+                            Value::Str(s) => Value::Bool(self.patterns.strings[re_str].is_match(&s)),
+                            Value::Bytes(b) => Value::Bool(self.patterns.bytes[re_str].is_match(&b)),
+                            v => unreachable!("expected `String` or `Bytes`, found {:?}", v),
                         }
                     }
-                    _ => unreachable!("Unknown function: {}, args: {:?}", name, args),
+                    _ => {
+                        let mut values = vec![fst];
+                        values.extend(args[1..].iter().map(|a| self.eval_expr(*a, ts)));
+                        self.functions.call(name, &values)
+                    }
                 }
             }
 
-            Tuple(entries) => Value::Tuple(entries.iter().map(|e| self.eval_expr(e, ts)).collect()),
+            Tuple(entries) => Value::Tuple(entries.iter().map(|e| self.eval_expr(*e, ts)).collect()),
+
+            ParameterAccess(ix) => self.parameters[*ix].clone(),
+
+            InstanceAccess(target, args) => {
+                let parameters: Vec<Value> = args.iter().map(|a| self.eval_expr(*a, ts)).collect();
+                let instance = self.global_store.get_param_instance(target.out_ix(), &parameters);
+                instance.and_then(|inst| inst.get_value(0)).unwrap_or(Value::None)
+            }
 
             Convert { from, to, expr } => {
                 use Type::*;
-                let v = self.eval_expr(expr, ts);
+                let v = self.eval_expr(*expr, ts);
                 match (from, v) {
                     (UInt(_), Value::Unsigned(u)) => match to {
                         UInt(_) => Value::Unsigned(u),
@@ -531,17 +1072,19 @@ impl<'a> ExpressionEvaluator<'a> {
                 }
             }
 
+            Cast { to, expr, .. } => self.eval_expr(*expr, ts).checked_cast(to),
+
             Default { expr, default, .. } => {
-                let v = self.eval_expr(expr, ts);
+                let v = self.eval_expr(*expr, ts);
                 if let Value::None = v {
-                    self.eval_expr(default, ts)
+                    self.eval_expr(*default, ts)
                 } else {
                     v
                 }
             }
 
             TupleAccess(expr, num) => {
-                if let Value::Tuple(entries) = self.eval_expr(expr, ts) {
+                if let Value::Tuple(entries) = self.eval_expr(*expr, ts) {
                     entries[*num].clone()
                 } else {
                     unreachable!("verified by type checker")
@@ -550,87 +1093,158 @@ impl<'a> ExpressionEvaluator<'a> {
         }
     }
 
+    /// Computes the quantitative robustness of a boolean expression.
+    ///
+    /// A positive value means the expression holds, a negative one that it does not; the magnitude states how much the
+    /// compared values have to change to flip the verdict.  Comparisons of numbers yield the distance of the operands,
+    /// conjunctions the minimum, disjunctions the maximum, and negations the negated robustness of their operands.
+    /// All other boolean expressions are infinitely robust.
+    fn eval_robustness(&self, expr: ExprId, ts: Time) -> f64 {
+        use rtlola_frontend::ir::ArithLogOp::*;
+        use rtlola_frontend::ir::NodeKind::*;
+        match &self.expressions[expr].kind {
+            ArithLog(Not, operands, _) => return -self.eval_robustness(operands[0], ts),
+            ArithLog(And, operands, _) => {
+                return self.eval_robustness(operands[0], ts).min(self.eval_robustness(operands[1], ts))
+            }
+            ArithLog(Or, operands, _) => {
+                return self.eval_robustness(operands[0], ts).max(self.eval_robustness(operands[1], ts))
+            }
+            ArithLog(op @ Lt, operands, _)
+            | ArithLog(op @ Le, operands, _)
+            | ArithLog(op @ Gt, operands, _)
+            | ArithLog(op @ Ge, operands, _)
+            | ArithLog(op @ Eq, operands, _)
+            | ArithLog(op @ Ne, operands, _) => {
+                let lhs = self.eval_expr(operands[0], ts).get_numeric();
+                let rhs = self.eval_expr(operands[1], ts).get_numeric();
+                if let (Some(lhs), Some(rhs)) = (lhs, rhs) {
+                    return match op {
+                        Lt | Le => rhs - lhs,
+                        Gt | Ge => lhs - rhs,
+                        Eq => -(lhs - rhs).abs(),
+                        Ne => (lhs - rhs).abs(),
+                        _ => unreachable!(),
+                    };
+                }
+            }
+            Ite { condition, consequence, alternative, .. } => {
+                return if self.eval_expr(*condition, ts).get_bool() {
+                    self.eval_robustness(*consequence, ts)
+                } else {
+                    self.eval_robustness(*alternative, ts)
+                };
+            }
+            Default { expr: inner, default, .. } => {
+                return if let Value::None = self.eval_expr(*inner, ts) {
+                    self.eval_robustness(*default, ts)
+                } else {
+                    self.eval_robustness(*inner, ts)
+                };
+            }
+            _ => {}
+        }
+        match self.eval_expr(expr, ts) {
+            Value::Bool(true) => f64::INFINITY,
+            _ => f64::NEG_INFINITY,
+        }
+    }
+
     fn lookup_latest(&self, stream_ref: StreamReference) -> Value {
         let inst = match stream_ref {
-            StreamReference::InRef(ix) => self.global_store.get_in_instance(ix),
-            StreamReference::OutRef(ix) => self.global_store.get_out_instance(ix).expect("no out instance"),
+            StreamReference::InRef(ix) => Some(self.global_store.get_in_instance(ix)),
+            StreamReference::OutRef(ix) => self.out_instance(ix),
         };
-        inst.get_value(0).unwrap_or(Value::None)
+        inst.and_then(|inst| inst.get_value(0)).unwrap_or(Value::None)
     }
 
     fn lookup_latest_check(&self, stream_ref: StreamReference) -> Value {
         let inst = match stream_ref {
             StreamReference::InRef(ix) => {
                 debug_assert!(self.fresh_inputs.contains(ix), "ix={}", ix);
-                self.global_store.get_in_instance(ix)
+                Some(self.global_store.get_in_instance(ix))
             }
             StreamReference::OutRef(ix) => {
                 debug_assert!(self.fresh_outputs.contains(ix), "ix={}", ix);
-                self.global_store.get_out_instance(ix).expect("no out instance")
+                self.out_instance(ix)
             }
         };
-        inst.get_value(0).unwrap_or(Value::None)
+        inst.and_then(|inst| inst.get_value(0)).unwrap_or(Value::None)
     }
 
     fn lookup_with_offset(&self, stream_ref: StreamReference, offset: i16) -> Value {
         let (inst, fresh) = match stream_ref {
-            StreamReference::InRef(ix) => (self.global_store.get_in_instance(ix), self.fresh_inputs.contains(ix)),
-            StreamReference::OutRef(ix) => {
-                (self.global_store.get_out_instance(ix).expect("no out instance"), self.fresh_outputs.contains(ix))
-            }
+            StreamReference::InRef(ix) => (Some(self.global_store.get_in_instance(ix)), self.fresh_inputs.contains(ix)),
+            StreamReference::OutRef(ix) => (self.out_instance(ix), self.fresh_outputs.contains(ix)),
         };
-        if fresh {
-            inst.get_value(offset).unwrap_or(Value::None)
-        } else {
-            inst.get_value(offset + 1).unwrap_or(Value::None)
-        }
+        let offset = if fresh { offset } else { offset + 1 };
+        inst.and_then(|inst| inst.get_value(offset)).unwrap_or(Value::None)
     }
 
     fn lookup_window(&self, window_ref: WindowReference, ts: Time) -> Value {
         self.global_store.get_window(window_ref).get_value(ts)
     }
+
+    /// Returns the storage of an output stream, which is the instance being evaluated for a parameterized stream.
+    /// Returns `None` for a parameterized stream accessed without parameters or for an instance that was closed.
+    fn out_instance(&self, ix: OutputReference) -> Option<&InstanceStore> {
+        let store = self.global_store;
+        store.get_out_instance(ix).or_else(|| store.get_param_instance(ix, self.parameters))
+    }
 }
 
 impl<'e> EvaluationContext<'e> {
     pub(crate) fn lookup_latest(&self, stream_ref: StreamReference) -> Value {
         let inst = match stream_ref {
-            StreamReference::InRef(ix) => self.global_store.get_in_instance(ix),
-            StreamReference::OutRef(ix) => self.global_store.get_out_instance(ix).expect("no out instance"),
+            StreamReference::InRef(ix) => Some(self.global_store.get_in_instance(ix)),
+            StreamReference::OutRef(ix) => self.out_instance(ix),
         };
-        inst.get_value(0).unwrap_or(Value::None)
+        inst.and_then(|inst| inst.get_value(0)).unwrap_or(Value::None)
     }
 
     pub(crate) fn lookup_latest_check(&self, stream_ref: StreamReference) -> Value {
         let inst = match stream_ref {
             StreamReference::InRef(ix) => {
                 debug_assert!(self.fresh_inputs.contains(ix), "ix={}", ix);
-                self.global_store.get_in_instance(ix)
+                Some(self.global_store.get_in_instance(ix))
             }
             StreamReference::OutRef(ix) => {
                 debug_assert!(self.fresh_outputs.contains(ix), "ix={}", ix);
-                self.global_store.get_out_instance(ix).expect("no out instance")
+                self.out_instance(ix)
             }
         };
-        inst.get_value(0).unwrap_or(Value::None)
+        inst.and_then(|inst| inst.get_value(0)).unwrap_or(Value::None)
     }
 
     pub(crate) fn lookup_with_offset(&self, stream_ref: StreamReference, offset: i16) -> Value {
         let (inst, fresh) = match stream_ref {
-            StreamReference::InRef(ix) => (self.global_store.get_in_instance(ix), self.fresh_inputs.contains(ix)),
-            StreamReference::OutRef(ix) => {
-                (self.global_store.get_out_instance(ix).expect("no out instance"), self.fresh_outputs.contains(ix))
-            }
+            StreamReference::InRef(ix) => (Some(self.global_store.get_in_instance(ix)), self.fresh_inputs.contains(ix)),
+            StreamReference::OutRef(ix) => (self.out_instance(ix), self.fresh_outputs.contains(ix)),
         };
-        if fresh {
-            inst.get_value(offset).unwrap_or(Value::None)
-        } else {
-            inst.get_value(offset + 1).unwrap_or(Value::None)
-        }
+        let offset = if fresh { offset } else { offset + 1 };
+        inst.and_then(|inst| inst.get_value(offset)).unwrap_or(Value::None)
     }
 
     pub(crate) fn lookup_window(&self, window_ref: WindowReference) -> Value {
         self.global_store.get_window(window_ref).get_value(self.ts)
     }
+
+    /// Returns the latest value of the instance of a parameterized stream, `Value::None` if it was not spawned.
+    pub(crate) fn lookup_instance(&self, target: StreamReference, parameters: &[Value]) -> Value {
+        let instance = self.global_store.get_param_instance(target.out_ix(), parameters);
+        instance.and_then(|inst| inst.get_value(0)).unwrap_or(Value::None)
+    }
+
+    /// Returns the storage of an output stream, which is the instance being evaluated for a parameterized stream.
+    /// Returns `None` for a parameterized stream accessed without parameters or for an instance that was closed.
+    fn out_instance(&self, ix: OutputReference) -> Option<&InstanceStore> {
+        let store = self.global_store;
+        store.get_out_instance(ix).or_else(|| store.get_param_instance(ix, self.parameters))
+    }
+
+    pub(crate) fn now(&self) -> Value {
+        Value::new_float(self.ts.as_secs_f64())
+    }
 }
 
 impl ActivationCondition {
@@ -698,7 +1312,7 @@ mod tests {
         config.verbosity = crate::basics::Verbosity::WarningsOnly;
         let handler = Arc::new(OutputHandler::new(&config, ir.triggers.len()));
         let now = Instant::now();
-        let eval = EvaluatorData::new(ir.clone(), config, handler, now);
+        let eval = EvaluatorData::new(ir.clone(), config, handler, Some(now));
         (ir, eval, now)
     }
 
@@ -1019,6 +1633,23 @@ mod tests {
         assert_eq!(eval.peek_value(trig_ref, &Vec::new(), 0).unwrap(), Bool(true));
     }
 
+    #[test]
+    fn test_regex_interpreted() {
+        let spec = "import regex\ninput a: String\ninput b: Bytes\n\
+                    output x := a.matches(regex: \"^err\")\noutput y := b.matches(regex: \"^err\")";
+        let ir = parse(spec).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+        let mut config = EvalConfig::default();
+        config.verbosity = crate::basics::Verbosity::WarningsOnly;
+        config.evaluator = Interpreted;
+        let handler = Arc::new(OutputHandler::new(&config, ir.triggers.len()));
+        let start = Instant::now();
+        let mut eval = EvaluatorData::new(ir, config, handler, Some(start)).into_evaluator();
+        accept_input!(eval, start, StreamReference::InRef(0), Str("error: disk full".into()));
+        accept_input!(eval, start, StreamReference::InRef(1), Bytes(b"warning: disk full".to_vec().into()));
+        peek_assert_eq!(eval, start, 0, Bool(true));
+        peek_assert_eq!(eval, start, 1, Bool(false));
+    }
+
     #[test]
     fn test_sum_window() {
         let (_, eval, mut time) =
@@ -1133,4 +1764,65 @@ mod tests {
         eval_stream!(eval, start, 0);
         assert_eq!(eval.peek_value(out_ref, &Vec::new(), 0).unwrap(), expected);
     }
+
+    #[test]
+    fn escalating_trigger_messages() {
+        let (ir, eval, _) = setup_time("input a: Int64\ntrigger a > 5 escalate after 2 within 1s \"{count} times\"");
+        let mut eval = eval.into_evaluator();
+        let trig = &ir.triggers[0];
+        let ms = Duration::from_millis;
+        assert!(eval.notification(trig, &Bool(true), ms(0)).is_none());
+        assert!(eval.notification(trig, &Bool(false), ms(500)).is_none());
+        assert_eq!(eval.notification(trig, &Bool(true), ms(1000)), Some(String::from("2 times")));
+        assert_eq!(eval.notification(trig, &Bool(true), ms(1500)), Some(String::from("2 times")));
+        assert_eq!(eval.notification(trig, &Bool(true), ms(1800)), Some(String::from("3 times")));
+        assert!(eval.notification(trig, &Bool(true), ms(3000)).is_none());
+    }
+
+    #[test]
+    fn trigger_messages_with_stream_values() {
+        let spec = "input status: String\ninput altitude: Float64\ntrigger status == \"error\" \"{status} at {altitude}m, {{status}}\"";
+        let (ir, eval, _) = setup_time(spec);
+        let mut eval = eval.into_evaluator();
+        let trig = &ir.triggers[0];
+        let event = |status: &str, altitude: f64| vec![Str(status.into()), Value::new_float(altitude)];
+        eval.eval_event(&event("ok", 120.0), Duration::from_secs(0));
+        assert_eq!(eval.peek_value(trig.reference, &[], 0), Some(Bool(false)));
+        eval.eval_event(&event("error", 80.5), Duration::from_secs(1));
+        assert_eq!(eval.peek_value(trig.reference, &[], 0), Some(Bool(true)));
+        let message = eval.notification(trig, &Bool(true), Duration::from_secs(1));
+        assert_eq!(message, Some(String::from("error at 80.5m, {status}")));
+    }
+
+    #[test]
+    fn trigger_messages_with_escapes() {
+        let spec = "input altitude: Float64\ntrigger altitude < 100.0 \"\\\"low\\\"\\n\\t{altitude}m\"";
+        let (ir, eval, _) = setup_time(spec);
+        let mut eval = eval.into_evaluator();
+        let trig = &ir.triggers[0];
+        eval.eval_event(&[Value::new_float(80.5)], Duration::from_secs(0));
+        let message = eval.notification(trig, &Bool(true), Duration::from_secs(0));
+        assert_eq!(message, Some(String::from("\"low\"\n\t80.5m")));
+    }
+
+    #[test]
+    fn trigger_messages_with_variants() {
+        let spec = "enum Mode { Init, Armed, Flying }\ninput m: Mode\ntrigger m == Mode::Flying \"flying {m}\"";
+        let (ir, eval, _) = setup_time(spec);
+        let mut eval = eval.into_evaluator();
+        let trig = &ir.triggers[0];
+        eval.eval_event(&[Unsigned(2)], Duration::from_secs(0));
+        let message = eval.notification(trig, &Bool(true), Duration::from_secs(0));
+        assert_eq!(message, Some(String::from("flying Flying")));
+    }
+
+    #[test]
+    fn assumptions_of_triggers() {
+        let spec = "input a: Float64\ninput b: Float64\nassume a >= 0.0\ntrigger a < 10.0\ntrigger b > 5.0";
+        let (ir, eval, _) = setup_time(spec);
+        let out_ix = |ix: usize| ir.triggers[ix].reference.out_ix();
+        assert!(ir.triggers[0].assumption);
+        assert_eq!(eval.assumptions[out_ix(1)], vec![out_ix(0)]);
+        assert!(eval.assumptions[out_ix(2)].is_empty());
+    }
 }
@@ -0,0 +1,305 @@
+//! This module explains why a trigger fired, see `Explanation`.
+//!
+//! An explanation lists the values of the sub-expressions of the trigger condition and the latest values of the streams it depends on.
+//! Sub-expressions are rendered in the syntax of the specification, where constants and synchronous accesses are left out since their values are obvious or listed with the streams.
+
+use crate::basics::Time;
+use crate::storage::Value;
+use rtlola_frontend::ir::{
+    ArithLogOp, Constant, ExprId, NodeKind, Offset, RTLolaIR, StreamAccessKind, StreamReference, Trigger,
+    WindowOperation,
+};
+use std::fmt::{Display, Formatter, Result};
+
+/// The values that determined the verdict of a trigger.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Explanation {
+    /// The message of the trigger.
+    pub trigger: String,
+    /// The time of the verdict.
+    pub time: Time,
+    /// Each sub-expression of the trigger condition with its value, outermost first.
+    pub subexpressions: Vec<(String, Value)>,
+    /// Each stream the trigger depends on, directly or transitively, with its latest value.
+    pub streams: Vec<(String, Value)>,
+}
+
+impl Explanation {
+    /// Explains the trigger with the values of its sub-expressions and the latest values of streams.
+    pub(crate) fn new(
+        ir: &RTLolaIR,
+        trigger: &Trigger,
+        time: Time,
+        eval: impl Fn(ExprId) -> Value,
+        latest: impl Fn(StreamReference) -> Value,
+    ) -> Explanation {
+        let mut ids = Vec::new();
+        collect(ir, ir.outputs[trigger.reference.out_ix()].expr_id, &mut ids);
+        let subexpressions = ids.into_iter().map(|id| (render(ir, id), eval(id))).collect();
+        let streams =
+            upstream(ir, trigger.reference).into_iter().map(|sr| (name(ir, sr).to_string(), latest(sr))).collect();
+        Explanation { trigger: trigger.message.clone(), time, subexpressions, streams }
+    }
+}
+
+/// Renders the explanation as a JSON object on a single line.
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        let entries = |key: &str, entries: &[(String, Value)]| -> String {
+            let entries: Vec<String> = entries
+                .iter()
+                .map(|(name, value)| format!("{{\"{}\": {}, \"value\": {}}}", key, string(name), json(value)))
+                .collect();
+            entries.join(", ")
+        };
+        write!(
+            f,
+            "{{\"trigger\": {}, \"time\": {}, \"subexpressions\": [{}], \"streams\": [{}]}}",
+            string(&self.trigger),
+            self.time.as_secs_f64(),
+            entries("expression", &self.subexpressions),
+            entries("stream", &self.streams)
+        )
+    }
+}
+
+/// Collects the sub-expressions worth explaining in preorder, each once.
+fn collect(ir: &RTLolaIR, id: ExprId, ids: &mut Vec<ExprId>) {
+    let node = &ir.expressions[id];
+    let explained = match &node.kind {
+        NodeKind::LoadConstant(_) | NodeKind::StreamAccess(_, StreamAccessKind::Sync) => false,
+        // conversions are transparent, so their operand is explained instead
+        NodeKind::Convert { .. } => false,
+        _ => true,
+    };
+    if explained && !ids.contains(&id) {
+        ids.push(id);
+    }
+    match &node.kind {
+        NodeKind::ArithLog(_, args, _)
+        | NodeKind::Tuple(args)
+        | NodeKind::Function(_, args, _)
+        | NodeKind::InstanceAccess(_, args) => args.iter().for_each(|arg| collect(ir, *arg, ids)),
+        NodeKind::Ite { condition, consequence, alternative } => {
+            collect(ir, *condition, ids);
+            collect(ir, *consequence, ids);
+            collect(ir, *alternative, ids);
+        }
+        NodeKind::TupleAccess(expr, _) | NodeKind::Convert { expr, .. } | NodeKind::Cast { expr, .. } => {
+            collect(ir, *expr, ids)
+        }
+        NodeKind::Default { expr, default } => {
+            collect(ir, *expr, ids);
+            collect(ir, *default, ids);
+        }
+        NodeKind::LoadConstant(_)
+        | NodeKind::ConfigurableConstant(_)
+        | NodeKind::OffsetLookup { .. }
+        | NodeKind::StreamAccess(..)
+        | NodeKind::WindowLookup(_)
+        | NodeKind::Now
+        | NodeKind::ParameterAccess(_) => {}
+    }
+}
+
+/// Returns the streams the output depends on, directly or transitively, in the order of their references.
+fn upstream(ir: &RTLolaIR, output: StreamReference) -> Vec<StreamReference> {
+    let mut streams = Vec::new();
+    let mut pending = vec![output];
+    while let Some(sr) = pending.pop() {
+        if let StreamReference::OutRef(_) = sr {
+            let out = ir.get_out(sr);
+            for dependency in out.outgoing_dependencies.iter().map(|d| d.stream).chain(out.input_dependencies.clone()) {
+                if dependency != output && !streams.contains(&dependency) {
+                    streams.push(dependency);
+                    pending.push(dependency);
+                }
+            }
+        }
+    }
+    streams.sort_by_key(|sr| match sr {
+        StreamReference::InRef(ix) => (0, *ix),
+        StreamReference::OutRef(ix) => (1, *ix),
+    });
+    // parameterized streams have no single latest value
+    streams.retain(|sr| match sr {
+        StreamReference::InRef(_) => true,
+        StreamReference::OutRef(_) => ir.get_out(*sr).params.is_empty(),
+    });
+    streams
+}
+
+fn name(ir: &RTLolaIR, sr: StreamReference) -> &str {
+    match sr {
+        StreamReference::InRef(_) => &ir.get_in(sr).name,
+        StreamReference::OutRef(_) => &ir.get_out(sr).name,
+    }
+}
+
+/// Renders the expression in the syntax of the specification.
+fn render(ir: &RTLolaIR, id: ExprId) -> String {
+    // operands binding weaker than their operator are parenthesized, as are right operands binding equally strong
+    let operand = |mut id: ExprId, binding: u8| {
+        while let NodeKind::Convert { expr, .. } = &ir.expressions[id].kind {
+            id = *expr;
+        }
+        match &ir.expressions[id].kind {
+            NodeKind::ArithLog(op, args, _) if args.len() == 2 && operator(*op).1 < binding => {
+                format!("({})", render(ir, id))
+            }
+            NodeKind::Ite { .. } => format!("({})", render(ir, id)),
+            NodeKind::Cast { .. } if binding >= 12 => format!("({})", render(ir, id)),
+            _ => render(ir, id),
+        }
+    };
+    let list = |args: &[ExprId]| args.iter().map(|arg| render(ir, *arg)).collect::<Vec<String>>().join(", ");
+    match &ir.expressions[id].kind {
+        NodeKind::LoadConstant(Constant::Str(s)) => format!("\"{}\"", s),
+        NodeKind::LoadConstant(c) => c.to_string(),
+        NodeKind::ConfigurableConstant(ix) => ir.constants[*ix].name.clone(),
+        NodeKind::ArithLog(op, args, _) if args.len() == 1 => format!("{}{}", operator(*op).0, operand(args[0], 12)),
+        NodeKind::ArithLog(op, args, _) => {
+            let (symbol, binding) = operator(*op);
+            format!("{} {} {}", operand(args[0], binding), symbol, operand(args[1], binding + 1))
+        }
+        NodeKind::OffsetLookup { target, offset } => {
+            let offset = match offset {
+                Offset::PastDiscreteOffset(u) => format!("-{}", u),
+                Offset::FutureDiscreteOffset(u) => u.to_string(),
+                Offset::PastRealTimeOffset(d) => format!("-{:?}", d),
+                Offset::FutureRealTimeOffset(d) => format!("{:?}", d),
+            };
+            format!("{}.offset(by: {})", name(ir, *target), offset)
+        }
+        NodeKind::StreamAccess(target, StreamAccessKind::Sync) => name(ir, *target).to_string(),
+        NodeKind::StreamAccess(target, StreamAccessKind::Hold) => format!("{}.hold()", name(ir, *target)),
+        NodeKind::StreamAccess(target, StreamAccessKind::Optional) => format!("{}.get()", name(ir, *target)),
+        NodeKind::WindowLookup(window) => {
+            let window = ir.get_window(*window);
+            let percentile;
+            let op = match &window.op {
+                WindowOperation::Sum => "sum",
+                WindowOperation::Product => "product",
+                WindowOperation::Average => "avg",
+                WindowOperation::Count => "count",
+                WindowOperation::Integral => "integral",
+                WindowOperation::Min => "min",
+                WindowOperation::Max => "max",
+                WindowOperation::Disjunction => "exists",
+                WindowOperation::Conjunction => "forall",
+                WindowOperation::Median => "median",
+                WindowOperation::First => "first",
+                WindowOperation::Last => "last",
+                WindowOperation::Percentile(p) => {
+                    percentile = format!("percentile({})", p);
+                    percentile.as_str()
+                }
+                WindowOperation::Custom(name) => name.as_str(),
+            };
+            format!("{}.aggregate(over: {:?}, using: {})", name(ir, window.target), window.duration, op)
+        }
+        NodeKind::Now => String::from("now"),
+        NodeKind::Ite { condition, consequence, alternative } => {
+            format!("if {} then {} else {}", render(ir, *condition), render(ir, *consequence), render(ir, *alternative))
+        }
+        NodeKind::Tuple(entries) => format!("({})", list(entries)),
+        NodeKind::TupleAccess(expr, num) => format!("{}.{}", operand(*expr, 12), num),
+        NodeKind::Function(name, args, _) => format!("{}({})", name, list(args)),
+        NodeKind::InstanceAccess(target, args) => format!("{}({})", name(ir, *target), list(args)),
+        NodeKind::ParameterAccess(_) => unreachable!("triggers have no parameters"),
+        NodeKind::Convert { expr, .. } => render(ir, *expr),
+        NodeKind::Cast { to, expr, .. } => format!("{} as {}", operand(*expr, 12), to),
+        NodeKind::Default { expr, default } => format!("{}.defaults(to: {})", operand(*expr, 12), render(ir, *default)),
+    }
+}
+
+/// Returns the symbol of the operator and how strongly it binds, following the precedence of the parser.
+fn operator(op: ArithLogOp) -> (&'static str, u8) {
+    use ArithLogOp::*;
+    match op {
+        Or => ("||", 1),
+        And => ("&&", 2),
+        BitOr => ("|", 3),
+        BitXor => ("^", 4),
+        BitAnd => ("&", 5),
+        Eq => ("==", 6),
+        Ne => ("!=", 6),
+        Lt => ("<", 7),
+        Le => ("<=", 7),
+        Gt => (">", 7),
+        Ge => (">=", 7),
+        Shl => ("<<", 8),
+        Shr => (">>", 8),
+        Add => ("+", 9),
+        Sub => ("-", 9),
+        Mul => ("*", 10),
+        Div => ("/", 10),
+        Rem => ("%", 10),
+        Pow => ("**", 11),
+        Not => ("!", 12),
+        Neg => ("-", 12),
+        BitNot => ("~", 12),
+    }
+}
+
+/// Renders the value as JSON, where absent values are `null` and tuples are arrays.
+fn json(value: &Value) -> String {
+    match value {
+        Value::None => String::from("null"),
+        Value::Bool(b) => b.to_string(),
+        Value::Unsigned(u) => u.to_string(),
+        Value::Signed(i) => i.to_string(),
+        Value::Float(f) => f.to_string(),
+        Value::Str(s) => string(s),
+        Value::Tuple(entries) => format!("[{}]", entries.iter().map(json).collect::<Vec<String>>().join(", ")),
+        Value::Bytes(b) => string(&String::from_utf8_lossy(b)),
+    }
+}
+
+/// Escapes and quotes a JSON string.
+fn string(s: &str) -> String {
+    let mut escaped = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => escaped += "\\\"",
+            '\\' => escaped += "\\\\",
+            '\n' => escaped += "\\n",
+            c if (c as u32) < 0x20 => escaped += &format!("\\u{:04x}", c as u32),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CONFIG;
+
+    #[test]
+    fn subexpressions_are_rendered() {
+        let spec = "input a: Int64\ninput b: Bool\noutput c := a.offset(by: -1).defaults(to: 0)\ntrigger a + c > 5 && b \"too high\"";
+        let ir = rtlola_frontend::parse("spec.lola", spec, CONFIG).unwrap();
+        let trigger = &ir.triggers[0];
+        let explanation = Explanation::new(&ir, trigger, Time::from_secs(2), |_| Value::Bool(true), |_| Value::None);
+        let subexpressions: Vec<&str> = explanation.subexpressions.iter().map(|(e, _)| e.as_str()).collect();
+        assert_eq!(subexpressions, vec!["a + c > 5 && b", "a + c > 5", "a + c"]);
+        let streams: Vec<&str> = explanation.streams.iter().map(|(s, _)| s.as_str()).collect();
+        assert_eq!(streams, vec!["a", "b", "c"]);
+    }
+
+    #[test]
+    fn explanations_are_json() {
+        let explanation = Explanation {
+            trigger: String::from("\"high\""),
+            time: Time::from_millis(1500),
+            subexpressions: vec![(String::from("a > 5"), Value::Bool(true))],
+            streams: vec![(String::from("a"), Value::Signed(7)), (String::from("b"), Value::None)],
+        };
+        assert_eq!(
+            explanation.to_string(),
+            "{\"trigger\": \"\\\"high\\\"\", \"time\": 1.5, \"subexpressions\": [{\"expression\": \"a > 5\", \"value\": true}], \"streams\": [{\"stream\": \"a\", \"value\": 7}, {\"stream\": \"b\", \"value\": null}]}"
+        );
+    }
+}
@@ -14,7 +14,13 @@
 mod basics;
 mod closuregen;
 mod coordination;
+mod counterexample;
 mod evaluator;
+mod explanation;
+mod minimize;
+mod profile;
+mod scaffold;
+mod slice;
 mod storage;
 #[cfg(test)]
 mod tests;
@@ -22,18 +28,21 @@ mod tests;
 use crate::basics::OutputHandler;
 use crate::coordination::Controller;
 use basics::{
-    CSVInputSource, EvalConfig, EvaluatorChoice, EventSourceConfig, ExecutionMode, OutputChannel, PCAPInputSource,
-    Statistics, TimeFormat, TimeRepresentation, Verbosity,
+    CANInputSource, CSVInputSource, EvaluatorChoice, EventSourceConfig, ExecutionMode, HostMetricsInputSource,
+    InfluxConfig, InfluxTarget, MAVLinkInputSource, OutputChannel, PCAPEventSource, PCAPInputSource, Statistics,
+    Verbosity,
 };
 use clap::{App, AppSettings, Arg, ArgGroup, SubCommand};
 use rtlola_frontend;
 use rtlola_frontend::ir::RTLolaIR;
-use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_frontend::{FrontendConfig, TypeConfig, WarningConfig};
 use std::fs;
 use std::sync::Arc;
 
-pub use crate::coordination::Monitor;
-pub use crate::storage::Value;
+pub use crate::basics::{EvalConfig, FunctionImpl, TimeFormat, TimeRepresentation};
+pub use crate::coordination::{Monitor, StateSlice, Update};
+pub use crate::explanation::Explanation;
+pub use crate::storage::{Value, WindowAggregation};
 
 // TODO add example to doc
 
@@ -50,7 +59,7 @@ pub struct Config {
 }
 
 const CONFIG: FrontendConfig =
-    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+    FrontendConfig { ty: TypeConfig { use_64bit_only: false, type_aliases: false }, allow_parameters: true };
 
 impl Config {
     // TODO find appropriate name
@@ -61,6 +70,31 @@ impl Config {
         Config { cfg, ir }
     }
 
+    /**
+    Parses the specification and creates a new `Config` which can then be turned into a `Monitor` by `into_monitor`.
+
+    Warnings of the specification are reported according to `cfg.warnings`; if a warning is denied, an error is returned.
+    Declarations with `cfg` attributes are kept according to `cfg.features`.
+    */
+    pub fn from_spec(filename: &str, spec: &str, cfg: EvalConfig) -> Result<Config, String> {
+        let ir = rtlola_frontend::parse_with_features(filename, spec, CONFIG, &cfg.features, &cfg.warnings)?;
+        Ok(Config { cfg, ir })
+    }
+
+    /**
+    Parses several independent specifications sharing their inputs, given by name and content, and creates a new `Config` monitoring them together, see `RTLolaIR::merge`.
+
+    Each event is decoded once for all specifications; the triggers of the monitor refer to their specification by `Trigger::specification`.
+    */
+    pub fn from_specs(specs: &[(&str, &str)], cfg: EvalConfig) -> Result<Config, String> {
+        let mut specifications = Vec::with_capacity(specs.len());
+        for (name, spec) in specs {
+            let ir = rtlola_frontend::parse_with_features(name, spec, CONFIG, &cfg.features, &cfg.warnings)?;
+            specifications.push((name.to_string(), ir));
+        }
+        Ok(Config { cfg, ir: RTLolaIR::merge(specifications)? })
+    }
+
     /**
     Parses command line arguments and return a `Config` if successful.
 
@@ -78,8 +112,9 @@ impl Config {
             .about("Start monitoring for the given specification")
             .arg(
                 Arg::with_name("SPEC")
-                    .help("Sets the specification file to use")
+                    .help("Sets the specification file to use\nSeveral specifications sharing their inputs are monitored together.")
                     .required(true)
+                    .multiple(true)
                     .index(1),
             )
             .arg(
@@ -95,6 +130,63 @@ impl Config {
                     .number_of_values(1)
                     .conflicts_with("STDIN")
             )
+            .arg(
+                Arg::with_name("MAVLINK_UDP")
+                    .help("Read MAVLink telemetry from UDP datagrams received on the given address, e.g., 0.0.0.0:14550\nInputs are named MESSAGE::field, e.g., ATTITUDE::roll.")
+                    .long("mavlink-udp")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .conflicts_with_all(&["STDIN", "CSV_INPUT_FILE"])
+            )
+            .arg(
+                Arg::with_name("MAVLINK_SERIAL")
+                    .help("Read MAVLink telemetry from a configured serial device, e.g., /dev/ttyUSB0\nInputs are named MESSAGE::field, e.g., ATTITUDE::roll.")
+                    .long("mavlink-serial")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .conflicts_with_all(&["STDIN", "CSV_INPUT_FILE", "MAVLINK_UDP"])
+            )
+            .arg(
+                Arg::with_name("CAN_INTERFACE")
+                    .help("Read CAN frames from a SocketCAN interface, e.g., can0\nRequires a DBC file, inputs are named MESSAGE::SIGNAL.")
+                    .long("can-iface")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .requires("DBC")
+                    .conflicts_with_all(&["STDIN", "CSV_INPUT_FILE", "MAVLINK_UDP", "MAVLINK_SERIAL"])
+            )
+            .arg(
+                Arg::with_name("CANDUMP_FILE")
+                    .help("Replay CAN frames from a log file recorded with `candump -l`\nRequires a DBC file, inputs are named MESSAGE::SIGNAL.")
+                    .long("candump-in")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .requires("DBC")
+                    .conflicts_with_all(&["STDIN", "CSV_INPUT_FILE", "MAVLINK_UDP", "MAVLINK_SERIAL", "CAN_INTERFACE"])
+            )
+            .arg(
+                Arg::with_name("HOST_METRICS")
+                    .help("Sample metrics of the host at the given rate, e.g., 1s or 500ms (Linux only)\nInputs are named after the metrics, e.g., host::cpu_usage or host::mem_available.")
+                    .long("host-metrics")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .conflicts_with_all(&["STDIN", "CSV_INPUT_FILE", "MAVLINK_UDP", "MAVLINK_SERIAL", "CAN_INTERFACE", "CANDUMP_FILE"])
+            )
+            .arg(
+                Arg::with_name("HOST_METRICS_PID")
+                    .help("The process whose statistics are provided to the process::* inputs")
+                    .long("host-metrics-pid")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .requires("HOST_METRICS")
+            )
+            .arg(
+                Arg::with_name("DBC")
+                    .help("The DBC file used to decode CAN signals")
+                    .long("dbc")
+                    .takes_value(true)
+                    .number_of_values(1)
+            )
             .arg(
                 Arg::with_name("CSV_TIME_COLUMN")
                     .help("The column in the CSV that contains time info")
@@ -114,6 +206,38 @@ impl Config {
                     .long("stderr")
                     .conflicts_with_all(&["STDOUT", "OUTPUT_FILE"])
             )
+            .arg(
+                Arg::with_name("INFLUX_FILE")
+                    .help("Write stream values and trigger firings in InfluxDB line protocol to a file")
+                    .long("influx-out")
+                    .takes_value(true)
+                    .number_of_values(1)
+            )
+            .arg(
+                Arg::with_name("INFLUX_URL")
+                    .help("Post stream values and trigger firings in InfluxDB line protocol to a write endpoint,\ne.g., http://localhost:8086/write?db=telemetry")
+                    .long("influx-url")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .conflicts_with("INFLUX_FILE")
+            )
+            .arg(
+                Arg::with_name("INFLUX_MEASUREMENT")
+                    .help("The measurement name used for the InfluxDB output [default: rtlola]")
+                    .long("influx-measurement")
+                    .takes_value(true)
+                    .number_of_values(1)
+            )
+            .arg(
+                Arg::with_name("ROBUSTNESS")
+                    .help("Report the robustness of trigger conditions, i.e., how far the compared values are from flipping the verdict")
+                    .long("robustness")
+            )
+            .arg(
+                Arg::with_name("EXPLAIN")
+                    .help("Report the values of the sub-expressions and upstream streams that made a trigger fire, as JSON")
+                    .long("explain")
+            )
             .arg(
                 Arg::with_name("DELAY")
                     .help("Delay [ms] between reading in two lines from the input\nOnly used for file input.")
@@ -162,6 +286,45 @@ impl Config {
                     .required(true)
                     .args(&["ONLINE", "OFFLINE"])
             )
+            .arg(
+                Arg::with_name("DENY_WARNINGS")
+                    .help("Treat all warnings of the specification as errors")
+                    .long("deny-warnings")
+            )
+            .arg(
+                Arg::with_name("DENY")
+                    .help("Treat the warning with the given code as an error, e.g., W0201")
+                    .long("deny")
+                    .short("D")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .multiple(true)
+            )
+            .arg(
+                Arg::with_name("ALLOW")
+                    .help("Suppress the warning with the given code, e.g., W0201")
+                    .long("allow")
+                    .short("A")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .multiple(true)
+            )
+            .arg(
+                Arg::with_name("FEATURE")
+                    .help("Enables a feature, keeping the declarations with a `cfg` attribute that holds for it, e.g., simulation")
+                    .long("feature")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .multiple(true)
+            )
+            .arg(
+                Arg::with_name("CONSTANT")
+                    .help("Overrides the value of a configurable constant, e.g., max_speed=120")
+                    .long("constant")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .multiple(true)
+            )
             .arg(
                 Arg::with_name("INTERPRETED")
                     .long("interpreted")
@@ -179,6 +342,161 @@ impl Config {
                     .index(1),
             )
         )
+        .subcommand(
+            SubCommand::with_name("diff")
+            .about("Reports the added, removed, and changed declarations of a specification")
+            .arg(
+                Arg::with_name("OLD")
+                    .help("Sets the old specification file")
+                    .required(true)
+                    .index(1),
+            )
+            .arg(
+                Arg::with_name("NEW")
+                    .help("Sets the new specification file")
+                    .required(true)
+                    .index(2),
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("minimize")
+            .about("Shrinks a specification to a minimal one on which a trigger still fires or the frontend still crashes")
+            .arg(
+                Arg::with_name("SPEC")
+                    .help("Sets the specification file to minimize")
+                    .required(true)
+                    .index(1),
+            )
+            .arg(
+                Arg::with_name("CSV_INPUT_FILE")
+                    .help("The CSV trace on which the trigger fires")
+                    .long("csv-in")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .requires("TRIGGER")
+            )
+            .arg(
+                Arg::with_name("TRIGGER")
+                    .help("The message of the trigger that has to fire")
+                    .long("trigger")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .requires("CSV_INPUT_FILE")
+            )
+            .arg(
+                Arg::with_name("CRASH")
+                    .help("Preserve a crash of the frontend instead of a trigger")
+                    .long("crash")
+            )
+            .group(
+                ArgGroup::with_name("BEHAVIOR")
+                .args(&["TRIGGER", "CRASH"])
+                .required(true)
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("counterexample")
+            .about("Searches for a short input trace on which a trigger fires and prints it as CSV")
+            .arg(
+                Arg::with_name("SPEC")
+                    .help("Sets the specification file to use")
+                    .required(true)
+                    .index(1),
+            )
+            .arg(
+                Arg::with_name("TRIGGER")
+                    .help("The message of the trigger that has to fire")
+                    .long("trigger")
+                    .required(true)
+                    .takes_value(true)
+                    .number_of_values(1)
+            )
+            .arg(
+                Arg::with_name("MAX_LENGTH")
+                    .help("The maximal number of events of the trace")
+                    .long("max-length")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .default_value("3")
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("slice")
+            .about("Slices a CSV trace to a minimal sub-trace on which a trigger still fires")
+            .arg(
+                Arg::with_name("SPEC")
+                    .help("Sets the specification file to use")
+                    .required(true)
+                    .index(1),
+            )
+            .arg(
+                Arg::with_name("CSV_INPUT_FILE")
+                    .help("The recorded CSV trace on which the trigger fires")
+                    .long("csv-in")
+                    .required(true)
+                    .takes_value(true)
+                    .number_of_values(1)
+            )
+            .arg(
+                Arg::with_name("TRIGGER")
+                    .help("The message of the trigger that has to fire")
+                    .long("trigger")
+                    .required(true)
+                    .takes_value(true)
+                    .number_of_values(1)
+            )
+            .arg(
+                Arg::with_name("OUTPUT_FILE")
+                    .help("Write the sub-trace to the file instead of stdout")
+                    .long("output")
+                    .short("o")
+                    .takes_value(true)
+                    .number_of_values(1)
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("optimize")
+            .about("Reorders the operands of conjunctions and disjunctions by their profile on a representative trace")
+            .arg(
+                Arg::with_name("SPEC")
+                    .help("Sets the specification file to optimize")
+                    .required(true)
+                    .index(1),
+            )
+            .arg(
+                Arg::with_name("CSV_INPUT_FILE")
+                    .help("The representative CSV trace")
+                    .long("csv-in")
+                    .required(true)
+                    .takes_value(true)
+                    .number_of_values(1)
+            )
+            .arg(
+                Arg::with_name("OUTPUT_FILE")
+                    .help("Write the optimized specification to the file instead of stdout")
+                    .long("output")
+                    .short("o")
+                    .takes_value(true)
+                    .number_of_values(1)
+            )
+        )
+        .subcommand(
+            SubCommand::with_name("new")
+            .about("Creates a starter project with a specification, a monitor.toml, and a sample trace")
+            .arg(
+                Arg::with_name("PATH")
+                    .help("Sets the directory of the project, which must not exist or be empty")
+                    .required(true)
+                    .index(1),
+            )
+            .arg(
+                Arg::with_name("TEMPLATE")
+                    .help("Sets the monitored system, which determines the input source")
+                    .long("template")
+                    .possible_values(&scaffold::Template::NAMES)
+                    .default_value("uav")
+            )
+        )
         .subcommand(
             SubCommand::with_name("ids")
             .about("Use the rtlola monitor as a network intrusion detection system")
@@ -256,6 +574,29 @@ impl Config {
                     ])
                     .default_value("hide")
             )
+            .arg(
+                Arg::with_name("DENY_WARNINGS")
+                    .help("Treat all warnings of the specification as errors")
+                    .long("deny-warnings")
+            )
+            .arg(
+                Arg::with_name("DENY")
+                    .help("Treat the warning with the given code as an error, e.g., W0201")
+                    .long("deny")
+                    .short("D")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .multiple(true)
+            )
+            .arg(
+                Arg::with_name("ALLOW")
+                    .help("Suppress the warning with the given code, e.g., W0201")
+                    .long("allow")
+                    .short("A")
+                    .takes_value(true)
+                    .number_of_values(1)
+                    .multiple(true)
+            )
             .arg(
                 Arg::with_name("INTERPRETED")
                     .long("interpreted")
@@ -270,6 +611,148 @@ impl Config {
             rtlola_frontend::analyze(filename.as_str(), CONFIG);
             std::process::exit(0);
         }
+        if let Some(parse_matches) = parse_matches.subcommand_matches("diff") {
+            let read = |name| {
+                let filename = parse_matches.value_of(name).unwrap();
+                fs::read_to_string(filename).unwrap_or_else(|e| {
+                    eprintln!("Could not read file `{}`: {}", filename, e);
+                    std::process::exit(2)
+                })
+            };
+            // like `diff`, the exit code is 1 if there are differences and 2 if there is trouble
+            match rtlola_frontend::diff(&read("OLD"), &read("NEW"), CONFIG) {
+                Ok(changes) if changes.is_empty() => std::process::exit(0),
+                Ok(changes) => {
+                    changes.iter().for_each(|change| println!("{}", change));
+                    std::process::exit(1)
+                }
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(2)
+                }
+            }
+        }
+        if let Some(parse_matches) = parse_matches.subcommand_matches("minimize") {
+            let filename = parse_matches.value_of("SPEC").unwrap();
+            let spec = fs::read_to_string(filename).unwrap_or_else(|e| {
+                eprintln!("Could not read file `{}`: {}", filename, e);
+                std::process::exit(1)
+            });
+            let behavior = match parse_matches.value_of("TRIGGER") {
+                Some(message) => minimize::Behavior::Trigger {
+                    trace: parse_matches.value_of("CSV_INPUT_FILE").unwrap().to_string(),
+                    message: message.to_string(),
+                },
+                None => minimize::Behavior::Crash,
+            };
+            match minimize::minimize(&spec, &behavior) {
+                Ok(minimized) => print!("{}", minimized),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1)
+                }
+            }
+            std::process::exit(0);
+        }
+        if let Some(parse_matches) = parse_matches.subcommand_matches("counterexample") {
+            let filename = parse_matches.value_of("SPEC").unwrap();
+            let spec = fs::read_to_string(filename).unwrap_or_else(|e| {
+                eprintln!("Could not read file `{}`: {}", filename, e);
+                std::process::exit(2)
+            });
+            let max_length = parse_matches.value_of("MAX_LENGTH").unwrap().parse::<usize>().unwrap_or_else(|_| {
+                eprintln!("The maximal length has to be a number");
+                std::process::exit(2)
+            });
+            // the exit code is 0 if a trace was found, 1 if there is none up to the length, and 2 if the search failed
+            match counterexample::search(&spec, parse_matches.value_of("TRIGGER").unwrap(), max_length) {
+                Ok(Some(trace)) => print!("{}", trace),
+                Ok(None) => {
+                    eprintln!("No trace of at most {} events fires the trigger", max_length);
+                    std::process::exit(1)
+                }
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(2)
+                }
+            }
+            std::process::exit(0);
+        }
+        if let Some(parse_matches) = parse_matches.subcommand_matches("slice") {
+            let filename = parse_matches.value_of("SPEC").unwrap();
+            let spec = fs::read_to_string(filename).unwrap_or_else(|e| {
+                eprintln!("Could not read file `{}`: {}", filename, e);
+                std::process::exit(1)
+            });
+            let trace = parse_matches.value_of("CSV_INPUT_FILE").unwrap();
+            let slice = slice::slice(&spec, trace, parse_matches.value_of("TRIGGER").unwrap()).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                std::process::exit(1)
+            });
+            // the origin of the sub-trace is reported on stderr, so the sub-trace can be piped
+            eprintln!(
+                "sliced events {} to {} of the trace, keeping the inputs {}",
+                slice.events.start,
+                slice.events.end - 1,
+                slice.inputs.join(", ")
+            );
+            match parse_matches.value_of("OUTPUT_FILE") {
+                Some(path) => fs::write(path, slice.trace).unwrap_or_else(|e| {
+                    eprintln!("Could not write file `{}`: {}", path, e);
+                    std::process::exit(1)
+                }),
+                None => print!("{}", slice.trace),
+            }
+            std::process::exit(0);
+        }
+        if let Some(parse_matches) = parse_matches.subcommand_matches("optimize") {
+            let filename = parse_matches.value_of("SPEC").unwrap();
+            let spec = fs::read_to_string(filename).unwrap_or_else(|e| {
+                eprintln!("Could not read file `{}`: {}", filename, e);
+                std::process::exit(1)
+            });
+            let (optimized, profile) =
+                profile::optimize(&spec, parse_matches.value_of("CSV_INPUT_FILE").unwrap()).unwrap_or_else(|e| {
+                    eprintln!("{}", e);
+                    std::process::exit(1)
+                });
+            // the profile is reported on stderr, so the optimized specification can be piped
+            for (message, count) in &profile.triggers {
+                eprintln!("trigger \"{}\" fired {} times", message, count);
+            }
+            for (sc, before, after) in &profile.swapped {
+                let operator = if sc.conjunction { "&&" } else { "||" };
+                eprintln!(
+                    "swapped `{}` {} `{}` (line {}), deciding {:.0}% instead of {:.0}% of evaluations",
+                    &spec[sc.lhs.start..sc.lhs.end],
+                    operator,
+                    &spec[sc.rhs.start..sc.rhs.end],
+                    sc.lhs.line,
+                    after * 100.0,
+                    before * 100.0
+                );
+            }
+            match parse_matches.value_of("OUTPUT_FILE") {
+                Some(path) => fs::write(path, optimized).unwrap_or_else(|e| {
+                    eprintln!("Could not write file `{}`: {}", path, e);
+                    std::process::exit(1)
+                }),
+                None => print!("{}", optimized),
+            }
+            std::process::exit(0);
+        }
+        if let Some(parse_matches) = parse_matches.subcommand_matches("new") {
+            let path = std::path::Path::new(parse_matches.value_of("PATH").unwrap());
+            let template = scaffold::Template::from_name(parse_matches.value_of("TEMPLATE").unwrap()).unwrap();
+            let files = scaffold::create(path, template).unwrap_or_else(|e| {
+                eprintln!("Could not create project `{}`: {}", path.display(), e);
+                std::process::exit(1)
+            });
+            println!("Created project `{}`:", path.display());
+            files.iter().for_each(|file| println!("    {}", file.display()));
+            println!("Replay the sample trace in the project directory with\n    {}", template.commands().1);
+            std::process::exit(0);
+        }
         let mut ids_mode = false;
         let parse_matches = if let Some(matches) = parse_matches.subcommand_matches("monitor") {
             matches
@@ -281,17 +764,41 @@ impl Config {
             std::process::exit(1)
         };
 
-        let filename = parse_matches.value_of("SPEC").map(|s| s.to_string()).unwrap();
-        let contents = fs::read_to_string(&filename).unwrap_or_else(|e| {
-            eprintln!("Could not read file `{}`: {}", filename, e);
-            std::process::exit(1)
-        });
-
-        let ir = match rtlola_frontend::parse(&filename, contents.as_str(), CONFIG) {
-            Ok(ir) => ir,
-            Err(err) => {
-                eprintln!("{}", err);
-                std::process::exit(1);
+        let values = |name| parse_matches.values_of(name).map(|v| v.map(String::from).collect()).unwrap_or_default();
+        let warnings = WarningConfig {
+            deny_all: parse_matches.is_present("DENY_WARNINGS"),
+            deny: values("DENY"),
+            allow: values("ALLOW"),
+        };
+        let features = values("FEATURE");
+        let parse = |filename: &String| {
+            let contents = fs::read_to_string(filename).unwrap_or_else(|e| {
+                eprintln!("Could not read file `{}`: {}", filename, e);
+                std::process::exit(1)
+            });
+            match rtlola_frontend::parse_with_features(filename, &contents, CONFIG, &features, &warnings) {
+                Ok(ir) => ir,
+                Err(err) => {
+                    eprintln!("{}", err);
+                    std::process::exit(1);
+                }
+            }
+        };
+        let filenames: Vec<String> = values("SPEC");
+        let ir = match filenames.as_slice() {
+            [filename] => parse(filename),
+            _ => {
+                // the specifications are named after their files, e.g., `cruise` for `specs/cruise.lola`
+                let name = |filename: &String| {
+                    std::path::Path::new(filename)
+                        .file_stem()
+                        .map_or_else(String::new, |s| s.to_string_lossy().into_owned())
+                };
+                let specifications = filenames.iter().map(|filename| (name(filename), parse(filename))).collect();
+                RTLolaIR::merge(specifications).unwrap_or_else(|err| {
+                    eprintln!("Could not monitor the specifications together: {}", err);
+                    std::process::exit(1)
+                })
             }
         };
 
@@ -323,8 +830,7 @@ impl Config {
         };
 
         let src = if ids_mode {
-            let pcap_load = unsafe { pcap_on_demand::load_pcap_library() };
-            if let Err(err) = pcap_load {
+            if let Err(err) = PCAPEventSource::load_library() {
                 eprintln!("Could not load PCAP library: {}", err.to_string());
                 std::process::exit(1);
             }
@@ -338,6 +844,28 @@ impl Config {
             } else {
                 unreachable!(); //Excluded by CLAP
             }
+        } else if let Some(addr) = parse_matches.value_of("MAVLINK_UDP") {
+            EventSourceConfig::MAVLink { src: MAVLinkInputSource::Udp { addr: String::from(addr) } }
+        } else if let Some(path) = parse_matches.value_of("MAVLINK_SERIAL") {
+            EventSourceConfig::MAVLink { src: MAVLinkInputSource::Serial { path: String::from(path) } }
+        } else if let Some(name) = parse_matches.value_of("CAN_INTERFACE") {
+            let dbc = String::from(parse_matches.value_of("DBC").unwrap());
+            EventSourceConfig::CAN { src: CANInputSource::Interface { name: String::from(name), dbc } }
+        } else if let Some(path) = parse_matches.value_of("CANDUMP_FILE") {
+            let dbc = String::from(parse_matches.value_of("DBC").unwrap());
+            EventSourceConfig::CAN { src: CANInputSource::Log { path: String::from(path), dbc } }
+        } else if let Some(rate) = parse_matches.value_of("HOST_METRICS") {
+            let rate = rate.parse::<humantime::Duration>().unwrap_or_else(|e| {
+                eprintln!("Could not parse HOST_METRICS rate `{}`: {}.", rate, e);
+                std::process::exit(1);
+            });
+            let pid = parse_matches.value_of("HOST_METRICS_PID").map(|pid| {
+                pid.parse::<u32>().unwrap_or_else(|_| {
+                    eprintln!("process id needs to be a positive integer");
+                    std::process::exit(1)
+                })
+            });
+            EventSourceConfig::HostMetrics { src: HostMetricsInputSource { rate: rate.into(), pid } }
         } else if let Some(file) = parse_matches.value_of("CSV_INPUT_FILE") {
             EventSourceConfig::CSV { src: CSVInputSource::file(String::from(file), delay, csv_time_column) }
         } else {
@@ -389,7 +917,32 @@ impl Config {
             _ => unreachable!(),
         };
 
-        let cfg = EvalConfig::new(src, Statistics::None, verbosity, out, evaluator, mode, time_representation);
+        let mut cfg = EvalConfig::new(src, Statistics::None, verbosity, out, evaluator, mode, time_representation);
+        let influx_target = match parse_matches.value_of("INFLUX_FILE") {
+            Some(file) => Some(InfluxTarget::File(String::from(file))),
+            None => parse_matches.value_of("INFLUX_URL").map(|url| InfluxTarget::Http(String::from(url))),
+        };
+        cfg.influx = influx_target.map(|target| {
+            let mut influx = InfluxConfig::new(target);
+            if let Some(measurement) = parse_matches.value_of("INFLUX_MEASUREMENT") {
+                influx.measurement = String::from(measurement);
+            }
+            influx
+        });
+        cfg.robustness = parse_matches.is_present("ROBUSTNESS");
+        cfg.explain = parse_matches.is_present("EXPLAIN");
+        cfg.warnings = warnings;
+        cfg.features = features;
+        cfg.constants = values("CONSTANT")
+            .into_iter()
+            .map(|constant: String| match constant.find('=') {
+                Some(ix) => (constant[..ix].to_string(), constant[ix + 1..].to_string()),
+                None => {
+                    eprintln!("Could not parse CONSTANT value `{}`, expected NAME=VALUE.", constant);
+                    std::process::exit(1);
+                }
+            })
+            .collect();
 
         Config { cfg, ir }
     }
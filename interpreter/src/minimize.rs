@@ -0,0 +1,171 @@
+//! This module shrinks a specification to a minimal one that still exhibits a behavior, see `minimize`.
+//!
+//! The specification is split into its top-level declarations, which are removed by delta debugging (ddmin) as long as the behavior persists.
+//! Declarations are split lexically, so specifications that do not parse or crash the frontend can be minimized as well.
+//! The result is 1-minimal: removing any single of its declarations loses the behavior.
+
+use crate::basics::{
+    CSVInputSource, EvaluatorChoice, EventSourceConfig, ExecutionMode, OutputChannel, Statistics, Verbosity,
+};
+use crate::{Config, EvalConfig, TimeRepresentation, CONFIG};
+use std::panic::{self, AssertUnwindSafe};
+
+/// The behavior preserved by the minimization.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Behavior {
+    /// The trigger with the message fires at least once on the CSV trace.
+    Trigger { trace: String, message: String },
+    /// The frontend panics on the specification.
+    Crash,
+}
+
+impl Behavior {
+    fn exhibited_by(&self, spec: &str) -> bool {
+        match self {
+            Behavior::Trigger { trace, message } => {
+                let ir = match rtlola_frontend::parse_with_diagnostics("spec.lola", spec, CONFIG).0 {
+                    Ok(ir) => ir,
+                    Err(_) => return false,
+                };
+                let trigger = match ir.triggers.iter().position(|t| &t.message == message) {
+                    Some(trigger) => trigger,
+                    None => return false,
+                };
+                let cfg = EvalConfig::new(
+                    EventSourceConfig::CSV { src: CSVInputSource::file(trace.clone(), None, None) },
+                    Statistics::Debug,
+                    Verbosity::Silent,
+                    OutputChannel::None,
+                    EvaluatorChoice::ClosureBased,
+                    ExecutionMode::Offline,
+                    TimeRepresentation::Hide,
+                );
+                // a panic of the evaluator does not count as firing
+                let run = panic::catch_unwind(AssertUnwindSafe(|| Config { cfg, ir }.run()));
+                match run {
+                    Ok(Ok(handler)) => handler.statistics.as_ref().map_or(false, |s| s.get_num_trigger(trigger) > 0),
+                    _ => false,
+                }
+            }
+            Behavior::Crash => {
+                panic::catch_unwind(|| rtlola_frontend::parse_with_diagnostics("spec.lola", spec, CONFIG)).is_err()
+            }
+        }
+    }
+}
+
+/// Returns the smallest specification found that still exhibits the behavior, or an error if the specification does not exhibit it.
+pub(crate) fn minimize(spec: &str, behavior: &Behavior) -> Result<String, String> {
+    let (prefix, declarations) = split(spec);
+    let join = |selected: &[usize]| {
+        let mut spec = prefix.to_string();
+        selected.iter().for_each(|&ix| spec += declarations[ix]);
+        spec
+    };
+    // the panics of the frontend and evaluator are expected, so their messages are not printed
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let all: Vec<usize> = (0..declarations.len()).collect();
+    let result = if behavior.exhibited_by(spec) {
+        Ok(join(&ddmin(all, |selected| behavior.exhibited_by(&join(selected)))))
+    } else {
+        Err(String::from("the specification does not exhibit the behavior"))
+    };
+    panic::set_hook(hook);
+    result
+}
+
+/// Splits the specification into the text before the first declaration and the top-level declarations.
+/// A declaration starts with a line beginning with a keyword or an attribute and extends to the next declaration, including trailing comments.
+fn split(spec: &str) -> (&str, Vec<&str>) {
+    const KEYWORDS: [&str; 8] =
+        ["import ", "type ", "configurable ", "constant ", "input ", "output ", "trigger ", "assume "];
+    let mut starts = Vec::new();
+    let mut offset = 0;
+    let mut after_attribute = false;
+    for line in spec.split('\n') {
+        let trimmed = line.trim_start();
+        let attribute = trimmed.starts_with("#[");
+        if (attribute || KEYWORDS.iter().any(|k| trimmed.starts_with(k))) && !after_attribute {
+            starts.push(offset);
+        }
+        // attributes belong to the declaration they precede
+        if !trimmed.is_empty() {
+            after_attribute = attribute;
+        }
+        offset += line.len() + 1;
+    }
+    let prefix = &spec[..starts.first().copied().unwrap_or_else(|| spec.len())];
+    let mut declarations: Vec<&str> = starts.windows(2).map(|w| &spec[w[0]..w[1]]).collect();
+    if let Some(&last) = starts.last() {
+        declarations.push(&spec[last..]);
+    }
+    (prefix, declarations)
+}
+
+/// Returns a 1-minimal subset of the elements that passes the test, assuming that all elements pass it.
+fn ddmin(mut current: Vec<usize>, test: impl Fn(&[usize]) -> bool) -> Vec<usize> {
+    let mut granularity = 2;
+    while current.len() >= 2 {
+        let size = (current.len() + granularity - 1) / granularity;
+        let subsets: Vec<Vec<usize>> = current.chunks(size).map(<[usize]>::to_vec).collect();
+        let complement =
+            |subset: &Vec<usize>| -> Vec<usize> { current.iter().filter(|e| !subset.contains(e)).copied().collect() };
+        if let Some(subset) = subsets.iter().find(|subset| test(subset)) {
+            current = subset.clone();
+            granularity = 2;
+        } else if let Some(complement) = subsets.iter().map(complement).find(|complement| test(complement)) {
+            current = complement;
+            granularity = std::cmp::max(granularity - 1, 2);
+        } else if granularity < current.len() {
+            granularity = std::cmp::min(granularity * 2, current.len());
+        } else {
+            break;
+        }
+    }
+    // a single element may be removable as well
+    if current.len() == 1 && test(&[]) {
+        current.clear();
+    }
+    current
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn declarations_are_split() {
+        let spec = "// monitors a\ninput a: Int64\n#[allow(W0201)]\noutput b := a\n  + 1\n// trailing\ntrigger b > 1";
+        let (prefix, declarations) = split(spec);
+        assert_eq!(prefix, "// monitors a\n");
+        assert_eq!(
+            declarations,
+            vec!["input a: Int64\n", "#[allow(W0201)]\noutput b := a\n  + 1\n// trailing\n", "trigger b > 1"]
+        );
+    }
+
+    #[test]
+    fn ddmin_finds_minimal_subsets() {
+        let minimal = ddmin((0..10).collect(), |selected| selected.contains(&3) && selected.contains(&7));
+        assert_eq!(minimal, vec![3, 7]);
+    }
+
+    #[test]
+    fn irrelevant_declarations_are_removed() {
+        let spec = "input a: Int64\ninput b: Int64\noutput c := b + 1\noutput d := a * 2\ntrigger d > 5 \"too high\"\ntrigger c < 0 \"too low\"\n";
+        let mut trace = NamedTempFile::new().expect("failed to create temporary file");
+        write!(trace, "a,b,time\n1,1,0.0\n3,1,1.0\n").expect("writing tempfile failed");
+        let behavior =
+            Behavior::Trigger { trace: trace.path().to_str().unwrap().to_string(), message: String::from("too high") };
+        assert_eq!(
+            minimize(spec, &behavior),
+            Ok(String::from("input a: Int64\noutput d := a * 2\ntrigger d > 5 \"too high\"\n"))
+        );
+        let behavior =
+            Behavior::Trigger { trace: trace.path().to_str().unwrap().to_string(), message: String::from("too low") };
+        assert!(minimize(spec, &behavior).is_err());
+    }
+}
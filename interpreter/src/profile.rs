@@ -0,0 +1,113 @@
+//! This module optimizes a specification with a profile of a representative trace, see `optimize`.
+//!
+//! The profile counts how often each trigger fires and how often each operand of a conjunction or disjunction is true.
+//! Operands are profiled by probe triggers appended to the specification, one firing whenever the operand is true and one whenever it is evaluated.
+//! The optimization swaps the operands of a conjunction if the right operand is false more often, and those of a disjunction if it is true more often, so the evaluation short-circuits more often.
+
+use crate::basics::{
+    CSVInputSource, EvaluatorChoice, EventSourceConfig, ExecutionMode, OutputChannel, Statistics, Verbosity,
+};
+use crate::{Config, EvalConfig, TimeRepresentation, CONFIG};
+use rtlola_frontend::reorder::ShortCircuit;
+
+/// The message prefix of the probe triggers.
+const PROBE: &str = "__profile";
+
+/// The profile of a specification on a trace.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Profile {
+    /// The message of each trigger of the specification and how often it fired.
+    pub(crate) triggers: Vec<(String, u64)>,
+    /// The short circuits with swapped operands, with the rate of the operands deciding the result before and after.
+    pub(crate) swapped: Vec<(ShortCircuit, f64, f64)>,
+}
+
+/// Returns the specification with reordered operands and the profile it is based on.
+pub(crate) fn optimize(spec: &str, trace: &str) -> Result<(String, Profile), String> {
+    let short_circuits = rtlola_frontend::short_circuits(spec, CONFIG);
+    let operand = |l: &rtlola_frontend::navigation::Location| &spec[l.start..l.end];
+    let mut probed = format!("{}\n", spec);
+    for (ix, sc) in short_circuits.iter().enumerate() {
+        for (side, text) in &[("lhs", operand(&sc.lhs)), ("rhs", operand(&sc.rhs))] {
+            probed += &format!("trigger ({}) \"{}_{}_{}_true\"\n", text, PROBE, ix, side);
+            probed += &format!("trigger ({}) || !({}) \"{}_{}_{}_evaluated\"\n", text, text, PROBE, ix, side);
+        }
+    }
+    let counts = run(&probed, trace)?;
+    let count = |message: String| counts.iter().find(|(m, _)| *m == message).map_or(0, |(_, count)| *count);
+    // the rate of the operand deciding the result, i.e., being false in a conjunction or true in a disjunction
+    let deciding = |ix: usize, side: &str, conjunction: bool| {
+        let evaluated = count(format!("{}_{}_{}_evaluated", PROBE, ix, side));
+        if evaluated == 0 {
+            return None;
+        }
+        let rate = count(format!("{}_{}_{}_true", PROBE, ix, side)) as f64 / evaluated as f64;
+        Some(if conjunction { 1.0 - rate } else { rate })
+    };
+    let mut swapped: Vec<(ShortCircuit, f64, f64)> = short_circuits
+        .iter()
+        .enumerate()
+        .filter_map(|(ix, sc)| {
+            let lhs = deciding(ix, "lhs", sc.conjunction)?;
+            let rhs = deciding(ix, "rhs", sc.conjunction)?;
+            if rhs > lhs {
+                Some((sc.clone(), lhs, rhs))
+            } else {
+                None
+            }
+        })
+        .collect();
+    // nested short circuits overlap, the one with the largest gain is swapped
+    swapped.sort_by(|(_, l1, r1), (_, l2, r2)| (r2 - l2).partial_cmp(&(r1 - l1)).unwrap());
+    let order: Vec<ShortCircuit> = swapped.iter().map(|(sc, _, _)| sc.clone()).collect();
+    let optimized = rtlola_frontend::swap_operands(spec, &order);
+    swapped.retain(|(sc, _, _)| {
+        let overlaps = |other: &ShortCircuit| other.lhs.start < sc.rhs.end && sc.lhs.start < other.rhs.end;
+        // a short circuit is swapped unless an earlier one with larger gain overlaps it
+        !order.iter().take_while(|other| *other != sc).any(|other| overlaps(other))
+    });
+    let triggers = counts.into_iter().filter(|(message, _)| !message.starts_with(PROBE)).collect();
+    Ok((optimized, Profile { triggers, swapped }))
+}
+
+/// Runs the specification on the CSV trace and returns the message of each trigger and how often it fired.
+fn run(spec: &str, trace: &str) -> Result<Vec<(String, u64)>, String> {
+    let ir = rtlola_frontend::parse("spec.lola", spec, CONFIG)?;
+    let cfg = EvalConfig::new(
+        EventSourceConfig::CSV { src: CSVInputSource::file(trace.to_string(), None, None) },
+        Statistics::Debug,
+        Verbosity::Silent,
+        OutputChannel::None,
+        EvaluatorChoice::ClosureBased,
+        ExecutionMode::Offline,
+        TimeRepresentation::Hide,
+    );
+    let triggers: Vec<(String, usize)> = ir.triggers.iter().map(|t| (t.message.clone(), t.trigger_idx)).collect();
+    let handler = Config { cfg, ir }.run().map_err(|e| e.to_string())?;
+    let statistics = handler.statistics.as_ref().expect("statistics are enabled");
+    Ok(triggers.into_iter().map(|(message, ix)| (message, statistics.get_num_trigger(ix))).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn operands_deciding_more_often_come_first() {
+        let spec = "input a: Int64\ninput b: Bool\ntrigger a > 5 && b \"alarm\"\ntrigger b || a > 5 \"warning\"";
+        let mut trace = NamedTempFile::new().expect("failed to create temporary file");
+        // `a > 5` is mostly true, `b` is mostly false
+        write!(trace, "a,b,time\n6,false,0.0\n7,false,1.0\n8,true,2.0\n1,false,3.0\n")
+            .expect("writing tempfile failed");
+        let (optimized, profile) = optimize(spec, trace.path().to_str().unwrap()).unwrap();
+        assert_eq!(
+            optimized,
+            "input a: Int64\ninput b: Bool\ntrigger b && a > 5 \"alarm\"\ntrigger a > 5 || b \"warning\""
+        );
+        assert_eq!(profile.triggers, vec![(String::from("alarm"), 1), (String::from("warning"), 3)]);
+        assert_eq!(profile.swapped.len(), 2);
+        assert_eq!((profile.swapped[0].1, profile.swapped[0].2), (0.25, 0.75));
+    }
+}
@@ -0,0 +1,249 @@
+//! This module generates the starter projects of the `new` subcommand, see `create`.
+//!
+//! A project consists of a specification, a `monitor.toml` recording the input source and the commands running the monitor, and a sample trace that can be replayed with the input source of the template.
+
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The kind of system monitored by a starter project.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Template {
+    /// Telemetry of a UAV received via MAVLink, with a CSV trace.
+    Uav,
+    /// Network traffic captured via PCAP, with a PCAP trace.
+    Network,
+    /// Process data of a PLC received via CAN, with a DBC file and a `candump` log.
+    Plc,
+}
+
+impl Template {
+    pub(crate) const NAMES: [&'static str; 3] = ["uav", "network", "plc"];
+
+    pub(crate) fn from_name(name: &str) -> Option<Template> {
+        match name {
+            "uav" => Some(Template::Uav),
+            "network" => Some(Template::Network),
+            "plc" => Some(Template::Plc),
+            _ => None,
+        }
+    }
+
+    /// Returns the command monitoring the live input source and the command replaying the sample trace.
+    pub(crate) fn commands(self) -> (&'static str, &'static str) {
+        match self {
+            Template::Uav => (
+                "rtlola-interpreter monitor spec.lola --online --mavlink-udp 0.0.0.0:14550",
+                "rtlola-interpreter monitor spec.lola --offline --csv-in trace.csv",
+            ),
+            Template::Network => (
+                "rtlola-interpreter ids spec.lola 192.168.0.0/24 --net-iface eth0",
+                "rtlola-interpreter ids spec.lola 192.168.0.0/24 --pcap-in trace.pcap",
+            ),
+            Template::Plc => (
+                "rtlola-interpreter monitor spec.lola --online --can-iface can0 --dbc plc.dbc",
+                "rtlola-interpreter monitor spec.lola --offline --candump-in trace.log --dbc plc.dbc",
+            ),
+        }
+    }
+
+    /// Returns the names and contents of the files of a project.
+    fn files(self, name: &str) -> Vec<(&'static str, Vec<u8>)> {
+        let (spec, source, trace) = match self {
+            Template::Uav => {
+                (UAV_SPEC, "kind = \"mavlink-udp\"\naddress = \"0.0.0.0:14550\"", ("trace.csv", uav_trace()))
+            }
+            Template::Network => (
+                NETWORK_SPEC,
+                "kind = \"pcap\"\ninterface = \"eth0\"\nlocal_network = \"192.168.0.0/24\"",
+                ("trace.pcap", network_trace()),
+            ),
+            Template::Plc => {
+                (PLC_SPEC, "kind = \"can\"\ninterface = \"can0\"\ndbc = \"plc.dbc\"", ("trace.log", plc_trace()))
+            }
+        };
+        let (live, replay) = self.commands();
+        let config = format!(
+            "# The configuration of the monitor `{name}`, generated by `rtlola-interpreter new --template {template}`.\n\
+             # The interpreter takes these settings as command line options, see `command`.\n\n\
+             [monitor]\nname = \"{name}\"\nspec = \"spec.lola\"\n\n\
+             [source]\n{source}\ncommand = \"{live}\"\n\n\
+             [replay]\ntrace = \"{trace}\"\ncommand = \"{replay}\"\n",
+            name = name,
+            template = Template::NAMES[self as usize],
+            source = source,
+            live = live,
+            trace = trace.0,
+            replay = replay,
+        );
+        let mut files = vec![("spec.lola", spec.as_bytes().to_vec()), ("monitor.toml", config.into_bytes()), trace];
+        if self == Template::Plc {
+            files.push(("plc.dbc", PLC_DBC.as_bytes().to_vec()));
+        }
+        files
+    }
+}
+
+/// Creates a project from the template in the directory, which must not exist or be empty, and returns the created files.
+pub(crate) fn create(dir: &Path, template: Template) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    if dir.exists() && dir.read_dir()?.next().is_some() {
+        return Err(format!("destination `{}` already exists and is not empty", dir.display()).into());
+    }
+    let name = dir.file_name().and_then(|n| n.to_str()).unwrap_or("monitor");
+    fs::create_dir_all(dir)?;
+    let mut created = Vec::new();
+    for (file, content) in template.files(name) {
+        let path = dir.join(file);
+        fs::write(&path, content)?;
+        created.push(path);
+    }
+    Ok(created)
+}
+
+const UAV_SPEC: &str = "// Monitors the telemetry of a UAV, e.g., sent by PX4 or ArduPilot via MAVLink.
+// Inputs are named after the MAVLink message and its field.
+input ATTITUDE::roll: Float64
+input ATTITUDE::pitch: Float64
+input SYS_STATUS::voltage_battery: UInt64
+input GPS_RAW_INT::satellites_visible: UInt64
+
+// roll and pitch are given in radians, 0.52 is about 30 degrees
+output tilted := abs(ATTITUDE::roll) > 0.52 || abs(ATTITUDE::pitch) > 0.52
+
+trigger tilted \"attitude exceeds 30 degrees\"
+trigger SYS_STATUS::voltage_battery < 10500 \"battery low\"
+trigger GPS_RAW_INT::satellites_visible < 6 \"weak GPS reception\"
+";
+
+const NETWORK_SPEC: &str = "// Monitors network traffic, e.g., of a network interface or a PCAP file.
+// Inputs are named after the protocol and its header field.
+input TCP::destination: UInt64
+input TCP::flags::syn: Bool
+input TCP::flags::ack: Bool
+
+// connection attempts, i.e., packets with SYN but without ACK flag
+output syn: UInt64 := if TCP::flags::syn && !TCP::flags::ack then 1 else 0
+output syn_rate @ 1Hz := syn.aggregate(over: 1s, using: sum)
+
+trigger syn_rate > 100 \"possible SYN flood\"
+trigger TCP::destination == 23 \"telnet connection\"
+";
+
+const PLC_SPEC: &str = "// Monitors the process data of a PLC sent via CAN.
+// Inputs are named after the message and signal in plc.dbc.
+input Tank::Level: Float64
+input Tank::Temperature: Float64
+input Pump::Running: Bool
+
+trigger Tank::Level > 90.0 \"tank almost full\"
+trigger Tank::Level > 80.0 && Pump::Running.hold().defaults(to: false) \"pump running while tank is filled\"
+trigger Tank::Temperature > 70.0 \"tank overheated\"
+";
+
+const PLC_DBC: &str = "VERSION \"\"
+
+BO_ 256 Tank: 4 PLC
+ SG_ Level : 0|16@1+ (0.1,0) [0|100] \"%\" Vector__XXX
+ SG_ Temperature : 16|16@1- (0.1,0) [-50|150] \"degC\" Vector__XXX
+
+BO_ 257 Pump: 1 PLC
+ SG_ Running : 0|1@1+ (1,0) [0|1] \"\" Vector__XXX
+";
+
+/// A flight of 10 seconds with a strong roll, a weak GPS reception, and a draining battery reported once a second.
+fn uav_trace() -> Vec<u8> {
+    let mut trace = String::from(
+        "time,ATTITUDE::roll,ATTITUDE::pitch,SYS_STATUS::voltage_battery,GPS_RAW_INT::satellites_visible\n",
+    );
+    for step in 0..100 {
+        let roll = if (40..45).contains(&step) { 0.61 } else { 0.05 };
+        let (battery, satellites) = if step % 10 == 0 {
+            let satellites = if (60..80).contains(&step) { 4 } else { 11 };
+            ((12600 - step * 25).to_string(), satellites.to_string())
+        } else {
+            (String::from("#"), String::from("#"))
+        };
+        trace += &format!("{}.{},{:.2},{:.2},{},{}\n", step / 10, step % 10, roll, 0.02, battery, satellites);
+    }
+    trace.into_bytes()
+}
+
+/// A capture of 3 seconds of regular web traffic, a burst of 200 connection attempts in the second second, and a telnet connection.
+fn network_trace() -> Vec<u8> {
+    const SYN: u8 = 0x02;
+    const ACK: u8 = 0x10;
+    // the global header of a PCAP file with Ethernet frames
+    let mut trace = vec![0xd4, 0xc3, 0xb2, 0xa1, 2, 0, 4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xff, 0xff, 0, 0, 1, 0, 0, 0];
+    let mut packet = |micros: u32, source: [u8; 4], port: u16, flags: u8| {
+        let mut frame = vec![0x02, 0, 0, 0, 0, 0x01, 0x02, 0, 0, 0, 0, 0x02, 0x08, 0x00];
+        let mut ip = vec![0x45, 0, 0, 40, 0, 0, 0x40, 0, 64, 6, 0, 0];
+        ip.extend_from_slice(&source);
+        ip.extend_from_slice(&[192, 168, 0, 10]);
+        let checksum = !ip.chunks(2).fold(0u32, |sum, word| {
+            let sum = sum + u32::from(u16::from_be_bytes([word[0], word[1]]));
+            (sum & 0xffff) + (sum >> 16)
+        }) as u16;
+        ip[10..12].copy_from_slice(&checksum.to_be_bytes());
+        frame.extend(ip);
+        frame.extend_from_slice(&49152u16.to_be_bytes());
+        frame.extend_from_slice(&port.to_be_bytes());
+        frame.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0, 0x50, flags, 0xff, 0xff, 0, 0, 0, 0]);
+        let header = [1_600_000_000 + micros / 1_000_000, micros % 1_000_000, frame.len() as u32, frame.len() as u32];
+        header.iter().for_each(|field| trace.extend_from_slice(&field.to_le_bytes()));
+        trace.extend(frame);
+    };
+    for step in 0..30 {
+        packet(step * 100_000, [203, 0, 113, 5], 443, if step % 10 == 0 { SYN } else { ACK });
+    }
+    for step in 0..200 {
+        packet(1_000_000 + step * 4_000, [198, 51, 100, step as u8], 80, SYN);
+    }
+    packet(2_500_000, [203, 0, 113, 7], 23, SYN);
+    trace
+}
+
+/// A tank filled for 20 seconds while the pump is running and heated up in the end, reported every 500ms.
+fn plc_trace() -> Vec<u8> {
+    let mut trace = String::new();
+    for step in 0..40u32 {
+        let time = format!("({}.{:06})", 1_600_000_000 + step / 2, step % 2 * 500_000);
+        let level = 400 + step * 13;
+        let temperature = if step < 35 { 250 } else { 250 + (step - 34) * 110 };
+        let [l0, l1, ..] = level.to_le_bytes();
+        let [t0, t1, ..] = temperature.to_le_bytes();
+        trace += &format!("{} can0 100#{:02X}{:02X}{:02X}{:02X}\n", time, l0, l1, t0, t1);
+        if step % 4 == 0 {
+            trace += &format!("{} can0 101#{:02X}\n", time, u8::from(step < 36));
+        }
+    }
+    trace.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn specifications_are_valid() {
+        for name in &Template::NAMES {
+            let template = Template::from_name(name).unwrap();
+            let files = template.files("monitor");
+            let spec = std::str::from_utf8(&files[0].1).unwrap();
+            assert!(rtlola_frontend::parse(name, spec, crate::CONFIG).is_ok(), "template `{}` is invalid", name);
+        }
+    }
+
+    #[test]
+    fn projects_are_created() {
+        let dir = tempdir().unwrap();
+        let project = dir.path().join("my-monitor");
+        let files = create(&project, Template::Plc).unwrap();
+        assert_eq!(files.len(), 4);
+        let config = fs::read_to_string(project.join("monitor.toml")).unwrap();
+        assert!(config.contains("name = \"my-monitor\""));
+        assert!(config.contains("--candump-in trace.log --dbc plc.dbc"));
+        // existing projects are not overwritten
+        assert!(create(&project, Template::Uav).is_err());
+    }
+}
@@ -0,0 +1,153 @@
+//! This module slices a recorded trace to a minimal sub-trace on which a trigger still fires, see `slice`.
+//!
+//! The trace is cut after the event at which the trigger fires first, and the values of inputs on which the trigger does not depend are removed.
+//! Then, the shortest suffix on which the trigger still fires is kept, where times are relative to the first event of the suffix as when replaying it.
+//! Finally, events without any remaining value are removed if the trigger still fires without them.
+
+use crate::basics::{
+    parse_time, EvaluatorChoice, EventSourceConfig, ExecutionMode, OutputChannel, Statistics, Verbosity,
+};
+use crate::{Config, EvalConfig, TimeRepresentation, Value, CONFIG};
+use csv::ReaderBuilder;
+use rtlola_frontend::ir::RTLolaIR;
+use std::ops::Range;
+use std::time::Duration;
+
+/// A sub-trace on which a trigger fires.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Slice {
+    /// The sub-trace in CSV format with a `time` column, where `#` marks the absence of a value.
+    pub(crate) trace: String,
+    /// The events of the original trace the sub-trace is taken from, counted from 0.
+    pub(crate) events: Range<usize>,
+    /// The names of the inputs whose values are kept.
+    pub(crate) inputs: Vec<String>,
+}
+
+/// An event of the trace, the values of the inputs as given in the CSV file and its time.
+type Event = (Vec<String>, Duration);
+
+/// Returns the minimal sub-trace of the CSV trace on which the trigger with the message fires.
+/// Returns an error if the specification or trace is invalid, it has no such trigger, or the trigger does not fire on the trace.
+pub(crate) fn slice(spec: &str, trace: &str, message: &str) -> Result<Slice, String> {
+    let ir = rtlola_frontend::parse("spec.lola", spec, CONFIG)?;
+    let trigger = ir.triggers.iter().find(|t| t.message == message).ok_or("the specification has no such trigger")?;
+    let (mut events, time) = read(&ir, trace)?;
+    let fires_on = |events: &[Event]| fires(&ir, trigger.trigger_idx, events);
+    let end =
+        (1..=events.len()).find(|&end| fires_on(&events[..end])).ok_or("the trigger does not fire on the trace")?;
+    events.truncate(end);
+    // the values of irrelevant inputs are removed, unless the trigger depends on them after all
+    let relevant = &ir.outputs[trigger.reference.out_ix()].input_dependencies;
+    let mut restricted = events.clone();
+    for (values, _) in &mut restricted {
+        for (ix, value) in values.iter_mut().enumerate() {
+            if !relevant.iter().any(|r| r.in_ix() == ix) {
+                *value = String::from("#");
+            }
+        }
+    }
+    let inputs: Vec<String> = if fires_on(&restricted) {
+        events = restricted;
+        ir.inputs.iter().filter(|i| relevant.contains(&i.reference)).map(|i| i.name.clone()).collect()
+    } else {
+        ir.inputs.iter().map(|i| i.name.clone()).collect()
+    };
+    let start = (0..end).rev().find(|&start| fires_on(&events[start..])).expect("the trigger fires on the whole trace");
+    let mut suffix = events.split_off(start);
+    let empty = |(values, _): &Event| values.iter().all(|value| value == "#");
+    let nonempty: Vec<Event> = suffix.iter().filter(|event| !empty(event)).cloned().collect();
+    if nonempty.len() < suffix.len() && fires_on(&nonempty) {
+        suffix = nonempty;
+    }
+    let mut csv: String = ir.inputs.iter().map(|i| format!("{},", i.name)).collect();
+    csv += &time;
+    csv += "\n";
+    for (values, time) in &suffix {
+        values.iter().for_each(|value| csv += &format!("{},", value));
+        csv += &format!("{}.{:09}\n", time.as_secs(), time.subsec_nanos());
+    }
+    Ok(Slice { trace: csv, events: start..end, inputs })
+}
+
+/// Reads the events of the CSV trace and the name of its time column.
+fn read(ir: &RTLolaIR, trace: &str) -> Result<(Vec<Event>, String), String> {
+    let mut reader = ReaderBuilder::new().from_path(trace).map_err(|e| format!("could not read the trace: {}", e))?;
+    let header = reader.headers().map_err(|e| format!("could not read the trace: {}", e))?.clone();
+    let column = |name: &str| {
+        header.iter().position(|entry| entry == name).ok_or_else(|| format!("the trace has no column for `{}`", name))
+    };
+    let columns = ir.inputs.iter().map(|i| column(&i.name)).collect::<Result<Vec<usize>, String>>()?;
+    let time = ["time", "ts", "timestamp"]
+        .iter()
+        .find_map(|name| header.iter().position(|entry| entry.to_lowercase() == *name))
+        .ok_or("the trace has no time column")?;
+    let mut events = Vec::new();
+    for record in reader.records() {
+        let record = record.map_err(|e| format!("could not read the trace: {}", e))?;
+        let values = columns.iter().map(|&col| record[col].to_string()).collect();
+        events.push((values, parse_time(&record[time])?));
+    }
+    Ok((events, header[time].to_string()))
+}
+
+/// Returns whether the trigger fires on the events, where times are relative to the first event.
+fn fires(ir: &RTLolaIR, trigger: usize, events: &[Event]) -> bool {
+    let cfg = EvalConfig::new(
+        EventSourceConfig::API,
+        Statistics::Debug,
+        Verbosity::Silent,
+        OutputChannel::None,
+        EvaluatorChoice::ClosureBased,
+        ExecutionMode::API,
+        TimeRepresentation::Hide,
+    );
+    let mut monitor = Config::new_api(cfg, ir.clone()).into_monitor().expect("the specification is valid");
+    let start = events.first().map_or_else(Duration::default, |(_, time)| *time);
+    for (values, time) in events {
+        let event: Vec<Value> = values
+            .iter()
+            .zip(&ir.inputs)
+            .map(|(value, input)| match value.as_str() {
+                "#" => Value::None,
+                value => Value::try_from(value.as_bytes(), &input.ty).unwrap_or(Value::None),
+            })
+            .collect();
+        monitor.accept_event(event, *time - start);
+        if monitor.output_handler.statistics.as_ref().map_or(false, |s| s.get_num_trigger(trigger) > 0) {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn trace_is_sliced() {
+        let spec = "input a: Int64\ninput b: Bool\noutput c := a + a.offset(by: -1).defaults(to: 0)\ntrigger c > 10 \"too high\"";
+        let mut trace = NamedTempFile::new().expect("failed to create temporary file");
+        write!(
+            trace,
+            "a,b,time\n1,true,0.0\n2,false,1.0\n#,true,2.0\n3,false,3.0\n8,true,4.0\n9,false,5.0\n1,true,6.0\n"
+        )
+        .expect("writing tempfile failed");
+        let slice = slice(spec, trace.path().to_str().unwrap(), "too high").unwrap();
+        assert_eq!(slice.trace, "a,b,time\n3,#,3.000000000\n8,#,4.000000000\n");
+        assert_eq!(slice.events, 3..5);
+        assert_eq!(slice.inputs, vec![String::from("a")]);
+    }
+
+    #[test]
+    fn trigger_has_to_fire() {
+        let spec = "input a: Int64\ntrigger a > 10 \"too high\"";
+        let mut trace = NamedTempFile::new().expect("failed to create temporary file");
+        write!(trace, "a,time\n1,0.0\n2,1.0\n").expect("writing tempfile failed");
+        assert!(slice(spec, trace.path().to_str().unwrap(), "too high").is_err());
+        assert!(slice(spec, trace.path().to_str().unwrap(), "unknown").is_err());
+    }
+}
@@ -3,6 +3,7 @@ mod value;
 mod window;
 mod window_aggregations;
 
-pub(crate) use self::stores::GlobalStore;
+pub(crate) use self::stores::{GlobalStore, InstanceStore};
 pub use self::value::Value;
 pub(crate) use self::window::SlidingWindow;
+pub use self::window::WindowAggregation;
@@ -1,6 +1,6 @@
 use super::Value;
 
-use crate::basics::Time;
+use crate::basics::{Aggregations, Time};
 use crate::storage::SlidingWindow;
 use rtlola_frontend::ir::{
     InputReference, MemorizationBound, OutputReference, OutputStream, RTLolaIR, Type, WindowReference,
@@ -15,9 +15,15 @@ pub(crate) struct GlobalStore {
     /// Transforms a output stream reference into the respective index of the stream vectors ((non-)parametrized).
     index_map: Vec<usize>,
 
+    /// Whether an output stream is parametrized. Access by output reference.
+    parametrized: Vec<bool>,
+
     /// Non-parametrized outputs. Access by index.
     np_outputs: Vec<InstanceStore>,
 
+    /// Parametrized outputs. Access by index, the instances are kept in the order they were spawned.
+    p_outputs: Vec<ParamOutput>,
+
     /// Non-parametrized windows, access by WindowReference.
     np_windows: Vec<SlidingWindow>,
 }
@@ -26,35 +32,49 @@ pub(crate) struct GlobalStore {
 pub(crate) type InInstance = InputReference;
 pub(crate) type OutInstance = OutputReference;
 
+/// The instances of a parametrized output stream, identified by the values of their parameters
+struct ParamOutput {
+    ty: Type,
+    memory_bound: MemorizationBound,
+    instances: Vec<(Vec<Value>, InstanceStore)>,
+}
+
 impl GlobalStore {
     /// Returns a Global Storage for a given specification and starting point in time, given as:
     ///
     ///  # Arguments
     /// * `ir` - An intermediate representation of the specification
+    /// * `aggregations` - The implementations of the aggregation functions registered by the host application
     /// * `time` - The starting time of the monitor
-    pub(crate) fn new(ir: &RTLolaIR, ts: Time) -> GlobalStore {
+    pub(crate) fn new(ir: &RTLolaIR, aggregations: &Aggregations, ts: Time) -> GlobalStore {
         let mut index_map: Vec<Option<usize>> = vec![None; ir.outputs.len()];
 
-        let nps: Vec<&OutputStream> = index_map
-            .iter()
-            .enumerate()
-            .flat_map(|(ix, v)| if v.is_none() { Some(ix) } else { None })
-            .map(|ix| &ir.outputs[ix])
-            .collect(); // Give it a type.
+        let (ps, nps): (Vec<&OutputStream>, Vec<&OutputStream>) = ir.outputs.iter().partition(|o| !o.params.is_empty());
 
         for (np_ix, o) in nps.iter().enumerate() {
             index_map[o.reference.out_ix()] = Some(np_ix);
         }
+        for (p_ix, o) in ps.iter().enumerate() {
+            index_map[o.reference.out_ix()] = Some(p_ix);
+        }
 
         assert!(index_map.iter().all(Option::is_some));
 
         let index_map = index_map.into_iter().flatten().collect();
         let np_outputs = nps.iter().map(|o| InstanceStore::new(&o.ty, o.memory_bound)).collect();
+        let p_outputs = ps
+            .iter()
+            .map(|o| ParamOutput { ty: o.ty.clone(), memory_bound: o.memory_bound, instances: Vec::new() })
+            .collect();
+        let parametrized = ir.outputs.iter().map(|o| !o.params.is_empty()).collect();
         let inputs = ir.inputs.iter().map(|i| InstanceStore::new(&i.ty, i.memory_bound)).collect();
-        let np_windows =
-            ir.sliding_windows.iter().map(|w| SlidingWindow::new(w.duration, w.wait, w.op, ts, &w.ty)).collect();
+        let np_windows = ir
+            .sliding_windows
+            .iter()
+            .map(|w| SlidingWindow::new(w.duration, w.wait, &w.op, ts, &w.ty, aggregations))
+            .collect();
 
-        GlobalStore { inputs, index_map, np_outputs, np_windows }
+        GlobalStore { inputs, index_map, parametrized, np_outputs, p_outputs, np_windows }
     }
 
     /// Returns the storage of an input stream instance
@@ -69,18 +89,68 @@ impl GlobalStore {
         &mut self.inputs[ix]
     }
 
-    /// Returns the storage of an input stream instance
+    /// Returns the storage of an output stream instance, `None` for parametrized streams
     pub(crate) fn get_out_instance(&self, inst: OutInstance) -> Option<&InstanceStore> {
         let ix = inst;
+        if self.parametrized[ix] {
+            return None;
+        }
         Some(&self.np_outputs[self.index_map[ix]])
     }
 
-    /// Returns the storage of an output stream instance (mutable)
+    /// Returns the storage of an output stream instance (mutable), `None` for parametrized streams
     pub(crate) fn get_out_instance_mut(&mut self, inst: OutInstance) -> Option<&mut InstanceStore> {
         let ix = inst;
+        if self.parametrized[ix] {
+            return None;
+        }
         Some(&mut self.np_outputs[self.index_map[ix]])
     }
 
+    /// Returns the storage of the instance of a parametrized output stream with the given parameters, if it was spawned
+    pub(crate) fn get_param_instance(&self, inst: OutInstance, parameters: &[Value]) -> Option<&InstanceStore> {
+        if !self.parametrized[inst] {
+            return None;
+        }
+        let instances = &self.p_outputs[self.index_map[inst]].instances;
+        instances.iter().find(|(p, _)| p.as_slice() == parameters).map(|(_, store)| store)
+    }
+
+    /// Returns the storage of the instance of a parametrized output stream with the given parameters (mutable), if it was spawned
+    pub(crate) fn get_param_instance_mut(
+        &mut self,
+        inst: OutInstance,
+        parameters: &[Value],
+    ) -> Option<&mut InstanceStore> {
+        assert!(self.parametrized[inst]);
+        let instances = &mut self.p_outputs[self.index_map[inst]].instances;
+        instances.iter_mut().find(|(p, _)| p.as_slice() == parameters).map(|(_, store)| store)
+    }
+
+    /// Returns the parameters of the instances of a parametrized output stream in the order they were spawned
+    pub(crate) fn instances(&self, inst: OutInstance) -> Vec<Vec<Value>> {
+        assert!(self.parametrized[inst]);
+        self.p_outputs[self.index_map[inst]].instances.iter().map(|(p, _)| p.clone()).collect()
+    }
+
+    /// Spawns the instance of a parametrized output stream with the given parameters, returns false if it exists already
+    pub(crate) fn spawn_instance(&mut self, inst: OutInstance, parameters: Vec<Value>) -> bool {
+        assert!(self.parametrized[inst]);
+        let output = &mut self.p_outputs[self.index_map[inst]];
+        if output.instances.iter().any(|(p, _)| *p == parameters) {
+            return false;
+        }
+        let store = InstanceStore::new(&output.ty, output.memory_bound);
+        output.instances.push((parameters, store));
+        true
+    }
+
+    /// Closes the instance of a parametrized output stream with the given parameters, discarding its values
+    pub(crate) fn terminate_instance(&mut self, inst: OutInstance, parameters: &[Value]) {
+        assert!(self.parametrized[inst]);
+        self.p_outputs[self.index_map[inst]].instances.retain(|(p, _)| p.as_slice() != parameters);
+    }
+
     /// Returns the storage of a sliding window instance
     pub(crate) fn get_window(&self, window: WindowReference) -> &SlidingWindow {
         let ix = window.idx();
@@ -97,10 +167,17 @@ impl GlobalStore {
 /// Storage of a stream instance
 #[derive(Clone, Debug)]
 pub(crate) struct InstanceStore {
-    /// Buffer contains the offset values, where new elements get stored at the front
-    buffer: VecDeque<Value>,
-    /// Bound of the buffer
-    bound: MemorizationBound,
+    /// Buffer contains the offset values
+    buffer: Buffer,
+}
+
+/// The values of a stream instance, the newest value has offset 0
+#[derive(Clone, Debug)]
+enum Buffer {
+    /// A buffer of fixed capacity overwriting the oldest value, `head` is the index of the newest value
+    Ring { values: Box<[Value]>, head: usize, len: usize },
+    /// A growable buffer for streams without memory bound, where new elements get stored at the front
+    Growable(VecDeque<Value>),
 }
 
 const SIZE: usize = 256;
@@ -109,32 +186,69 @@ impl InstanceStore {
     // _type might be used later.
     /// Returns the storage of a stream instance, by setting the size of the buffer to the given bound
     pub(crate) fn new(_type: &Type, bound: MemorizationBound) -> InstanceStore {
-        match bound {
+        let buffer = match bound {
             MemorizationBound::Bounded(limit) => {
-                InstanceStore { buffer: VecDeque::with_capacity(limit as usize), bound }
+                // the current value is stored even if no offset is accessed
+                let capacity = std::cmp::max(limit as usize, 1);
+                Buffer::Ring { values: vec![Value::None; capacity].into_boxed_slice(), head: 0, len: 0 }
             }
-            MemorizationBound::Unbounded => InstanceStore { buffer: VecDeque::with_capacity(SIZE), bound },
-        }
+            MemorizationBound::Unbounded => Buffer::Growable(VecDeque::with_capacity(SIZE)),
+        };
+        InstanceStore { buffer }
     }
 
     /// Returns the current value of a stream instance at the given offset
     pub(crate) fn get_value(&self, offset: i16) -> Option<Value> {
         assert!(offset <= 0);
-        if offset == 0 {
-            self.buffer.front().cloned()
-        } else {
-            let offset = offset.abs() as usize;
-            self.buffer.get(offset).cloned()
+        let offset = offset.abs() as usize;
+        match &self.buffer {
+            Buffer::Ring { values, head, len } => {
+                if offset < *len {
+                    Some(values[(head + offset) % values.len()].clone())
+                } else {
+                    None
+                }
+            }
+            Buffer::Growable(values) => values.get(offset).cloned(),
         }
     }
 
     /// Updates the buffer of stream instance
     pub(crate) fn push_value(&mut self, v: Value) {
-        if let MemorizationBound::Bounded(limit) = self.bound {
-            if self.buffer.len() == limit as usize {
-                self.buffer.pop_back();
+        match &mut self.buffer {
+            Buffer::Ring { values, head, len } => {
+                let capacity = values.len();
+                *head = (*head + capacity - 1) % capacity;
+                values[*head] = v;
+                *len = std::cmp::min(*len + 1, capacity);
             }
+            Buffer::Growable(values) => values.push_front(v),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ring_buffer_keeps_the_newest_values() {
+        let mut store = InstanceStore::new(&Type::Bool, MemorizationBound::Bounded(3));
+        assert_eq!(store.get_value(0), None);
+        for i in 1..=5 {
+            store.push_value(Value::Unsigned(i));
+        }
+        assert_eq!(store.get_value(0), Some(Value::Unsigned(5)));
+        assert_eq!(store.get_value(-2), Some(Value::Unsigned(3)));
+        assert_eq!(store.get_value(-3), None);
+    }
+
+    #[test]
+    fn unbounded_buffer_keeps_all_values() {
+        let mut store = InstanceStore::new(&Type::Bool, MemorizationBound::Unbounded);
+        for i in 0..SIZE as u64 + 10 {
+            store.push_value(Value::Unsigned(i));
         }
-        self.buffer.push_front(v);
+        assert_eq!(store.get_value(-(SIZE as i16) - 9), Some(Value::Unsigned(0)));
     }
 }
@@ -1,6 +1,9 @@
 use ordered_float::NotNan;
-use rtlola_frontend::ir::Type;
+use rtlola_frontend::ir::{Number, Type};
+use std::borrow::Cow;
 use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt;
 use std::ops;
 
 use self::Value::*;
@@ -30,9 +33,9 @@ pub enum Value {
     */
     Float(NotNan<f64>),
     /**
-    A tuple of `Value`s.
+    A tuple of `Value`s, also representing arrays.
 
-    The nested values can be of different type.
+    The nested values can be of different type, except for arrays.
     */
     Tuple(Box<[Value]>),
     /**
@@ -52,25 +55,45 @@ impl Value {
     /// * 'source' - A byte slice that holds the value
     /// * 'ty' - the type of the interpretation
     pub(crate) fn try_from(source: &[u8], ty: &Type) -> Option<Value> {
-        if let Type::Bytes = ty {
-            return Some(Bytes(source.into()));
+        // booleans and integers are decoded from the bytes directly, without validating and parsing a `str`
+        match ty {
+            Type::Bytes => return Some(Bytes(source.into())),
+            Type::Bool => {
+                return match source {
+                    b"true" => Some(Bool(true)),
+                    b"false" => Some(Bool(false)),
+                    _ => Option::None,
+                }
+            }
+            Type::Int(_) => return parse_signed(source).map(Signed),
+            Type::UInt(_) => {
+                // TODO: This is just a quickfix!! Think of something more general.
+                if source == b"0.0" {
+                    return Some(Unsigned(0));
+                }
+                return parse_unsigned(source).map(Unsigned);
+            }
+            _ => {}
         }
         if let Ok(source) = std::str::from_utf8(source) {
             match ty {
-                Type::Bool => source.parse::<bool>().map(Bool).ok(),
-                Type::Int(_) => source.parse::<i64>().map(Signed).ok(),
-                Type::UInt(_) => {
-                    // TODO: This is just a quickfix!! Think of something more general.
-                    if source == "0.0" {
-                        Some(Unsigned(0))
-                    } else {
-                        source.parse::<u64>().map(Unsigned).ok()
-                    }
-                }
                 Type::Float(_) => source.parse::<f64>().ok().map(|f| Float(NotNan::new(f).unwrap())),
                 Type::String => Some(Str(source.into())),
+                // variants are given by their name and represented by their index
+                Type::Enum(_, variants) => variants.iter().position(|v| v == source).map(|i| Unsigned(i as u64)),
                 Type::Tuple(_) => unimplemented!(),
-                Type::Option(_) | Type::Function(_, _) | Type::Bytes => unreachable!(),
+                Type::Array(ty, size) => {
+                    // arrays of scalar values are given as `[a, b, c]`
+                    let elems = source.trim().strip_prefix('[')?.strip_suffix(']')?.split(',');
+                    let elems = elems.map(|e| Value::try_from(e.trim().as_bytes(), ty)).collect::<Option<Vec<_>>>()?;
+                    if elems.len() == *size {
+                        Some(Tuple(elems.into()))
+                    } else {
+                        Option::None
+                    }
+                }
+                Type::Option(_) | Type::Function(_, _) => unreachable!(),
+                Type::Bool | Type::Int(_) | Type::UInt(_) | Type::Bytes => unreachable!("decoded from bytes above"),
             }
         } else {
             Option::None // TODO: error message about non-utf8 encoded string?
@@ -84,6 +107,89 @@ impl Value {
         Float(NotNan::new(f).unwrap())
     }
 
+    /// Returns the result of a logarithm as 'Value' type:
+    /// values are never NaN, so results that are not a number, e.g., for negative arguments, are negative infinity.
+    pub(crate) fn new_log(f: f64) -> Value {
+        if f.is_nan() {
+            Float(NotNan::new(f64::NEG_INFINITY).unwrap())
+        } else {
+            Value::new_float(f)
+        }
+    }
+
+    /// Returns the value of a JSON value with the given type, if possible.
+    /// `null` is the absence of a value, bytes are given as a string or an array of numbers, and variants of enumerations by their name.
+    #[cfg(feature = "json")]
+    pub fn from_json(json: &serde_json::Value, ty: &Type) -> Option<Value> {
+        use serde_json::Value as Json;
+        if json.is_null() {
+            return Some(None);
+        }
+        Some(match ty {
+            Type::Bool => Bool(json.as_bool()?),
+            Type::Int(_) => Signed(json.as_i64()?),
+            Type::UInt(_) => Unsigned(json.as_u64()?),
+            Type::Float(_) => Float(NotNan::new(json.as_f64()?).ok()?),
+            Type::String => Str(json.as_str()?.into()),
+            Type::Enum(_, variants) => {
+                Unsigned(variants.iter().position(|variant| Some(variant.as_str()) == json.as_str())? as u64)
+            }
+            Type::Bytes => match json {
+                Json::String(s) => Bytes(s.as_bytes().into()),
+                Json::Array(bytes) => {
+                    let bytes = bytes.iter().map(|b| b.as_u64().filter(|b| *b <= 255).map(|b| b as u8));
+                    Bytes(bytes.collect::<Option<Vec<u8>>>()?.into())
+                }
+                _ => return Option::None,
+            },
+            Type::Tuple(tys) => {
+                let elems = json.as_array().filter(|elems| elems.len() == tys.len())?;
+                Tuple(elems.iter().zip(tys).map(|(e, ty)| Value::from_json(e, ty)).collect::<Option<Vec<_>>>()?.into())
+            }
+            Type::Array(ty, size) => {
+                let elems = json.as_array().filter(|elems| elems.len() == *size)?;
+                Tuple(elems.iter().map(|e| Value::from_json(e, ty)).collect::<Option<Vec<_>>>()?.into())
+            }
+            Type::Option(_) | Type::Function(_, _) => return Option::None,
+        })
+    }
+
+    /// Returns the value as JSON value, where the absence of a value is `null` and tuples are arrays.
+    #[cfg(feature = "json")]
+    pub fn into_json(self) -> serde_json::Value {
+        use serde_json::Value as Json;
+        match self {
+            None => Json::Null,
+            Bool(b) => Json::from(b),
+            Unsigned(u) => Json::from(u),
+            Signed(i) => Json::from(i),
+            Float(f) => Json::from(f.into_inner()),
+            Str(s) => Json::from(s.into_string()),
+            Bytes(b) => Json::from(b.into_vec()),
+            Tuple(vals) => Json::Array(vals.into_vec().into_iter().map(Value::into_json).collect()),
+        }
+    }
+
+    /// Returns the value as presented to users given its type, where variants of enumerations are given by their name
+    /// and arrays are written like their literals, e.g., `[1, 2]`.
+    pub(crate) fn render(&self, ty: &Type) -> String {
+        match (self, ty) {
+            (Unsigned(ix), Type::Enum(_, variants)) => {
+                variants.get(*ix as usize).cloned().unwrap_or_else(|| self.to_string())
+            }
+            (Tuple(entries), Type::Tuple(tys)) => {
+                let entries: Vec<String> = entries.iter().zip(tys).map(|(v, ty)| v.render(ty)).collect();
+                format!("({})", entries.join(", "))
+            }
+            (Tuple(entries), Type::Array(ty, _)) => {
+                let entries: Vec<String> = entries.iter().map(|v| v.render(ty)).collect();
+                format!("[{}]", entries.join(", "))
+            }
+            (_, Type::Option(ty)) => self.render(ty),
+            _ => self.to_string(),
+        }
+    }
+
     /// Decides if a value is of type bool
     pub(crate) fn is_bool(&self) -> bool {
         if let Bool(_) = self {
@@ -101,6 +207,16 @@ impl Value {
             unreachable!()
         }
     }
+
+    /// Returns the value of a numeric value type as float, or 'None' for other value types
+    pub(crate) fn get_numeric(&self) -> Option<f64> {
+        match *self {
+            Unsigned(u) => Some(u as f64),
+            Signed(i) => Some(i as f64),
+            Float(f) => Some(f.into_inner()),
+            _ => Option::None,
+        }
+    }
 }
 
 impl ops::Add for Value {
@@ -110,6 +226,8 @@ impl ops::Add for Value {
             (Unsigned(v1), Unsigned(v2)) => Unsigned(v1 + v2),
             (Signed(v1), Signed(v2)) => Signed(v1 + v2),
             (Float(v1), Float(v2)) => Float(v1 + v2),
+            // arrays are combined elementwise
+            (Tuple(v1), Tuple(v2)) => Tuple(v1.into_vec().into_iter().zip(v2.into_vec()).map(|(a, b)| a + b).collect()),
             (a, b) => panic!("Incompatible types: ({:?},{:?})", a, b),
         }
     }
@@ -122,6 +240,7 @@ impl ops::Sub for Value {
             (Unsigned(v1), Unsigned(v2)) => Unsigned(v1 - v2),
             (Signed(v1), Signed(v2)) => Signed(v1 - v2),
             (Float(v1), Float(v2)) => Float(v1 - v2),
+            (Tuple(v1), Tuple(v2)) => Tuple(v1.into_vec().into_iter().zip(v2.into_vec()).map(|(a, b)| a - b).collect()),
             (a, b) => panic!("Incompatible types: ({:?},{:?})", a, b),
         }
     }
@@ -134,6 +253,7 @@ impl ops::Mul for Value {
             (Unsigned(v1), Unsigned(v2)) => Unsigned(v1 * v2),
             (Signed(v1), Signed(v2)) => Signed(v1 * v2),
             (Float(v1), Float(v2)) => Float(v1 * v2),
+            (Tuple(v1), Tuple(v2)) => Tuple(v1.into_vec().into_iter().zip(v2.into_vec()).map(|(a, b)| a * b).collect()),
             (a, b) => panic!("Incompatible types: ({:?},{:?})", a, b),
         }
     }
@@ -146,6 +266,7 @@ impl ops::Div for Value {
             (Unsigned(v1), Unsigned(v2)) => Unsigned(v1 / v2),
             (Signed(v1), Signed(v2)) => Signed(v1 / v2),
             (Float(v1), Float(v2)) => Float(v1 / v2),
+            (Tuple(v1), Tuple(v2)) => Tuple(v1.into_vec().into_iter().zip(v2.into_vec()).map(|(a, b)| a / b).collect()),
             (a, b) => panic!("Incompatible types: ({:?},{:?})", a, b),
         }
     }
@@ -178,6 +299,522 @@ impl Value {
     }
 }
 
+/// The string functions of the standard library.
+impl Value {
+    fn get_str(&self) -> &str {
+        match self {
+            Str(s) => s,
+            v => panic!("Incompatible type: expected a String, found {:?}", v),
+        }
+    }
+
+    fn get_unsigned(&self) -> u64 {
+        match *self {
+            Unsigned(u) => u,
+            ref v => panic!("Incompatible type: expected an unsigned integer, found {:?}", v),
+        }
+    }
+
+    /// Returns the number of characters of a string.
+    pub(crate) fn length(&self) -> Value {
+        Unsigned(self.get_str().chars().count() as u64)
+    }
+
+    pub(crate) fn contains(&self, pattern: &Value) -> Value {
+        Bool(self.get_str().contains(pattern.get_str()))
+    }
+
+    pub(crate) fn starts_with(&self, prefix: &Value) -> Value {
+        Bool(self.get_str().starts_with(prefix.get_str()))
+    }
+
+    pub(crate) fn ends_with(&self, suffix: &Value) -> Value {
+        Bool(self.get_str().ends_with(suffix.get_str()))
+    }
+
+    pub(crate) fn to_upper(&self) -> Value {
+        Str(self.get_str().to_uppercase().into_boxed_str())
+    }
+
+    pub(crate) fn to_lower(&self) -> Value {
+        Str(self.get_str().to_lowercase().into_boxed_str())
+    }
+
+    /// Returns the characters of a string from index `from` up to, but excluding, index `to`.
+    /// Indices beyond the end of the string are clamped, an empty range yields the empty string.
+    pub(crate) fn substring(&self, from: &Value, to: &Value) -> Value {
+        let (from, to) = (from.get_unsigned() as usize, to.get_unsigned() as usize);
+        Str(self.get_str().chars().skip(from).take(to.saturating_sub(from)).collect::<String>().into_boxed_str())
+    }
+
+    /// Parses a decimal integer, ignoring surrounding whitespace; yields no value if the string is not an `i64`.
+    pub(crate) fn parse_int(&self) -> Value {
+        self.get_str().trim().parse::<i64>().map_or(Value::None, Signed)
+    }
+
+    /// Parses a decimal number, ignoring surrounding whitespace; yields no value if the string is not a number.
+    pub(crate) fn parse_float(&self) -> Value {
+        match self.get_str().trim().parse::<f64>().map(NotNan::new) {
+            Ok(Ok(f)) => Float(f),
+            _ => Value::None,
+        }
+    }
+}
+
+/// Numbers stored in `Bytes` at a byte offset, no value is returned if the bytes end before the number.
+impl Value {
+    fn bytes_at_offset(&self, offset: &Value, len: usize) -> Option<&[u8]> {
+        let start = usize::try_from(offset.get_unsigned()).ok()?;
+        match self {
+            Bytes(b) => b.get(start..start.checked_add(len)?),
+            v => panic!("Incompatible type: expected Bytes, found {:?}", v),
+        }
+    }
+
+    fn u16_at(&self, offset: &Value, from_bytes: fn([u8; 2]) -> u16) -> Value {
+        match self.bytes_at_offset(offset, 2) {
+            Some(b) => Unsigned(u64::from(from_bytes(<[u8; 2]>::try_from(b).unwrap()))),
+            Option::None => Value::None,
+        }
+    }
+
+    fn i32_at(&self, offset: &Value, from_bytes: fn([u8; 4]) -> i32) -> Value {
+        match self.bytes_at_offset(offset, 4) {
+            Some(b) => Signed(i64::from(from_bytes(<[u8; 4]>::try_from(b).unwrap()))),
+            Option::None => Value::None,
+        }
+    }
+
+    /// NaN is not a value, so it yields no value as well.
+    fn f32_at(&self, offset: &Value, from_bytes: fn([u8; 4]) -> f32) -> Value {
+        match self
+            .bytes_at_offset(offset, 4)
+            .map(|b| NotNan::new(f64::from(from_bytes(<[u8; 4]>::try_from(b).unwrap()))))
+        {
+            Some(Ok(f)) => Float(f),
+            _ => Value::None,
+        }
+    }
+
+    pub(crate) fn u16_le(&self, offset: &Value) -> Value {
+        self.u16_at(offset, u16::from_le_bytes)
+    }
+
+    pub(crate) fn u16_be(&self, offset: &Value) -> Value {
+        self.u16_at(offset, u16::from_be_bytes)
+    }
+
+    pub(crate) fn i32_le(&self, offset: &Value) -> Value {
+        self.i32_at(offset, i32::from_le_bytes)
+    }
+
+    pub(crate) fn i32_be(&self, offset: &Value) -> Value {
+        self.i32_at(offset, i32::from_be_bytes)
+    }
+
+    pub(crate) fn f32_le(&self, offset: &Value) -> Value {
+        self.f32_at(offset, f32::from_le_bytes)
+    }
+
+    pub(crate) fn f32_be(&self, offset: &Value) -> Value {
+        self.f32_at(offset, f32::from_be_bytes)
+    }
+}
+
+impl Value {
+    /// Converts an integer into a signed integer, unsigned integers beyond the range saturate at `i64::MAX`.
+    pub(crate) fn to_int(&self) -> Value {
+        match *self {
+            Signed(i) => Signed(i),
+            Unsigned(u) => Signed(i64::try_from(u).unwrap_or(i64::MAX)),
+            ref v => panic!("Incompatible type: expected an integer, found {:?}", v),
+        }
+    }
+
+    /// Converts a number into the nearest float.
+    pub(crate) fn to_float(&self) -> Value {
+        match *self {
+            Signed(i) => Value::new_float(i as f64),
+            Unsigned(u) => Value::new_float(u as f64),
+            Float(f) => Float(f),
+            ref v => panic!("Incompatible type: expected a number, found {:?}", v),
+        }
+    }
+
+    /// Rounds a float to the nearest signed integer, halfway cases are rounded away from zero.
+    pub(crate) fn round_to_int(&self) -> Value {
+        Value::saturating_int(self.get_float().round())
+    }
+
+    /// Truncates the fractional part of a float.
+    pub(crate) fn trunc_to_int(&self) -> Value {
+        Value::saturating_int(self.get_float().trunc())
+    }
+
+    /// Rounds a float down to the given number of decimal digits, zero if omitted.
+    pub(crate) fn floor(&self, digits: Option<&Value>) -> Value {
+        self.round_to_digits(digits, f64::floor)
+    }
+
+    /// Rounds a float up to the given number of decimal digits, zero if omitted.
+    pub(crate) fn ceil(&self, digits: Option<&Value>) -> Value {
+        self.round_to_digits(digits, f64::ceil)
+    }
+
+    /// Rounds a float to the nearest value with the given number of decimal digits, zero if omitted.
+    /// Halfway cases are rounded away from zero.
+    pub(crate) fn round(&self, digits: Option<&Value>) -> Value {
+        self.round_to_digits(digits, f64::round)
+    }
+
+    /// Negative digits round to tens, hundreds, etc.; digits beyond the precision of the float leave it unchanged.
+    fn round_to_digits(&self, digits: Option<&Value>, op: fn(f64) -> f64) -> Value {
+        let x = self.get_float();
+        let digits = match digits {
+            Option::None => 0,
+            Some(Signed(d)) => (*d).clamp(-308, 308) as i32,
+            Some(v) => panic!("Incompatible type: expected a signed integer, found {:?}", v),
+        };
+        if digits >= 0 {
+            let scaled = x * 10f64.powi(digits);
+            Value::new_float(if scaled.is_finite() { op(scaled) / 10f64.powi(digits) } else { x })
+        } else {
+            let factor = 10f64.powi(-digits);
+            Value::new_float(op(x / factor) * factor)
+        }
+    }
+
+    fn get_float(&self) -> f64 {
+        match *self {
+            Float(f) => f.into_inner(),
+            ref v => panic!("Incompatible type: expected a float, found {:?}", v),
+        }
+    }
+
+    /// Converts an integral float into a signed integer, values beyond the range saturate at the bounds.
+    fn saturating_int(f: f64) -> Value {
+        // 2^63 is exactly representable, unlike `i64::MAX`.
+        const BOUND: f64 = 9_223_372_036_854_775_808.0;
+        if f >= BOUND {
+            Signed(i64::MAX)
+        } else if f < -BOUND {
+            Signed(i64::MIN)
+        } else {
+            Signed(f as i64)
+        }
+    }
+}
+
+impl Value {
+    /// Returns the bytes checksums are computed over: integers are encoded as 8 bytes in little-endian order,
+    /// independent of their declared width, and strings in UTF-8.
+    fn checksum_bytes(&self) -> Cow<'_, [u8]> {
+        match self {
+            Signed(i) => Cow::Owned(i.to_le_bytes().to_vec()),
+            Unsigned(u) => Cow::Owned(u.to_le_bytes().to_vec()),
+            Str(s) => Cow::Borrowed(s.as_bytes()),
+            Bytes(b) => Cow::Borrowed(b),
+            v => panic!("Incompatible type: expected an integer, string, or bytes, found {:?}", v),
+        }
+    }
+
+    /// Returns the CRC-16/CCITT-FALSE checksum, i.e., polynomial 0x1021 with initial value 0xFFFF.
+    pub(crate) fn crc16(&self) -> Value {
+        let mut crc: u16 = 0xFFFF;
+        for byte in self.checksum_bytes().iter() {
+            crc ^= u16::from(*byte) << 8;
+            for _ in 0..8 {
+                crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+            }
+        }
+        Unsigned(u64::from(crc))
+    }
+
+    /// Returns the CRC-32 checksum as used by Ethernet and zlib.
+    pub(crate) fn crc32(&self) -> Value {
+        let mut crc: u32 = 0xFFFF_FFFF;
+        for byte in self.checksum_bytes().iter() {
+            crc ^= u32::from(*byte);
+            for _ in 0..8 {
+                crc = if crc & 1 != 0 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            }
+        }
+        Unsigned(u64::from(!crc))
+    }
+
+    /// Returns the 64-bit FNV-1a hash, which is stable across platforms and releases.
+    pub(crate) fn hash64(&self) -> Value {
+        let mut hash: u64 = 0xCBF2_9CE4_8422_2325;
+        for byte in self.checksum_bytes().iter() {
+            hash = (hash ^ u64::from(*byte)).wrapping_mul(0x0000_0100_0000_01B3);
+        }
+        Unsigned(hash)
+    }
+}
+
+/// Bits are indexed from the least significant bit within the `width` of the integer type,
+/// signed integers are represented in two's complement.
+impl Value {
+    /// Returns the width in bits of the first argument of a function, 64 for arguments without fixed size.
+    pub(crate) fn width_of_arg(ty: &Type) -> u32 {
+        match ty {
+            Type::Function(params, _) => params[0].size().map_or(64, |s| s.0 * 8),
+            _ => unreachable!("expected a function type, found {:?}", ty),
+        }
+    }
+
+    /// Returns the bits of an integer within its width, all higher bits are cleared.
+    fn raw_bits(&self, width: u32) -> u64 {
+        let bits = match *self {
+            Signed(i) => i as u64,
+            Unsigned(u) => u,
+            ref v => panic!("Incompatible type: expected an integer, found {:?}", v),
+        };
+        if width >= 64 {
+            bits
+        } else {
+            bits & ((1 << width) - 1)
+        }
+    }
+
+    /// Creates an integer of the same kind as `self` from bits within the width, signed integers are sign-extended.
+    fn with_raw_bits(&self, bits: u64, width: u32) -> Value {
+        match *self {
+            Signed(_) => {
+                let shift = 64 - width.min(64);
+                Signed(((bits << shift) as i64) >> shift)
+            }
+            _ => Unsigned(bits),
+        }
+    }
+
+    /// Returns whether the bit at `index` is set, false for indices beyond the width.
+    pub(crate) fn bit(&self, index: &Value, width: u32) -> Value {
+        match *index {
+            Unsigned(i) => Bool(i < u64::from(width) && (self.raw_bits(width) >> i) & 1 == 1),
+            ref v => panic!("Incompatible type: expected an unsigned index, found {:?}", v),
+        }
+    }
+
+    /// Returns the bits from `hi` down to `lo`, both inclusive, shifted to the least significant bits.
+    /// Indices beyond the width are limited to the most significant bit; the result is 0 if `lo` exceeds `hi`.
+    pub(crate) fn bits(&self, hi: &Value, lo: &Value, width: u32) -> Value {
+        let (hi, lo) = match (hi, lo) {
+            (Unsigned(hi), Unsigned(lo)) => ((*hi).min(u64::from(width) - 1), *lo),
+            (hi, lo) => panic!("Incompatible types: expected unsigned indices, found ({:?},{:?})", hi, lo),
+        };
+        if lo > hi {
+            return self.with_raw_bits(0, width);
+        }
+        let len = (hi - lo + 1) as u32;
+        let mask = if len >= 64 { u64::MAX } else { (1 << len) - 1 };
+        // Only a field spanning the whole width can reach the sign bit, which then keeps the value unchanged.
+        self.with_raw_bits((self.raw_bits(width) >> lo) & mask, width)
+    }
+
+    /// Returns the number of set bits, negative numbers are counted in two's complement.
+    pub(crate) fn popcount(&self, width: u32) -> Value {
+        Unsigned(u64::from(self.raw_bits(width).count_ones()))
+    }
+
+    /// Returns the value with the bit at `index` set or cleared, unchanged for indices beyond the width.
+    pub(crate) fn set_bit(&self, index: &Value, to: &Value, width: u32) -> Value {
+        let (index, to) = match (index, to) {
+            (Unsigned(i), Bool(b)) => (*i, *b),
+            (i, b) => panic!("Incompatible types: expected an unsigned index and a bool, found ({:?},{:?})", i, b),
+        };
+        if index >= u64::from(width) {
+            return self.clone();
+        }
+        let bits = self.raw_bits(width);
+        let bits = if to { bits | (1 << index) } else { bits & !(1 << index) };
+        self.with_raw_bits(bits, width)
+    }
+}
+
+/// Integer arithmetic within the `width` of the integer type instead of the 64 bits values are stored with.
+impl Value {
+    /// Returns the smallest and greatest value of the integer type of `self`.
+    fn int_bounds(&self, width: u32) -> (i128, i128) {
+        match self {
+            Signed(_) => (-(1 << (width - 1)), (1 << (width - 1)) - 1),
+            Unsigned(_) => (0, (1 << width) - 1),
+            v => panic!("Incompatible type: expected an integer, found {:?}", v),
+        }
+    }
+
+    fn int_operands(&self, other: &Value) -> (i128, i128) {
+        match (self, other) {
+            (Signed(a), Signed(b)) => (i128::from(*a), i128::from(*b)),
+            (Unsigned(a), Unsigned(b)) => (i128::from(*a), i128::from(*b)),
+            (a, b) => panic!("Incompatible types: ({:?},{:?})", a, b),
+        }
+    }
+
+    fn with_int(&self, i: i128) -> Value {
+        match self {
+            Signed(_) => Signed(i as i64),
+            _ => Unsigned(i as u64),
+        }
+    }
+
+    /// Applies `op` and returns the result, or no value if it fails or exceeds the integer type.
+    pub(crate) fn checked_arith(&self, other: &Value, op: fn(i128, i128) -> Option<i128>, width: u32) -> Value {
+        let (a, b) = self.int_operands(other);
+        let (min, max) = self.int_bounds(width);
+        match op(a, b) {
+            Some(res) if min <= res && res <= max => self.with_int(res),
+            _ => Value::None,
+        }
+    }
+
+    /// Applies `op` and clamps the result to the bounds of the integer type.
+    pub(crate) fn saturating_arith(&self, other: &Value, op: fn(i128, i128) -> i128, width: u32) -> Value {
+        let (a, b) = self.int_operands(other);
+        let (min, max) = self.int_bounds(width);
+        self.with_int(op(a, b).max(min).min(max))
+    }
+
+    /// Casts a number to the numeric type, or returns no value if the type cannot represent it, see `Number`.
+    pub(crate) fn checked_cast(&self, to: &Type) -> Value {
+        let number = match *self {
+            Signed(i) => Number::Integer(i128::from(i)),
+            Unsigned(u) => Number::Integer(i128::from(u)),
+            Float(f) => Number::Float(f.into_inner()),
+            ref v => panic!("Incompatible type: expected a number, found {:?}", v),
+        };
+        let cast = match to {
+            Type::Int(ty) => number.to_integer(ty.bounds()).map(|i| Signed(i as i64)),
+            Type::UInt(ty) => number.to_integer(ty.bounds()).map(|u| Unsigned(u as u64)),
+            Type::Float(ty) => number.to_float(*ty).map(Value::new_float),
+            ty => unreachable!("expected a numeric type, found {}", ty),
+        };
+        cast.unwrap_or(Value::None)
+    }
+}
+
+/// The number of seconds of a day.
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// Timestamps are seconds since the Unix epoch, 1970-01-01 00:00:00 UTC; leap seconds are ignored.
+impl Value {
+    /// Returns the seconds elapsed since midnight of a timestamp.
+    fn seconds_of_day(&self) -> f64 {
+        match *self {
+            Signed(i) => i.rem_euclid(SECONDS_PER_DAY) as f64,
+            Unsigned(u) => (u % SECONDS_PER_DAY as u64) as f64,
+            Float(f) => f.into_inner().rem_euclid(SECONDS_PER_DAY as f64),
+            ref v => panic!("Incompatible type: expected a timestamp, found {:?}", v),
+        }
+    }
+
+    /// Returns the days elapsed since the epoch, negative for timestamps before the epoch.
+    fn days_since_epoch(&self) -> i64 {
+        match *self {
+            Signed(i) => i.div_euclid(SECONDS_PER_DAY),
+            Unsigned(u) => (u / SECONDS_PER_DAY as u64) as i64,
+            Float(f) => f.into_inner().div_euclid(SECONDS_PER_DAY as f64) as i64,
+            ref v => panic!("Incompatible type: expected a timestamp, found {:?}", v),
+        }
+    }
+
+    /// Returns the hour of a timestamp in UTC, from 0 to 23.
+    pub(crate) fn hour_of(&self) -> Value {
+        Unsigned((self.seconds_of_day() / 3600.0) as u64)
+    }
+
+    /// Returns the day of the week of a timestamp in UTC, from 1 for Monday to 7 for Sunday as in ISO 8601.
+    pub(crate) fn day_of_week(&self) -> Value {
+        // The epoch was a Thursday.
+        Unsigned((self.days_since_epoch() + 3).rem_euclid(7) as u64 + 1)
+    }
+
+    /// Returns the seconds elapsed since midnight UTC of a timestamp.
+    pub(crate) fn since_midnight(&self) -> Value {
+        Value::new_float(self.seconds_of_day())
+    }
+
+    /// Returns the length in milliseconds of a duration, which is represented by its length in seconds.
+    pub(crate) fn millis(&self) -> Value {
+        Value::new_float(self.get_float() * 1000.0)
+    }
+}
+
+/// The mean radius of the earth in meters.
+const EARTH_RADIUS: f64 = 6_371_008.8;
+
+impl Value {
+    /// Returns the great-circle distance in meters between two positions given as latitude and longitude in degrees.
+    pub(crate) fn haversine(lat1: &Value, lon1: &Value, lat2: &Value, lon2: &Value) -> Value {
+        let (phi1, phi2) = (lat1.get_float().to_radians(), lat2.get_float().to_radians());
+        let delta_phi = phi2 - phi1;
+        let delta_lambda = (lon2.get_float() - lon1.get_float()).to_radians();
+        let a = (delta_phi / 2.0).sin().powi(2) + phi1.cos() * phi2.cos() * (delta_lambda / 2.0).sin().powi(2);
+        // Rounding errors may push `a` slightly above 1 for antipodal positions.
+        Value::new_float(2.0 * EARTH_RADIUS * a.sqrt().min(1.0).asin())
+    }
+
+    /// Returns the initial bearing in degrees from the first to the second position.
+    /// The bearing is measured clockwise from north and lies in `[0, 360)`.
+    pub(crate) fn bearing(lat1: &Value, lon1: &Value, lat2: &Value, lon2: &Value) -> Value {
+        let (phi1, phi2) = (lat1.get_float().to_radians(), lat2.get_float().to_radians());
+        let delta_lambda = (lon2.get_float() - lon1.get_float()).to_radians();
+        let y = delta_lambda.sin() * phi2.cos();
+        let x = phi1.cos() * phi2.sin() - phi1.sin() * phi2.cos() * delta_lambda.cos();
+        Value::new_float(y.atan2(x).to_degrees().rem_euclid(360.0))
+    }
+
+    /// Returns the equivalent angle in `(-half_turn, half_turn]`.
+    fn wrap_angle(&self, half_turn: f64) -> Value {
+        let angle = self.get_float().rem_euclid(2.0 * half_turn);
+        Value::new_float(if angle > half_turn { angle - 2.0 * half_turn } else { angle })
+    }
+
+    /// Returns the equivalent angle in degrees in `(-180, 180]`.
+    pub(crate) fn wrap_angle_deg(&self) -> Value {
+        self.wrap_angle(180.0)
+    }
+
+    /// Returns the equivalent angle in radians in `(-pi, pi]`.
+    pub(crate) fn wrap_angle_rad(&self) -> Value {
+        self.wrap_angle(std::f64::consts::PI)
+    }
+
+    /// Returns the shortest signed rotation in degrees from `other` to `self`, e.g., from 350 to 10 is 20 and not -340.
+    pub(crate) fn angle_diff(&self, other: &Value) -> Value {
+        Value::new_float(self.get_float() - other.get_float()).wrap_angle(180.0)
+    }
+}
+
+impl Value {
+    /// Returns the linear interpolation between `a` and `b`, `t` outside of `[0, 1]` extrapolates.
+    pub(crate) fn lerp(a: &Value, b: &Value, t: &Value) -> Value {
+        let (a, b) = (a.get_float(), b.get_float());
+        Value::new_float(a + (b - a) * t.get_float())
+    }
+
+    /// Maps `x` linearly from `[in_lo, in_hi]` to `[out_lo, out_hi]`, values outside of the range are extrapolated.
+    /// An empty input range maps every value to `out_lo`.
+    pub(crate) fn map_range(x: &Value, in_lo: &Value, in_hi: &Value, out_lo: &Value, out_hi: &Value) -> Value {
+        let (in_lo, in_hi) = (in_lo.get_float(), in_hi.get_float());
+        if in_lo == in_hi {
+            return out_lo.clone();
+        }
+        let t = Value::new_float((x.get_float() - in_lo) / (in_hi - in_lo));
+        Value::lerp(out_lo, out_hi, &t)
+    }
+
+    /// Returns the number of standard deviations `self` is away from the mean.
+    /// Without deviation, the mean itself has a z-score of 0 and any other value is infinitely far away.
+    pub(crate) fn zscore(&self, mean: &Value, stddev: &Value) -> Value {
+        let diff = self.get_float() - mean.get_float();
+        if diff == 0.0 {
+            return Value::new_float(0.0);
+        }
+        Value::new_float(diff / stddev.get_float().abs())
+    }
+}
+
 impl ops::BitAnd for Value {
     type Output = Value;
     fn bitand(self, other: Value) -> Value {
@@ -276,15 +913,311 @@ impl Ord for Value {
     }
 }
 
+/// Displays the value as in trigger messages, where `#` marks the absence of a value as in CSV traces.
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            None => write!(f, "#"),
+            Bool(b) => write!(f, "{}", b),
+            Unsigned(u) => write!(f, "{}", u),
+            Signed(i) => write!(f, "{}", i),
+            Float(x) => write!(f, "{}", x),
+            Tuple(entries) => {
+                let entries: Vec<String> = entries.iter().map(ToString::to_string).collect();
+                write!(f, "({})", entries.join(", "))
+            }
+            Str(s) => write!(f, "{}", s),
+            Bytes(b) => write!(f, "{}", String::from_utf8_lossy(b)),
+        }
+    }
+}
+
+/// Parses the decimal digits with an optional leading `+`, or returns `None` if they are empty, invalid, or overflow.
+fn parse_unsigned(source: &[u8]) -> Option<u64> {
+    let digits = match source {
+        [b'+', digits @ ..] => digits,
+        digits => digits,
+    };
+    if digits.is_empty() {
+        return Option::None;
+    }
+    digits.iter().try_fold(0u64, |value, &digit| {
+        if digit.is_ascii_digit() {
+            value.checked_mul(10)?.checked_add(u64::from(digit - b'0'))
+        } else {
+            Option::None
+        }
+    })
+}
+
+/// Parses the decimal digits with an optional leading sign, or returns `None` if they are empty, invalid, or overflow.
+fn parse_signed(source: &[u8]) -> Option<i64> {
+    match source {
+        [b'-', b'+', ..] => Option::None,
+        [b'-', digits @ ..] => {
+            let magnitude = parse_unsigned(digits)?;
+            if magnitude == i64::min_value() as u64 {
+                Some(i64::min_value())
+            } else {
+                i64::try_from(magnitude).ok().map(|v| -v)
+            }
+        }
+        digits => parse_unsigned(digits).and_then(|v| i64::try_from(v).ok()),
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    #[test]
+    #[cfg(feature = "json")]
+    fn json_conversion() {
+        use rtlola_frontend::ir::{FloatTy, IntTy, UIntTy};
+        use serde_json::json;
+        let (int, float) = (Type::Int(IntTy::I64), Type::Float(FloatTy::F64));
+        let pair = Type::Tuple(vec![int.clone(), Type::Bool]);
+        assert_eq!(Value::from_json(&json!(-3), &int), Some(Signed(-3)));
+        assert_eq!(Value::from_json(&json!(null), &int), Some(None));
+        assert_eq!(Value::from_json(&json!(1.5), &int), Option::None);
+        assert_eq!(Value::from_json(&json!(-3), &Type::UInt(UIntTy::U64)), Option::None);
+        assert_eq!(Value::from_json(&json!([1, true]), &pair), Some(Tuple(vec![Signed(1), Bool(true)].into())));
+        assert_eq!(Value::from_json(&json!([1]), &pair), Option::None);
+        assert_eq!(
+            Value::from_json(&json!([1.0, 2.5]), &Type::Array(Box::new(float.clone()), 2)).map(Value::into_json),
+            Some(json!([1.0, 2.5]))
+        );
+        assert_eq!(Value::from_json(&json!([104, 105]), &Type::Bytes), Some(Bytes(b"hi".to_vec().into())));
+        let mode = Type::Enum(String::from("Mode"), vec![String::from("Init"), String::from("Armed")]);
+        assert_eq!(Value::from_json(&json!("Armed"), &mode), Some(Unsigned(1)));
+        assert_eq!(Value::from_json(&json!("Flying"), &mode), Option::None);
+        assert_eq!(Value::from_json(&json!(2.5), &float).map(Value::into_json), Some(json!(2.5)));
+    }
+
+    #[test]
+    fn render_variants() {
+        use rtlola_frontend::ir::UIntTy;
+        let mode = Type::Enum(String::from("Mode"), vec![String::from("Init"), String::from("Armed")]);
+        assert_eq!(Unsigned(1).render(&mode), "Armed");
+        assert_eq!(None.render(&mode), "#");
+        let pair = Type::Tuple(vec![mode, Type::UInt(UIntTy::U8)]);
+        assert_eq!(Tuple(vec![Unsigned(0), Unsigned(0)].into()).render(&pair), "(Init, 0)");
+        let array = Type::Array(Box::new(Type::UInt(UIntTy::U8)), 2);
+        assert_eq!(Tuple(vec![Unsigned(1), Unsigned(2)].into()).render(&array), "[1, 2]");
+    }
+
+    #[test]
+    fn decode_from_bytes() {
+        use rtlola_frontend::ir::{IntTy, UIntTy};
+        let int = Type::Int(IntTy::I64);
+        let uint = Type::UInt(UIntTy::U64);
+        assert_eq!(Value::try_from(b"-42", &int), Some(Signed(-42)));
+        assert_eq!(Value::try_from(b"+7", &int), Some(Signed(7)));
+        assert_eq!(Value::try_from(b"-9223372036854775808", &int), Some(Signed(i64::min_value())));
+        assert_eq!(Value::try_from(b"9223372036854775808", &int), Option::None);
+        assert_eq!(Value::try_from(b"-+1", &int), Option::None);
+        assert_eq!(Value::try_from(b"18446744073709551615", &uint), Some(Unsigned(u64::max_value())));
+        assert_eq!(Value::try_from(b"1a", &uint), Option::None);
+        assert_eq!(Value::try_from(b"", &uint), Option::None);
+        assert_eq!(Value::try_from(b"true", &Type::Bool), Some(Bool(true)));
+        assert_eq!(Value::try_from(b"True", &Type::Bool), Option::None);
+    }
+
     #[test]
     fn size_of_value() {
         let result = std::mem::size_of::<Value>();
         let expected = 24;
         assert!(result == expected, "Size of `Value` should be {} bytes, was `{}`", expected, result);
     }
+
+    #[test]
+    fn log_without_nan() {
+        assert_eq!(Value::new_log(8f64.log2()), Value::new_float(3.0));
+        assert_eq!(Value::new_log(0f64.ln()), Value::new_float(f64::NEG_INFINITY));
+        assert_eq!(Value::new_log((-1f64).log10()), Value::new_float(f64::NEG_INFINITY));
+    }
+
+    #[test]
+    fn conversions() {
+        assert_eq!(Unsigned(u64::MAX).to_int(), Signed(i64::MAX));
+        assert_eq!(Unsigned(7).to_float(), Value::new_float(7.0));
+        assert_eq!(Value::new_float(-2.5).round_to_int(), Signed(-3));
+        assert_eq!(Value::new_float(-2.5).trunc_to_int(), Signed(-2));
+        assert_eq!(Value::new_float(1e30).trunc_to_int(), Signed(i64::MAX));
+        assert_eq!(Value::new_float(f64::NEG_INFINITY).round_to_int(), Signed(i64::MIN));
+    }
+
+    #[test]
+    fn geospatial_functions() {
+        let f = Value::new_float;
+        // Saarbrücken to Paris
+        let (lat1, lon1, lat2, lon2) = (f(49.2402), f(6.9969), f(48.8566), f(2.3522));
+        let dist = Value::haversine(&lat1, &lon1, &lat2, &lon2).get_float();
+        assert!((dist - 341_000.0).abs() < 1_000.0, "distance was {}", dist);
+        let bearing = Value::bearing(&lat1, &lon1, &lat2, &lon2).get_float();
+        assert!((bearing - 264.6).abs() < 0.1, "bearing was {}", bearing);
+        assert_eq!(Value::bearing(&f(0.0), &f(0.0), &f(1.0), &f(0.0)), f(0.0));
+        assert_eq!(Value::haversine(&lat1, &lon1, &lat1, &lon1), f(0.0));
+    }
+
+    #[test]
+    fn byte_decoding() {
+        let frame = Bytes(vec![0x00, 0x34, 0x12, 0xFE, 0xFF, 0xFF, 0xFF, 0x40, 0x49, 0x0F, 0xDB].into());
+        assert_eq!(frame.u16_le(&Unsigned(1)), Unsigned(0x1234));
+        assert_eq!(frame.u16_be(&Unsigned(1)), Unsigned(0x3412));
+        assert_eq!(frame.i32_le(&Unsigned(3)), Signed(-2));
+        assert_eq!(frame.i32_be(&Unsigned(0)), Signed(0x0034_12FE));
+        assert_eq!(frame.f32_be(&Unsigned(7)), Value::new_float(f64::from(std::f32::consts::PI)));
+        assert_eq!(Bytes(vec![0xDB, 0x0F, 0x49, 0x40].into()).f32_le(&Unsigned(0)), frame.f32_be(&Unsigned(7)));
+        assert_eq!(frame.u16_le(&Unsigned(10)), Value::None);
+        assert_eq!(frame.i32_le(&Unsigned(u64::MAX)), Value::None);
+        assert_eq!(Bytes(vec![0xFF, 0xFF, 0xFF, 0xFF].into()).f32_le(&Unsigned(0)), Value::None);
+    }
+
+    #[test]
+    fn angle_normalization() {
+        let f = Value::new_float;
+        assert_eq!(f(190.0).wrap_angle_deg(), f(-170.0));
+        assert_eq!(f(-180.0).wrap_angle_deg(), f(180.0));
+        assert_eq!(f(720.0).wrap_angle_deg(), f(0.0));
+        assert_eq!(f(-3.0 * std::f64::consts::PI).wrap_angle_rad(), f(std::f64::consts::PI));
+        assert_eq!(f(10.0).angle_diff(&f(350.0)), f(20.0));
+        assert_eq!(f(350.0).angle_diff(&f(10.0)), f(-20.0));
+        assert_eq!(f(-170.0).angle_diff(&f(170.0)), f(20.0));
+    }
+
+    #[test]
+    fn timestamp_functions() {
+        // 2020-02-29 17:30:15 UTC, a Saturday
+        let t = Unsigned(1_582_997_415);
+        assert_eq!(t.hour_of(), Unsigned(17));
+        assert_eq!(t.day_of_week(), Unsigned(6));
+        assert_eq!(t.since_midnight(), Value::new_float(63_015.0));
+        assert_eq!(Value::new_float(1_582_997_415.5).since_midnight(), Value::new_float(63_015.5));
+        // 1969-12-31 23:00:00 UTC, a Wednesday
+        let t = Signed(-3600);
+        assert_eq!(t.hour_of(), Unsigned(23));
+        assert_eq!(t.day_of_week(), Unsigned(3));
+    }
+
+    #[test]
+    fn duration_functions() {
+        assert_eq!(Value::new_float(0.25).millis(), Value::new_float(250.0));
+        assert_eq!(Value::new_float(2.0).millis(), Value::new_float(2000.0));
+    }
+
+    #[test]
+    fn checksums() {
+        let check = Str("123456789".into());
+        assert_eq!(check.crc16(), Unsigned(0x29B1));
+        assert_eq!(check.crc32(), Unsigned(0xCBF4_3926));
+        assert_eq!(check.hash64(), Unsigned(0x06D5_5739_23C6_CDFC));
+        assert_eq!(Bytes(b"123456789".to_vec().into()).crc32(), check.crc32());
+        assert_eq!(Signed(42).hash64(), Unsigned(42).hash64());
+        assert_eq!(Unsigned(42).hash64(), Unsigned(0xFF3A_DD6B_3789_DAEF));
+    }
+
+    #[test]
+    fn checked_and_saturating() {
+        let add: fn(i128, i128) -> Option<i128> = i128::checked_add;
+        assert_eq!(Unsigned(200).checked_arith(&Unsigned(55), add, 8), Unsigned(255));
+        assert_eq!(Unsigned(200).checked_arith(&Unsigned(56), add, 8), Value::None);
+        assert_eq!(Unsigned(u64::MAX).checked_arith(&Unsigned(1), add, 64), Value::None);
+        assert_eq!(Signed(-100).checked_arith(&Signed(29), i128::checked_sub, 8), Value::None);
+        assert_eq!(Signed(i64::MIN).checked_arith(&Signed(-1), i128::checked_div, 64), Value::None);
+        assert_eq!(Signed(7).checked_arith(&Signed(0), i128::checked_div, 32), Value::None);
+        assert_eq!(Signed(-7).checked_arith(&Signed(2), i128::checked_div, 32), Signed(-3));
+        assert_eq!(Unsigned(3).saturating_arith(&Unsigned(5), |a, b| a - b, 16), Unsigned(0));
+        assert_eq!(Signed(100).saturating_arith(&Signed(100), |a, b| a + b, 8), Signed(127));
+        assert_eq!(Signed(i64::MIN).saturating_arith(&Signed(2), |a, b| a * b, 64), Signed(i64::MIN));
+        assert_eq!(Signed(-3).saturating_arith(&Signed(4), |a, b| a * b, 8), Signed(-12));
+        assert_eq!(Unsigned(u64::MAX).saturating_arith(&Unsigned(1), |a, b| a + b, 64), Unsigned(u64::MAX));
+    }
+
+    #[test]
+    fn checked_cast() {
+        use rtlola_frontend::ir::{FloatTy, IntTy, UIntTy};
+        assert_eq!(Signed(-128).checked_cast(&Type::Int(IntTy::I8)), Signed(-128));
+        assert_eq!(Signed(-129).checked_cast(&Type::Int(IntTy::I8)), Value::None);
+        assert_eq!(Signed(-1).checked_cast(&Type::UInt(UIntTy::U64)), Value::None);
+        assert_eq!(Unsigned(u64::MAX).checked_cast(&Type::Int(IntTy::I64)), Value::None);
+        assert_eq!(Value::new_float(255.9).checked_cast(&Type::UInt(UIntTy::U8)), Unsigned(255));
+        assert_eq!(Value::new_float(256.0).checked_cast(&Type::UInt(UIntTy::U8)), Value::None);
+        assert_eq!(Unsigned(3).checked_cast(&Type::Float(FloatTy::F32)), Value::new_float(3.0));
+        assert_eq!(Value::new_float(1e40).checked_cast(&Type::Float(FloatTy::F32)), Value::None);
+    }
+
+    #[test]
+    fn interpolation() {
+        let f = Value::new_float;
+        assert_eq!(Value::lerp(&f(10.0), &f(20.0), &f(0.25)), f(12.5));
+        assert_eq!(Value::lerp(&f(10.0), &f(20.0), &f(1.5)), f(25.0));
+        // A 12-bit ADC reading converted to a pressure between 0 and 10 bar.
+        assert_eq!(Value::map_range(&f(2048.0), &f(0.0), &f(4096.0), &f(0.0), &f(10.0)), f(5.0));
+        // A current loop with 4 to 20 mA.
+        assert_eq!(Value::map_range(&f(12.0), &f(4.0), &f(20.0), &f(0.0), &f(10.0)), f(5.0));
+        assert_eq!(Value::map_range(&f(1.0), &f(3.0), &f(3.0), &f(-1.0), &f(1.0)), f(-1.0));
+        assert_eq!(f(13.0).zscore(&f(10.0), &f(1.5)), f(2.0));
+        assert_eq!(f(7.0).zscore(&f(10.0), &f(1.5)), f(-2.0));
+        assert_eq!(f(10.0).zscore(&f(10.0), &f(0.0)), f(0.0));
+        assert_eq!(f(11.0).zscore(&f(10.0), &f(0.0)), f(f64::INFINITY));
+    }
+
+    #[test]
+    fn rounding() {
+        let f = Value::new_float;
+        let two = Signed(2);
+        assert_eq!(f(3.14159).round(Some(&two)), f(3.14));
+        assert_eq!(f(3.14159).ceil(Some(&two)), f(3.15));
+        assert_eq!(f(-3.14159).floor(Some(&two)), f(-3.15));
+        assert_eq!(f(-2.5).round(Option::None), f(-3.0));
+        assert_eq!(f(1234.5).floor(Some(&Signed(-2))), f(1200.0));
+        assert_eq!(f(1e300).round(Some(&Signed(100))), f(1e300));
+    }
+
+    #[test]
+    fn bit_manipulation() {
+        let status = Unsigned(0b1011_0100);
+        assert_eq!(status.bit(&Unsigned(2), 8), Bool(true));
+        assert_eq!(status.bit(&Unsigned(3), 8), Bool(false));
+        assert_eq!(status.bit(&Unsigned(64), 8), Bool(false));
+        assert_eq!(status.bits(&Unsigned(5), &Unsigned(2), 8), Unsigned(0b1101));
+        assert_eq!(status.bits(&Unsigned(100), &Unsigned(4), 8), Unsigned(0b1011));
+        assert_eq!(status.bits(&Unsigned(2), &Unsigned(5), 8), Unsigned(0));
+        assert_eq!(status.popcount(8), Unsigned(4));
+        assert_eq!(status.set_bit(&Unsigned(0), &Bool(true), 8), Unsigned(0b1011_0101));
+        assert_eq!(status.set_bit(&Unsigned(7), &Bool(false), 8), Unsigned(0b0011_0100));
+        assert_eq!(status.set_bit(&Unsigned(8), &Bool(true), 8), status);
+        // Signed integers are treated in two's complement within their width.
+        let neg = Signed(-2);
+        assert_eq!(neg.popcount(8), Unsigned(7));
+        assert_eq!(neg.popcount(64), Unsigned(63));
+        assert_eq!(neg.bit(&Unsigned(7), 8), Bool(true));
+        assert_eq!(neg.bits(&Unsigned(3), &Unsigned(1), 8), Signed(0b111));
+        assert_eq!(neg.bits(&Unsigned(7), &Unsigned(0), 8), neg);
+        assert_eq!(neg.set_bit(&Unsigned(7), &Bool(false), 8), Signed(126));
+        assert_eq!(Signed(1).set_bit(&Unsigned(63), &Bool(true), 64), Signed(i64::MIN + 1));
+    }
+
+    #[test]
+    fn string_functions() {
+        let s = Str("Größe: 42".into());
+        assert_eq!(s.length(), Unsigned(9));
+        assert_eq!(s.contains(&Str("ße".into())), Bool(true));
+        assert_eq!(s.starts_with(&Str("gr".into())), Bool(false));
+        assert_eq!(s.ends_with(&Str("42".into())), Bool(true));
+        assert_eq!(s.to_upper(), Str("GRÖSSE: 42".into()));
+        assert_eq!(s.to_lower(), Str("größe: 42".into()));
+        assert_eq!(s.substring(&Unsigned(2), &Unsigned(5)), Str("öße".into()));
+        assert_eq!(s.substring(&Unsigned(7), &Unsigned(100)), Str("42".into()));
+        assert_eq!(s.substring(&Unsigned(5), &Unsigned(2)), Str("".into()));
+        assert_eq!(Str(" -42\n".into()).parse_int(), Signed(-42));
+        assert_eq!(Str("4.2".into()).parse_int(), Value::None);
+        assert_eq!(Str("9223372036854775808".into()).parse_int(), Value::None);
+        assert_eq!(Str("4.2e1".into()).parse_float(), Value::new_float(42.0));
+        assert_eq!(Str("-inf".into()).parse_float(), Value::new_float(f64::NEG_INFINITY));
+        assert_eq!(Str("NaN".into()).parse_float(), Value::None);
+        assert_eq!(s.parse_float(), Value::None);
+    }
 }
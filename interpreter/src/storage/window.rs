@@ -1,12 +1,13 @@
 use super::window_aggregations::*;
 use super::Value;
-use crate::basics::Time;
+use crate::basics::{Aggregations, Time};
 use ordered_float::NotNan;
 use rtlola_frontend::ir::{Type, WindowOperation as WinOp};
 use std::cmp::Ordering;
 use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::ops::Add;
+use std::sync::Arc;
 use std::time::Duration;
 
 const SIZE: usize = 64;
@@ -15,15 +16,16 @@ const SIZE: usize = 64;
 /// The enum differentiates the aggregation functions and between different value types, dependent on the aggregation function.
 /// # Example:
 /// * The aggregation function 'count' is independent of the value type.
-/// * The aggregation function 'min' depends on the value type, e.g., the minimum value of unsigned values is 0, whereas the minimum value for signed values is negative.
+/// * The aggregation function 'sum' depends on the value type, e.g., the sum of unsigned values is unsigned, whereas the sum of floats is a float.
+/// * The aggregation functions 'min' and 'max' compare the values directly and keep their type.
 pub(crate) enum SlidingWindow {
     Count(WindowInstance<CountIV>),
-    MinUnsigned(WindowInstance<MinIV<WindowSigned>>),
-    MinSigned(WindowInstance<MinIV<WindowUnsigned>>),
-    MinFloat(WindowInstance<MinIV<WindowFloat>>),
-    MaxUnsigned(WindowInstance<MaxIV<WindowSigned>>),
-    MaxSigned(WindowInstance<MaxIV<WindowUnsigned>>),
-    MaxFloat(WindowInstance<MaxIV<WindowFloat>>),
+    Min(ExtremumInstance),
+    Max(ExtremumInstance),
+    Percentile(PercentileInstance),
+    First(WindowInstance<FirstIV>),
+    Last(WindowInstance<LastIV>),
+    Custom(CustomInstance),
     SumUnsigned(WindowInstance<SumIV<WindowUnsigned>>),
     SumSigned(WindowInstance<SumIV<WindowSigned>>),
     SumFloat(WindowInstance<SumIV<WindowFloat>>),
@@ -44,15 +46,31 @@ impl SlidingWindow {
     /// * 'op' - the type of the aggregation function
     /// * 'ts' - the starting time of the window
     /// * 'ty' - the value type of the aggregated stream
-    pub(crate) fn new(dur: Duration, wait: bool, op: WinOp, ts: Time, ty: &Type) -> SlidingWindow {
+    /// * 'aggregations' - the implementations of the aggregation functions registered by the host application
+    pub(crate) fn new(
+        dur: Duration,
+        wait: bool,
+        op: &WinOp,
+        ts: Time,
+        ty: &Type,
+        aggregations: &Aggregations,
+    ) -> SlidingWindow {
         match (op, ty) {
             (WinOp::Count, _) => SlidingWindow::Count(WindowInstance::new(dur, wait, ts)),
-            (WinOp::Min, Type::UInt(_)) => SlidingWindow::MinUnsigned(WindowInstance::new(dur, wait, ts)),
-            (WinOp::Min, Type::Int(_)) => SlidingWindow::MinSigned(WindowInstance::new(dur, wait, ts)),
-            (WinOp::Min, Type::Float(_)) => SlidingWindow::MinFloat(WindowInstance::new(dur, wait, ts)),
-            (WinOp::Max, Type::UInt(_)) => SlidingWindow::MaxUnsigned(WindowInstance::new(dur, wait, ts)),
-            (WinOp::Max, Type::Int(_)) => SlidingWindow::MaxSigned(WindowInstance::new(dur, wait, ts)),
-            (WinOp::Max, Type::Float(_)) => SlidingWindow::MaxFloat(WindowInstance::new(dur, wait, ts)),
+            (WinOp::Min, Type::UInt(_)) | (WinOp::Min, Type::Int(_)) | (WinOp::Min, Type::Float(_)) => {
+                SlidingWindow::Min(ExtremumInstance::new(dur, wait, Ordering::Less, ts))
+            }
+            (WinOp::Max, Type::UInt(_)) | (WinOp::Max, Type::Int(_)) | (WinOp::Max, Type::Float(_)) => {
+                SlidingWindow::Max(ExtremumInstance::new(dur, wait, Ordering::Greater, ts))
+            }
+            (WinOp::Median, Type::UInt(_)) | (WinOp::Median, Type::Int(_)) | (WinOp::Median, Type::Float(_)) => {
+                SlidingWindow::Percentile(PercentileInstance::new(dur, wait, 50, ts))
+            }
+            (WinOp::Percentile(p), Type::UInt(_))
+            | (WinOp::Percentile(p), Type::Int(_))
+            | (WinOp::Percentile(p), Type::Float(_)) => {
+                SlidingWindow::Percentile(PercentileInstance::new(dur, wait, *p, ts))
+            }
             (WinOp::Sum, Type::UInt(_)) => SlidingWindow::SumUnsigned(WindowInstance::new(dur, wait, ts)),
             (WinOp::Sum, Type::Int(_)) => SlidingWindow::SumSigned(WindowInstance::new(dur, wait, ts)),
             (WinOp::Sum, Type::Float(_)) => SlidingWindow::SumFloat(WindowInstance::new(dur, wait, ts)),
@@ -61,9 +79,14 @@ impl SlidingWindow {
             (WinOp::Average, Type::Int(_)) => SlidingWindow::AvgSigned(WindowInstance::new(dur, wait, ts)),
             (WinOp::Average, Type::Float(_)) => SlidingWindow::AvgFloat(WindowInstance::new(dur, wait, ts)),
             (WinOp::Integral, _) => SlidingWindow::Integral(WindowInstance::new(dur, wait, ts)),
+            (WinOp::First, _) => SlidingWindow::First(WindowInstance::new(dur, wait, ts)),
+            (WinOp::Last, _) => SlidingWindow::Last(WindowInstance::new(dur, wait, ts)),
+            (WinOp::Custom(name), _) => {
+                SlidingWindow::Custom(CustomInstance::new(dur, wait, aggregations.get(name), ts))
+            }
             (WinOp::Conjunction, Type::Bool) => SlidingWindow::Conjunction(WindowInstance::new(dur, wait, ts)),
             (WinOp::Disjunction, Type::Bool) => SlidingWindow::Disjunction(WindowInstance::new(dur, wait, ts)),
-            (_, Type::Option(t)) => SlidingWindow::new(dur, wait, op, ts, t),
+            (_, Type::Option(t)) => SlidingWindow::new(dur, wait, op, ts, t, aggregations),
             _ => unimplemented!(),
         }
     }
@@ -74,12 +97,12 @@ impl SlidingWindow {
     pub(crate) fn update(&mut self, ts: Time) {
         match self {
             SlidingWindow::Count(wi) => wi.update_buckets(ts),
-            SlidingWindow::MinUnsigned(wi) => wi.update_buckets(ts),
-            SlidingWindow::MinSigned(wi) => wi.update_buckets(ts),
-            SlidingWindow::MinFloat(wi) => wi.update_buckets(ts),
-            SlidingWindow::MaxUnsigned(wi) => wi.update_buckets(ts),
-            SlidingWindow::MaxSigned(wi) => wi.update_buckets(ts),
-            SlidingWindow::MaxFloat(wi) => wi.update_buckets(ts),
+            SlidingWindow::Min(wi) => wi.update_buckets(ts),
+            SlidingWindow::Max(wi) => wi.update_buckets(ts),
+            SlidingWindow::Percentile(wi) => wi.update_buckets(ts),
+            SlidingWindow::First(wi) => wi.update_buckets(ts),
+            SlidingWindow::Last(wi) => wi.update_buckets(ts),
+            SlidingWindow::Custom(wi) => wi.update_buckets(ts),
             SlidingWindow::SumUnsigned(wi) => wi.update_buckets(ts),
             SlidingWindow::SumSigned(wi) => wi.update_buckets(ts),
             SlidingWindow::SumFloat(wi) => wi.update_buckets(ts),
@@ -100,12 +123,12 @@ impl SlidingWindow {
     pub(crate) fn get_value(&self, ts: Time) -> Value {
         match self {
             SlidingWindow::Count(wi) => wi.get_value(ts),
-            SlidingWindow::MinUnsigned(wi) => wi.get_value(ts),
-            SlidingWindow::MinSigned(wi) => wi.get_value(ts),
-            SlidingWindow::MinFloat(wi) => wi.get_value(ts),
-            SlidingWindow::MaxUnsigned(wi) => wi.get_value(ts),
-            SlidingWindow::MaxSigned(wi) => wi.get_value(ts),
-            SlidingWindow::MaxFloat(wi) => wi.get_value(ts),
+            SlidingWindow::Min(wi) => wi.get_value(ts),
+            SlidingWindow::Max(wi) => wi.get_value(ts),
+            SlidingWindow::Percentile(wi) => wi.get_value(ts),
+            SlidingWindow::First(wi) => wi.get_value(ts),
+            SlidingWindow::Last(wi) => wi.get_value(ts),
+            SlidingWindow::Custom(wi) => wi.get_value(ts),
             SlidingWindow::SumUnsigned(wi) => wi.get_value(ts),
             SlidingWindow::SumSigned(wi) => wi.get_value(ts),
             SlidingWindow::SumFloat(wi) => wi.get_value(ts),
@@ -126,12 +149,12 @@ impl SlidingWindow {
     pub(crate) fn accept_value(&mut self, v: Value, ts: Time) {
         match self {
             SlidingWindow::Count(wi) => wi.accept_value(v, ts),
-            SlidingWindow::MinUnsigned(wi) => wi.accept_value(v, ts),
-            SlidingWindow::MinSigned(wi) => wi.accept_value(v, ts),
-            SlidingWindow::MinFloat(wi) => wi.accept_value(v, ts),
-            SlidingWindow::MaxUnsigned(wi) => wi.accept_value(v, ts),
-            SlidingWindow::MaxFloat(wi) => wi.accept_value(v, ts),
-            SlidingWindow::MaxSigned(wi) => wi.accept_value(v, ts),
+            SlidingWindow::Min(wi) => wi.accept_value(v, ts),
+            SlidingWindow::Max(wi) => wi.accept_value(v, ts),
+            SlidingWindow::Percentile(wi) => wi.accept_value(v, ts),
+            SlidingWindow::First(wi) => wi.accept_value(v, ts),
+            SlidingWindow::Last(wi) => wi.accept_value(v, ts),
+            SlidingWindow::Custom(wi) => wi.accept_value(v, ts),
             SlidingWindow::SumUnsigned(wi) => wi.accept_value(v, ts),
             SlidingWindow::SumSigned(wi) => wi.accept_value(v, ts),
             SlidingWindow::SumFloat(wi) => wi.accept_value(v, ts),
@@ -250,24 +273,215 @@ impl<IV: WindowIV> WindowInstance<IV> {
     fn get_current_bucket(&self, ts: Time) -> BIx {
         // let overall_ix = ts.duration_since(self.start_time).div_duration(self.time_per_bucket);
         assert!(ts >= self.start_time, "Time does not behave monotonically!");
-        let overall_ix = Self::quickfix_duration_div(ts - self.start_time, self.time_per_bucket);
+        let overall_ix = quickfix_duration_div(ts - self.start_time, self.time_per_bucket);
         let overall_ix = overall_ix.floor() as usize;
         let period = overall_ix / self.buckets.len();
         let ix = overall_ix % self.buckets.len();
         BIx { period, ix }
     }
+}
+
+fn quickfix_duration_div(a: Duration, b: Duration) -> f64 {
+    let a_secs = a.as_secs();
+    let a_nanos = a.subsec_nanos();
+    let b_secs = b.as_secs();
+    let b_nanos = b.subsec_nanos();
+    let a = (a_secs as f64) + f64::from(a_nanos) / f64::from(1_000_000_000);
+    let b = (b_secs as f64) + f64::from(b_nanos) / f64::from(1_000_000_000);
+    a / b
+}
+
+/// Sliding window computing the minimum or maximum of the values with a monotonic deque.
+/// The window is discretized into the same buckets as a `WindowInstance`, but rather than folding over all buckets,
+/// it only keeps the values that can still become the extremum, so accepting a value and computing the result take amortized constant time.
+pub(crate) struct ExtremumInstance {
+    /// The candidates together with the overall index of their bucket, oldest first.
+    /// All older candidates are strictly better w.r.t. `keep`, so the first one is the extremum.
+    candidates: VecDeque<(usize, Value)>,
+    /// `Ordering::Less` for the minimum and `Ordering::Greater` for the maximum.
+    keep: Ordering,
+    time_per_bucket: Duration,
+    start_time: Time,
+    wait: bool,
+    wait_duration: Duration,
+}
+
+impl ExtremumInstance {
+    fn new(dur: Duration, wait: bool, keep: Ordering, ts: Time) -> ExtremumInstance {
+        ExtremumInstance {
+            candidates: VecDeque::new(),
+            keep,
+            time_per_bucket: dur / (SIZE as u32),
+            start_time: ts,
+            wait,
+            wait_duration: dur,
+        }
+    }
+
+    /// You should always call `ExtremumInstance::update_buckets` before calling `ExtremumInstance::get_value()`!
+    fn get_value(&self, ts: Time) -> Value {
+        if self.wait && ts < self.wait_duration {
+            return Value::None;
+        }
+        self.candidates.front().map_or(Value::None, |(_, v)| v.clone())
+    }
+
+    fn accept_value(&mut self, v: Value, ts: Time) {
+        self.update_buckets(ts);
+        if v == Value::None {
+            return;
+        }
+        // Older candidates that are not better than `v` are outlived by it, so they never become the extremum.
+        while self.candidates.back().map_or(false, |(_, c)| c.cmp(&v) != self.keep) {
+            self.candidates.pop_back();
+        }
+        self.candidates.push_back((overall_bucket(ts, self.start_time, self.time_per_bucket), v));
+    }
+
+    fn update_buckets(&mut self, ts: Time) {
+        let curr = overall_bucket(ts, self.start_time, self.time_per_bucket);
+        while self.candidates.front().map_or(false, |(bucket, _)| bucket + SIZE <= curr) {
+            self.candidates.pop_front();
+        }
+    }
+}
+
+/// Sliding window computing a percentile of the values by the nearest-rank method.
+/// A percentile cannot be combined from summaries of the buckets, so the window keeps all values within the last `SIZE` buckets.
+pub(crate) struct PercentileInstance {
+    /// The values together with the overall index of their bucket, oldest first.
+    values: VecDeque<(usize, Value)>,
+    percentile: u8,
+    time_per_bucket: Duration,
+    start_time: Time,
+    wait: bool,
+    wait_duration: Duration,
+}
+
+impl PercentileInstance {
+    fn new(dur: Duration, wait: bool, percentile: u8, ts: Time) -> PercentileInstance {
+        PercentileInstance {
+            values: VecDeque::new(),
+            percentile,
+            time_per_bucket: dur / (SIZE as u32),
+            start_time: ts,
+            wait,
+            wait_duration: dur,
+        }
+    }
+
+    /// You should always call `PercentileInstance::update_buckets` before calling `PercentileInstance::get_value()`!
+    fn get_value(&self, ts: Time) -> Value {
+        if (self.wait && ts < self.wait_duration) || self.values.is_empty() {
+            return Value::None;
+        }
+        let mut sorted: Vec<&Value> = self.values.iter().map(|(_, v)| v).collect();
+        sorted.sort_unstable();
+        // the smallest value such that at least `percentile` percent of the values are less than or equal to it
+        let rank = (usize::from(self.percentile) * sorted.len() + 99) / 100;
+        sorted[rank.max(1) - 1].clone()
+    }
+
+    fn accept_value(&mut self, v: Value, ts: Time) {
+        self.update_buckets(ts);
+        if v != Value::None {
+            self.values.push_back((overall_bucket(ts, self.start_time, self.time_per_bucket), v));
+        }
+    }
+
+    fn update_buckets(&mut self, ts: Time) {
+        let curr = overall_bucket(ts, self.start_time, self.time_per_bucket);
+        while self.values.front().map_or(false, |(bucket, _)| bucket + SIZE <= curr) {
+            self.values.pop_front();
+        }
+    }
+}
+
+/**
+An aggregation function of sliding windows implemented by the host application, see `EvalConfig::add_aggregation`.
+
+Like the built-in aggregations, a window is divided into buckets, each summarizing the values within it by a `Value`, e.g., a tuple of a sum and a count.
+The value of the window is computed from the summaries of the buckets from the oldest to the most recent one.
+*/
+pub trait WindowAggregation: Send + Sync {
+    /// Returns the summary of a bucket without values.
+    fn empty(&self) -> Value;
+
+    /// Adds the value `v` accepted at time `ts` to the `summary` of the current bucket.
+    fn accept(&self, summary: Value, v: Value, ts: Duration) -> Value;
+
+    /// Combines the summary of older values with the summary of newer ones.
+    fn combine(&self, older: Value, newer: Value) -> Value;
+
+    /// Computes the value of the window from the combined summary of all values within it.
+    fn finish(&self, summary: Value) -> Value;
+}
+
+/// Sliding window computing an aggregation function registered by the host application.
+/// Only the summaries of buckets containing values are kept.
+pub(crate) struct CustomInstance {
+    /// The summaries together with the overall index of their bucket, oldest first.
+    summaries: VecDeque<(usize, Value)>,
+    aggregation: Arc<dyn WindowAggregation>,
+    time_per_bucket: Duration,
+    start_time: Time,
+    wait: bool,
+    wait_duration: Duration,
+}
+
+impl CustomInstance {
+    fn new(dur: Duration, wait: bool, aggregation: Arc<dyn WindowAggregation>, ts: Time) -> CustomInstance {
+        CustomInstance {
+            summaries: VecDeque::new(),
+            aggregation,
+            time_per_bucket: dur / (SIZE as u32),
+            start_time: ts,
+            wait,
+            wait_duration: dur,
+        }
+    }
+
+    /// You should always call `CustomInstance::update_buckets` before calling `CustomInstance::get_value()`!
+    fn get_value(&self, ts: Time) -> Value {
+        if self.wait && ts < self.wait_duration {
+            return Value::None;
+        }
+        let summary = self
+            .summaries
+            .iter()
+            .fold(self.aggregation.empty(), |acc, (_, summary)| self.aggregation.combine(acc, summary.clone()));
+        self.aggregation.finish(summary)
+    }
+
+    fn accept_value(&mut self, v: Value, ts: Time) {
+        self.update_buckets(ts);
+        let curr = overall_bucket(ts, self.start_time, self.time_per_bucket);
+        match self.summaries.back_mut() {
+            Some((bucket, summary)) if *bucket == curr => {
+                let old = std::mem::replace(summary, Value::None);
+                *summary = self.aggregation.accept(old, v, ts);
+            }
+            _ => {
+                let summary = self.aggregation.accept(self.aggregation.empty(), v, ts);
+                self.summaries.push_back((curr, summary));
+            }
+        }
+    }
 
-    fn quickfix_duration_div(a: Duration, b: Duration) -> f64 {
-        let a_secs = a.as_secs();
-        let a_nanos = a.subsec_nanos();
-        let b_secs = b.as_secs();
-        let b_nanos = b.subsec_nanos();
-        let a = (a_secs as f64) + f64::from(a_nanos) / f64::from(1_000_000_000);
-        let b = (b_secs as f64) + f64::from(b_nanos) / f64::from(1_000_000_000);
-        a / b
+    fn update_buckets(&mut self, ts: Time) {
+        let curr = overall_bucket(ts, self.start_time, self.time_per_bucket);
+        while self.summaries.front().map_or(false, |(bucket, _)| bucket + SIZE <= curr) {
+            self.summaries.pop_front();
+        }
     }
 }
 
+/// Returns the index of the bucket containing `ts`, counting the buckets since `start_time`.
+fn overall_bucket(ts: Time, start_time: Time, time_per_bucket: Duration) -> usize {
+    assert!(ts >= start_time, "Time does not behave monotonically!");
+    quickfix_duration_div(ts - start_time, time_per_bucket).floor() as usize
+}
+
 pub(crate) trait WindowGeneric: Debug + Clone {
     fn from_value(v: Value) -> Value;
 }
@@ -320,3 +534,31 @@ impl WindowGeneric for WindowFloat {
         Value::Float(NotNan::new(f).unwrap())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extremum_drops_outlived_and_expired_candidates() {
+        let bucket = |b: u64| Duration::from_nanos(b * 1_000_000_000 / SIZE as u64);
+        let mut min = ExtremumInstance::new(Duration::from_secs(1), false, Ordering::Less, Duration::default());
+        let mut max = ExtremumInstance::new(Duration::from_secs(1), false, Ordering::Greater, Duration::default());
+        for (b, v) in [(0, 3), (1, 1), (2, 2), (3, 5), (SIZE as u64, 4)].iter() {
+            min.accept_value(Value::Signed(*v), bucket(*b));
+            max.accept_value(Value::Signed(*v), bucket(*b));
+        }
+        assert_eq!(min.candidates.len(), 3);
+        assert_eq!(max.candidates.len(), 2);
+        min.update_buckets(bucket(SIZE as u64));
+        max.update_buckets(bucket(SIZE as u64));
+        assert_eq!(min.get_value(bucket(SIZE as u64)), Value::Signed(1));
+        assert_eq!(max.get_value(bucket(SIZE as u64)), Value::Signed(5));
+        min.update_buckets(bucket(SIZE as u64 + 3));
+        max.update_buckets(bucket(SIZE as u64 + 3));
+        assert_eq!(min.get_value(bucket(SIZE as u64 + 3)), Value::Signed(4));
+        assert_eq!(max.get_value(bucket(SIZE as u64 + 3)), Value::Signed(4));
+        min.update_buckets(bucket(2 * SIZE as u64));
+        assert_eq!(min.get_value(bucket(2 * SIZE as u64)), Value::None);
+    }
+}
@@ -259,84 +259,66 @@ impl From<(Value, Time)> for CountIV {
     }
 }
 
-//////////////////// MIN/MAX ////////////////////
-
 #[derive(Clone, Debug)]
-pub(crate) struct MaxIV<G: WindowGeneric> {
-    max: Value,
-    _marker: PhantomData<G>,
-}
+pub(crate) struct FirstIV(Value);
 
-impl<G: WindowGeneric> WindowIV for MaxIV<G> {
-    fn default(_ts: Time) -> MaxIV<G> {
-        Self::from((Value::None, Time::default()))
+impl WindowIV for FirstIV {
+    fn default(_time: Time) -> FirstIV {
+        FirstIV(Value::None)
     }
 }
 
-impl<G: WindowGeneric> Into<Value> for MaxIV<G> {
+impl Into<Value> for FirstIV {
     fn into(self) -> Value {
-        self.max
+        self.0
     }
 }
 
-impl<G: WindowGeneric> Add for MaxIV<G> {
-    type Output = MaxIV<G>;
-    fn add(self, other: MaxIV<G>) -> MaxIV<G> {
-        let max = match (self.max, other.max) {
-            (Value::None, Value::None) => Value::None,
-            (Value::None, rhs) => rhs,
-            (lhs, Value::None) => lhs,
-            (Value::Unsigned(lhs), Value::Unsigned(rhs)) => Value::Unsigned(lhs.max(rhs)),
-            (Value::Signed(lhs), Value::Signed(rhs)) => Value::Signed(lhs.max(rhs)),
-            (Value::Float(lhs), Value::Float(rhs)) => Value::Float(lhs.max(rhs)),
-            _ => unreachable!("Mixed types in sliding window aggregation."),
-        };
-        MaxIV { max, _marker: PhantomData }
+impl Add for FirstIV {
+    type Output = FirstIV;
+    fn add(self, other: FirstIV) -> FirstIV {
+        // `self` summarizes the older values
+        match self.0 {
+            Value::None => other,
+            _ => self,
+        }
     }
 }
 
-impl<G: WindowGeneric> From<(Value, Time)> for MaxIV<G> {
-    fn from(v: (Value, Time)) -> MaxIV<G> {
-        MaxIV { max: v.0, _marker: PhantomData }
+impl From<(Value, Time)> for FirstIV {
+    fn from(v: (Value, Time)) -> FirstIV {
+        FirstIV(v.0)
     }
 }
 
 #[derive(Clone, Debug)]
-pub(crate) struct MinIV<G: WindowGeneric> {
-    min: Value,
-    _marker: PhantomData<G>,
-}
+pub(crate) struct LastIV(Value);
 
-impl<G: WindowGeneric> WindowIV for MinIV<G> {
-    fn default(_ts: Time) -> MinIV<G> {
-        Self::from((Value::None, Time::default()))
+impl WindowIV for LastIV {
+    fn default(_time: Time) -> LastIV {
+        LastIV(Value::None)
     }
 }
 
-impl<G: WindowGeneric> Into<Value> for MinIV<G> {
+impl Into<Value> for LastIV {
     fn into(self) -> Value {
-        self.min
+        self.0
     }
 }
 
-impl<G: WindowGeneric> Add for MinIV<G> {
-    type Output = MinIV<G>;
-    fn add(self, other: MinIV<G>) -> MinIV<G> {
-        let min = match (self.min, other.min) {
-            (Value::None, Value::None) => Value::None,
-            (Value::None, rhs) => rhs,
-            (lhs, Value::None) => lhs,
-            (Value::Unsigned(lhs), Value::Unsigned(rhs)) => Value::Unsigned(lhs.min(rhs)),
-            (Value::Signed(lhs), Value::Signed(rhs)) => Value::Signed(lhs.min(rhs)),
-            (Value::Float(lhs), Value::Float(rhs)) => Value::Float(lhs.min(rhs)),
-            _ => unreachable!("Mixed types in sliding window aggregation."),
-        };
-        MinIV { min, _marker: PhantomData }
+impl Add for LastIV {
+    type Output = LastIV;
+    fn add(self, other: LastIV) -> LastIV {
+        // `other` summarizes the newer values
+        match other.0 {
+            Value::None => self,
+            _ => other,
+        }
     }
 }
 
-impl<G: WindowGeneric> From<(Value, Time)> for MinIV<G> {
-    fn from(v: (Value, Time)) -> MinIV<G> {
-        MinIV { min: v.0, _marker: PhantomData }
+impl From<(Value, Time)> for LastIV {
+    fn from(v: (Value, Time)) -> LastIV {
+        LastIV(v.0)
     }
 }
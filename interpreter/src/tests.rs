@@ -2,6 +2,7 @@
 
 use super::*;
 use std::io::Write;
+use std::time::Duration;
 use tempfile::NamedTempFile;
 
 fn run(spec: &str, data: &str) -> Result<Arc<OutputHandler>, Box<dyn std::error::Error>> {
@@ -101,6 +102,57 @@ trigger c > 2 "c is too large"
     assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
 }
 
+#[test]
+fn explicit_activation_conditions() {
+    let spec = r#"
+input a: Int64
+input b: Int64
+
+output both @ (a ∧ b) := a + b
+output either @ (a || b) := a.hold().defaults(to: 0) + b.hold().defaults(to: 0)
+
+trigger both > 2
+trigger either > 2
+        "#;
+
+    let data = r#"a,b,time
+#,#,1547627523.000536
+3,#,1547627523.100536
+#,3,1547627523.200536
+1,1,1547627523.300536
+#,3,1547627523.400536
+3,#,1547627523.500536
+2,2,1547627523.600536"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(1), 5);
+}
+
+#[test]
+fn methods_and_tuple_projections() {
+    let spec = r#"
+input a: Int64
+input b: Int64
+
+output pair := (a, b)
+output sum := pair.0 + pair.1
+output prev := a.offset(by: -1).defaults(to: 0)
+
+trigger sum > 5
+trigger prev > 2
+        "#;
+
+    let data = r#"a,b,time
+1,2,0
+3,4,1
+2,1,2"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+}
+
 #[test]
 fn regex_simple() {
     let spec = r#"
@@ -328,6 +380,86 @@ trigger max == 2
     assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
 }
 
+#[test]
+fn percentile_window() {
+    let spec = r#"
+input a: Int64
+
+output median @ 1Hz := a.aggregate(over: 1s, using: median).defaults(to: 0)
+output p90 @ 1Hz := a.aggregate(over: 1s, using: percentile(90)).defaults(to: 0)
+
+trigger median == 5
+trigger p90 == 9
+    "#;
+
+    let data = r#"a,time
+5,0.1
+1,0.3
+9,0.5
+3,0.7
+7,0.9
+4,1.1
+"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+}
+
+#[test]
+fn first_last_window() {
+    let spec = r#"
+input a: Int64
+
+output drift @ 1Hz := a.aggregate(over: 1s, using: last).defaults(to: 0) - a.aggregate(over: 1s, using: first).defaults(to: 0)
+
+trigger drift == 7
+    "#;
+
+    let data = r#"a,time
+0,0
+2,0.1
+5,0.5
+9,0.9
+1,1.1
+"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+}
+
+#[test]
+fn forall_exists_window() {
+    let spec = r#"
+input alt_low: Bool
+
+output always_low @ 1Hz := alt_low.aggregate(over: 1s, using: forall)
+output once_low @ 1Hz := alt_low.aggregate(over: 1s, using: exists)
+
+trigger always_low
+trigger !once_low
+    "#;
+
+    let data = r#"alt_low,time
+false,0
+true,0.1
+true,0.5
+false,1.3
+true,1.7
+false,2.2
+#,3.1
+"#;
+
+    // The execution should look as follows:
+    //
+    // time       | 0.0   | 1.0  | 2.0   | 3.0
+    // always_low | false | true | false | false
+    // once_low   | false | true | true  | false
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(1), 2);
+}
+
 #[test]
 fn bytes_at() {
     let spec = r#"
@@ -350,6 +482,118 @@ trigger x == 50 // utf-8 character value of "2"
     assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
 }
 
+#[test]
+fn bytes_decoding() {
+    let spec = r#"
+input a: Bytes
+
+output x := a.u16_be(offset: 0).defaults(to: 0)
+
+trigger x == 12594 // the utf-8 characters "12"
+trigger u16_le(a, offset: 1).defaults(to: 0) == 13106 // the utf-8 characters "23"
+        "#;
+
+    let data = r#"a,time
+12,0
+123,0.1
+1234,0.5"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 3);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 2);
+}
+
+#[test]
+fn duration_arithmetic() {
+    let spec = r#"
+input sent: Float64
+input received: Float64
+
+output latency := to_duration(received - sent)
+
+trigger latency > 200ms + 50ms
+trigger latency.millis() < 100.0
+trigger seconds(latency) == 0.5
+        "#;
+
+    let data = r#"sent,received,time
+0.0,0.05,0
+1.0,1.3,1
+2.0,2.5,2"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 2);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(2), 1);
+}
+
+#[test]
+fn checked_casts() {
+    let spec = r#"
+input a: Int64
+input f: Float64
+
+output narrow := (a as Int8).defaults(to: -1)
+
+trigger narrow == -1
+trigger (f as UInt8).defaults(to: 0) == 255
+        "#;
+
+    let data = r#"a,f,time
+100,255.5,0
+300,256.0,1
+-128,-0.5,2"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+}
+
+#[test]
+fn let_bindings() {
+    let spec = r#"
+input a: Int64
+input b: Int64
+
+output sq := let d := a - b in d * d
+
+trigger sq > 10
+trigger let s := a + b in s > 0 && s < 10
+        "#;
+
+    let data = r#"a,b,time
+5,1,0
+1,2,1
+-3,2,2"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 2);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 2);
+}
+
+#[test]
+fn constant_expressions() {
+    let spec = r#"
+constant base: Int64 := 4
+constant limit: Int64 := base * base - 1
+
+input a: Int64
+
+trigger a > limit
+trigger a < -limit
+        "#;
+
+    let data = r#"a,time
+20,0
+3,1
+-16,2
+-15,3"#;
+
+    let handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+}
+
 #[test]
 fn rtlola_stream_but_eventbased() {
     let spec = r#"
@@ -366,3 +610,507 @@ fn rtlola_stream_but_eventbased() {
     assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
     //    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
 }
+
+#[test]
+fn trigger_robustness() {
+    let spec = r#"
+input a: Float64
+input b: Bool
+trigger a > 10.0 && !b "a is too high"
+    "#;
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let trigger = ir.triggers[0].reference.out_ix();
+    let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+    cfg.robustness = true;
+    let mut monitor = Config::new_api(cfg, ir).into_monitor().expect("failed to create monitor");
+    let float = Value::new_float;
+
+    monitor.accept_event(vec![float(12.5), Value::Bool(false)], Duration::from_secs(1));
+    assert_eq!(monitor.robustness(trigger), Some(2.5));
+    monitor.accept_event(vec![float(7.0), Value::Bool(false)], Duration::from_secs(2));
+    assert_eq!(monitor.robustness(trigger), Some(-3.0));
+    monitor.accept_event(vec![float(12.5), Value::Bool(true)], Duration::from_secs(3));
+    assert_eq!(monitor.robustness(trigger), Some(std::f64::NEG_INFINITY));
+}
+
+#[test]
+fn registered_functions() {
+    use rtlola_frontend::ty::{FloatTy, TypeConstraint, ValueTy};
+    use rtlola_frontend::{FuncDecl, FunctionRegistry};
+    let spec = r#"
+import aero
+input airspeed: Float64
+output speed := knots_to_mps(airspeed)
+output fast := airspeed.above(limit: 100.0)
+    "#;
+    let t = ValueTy::Param(0, "T".to_string());
+    let float = vec![ValueTy::Constr(TypeConstraint::FloatingPoint)];
+    let mut functions = FunctionRegistry::default();
+    functions.add_function(None, FuncDecl::new("knots_to_mps", &[None], float.clone(), vec![t.clone()], t.clone()));
+    let above = FuncDecl::new("above", &[None, Some("limit")], float, vec![t.clone(), t], ValueTy::Bool);
+    functions.add_method(Some("aero"), ValueTy::Float(FloatTy::F64), above);
+    let ir = rtlola_frontend::parse_with_functions("stdin", spec, CONFIG, &functions)
+        .unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+
+    for evaluator in &[EvaluatorChoice::ClosureBased, EvaluatorChoice::Interpreted] {
+        let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+        cfg.evaluator = *evaluator;
+        cfg.add_function("knots_to_mps", |args| match args {
+            [Value::Float(knots)] => Value::new_float(knots.into_inner() * 0.514_444),
+            _ => unreachable!(),
+        });
+        cfg.add_function("above", |args| match args {
+            [Value::Float(x), Value::Float(limit)] => Value::Bool(x > limit),
+            _ => unreachable!(),
+        });
+        let mut monitor = Config::new_api(cfg, ir.clone()).into_monitor().expect("failed to create monitor");
+        let update = monitor.accept_event(vec![Value::new_float(150.0)], Duration::from_secs(1));
+        assert_eq!(update.event, vec![(0, Value::new_float(150.0 * 0.514_444)), (1, Value::Bool(true))]);
+    }
+}
+
+#[test]
+fn registered_aggregations() {
+    use rtlola_frontend::ty::{TypeConstraint, ValueTy};
+    use rtlola_frontend::{FuncDecl, FunctionRegistry};
+
+    /// The difference between the largest and the smallest value, each bucket is summarized by both.
+    struct Range;
+    impl WindowAggregation for Range {
+        fn empty(&self) -> Value {
+            Value::None
+        }
+        fn accept(&self, summary: Value, v: Value, _ts: Duration) -> Value {
+            self.combine(summary, Value::Tuple(Box::new([v.clone(), v])))
+        }
+        fn combine(&self, older: Value, newer: Value) -> Value {
+            match (older, newer) {
+                (Value::None, summary) | (summary, Value::None) => summary,
+                (Value::Tuple(older), Value::Tuple(newer)) => Value::Tuple(Box::new([
+                    older[0].clone().min(newer[0].clone()),
+                    older[1].clone().max(newer[1].clone()),
+                ])),
+                _ => unreachable!(),
+            }
+        }
+        fn finish(&self, summary: Value) -> Value {
+            match summary {
+                Value::Tuple(bounds) => bounds[1].clone() - bounds[0].clone(),
+                _ => Value::None,
+            }
+        }
+    }
+
+    let spec = r#"
+input a: Int64
+output spread @ 1Hz := a.aggregate(over: 1s, using: range).defaults(to: 0)
+trigger spread == 8
+    "#;
+    let t = ValueTy::Param(0, "T".to_string());
+    let numeric = vec![ValueTy::Constr(TypeConstraint::Numeric)];
+    let mut functions = FunctionRegistry::default();
+    functions.add_aggregation(FuncDecl::new("range", &[None], numeric, vec![t.clone()], ValueTy::Option(t.into())));
+    assert!(rtlola_frontend::parse("stdin", spec, CONFIG).is_err());
+    let ir = rtlola_frontend::parse_with_functions("stdin", spec, CONFIG, &functions)
+        .unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+
+    let data = "a,time\n5,0.1\n1,0.3\n9,0.5\n3,1.1\n";
+    let mut file = NamedTempFile::new().expect("failed to create temporary file");
+    write!(file, "{}", data).expect("writing tempfile failed");
+    let mut cfg = EvalConfig::debug();
+    cfg.source =
+        EventSourceConfig::CSV { src: CSVInputSource::file(file.path().to_str().unwrap().to_string(), None, None) };
+    cfg.verbosity = Verbosity::Silent;
+    cfg.add_aggregation("range", Range);
+    let output_handler = Config { cfg, ir }.run().unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+}
+
+#[test]
+fn clamp_and_abs() {
+    let spec = r#"
+input x: Int64
+input u: UInt64
+input f: Float64
+output clamped_int := clamp(x, lo: -5, hi: 5)
+output clamped_uint := clamp(u, lo: 2, hi: 10)
+output clamped_float := clamp(f, lo: 0.0, hi: 1.0)
+output empty_range := clamp(x, lo: 5, hi: -5)
+output abs_int := abs(x)
+output abs_uint := abs(u)
+output abs_float := abs(f)
+    "#;
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let trace = [(-7, 0, -0.5), (3, 5, 0.25), (9, 12, 1.5)];
+    // An empty range, i.e., `lo > hi`, yields the upper bound.
+    let expected = [
+        [Value::Signed(-5), Value::Unsigned(2), Value::new_float(0.0), Value::Signed(-5)],
+        [Value::Signed(3), Value::Unsigned(5), Value::new_float(0.25), Value::Signed(-5)],
+        [Value::Signed(5), Value::Unsigned(10), Value::new_float(1.0), Value::Signed(-5)],
+    ];
+    let expected_abs = [
+        [Value::Signed(7), Value::Unsigned(0), Value::new_float(0.5)],
+        [Value::Signed(3), Value::Unsigned(5), Value::new_float(0.25)],
+        [Value::Signed(9), Value::Unsigned(12), Value::new_float(1.5)],
+    ];
+    for evaluator in &[EvaluatorChoice::ClosureBased, EvaluatorChoice::Interpreted] {
+        let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+        cfg.evaluator = *evaluator;
+        let mut monitor = Config::new_api(cfg, ir.clone()).into_monitor().expect("failed to create monitor");
+        for (step, (x, u, f)) in trace.iter().enumerate() {
+            let event = vec![Value::Signed(*x), Value::Unsigned(*u), Value::new_float(*f)];
+            let update = monitor.accept_event(event, Duration::from_secs(step as u64));
+            let value = |name: &str| {
+                let ix = ir.outputs.iter().position(|o| o.name == name).unwrap();
+                update.event.iter().find(|(out, _)| *out == ix).map(|(_, v)| v.clone()).unwrap()
+            };
+            let clamped = [value("clamped_int"), value("clamped_uint"), value("clamped_float"), value("empty_range")];
+            assert_eq!(clamped, expected[step], "{:?} at step {}", evaluator, step);
+            let abs = [value("abs_int"), value("abs_uint"), value("abs_float")];
+            assert_eq!(abs, expected_abs[step], "{:?} at step {}", evaluator, step);
+        }
+    }
+}
+
+#[test]
+fn filters() {
+    let spec = r#"
+input a: Float64
+output smooth := lowpass(a, alpha: 0.5)
+output limited := rate_limit(a, max_delta: 1.0)
+    "#;
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    // Both filters start with the first value, the low-pass filter halves each change and the limit is 1 per step.
+    let trace = [0.0, 4.0, 4.0, 0.0, 0.5];
+    let expected = [(0.0, 0.0), (2.0, 1.0), (3.0, 2.0), (1.5, 1.0), (1.0, 0.5)];
+    for evaluator in &[EvaluatorChoice::ClosureBased, EvaluatorChoice::Interpreted] {
+        let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+        cfg.evaluator = *evaluator;
+        let mut monitor = Config::new_api(cfg, ir.clone()).into_monitor().expect("failed to create monitor");
+        for (step, (a, (smooth, limited))) in trace.iter().zip(&expected).enumerate() {
+            let update = monitor.accept_event(vec![Value::new_float(*a)], Duration::from_secs(step as u64));
+            // the hidden streams of the filters are fresh as well
+            let value = |name: &str| {
+                let ix = ir.outputs.iter().position(|o| o.name == name).unwrap();
+                update.event.iter().find(|(out, _)| *out == ix).map(|(_, v)| v.clone())
+            };
+            assert_eq!(value("smooth"), Some(Value::new_float(*smooth)), "{:?} at step {}", evaluator, step);
+            assert_eq!(value("limited"), Some(Value::new_float(*limited)), "{:?} at step {}", evaluator, step);
+        }
+    }
+}
+
+#[test]
+fn trigger_hysteresis() {
+    let spec = "input temp: Float64\ntrigger temp > 80.0 for 2s until temp < 70.0 \"overheated\"";
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    // The trigger fires once the temperature is above 80 for 2s and until it is below 70.
+    let trace = [85.0, 90.0, 85.0, 75.0, 65.0, 85.0];
+    let expected: [u64; 6] = [0, 0, 1, 2, 2, 2];
+    for evaluator in &[EvaluatorChoice::ClosureBased, EvaluatorChoice::Interpreted] {
+        let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+        cfg.statistics = Statistics::Debug;
+        cfg.evaluator = *evaluator;
+        let mut monitor = Config::new_api(cfg, ir.clone()).into_monitor().expect("failed to create monitor");
+        for (step, (temp, fired)) in trace.iter().zip(&expected).enumerate() {
+            monitor.accept_event(vec![Value::new_float(*temp)], Duration::from_secs(step as u64));
+            assert_eq!(monitor.output_handler.statistics.as_ref().unwrap().get_num_trigger(0), *fired);
+        }
+    }
+}
+
+#[test]
+fn trigger_hysteresis_trace() {
+    let spec = "input temp: Float64\ntrigger temp > 80.0 for 2s until temp < 70.0 \"overheated\"";
+    // The drop to 75 at 1.0 restarts the hold, so the trigger first fires at 3.5, 2s after 1.5.
+    // It keeps firing between the thresholds until 69 releases it at 5.0, then the hold starts over at 5.5.
+    let data = r#"temp,time
+85.0,0.0
+75.0,1.0
+85.0,1.5
+90.0,3.0
+85.0,3.5
+75.0,4.0
+72.0,4.5
+69.0,5.0
+85.0,5.5
+85.0,7.0
+85.0,7.5
+"#;
+    // fired at 3.5, 4.0, 4.5, and 7.5
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 4);
+}
+
+#[test]
+fn trigger_escalation() {
+    let spec = "input temp: Float64\ntrigger temp > 80.0 escalate after 3 within 10s \"{count} violations\"";
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    // The violations at 0s, 4s, and 10s escalate, the one at 12s is the third within 10s again.
+    let trace = [(0, 85.0), (4, 90.0), (5, 70.0), (10, 85.0), (12, 81.0), (30, 95.0)];
+    let expected: [u64; 6] = [0, 0, 0, 1, 2, 2];
+    let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+    cfg.statistics = Statistics::Debug;
+    let mut monitor = Config::new_api(cfg, ir).into_monitor().expect("failed to create monitor");
+    for ((secs, temp), notified) in trace.iter().zip(&expected) {
+        monitor.accept_event(vec![Value::new_float(*temp)], Duration::from_secs(*secs));
+        assert_eq!(monitor.output_handler.statistics.as_ref().unwrap().get_num_trigger(0), *notified);
+    }
+}
+
+#[test]
+fn assumptions() {
+    let spec = r#"
+input alt: Float64
+input speed: Float64
+assume alt >= 0.0 "altimeter works"
+trigger alt < 100.0 "too low"
+trigger speed > 50.0 "too fast"
+    "#;
+    // The assumption is violated at time 2, the remaining verdicts about `alt` are vacuous.
+    let data = "alt,speed,time\n150.0,10.0,0.0\n50.0,60.0,1.0\n-5.0,60.0,2.0\n20.0,10.0,3.0\n";
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    let statistics = output_handler.statistics.as_ref().unwrap();
+    assert_eq!(statistics.get_num_trigger(0), 1);
+    assert_eq!(statistics.get_num_trigger(1), 3);
+    assert_eq!(statistics.get_num_trigger(2), 2);
+}
+
+#[test]
+fn derived_inputs() {
+    let spec = r#"
+input raw_alt: Int32
+input alt: Float64 := to_float(raw_alt) / 100.0
+trigger alt < 10.0 "too low"
+    "#;
+    let data = "raw_alt,time\n2000,0.0\n900,1.0\n1000,2.0\n";
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+}
+
+#[test]
+fn configurable_constants() {
+    let spec = "configurable constant max_speed: Float64 := 100.0\ninput speed: Float64\ntrigger speed > max_speed \"too fast\"";
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    for (constants, expected) in &[(vec![], 1), (vec![("max_speed", "150.0")], 0), (vec![("max_speed", "50")], 2)] {
+        let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+        cfg.statistics = Statistics::Debug;
+        constants.iter().for_each(|(name, value)| cfg.set_constant(name, value));
+        let mut monitor = Config::new_api(cfg, ir.clone()).into_monitor().expect("failed to create monitor");
+        for (ix, speed) in [60.0, 120.0].iter().enumerate() {
+            monitor.accept_event(vec![Value::new_float(*speed)], Duration::from_secs(ix as u64));
+        }
+        assert_eq!(monitor.output_handler.statistics.as_ref().unwrap().get_num_trigger(0), *expected);
+    }
+    let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+    cfg.set_constant("min_speed", "10.0");
+    assert!(Config::new_api(cfg, ir).into_monitor().is_err());
+}
+
+#[test]
+fn merged_specifications() {
+    let cruise = "input speed: Float64\ninput target: Float64\ntrigger speed - target > 5.0 \"too fast\"";
+    let brakes =
+        "input pressure: Float64\ninput speed: Float64\ntrigger speed > 10.0 && pressure < 1.0 \"no pressure\"";
+    let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+    cfg.statistics = Statistics::Debug;
+    let config = Config::from_specs(&[("cruise", cruise), ("brakes", brakes)], cfg).expect("failed to merge specs");
+    assert_eq!(config.ir.inputs.len(), 3);
+    let mut monitor = config.into_monitor().expect("failed to create monitor");
+    let event =
+        |speed, target, pressure| vec![Value::new_float(speed), Value::new_float(target), Value::new_float(pressure)];
+    monitor.accept_event(event(20.0, 10.0, 2.0), Duration::from_secs(0));
+    monitor.accept_event(event(12.0, 10.0, 0.5), Duration::from_secs(1));
+    let statistics = monitor.output_handler.statistics.as_ref().unwrap();
+    assert_eq!((statistics.get_num_trigger(0), statistics.get_num_trigger(1)), (1, 1));
+    assert_eq!(monitor.trigger_specification(1), Some("brakes"));
+    assert_eq!(monitor.name_for_output(1), "brakes::trigger_no_pressure");
+    let conflicting = Config::from_specs(&[("a", "input x: Bool"), ("b", "input x: Int64")], EvalConfig::default());
+    assert!(conflicting.is_err());
+}
+
+#[test]
+fn api_periodic_streams() {
+    let spec = "input a: Int64\noutput count @ 1Hz := count.offset(by: -1).defaults(to: 0) + 1";
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let mut monitor = Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir)
+        .into_monitor()
+        .expect("failed to create monitor");
+    let counts = |timed: Vec<(Duration, StateSlice)>| -> Vec<(u64, Vec<Value>)> {
+        timed.into_iter().map(|(ts, slice)| (ts.as_secs(), slice.into_iter().map(|(_, v)| v).collect())).collect()
+    };
+    let secs = Duration::from_secs;
+    assert_eq!(
+        counts(monitor.accept_time(Duration::from_millis(2500))),
+        vec![(0, vec![Value::Signed(1)]), (1, vec![Value::Signed(2)]), (2, vec![Value::Signed(3)])]
+    );
+    // later calls continue after the deadlines evaluated before
+    assert_eq!(counts(monitor.accept_time(secs(4))), vec![(3, vec![Value::Signed(4)])]);
+    let update = monitor.accept_event(vec![Value::Signed(1)], secs(5));
+    assert_eq!(counts(update.timed), vec![(4, vec![Value::Signed(5)])]);
+    assert!(monitor.accept_time(secs(5)).is_empty());
+}
+
+#[test]
+fn explanations() {
+    let spec = "input a: Int64\noutput b := a * 2\ntrigger b > 5 && a < 10 \"too high\"";
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let mut cfg = EvalConfig::api(TimeRepresentation::Hide);
+    cfg.explain = true;
+    let mut monitor = Config::new_api(cfg, ir).into_monitor().expect("failed to create monitor");
+    monitor.accept_event(vec![Value::Signed(2)], Duration::from_secs(0));
+    assert!(monitor.explanation(1).is_none());
+    monitor.accept_event(vec![Value::Signed(4)], Duration::from_secs(1));
+    let explanation = monitor.explanation(1).expect("the trigger fired");
+    assert_eq!(explanation.time, Duration::from_secs(1));
+    let subexpressions: Vec<(&str, &Value)> = explanation.subexpressions.iter().map(|(e, v)| (e.as_str(), v)).collect();
+    assert_eq!(
+        subexpressions,
+        vec![("b > 5 && a < 10", &Value::Bool(true)), ("b > 5", &Value::Bool(true)), ("a < 10", &Value::Bool(true))]
+    );
+    let streams: Vec<(&str, &Value)> = explanation.streams.iter().map(|(s, v)| (s.as_str(), v)).collect();
+    assert_eq!(streams, vec![("a", &Value::Signed(4)), ("b", &Value::Signed(8))]);
+}
+
+#[test]
+fn future_offsets() {
+    let spec = r#"
+input a: Int64
+
+output rising := a[+1].defaults(to: 0) > a
+output peak := rising[-1].defaults(to: false) && !rising
+
+trigger peak
+    "#;
+
+    let data = r#"a,time
+1,0.1
+3,0.2
+2,0.3
+5,0.4
+4,0.5
+"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 2);
+}
+
+#[test]
+fn instance_templates() {
+    let spec = r#"
+input id: Int64
+
+output cnt(p: Int64) @id { invoke id if id > 0 extend id == p terminate id == -p }
+    := self.offset(by: -1).defaults(to: 0) + 1
+
+trigger id > 0 && cnt(id) > 2 "id occurred more than twice"
+    "#;
+
+    let data = r#"id,time
+1,0.1
+2,0.2
+1,0.3
+1,0.4
+-1,0.5
+1,0.6
+2,0.7
+2,0.8
+"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 2);
+}
+
+#[test]
+fn api_instances() {
+    let spec = r#"
+input id: Int64
+
+output cnt(p: Int64) @id { invoke id if id > 0 extend id == p } := self.offset(by: -1).defaults(to: 0) + 1
+output seen @id := id
+trigger id > 0 && cnt(id) > 1 "id occurred again"
+    "#;
+    let ir = rtlola_frontend::parse("stdin", spec, CONFIG).unwrap_or_else(|e| panic!("spec is invalid: {}", e));
+    let mut monitor = Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir)
+        .into_monitor()
+        .expect("failed to create monitor");
+    let ms = Duration::from_millis;
+    let values = |slice: StateSlice| -> Vec<Value> { slice.into_iter().map(|(_, v)| v).collect() };
+    // the instances are reported by the handler, not as fresh values of the stream
+    let update = monitor.accept_event(vec![Value::Signed(1)], ms(500));
+    assert_eq!(values(update.event), vec![Value::Signed(1), Value::Bool(false)]);
+    monitor.accept_event(vec![Value::Signed(2)], ms(600));
+    let update = monitor.accept_event(vec![Value::Signed(1)], ms(700));
+    assert_eq!(values(update.event), vec![Value::Signed(1), Value::Bool(true)]);
+}
+
+#[test]
+fn optional_lookups() {
+    let spec = r#"
+input a: Int64
+
+output first := !a.offset(by: -1).is_some()
+output grew := a > a.offset(by: -1).unwrap_or(0)
+
+trigger first "first value"
+trigger grew "value grew"
+    "#;
+
+    let data = r#"a,time
+3,0.1
+1,0.2
+2,0.3
+5,0.4
+"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(1), 3);
+}
+
+#[test]
+fn arrays() {
+    let spec = r#"
+input q: [Float64; 2]
+
+output squared := q * q
+output norm := squared.0 + squared.1
+
+trigger norm > 1.0 "outside of the unit circle"
+    "#;
+
+    let data = r#"q,time
+"[0.5, 0.5]",0.1
+"[1.0, 0.5]",0.2
+"[0.0, 2.0]",0.3
+"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 2);
+}
+
+#[test]
+fn enums() {
+    let spec = r#"
+enum Mode { Init, Armed, Flying }
+input mode: Mode
+
+output armed := mode == Mode::Armed
+output took_off := mode == Mode::Flying && mode.offset(by: -1).defaults(to: Mode::Init) != Mode::Armed
+
+trigger armed "armed"
+trigger took_off "took off without arming"
+    "#;
+
+    let data = r#"mode,time
+Init,0.1
+Armed,0.2
+Flying,0.3
+Init,0.4
+Flying,0.5
+"#;
+
+    let output_handler = run(spec, data).unwrap_or_else(|e| panic!("E2E test failed: {}", e));
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(0), 1);
+    assert_eq!(output_handler.statistics.as_ref().unwrap().get_num_trigger(1), 1);
+}
@@ -0,0 +1,78 @@
+package org.rtlola;
+
+/**
+ * An in-process monitor for an RTLola specification.
+ *
+ * A monitor is not thread-safe; all calls have to be confined to a single thread.
+ * Close the monitor to free its native resources.
+ */
+public final class Monitor implements AutoCloseable {
+    static {
+        System.loadLibrary("rtlola_jni");
+    }
+
+    private long handle;
+
+    /**
+     * Parses the specification and creates a monitor for it.
+     *
+     * @throws IllegalArgumentException if the specification is invalid
+     */
+    public Monitor(String specification) {
+        this.handle = create(specification);
+    }
+
+    /** Returns the names of the input streams in the order expected by {@link #accept}. */
+    public String[] inputs() {
+        return inputs(checkedHandle());
+    }
+
+    /**
+     * Feeds an event into the monitor.
+     *
+     * The values are given in the order of {@link #inputs()}, null marks inputs without a new value.
+     * Values of Bool inputs are Booleans, numeric inputs accept any Number, String inputs Strings, and Bytes inputs
+     * byte arrays.
+     * Periodic streams due before the event are evaluated first; all verdicts are passed to the listener.
+     *
+     * @param time the timestamp of the event in seconds, has to be monotonically increasing
+     * @throws IllegalArgumentException if a value does not match the type of its input or time went backwards
+     */
+    public void accept(Object[] values, double time, VerdictListener listener) {
+        accept(checkedHandle(), values, time, listener);
+    }
+
+    /**
+     * Advances time and evaluates all periodic streams due until then.
+     *
+     * @throws IllegalArgumentException if the time is before the last event
+     */
+    public void acceptTime(double time, VerdictListener listener) {
+        acceptTime(checkedHandle(), time, listener);
+    }
+
+    @Override
+    public void close() {
+        if (handle != 0) {
+            destroy(handle);
+            handle = 0;
+        }
+    }
+
+    private long checkedHandle() {
+        if (handle == 0) {
+            throw new IllegalStateException("the monitor is closed");
+        }
+        return handle;
+    }
+
+    private static native long create(String specification);
+
+    private static native String[] inputs(long handle);
+
+    private static native void accept(long handle, Object[] values, double time, VerdictListener listener);
+
+    private static native void acceptTime(long handle, double time, VerdictListener listener);
+
+    private static native void destroy(long handle);
+}
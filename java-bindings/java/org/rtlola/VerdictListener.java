@@ -0,0 +1,18 @@
+package org.rtlola;
+
+/**
+ * Receives the verdicts computed by a {@link Monitor}.
+ *
+ * Times are given in seconds relative to the start of the monitor.
+ */
+public interface VerdictListener {
+    /** Called for each trigger firing with the message of the trigger. */
+    void onTrigger(double time, String message);
+
+    /**
+     * Called for each new value of an output stream.
+     *
+     * Values are boxed as Boolean, Long, Double, String, byte[], or Object[] for tuples.
+     */
+    default void onOutput(double time, String stream, Object value) {}
+}
@@ -0,0 +1,305 @@
+//! Java bindings for RTLola.
+//!
+//! The native methods of `org.rtlola.Monitor` (see `java/`) are implemented here, e.g.
+//!
+//! ```java
+//! try (Monitor monitor = new Monitor("input alt: Float64\noutput too_low := alt < 200.0\ntrigger too_low \"low\"")) {
+//!     monitor.accept(new Object[] { 10.2 }, 1.5, (time, message) -> System.out.println(message));
+//! }
+//! ```
+//!
+//! The Java object stores a pointer to the native monitor, which is freed by `Monitor.close`.
+
+use jni::objects::{JByteArray, JClass, JObject, JObjectArray, JString, JValue};
+use jni::sys::{jdouble, jlong, jobjectArray};
+use jni::JNIEnv;
+use ordered_float::NotNan;
+use rtlola_frontend::ir::Type;
+use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_interpreter::{Config, EvalConfig, Monitor, StateSlice, TimeRepresentation, Value};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::time::Duration;
+
+const CONFIG: FrontendConfig =
+    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+
+struct Handle {
+    monitor: Monitor,
+    inputs: Vec<(String, Type)>,
+    /// The time of the last event or `acceptTime` call.
+    last: Duration,
+}
+
+impl Handle {
+    /// Checks that `time` is a valid timestamp no earlier than the last one.
+    fn time(&mut self, time: jdouble) -> Result<Duration> {
+        let ts = to_time(time)?;
+        if ts < self.last {
+            let last = self.last.as_secs_f64();
+            return Err(Error::Argument(format!("time {}s is before the last event at {}s", time, last)));
+        }
+        self.last = ts;
+        Ok(ts)
+    }
+}
+
+enum Error {
+    /// A JNI call failed, possibly leaving a pending Java exception.
+    Jni(jni::errors::Error),
+    /// The arguments passed from Java are invalid.
+    Argument(String),
+}
+
+impl From<jni::errors::Error> for Error {
+    fn from(e: jni::errors::Error) -> Error {
+        Error::Jni(e)
+    }
+}
+
+type Result<T> = std::result::Result<T, Error>;
+
+/// Runs `f` and converts errors and panics into Java exceptions, returning the default value in that case.
+fn guard<'local, T: Default>(env: &mut JNIEnv<'local>, f: impl FnOnce(&mut JNIEnv<'local>) -> Result<T>) -> T {
+    let (class, message) = match catch_unwind(AssertUnwindSafe(|| f(env))) {
+        Ok(Ok(res)) => return res,
+        Ok(Err(Error::Argument(msg))) => ("java/lang/IllegalArgumentException", msg),
+        Ok(Err(Error::Jni(e))) => ("java/lang/RuntimeException", e.to_string()),
+        Err(_) => ("java/lang/RuntimeException", String::from("the monitor panicked")),
+    };
+    if !env.exception_check().unwrap_or(true) {
+        let _ = env.throw_new(class, message);
+    }
+    T::default()
+}
+
+fn from_handle<'a>(handle: jlong) -> &'a mut Handle {
+    // The Java class guarantees that the handle was returned by `create` and has not been destroyed.
+    unsafe { &mut *(handle as *mut Handle) }
+}
+
+fn to_time(time: jdouble) -> Result<Duration> {
+    if time.is_finite() && time >= 0.0 {
+        Ok(Duration::from_secs_f64(time))
+    } else {
+        Err(Error::Argument(format!("invalid timestamp {}", time)))
+    }
+}
+
+fn expect_instance(env: &mut JNIEnv, obj: &JObject, class: &str, ty: &Type) -> Result<()> {
+    if env.is_instance_of(obj, class)? {
+        Ok(())
+    } else {
+        Err(Error::Argument(format!("expected an instance of {} for a value of type {}", class, ty)))
+    }
+}
+
+fn to_value(env: &mut JNIEnv, obj: JObject, ty: &Type) -> Result<Value> {
+    if obj.is_null() {
+        return Ok(Value::None);
+    }
+    Ok(match ty {
+        Type::Bool => {
+            expect_instance(env, &obj, "java/lang/Boolean", ty)?;
+            Value::Bool(env.call_method(&obj, "booleanValue", "()Z", &[])?.z()?)
+        }
+        Type::Int(_) => {
+            expect_instance(env, &obj, "java/lang/Number", ty)?;
+            Value::Signed(env.call_method(&obj, "longValue", "()J", &[])?.j()?)
+        }
+        Type::UInt(_) => {
+            expect_instance(env, &obj, "java/lang/Number", ty)?;
+            let value = env.call_method(&obj, "longValue", "()J", &[])?.j()?;
+            if value < 0 {
+                return Err(Error::Argument(format!("expected a non-negative value for type {}", ty)));
+            }
+            Value::Unsigned(value as u64)
+        }
+        Type::Float(_) => {
+            expect_instance(env, &obj, "java/lang/Number", ty)?;
+            let value = env.call_method(&obj, "doubleValue", "()D", &[])?.d()?;
+            Value::Float(NotNan::new(value).map_err(|_| Error::Argument(String::from("NaN is not a valid value")))?)
+        }
+        Type::String => {
+            expect_instance(env, &obj, "java/lang/String", ty)?;
+            let s: String = env.get_string(&JString::from(obj))?.into();
+            Value::Str(s.into_boxed_str())
+        }
+        Type::Bytes => {
+            expect_instance(env, &obj, "[B", ty)?;
+            Value::Bytes(env.convert_byte_array(JByteArray::from(obj))?.into_boxed_slice())
+        }
+        Type::Tuple(tys) => {
+            expect_instance(env, &obj, "[Ljava/lang/Object;", ty)?;
+            let array = JObjectArray::from(obj);
+            if env.get_array_length(&array)? as usize != tys.len() {
+                return Err(Error::Argument(format!("expected {} elements for a value of type {}", tys.len(), ty)));
+            }
+            let mut values = Vec::with_capacity(tys.len());
+            for (ix, ty) in tys.iter().enumerate() {
+                let element = env.get_object_array_element(&array, ix as i32)?;
+                values.push(to_value(env, element, ty)?);
+            }
+            Value::Tuple(values.into_boxed_slice())
+        }
+        Type::Array(elem_ty, size) => {
+            expect_instance(env, &obj, "[Ljava/lang/Object;", ty)?;
+            let array = JObjectArray::from(obj);
+            if env.get_array_length(&array)? as usize != *size {
+                return Err(Error::Argument(format!("expected {} elements for a value of type {}", size, ty)));
+            }
+            let mut values = Vec::with_capacity(*size);
+            for ix in 0..*size {
+                let element = env.get_object_array_element(&array, ix as i32)?;
+                values.push(to_value(env, element, elem_ty)?);
+            }
+            Value::Tuple(values.into_boxed_slice())
+        }
+        _ => return Err(Error::Argument(format!("inputs of type {} are not supported", ty))),
+    })
+}
+
+fn from_value<'local>(env: &mut JNIEnv<'local>, value: &Value) -> Result<JObject<'local>> {
+    let boxed = |env: &mut JNIEnv<'local>, class: &str, sig: &str, arg: JValue| {
+        env.call_static_method(class, "valueOf", sig, &[arg]).and_then(|v| v.l())
+    };
+    Ok(match value {
+        Value::None => JObject::null(),
+        Value::Bool(b) => boxed(env, "java/lang/Boolean", "(Z)Ljava/lang/Boolean;", JValue::Bool(u8::from(*b)))?,
+        Value::Unsigned(u) => boxed(env, "java/lang/Long", "(J)Ljava/lang/Long;", JValue::Long(*u as i64))?,
+        Value::Signed(i) => boxed(env, "java/lang/Long", "(J)Ljava/lang/Long;", JValue::Long(*i))?,
+        Value::Float(f) => boxed(env, "java/lang/Double", "(D)Ljava/lang/Double;", JValue::Double(f.into_inner()))?,
+        Value::Str(s) => env.new_string(s)?.into(),
+        Value::Bytes(b) => env.byte_array_from_slice(b)?.into(),
+        Value::Tuple(values) => {
+            let array = env.new_object_array(values.len() as i32, "java/lang/Object", JObject::null())?;
+            for (ix, v) in values.iter().enumerate() {
+                let element = from_value(env, v)?;
+                env.set_object_array_element(&array, ix as i32, &element)?;
+                env.delete_local_ref(element)?;
+            }
+            array.into()
+        }
+    })
+}
+
+/// Passes the trigger firings and output values of the slice to the listener.
+fn report(env: &mut JNIEnv, monitor: &Monitor, ts: Duration, slice: StateSlice, listener: &JObject) -> Result<()> {
+    let time = ts.as_secs_f64();
+    for (out, value) in slice {
+        match monitor.trigger_message(out) {
+            Some(message) => {
+                if let Value::Bool(true) = value {
+                    let message = env.new_string(message)?;
+                    let args = [JValue::Double(time), JValue::Object(&message)];
+                    env.call_method(listener, "onTrigger", "(DLjava/lang/String;)V", &args)?;
+                    env.delete_local_ref(message)?;
+                }
+            }
+            None => {
+                if let Value::None = value {
+                    continue;
+                }
+                let name = env.new_string(monitor.name_for_output(out))?;
+                let value = from_value(env, &value)?;
+                let sig = "(DLjava/lang/String;Ljava/lang/Object;)V";
+                let args = [JValue::Double(time), JValue::Object(&name), JValue::Object(&value)];
+                env.call_method(listener, "onOutput", sig, &args)?;
+                env.delete_local_ref(name)?;
+                env.delete_local_ref(value)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_rtlola_Monitor_create<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    spec: JString<'local>,
+) -> jlong {
+    guard(&mut env, |env| {
+        let spec: String = env.get_string(&spec)?.into();
+        let ir = rtlola_frontend::parse("java", &spec, CONFIG).map_err(Error::Argument)?;
+        let inputs = ir.inputs.iter().map(|i| (i.name.clone(), i.ty.clone())).collect();
+        let monitor = Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir)
+            .into_monitor()
+            .map_err(|e| Error::Argument(e.to_string()))?;
+        Ok(Box::into_raw(Box::new(Handle { monitor, inputs, last: Duration::default() })) as jlong)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_rtlola_Monitor_inputs<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle_ptr: jlong,
+) -> jobjectArray {
+    let handle = from_handle(handle_ptr);
+    let array = guard(&mut env, |env| {
+        let array = env.new_object_array(handle.inputs.len() as i32, "java/lang/String", JObject::null())?;
+        for (ix, (name, _)) in handle.inputs.iter().enumerate() {
+            let name = env.new_string(name)?;
+            env.set_object_array_element(&array, ix as i32, &name)?;
+            env.delete_local_ref(name)?;
+        }
+        Ok(Some(array.into_raw()))
+    });
+    array.unwrap_or(std::ptr::null_mut())
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_rtlola_Monitor_accept<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle_ptr: jlong,
+    values: JObjectArray<'local>,
+    time: jdouble,
+    listener: JObject<'local>,
+) {
+    let handle = from_handle(handle_ptr);
+    guard(&mut env, |env| {
+        if env.get_array_length(&values)? as usize != handle.inputs.len() {
+            return Err(Error::Argument(format!("expected {} values, one for each input", handle.inputs.len())));
+        }
+        let mut event = Vec::with_capacity(handle.inputs.len());
+        for (ix, (_, ty)) in handle.inputs.iter().enumerate() {
+            let obj = env.get_object_array_element(&values, ix as i32)?;
+            event.push(to_value(env, obj, ty)?);
+        }
+        let ts = handle.time(time)?;
+        let update = handle.monitor.accept_event(event, ts);
+        for (ts, slice) in update.timed {
+            report(env, &handle.monitor, ts, slice, &listener)?;
+        }
+        report(env, &handle.monitor, ts, update.event, &listener)
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_rtlola_Monitor_acceptTime<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle_ptr: jlong,
+    time: jdouble,
+    listener: JObject<'local>,
+) {
+    let handle = from_handle(handle_ptr);
+    guard(&mut env, |env| {
+        let ts = handle.time(time)?;
+        for (ts, slice) in handle.monitor.accept_time(ts) {
+            report(env, &handle.monitor, ts, slice, &listener)?;
+        }
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub extern "system" fn Java_org_rtlola_Monitor_destroy<'local>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    handle_ptr: jlong,
+) {
+    // The Java class ensures the handle is destroyed at most once.
+    drop(unsafe { Box::from_raw(handle_ptr as *mut Handle) });
+}
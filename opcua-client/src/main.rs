@@ -0,0 +1,156 @@
+//! An OPC-UA input source for the RTLola interpreter.
+//!
+//! The client subscribes to the configured node ids, converts the variant values to the declared input types, and
+//! feeds them into a monitor.  Time is taken from the source timestamps of the server (falling back to the server
+//! timestamps), so the verdicts are accurate with respect to the monitored system rather than the network.
+
+use clap::{App, Arg};
+use opcua::client::prelude::*;
+use ordered_float::NotNan;
+use rtlola_frontend::ir::Type;
+use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_interpreter::{Config, EvalConfig, TimeRepresentation, Value};
+use std::sync::mpsc;
+use std::time::Duration;
+
+const CONFIG: FrontendConfig =
+    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+
+/// A new value of an input stream together with its timestamp in nanoseconds since the Unix epoch.
+type Sample = (usize, Variant, i64);
+
+fn to_value(variant: &Variant, ty: &Type) -> Option<Value> {
+    let float = |f: f64| NotNan::new(f).ok().map(Value::Float);
+    Some(match (variant, ty) {
+        (Variant::Empty, _) => Value::None,
+        (Variant::Boolean(b), Type::Bool) => Value::Bool(*b),
+        (Variant::SByte(i), Type::Int(_)) => Value::Signed(i64::from(*i)),
+        (Variant::Int16(i), Type::Int(_)) => Value::Signed(i64::from(*i)),
+        (Variant::Int32(i), Type::Int(_)) => Value::Signed(i64::from(*i)),
+        (Variant::Int64(i), Type::Int(_)) => Value::Signed(*i),
+        (Variant::Byte(u), Type::UInt(_)) => Value::Unsigned(u64::from(*u)),
+        (Variant::UInt16(u), Type::UInt(_)) => Value::Unsigned(u64::from(*u)),
+        (Variant::UInt32(u), Type::UInt(_)) => Value::Unsigned(u64::from(*u)),
+        (Variant::UInt64(u), Type::UInt(_)) => Value::Unsigned(*u),
+        (Variant::Byte(u), Type::Int(_)) => Value::Signed(i64::from(*u)),
+        (Variant::UInt16(u), Type::Int(_)) => Value::Signed(i64::from(*u)),
+        (Variant::UInt32(u), Type::Int(_)) => Value::Signed(i64::from(*u)),
+        (Variant::Float(f), Type::Float(_)) => float(f64::from(*f))?,
+        (Variant::Double(f), Type::Float(_)) => float(*f)?,
+        (Variant::SByte(i), Type::Float(_)) => float(f64::from(*i))?,
+        (Variant::Int16(i), Type::Float(_)) => float(f64::from(*i))?,
+        (Variant::Int32(i), Type::Float(_)) => float(f64::from(*i))?,
+        (Variant::Byte(u), Type::Float(_)) => float(f64::from(*u))?,
+        (Variant::UInt16(u), Type::Float(_)) => float(f64::from(*u))?,
+        (Variant::UInt32(u), Type::Float(_)) => float(f64::from(*u))?,
+        (Variant::String(s), Type::String) => Value::Str(s.as_ref().into()),
+        (Variant::ByteString(b), Type::Bytes) => Value::Bytes(b.as_ref().into()),
+        _ => return None,
+    })
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let matches = App::new("RTLola OPC-UA Client")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Monitors OPC-UA telemetry with the RTLola interpreter.")
+        .arg(Arg::with_name("SPEC").help("Sets the specification file to use").required(true))
+        .arg(
+            Arg::with_name("ENDPOINT")
+                .help("The endpoint of the server, e.g., opc.tcp://localhost:4855")
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("NODE")
+                .help("Maps an input stream to a node id, e.g., --node temp=ns=2;s=Temperature")
+                .long("node")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("INTERVAL")
+                .help("The publishing interval of the subscription in milliseconds")
+                .long("interval")
+                .takes_value(true)
+                .default_value("100"),
+        )
+        .get_matches();
+
+    let path = matches.value_of("SPEC").unwrap();
+    let ir = rtlola_frontend::parse(path, &std::fs::read_to_string(path)?, CONFIG)?;
+    let inputs: Vec<(String, Type)> = ir.inputs.iter().map(|i| (i.name.clone(), i.ty.clone())).collect();
+
+    let mut nodes: Vec<(NodeId, usize)> = vec![];
+    for mapping in matches.values_of("NODE").unwrap() {
+        let eq = mapping.find('=').ok_or_else(|| format!("malformed node mapping `{}`", mapping))?;
+        let (name, node) = (&mapping[..eq], &mapping[eq + 1..]);
+        let ix =
+            inputs.iter().position(|(n, _)| n == name).ok_or_else(|| format!("unknown input stream `{}`", name))?;
+        let node = node.parse::<NodeId>().map_err(|_| format!("malformed node id `{}`", node))?;
+        nodes.push((node, ix));
+    }
+    let interval: f64 = matches.value_of("INTERVAL").unwrap().parse()?;
+
+    let mut client = ClientBuilder::new()
+        .application_name("RTLola")
+        .application_uri("urn:rtlola")
+        .trust_server_certs(true)
+        .create_sample_keypair(true)
+        .session_retry_limit(3)
+        .client()
+        .ok_or("invalid client configuration")?;
+    let endpoint: EndpointDescription = (
+        matches.value_of("ENDPOINT").unwrap(),
+        SecurityPolicy::None.to_str(),
+        MessageSecurityMode::None,
+        UserTokenPolicy::anonymous(),
+    )
+        .into();
+    let session = client.connect_to_endpoint(endpoint, IdentityToken::Anonymous)?;
+
+    let (tx, rx) = mpsc::channel::<Sample>();
+    {
+        let session = session.read();
+        let callback_nodes = nodes.clone();
+        let callback = DataChangeCallback::new(move |items| {
+            for item in items {
+                let node = &item.item_to_monitor().node_id;
+                let value = item.last_value();
+                let ts = value.source_timestamp.as_ref().or_else(|| value.server_timestamp.as_ref());
+                if let (Some((_, ix)), Some(variant), Some(ts)) =
+                    (callback_nodes.iter().find(|(n, _)| n == node), &value.value, ts)
+                {
+                    let _ = tx.send((*ix, variant.clone(), ts.as_chrono().timestamp_nanos()));
+                }
+            }
+        });
+        let subscription = session.create_subscription(interval, 10, 30, 0, 0, true, callback)?;
+        let items: Vec<MonitoredItemCreateRequest> = nodes.iter().map(|(n, _)| n.clone().into()).collect();
+        session.create_monitored_items(subscription, TimestampsToReturn::Both, &items)?;
+    }
+    let _stop = Session::run_async(session);
+
+    let mut monitor = Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir).into_monitor()?;
+    let mut start: Option<i64> = None;
+    let mut last = Duration::default();
+    for (ix, variant, nanos) in rx {
+        let ty = &inputs[ix].1;
+        let value = to_value(&variant, ty).ok_or_else(|| format!("cannot convert {:?} to {}", variant, ty))?;
+        let mut event = vec![Value::None; inputs.len()];
+        event[ix] = value;
+        // Samples of different nodes may arrive slightly out of order; time must not go backwards.
+        let start = *start.get_or_insert(nanos);
+        let ts = Duration::from_nanos((nanos - start).max(0) as u64).max(last);
+        last = ts;
+        let update = monitor.accept_event(event, ts);
+        for (ts, slice) in update.timed.iter().chain(std::iter::once(&(ts, update.event.clone()))) {
+            for (out, v) in slice {
+                if let (Some(msg), Value::Bool(true)) = (monitor.trigger_message(*out), v) {
+                    println!("[{:.3}s] Trigger: {}", ts.as_secs_f64(), msg);
+                }
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,207 @@
+//! Python bindings for RTLola.
+//!
+//! The module exposes the push-based monitor API of the interpreter, e.g.
+//!
+//! ```python
+//! import rtlola
+//! m = rtlola.Monitor("input alt: Float64\noutput too_low := alt < 200.0\ntrigger too_low")
+//! update = m.accept({'alt': 10.2}, t=1.5)
+//! ```
+
+use ordered_float::NotNan;
+use pyo3::exceptions::{PyTypeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict, PyList, PyString, PyTuple};
+use rtlola_frontend::ir::{RTLolaIR, Type};
+use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_interpreter::{Config, EvalConfig, StateSlice, TimeRepresentation, Value};
+use std::time::Duration;
+
+const CONFIG: FrontendConfig =
+    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+
+fn parse_spec(spec: &str) -> PyResult<RTLolaIR> {
+    rtlola_frontend::parse("python", spec, CONFIG).map_err(PyValueError::new_err)
+}
+
+/// A parsed and analyzed RTLola specification.
+#[pyclass]
+#[derive(Debug, Clone)]
+struct Specification {
+    /// The names and types of the input streams.
+    #[pyo3(get)]
+    inputs: Vec<(String, String)>,
+    /// The names and types of the output streams.
+    #[pyo3(get)]
+    outputs: Vec<(String, String)>,
+    /// The messages of the triggers.
+    #[pyo3(get)]
+    triggers: Vec<String>,
+}
+
+impl From<&RTLolaIR> for Specification {
+    fn from(ir: &RTLolaIR) -> Specification {
+        Specification {
+            inputs: ir.inputs.iter().map(|i| (i.name.clone(), i.ty.to_string())).collect(),
+            outputs: ir.outputs.iter().map(|o| (o.name.clone(), o.ty.to_string())).collect(),
+            triggers: ir.triggers.iter().map(|t| t.message.clone()).collect(),
+        }
+    }
+}
+
+/// Parses and analyzes a specification, raises a `ValueError` if the specification is invalid.
+#[pyfunction]
+fn parse(spec: &str) -> PyResult<Specification> {
+    parse_spec(spec).map(|ir| Specification::from(&ir))
+}
+
+/// A monitor for an RTLola specification accepting events one at a time.
+#[pyclass(unsendable)]
+struct Monitor {
+    monitor: rtlola_interpreter::Monitor,
+    inputs: Vec<(String, Type)>,
+    /// The time of the last event or `accept_time` call.
+    last: Duration,
+}
+
+#[pymethods]
+impl Monitor {
+    #[new]
+    fn new(spec: &str) -> PyResult<Self> {
+        let ir = parse_spec(spec)?;
+        let inputs = ir.inputs.iter().map(|i| (i.name.clone(), i.ty.clone())).collect();
+        let monitor = Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir)
+            .into_monitor()
+            .map_err(|e| PyValueError::new_err(e.to_string()))?;
+        Ok(Monitor { monitor, inputs, last: Duration::default() })
+    }
+
+    /// Accepts an event given as a dictionary from input names to values at time `t` in seconds.
+    /// Inputs missing from the dictionary or mapped to `None` do not receive a new value.
+    #[pyo3(signature = (event, t))]
+    fn accept(&mut self, py: Python<'_>, event: &PyDict, t: f64) -> PyResult<PyObject> {
+        let values = self
+            .inputs
+            .iter()
+            .map(|(name, ty)| match event.get_item(name)? {
+                Some(v) => to_value(v, ty),
+                None => Ok(Value::None),
+            })
+            .collect::<PyResult<Vec<Value>>>()?;
+        let ts = self.time(t)?;
+        let update = self.monitor.accept_event(values, ts);
+        let res = PyDict::new(py);
+        res.set_item("timed", self.timed_to_py(py, update.timed)?)?;
+        res.set_item("event", self.slice_to_py(py, update.event)?)?;
+        Ok(res.into())
+    }
+
+    /// Computes all periodic streams up to time `t` in seconds.
+    #[pyo3(signature = (t))]
+    fn accept_time(&mut self, py: Python<'_>, t: f64) -> PyResult<PyObject> {
+        let ts = self.time(t)?;
+        let timed = self.monitor.accept_time(ts);
+        self.timed_to_py(py, timed)
+    }
+}
+
+impl Monitor {
+    /// Checks that `t` is a valid timestamp no earlier than the last one, raises a `ValueError` otherwise.
+    fn time(&mut self, t: f64) -> PyResult<Duration> {
+        let ts = to_duration(t)?;
+        if ts < self.last {
+            let last = self.last.as_secs_f64();
+            return Err(PyValueError::new_err(format!("time {}s is before the last event at {}s", t, last)));
+        }
+        self.last = ts;
+        Ok(ts)
+    }
+
+    fn slice_to_py(&self, py: Python<'_>, slice: StateSlice) -> PyResult<PyObject> {
+        let dict = PyDict::new(py);
+        for (out, v) in slice {
+            dict.set_item(self.monitor.name_for_output(out), to_py(py, v))?;
+        }
+        Ok(dict.into())
+    }
+
+    fn timed_to_py(&self, py: Python<'_>, timed: Vec<(Duration, StateSlice)>) -> PyResult<PyObject> {
+        let entries = timed
+            .into_iter()
+            .map(|(ts, slice)| Ok((ts.as_secs_f64(), self.slice_to_py(py, slice)?).into_py(py)))
+            .collect::<PyResult<Vec<PyObject>>>()?;
+        Ok(PyList::new(py, entries).into())
+    }
+}
+
+fn to_duration(t: f64) -> PyResult<Duration> {
+    if t.is_finite() && t >= 0.0 {
+        Ok(Duration::from_secs_f64(t))
+    } else {
+        Err(PyValueError::new_err(format!("invalid timestamp {}", t)))
+    }
+}
+
+fn to_value(obj: &PyAny, ty: &Type) -> PyResult<Value> {
+    if obj.is_none() {
+        return Ok(Value::None);
+    }
+    Ok(match ty {
+        Type::Bool => Value::Bool(obj.extract()?),
+        Type::Int(_) => Value::Signed(obj.extract()?),
+        Type::UInt(_) => Value::Unsigned(obj.extract()?),
+        Type::Float(_) => {
+            let f: f64 = obj.extract()?;
+            Value::Float(NotNan::new(f).map_err(|_| PyValueError::new_err("NaN is not a valid input value"))?)
+        }
+        Type::String => Value::Str(obj.extract::<String>()?.into_boxed_str()),
+        Type::Enum(name, variants) => {
+            let variant: String = obj.extract()?;
+            match variants.iter().position(|v| *v == variant) {
+                Some(index) => Value::Unsigned(index as u64),
+                None => return Err(PyValueError::new_err(format!("`{}` is no variant of {}", variant, name))),
+            }
+        }
+        Type::Bytes => Value::Bytes(obj.extract::<Vec<u8>>()?.into_boxed_slice()),
+        Type::Tuple(tys) => {
+            let tuple: &PyTuple = obj.downcast()?;
+            if tuple.len() != tys.len() {
+                return Err(PyTypeError::new_err(format!("expected a tuple of length {}", tys.len())));
+            }
+            Value::Tuple(tuple.iter().zip(tys).map(|(o, t)| to_value(o, t)).collect::<PyResult<Vec<_>>>()?.into())
+        }
+        Type::Array(ty, size) => {
+            let elems: Vec<&PyAny> = obj.extract()?;
+            if elems.len() != *size {
+                return Err(PyTypeError::new_err(format!("expected a sequence of length {}", size)));
+            }
+            Value::Tuple(elems.into_iter().map(|o| to_value(o, ty)).collect::<PyResult<Vec<_>>>()?.into())
+        }
+        Type::Option(_) | Type::Function(_, _) => {
+            return Err(PyTypeError::new_err(format!("unsupported input type {}", ty)));
+        }
+    })
+}
+
+fn to_py(py: Python<'_>, v: Value) -> PyObject {
+    match v {
+        Value::None => py.None(),
+        Value::Bool(b) => b.into_py(py),
+        Value::Unsigned(u) => u.into_py(py),
+        Value::Signed(i) => i.into_py(py),
+        Value::Float(f) => f.into_inner().into_py(py),
+        Value::Tuple(vals) => {
+            PyTuple::new(py, vals.into_vec().into_iter().map(|v| to_py(py, v)).collect::<Vec<_>>()).into()
+        }
+        Value::Str(s) => PyString::new(py, &s).into(),
+        Value::Bytes(b) => PyBytes::new(py, &b).into(),
+    }
+}
+
+#[pymodule]
+fn rtlola(_py: Python<'_>, m: &PyModule) -> PyResult<()> {
+    m.add_class::<Monitor>()?;
+    m.add_class::<Specification>()?;
+    m.add_function(wrap_pyfunction!(parse, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,36 @@
+import unittest
+
+import rtlola
+
+PERIODIC = "input alt: Float64\noutput count @ 1Hz := count.offset(by: -1).defaults(to: 0) + 1"
+
+
+class MonitorTest(unittest.TestCase):
+    def test_event_driven_streams(self):
+        m = rtlola.Monitor("input alt: Float64\noutput too_low := alt < 200.0")
+        self.assertEqual(m.accept({'alt': 10.2}, t=1.5)['event'], {'too_low': True})
+        self.assertEqual(m.accept({'alt': None}, t=2.0)['event'], {})
+
+    def test_periodic_streams_continue_across_calls(self):
+        m = rtlola.Monitor(PERIODIC)
+        self.assertEqual(m.accept_time(2.5), [(0.0, {'count': 1}), (1.0, {'count': 2}), (2.0, {'count': 3})])
+        self.assertEqual(m.accept_time(4.0), [(3.0, {'count': 4})])
+        self.assertEqual(m.accept({'alt': 100.0}, t=5.0)['timed'], [(4.0, {'count': 5})])
+        self.assertEqual(m.accept_time(5.0), [])
+
+    def test_time_does_not_go_backwards(self):
+        m = rtlola.Monitor(PERIODIC)
+        m.accept({'alt': 100.0}, t=2.0)
+        with self.assertRaises(ValueError):
+            m.accept({'alt': 100.0}, t=1.0)
+        with self.assertRaises(ValueError):
+            m.accept_time(1.5)
+        self.assertEqual(m.accept_time(3.0), [(2.0, {'count': 3})])
+
+    def test_invalid_specification(self):
+        with self.assertRaises(ValueError):
+            rtlola.Monitor("output a := b")
+
+
+if __name__ == '__main__':
+    unittest.main()
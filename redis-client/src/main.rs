@@ -0,0 +1,192 @@
+//! A Redis Streams input source for the RTLola interpreter.
+//!
+//! The client reads the entries of a stream as member of a consumer group, converts the fields mapped to input
+//! streams to their declared types, and feeds each entry as event into a monitor.
+//! Entries are acknowledged once they have been processed, so a restarted client continues where it stopped.
+
+use clap::{App, Arg};
+use ordered_float::NotNan;
+use redis::streams::{StreamId, StreamReadOptions, StreamReadReply};
+use redis::{Commands, Connection, RedisResult};
+use rtlola_frontend::ir::Type;
+use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_interpreter::{Config, EvalConfig, Monitor, TimeRepresentation, Value};
+use std::error::Error;
+use std::time::Duration;
+
+const CONFIG: FrontendConfig =
+    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+
+struct Consumer {
+    monitor: Monitor,
+    /// The input streams with the names of their fields.
+    inputs: Vec<(String, Type)>,
+    /// The field containing the timestamp in seconds, the time encoded in the entry id is used otherwise.
+    time_field: Option<String>,
+    /// The first timestamp, times are relative to it.
+    start: Option<Duration>,
+    last: Duration,
+}
+
+impl Consumer {
+    /// Feeds an entry into the monitor and prints the trigger firings.
+    fn accept(&mut self, entry: &StreamId) -> Result<(), String> {
+        let time = match &self.time_field {
+            Some(field) => {
+                let secs = entry.get::<String>(field).ok_or_else(|| format!("missing field `{}`", field))?;
+                let secs = secs.trim().parse::<f64>().ok().filter(|t| t.is_finite() && *t >= 0.0);
+                Duration::from_secs_f64(secs.ok_or_else(|| format!("invalid time in field `{}`", field))?)
+            }
+            None => {
+                let millis = entry.id.split('-').next().and_then(|ms| ms.parse().ok());
+                Duration::from_millis(millis.ok_or_else(|| format!("malformed entry id `{}`", entry.id))?)
+            }
+        };
+        let start = *self.start.get_or_insert(time);
+        let ts = time.checked_sub(start).ok_or("the entry precedes the first entry")?;
+        if ts < self.last {
+            let (ts, last) = (ts.as_secs_f64(), self.last.as_secs_f64());
+            return Err(format!("the entry at {}s arrived after an entry at {}s", ts, last));
+        }
+        let values = self
+            .inputs
+            .iter()
+            .map(|(field, ty)| {
+                let raw = entry.map.get(field).and_then(|v| redis::from_redis_value::<Vec<u8>>(v).ok());
+                to_value(raw.as_deref(), ty).ok_or_else(|| format!("invalid value for `{}`", field))
+            })
+            .collect::<Result<Vec<Value>, String>>()?;
+        self.last = ts;
+
+        let update = self.monitor.accept_event(values, ts);
+        for (ts, slice) in update.timed.iter().chain(std::iter::once(&(ts, update.event.clone()))) {
+            for (out, v) in slice {
+                if let (Some(msg), Value::Bool(true)) = (self.monitor.trigger_message(*out), v) {
+                    println!("[{:.3}s] Trigger: {}", ts.as_secs_f64(), msg);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Converts the value of a field, missing fields do not yield a new value.
+fn to_value(raw: Option<&[u8]>, ty: &Type) -> Option<Value> {
+    let raw = match raw {
+        Some(raw) => raw,
+        None => return Some(Value::None),
+    };
+    if let Type::Bytes = ty {
+        return Some(Value::Bytes(raw.into()));
+    }
+    let s = std::str::from_utf8(raw).ok()?;
+    Some(match ty {
+        Type::Bool => match s.trim() {
+            "true" | "1" => Value::Bool(true),
+            "false" | "0" => Value::Bool(false),
+            _ => return None,
+        },
+        Type::Int(_) => Value::Signed(s.trim().parse().ok()?),
+        Type::UInt(_) => Value::Unsigned(s.trim().parse().ok()?),
+        Type::Float(_) => Value::Float(NotNan::new(s.trim().parse().ok()?).ok()?),
+        Type::String => Value::Str(s.into()),
+        _ => return None,
+    })
+}
+
+/// Creates the consumer group unless it exists already.
+fn create_group(con: &mut Connection, stream: &str, group: &str, from_start: bool) -> RedisResult<()> {
+    let id = if from_start { "0" } else { "$" };
+    match con.xgroup_create_mkstream::<_, _, _, ()>(stream, group, id) {
+        Err(e) if e.code() == Some("BUSYGROUP") => Ok(()),
+        res => res,
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let matches = App::new("RTLola Redis Client")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Monitors the entries of a Redis Stream with the RTLola interpreter.")
+        .arg(Arg::with_name("SPEC").help("Sets the specification file to use").required(true))
+        .arg(Arg::with_name("URL").help("The URL of the Redis server, e.g., redis://localhost:6379").required(true))
+        .arg(Arg::with_name("STREAM").help("The key of the stream").long("stream").takes_value(true).required(true))
+        .arg(
+            Arg::with_name("GROUP")
+                .help("The consumer group, created if it does not exist")
+                .long("group")
+                .takes_value(true)
+                .default_value("rtlola"),
+        )
+        .arg(
+            Arg::with_name("CONSUMER")
+                .help("The name of this consumer within the group")
+                .long("consumer")
+                .takes_value(true)
+                .default_value("rtlola-1"),
+        )
+        .arg(
+            Arg::with_name("FROM_START")
+                .help("Lets a newly created group read the stream from the start instead of only new entries")
+                .long("from-start"),
+        )
+        .arg(
+            Arg::with_name("TIME")
+                .help("The field containing the timestamps in seconds, the time of the entry id is used otherwise")
+                .long("time-field")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("FIELD")
+                .help("Reads an input stream from a field with a different name, e.g., --field velo=velocity")
+                .long("field")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("COUNT")
+                .help("The maximal number of entries read at once")
+                .long("count")
+                .takes_value(true)
+                .default_value("100"),
+        )
+        .get_matches();
+
+    let path = matches.value_of("SPEC").unwrap();
+    let ir = rtlola_frontend::parse(path, &std::fs::read_to_string(path)?, CONFIG)?;
+    let mut inputs: Vec<(String, Type)> = ir.inputs.iter().map(|i| (i.name.clone(), i.ty.clone())).collect();
+    for mapping in matches.values_of("FIELD").into_iter().flatten() {
+        let eq = mapping.find('=').ok_or_else(|| format!("malformed field mapping `{}`", mapping))?;
+        let (name, field) = (&mapping[..eq], &mapping[eq + 1..]);
+        let input = ir.inputs.iter().position(|i| i.name == name).ok_or_else(|| format!("unknown input `{}`", name))?;
+        inputs[input].0 = field.to_string();
+    }
+    let count: usize =
+        matches.value_of("COUNT").unwrap().parse().map_err(|_| "the count must be a positive integer")?;
+    let stream = matches.value_of("STREAM").unwrap();
+    let group = matches.value_of("GROUP").unwrap();
+    let consumer = matches.value_of("CONSUMER").unwrap();
+
+    let mut con = redis::Client::open(matches.value_of("URL").unwrap())?.get_connection()?;
+    create_group(&mut con, stream, group, matches.is_present("FROM_START"))?;
+
+    let monitor = Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir).into_monitor()?;
+    let time_field = matches.value_of("TIME").map(String::from);
+    let mut client = Consumer { monitor, inputs, time_field, start: None, last: Duration::default() };
+    let options = StreamReadOptions::default().group(group, consumer).count(count).block(1000);
+    loop {
+        // `>` requests entries that were not delivered to any consumer of the group yet.
+        let reply: Option<StreamReadReply> = con.xread_options(&[stream], &[">"], &options)?;
+        let entries: Vec<StreamId> = reply.into_iter().flat_map(|r| r.keys).flat_map(|k| k.ids).collect();
+        if entries.is_empty() {
+            continue;
+        }
+        for entry in &entries {
+            if let Err(e) = client.accept(entry) {
+                eprintln!("warning: dropped entry {}: {}", entry.id, e);
+            }
+        }
+        let ids: Vec<&str> = entries.iter().map(|e| e.id.as_str()).collect();
+        con.xack::<_, _, _, ()>(stream, group, &ids)?;
+    }
+}
@@ -0,0 +1,206 @@
+//! JavaScript/WebAssembly bindings for RTLola.
+//!
+//! The bindings expose the frontend so that specifications can be checked entirely in the browser, e.g.
+//!
+//! ```js
+//! import { check } from "rtlola-wasm";
+//! const spec = check("input alt: Float64\noutput too_low := alt < 200.0");
+//! console.log(spec.inputs(), spec.outputs());
+//! ```
+//!
+//! and the monitor of the interpreter, which evaluates events pushed by the page, e.g.
+//!
+//! ```js
+//! import { Monitor } from "rtlola-wasm";
+//! const monitor = new Monitor("input alt: Float64\ntrigger alt < 200.0 \"too low\"");
+//! monitor.acceptEvent({ alt: 10.2 }, 1.5); // [{ time: 1.5, trigger: "too low" }]
+//! ```
+
+use rtlola_frontend::ir::{RTLolaIR, Type};
+use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_interpreter::{Config, EvalConfig, StateSlice, TimeRepresentation, Value};
+use serde_json::{json, Value as Json};
+use std::time::Duration;
+use wasm_bindgen::prelude::*;
+
+/// The configuration of the frontend for checking and monitoring, the same as the one of the interpreter,
+/// so every specification passing `check` can be monitored.
+const CONFIG: FrontendConfig =
+    FrontendConfig { ty: TypeConfig { use_64bit_only: false, type_aliases: false }, allow_parameters: true };
+
+/// A successfully parsed and analyzed RTLola specification.
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct Specification {
+    ir: RTLolaIR,
+}
+
+#[wasm_bindgen]
+impl Specification {
+    /// The input streams as `name: Type` strings.
+    pub fn inputs(&self) -> Box<[JsValue]> {
+        self.ir.inputs.iter().map(|i| JsValue::from(format!("{}: {}", i.name, i.ty))).collect()
+    }
+
+    /// The output streams as `name: Type` strings.
+    pub fn outputs(&self) -> Box<[JsValue]> {
+        self.ir.outputs.iter().map(|o| JsValue::from(format!("{}: {}", o.name, o.ty))).collect()
+    }
+
+    /// The messages of the triggers.
+    pub fn triggers(&self) -> Box<[JsValue]> {
+        self.ir.triggers.iter().map(|t| JsValue::from(t.message.as_str())).collect()
+    }
+
+    /// The expressions of the output streams as `name := expression` strings.
+    pub fn expressions(&self) -> Box<[JsValue]> {
+        self.ir.outputs.iter().map(|o| JsValue::from(format!("{} := {}", o.name, o.expr))).collect()
+    }
+
+    /// Whether the specification contains periodic streams.
+    #[wasm_bindgen(js_name = hasPeriodicStreams)]
+    pub fn has_periodic_streams(&self) -> bool {
+        !self.ir.time_driven.is_empty()
+    }
+}
+
+/// Parses and analyzes a specification, throws an error string if the specification is invalid.
+#[wasm_bindgen]
+pub fn check(spec: &str) -> Result<Specification, JsValue> {
+    rtlola_frontend::parse("playground", spec, CONFIG)
+        .map(|ir| Specification { ir })
+        .map_err(|e| JsValue::from(e.as_str()))
+}
+
+/// A monitor for a specification, evaluating the events pushed by the page.
+/// The events are objects from input names to values, inputs without a value do not receive a new one.
+/// The triggers fired are returned as `{ time, trigger }` objects with the time in seconds.
+#[wasm_bindgen]
+#[allow(missing_debug_implementations)]
+pub struct Monitor {
+    monitor: rtlola_interpreter::Monitor,
+    inputs: Vec<(String, Type)>,
+    last: Duration,
+}
+
+#[wasm_bindgen]
+impl Monitor {
+    /// Creates a monitor for a specification, throws an error string if the specification is invalid.
+    #[wasm_bindgen(constructor)]
+    pub fn new(spec: &str) -> Result<Monitor, JsValue> {
+        Monitor::from_spec(spec).map_err(|e| JsValue::from(e.as_str()))
+    }
+
+    /// Accepts an event at `time` in seconds and returns the triggers fired up to and including the event.
+    /// Throws an error string if a value does not match the type of its input or time went backwards.
+    #[wasm_bindgen(js_name = acceptEvent)]
+    pub fn accept_event(&mut self, event: &JsValue, time: f64) -> Result<JsValue, JsValue> {
+        let event = js_sys::JSON::stringify(event).map(String::from).unwrap_or_default();
+        let event = serde_json::from_str(&event).map_err(|e| JsValue::from(e.to_string().as_str()))?;
+        let fired = self.accept(&event, time).map_err(|e| JsValue::from(e.as_str()))?;
+        to_js(&Json::Array(fired))
+    }
+
+    /// Evaluates the periodic streams up to `time` in seconds and returns the triggers fired.
+    #[wasm_bindgen(js_name = acceptTime)]
+    pub fn accept_time(&mut self, time: f64) -> Result<JsValue, JsValue> {
+        let fired = self.advance(time).map_err(|e| JsValue::from(e.as_str()))?;
+        to_js(&Json::Array(fired))
+    }
+}
+
+impl Monitor {
+    fn from_spec(spec: &str) -> Result<Monitor, String> {
+        let ir = rtlola_frontend::parse("playground", spec, CONFIG)?;
+        let inputs = ir.inputs.iter().map(|i| (i.name.clone(), i.ty.clone())).collect();
+        let monitor =
+            Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir).into_monitor().map_err(|e| e.to_string())?;
+        Ok(Monitor { monitor, inputs, last: Duration::default() })
+    }
+
+    fn accept(&mut self, event: &Json, time: f64) -> Result<Vec<Json>, String> {
+        // the event is converted completely before the monitor sees any of its values
+        let values = self
+            .inputs
+            .iter()
+            .map(|(name, ty)| Value::from_json(&event[name], ty).ok_or(format!("invalid value for `{}`", name)))
+            .collect::<Result<Vec<Value>, String>>()?;
+        let ts = self.time(time)?;
+        let update = self.monitor.accept_event(values, ts);
+        let mut fired = self.fired(update.timed);
+        fired.extend(self.fired(vec![(ts, update.event)]));
+        Ok(fired)
+    }
+
+    fn advance(&mut self, time: f64) -> Result<Vec<Json>, String> {
+        let ts = self.time(time)?;
+        let timed = self.monitor.accept_time(ts);
+        Ok(self.fired(timed))
+    }
+
+    /// Checks that `time` is a valid timestamp no earlier than the last one.
+    fn time(&mut self, time: f64) -> Result<Duration, String> {
+        if !time.is_finite() || time < 0.0 {
+            return Err(format!("invalid timestamp {}", time));
+        }
+        let ts = Duration::from_secs_f64(time);
+        if ts < self.last {
+            return Err(format!("time {}s is before the last event at {}s", time, self.last.as_secs_f64()));
+        }
+        self.last = ts;
+        Ok(ts)
+    }
+
+    fn fired(&self, slices: Vec<(Duration, StateSlice)>) -> Vec<Json> {
+        let mut fired = vec![];
+        for (ts, slice) in slices {
+            for (out, v) in slice {
+                if let (Some(msg), Value::Bool(true)) = (self.monitor.trigger_message(out), v) {
+                    fired.push(json!({ "time": ts.as_secs_f64(), "trigger": msg }));
+                }
+            }
+        }
+        fired
+    }
+}
+
+fn to_js(json: &Json) -> Result<JsValue, JsValue> {
+    js_sys::JSON::parse(&json.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn triggers_are_returned() {
+        let mut monitor = Monitor::from_spec("input alt: Float64\ntrigger alt < 200.0 \"too low\"").unwrap();
+        assert_eq!(monitor.accept(&json!({ "alt": 300.0 }), 1.0), Ok(vec![]));
+        assert_eq!(
+            monitor.accept(&json!({ "alt": 10.2 }), 1.5),
+            Ok(vec![json!({ "time": 1.5, "trigger": "too low" })])
+        );
+        assert!(monitor.accept(&json!({ "alt": "high" }), 2.0).is_err());
+        assert!(monitor.accept(&json!({ "alt": 10.0 }), 1.0).is_err());
+        assert!(Monitor::from_spec("output a := b").is_err());
+    }
+
+    #[test]
+    fn periodic_streams() {
+        let spec =
+            "input alt: Float64\noutput count @ 1Hz := count.offset(by: -1).defaults(to: 0) + 1\ntrigger count == 3 \"third\"";
+        let mut monitor = Monitor::from_spec(spec).unwrap();
+        assert_eq!(monitor.advance(1.5), Ok(vec![]));
+        assert_eq!(monitor.advance(2.5), Ok(vec![json!({ "time": 2.0, "trigger": "third" })]));
+        assert_eq!(monitor.accept(&json!({}), 5.0), Ok(vec![]));
+    }
+
+    #[test]
+    fn checked_specifications_can_be_monitored() {
+        let spec = "input raw: Int32
+trigger raw > 3 \"large\"";
+        assert!(check(spec).is_ok());
+        let mut monitor = Monitor::from_spec(spec).unwrap();
+        assert_eq!(monitor.accept(&json!({ "raw": 4 }), 1.0), Ok(vec![json!({ "time": 1.0, "trigger": "large" })]));
+    }
+}
@@ -0,0 +1,210 @@
+//! A ZeroMQ transport for the RTLola interpreter.
+//!
+//! Events are received via a SUB or PULL socket and verdicts are sent via a PUB or PUSH socket.
+//! Both are framed as JSON or MessagePack:
+//! * an event is an object `{"time": 1.5, "values": {"alt": 10.2}}` or an array of such objects,
+//! * a verdict is an object `{"time": 1.5, "outputs": {"too_low": true}, "triggers": ["altitude too low"]}`.
+
+use clap::{App, Arg};
+use rtlola_frontend::ir::Type;
+use rtlola_frontend::{FrontendConfig, TypeConfig};
+use rtlola_interpreter::{Config, EvalConfig, Monitor, StateSlice, TimeRepresentation, Value};
+use serde_json::{json, Map, Value as Json};
+use std::error::Error;
+use std::time::Duration;
+
+const CONFIG: FrontendConfig =
+    FrontendConfig { ty: TypeConfig { use_64bit_only: true, type_aliases: false }, allow_parameters: false };
+
+#[derive(Debug, Clone, Copy)]
+enum Framing {
+    Json,
+    MessagePack,
+}
+
+impl Framing {
+    fn decode(self, bytes: &[u8]) -> Result<Json, String> {
+        match self {
+            Framing::Json => serde_json::from_slice(bytes).map_err(|e| e.to_string()),
+            Framing::MessagePack => rmp_serde::from_slice(bytes).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn encode(self, value: &Json) -> Vec<u8> {
+        match self {
+            Framing::Json => serde_json::to_vec(value).expect("JSON values are serializable"),
+            Framing::MessagePack => rmp_serde::to_vec_named(value).expect("JSON values are serializable"),
+        }
+    }
+}
+
+/// Creates a socket from a `kind:endpoint` description; endpoints with a wildcard are bound, others connected.
+fn socket(ctx: &zmq::Context, description: &str, kinds: &[(&str, zmq::SocketType)]) -> Result<zmq::Socket, String> {
+    let colon = description.find(':').ok_or_else(|| format!("malformed socket `{}`", description))?;
+    let (kind, endpoint) = (&description[..colon], &description[colon + 1..]);
+    let ty = kinds.iter().find(|(k, _)| *k == kind).map(|(_, ty)| *ty).ok_or_else(|| {
+        let names: Vec<&str> = kinds.iter().map(|(k, _)| *k).collect();
+        format!("unsupported socket type `{}`, expected one of {}", kind, names.join(", "))
+    })?;
+    let socket = ctx.socket(ty).map_err(|e| e.to_string())?;
+    let res = if endpoint.contains('*') { socket.bind(endpoint) } else { socket.connect(endpoint) };
+    res.map_err(|e| format!("cannot open `{}`: {}", endpoint, e))?;
+    Ok(socket)
+}
+
+struct Bridge {
+    monitor: Monitor,
+    inputs: Vec<(String, Type)>,
+    last: Duration,
+}
+
+impl Bridge {
+    /// Feeds the events of a message into the monitor and returns the resulting verdicts.
+    fn accept(&mut self, message: Json) -> Result<Vec<Json>, String> {
+        let events = match message {
+            Json::Array(events) => events,
+            event => vec![event],
+        };
+        // the whole message is converted first, so the monitor sees none of its events if one is invalid
+        let mut last = self.last;
+        let events = events
+            .iter()
+            .map(|event| {
+                let time =
+                    event["time"].as_f64().filter(|t| t.is_finite() && *t >= 0.0).ok_or("missing or invalid time")?;
+                let ts = Duration::from_secs_f64(time);
+                if ts < last {
+                    return Err(format!("event at {}s arrived after an event at {}s", time, last.as_secs_f64()));
+                }
+                last = ts;
+                let values = self
+                    .inputs
+                    .iter()
+                    .map(|(name, ty)| {
+                        Value::from_json(&event["values"][name], ty).ok_or(format!("invalid value for `{}`", name))
+                    })
+                    .collect::<Result<Vec<Value>, String>>()?;
+                Ok((values, ts))
+            })
+            .collect::<Result<Vec<(Vec<Value>, Duration)>, String>>()?;
+        self.last = last;
+        let mut verdicts = vec![];
+        for (values, ts) in events {
+            let update = self.monitor.accept_event(values, ts);
+            for (ts, slice) in update.timed.into_iter().chain(std::iter::once((ts, update.event))) {
+                if !slice.is_empty() {
+                    verdicts.push(self.verdict(ts, slice));
+                }
+            }
+        }
+        Ok(verdicts)
+    }
+
+    fn verdict(&self, ts: Duration, slice: StateSlice) -> Json {
+        let mut outputs = Map::new();
+        let mut triggers = vec![];
+        for (out, v) in slice {
+            match self.monitor.trigger_message(out) {
+                Some(msg) => {
+                    if let Value::Bool(true) = v {
+                        triggers.push(Json::from(msg));
+                    }
+                }
+                None => {
+                    outputs.insert(self.monitor.name_for_output(out).to_string(), v.into_json());
+                }
+            }
+        }
+        json!({ "time": ts.as_secs_f64(), "outputs": outputs, "triggers": triggers })
+    }
+}
+
+fn main() -> Result<(), Box<dyn Error>> {
+    let matches = App::new("RTLola ZeroMQ Bridge")
+        .version(env!("CARGO_PKG_VERSION"))
+        .about("Connects the RTLola interpreter to ZeroMQ sockets.")
+        .arg(Arg::with_name("SPEC").help("Sets the specification file to use").required(true))
+        .arg(
+            Arg::with_name("EVENTS")
+                .help("The socket receiving events, e.g., sub:tcp://localhost:5556 or pull:tcp://*:5557")
+                .long("events")
+                .takes_value(true)
+                .required(true),
+        )
+        .arg(
+            Arg::with_name("VERDICTS")
+                .help("The socket sending verdicts, e.g., pub:tcp://*:5558, triggers are printed to stdout otherwise")
+                .long("verdicts")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("FORMAT")
+                .help("The framing of events and verdicts")
+                .long("format")
+                .takes_value(true)
+                .possible_values(&["json", "msgpack"])
+                .default_value("json"),
+        )
+        .arg(
+            Arg::with_name("TOPIC")
+                .help("The topic subscribed to and prefixed to published verdicts as separate frame")
+                .long("topic")
+                .takes_value(true)
+                .default_value(""),
+        )
+        .get_matches();
+
+    let path = matches.value_of("SPEC").unwrap();
+    let ir = rtlola_frontend::parse(path, &std::fs::read_to_string(path)?, CONFIG)?;
+    let inputs = ir.inputs.iter().map(|i| (i.name.clone(), i.ty.clone())).collect();
+    let monitor = Config::new_api(EvalConfig::api(TimeRepresentation::Hide), ir).into_monitor()?;
+    let mut bridge = Bridge { monitor, inputs, last: Duration::default() };
+    let framing = match matches.value_of("FORMAT").unwrap() {
+        "json" => Framing::Json,
+        "msgpack" => Framing::MessagePack,
+        _ => unreachable!(),
+    };
+    let topic = matches.value_of("TOPIC").unwrap();
+
+    let ctx = zmq::Context::new();
+    let events = socket(&ctx, matches.value_of("EVENTS").unwrap(), &[("sub", zmq::SUB), ("pull", zmq::PULL)])?;
+    if events.get_socket_type()? == zmq::SUB {
+        events.set_subscribe(topic.as_bytes())?;
+    }
+    let verdicts = match matches.value_of("VERDICTS") {
+        Some(description) => Some(socket(&ctx, description, &[("pub", zmq::PUB), ("push", zmq::PUSH)])?),
+        None => None,
+    };
+
+    loop {
+        let frames = events.recv_multipart(0)?;
+        // Messages of publishers may carry the topic in a separate first frame.
+        let payload = frames.last().map(Vec::as_slice).unwrap_or_default();
+        let result = framing.decode(payload).and_then(|message| bridge.accept(message));
+        let verdicts_of_message = match result {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("warning: dropped message: {}", e);
+                continue;
+            }
+        };
+        for verdict in verdicts_of_message {
+            match &verdicts {
+                Some(socket) => {
+                    let payload = framing.encode(&verdict);
+                    if socket.get_socket_type()? == zmq::PUB && !topic.is_empty() {
+                        socket.send_multipart([topic.as_bytes(), payload.as_slice()], 0)?;
+                    } else {
+                        socket.send(payload, 0)?;
+                    }
+                }
+                None => {
+                    let time = verdict["time"].as_f64().unwrap_or_default();
+                    for trigger in verdict["triggers"].as_array().into_iter().flatten() {
+                        println!("[{:.3}s] Trigger: {}", time, trigger.as_str().unwrap_or_default());
+                    }
+                }
+            }
+        }
+    }
+}